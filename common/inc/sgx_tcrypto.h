@@ -47,8 +47,12 @@
 #define SGX_SHA1_HASH_SIZE              20
 #define SGX_SHA256_HASH_SIZE            32
 #define SGX_SHA384_HASH_SIZE            48
+#define SGX_SHA512_HASH_SIZE            64
+#define SGX_SHA512_256_HASH_SIZE        32
 #define SGX_ECP256_KEY_SIZE             32
 #define SGX_NISTP_ECP256_KEY_SIZE       (SGX_ECP256_KEY_SIZE/sizeof(uint32_t))
+#define SGX_ECP384_KEY_SIZE             48
+#define SGX_NISTP_ECP384_KEY_SIZE       (SGX_ECP384_KEY_SIZE/sizeof(uint32_t))
 #define SGX_AESGCM_IV_SIZE              12
 #define SGX_AESCBC_IV_SIZE              16
 #define SGX_AESGCM_KEY_SIZE             16
@@ -68,6 +72,9 @@
 #define SGX_RSA3072_KEY_SIZE            384
 #define SGX_RSA3072_PRI_EXP_SIZE        384
 #define SGX_RSA3072_PUB_EXP_SIZE        4
+#define SGX_RSA4096_KEY_SIZE            512
+#define SGX_RSA4096_PRI_EXP_SIZE        512
+#define SGX_RSA4096_PUB_EXP_SIZE        4
 #define SGX_SM3_HASH_SIZE               32
 #define SGX_SM4CCM_IV_SIZE              12
 #define SGX_SM4CCM_KEY_SIZE             16
@@ -98,6 +105,28 @@ typedef struct _sgx_ec256_signature_t
     uint32_t y[SGX_NISTP_ECP256_KEY_SIZE];
 } sgx_ec256_signature_t;
 
+typedef struct _sgx_ec384_dh_shared_t
+{
+    uint8_t s[SGX_ECP384_KEY_SIZE];
+} sgx_ec384_dh_shared_t;
+
+typedef struct _sgx_ec384_private_t
+{
+    uint8_t r[SGX_ECP384_KEY_SIZE];
+} sgx_ec384_private_t;
+
+typedef struct _sgx_ec384_public_t
+{
+    uint8_t gx[SGX_ECP384_KEY_SIZE];
+    uint8_t gy[SGX_ECP384_KEY_SIZE];
+} sgx_ec384_public_t;
+
+typedef struct _sgx_ec384_signature_t
+{
+    uint32_t x[SGX_NISTP_ECP384_KEY_SIZE];
+    uint32_t y[SGX_NISTP_ECP384_KEY_SIZE];
+} sgx_ec384_signature_t;
+
 typedef struct _sgx_rsa2048_public_key_t
 {
     uint8_t mod[SGX_RSA2048_KEY_SIZE];
@@ -128,6 +157,21 @@ typedef struct _sgx_rsa3072_key_t
 
 typedef uint8_t sgx_rsa3072_signature_t[SGX_RSA3072_KEY_SIZE];
 
+typedef struct _sgx_rsa4096_public_key_t
+{
+    uint8_t mod[SGX_RSA4096_KEY_SIZE];
+    uint8_t exp[SGX_RSA4096_PUB_EXP_SIZE];
+} sgx_rsa4096_public_key_t;
+
+typedef struct _sgx_rsa4096_key_t
+{
+    uint8_t mod[SGX_RSA4096_KEY_SIZE];
+    uint8_t d[SGX_RSA4096_PRI_EXP_SIZE];
+    uint8_t e[SGX_RSA4096_PUB_EXP_SIZE];
+} sgx_rsa4096_key_t;
+
+typedef uint8_t sgx_rsa4096_signature_t[SGX_RSA4096_KEY_SIZE];
+
 typedef void* sgx_sha_state_handle_t;
 typedef void* sgx_hmac_state_handle_t;
 typedef void* sgx_cmac_state_handle_t;
@@ -139,6 +183,8 @@ typedef void* sgx_sm4_state_handle_t;
 typedef uint8_t sgx_sha1_hash_t[SGX_SHA1_HASH_SIZE];
 typedef uint8_t sgx_sha256_hash_t[SGX_SHA256_HASH_SIZE];
 typedef uint8_t sgx_sha384_hash_t[SGX_SHA384_HASH_SIZE];
+typedef uint8_t sgx_sha512_hash_t[SGX_SHA512_HASH_SIZE];
+typedef uint8_t sgx_sha512_256_hash_t[SGX_SHA512_256_HASH_SIZE];
 typedef uint8_t sgx_sm3_hash_t[SGX_SM3_HASH_SIZE];
 
 typedef uint8_t sgx_aes_gcm_128bit_key_t[SGX_AESGCM_KEY_SIZE];
@@ -196,6 +242,12 @@ typedef enum {
     SGX_RSA_PUBLIC_KEY    /* RSA public key state */
 } sgx_rsa_key_type_t;
 
+typedef enum {
+    SGX_RSA_HASH_SHA256,    /* SHA-256 digest and MGF1 hash */
+    SGX_RSA_HASH_SHA384,    /* SHA-384 digest and MGF1 hash */
+    SGX_RSA_HASH_SHA512     /* SHA-512 digest and MGF1 hash */
+} sgx_rsa_hash_t;
+
 #define N_SIZE_IN_BYTES    384
 #define E_SIZE_IN_BYTES    4
 #define D_SIZE_IN_BYTES    384
@@ -260,6 +312,8 @@ extern "C" {
     *           uint32_t src_len - Length of the input stream to be hashed
     *   Output: sgx_sha#_hash_t *p_hash - Resultant hash from operation
     */
+    sgx_status_t SGXAPI sgx_sha512_msg(const uint8_t *p_src, uint32_t src_len, sgx_sha512_hash_t *p_hash);
+    sgx_status_t SGXAPI sgx_sha512_256_msg(const uint8_t *p_src, uint32_t src_len, sgx_sha512_256_hash_t *p_hash);
     sgx_status_t SGXAPI sgx_sha384_msg(const uint8_t *p_src, uint32_t src_len, sgx_sha384_hash_t *p_hash);
     sgx_status_t SGXAPI sgx_sha256_msg(const uint8_t *p_src, uint32_t src_len, sgx_sha256_hash_t *p_hash);
     sgx_status_t SGXAPI sgx_sha1_msg(const uint8_t *p_src, uint32_t src_len, sgx_sha1_hash_t *p_hash);
@@ -270,6 +324,8 @@ extern "C" {
     *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
     *   Output: sgx_sha_state_handle_t *p_sha_handle - Pointer to the handle of the SHA state
     */
+    sgx_status_t SGXAPI sgx_sha512_init(sgx_sha_state_handle_t* p_sha_handle);
+    sgx_status_t SGXAPI sgx_sha512_256_init(sgx_sha_state_handle_t* p_sha_handle);
     sgx_status_t SGXAPI sgx_sha384_init(sgx_sha_state_handle_t* p_sha_handle);
     sgx_status_t SGXAPI sgx_sha256_init(sgx_sha_state_handle_t* p_sha_handle);
     sgx_status_t SGXAPI sgx_sha1_init(sgx_sha_state_handle_t* p_sha_handle);
@@ -282,6 +338,8 @@ extern "C" {
     *           uint8_t *p_src - Pointer to the input stream to be hashed
     *           uint32_t src_len - Length of the input stream to be hashed
     */
+    sgx_status_t SGXAPI sgx_sha512_update(const uint8_t *p_src, uint32_t src_len, sgx_sha_state_handle_t sha_handle);
+    sgx_status_t SGXAPI sgx_sha512_256_update(const uint8_t *p_src, uint32_t src_len, sgx_sha_state_handle_t sha_handle);
     sgx_status_t SGXAPI sgx_sha384_update(const uint8_t *p_src, uint32_t src_len, sgx_sha_state_handle_t sha_handle);
     sgx_status_t SGXAPI sgx_sha256_update(const uint8_t *p_src, uint32_t src_len, sgx_sha_state_handle_t sha_handle);
     sgx_status_t SGXAPI sgx_sha1_update(const uint8_t *p_src, size_t src_len, sgx_sha_state_handle_t sha_handle);
@@ -293,6 +351,8 @@ extern "C" {
     *   Input:  sgx_sha_state_handle_t sha_handle - Handle to the SHA state
     *   Output: sgx_sha#_hash_t *p_hash - Resultant hash from operation
     */
+    sgx_status_t SGXAPI sgx_sha512_get_hash(sgx_sha_state_handle_t sha_handle, sgx_sha512_hash_t *p_hash);
+    sgx_status_t SGXAPI sgx_sha512_256_get_hash(sgx_sha_state_handle_t sha_handle, sgx_sha512_256_hash_t *p_hash);
     sgx_status_t SGXAPI sgx_sha384_get_hash(sgx_sha_state_handle_t sha_handle, sgx_sha384_hash_t *p_hash);
     sgx_status_t SGXAPI sgx_sha256_get_hash(sgx_sha_state_handle_t sha_handle, sgx_sha256_hash_t *p_hash);
     sgx_status_t SGXAPI sgx_sha1_get_hash(sgx_sha_state_handle_t sha_handle, sgx_sha1_hash_t *p_hash);
@@ -303,6 +363,8 @@ extern "C" {
     *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
     *   Input:  sgx_sha_state_handle_t sha_handle - Handle to the SHA state
     */
+    sgx_status_t SGXAPI sgx_sha512_close(sgx_sha_state_handle_t sha_handle);
+    sgx_status_t SGXAPI sgx_sha512_256_close(sgx_sha_state_handle_t sha_handle);
     sgx_status_t SGXAPI sgx_sha384_close(sgx_sha_state_handle_t sha_handle);
     sgx_status_t SGXAPI sgx_sha256_close(sgx_sha_state_handle_t sha_handle);
     sgx_status_t SGXAPI sgx_sha1_close(sgx_sha_state_handle_t sha_handle);
@@ -371,6 +433,33 @@ extern "C" {
                                                 uint32_t aad_len,
                                                 const sgx_aes_gcm_128bit_tag_t *p_in_mac);
 
+    /** AES-GCM with 128, 192 or 256-bit keys, otherwise the same as sgx_rijndael128GCM_encrypt/decrypt.
+    *
+    * Parameters:
+    *   Inputs: uint8_t *p_key - Pointer to the key used in encryption/decryption operation
+    *           uint32_t key_len - Length of the key - MUST BE 16, 24 or 32 (bytes)
+    */
+    sgx_status_t SGXAPI sgx_aes_gcm_encrypt_ex(const uint8_t *p_key,
+                                                uint32_t key_len,
+                                                const uint8_t *p_src,
+                                                uint32_t src_len,
+                                                uint8_t *p_dst,
+                                                const uint8_t *p_iv,
+                                                uint32_t iv_len,
+                                                const uint8_t *p_aad,
+                                                uint32_t aad_len,
+                                                sgx_aes_gcm_128bit_tag_t *p_out_mac);
+    sgx_status_t SGXAPI sgx_aes_gcm_decrypt_ex(const uint8_t *p_key,
+                                                uint32_t key_len,
+                                                const uint8_t *p_src,
+                                                uint32_t src_len,
+                                                uint8_t *p_dst,
+                                                const uint8_t *p_iv,
+                                                uint32_t iv_len,
+                                                const uint8_t *p_aad,
+                                                uint32_t aad_len,
+                                                const sgx_aes_gcm_128bit_tag_t *p_in_mac);
+
    /** Message Authentication Rijndael 128 CMAC - Only 128-bit key size is supported.
     * NOTE: Use sgx_rijndael128_cmac_msg if the src ptr contains the complete msg to perform hash (Option 1)
     *       Else use the Init, Update, Update, ..., Final, Close procedure (Option 2)
@@ -494,6 +583,53 @@ extern "C" {
     */
     sgx_status_t SGXAPI sgx_hmac_sha256_close(sgx_hmac_state_handle_t hmac_handle);
 
+    /* Message Authentication - HMAC sha384
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined sgx_error.h
+    *   Inputs: const unsigned char *p_src - Pointer to input stream to be MACed
+    *           int src_len - Source length
+    *           const unsigned char *p_key - Pointer to key used in message authentication operation
+    *           int key_len - Key length
+    *           int mac_len - Expected output MAC length
+    *   Output: unsigned char *p_mac - Pointer to resultant MAC
+    */
+    sgx_status_t SGXAPI sgx_hmac_sha384_msg(const unsigned char *p_src, int src_len, const unsigned char *p_key, int key_len,
+        unsigned char *p_mac, int mac_len);
+
+    /* Allocates and initializes HMAC state
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: const unsigned char *p_key - Pointer to the key used in message authentication operation
+    *           int key_len - Key length
+    *   Output: sgx_hmac_state_handle_t *p_hmac_handle - Pointer to the initialized HMAC state handle
+    */
+    sgx_status_t SGXAPI sgx_hmac_sha384_init(const unsigned char *p_key, int key_len, sgx_hmac_state_handle_t *p_hmac_handle);
+
+    /* Updates HMAC hash calculation based on the input message
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.
+    *	Input:  uint8_t *p_src - Pointer to the input stream to be hashed
+    *	        int src_len - Length of input stream to be hashed
+    *	        sgx_hmac_state_handle_t hmac_handle - Handle to the HMAC state
+    */
+    sgx_status_t SGXAPI sgx_hmac_sha384_update(const uint8_t *p_src, int src_len, sgx_hmac_state_handle_t hmac_handle);
+
+    /* Returns calculated hash
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *	Input:  sgx_hmac_state_handle_t hmac_handle - Handle to the HMAC state
+    *	        int hash_len - Expected MAC length
+    *   Output: unsigned char *p_hash - Resultant hash from HMAC operation
+    */
+    sgx_status_t SGXAPI sgx_hmac_sha384_final(unsigned char *p_hash, int hash_len, sgx_hmac_state_handle_t hmac_handle);
+
+    /* Clean up and free the HMAC state
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS
+    *   Input:  sgx_hmac_state_handle_t hmac_handle  - Handle to the HMAC state
+    */
+    sgx_status_t SGXAPI sgx_hmac_sha384_close(sgx_hmac_state_handle_t hmac_handle);
+
 
    /** AES-CTR 128-bit - Only 128-bit key size is supported.
     *
@@ -552,6 +688,25 @@ extern "C" {
                         const uint32_t ctr_inc_bits,
                         uint8_t *p_dst);
 
+    /** AES-CTR with 128, 192 or 256-bit keys, key_len MUST BE 16, 24 or 32 (bytes). */
+    sgx_status_t SGXAPI sgx_aes_ctr_encrypt_ex(
+                        const uint8_t *p_key,
+                        uint32_t key_len,
+                        const uint8_t *p_src,
+                        const uint32_t src_len,
+                        uint8_t *p_ctr,
+                        const uint32_t ctr_inc_bits,
+                        uint8_t *p_dst);
+
+    sgx_status_t SGXAPI sgx_aes_ctr_decrypt_ex(
+                        const uint8_t *p_key,
+                        uint32_t key_len,
+                        const uint8_t *p_src,
+                        const uint32_t src_len,
+                        uint8_t *p_ctr,
+                        const uint32_t ctr_inc_bits,
+                        uint8_t *p_dst);
+
    /**
     * Elliptic Curve Cryptography based on GF(p), 256 bit.
     *
@@ -723,6 +878,22 @@ extern "C" {
                                     sgx_ec256_signature_t *p_signature,
                                     sgx_ecc_state_handle_t ecc_handle);
 
+   /** Computes a signature over a SHA256 hash with a caller-supplied nonce, e.g. one
+    * derived as in RFC 6979. See sgx_ecdsa_sign. The nonce must be in [1, n-1].
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           uint8_t *p_hash - Pointer to the hash to be signed - BIG ENDIAN
+    *           sgx_ec256_private_t *p_private - Pointer to the private key - LITTLE ENDIAN
+    *           sgx_ec256_private_t *p_nonce - Pointer to the nonce - LITTLE ENDIAN
+    *   Output: sgx_ec256_signature_t *p_signature - Pointer to the signature - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_ecdsa_sign_with_nonce(const uint8_t *p_hash,
+                                    const sgx_ec256_private_t *p_private,
+                                    const sgx_ec256_private_t *p_nonce,
+                                    sgx_ec256_signature_t *p_signature,
+                                    sgx_ecc_state_handle_t ecc_handle);
+
    /** Verifies the signature for the given data based on the public key.
     * This API verifies the hash of input data `verify(SHA256(p_data))`. First it'll calculate SHA256 hash for given data
     * and then verify the signature for this hash.
@@ -787,6 +958,130 @@ extern "C" {
                                         uint8_t *p_result,
                                         sgx_ecc_state_handle_t ecc_handle);
 
+   /** Allocates and initializes an ecc context over the NIST P-384 curve.
+    *  See sgx_ecc256_open_context.
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Output: sgx_ecc_state_handle_t *p_ecc_handle - Pointer to the handle of the ECC crypto system
+    */
+    sgx_status_t SGXAPI sgx_ecc384_open_context(sgx_ecc_state_handle_t* p_ecc_handle);
+
+   /** Cleans up a P-384 ecc context.
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Output: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    */
+    sgx_status_t SGXAPI sgx_ecc384_close_context(sgx_ecc_state_handle_t ecc_handle);
+
+   /** Populates a P-384 private/public key pair. See sgx_ecc256_create_key_pair.
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *   Outputs: sgx_ec384_private_t *p_private - Pointer to the private key - LITTLE ENDIAN
+    *            sgx_ec384_public_t *p_public - Pointer to the public key - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_ecc384_create_key_pair(sgx_ec384_private_t *p_private,
+                                                sgx_ec384_public_t *p_public,
+                                                sgx_ecc_state_handle_t ecc_handle);
+
+    /** Checks whether the input point is a valid point on the P-384 curve.
+     * Parameters:
+     *  Return: sgx_status_t - SGX_SUCCESS or failure as defined sgx_error.h
+     *  Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to ECC crypto system
+     *          sgx_ec384_public_t *p_point - Pointer to perform validity check on - LITTLE ENDIAN
+     *  Output: int *p_valid - Return 0 if the point is an invalid point on ECC curve
+     */
+    sgx_status_t SGXAPI sgx_ecc384_check_point(const sgx_ec384_public_t *p_point,
+                                    const sgx_ecc_state_handle_t ecc_handle,
+                                    int *p_valid);
+
+   /** Computes the P-384 DH shared key. See sgx_ecc256_compute_shared_dhkey.
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           sgx_ec384_private_t *p_private_b - Pointer to the local private key - LITTLE ENDIAN
+    *           sgx_ec384_public_t *p_public_ga - Pointer to the remote public key - LITTLE ENDIAN
+    *   Output: sgx_ec384_dh_shared_t *p_shared_key - Pointer to the shared DH key - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_ecc384_compute_shared_dhkey(const sgx_ec384_private_t *p_private_b,
+                                                    const sgx_ec384_public_t *p_public_ga,
+                                                    sgx_ec384_dh_shared_t *p_shared_key,
+                                                    sgx_ecc_state_handle_t ecc_handle);
+
+   /** Computes an ECDSA P-384 signature over SHA384(p_data). See sgx_ecdsa_sign.
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           sgx_ec384_private_t *p_private - Pointer to the private key - LITTLE ENDIAN
+    *           uint8_t *p_data - Pointer to the data to be signed
+    *           uint32_t data_size - Size of the data to be signed
+    *   Output: sgx_ec384_signature_t *p_signature - Pointer to the signature - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_ecdsa384_sign(const uint8_t *p_data,
+                                    uint32_t data_size,
+                                    const sgx_ec384_private_t *p_private,
+                                    sgx_ec384_signature_t *p_signature,
+                                    sgx_ecc_state_handle_t ecc_handle);
+
+   /** Computes a signature over a SHA384 hash with a caller-supplied nonce, e.g. one
+    * derived as in RFC 6979. See sgx_ecdsa384_sign. The nonce must be in [1, n-1].
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           uint8_t *p_hash - Pointer to the hash to be signed - BIG ENDIAN
+    *           sgx_ec384_private_t *p_private - Pointer to the private key - LITTLE ENDIAN
+    *           sgx_ec384_private_t *p_nonce - Pointer to the nonce - LITTLE ENDIAN
+    *   Output: sgx_ec384_signature_t *p_signature - Pointer to the signature - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_ecdsa384_sign_with_nonce(const uint8_t *p_hash,
+                                    const sgx_ec384_private_t *p_private,
+                                    const sgx_ec384_private_t *p_nonce,
+                                    sgx_ec384_signature_t *p_signature,
+                                    sgx_ecc_state_handle_t ecc_handle);
+
+   /** Verifies an ECDSA P-384 signature over SHA384(p_data). See sgx_ecdsa_verify.
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           sgx_ec384_public_t *p_public - Pointer to the public key
+    *           uint8_t *p_data - Pointer to the data to be verified
+    *           uint32_t data_size - Size of the data to be verified
+    *           sgx_ec384_signature_t *p_signature - Pointer to the signature
+    *   Output: uint8_t *p_result - Pointer to the result of verification check
+    */
+    sgx_status_t SGXAPI sgx_ecdsa384_verify(const uint8_t *p_data,
+                                        uint32_t data_size,
+                                        const sgx_ec384_public_t *p_public,
+                                        const sgx_ec384_signature_t *p_signature,
+                                        uint8_t *p_result,
+                                        sgx_ecc_state_handle_t ecc_handle);
+
+   /** Verifies an ECDSA P-384 signature over a SHA384 hash. See sgx_ecdsa_verify_hash.
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           sgx_ec384_public_t *p_public - Pointer to the public key
+    *           uint8_t *hash - Pointer to the SHA384 hash to be verified
+    *           sgx_ec384_signature_t *p_signature - Pointer to the signature
+    *   Output: uint8_t *p_result - Pointer to the result of verification check
+    */
+    sgx_status_t SGXAPI sgx_ecdsa384_verify_hash(const uint8_t *hash,
+                                        const sgx_ec384_public_t *p_public,
+                                        const sgx_ec384_signature_t *p_signature,
+                                        uint8_t *p_result,
+                                        sgx_ecc_state_handle_t ecc_handle);
+
+    /** Generates a P-384 public key based on a given private key.
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Input: p_att_priv_key - Input private key
+    *   Output: p_att_pub_key - Output public key - LITTLE ENDIAN
+    *
+    */
+    sgx_status_t SGXAPI sgx_ecc384_calculate_pub_from_priv(const sgx_ec384_private_t *p_att_priv_key,
+        sgx_ec384_public_t  *p_att_pub_key);
+
     /** Computes signature for a given data based on RSA 2048 private key
     *
     * A digital signature over a message consists of a 2048 bit number.
@@ -1027,6 +1322,109 @@ extern "C" {
     */
     sgx_status_t SGXAPI sgx_free_rsa_key(void *p_rsa_key, sgx_rsa_key_type_t key_type, int mod_size, int exp_size);
 
+    /** Computes an RSASSA-PKCS1-v1_5 signature over HASH(p_data).
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: rsa_key - Pointer to the RSA private key.
+    *           rsa_pub_key - Pointer to the RSA public key (optional), used to verify the signature before it is returned.
+    *           hash - Digest algorithm.
+    *           p_data - Pointer to the data to be signed.
+    *           data_size - Size of the data to be signed.
+    *           signature_size - Size of the signature buffer, at least the modulus size.
+    *   Output: p_signature - Pointer to the signature output - BIG ENDIAN
+    *
+    */
+    sgx_status_t SGXAPI sgx_rsa_sign_pkcs1_v15(const void *rsa_key, const void *rsa_pub_key, sgx_rsa_hash_t hash,
+        const uint8_t *p_data, size_t data_size, uint8_t *p_signature, size_t signature_size);
+
+    /** Verifies an RSASSA-PKCS1-v1_5 signature over HASH(p_data).
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: rsa_pub_key - Pointer to the RSA public key.
+    *           hash - Digest algorithm.
+    *           p_data - Pointer to the signed data.
+    *           data_size - Size of the signed data.
+    *           p_signature - Pointer to the signature - BIG ENDIAN
+    *           signature_size - Size of the signature.
+    *   Output: p_result - Pointer to the result of verification check
+    *
+    */
+    sgx_status_t SGXAPI sgx_rsa_verify_pkcs1_v15(const void *rsa_pub_key, sgx_rsa_hash_t hash,
+        const uint8_t *p_data, size_t data_size, const uint8_t *p_signature, size_t signature_size,
+        sgx_rsa_result_t *p_result);
+
+    /** Computes an RSASSA-PSS signature over HASH(p_data), using MGF1 with the same hash and a random salt.
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: rsa_key - Pointer to the RSA private key.
+    *           rsa_pub_key - Pointer to the RSA public key (optional), used to verify the signature before it is returned.
+    *           hash - Digest and MGF1 algorithm.
+    *           salt_size - Salt length in bytes, at most modulus size - hash size - 2.
+    *           p_data - Pointer to the data to be signed.
+    *           data_size - Size of the data to be signed.
+    *           signature_size - Size of the signature buffer, at least the modulus size.
+    *   Output: p_signature - Pointer to the signature output - BIG ENDIAN
+    *
+    */
+    sgx_status_t SGXAPI sgx_rsa_sign_pss(const void *rsa_key, const void *rsa_pub_key, sgx_rsa_hash_t hash, size_t salt_size,
+        const uint8_t *p_data, size_t data_size, uint8_t *p_signature, size_t signature_size);
+
+    /** Verifies an RSASSA-PSS signature over HASH(p_data), accepting any salt length.
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: rsa_pub_key - Pointer to the RSA public key.
+    *           hash - Digest and MGF1 algorithm.
+    *           p_data - Pointer to the signed data.
+    *           data_size - Size of the signed data.
+    *           p_signature - Pointer to the signature - BIG ENDIAN
+    *           signature_size - Size of the signature.
+    *   Output: p_result - Pointer to the result of verification check
+    *
+    */
+    sgx_status_t SGXAPI sgx_rsa_verify_pss(const void *rsa_pub_key, sgx_rsa_hash_t hash,
+        const uint8_t *p_data, size_t data_size, const uint8_t *p_signature, size_t signature_size,
+        sgx_rsa_result_t *p_result);
+
+    /** Encrypt input data [pin_data] using RSA public key, with OAEP and the given hash and label
+    *
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: rsa_key - Pointer to the RSA public key.
+    *           hash - Digest and MGF1 algorithm.
+    *           p_label - Pointer to the label, may be NULL when label_len is 0.
+    *           label_len - Label size.
+    *           pin_data - Pointer to the input data buffer.
+    *           pin_len - Input buffer size, at most modulus size - 2 * hash size - 2.
+    *           pout_len[In/Out] - Pointer to the length of output buffer.
+    *   Output: pout_data - Pointer to the output buffer.
+    *           pout_len - Pointer to amount of data (ciphertext) written.
+    *
+    */
+    sgx_status_t SGXAPI sgx_rsa_pub_encrypt_oaep(const void *rsa_key, sgx_rsa_hash_t hash, const uint8_t *p_label, size_t label_len,
+        unsigned char *pout_data, size_t *pout_len, const unsigned char *pin_data, const size_t pin_len);
+
+    /** Decrypt ciphertext [pin_data] using RSA private key, with OAEP and the given hash and label
+    *
+    * Parameters:
+    *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: rsa_key - Pointer to the RSA private key.
+    *           hash - Digest and MGF1 algorithm.
+    *           p_label - Pointer to the label, may be NULL when label_len is 0.
+    *           label_len - Label size.
+    *           pin_data - Pointer to the input ciphertext buffer.
+    *           pin_len - Ciphertext buffer size, the modulus size.
+    *           pout_len[In/Out] - Pointer to the length of output buffer.
+    *   Output: pout_data - Pointer to the output buffer.
+    *           pout_len - Pointer to amount of data written.
+    *
+    */
+    sgx_status_t SGXAPI sgx_rsa_priv_decrypt_oaep(const void *rsa_key, sgx_rsa_hash_t hash, const uint8_t *p_label, size_t label_len,
+        unsigned char *pout_data, size_t *pout_len, const unsigned char *pin_data, const size_t pin_len);
+
     /** Generates an ECDSA private key based on input random seed.
     *
     * Parameters:
@@ -1074,6 +1472,21 @@ extern "C" {
         uint32_t aad_len,
         sgx_aes_state_handle_t *aes_gcm_state);
 
+    /** Same as sgx_aes_gcm128_init, for 128, 192 or 256-bit keys.
+    *
+    * Parameters:
+    *   Inputs: p_key - Input private key.
+    *           key_len - Length of the key, 16, 24 or 32 bytes.
+    */
+    sgx_status_t SGXAPI sgx_aes_gcm_init_ex(
+        const uint8_t *p_key,
+        uint32_t key_len,
+        const uint8_t *p_iv,
+        uint32_t iv_len,
+        const uint8_t *p_aad,
+        uint32_t aad_len,
+        sgx_aes_state_handle_t *aes_gcm_state);
+
     /** Get authentication tag from the given AES-GCM state.
     *
     * Parameters:
@@ -1157,6 +1570,25 @@ extern "C" {
                         const uint8_t *p_iv,
                         const uint32_t iv_len);
 
+    /** AES-CBC with 128, 192 or 256-bit keys, key_len MUST BE 16, 24 or 32 (bytes). */
+    sgx_status_t SGXAPI sgx_aes_cbc_encrypt_ex(
+                        const uint8_t *p_key,
+                        uint32_t key_len,
+                        const uint8_t *p_src,
+                        const uint32_t src_len,
+                        uint8_t *p_dst,
+                        const uint8_t *p_iv,
+                        const uint32_t iv_len);
+
+    sgx_status_t SGXAPI sgx_aes_cbc_decrypt_ex(
+                        const uint8_t *p_key,
+                        uint32_t key_len,
+                        const uint8_t *p_src,
+                        const uint32_t src_len,
+                        uint8_t *p_dst,
+                        const uint8_t *p_iv,
+                        const uint32_t iv_len);
+
     /** AES-CCM - Only 128-bit key AES-CCM Encryption/Decryption is supported
     *
     * sgx_aes_ccm128_encrypt:
@@ -1209,6 +1641,33 @@ extern "C" {
                                                 uint32_t aad_len,
                                                 const sgx_aes_ccm_128bit_tag_t *p_in_mac);
 
+    /** AES-CCM with 128, 192 or 256-bit keys, otherwise the same as sgx_aes_ccm128_encrypt/decrypt.
+    *
+    * Parameters:
+    *   Inputs: uint8_t *p_key - Pointer to the key used in encryption/decryption operation
+    *           uint32_t key_len - Length of the key - MUST BE 16, 24 or 32 (bytes)
+    */
+    sgx_status_t SGXAPI sgx_aes_ccm_encrypt_ex(const uint8_t *p_key,
+                                                uint32_t key_len,
+                                                const uint8_t *p_src,
+                                                uint32_t src_len,
+                                                uint8_t *p_dst,
+                                                const uint8_t *p_iv,
+                                                uint32_t iv_len,
+                                                const uint8_t *p_aad,
+                                                uint32_t aad_len,
+                                                sgx_aes_ccm_128bit_tag_t *p_out_mac);
+    sgx_status_t SGXAPI sgx_aes_ccm_decrypt_ex(const uint8_t *p_key,
+                                                uint32_t key_len,
+                                                const uint8_t *p_src,
+                                                uint32_t src_len,
+                                                uint8_t *p_dst,
+                                                const uint8_t *p_iv,
+                                                uint32_t iv_len,
+                                                const uint8_t *p_aad,
+                                                uint32_t aad_len,
+                                                const sgx_aes_ccm_128bit_tag_t *p_in_mac);
+
     /** Allocate and initialize AES-CCM encryption state.
     *
     * Parameters:
@@ -1229,6 +1688,21 @@ extern "C" {
         uint32_t aad_len,
         sgx_aes_state_handle_t *aes_ccm_state);
 
+    /** Same as sgx_aes_ccm128_init, for 128, 192 or 256-bit keys.
+    *
+    * Parameters:
+    *   Inputs: p_key - Input private key.
+    *           key_len - Length of the key, 16, 24 or 32 bytes.
+    */
+    sgx_status_t SGXAPI sgx_aes_ccm_init_ex(
+        const uint8_t *p_key,
+        uint32_t key_len,
+        const uint8_t *p_iv,
+        uint32_t iv_len,
+        const uint8_t *p_aad,
+        uint32_t aad_len,
+        sgx_aes_state_handle_t *aes_ccm_state);
+
     /** Get authentication tag from the given AES-CCM state.
     *
     * Parameters:
@@ -1717,6 +2191,44 @@ extern "C" {
                                                     sgx_ec256_dh_shared_t *p_shared_key,
                                                     sgx_ecc_state_handle_t ecc_handle);
 
+    /** Computes the point [k]P on the SM2 curve.
+    *
+    * Return: If context, scalar, point, or result pointer is NULL,
+    *                            SGX_ERROR_INVALID_PARAMETER is returned.
+    *         If P is not a valid point on the elliptic curve, or the result is the point at infinity,
+    *                            SGX_ERROR_INVALID_PARAMETER is returned.
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           sgx_ec256_private_t *p_scalar - Pointer to the scalar k - LITTLE ENDIAN
+    *           sgx_ec256_public_t *p_point - Pointer to the point P - LITTLE ENDIAN
+    *   Output: sgx_ec256_public_t *p_result - Pointer to the resulting point - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_sm2_point_mul(const sgx_ec256_private_t *p_scalar,
+                                          const sgx_ec256_public_t *p_point,
+                                          sgx_ec256_public_t *p_result,
+                                          sgx_ecc_state_handle_t ecc_handle);
+
+    /** Computes the point P + Q on the SM2 curve.
+    *
+    * Return: If context, point, or result pointer is NULL,
+    *                            SGX_ERROR_INVALID_PARAMETER is returned.
+    *         If P or Q is not a valid point on the elliptic curve, or the result is the point at infinity,
+    *                            SGX_ERROR_INVALID_PARAMETER is returned.
+    *
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           sgx_ec256_public_t *p_point_p - Pointer to the point P - LITTLE ENDIAN
+    *           sgx_ec256_public_t *p_point_q - Pointer to the point Q - LITTLE ENDIAN
+    *   Output: sgx_ec256_public_t *p_result - Pointer to the resulting point - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_sm2_point_add(const sgx_ec256_public_t *p_point_p,
+                                          const sgx_ec256_public_t *p_point_q,
+                                          sgx_ec256_public_t *p_result,
+                                          sgx_ecc_state_handle_t ecc_handle);
+
     /** Generates an SM2 private key based on input random seed.
     *
     * Parameters:
@@ -1778,6 +2290,22 @@ extern "C" {
                                     sgx_ec256_signature_t *p_signature,
                                     sgx_ecc_state_handle_t ecc_handle);
 
+   /** Computes a signature over the digest e = SM3(Z || M) with a caller-supplied nonce,
+    * e.g. one derived as in RFC 6979. See sgx_sm2_sign. The nonce must be in [1, n-1].
+    * Parameters:
+    *   Return: sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
+    *   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to the ECC crypto system
+    *           uint8_t *p_hash - Pointer to the hash to be signed - BIG ENDIAN
+    *           sgx_ec256_private_t *p_private - Pointer to the private key - LITTLE ENDIAN
+    *           sgx_ec256_private_t *p_nonce - Pointer to the nonce - LITTLE ENDIAN
+    *   Output: sgx_ec256_signature_t *p_signature - Pointer to the signature - LITTLE ENDIAN
+    */
+    sgx_status_t SGXAPI sgx_sm2_sign_with_nonce(const uint8_t *p_hash,
+                                    const sgx_ec256_private_t *p_private,
+                                    const sgx_ec256_private_t *p_nonce,
+                                    sgx_ec256_signature_t *p_signature,
+                                    sgx_ecc_state_handle_t ecc_handle);
+
    /** Verifies the signature for the given data based on the public key.
     * This API verifies the hash of input data `verify(SM3(p_data))`. First it'll calculate SHA256 hash for given data
     * and then verify the signature for this hash.
@@ -29,6 +29,12 @@ fn main() -> Result<(), &'static str> {
     let target = env::var("TARGET").expect("TARGET was not set");
     let host = env::var("HOST").expect("HOST was not set");
 
+    // The pure-Rust backend needs no native library, unless it is being
+    // cross-checked against IPP.
+    if cfg!(feature = "rcrypto") && !cfg!(feature = "crosscheck") {
+        return Ok(());
+    }
+
     build_libtcrypto(&host, &target).map_err(|_| "Failed to build crypto library.")
 }
 
@@ -24,10 +24,22 @@
 #![no_std]
 #![cfg_attr(target_vendor = "teaclave", feature(rustc_private))]
 
+#[cfg(feature = "rcrypto")]
+extern crate alloc;
+
+#[cfg_attr(feature = "rcrypto", macro_use)]
 extern crate sgx_types;
 
+#[cfg(not(feature = "rcrypto"))]
 pub use self::bindings::*;
+#[cfg(feature = "rcrypto")]
+pub use self::rcrypto::*;
+
+#[cfg(feature = "rcrypto")]
+mod rcrypto;
 
+#[cfg(any(not(feature = "rcrypto"), all(test, feature = "crosscheck")))]
+#[cfg_attr(feature = "rcrypto", allow(dead_code))]
 mod bindings {
     use sgx_types::error::SgxStatus;
     use sgx_types::types::*;
@@ -52,6 +64,34 @@ mod bindings {
         pub fn sgx_sha384_get_hash(sha_handle: ShaHandle, p_hash: *mut Sha384Hash) -> SgxStatus;
         pub fn sgx_sha384_close(sha_handle: ShaHandle) -> SgxStatus;
 
+        pub fn sgx_sha512_msg(p_src: *const u8, src_len: u32, p_hash: *mut Sha512Hash)
+            -> SgxStatus;
+        pub fn sgx_sha512_init(p_sha_handle: *mut ShaHandle) -> SgxStatus;
+        pub fn sgx_sha512_update(
+            p_src: *const u8,
+            src_len: u32,
+            sha_handle: ShaHandle,
+        ) -> SgxStatus;
+        pub fn sgx_sha512_get_hash(sha_handle: ShaHandle, p_hash: *mut Sha512Hash) -> SgxStatus;
+        pub fn sgx_sha512_close(sha_handle: ShaHandle) -> SgxStatus;
+
+        pub fn sgx_sha512_256_msg(
+            p_src: *const u8,
+            src_len: u32,
+            p_hash: *mut Sha512_256Hash,
+        ) -> SgxStatus;
+        pub fn sgx_sha512_256_init(p_sha_handle: *mut ShaHandle) -> SgxStatus;
+        pub fn sgx_sha512_256_update(
+            p_src: *const u8,
+            src_len: u32,
+            sha_handle: ShaHandle,
+        ) -> SgxStatus;
+        pub fn sgx_sha512_256_get_hash(
+            sha_handle: ShaHandle,
+            p_hash: *mut Sha512_256Hash,
+        ) -> SgxStatus;
+        pub fn sgx_sha512_256_close(sha_handle: ShaHandle) -> SgxStatus;
+
         pub fn sgx_sha256_msg(p_src: *const u8, src_len: u32, p_hash: *mut Sha256Hash)
             -> SgxStatus;
         pub fn sgx_sha256_init(p_sha_handle: *mut ShaHandle) -> SgxStatus;
@@ -94,6 +134,32 @@ mod bindings {
             p_in_mac: *const Mac128bit,
         ) -> SgxStatus;
 
+        pub fn sgx_aes_gcm_encrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_dst: *mut u8,
+            p_iv: *const u8,
+            iv_len: u32,
+            p_aad: *const u8,
+            aad_len: u32,
+            p_out_mac: *mut Mac128bit,
+        ) -> SgxStatus;
+
+        pub fn sgx_aes_gcm_decrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_dst: *mut u8,
+            p_iv: *const u8,
+            iv_len: u32,
+            p_aad: *const u8,
+            aad_len: u32,
+            p_in_mac: *const Mac128bit,
+        ) -> SgxStatus;
+
         pub fn sgx_rijndael128_cmac_msg(
             p_key: *const Key128bit,
             p_src: *const u8,
@@ -146,6 +212,35 @@ mod bindings {
 
         pub fn sgx_hmac_sha256_close(hmac_handle: HMacHandle) -> SgxStatus;
 
+        pub fn sgx_hmac_sha384_msg(
+            p_src: *const u8,
+            src_len: i32,
+            p_key: *const u8,
+            key_len: i32,
+            p_mac: *mut u8,
+            mac_len: i32,
+        ) -> SgxStatus;
+
+        pub fn sgx_hmac_sha384_init(
+            p_key: *const u8,
+            key_len: i32,
+            p_hmac_handle: *mut HMacHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_hmac_sha384_update(
+            p_src: *const u8,
+            src_len: i32,
+            hmac_handle: HMacHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_hmac_sha384_final(
+            p_hash: *mut u8,
+            hash_len: i32,
+            hmac_handle: HMacHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_hmac_sha384_close(hmac_handle: HMacHandle) -> SgxStatus;
+
         pub fn sgx_aes_ctr_encrypt(
             p_key: *const Key128bit,
             p_src: *const u8,
@@ -164,6 +259,26 @@ mod bindings {
             p_dst: *mut u8,
         ) -> SgxStatus;
 
+        pub fn sgx_aes_ctr_encrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_ctr: *mut u8,
+            ctr_inc_bits: u32,
+            p_dst: *mut u8,
+        ) -> SgxStatus;
+
+        pub fn sgx_aes_ctr_decrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_ctr: *mut u8,
+            ctr_inc_bits: u32,
+            p_dst: *mut u8,
+        ) -> SgxStatus;
+
         pub fn sgx_ecc256_open_context(p_ecc_handle: *mut EccHandle) -> SgxStatus;
         pub fn sgx_ecc256_close_context(ecc_handle: EccHandle) -> SgxStatus;
 
@@ -203,6 +318,14 @@ mod bindings {
             ecc_handle: EccHandle,
         ) -> SgxStatus;
 
+        pub fn sgx_ecdsa_sign_with_nonce(
+            p_hash: *const u8,
+            p_private: *const Ec256PrivateKey,
+            p_nonce: *const Ec256PrivateKey,
+            p_signature: *mut Ec256Signature,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
         pub fn sgx_ecdsa_verify(
             p_data: *const u8,
             data_size: u32,
@@ -236,6 +359,66 @@ mod bindings {
             p_att_pub_key: *mut Ec256PublicKey,
         ) -> SgxStatus;
 
+        pub fn sgx_ecc384_open_context(p_ecc_handle: *mut EccHandle) -> SgxStatus;
+        pub fn sgx_ecc384_close_context(ecc_handle: EccHandle) -> SgxStatus;
+
+        pub fn sgx_ecc384_create_key_pair(
+            p_private: *mut Ec384PrivateKey,
+            p_public: *mut Ec384PublicKey,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecc384_check_point(
+            p_point: *const Ec384PublicKey,
+            ecc_handle: EccHandle,
+            p_valid: *mut i32,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecc384_compute_shared_dhkey(
+            p_private_b: *const Ec384PrivateKey,
+            p_public_ga: *const Ec384PublicKey,
+            p_shared_key: *mut Ec384SharedKey,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecdsa384_sign(
+            p_data: *const u8,
+            data_size: u32,
+            p_private: *const Ec384PrivateKey,
+            p_signature: *mut Ec384Signature,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecdsa384_sign_with_nonce(
+            p_hash: *const u8,
+            p_private: *const Ec384PrivateKey,
+            p_nonce: *const Ec384PrivateKey,
+            p_signature: *mut Ec384Signature,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecdsa384_verify(
+            p_data: *const u8,
+            data_size: u32,
+            p_public: *const Ec384PublicKey,
+            p_signature: *const Ec384Signature,
+            p_result: *mut u8,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecdsa384_verify_hash(
+            hash: *const u8,
+            p_public: *const Ec384PublicKey,
+            p_signature: *const Ec384Signature,
+            p_result: *mut u8,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_ecc384_calculate_pub_from_priv(
+            p_att_priv_key: *const Ec384PrivateKey,
+            p_att_pub_key: *mut Ec384PublicKey,
+        ) -> SgxStatus;
+
         pub fn sgx_rsa2048_sign(
             p_data: *const u8,
             data_size: u32,
@@ -352,6 +535,69 @@ mod bindings {
             exp_size: i32,
         ) -> SgxStatus;
 
+        pub fn sgx_rsa_sign_pkcs1_v15(
+            rsa_key: *const c_void,
+            rsa_pub_key: *const c_void,
+            hash: RsaHash,
+            p_data: *const u8,
+            data_size: usize,
+            p_signature: *mut u8,
+            signature_size: usize,
+        ) -> SgxStatus;
+
+        pub fn sgx_rsa_verify_pkcs1_v15(
+            rsa_pub_key: *const c_void,
+            hash: RsaHash,
+            p_data: *const u8,
+            data_size: usize,
+            p_signature: *const u8,
+            signature_size: usize,
+            p_result: *mut RsaResult,
+        ) -> SgxStatus;
+
+        pub fn sgx_rsa_sign_pss(
+            rsa_key: *const c_void,
+            rsa_pub_key: *const c_void,
+            hash: RsaHash,
+            salt_size: usize,
+            p_data: *const u8,
+            data_size: usize,
+            p_signature: *mut u8,
+            signature_size: usize,
+        ) -> SgxStatus;
+
+        pub fn sgx_rsa_verify_pss(
+            rsa_pub_key: *const c_void,
+            hash: RsaHash,
+            p_data: *const u8,
+            data_size: usize,
+            p_signature: *const u8,
+            signature_size: usize,
+            p_result: *mut RsaResult,
+        ) -> SgxStatus;
+
+        pub fn sgx_rsa_pub_encrypt_oaep(
+            rsa_key: *const c_void,
+            hash: RsaHash,
+            p_label: *const u8,
+            label_len: usize,
+            pout_data: *mut u8,
+            pout_len: *mut usize,
+            pin_data: *const u8,
+            pin_len: usize,
+        ) -> SgxStatus;
+
+        pub fn sgx_rsa_priv_decrypt_oaep(
+            rsa_key: *const c_void,
+            hash: RsaHash,
+            p_label: *const u8,
+            label_len: usize,
+            pout_data: *mut u8,
+            pout_len: *mut usize,
+            pin_data: *const u8,
+            pin_len: usize,
+        ) -> SgxStatus;
+
         /* intel sgx sdk 2.4 */
         pub fn sgx_aes_gcm128_init(
             p_key: *const u8,
@@ -362,6 +608,16 @@ mod bindings {
             aes_gcm_state: *mut AesHandle,
         ) -> SgxStatus;
 
+        pub fn sgx_aes_gcm_init_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_iv: *const u8,
+            iv_len: u32,
+            p_aad: *const u8,
+            aad_len: u32,
+            aes_gcm_state: *mut AesHandle,
+        ) -> SgxStatus;
+
         pub fn sgx_aes_gcm128_enc_update(
             p_src: *const u8,
             src_len: u32,
@@ -407,6 +663,32 @@ mod bindings {
             p_in_mac: *const Mac128bit,
         ) -> SgxStatus;
 
+        pub fn sgx_aes_ccm_encrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_dst: *mut u8,
+            p_iv: *const u8,
+            iv_len: u32,
+            p_aad: *const u8,
+            aad_len: u32,
+            p_out_mac: *mut Mac128bit,
+        ) -> SgxStatus;
+
+        pub fn sgx_aes_ccm_decrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_dst: *mut u8,
+            p_iv: *const u8,
+            iv_len: u32,
+            p_aad: *const u8,
+            aad_len: u32,
+            p_in_mac: *const Mac128bit,
+        ) -> SgxStatus;
+
         pub fn sgx_aes_ccm128_init(
             p_key: *const u8,
             p_iv: *const u8,
@@ -416,6 +698,16 @@ mod bindings {
             aes_ccm_state: *mut AesHandle,
         ) -> SgxStatus;
 
+        pub fn sgx_aes_ccm_init_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_iv: *const u8,
+            iv_len: u32,
+            p_aad: *const u8,
+            aad_len: u32,
+            aes_ccm_state: *mut AesHandle,
+        ) -> SgxStatus;
+
         pub fn sgx_aes_ccm128_enc_update(
             p_src: *const u8,
             src_len: u32,
@@ -455,6 +747,26 @@ mod bindings {
             iv_len: u32,
         ) -> SgxStatus;
 
+        pub fn sgx_aes_cbc_encrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_dst: *mut u8,
+            p_iv: *const u8,
+            iv_len: u32,
+        ) -> SgxStatus;
+
+        pub fn sgx_aes_cbc_decrypt_ex(
+            p_key: *const u8,
+            key_len: u32,
+            p_src: *const u8,
+            src_len: u32,
+            p_dst: *mut u8,
+            p_iv: *const u8,
+            iv_len: u32,
+        ) -> SgxStatus;
+
         pub fn sgx_sm3_msg(p_src: *const u8, src_len: u32, p_hash: *mut Sm3Hash) -> SgxStatus;
         pub fn sgx_sm3_init(p_sm3_handle: *mut Sm3Handle) -> SgxStatus;
         pub fn sgx_sm3_update(p_src: *const u8, src_len: u32, sm3_handle: Sm3Handle) -> SgxStatus;
@@ -602,6 +914,20 @@ mod bindings {
             ecc_handle: EccHandle,
         ) -> SgxStatus;
 
+        pub fn sgx_sm2_point_mul(
+            p_scalar: *const Ec256PrivateKey,
+            p_point: *const Ec256PublicKey,
+            p_result: *mut Ec256PublicKey,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
+        pub fn sgx_sm2_point_add(
+            p_point_p: *const Ec256PublicKey,
+            p_point_q: *const Ec256PublicKey,
+            p_result: *mut Ec256PublicKey,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
         pub fn sgx_sm2_sign(
             p_data: *const u8,
             data_size: u32,
@@ -610,6 +936,14 @@ mod bindings {
             ecc_handle: EccHandle,
         ) -> SgxStatus;
 
+        pub fn sgx_sm2_sign_with_nonce(
+            p_hash: *const u8,
+            p_private: *const Ec256PrivateKey,
+            p_nonce: *const Ec256PrivateKey,
+            p_signature: *mut Ec256Signature,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus;
+
         pub fn sgx_sm2_verify(
             p_data: *const u8,
             data_size: u32,
@@ -0,0 +1,807 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::modes::{self, Block, BlockCipher128, BLOCK_SIZE, CCM_IV_SIZE};
+use super::{as_slice, copy_in, copy_out, free_handle, handle_mut, into_handle, zero};
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::{Aes128, Aes192, Aes256};
+use cmac::{Cmac, Mac};
+use ghash::universal_hash::UniversalHash;
+use ghash::GHash;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::*;
+use subtle::ConstantTimeEq;
+
+const GCM_IV_SIZE: usize = 12;
+const GCM_MAC_SIZE: usize = 16;
+const CBC_IV_SIZE: u32 = 16;
+
+pub enum Aes {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl Aes {
+    unsafe fn from_raw(p_key: *const u8, key_len: u32) -> SgxResult<Aes> {
+        ensure!(!p_key.is_null(), SgxStatus::InvalidParameter);
+
+        let key = as_slice(p_key, key_len as usize);
+        let aes = match key.len() {
+            16 => Aes::Aes128(Aes128::new(GenericArray::from_slice(key))),
+            24 => Aes::Aes192(Aes192::new(GenericArray::from_slice(key))),
+            32 => Aes::Aes256(Aes256::new(GenericArray::from_slice(key))),
+            _ => bail!(SgxStatus::InvalidParameter),
+        };
+        Ok(aes)
+    }
+}
+
+impl BlockCipher128 for Aes {
+    fn encrypt_block(&self, block: &mut Block) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            Aes::Aes128(aes) => aes.encrypt_block(block),
+            Aes::Aes192(aes) => aes.encrypt_block(block),
+            Aes::Aes256(aes) => aes.encrypt_block(block),
+        }
+    }
+
+    fn decrypt_block(&self, block: &mut Block) {
+        let block = GenericArray::from_mut_slice(block);
+        match self {
+            Aes::Aes128(aes) => aes.decrypt_block(block),
+            Aes::Aes192(aes) => aes.decrypt_block(block),
+            Aes::Aes256(aes) => aes.decrypt_block(block),
+        }
+    }
+}
+
+// GCM with a 96-bit IV, the only size the sgx_tcrypto wrappers accept.
+pub struct Gcm {
+    cipher: Aes,
+    ghash: GHash,
+    j0: Block,
+    counter: Block,
+    keystream: Block,
+    keystream_pos: usize,
+    pending: Block,
+    pending_len: usize,
+    aad_len: u64,
+    text_len: u64,
+}
+
+impl Gcm {
+    fn new(cipher: Aes, iv: &[u8], aad: &[u8]) -> Gcm {
+        let mut h = [0_u8; BLOCK_SIZE];
+        cipher.encrypt_block(&mut h);
+        let mut ghash = GHash::new(GenericArray::from_slice(&h));
+        ghash.update_padded(aad);
+
+        let mut j0 = [0_u8; BLOCK_SIZE];
+        j0[..GCM_IV_SIZE].copy_from_slice(iv);
+        j0[BLOCK_SIZE - 1] = 1;
+
+        Gcm {
+            cipher,
+            ghash,
+            j0,
+            counter: j0,
+            keystream: [0; BLOCK_SIZE],
+            keystream_pos: BLOCK_SIZE,
+            pending: [0; BLOCK_SIZE],
+            pending_len: 0,
+            aad_len: aad.len() as u64,
+            text_len: 0,
+        }
+    }
+
+    fn encrypt(&mut self, data: &mut [u8]) {
+        self.apply_keystream(data);
+        self.hash_update(data);
+    }
+
+    fn decrypt(&mut self, data: &mut [u8]) {
+        self.hash_update(data);
+        self.apply_keystream(data);
+    }
+
+    fn tag(&self) -> Block {
+        let mut ghash = self.ghash.clone();
+        if self.pending_len > 0 {
+            ghash.update_padded(&self.pending[..self.pending_len]);
+        }
+        let mut lengths = [0_u8; BLOCK_SIZE];
+        lengths[..8].copy_from_slice(&(self.aad_len * 8).to_be_bytes());
+        lengths[8..].copy_from_slice(&(self.text_len * 8).to_be_bytes());
+        ghash.update(&[lengths.into()]);
+
+        let mut tag = self.j0;
+        self.cipher.encrypt_block(&mut tag);
+        tag.iter_mut()
+            .zip(ghash.finalize())
+            .for_each(|(t, s)| *t ^= s);
+        tag
+    }
+
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            if self.keystream_pos == BLOCK_SIZE {
+                modes::increment_be(&mut self.counter[BLOCK_SIZE - 4..]);
+                self.keystream = self.counter;
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.keystream_pos = 0;
+            }
+            *byte ^= self.keystream[self.keystream_pos];
+            self.keystream_pos += 1;
+        }
+    }
+
+    fn hash_update(&mut self, mut data: &[u8]) {
+        self.text_len += data.len() as u64;
+        while !data.is_empty() {
+            let n = (BLOCK_SIZE - self.pending_len).min(data.len());
+            self.pending[self.pending_len..self.pending_len + n].copy_from_slice(&data[..n]);
+            self.pending_len += n;
+            data = &data[n..];
+            if self.pending_len == BLOCK_SIZE {
+                self.ghash.update(&[self.pending.into()]);
+                self.pending_len = 0;
+            }
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn sgx_rijndael128GCM_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_out_mac: *mut Mac128bit,
+) -> SgxStatus {
+    sgx_aes_gcm_encrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        p_aad,
+        aad_len,
+        p_out_mac,
+    )
+}
+
+#[allow(non_snake_case)]
+pub unsafe fn sgx_rijndael128GCM_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_in_mac: *const Mac128bit,
+) -> SgxStatus {
+    sgx_aes_gcm_decrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        p_aad,
+        aad_len,
+        p_in_mac,
+    )
+}
+
+pub unsafe fn sgx_aes_gcm_encrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_out_mac: *mut Mac128bit,
+) -> SgxStatus {
+    try_status!(modes::check_aead(
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        GCM_IV_SIZE,
+        p_aad,
+        aad_len,
+        p_out_mac.cast(),
+    ));
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    let mut data = copy_in(p_src, src_len as usize);
+    let mut gcm = Gcm::new(
+        cipher,
+        &copy_in(p_iv, GCM_IV_SIZE),
+        &copy_in(p_aad, aad_len as usize),
+    );
+    gcm.encrypt(&mut data);
+    copy_out(p_dst, &data);
+    (*p_out_mac).copy_from_slice(&gcm.tag());
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_gcm_decrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_in_mac: *const Mac128bit,
+) -> SgxStatus {
+    try_status!(modes::check_aead(
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        GCM_IV_SIZE,
+        p_aad,
+        aad_len,
+        p_in_mac.cast(),
+    ));
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    let mut data = copy_in(p_src, src_len as usize);
+    let mut gcm = Gcm::new(
+        cipher,
+        &copy_in(p_iv, GCM_IV_SIZE),
+        &copy_in(p_aad, aad_len as usize),
+    );
+    gcm.decrypt(&mut data);
+    if !bool::from(gcm.tag().ct_eq(&*p_in_mac)) {
+        zero(p_dst, src_len as usize);
+        return SgxStatus::MacMismatch;
+    }
+    copy_out(p_dst, &data);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_gcm128_init(
+    p_key: *const u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    aes_gcm_state: *mut AesHandle,
+) -> SgxStatus {
+    sgx_aes_gcm_init_ex(
+        p_key,
+        KEY_128BIT_SIZE as u32,
+        p_iv,
+        iv_len,
+        p_aad,
+        aad_len,
+        aes_gcm_state,
+    )
+}
+
+pub unsafe fn sgx_aes_gcm_init_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    aes_gcm_state: *mut AesHandle,
+) -> SgxStatus {
+    if aad_len >= i32::MAX as u32
+        || iv_len as usize != GCM_IV_SIZE
+        || (aad_len > 0 && p_aad.is_null())
+        || p_iv.is_null()
+        || aes_gcm_state.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    let gcm = Gcm::new(
+        cipher,
+        &copy_in(p_iv, GCM_IV_SIZE),
+        &copy_in(p_aad, aad_len as usize),
+    );
+    *aes_gcm_state = into_handle(gcm);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_gcm128_enc_update(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    aes_gcm_state: AesHandle,
+) -> SgxStatus {
+    try_status!(modes::check_update(aes_gcm_state, p_src, src_len, p_dst));
+
+    let mut data = copy_in(p_src, src_len as usize);
+    handle_mut::<Gcm>(aes_gcm_state).encrypt(&mut data);
+    copy_out(p_dst, &data);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_gcm128_dec_update(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    aes_gcm_state: AesHandle,
+) -> SgxStatus {
+    try_status!(modes::check_update(aes_gcm_state, p_src, src_len, p_dst));
+
+    let mut data = copy_in(p_src, src_len as usize);
+    handle_mut::<Gcm>(aes_gcm_state).decrypt(&mut data);
+    copy_out(p_dst, &data);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_gcm128_enc_get_mac(mac: *mut u8, aes_gcm_state: AesHandle) -> SgxStatus {
+    if mac.is_null() || aes_gcm_state.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    copy_out(mac, &handle_mut::<Gcm>(aes_gcm_state).tag());
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_gcm128_dec_verify_mac(mac: *const u8, aes_gcm_state: AesHandle) -> SgxStatus {
+    if mac.is_null() || aes_gcm_state.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let tag = handle_mut::<Gcm>(aes_gcm_state).tag();
+    if bool::from(tag.ct_eq(&copy_in(mac, GCM_MAC_SIZE))) {
+        SgxStatus::Success
+    } else {
+        SgxStatus::MacMismatch
+    }
+}
+
+pub unsafe fn sgx_aes_gcm_close(aes_gcm_state: AesHandle) -> SgxStatus {
+    if aes_gcm_state.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    free_handle::<Gcm>(aes_gcm_state);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_rijndael128_cmac_msg(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_mac: *mut Mac128bit,
+) -> SgxStatus {
+    if p_key.is_null() || p_src.is_null() || p_mac.is_null() || src_len > i32::MAX as u32 {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let mut cmac = <Cmac<Aes128> as KeyInit>::new(GenericArray::from_slice(&*p_key));
+    cmac.update(as_slice(p_src, src_len as usize));
+    (*p_mac).copy_from_slice(&cmac.finalize().into_bytes());
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_cmac128_init(
+    p_key: *const Key128bit,
+    p_cmac_handle: *mut CMacHandle,
+) -> SgxStatus {
+    if p_key.is_null() || p_cmac_handle.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let cmac = <Cmac<Aes128> as KeyInit>::new(GenericArray::from_slice(&*p_key));
+    *p_cmac_handle = into_handle(cmac);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_cmac128_update(
+    p_src: *const u8,
+    src_len: u32,
+    cmac_handle: CMacHandle,
+) -> SgxStatus {
+    if p_src.is_null() || cmac_handle.is_null() || src_len > i32::MAX as u32 {
+        return SgxStatus::InvalidParameter;
+    }
+
+    handle_mut::<Cmac<Aes128>>(cmac_handle).update(as_slice(p_src, src_len as usize));
+    SgxStatus::Success
+}
+
+// Finalising restarts the computation, as IPP's CMACFinal does.
+pub unsafe fn sgx_cmac128_final(cmac_handle: CMacHandle, p_hash: *mut Mac128bit) -> SgxStatus {
+    if cmac_handle.is_null() || p_hash.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let mac = handle_mut::<Cmac<Aes128>>(cmac_handle).finalize_reset();
+    (*p_hash).copy_from_slice(&mac.into_bytes());
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_cmac128_close(cmac_handle: CMacHandle) -> SgxStatus {
+    if cmac_handle.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    free_handle::<Cmac<Aes128>>(cmac_handle);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_aes_ctr_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    sgx_aes_ctr_encrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_ctr,
+        ctr_inc_bits,
+        p_dst,
+    )
+}
+
+pub unsafe fn sgx_aes_ctr_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    sgx_aes_ctr_decrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_ctr,
+        ctr_inc_bits,
+        p_dst,
+    )
+}
+
+pub unsafe fn sgx_aes_ctr_encrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    if p_src.is_null() || p_ctr.is_null() || p_dst.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    modes::ctr_crypt(&cipher, p_src, src_len, p_ctr, ctr_inc_bits, p_dst)
+}
+
+pub unsafe fn sgx_aes_ctr_decrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    sgx_aes_ctr_encrypt_ex(p_key, key_len, p_src, src_len, p_ctr, ctr_inc_bits, p_dst)
+}
+
+pub unsafe fn sgx_aes_ccm128_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_out_mac: *mut Mac128bit,
+) -> SgxStatus {
+    sgx_aes_ccm_encrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        p_aad,
+        aad_len,
+        p_out_mac,
+    )
+}
+
+pub unsafe fn sgx_aes_ccm128_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_in_mac: *const Mac128bit,
+) -> SgxStatus {
+    sgx_aes_ccm_decrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        p_aad,
+        aad_len,
+        p_in_mac,
+    )
+}
+
+pub unsafe fn sgx_aes_ccm_encrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_out_mac: *mut Mac128bit,
+) -> SgxStatus {
+    try_status!(modes::check_aead(
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        CCM_IV_SIZE,
+        p_aad,
+        aad_len,
+        p_out_mac.cast(),
+    ));
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    modes::ccm_encrypt(
+        cipher,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        p_aad,
+        aad_len,
+        p_out_mac.cast(),
+    )
+}
+
+pub unsafe fn sgx_aes_ccm_decrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_in_mac: *const Mac128bit,
+) -> SgxStatus {
+    try_status!(modes::check_aead(
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        CCM_IV_SIZE,
+        p_aad,
+        aad_len,
+        p_in_mac.cast(),
+    ));
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    modes::ccm_decrypt(
+        cipher,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        p_aad,
+        aad_len,
+        p_in_mac.cast(),
+    )
+}
+
+pub unsafe fn sgx_aes_ccm128_init(
+    p_key: *const u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    aes_ccm_state: *mut AesHandle,
+) -> SgxStatus {
+    sgx_aes_ccm_init_ex(
+        p_key,
+        KEY_128BIT_SIZE as u32,
+        p_iv,
+        iv_len,
+        p_aad,
+        aad_len,
+        aes_ccm_state,
+    )
+}
+
+pub unsafe fn sgx_aes_ccm_init_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    aes_ccm_state: *mut AesHandle,
+) -> SgxStatus {
+    if aad_len >= i32::MAX as u32
+        || iv_len as usize != CCM_IV_SIZE
+        || (aad_len > 0 && p_aad.is_null())
+        || p_iv.is_null()
+        || aes_ccm_state.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    modes::ccm_init(cipher, p_iv, p_aad, aad_len, aes_ccm_state)
+}
+
+pub unsafe fn sgx_aes_ccm128_enc_update(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    aes_ccm_state: AesHandle,
+) -> SgxStatus {
+    modes::ccm_update::<Aes>(p_src, src_len, p_dst, aes_ccm_state, true)
+}
+
+pub unsafe fn sgx_aes_ccm128_dec_update(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    aes_ccm_state: AesHandle,
+) -> SgxStatus {
+    modes::ccm_update::<Aes>(p_src, src_len, p_dst, aes_ccm_state, false)
+}
+
+pub unsafe fn sgx_aes_ccm128_enc_get_mac(mac: *mut u8, aes_ccm_state: AesHandle) -> SgxStatus {
+    modes::ccm_get_mac::<Aes>(mac, aes_ccm_state)
+}
+
+pub unsafe fn sgx_aes_ccm128_dec_verify_mac(mac: *const u8, aes_ccm_state: AesHandle) -> SgxStatus {
+    modes::ccm_verify_mac::<Aes>(mac, aes_ccm_state)
+}
+
+pub unsafe fn sgx_aes_ccm_close(aes_ccm_state: AesHandle) -> SgxStatus {
+    modes::ccm_close::<Aes>(aes_ccm_state)
+}
+
+pub unsafe fn sgx_aes_cbc_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+) -> SgxStatus {
+    sgx_aes_cbc_encrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+    )
+}
+
+pub unsafe fn sgx_aes_cbc_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+) -> SgxStatus {
+    sgx_aes_cbc_decrypt_ex(
+        p_key.cast(),
+        KEY_128BIT_SIZE as u32,
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+    )
+}
+
+pub unsafe fn sgx_aes_cbc_encrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+) -> SgxStatus {
+    aes_cbc(p_key, key_len, p_src, src_len, p_dst, p_iv, iv_len, true)
+}
+
+pub unsafe fn sgx_aes_cbc_decrypt_ex(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+) -> SgxStatus {
+    aes_cbc(p_key, key_len, p_src, src_len, p_dst, p_iv, iv_len, false)
+}
+
+unsafe fn aes_cbc(
+    p_key: *const u8,
+    key_len: u32,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    encrypt: bool,
+) -> SgxStatus {
+    if p_src.is_null() || p_iv.is_null() || iv_len != CBC_IV_SIZE || p_dst.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+    let cipher = try_status!(Aes::from_raw(p_key, key_len));
+
+    modes::cbc_crypt(&cipher, p_src, src_len, p_dst, p_iv, encrypt)
+}
@@ -0,0 +1,447 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// NIST P-256 and P-384. Keys, coordinates and signature words are stored
+// little-endian, while the hashes taken by the `_hash`/`_with_nonce` entry
+// points are big-endian, as in the IPP wrappers.
+
+use super::{as_slice, zero};
+use num_bigint::BigUint;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::*;
+
+macro_rules! impl_ecc {
+    ($curve:ident, $uint:ty, $hasher:ty, $key_size:expr,
+     $private:ty, $public:ty, $shared:ty, $signature:ty,
+     $open:ident, $close:ident, $create:ident, $check:ident, $dh:ident,
+     $sign:ident, $sign_with_nonce:ident, $verify:ident, $verify_hash:ident,
+     $pub_from_priv:ident) => {
+        use super::super::{as_slice, free_handle, into_handle, into_status, read_rand, zero};
+        use sgx_types::error::{SgxResult, SgxStatus};
+        use sgx_types::types::*;
+        use sha2::Digest;
+        use $curve::elliptic_curve::group::prime::PrimeCurveAffine;
+        use $curve::elliptic_curve::ops::Reduce;
+        use $curve::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+        use $curve::elliptic_curve::{AffineXCoordinate, Field, PrimeField};
+        use $curve::{
+            AffinePoint, EncodedPoint, FieldBytes, NonZeroScalar, ProjectivePoint, Scalar,
+        };
+
+        const KEY_SIZE: usize = $key_size;
+
+        struct EccContext;
+
+        fn from_le(le: &[u8]) -> FieldBytes {
+            let mut be = FieldBytes::default();
+            be.iter_mut()
+                .zip(le.iter().rev())
+                .for_each(|(b, l)| *b = *l);
+            be
+        }
+
+        fn to_le(be: &FieldBytes, le: &mut [u8]) {
+            le.iter_mut()
+                .zip(be.iter().rev())
+                .for_each(|(l, b)| *l = *b);
+        }
+
+        fn from_words(words: &[u32]) -> Option<Scalar> {
+            let mut le = [0_u8; KEY_SIZE];
+            le.chunks_exact_mut(4)
+                .zip(words)
+                .for_each(|(l, w)| l.copy_from_slice(&w.to_le_bytes()));
+            Option::<Scalar>::from(Scalar::from_repr(from_le(&le)))
+                .filter(|s| !bool::from(s.is_zero()))
+        }
+
+        fn to_words(scalar: &Scalar, words: &mut [u32]) {
+            let mut le = [0_u8; KEY_SIZE];
+            to_le(&scalar.to_repr(), &mut le);
+            words
+                .iter_mut()
+                .zip(le.chunks_exact(4))
+                .for_each(|(w, l)| *w = u32::from_le_bytes([l[0], l[1], l[2], l[3]]));
+        }
+
+        fn private_key(r: &[u8]) -> SgxResult<NonZeroScalar> {
+            Option::from(NonZeroScalar::from_repr(from_le(r))).ok_or(SgxStatus::Unexpected)
+        }
+
+        fn random_scalar() -> SgxResult<NonZeroScalar> {
+            let mut le = [0_u8; KEY_SIZE];
+            loop {
+                read_rand(&mut le)?;
+                if let Ok(k) = private_key(&le) {
+                    return Ok(k);
+                }
+            }
+        }
+
+        fn public_point(public: &$public) -> Option<AffinePoint> {
+            let encoded = EncodedPoint::from_affine_coordinates(
+                &from_le(&public.gx),
+                &from_le(&public.gy),
+                false,
+            );
+            Option::from(AffinePoint::from_encoded_point(&encoded))
+        }
+
+        fn write_public(point: &ProjectivePoint, public: &mut $public) -> SgxResult {
+            let encoded = point.to_affine().to_encoded_point(false);
+            match (encoded.x(), encoded.y()) {
+                (Some(x), Some(y)) => {
+                    to_le(x, &mut public.gx);
+                    to_le(y, &mut public.gy);
+                    Ok(())
+                }
+                _ => Err(SgxStatus::Unexpected),
+            }
+        }
+
+        fn sign_digest(
+            digest: &FieldBytes,
+            private: &NonZeroScalar,
+            nonce: &NonZeroScalar,
+            signature: &mut $signature,
+        ) -> SgxResult {
+            let z = <Scalar as Reduce<$uint>>::from_be_bytes_reduced(*digest);
+            let point = (ProjectivePoint::GENERATOR * **nonce).to_affine();
+            let r = <Scalar as Reduce<$uint>>::from_be_bytes_reduced(point.x());
+            let k_inv =
+                Option::<Scalar>::from(Field::invert(&**nonce)).ok_or(SgxStatus::Unexpected)?;
+            let s = k_inv * (z + r * **private);
+            ensure!(
+                !bool::from(r.is_zero()) && !bool::from(s.is_zero()),
+                SgxStatus::Unexpected
+            );
+
+            to_words(&r, &mut signature.x);
+            to_words(&s, &mut signature.y);
+            Ok(())
+        }
+
+        fn verify_digest(digest: &FieldBytes, public: &$public, signature: &$signature) -> bool {
+            let (q, r, s) = match (
+                public_point(public),
+                from_words(&signature.x),
+                from_words(&signature.y),
+            ) {
+                (Some(q), Some(r), Some(s)) => (q, r, s),
+                _ => return false,
+            };
+
+            let z = <Scalar as Reduce<$uint>>::from_be_bytes_reduced(*digest);
+            let w = Option::<Scalar>::from(Field::invert(&s)).unwrap_or(Scalar::ZERO);
+            let point = (ProjectivePoint::GENERATOR * (z * w) + ProjectivePoint::from(q) * (r * w))
+                .to_affine();
+            if bool::from(point.is_identity()) {
+                return false;
+            }
+            <Scalar as Reduce<$uint>>::from_be_bytes_reduced(point.x()) == r
+        }
+
+        pub unsafe fn $open(p_ecc_handle: *mut EccHandle) -> SgxStatus {
+            if p_ecc_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            *p_ecc_handle = into_handle(EccContext);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $close(ecc_handle: EccHandle) -> SgxStatus {
+            if ecc_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            free_handle::<EccContext>(ecc_handle);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $create(
+            p_private: *mut $private,
+            p_public: *mut $public,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus {
+            if ecc_handle.is_null() || p_private.is_null() || p_public.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let private = try_status!(random_scalar());
+            try_status!(write_public(
+                &(ProjectivePoint::GENERATOR * *private),
+                &mut *p_public
+            ));
+            to_le(&private.to_repr(), &mut (*p_private).r);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $check(
+            p_point: *const $public,
+            ecc_handle: EccHandle,
+            p_valid: *mut i32,
+        ) -> SgxStatus {
+            if ecc_handle.is_null() || p_point.is_null() || p_valid.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            *p_valid = public_point(&*p_point).is_some() as i32;
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $dh(
+            p_private_b: *const $private,
+            p_public_ga: *const $public,
+            p_shared_key: *mut $shared,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus {
+            if ecc_handle.is_null()
+                || p_private_b.is_null()
+                || p_public_ga.is_null()
+                || p_shared_key.is_null()
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let point = match public_point(&*p_public_ga) {
+                Some(point) => point,
+                None => return SgxStatus::InvalidParameter,
+            };
+            let private = try_status!(private_key(&(*p_private_b).r));
+            let shared = (ProjectivePoint::from(point) * *private).to_affine();
+            if bool::from(shared.is_identity()) {
+                return SgxStatus::Unexpected;
+            }
+
+            to_le(&shared.x(), &mut (*p_shared_key).s);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $sign(
+            p_data: *const u8,
+            data_size: u32,
+            p_private: *const $private,
+            p_signature: *mut $signature,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus {
+            if ecc_handle.is_null()
+                || p_private.is_null()
+                || p_signature.is_null()
+                || p_data.is_null()
+                || data_size < 1
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let digest = <$hasher>::digest(as_slice(p_data, data_size as usize));
+            let private = try_status!(private_key(&(*p_private).r));
+            let nonce = try_status!(random_scalar());
+            into_status(sign_digest(&digest, &private, &nonce, &mut *p_signature))
+        }
+
+        pub unsafe fn $sign_with_nonce(
+            p_hash: *const u8,
+            p_private: *const $private,
+            p_nonce: *const $private,
+            p_signature: *mut $signature,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus {
+            if ecc_handle.is_null()
+                || p_private.is_null()
+                || p_nonce.is_null()
+                || p_signature.is_null()
+                || p_hash.is_null()
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let digest = FieldBytes::clone_from_slice(as_slice(p_hash, KEY_SIZE));
+            let private = try_status!(private_key(&(*p_private).r));
+            let nonce = try_status!(private_key(&(*p_nonce).r));
+            into_status(sign_digest(&digest, &private, &nonce, &mut *p_signature))
+        }
+
+        pub unsafe fn $verify(
+            p_data: *const u8,
+            data_size: u32,
+            p_public: *const $public,
+            p_signature: *const $signature,
+            p_result: *mut u8,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus {
+            if ecc_handle.is_null()
+                || p_public.is_null()
+                || p_signature.is_null()
+                || p_data.is_null()
+                || data_size < 1
+                || p_result.is_null()
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let digest = <$hasher>::digest(as_slice(p_data, data_size as usize));
+            $verify_hash(digest.as_ptr(), p_public, p_signature, p_result, ecc_handle)
+        }
+
+        pub unsafe fn $verify_hash(
+            hash: *const u8,
+            p_public: *const $public,
+            p_signature: *const $signature,
+            p_result: *mut u8,
+            ecc_handle: EccHandle,
+        ) -> SgxStatus {
+            if ecc_handle.is_null()
+                || p_public.is_null()
+                || p_signature.is_null()
+                || hash.is_null()
+                || p_result.is_null()
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let digest = FieldBytes::clone_from_slice(as_slice(hash, KEY_SIZE));
+            *p_result = if verify_digest(&digest, &*p_public, &*p_signature) {
+                EcResult::Valid as u8
+            } else {
+                EcResult::InvalidSignature as u8
+            };
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $pub_from_priv(
+            p_att_priv_key: *const $private,
+            p_att_pub_key: *mut $public,
+        ) -> SgxStatus {
+            if p_att_priv_key.is_null() || p_att_pub_key.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let result = private_key(&(*p_att_priv_key).r).and_then(|private| {
+                write_public(
+                    &(ProjectivePoint::GENERATOR * *private),
+                    &mut *p_att_pub_key,
+                )
+            });
+            if result.is_err() {
+                zero(p_att_pub_key.cast(), core::mem::size_of::<$public>());
+            }
+            into_status(result)
+        }
+    };
+}
+
+mod nistp256 {
+    impl_ecc!(
+        p256,
+        p256::U256,
+        sha2::Sha256,
+        ECP256_KEY_SIZE,
+        Ec256PrivateKey,
+        Ec256PublicKey,
+        Ec256SharedKey,
+        Ec256Signature,
+        sgx_ecc256_open_context,
+        sgx_ecc256_close_context,
+        sgx_ecc256_create_key_pair,
+        sgx_ecc256_check_point,
+        sgx_ecc256_compute_shared_dhkey,
+        sgx_ecdsa_sign,
+        sgx_ecdsa_sign_with_nonce,
+        sgx_ecdsa_verify,
+        sgx_ecdsa_verify_hash,
+        sgx_ecc256_calculate_pub_from_priv
+    );
+}
+
+mod nistp384 {
+    impl_ecc!(
+        p384,
+        p384::U384,
+        sha2::Sha384,
+        ECP384_KEY_SIZE,
+        Ec384PrivateKey,
+        Ec384PublicKey,
+        Ec384SharedKey,
+        Ec384Signature,
+        sgx_ecc384_open_context,
+        sgx_ecc384_close_context,
+        sgx_ecc384_create_key_pair,
+        sgx_ecc384_check_point,
+        sgx_ecc384_compute_shared_dhkey,
+        sgx_ecdsa384_sign,
+        sgx_ecdsa384_sign_with_nonce,
+        sgx_ecdsa384_verify,
+        sgx_ecdsa384_verify_hash,
+        sgx_ecc384_calculate_pub_from_priv
+    );
+}
+
+pub use self::nistp256::*;
+pub use self::nistp384::*;
+
+// out_key = (hash_drg mod m) + 1, written big-endian. Both inputs are
+// little-endian; only a 32-byte modulus is accepted.
+pub(super) unsafe fn calculate_priv_key(
+    hash_drg: *const u8,
+    hash_drg_len: i32,
+    modulus: *const u8,
+    modulus_len: i32,
+    out_key: *mut u8,
+    out_key_len: i32,
+) -> SgxStatus {
+    if out_key.is_null()
+        || hash_drg_len <= 0
+        || modulus_len <= 0
+        || out_key_len <= 0
+        || hash_drg.is_null()
+        || modulus.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let m = BigUint::from_bytes_le(as_slice(modulus, modulus_len as usize));
+    if modulus_len as usize != ECP256_KEY_SIZE || m == BigUint::default() {
+        zero(out_key, out_key_len as usize);
+        return SgxStatus::Unexpected;
+    }
+
+    let d = BigUint::from_bytes_le(as_slice(hash_drg, hash_drg_len as usize));
+    let key = (d % m + 1_u32).to_bytes_be();
+    let out = super::as_slice_mut(out_key, ECP256_KEY_SIZE);
+    out.fill(0);
+    out[ECP256_KEY_SIZE - key.len()..].copy_from_slice(&key);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_calculate_ecdsa_priv_key(
+    hash_drg: *const u8,
+    hash_drg_len: i32,
+    sgx_nistp256_r_m1: *const u8,
+    sgx_nistp256_r_m1_len: i32,
+    out_key: *mut u8,
+    out_key_len: i32,
+) -> SgxStatus {
+    calculate_priv_key(
+        hash_drg,
+        hash_drg_len,
+        sgx_nistp256_r_m1,
+        sgx_nistp256_r_m1_len,
+        out_key,
+        out_key_len,
+    )
+}
@@ -0,0 +1,142 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::sm3::Sm3;
+use super::{as_slice, free_handle, handle_mut, into_handle, zero};
+use hmac::{Hmac, Mac};
+use sgx_types::error::SgxStatus;
+use sgx_types::types::*;
+use sha2::{Sha256, Sha384};
+
+// Writes the leading `len` bytes of the tag; asking for more than the digest
+// size is an error, which IPP reports after clearing the output.
+unsafe fn write_mac(tag: &[u8], p_mac: *mut u8, len: i32) -> SgxStatus {
+    let len = len as usize;
+    if len > tag.len() {
+        zero(p_mac, len);
+        return SgxStatus::Unexpected;
+    }
+
+    p_mac.copy_from_nonoverlapping(tag.as_ptr(), len);
+    SgxStatus::Success
+}
+
+macro_rules! impl_hmac {
+    ($hasher:ty, $msg:ident, $init:ident, $update:ident, $final:ident, $close:ident) => {
+        pub unsafe fn $msg(
+            p_src: *const u8,
+            src_len: i32,
+            p_key: *const u8,
+            key_len: i32,
+            p_mac: *mut u8,
+            mac_len: i32,
+        ) -> SgxStatus {
+            if p_src.is_null()
+                || p_key.is_null()
+                || p_mac.is_null()
+                || src_len <= 0
+                || key_len <= 0
+                || mac_len <= 0
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let mut hmac = <Hmac<$hasher>>::new_from_slice(as_slice(p_key, key_len as usize))
+                .expect("HMAC accepts keys of any length");
+            hmac.update(as_slice(p_src, src_len as usize));
+            write_mac(&hmac.finalize().into_bytes(), p_mac, mac_len)
+        }
+
+        pub unsafe fn $init(
+            p_key: *const u8,
+            key_len: i32,
+            p_hmac_handle: *mut HMacHandle,
+        ) -> SgxStatus {
+            if p_key.is_null() || key_len <= 0 || p_hmac_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let hmac = <Hmac<$hasher>>::new_from_slice(as_slice(p_key, key_len as usize))
+                .expect("HMAC accepts keys of any length");
+            *p_hmac_handle = into_handle(hmac);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $update(
+            p_src: *const u8,
+            src_len: i32,
+            hmac_handle: HMacHandle,
+        ) -> SgxStatus {
+            if p_src.is_null() || src_len <= 0 || hmac_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            handle_mut::<Hmac<$hasher>>(hmac_handle).update(as_slice(p_src, src_len as usize));
+            SgxStatus::Success
+        }
+
+        // A successful final restarts the computation with the same key.
+        pub unsafe fn $final(p_hash: *mut u8, hash_len: i32, hmac_handle: HMacHandle) -> SgxStatus {
+            if p_hash.is_null() || hash_len <= 0 || hmac_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let hmac = handle_mut::<Hmac<$hasher>>(hmac_handle);
+            let status = write_mac(&hmac.clone().finalize().into_bytes(), p_hash, hash_len);
+            if status == SgxStatus::Success {
+                hmac.reset();
+            }
+            status
+        }
+
+        pub unsafe fn $close(hmac_handle: HMacHandle) -> SgxStatus {
+            if hmac_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            free_handle::<Hmac<$hasher>>(hmac_handle);
+            SgxStatus::Success
+        }
+    };
+}
+
+impl_hmac!(
+    Sha256,
+    sgx_hmac_sha256_msg,
+    sgx_hmac_sha256_init,
+    sgx_hmac_sha256_update,
+    sgx_hmac_sha256_final,
+    sgx_hmac_sha256_close
+);
+
+impl_hmac!(
+    Sha384,
+    sgx_hmac_sha384_msg,
+    sgx_hmac_sha384_init,
+    sgx_hmac_sha384_update,
+    sgx_hmac_sha384_final,
+    sgx_hmac_sha384_close
+);
+
+impl_hmac!(
+    Sm3,
+    sgx_hmac_sm3_msg,
+    sgx_hmac_sm3_init,
+    sgx_hmac_sm3_update,
+    sgx_hmac_sm3_final,
+    sgx_hmac_sm3_close
+);
@@ -0,0 +1,136 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! Pure-Rust implementation of the sgx_tcrypto interface.
+//!
+//! Every function mirrors the signature and error behaviour of its
+//! IPP-backed counterpart in `bindings`, so `sgx_crypto` builds unchanged on
+//! top of either backend.
+
+#![allow(clippy::missing_safety_doc)]
+#![allow(clippy::too_many_arguments)]
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr;
+use core::slice;
+use rand_core::{OsRng, RngCore};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::c_void;
+use zeroize::Zeroizing;
+
+macro_rules! try_status {
+    ($expr:expr) => {
+        match $expr {
+            Ok(v) => v,
+            Err(e) => return e,
+        }
+    };
+}
+
+mod aes;
+mod ecc;
+mod mac;
+mod modes;
+mod rsa;
+mod sha;
+mod sm2;
+mod sm3;
+mod sm4;
+
+#[cfg(all(test, feature = "crosscheck"))]
+mod tests;
+
+pub use self::aes::*;
+pub use self::ecc::*;
+pub use self::mac::*;
+pub use self::rsa::*;
+pub use self::sha::*;
+pub use self::sm2::*;
+pub use self::sm4::*;
+
+pub unsafe fn sgx_init_crypto_lib(_cpu_features: u64, _cpuid_table: *const u32) -> SgxStatus {
+    SgxStatus::Success
+}
+
+#[inline]
+fn into_status(result: SgxResult) -> SgxStatus {
+    match result {
+        Ok(()) => SgxStatus::Success,
+        Err(e) => e,
+    }
+}
+
+fn read_rand(buf: &mut [u8]) -> SgxResult {
+    OsRng.try_fill_bytes(buf).map_err(|_| SgxStatus::Unexpected)
+}
+
+// A null pointer is only accepted together with a zero length; callers
+// validate that combination first, as the C wrappers do.
+#[inline]
+unsafe fn as_slice<'a>(p: *const u8, len: usize) -> &'a [u8] {
+    if p.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(p, len)
+    }
+}
+
+#[inline]
+unsafe fn as_slice_mut<'a>(p: *mut u8, len: usize) -> &'a mut [u8] {
+    if p.is_null() || len == 0 {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(p, len)
+    }
+}
+
+#[inline]
+fn into_handle<T>(state: T) -> *mut c_void {
+    Box::into_raw(Box::new(state)).cast()
+}
+
+#[inline]
+unsafe fn handle_mut<'a, T>(handle: *mut c_void) -> &'a mut T {
+    &mut *handle.cast::<T>()
+}
+
+#[inline]
+unsafe fn free_handle<T>(handle: *mut c_void) {
+    drop(Box::from_raw(handle.cast::<T>()));
+}
+
+#[inline]
+unsafe fn zero(p: *mut u8, len: usize) {
+    if !p.is_null() {
+        ptr::write_bytes(p, 0, len);
+    }
+}
+
+// The source is copied before the destination is touched, so in-place calls
+// (p_src == p_dst) never alias a shared and a mutable slice.
+#[inline]
+unsafe fn copy_in(p: *const u8, len: usize) -> Zeroizing<Vec<u8>> {
+    Zeroizing::new(as_slice(p, len).to_vec())
+}
+
+#[inline]
+unsafe fn copy_out(p: *mut u8, data: &[u8]) {
+    if !data.is_empty() {
+        ptr::copy_nonoverlapping(data.as_ptr(), p, data.len());
+    }
+}
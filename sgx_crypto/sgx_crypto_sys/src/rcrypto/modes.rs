@@ -0,0 +1,420 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// Block cipher modes shared by the AES and SM4 front ends. All of them work
+// in place, so the FFI layer copies the input first and never aliases the
+// caller's source and destination buffers.
+
+use super::{copy_in, copy_out, free_handle, handle_mut, into_handle, into_status, zero};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::c_void;
+use subtle::ConstantTimeEq;
+
+pub const BLOCK_SIZE: usize = 16;
+
+pub type Block = [u8; BLOCK_SIZE];
+
+pub trait BlockCipher128 {
+    fn encrypt_block(&self, block: &mut Block);
+    fn decrypt_block(&self, block: &mut Block);
+}
+
+#[inline]
+fn xor_in_place(data: &mut [u8], keystream: &[u8]) {
+    data.iter_mut().zip(keystream).for_each(|(d, k)| *d ^= k);
+}
+
+// IPP treats the low `inc_bits` bits of the counter block as a big-endian
+// counter that wraps without carrying into the nonce bits, and returns the
+// next unused counter to the caller.
+pub fn ctr<C: BlockCipher128>(
+    cipher: &C,
+    counter: &mut Block,
+    inc_bits: u32,
+    data: &mut [u8],
+) -> SgxResult {
+    ensure!(!data.is_empty(), SgxStatus::InvalidParameter);
+    ensure!((1..=128).contains(&inc_bits), SgxStatus::Unexpected);
+
+    let mask = if inc_bits == 128 {
+        u128::MAX
+    } else {
+        (1_u128 << inc_bits) - 1
+    };
+    let mut ctr = u128::from_be_bytes(*counter);
+    for chunk in data.chunks_mut(BLOCK_SIZE) {
+        let mut keystream = ctr.to_be_bytes();
+        cipher.encrypt_block(&mut keystream);
+        xor_in_place(chunk, &keystream);
+        ctr = (ctr & !mask) | (ctr.wrapping_add(1) & mask);
+    }
+    *counter = ctr.to_be_bytes();
+    Ok(())
+}
+
+fn check_cbc_len(len: usize) -> SgxResult {
+    ensure!(len > 0, SgxStatus::InvalidParameter);
+    ensure!(len % BLOCK_SIZE == 0, SgxStatus::Unexpected);
+    Ok(())
+}
+
+pub fn cbc_encrypt<C: BlockCipher128>(cipher: &C, iv: &Block, data: &mut [u8]) -> SgxResult {
+    check_cbc_len(data.len())?;
+
+    let mut chain = *iv;
+    for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+        xor_in_place(&mut chain, chunk);
+        cipher.encrypt_block(&mut chain);
+        chunk.copy_from_slice(&chain);
+    }
+    Ok(())
+}
+
+pub fn cbc_decrypt<C: BlockCipher128>(cipher: &C, iv: &Block, data: &mut [u8]) -> SgxResult {
+    check_cbc_len(data.len())?;
+
+    let mut chain = *iv;
+    for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+        let mut block = [0_u8; BLOCK_SIZE];
+        block.copy_from_slice(chunk);
+        let next = block;
+        cipher.decrypt_block(&mut block);
+        xor_in_place(&mut block, &chain);
+        chunk.copy_from_slice(&block);
+        chain = next;
+    }
+    Ok(())
+}
+
+pub const CCM_IV_SIZE: usize = 12;
+pub const CCM_MAC_SIZE: usize = 16;
+
+// CCM with a 12-byte nonce and a 16-byte tag, as driven by the sgx_tcrypto
+// wrappers. They call CCMStart before setting the tag and message lengths,
+// so B0 is built from IPP's initial values (a 4-byte tag and an empty
+// message) rather than from the real ones.
+pub struct Ccm<C: BlockCipher128> {
+    cipher: C,
+    mac: Block,
+    pending: Block,
+    pending_len: usize,
+    counter: Block,
+    s0: Block,
+    keystream: Block,
+    keystream_pos: usize,
+}
+
+impl<C: BlockCipher128> Ccm<C> {
+    pub fn new(cipher: C, iv: &[u8], aad: &[u8]) -> Ccm<C> {
+        let mut b0 = [0_u8; BLOCK_SIZE];
+        // M' = (4 - 2) / 2, L' = 15 - 12 - 1.
+        b0[0] = 0x08 | 0x02;
+        if !aad.is_empty() {
+            b0[0] |= 0x40;
+        }
+        b0[1..=CCM_IV_SIZE].copy_from_slice(iv);
+        cipher.encrypt_block(&mut b0);
+
+        let mut counter = [0_u8; BLOCK_SIZE];
+        counter[0] = (BLOCK_SIZE - 1 - CCM_IV_SIZE - 1) as u8;
+        counter[1..=CCM_IV_SIZE].copy_from_slice(iv);
+        let mut s0 = counter;
+        cipher.encrypt_block(&mut s0);
+
+        let mut ccm = Ccm {
+            cipher,
+            mac: b0,
+            pending: [0; BLOCK_SIZE],
+            pending_len: 0,
+            counter,
+            s0,
+            keystream: [0; BLOCK_SIZE],
+            keystream_pos: BLOCK_SIZE,
+        };
+
+        if !aad.is_empty() {
+            let len = aad.len();
+            if len < 0xff00 {
+                ccm.mac_update(&(len as u16).to_be_bytes());
+            } else {
+                ccm.mac_update(&[0xff, 0xfe]);
+                ccm.mac_update(&(len as u32).to_be_bytes());
+            }
+            ccm.mac_update(aad);
+            ccm.mac_flush();
+        }
+        ccm
+    }
+
+    pub fn encrypt(&mut self, data: &mut [u8]) {
+        self.mac_update(data);
+        self.apply_keystream(data);
+    }
+
+    pub fn decrypt(&mut self, data: &mut [u8]) {
+        self.apply_keystream(data);
+        self.mac_update(data);
+    }
+
+    pub fn tag(&self) -> Block {
+        let mut mac = self.mac;
+        if self.pending_len > 0 {
+            xor_in_place(&mut mac, &self.pending[..self.pending_len]);
+            self.cipher.encrypt_block(&mut mac);
+        }
+        xor_in_place(&mut mac, &self.s0);
+        mac
+    }
+
+    fn mac_update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = (BLOCK_SIZE - self.pending_len).min(data.len());
+            self.pending[self.pending_len..self.pending_len + n].copy_from_slice(&data[..n]);
+            self.pending_len += n;
+            data = &data[n..];
+            if self.pending_len == BLOCK_SIZE {
+                self.mac_flush();
+            }
+        }
+    }
+
+    fn mac_flush(&mut self) {
+        if self.pending_len > 0 {
+            xor_in_place(&mut self.mac, &self.pending[..self.pending_len]);
+            self.cipher.encrypt_block(&mut self.mac);
+            self.pending_len = 0;
+        }
+    }
+
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            if self.keystream_pos == BLOCK_SIZE {
+                increment_be(&mut self.counter[BLOCK_SIZE - 3..]);
+                self.keystream = self.counter;
+                self.cipher.encrypt_block(&mut self.keystream);
+                self.keystream_pos = 0;
+            }
+            *byte ^= self.keystream[self.keystream_pos];
+            self.keystream_pos += 1;
+        }
+    }
+}
+
+#[inline]
+pub fn increment_be(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
+
+// Parameter checks shared by the one-shot GCM and CCM wrappers.
+pub unsafe fn check_aead(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *const u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    iv_size: usize,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_mac: *const u8,
+) -> SgxResult {
+    ensure!(
+        !(src_len > 0 && (p_src.is_null() || p_dst.is_null()))
+            && !(aad_len > 0 && p_aad.is_null())
+            && !(p_src.is_null() && p_aad.is_null())
+            && !p_iv.is_null()
+            && iv_len as usize == iv_size
+            && !p_mac.is_null()
+            && src_len <= i32::MAX as u32
+            && aad_len <= i32::MAX as u32,
+        SgxStatus::InvalidParameter
+    );
+    Ok(())
+}
+
+pub unsafe fn check_update(
+    state: *mut c_void,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+) -> SgxResult {
+    ensure!(
+        !state.is_null()
+            && !p_src.is_null()
+            && !p_dst.is_null()
+            && src_len > 0
+            && src_len < i32::MAX as u32,
+        SgxStatus::InvalidParameter
+    );
+    Ok(())
+}
+
+pub unsafe fn ccm_encrypt<C: BlockCipher128>(
+    cipher: C,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_out_mac: *mut u8,
+) -> SgxStatus {
+    let mut data = copy_in(p_src, src_len as usize);
+    let mut ccm = Ccm::new(
+        cipher,
+        &copy_in(p_iv, CCM_IV_SIZE),
+        &copy_in(p_aad, aad_len as usize),
+    );
+    ccm.encrypt(&mut data);
+    copy_out(p_dst, &data);
+    copy_out(p_out_mac, &ccm.tag());
+    SgxStatus::Success
+}
+
+pub unsafe fn ccm_decrypt<C: BlockCipher128>(
+    cipher: C,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_in_mac: *const u8,
+) -> SgxStatus {
+    let mut data = copy_in(p_src, src_len as usize);
+    let mut ccm = Ccm::new(
+        cipher,
+        &copy_in(p_iv, CCM_IV_SIZE),
+        &copy_in(p_aad, aad_len as usize),
+    );
+    ccm.decrypt(&mut data);
+    let tag = ccm.tag();
+    if !bool::from(tag.ct_eq(&copy_in(p_in_mac, CCM_MAC_SIZE))) {
+        zero(p_dst, src_len as usize);
+        return SgxStatus::MacMismatch;
+    }
+    copy_out(p_dst, &data);
+    SgxStatus::Success
+}
+
+pub unsafe fn ccm_init<C: BlockCipher128>(
+    cipher: C,
+    p_iv: *const u8,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_state: *mut *mut c_void,
+) -> SgxStatus {
+    let ccm = Ccm::new(
+        cipher,
+        &copy_in(p_iv, CCM_IV_SIZE),
+        &copy_in(p_aad, aad_len as usize),
+    );
+    *p_state = into_handle(ccm);
+    SgxStatus::Success
+}
+
+pub unsafe fn ccm_update<C: BlockCipher128>(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    state: *mut c_void,
+    encrypt: bool,
+) -> SgxStatus {
+    into_status(check_update(state, p_src, src_len, p_dst).map(|_| {
+        let ccm = handle_mut::<Ccm<C>>(state);
+        let mut data = copy_in(p_src, src_len as usize);
+        if encrypt {
+            ccm.encrypt(&mut data);
+        } else {
+            ccm.decrypt(&mut data);
+        }
+        copy_out(p_dst, &data);
+    }))
+}
+
+pub unsafe fn ccm_get_mac<C: BlockCipher128>(mac: *mut u8, state: *mut c_void) -> SgxStatus {
+    if mac.is_null() || state.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    copy_out(mac, &handle_mut::<Ccm<C>>(state).tag());
+    SgxStatus::Success
+}
+
+pub unsafe fn ccm_verify_mac<C: BlockCipher128>(mac: *const u8, state: *mut c_void) -> SgxStatus {
+    if mac.is_null() || state.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let tag = handle_mut::<Ccm<C>>(state).tag();
+    if bool::from(tag.ct_eq(&copy_in(mac, CCM_MAC_SIZE))) {
+        SgxStatus::Success
+    } else {
+        SgxStatus::MacMismatch
+    }
+}
+
+pub unsafe fn ccm_close<C: BlockCipher128>(state: *mut c_void) -> SgxStatus {
+    if state.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    free_handle::<Ccm<C>>(state);
+    SgxStatus::Success
+}
+
+pub unsafe fn ctr_crypt<C: BlockCipher128>(
+    cipher: &C,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    let mut data = copy_in(p_src, src_len as usize);
+    let mut counter = [0_u8; BLOCK_SIZE];
+    counter.copy_from_slice(&copy_in(p_ctr, BLOCK_SIZE));
+
+    into_status(ctr(cipher, &mut counter, ctr_inc_bits, &mut data).map(|_| {
+        copy_out(p_dst, &data);
+        copy_out(p_ctr, &counter);
+    }))
+}
+
+pub unsafe fn cbc_crypt<C: BlockCipher128>(
+    cipher: &C,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    encrypt: bool,
+) -> SgxStatus {
+    let mut data = copy_in(p_src, src_len as usize);
+    let mut iv = [0_u8; BLOCK_SIZE];
+    iv.copy_from_slice(&copy_in(p_iv, BLOCK_SIZE));
+
+    let result = if encrypt {
+        cbc_encrypt(cipher, &iv, &mut data)
+    } else {
+        cbc_decrypt(cipher, &iv, &mut data)
+    };
+    into_status(result.map(|_| copy_out(p_dst, &data)))
+}
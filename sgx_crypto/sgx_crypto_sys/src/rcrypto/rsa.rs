@@ -0,0 +1,1057 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// RSA keys, PKCS#1 v1.5, PSS and OAEP. Key components cross the interface
+// little-endian, while signatures and ciphertexts are big-endian octet
+// strings, as in the IPP wrappers.
+
+use super::{as_slice, as_slice_mut, copy_out, free_handle, into_handle, read_rand, zero};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ptr;
+use num_bigint::prime::probably_prime;
+use num_bigint::{BigUint, ModInverse};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::*;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, Zeroizing};
+
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+const SHA384_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05,
+    0x00, 0x04, 0x30,
+];
+const SHA512_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05,
+    0x00, 0x04, 0x40,
+];
+
+struct RsaPublic {
+    n: BigUint,
+    e: BigUint,
+}
+
+// Type 1 keys hold (n, d) and type 2 keys the CRT form. The public exponent
+// is optional for both; when present it is used to blind the private
+// operation.
+enum RsaPrivate {
+    Type1 {
+        n: BigUint,
+        d: BigUint,
+        e: Option<BigUint>,
+    },
+    Type2 {
+        n: BigUint,
+        p: BigUint,
+        q: BigUint,
+        dp: BigUint,
+        dq: BigUint,
+        qinv: BigUint,
+        e: Option<BigUint>,
+    },
+}
+
+impl Drop for RsaPrivate {
+    fn drop(&mut self) {
+        match self {
+            RsaPrivate::Type1 { d, .. } => d.zeroize(),
+            RsaPrivate::Type2 {
+                p, q, dp, dq, qinv, ..
+            } => {
+                p.zeroize();
+                q.zeroize();
+                dp.zeroize();
+                dq.zeroize();
+                qinv.zeroize();
+            }
+        }
+    }
+}
+
+impl RsaPublic {
+    fn size(&self) -> usize {
+        (self.n.bits() + 7) / 8
+    }
+
+    fn encrypt(&self, m: &BigUint) -> SgxResult<BigUint> {
+        ensure!(*m < self.n, SgxStatus::InvalidParameter);
+        Ok(m.modpow(&self.e, &self.n))
+    }
+}
+
+impl RsaPrivate {
+    fn modulus(&self) -> &BigUint {
+        match self {
+            RsaPrivate::Type1 { n, .. } | RsaPrivate::Type2 { n, .. } => n,
+        }
+    }
+
+    fn exponent(&self) -> Option<&BigUint> {
+        match self {
+            RsaPrivate::Type1 { e, .. } | RsaPrivate::Type2 { e, .. } => e.as_ref(),
+        }
+    }
+
+    // The modulus size the C wrappers report, which for type 2 keys is
+    // derived from the size of p.
+    fn size(&self) -> usize {
+        match self {
+            RsaPrivate::Type1 { n, .. } => (n.bits() + 7) / 8,
+            RsaPrivate::Type2 { p, .. } => (p.bits() + 7) / 8 * 2,
+        }
+    }
+
+    fn block_size(&self) -> usize {
+        (self.modulus().bits() + 7) / 8
+    }
+
+    fn raw_decrypt(&self, c: &BigUint) -> BigUint {
+        match self {
+            RsaPrivate::Type1 { n, d, .. } => c.modpow(d, n),
+            RsaPrivate::Type2 {
+                p, q, dp, dq, qinv, ..
+            } => {
+                let m1 = Zeroizing::new(c.modpow(dp, p));
+                let m2 = Zeroizing::new(c.modpow(dq, q));
+                let h = Zeroizing::new(((&*m1 + p) - (&*m2 % p)) * qinv % p);
+                &*m2 + &*h * q
+            }
+        }
+    }
+
+    fn decrypt(&self, c: &BigUint) -> SgxResult<BigUint> {
+        let n = self.modulus();
+        ensure!(c < n, SgxStatus::InvalidParameter);
+
+        let e = match self.exponent() {
+            Some(e) => e,
+            None => return Ok(self.raw_decrypt(c)),
+        };
+
+        let (r, r_inv) = blinding_factor(n)?;
+        let blinded = c * r.modpow(e, n) % n;
+        let m = self.raw_decrypt(&blinded) * &*r_inv % n;
+        // An exponent that does not belong to the key breaks the blinding;
+        // fall back to the plain operation in that case.
+        if m.modpow(e, n) == *c {
+            Ok(m)
+        } else {
+            Ok(self.raw_decrypt(c))
+        }
+    }
+
+    fn sign(&self, em: &[u8], public: Option<&RsaPublic>) -> SgxResult<Vec<u8>> {
+        let m = Zeroizing::new(BigUint::from_bytes_be(em));
+        let s = self.decrypt(&m)?;
+        if let Some(public) = public {
+            ensure!(public.encrypt(&s)? == *m, SgxStatus::Unexpected);
+        }
+        Ok(i2osp(&s, self.block_size()))
+    }
+}
+
+fn blinding_factor(n: &BigUint) -> SgxResult<(BigUint, Zeroizing<BigUint>)> {
+    let mut bytes = Zeroizing::new(vec![0_u8; (n.bits() + 7) / 8]);
+    loop {
+        read_rand(&mut bytes)?;
+        let r = BigUint::from_bytes_be(&bytes) % n;
+        if let Some(r_inv) = (&r).mod_inverse(n).and_then(|v| v.to_biguint()) {
+            return Ok((r, Zeroizing::new(r_inv)));
+        }
+    }
+}
+
+struct KeyPair {
+    n: BigUint,
+    d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    dp: BigUint,
+    dq: BigUint,
+    qinv: BigUint,
+}
+
+impl Drop for KeyPair {
+    fn drop(&mut self) {
+        self.d.zeroize();
+        self.p.zeroize();
+        self.q.zeroize();
+        self.dp.zeroize();
+        self.dq.zeroize();
+        self.qinv.zeroize();
+    }
+}
+
+// A random prime of exactly `bits` bits with the top two bits set, so that
+// the product of two of them has exactly 2 * bits bits, and with p - 1
+// coprime to e.
+fn generate_prime(bits: usize, e: &BigUint) -> SgxResult<BigUint> {
+    let one = BigUint::from(1_u32);
+    let mut bytes = Zeroizing::new(vec![0_u8; (bits + 7) / 8]);
+    loop {
+        read_rand(&mut bytes)?;
+        let excess = bytes.len() * 8 - bits;
+        bytes[0] &= 0xff >> excess;
+        bytes[0] |= 0xc0 >> excess;
+        if excess == 7 {
+            bytes[1] |= 0x80;
+        }
+        let last = bytes.len() - 1;
+        bytes[last] |= 1;
+
+        let p = BigUint::from_bytes_be(&bytes);
+        if probably_prime(&p, 20) && (&p - &one).mod_inverse(e).is_some() {
+            return Ok(p);
+        }
+    }
+}
+
+fn generate_key(prime_bits: usize, e: &BigUint) -> SgxResult<KeyPair> {
+    let one = BigUint::from(1_u32);
+    ensure!(
+        prime_bits >= 16 && *e > one && e.bits() < prime_bits,
+        SgxStatus::Unexpected
+    );
+
+    loop {
+        let p = generate_prime(prime_bits, e)?;
+        let q = generate_prime(prime_bits, e)?;
+        if p == q {
+            continue;
+        }
+
+        let phi = Zeroizing::new((&p - &one) * (&q - &one));
+        let d = match e.mod_inverse(&*phi).and_then(|d| d.to_biguint()) {
+            Some(d) => d,
+            None => continue,
+        };
+        let qinv = match (&q).mod_inverse(&p).and_then(|v| v.to_biguint()) {
+            Some(qinv) => qinv,
+            None => continue,
+        };
+        return Ok(KeyPair {
+            n: &p * &q,
+            dp: &d % (&p - &one),
+            dq: &d % (&q - &one),
+            d,
+            p,
+            q,
+            qinv,
+        });
+    }
+}
+
+fn i2osp(x: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = Zeroizing::new(x.to_bytes_be());
+    let mut out = vec![0_u8; len];
+    out[len - bytes.len()..].copy_from_slice(&bytes);
+    out
+}
+
+unsafe fn from_le(p: *const u8, len: usize) -> BigUint {
+    BigUint::from_bytes_le(as_slice(p, len))
+}
+
+unsafe fn write_le(x: &BigUint, p: *mut u8, len: usize) -> SgxResult {
+    let bytes = Zeroizing::new(x.to_bytes_le());
+    ensure!(bytes.len() <= len, SgxStatus::Unexpected);
+    let out = as_slice_mut(p, len);
+    out.fill(0);
+    out[..bytes.len()].copy_from_slice(&bytes);
+    Ok(())
+}
+
+fn hash_size(hash: RsaHash) -> usize {
+    hash.hash_size()
+}
+
+fn digest(hash: RsaHash, parts: &[&[u8]]) -> Vec<u8> {
+    fn run<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+        let mut hasher = D::new();
+        parts.iter().for_each(|part| hasher.update(part));
+        hasher.finalize().to_vec()
+    }
+
+    match hash {
+        RsaHash::Sha256 => run::<Sha256>(parts),
+        RsaHash::Sha384 => run::<Sha384>(parts),
+        RsaHash::Sha512 => run::<Sha512>(parts),
+    }
+}
+
+fn digest_info(hash: RsaHash) -> &'static [u8] {
+    match hash {
+        RsaHash::Sha256 => &SHA256_DIGEST_INFO,
+        RsaHash::Sha384 => &SHA384_DIGEST_INFO,
+        RsaHash::Sha512 => &SHA512_DIGEST_INFO,
+    }
+}
+
+// MGF1 (RFC 8017, B.2.1), XORed into `out`.
+fn mgf1_xor(hash: RsaHash, seed: &[u8], out: &mut [u8]) {
+    for (counter, chunk) in out.chunks_mut(hash_size(hash)).enumerate() {
+        let mask = digest(hash, &[seed, &(counter as u32).to_be_bytes()]);
+        chunk.iter_mut().zip(mask).for_each(|(o, m)| *o ^= m);
+    }
+}
+
+fn pkcs1_v15_encode(hash: RsaHash, data: &[u8], em_len: usize) -> SgxResult<Vec<u8>> {
+    let info = digest_info(hash);
+    let t_len = info.len() + hash_size(hash);
+    ensure!(em_len >= t_len + 11, SgxStatus::InvalidParameter);
+
+    let mut em = vec![0xff_u8; em_len];
+    em[0] = 0x00;
+    em[1] = 0x01;
+    em[em_len - t_len - 1] = 0x00;
+    em[em_len - t_len..em_len - hash_size(hash)].copy_from_slice(info);
+    em[em_len - hash_size(hash)..].copy_from_slice(&digest(hash, &[data]));
+    Ok(em)
+}
+
+fn pkcs1_v15_verify(
+    public: &RsaPublic,
+    hash: RsaHash,
+    data: &[u8],
+    signature: &[u8],
+) -> SgxResult<bool> {
+    let s = BigUint::from_bytes_be(signature);
+    if s >= public.n {
+        return Ok(false);
+    }
+    let em = i2osp(&public.encrypt(&s)?, public.size());
+    let expected = match pkcs1_v15_encode(hash, data, public.size()) {
+        Ok(expected) => expected,
+        Err(_) => return Ok(false),
+    };
+    Ok(bool::from(em.ct_eq(&expected)))
+}
+
+// EMSA-PSS (RFC 8017, 9.1) with emBits = modBits - 1.
+fn pss_encode(hash: RsaHash, data: &[u8], salt: &[u8], mod_bits: usize) -> SgxResult<Vec<u8>> {
+    let h_len = hash_size(hash);
+    let em_bits = mod_bits - 1;
+    let em_len = (em_bits + 7) / 8;
+    ensure!(
+        em_len >= h_len + salt.len() + 2,
+        SgxStatus::InvalidParameter
+    );
+
+    let m_hash = digest(hash, &[data]);
+    let h = digest(hash, &[&[0_u8; 8], &m_hash, salt]);
+
+    let db_len = em_len - h_len - 1;
+    let mut em = vec![0_u8; em_len];
+    em[db_len - salt.len() - 1] = 0x01;
+    em[db_len - salt.len()..db_len].copy_from_slice(salt);
+    mgf1_xor(hash, &h, &mut em[..db_len]);
+    em[0] &= 0xff >> (8 * em_len - em_bits);
+    em[db_len..em_len - 1].copy_from_slice(&h);
+    em[em_len - 1] = 0xbc;
+    Ok(em)
+}
+
+// The salt length is recovered from the encoding rather than fixed.
+fn pss_verify(public: &RsaPublic, hash: RsaHash, data: &[u8], signature: &[u8]) -> SgxResult<bool> {
+    let s = BigUint::from_bytes_be(signature);
+    if s >= public.n {
+        return Ok(false);
+    }
+
+    let h_len = hash_size(hash);
+    let em_bits = public.n.bits() - 1;
+    let em_len = (em_bits + 7) / 8;
+    let m = public.encrypt(&s)?;
+    if m.bits() > em_bits || em_len < h_len + 2 {
+        return Ok(false);
+    }
+
+    let mut em = i2osp(&m, em_len);
+    if em[em_len - 1] != 0xbc {
+        return Ok(false);
+    }
+
+    let db_len = em_len - h_len - 1;
+    let h = em[db_len..em_len - 1].to_vec();
+    mgf1_xor(hash, &h, &mut em[..db_len]);
+    em[0] &= 0xff >> (8 * em_len - em_bits);
+
+    let salt_start = match em[..db_len].iter().position(|b| *b != 0) {
+        Some(i) if em[i] == 0x01 => i + 1,
+        _ => return Ok(false),
+    };
+    let expected = digest(
+        hash,
+        &[&[0_u8; 8], &digest(hash, &[data]), &em[salt_start..db_len]],
+    );
+    Ok(bool::from(h.ct_eq(&expected)))
+}
+
+// RSAES-OAEP (RFC 8017, 7.1).
+fn oaep_encrypt(public: &RsaPublic, hash: RsaHash, label: &[u8], msg: &[u8]) -> SgxResult<Vec<u8>> {
+    let h_len = hash_size(hash);
+    let k = public.size();
+    ensure!(
+        k >= 2 * h_len + 2 && msg.len() <= k - 2 * h_len - 2,
+        SgxStatus::InvalidParameter
+    );
+
+    let mut em = Zeroizing::new(vec![0_u8; k]);
+    let (seed, db) = em[1..].split_at_mut(h_len);
+    read_rand(seed)?;
+    db[..h_len].copy_from_slice(&digest(hash, &[label]));
+    let ps_end = db.len() - msg.len() - 1;
+    db[ps_end] = 0x01;
+    db[ps_end + 1..].copy_from_slice(msg);
+    mgf1_xor(hash, seed, db);
+    mgf1_xor(hash, db, seed);
+
+    let c = public.encrypt(&BigUint::from_bytes_be(&em))?;
+    Ok(i2osp(&c, k))
+}
+
+fn oaep_decrypt(
+    private: &RsaPrivate,
+    hash: RsaHash,
+    label: &[u8],
+    cipher: &[u8],
+) -> SgxResult<Zeroizing<Vec<u8>>> {
+    let h_len = hash_size(hash);
+    let k = private.block_size();
+    ensure!(
+        k >= 2 * h_len + 2 && cipher.len() >= k,
+        SgxStatus::InvalidParameter
+    );
+
+    let m = Zeroizing::new(private.decrypt(&BigUint::from_bytes_be(&cipher[..k]))?);
+    let mut em = Zeroizing::new(i2osp(&m, k));
+    let (y, rest) = em.split_at_mut(1);
+    let (seed, db) = rest.split_at_mut(h_len);
+    mgf1_xor(hash, db, seed);
+    mgf1_xor(hash, seed, db);
+
+    let l_hash = digest(hash, &[label]);
+    let mut valid = y[0].ct_eq(&0) & db[..h_len].ct_eq(&l_hash);
+    let start = db[h_len..]
+        .iter()
+        .position(|b| *b != 0)
+        .map(|i| h_len + i)
+        .unwrap_or(db.len());
+    valid &= Choice::from(u8::from(start < db.len() && db[start] == 0x01));
+    ensure!(bool::from(valid), SgxStatus::Unexpected);
+
+    Ok(Zeroizing::new(db[start + 1..].to_vec()))
+}
+
+unsafe fn private_key<'a>(rsa_key: *const c_void) -> &'a RsaPrivate {
+    &*rsa_key.cast::<RsaPrivate>()
+}
+
+unsafe fn public_key<'a>(rsa_key: *const c_void) -> &'a RsaPublic {
+    &*rsa_key.cast::<RsaPublic>()
+}
+
+unsafe fn optional_public_key<'a>(rsa_key: *const c_void) -> Option<&'a RsaPublic> {
+    if rsa_key.is_null() {
+        None
+    } else {
+        Some(public_key(rsa_key))
+    }
+}
+
+macro_rules! impl_rsa_fixed {
+    ($key:ty, $pub_key:ty, $signature:ty, $sign:ident, $sign_ex:ident, $verify:ident) => {
+        pub unsafe fn $sign(
+            p_data: *const u8,
+            data_size: u32,
+            p_key: *const $key,
+            p_signature: *mut $signature,
+        ) -> SgxStatus {
+            $sign_ex(p_data, data_size, p_key, ptr::null(), p_signature)
+        }
+
+        pub unsafe fn $sign_ex(
+            p_data: *const u8,
+            data_size: u32,
+            p_key: *const $key,
+            p_public: *const $pub_key,
+            p_signature: *mut $signature,
+        ) -> SgxStatus {
+            if p_data.is_null() || data_size < 1 || p_key.is_null() || p_signature.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let key = &*p_key;
+            let private = RsaPrivate::Type1 {
+                n: BigUint::from_bytes_le(&key.modulus),
+                d: BigUint::from_bytes_le(&key.d),
+                e: Some(BigUint::from_bytes_le(&key.e)),
+            };
+            let public = p_public.as_ref().map(|public| RsaPublic {
+                n: BigUint::from_bytes_le(&public.modulus),
+                e: BigUint::from_bytes_le(&public.exponent),
+            });
+
+            let signature = &mut (*p_signature).signature;
+            let em = try_status!(pkcs1_v15_encode(
+                RsaHash::Sha256,
+                as_slice(p_data, data_size as usize),
+                private.block_size(),
+            ));
+            match private.sign(&em, public.as_ref()) {
+                Ok(s) => {
+                    signature[..s.len()].copy_from_slice(&s);
+                    SgxStatus::Success
+                }
+                Err(e) => e,
+            }
+        }
+
+        pub unsafe fn $verify(
+            p_data: *const u8,
+            data_size: u32,
+            p_public: *const $pub_key,
+            p_signature: *const $signature,
+            p_result: *mut RsaResult,
+        ) -> SgxStatus {
+            if p_data.is_null()
+                || data_size < 1
+                || p_public.is_null()
+                || p_signature.is_null()
+                || p_result.is_null()
+            {
+                return SgxStatus::InvalidParameter;
+            }
+
+            *p_result = RsaResult::InvalidSignature;
+            let public = RsaPublic {
+                n: BigUint::from_bytes_le(&(*p_public).modulus),
+                e: BigUint::from_bytes_le(&(*p_public).exponent),
+            };
+            let valid = try_status!(pkcs1_v15_verify(
+                &public,
+                RsaHash::Sha256,
+                as_slice(p_data, data_size as usize),
+                &(*p_signature).signature,
+            ));
+            if valid {
+                *p_result = RsaResult::Valid;
+            }
+            SgxStatus::Success
+        }
+    };
+}
+
+impl_rsa_fixed!(
+    Rsa2048Key,
+    Rsa2048PubKey,
+    Rsa2048Signature,
+    sgx_rsa2048_sign,
+    sgx_rsa2048_sign_ex,
+    sgx_rsa2048_verify
+);
+impl_rsa_fixed!(
+    Rsa3072Key,
+    Rsa3072PubKey,
+    Rsa3072Signature,
+    sgx_rsa3072_sign,
+    sgx_rsa3072_sign_ex,
+    sgx_rsa3072_verify
+);
+
+pub unsafe fn sgx_create_rsa_key_pair(
+    n_byte_size: i32,
+    e_byte_size: i32,
+    p_n: *mut u8,
+    p_d: *mut u8,
+    p_e: *mut u8,
+    p_p: *mut u8,
+    p_q: *mut u8,
+    p_dmp1: *mut u8,
+    p_dmq1: *mut u8,
+    p_iqmp: *mut u8,
+) -> SgxStatus {
+    if n_byte_size <= 0
+        || e_byte_size <= 0
+        || p_n.is_null()
+        || p_d.is_null()
+        || p_e.is_null()
+        || p_p.is_null()
+        || p_q.is_null()
+        || p_dmp1.is_null()
+        || p_dmq1.is_null()
+        || p_iqmp.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let n_size = n_byte_size as usize;
+    let e_size = e_byte_size as usize;
+    let e = from_le(p_e, e_size);
+    let key = try_status!(generate_key(n_size / 2 * 8, &e));
+
+    let result = write_le(&key.n, p_n, n_size)
+        .and_then(|_| write_le(&e, p_e, e_size))
+        .and_then(|_| write_le(&key.d, p_d, n_size))
+        .and_then(|_| write_le(&key.p, p_p, n_size / 2))
+        .and_then(|_| write_le(&key.q, p_q, n_size / 2))
+        .and_then(|_| write_le(&key.dp, p_dmp1, n_size / 2))
+        .and_then(|_| write_le(&key.dq, p_dmq1, n_size / 2))
+        .and_then(|_| write_le(&key.qinv, p_iqmp, n_size / 2));
+    if let Err(e) = result {
+        zero(p_d, n_size);
+        zero(p_p, n_size / 2);
+        zero(p_q, n_size / 2);
+        zero(p_dmp1, n_size / 2);
+        zero(p_dmq1, n_size / 2);
+        zero(p_iqmp, n_size / 2);
+        return e;
+    }
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_create_rsa_priv2_key(
+    mod_size: i32,
+    exp_size: i32,
+    p_rsa_key_e: *const u8,
+    p_rsa_key_p: *const u8,
+    p_rsa_key_q: *const u8,
+    p_rsa_key_dmp1: *const u8,
+    p_rsa_key_dmq1: *const u8,
+    p_rsa_key_iqmp: *const u8,
+    new_pri_key2: *mut *mut c_void,
+) -> SgxStatus {
+    if mod_size <= 0
+        || p_rsa_key_p.is_null()
+        || p_rsa_key_q.is_null()
+        || p_rsa_key_dmp1.is_null()
+        || p_rsa_key_dmq1.is_null()
+        || p_rsa_key_iqmp.is_null()
+        || new_pri_key2.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let half = mod_size as usize / 2;
+    let p = from_le(p_rsa_key_p, half);
+    let q = from_le(p_rsa_key_q, half);
+    if p.bits() == 0 || q.bits() == 0 {
+        return SgxStatus::Unexpected;
+    }
+    let e = if p_rsa_key_e.is_null() || exp_size <= 0 {
+        None
+    } else {
+        Some(from_le(p_rsa_key_e, exp_size as usize))
+    };
+
+    *new_pri_key2 = into_handle(RsaPrivate::Type2 {
+        n: &p * &q,
+        dp: from_le(p_rsa_key_dmp1, half),
+        dq: from_le(p_rsa_key_dmq1, half),
+        qinv: from_le(p_rsa_key_iqmp, half),
+        p,
+        q,
+        e,
+    });
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_create_rsa_priv1_key(
+    n_byte_size: i32,
+    e_byte_size: i32,
+    d_byte_size: i32,
+    le_n: *const u8,
+    le_e: *const u8,
+    le_d: *const u8,
+    new_pri_key1: *mut *mut c_void,
+) -> SgxStatus {
+    if n_byte_size <= 0
+        || d_byte_size <= 0
+        || new_pri_key1.is_null()
+        || le_n.is_null()
+        || le_d.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let n = from_le(le_n, n_byte_size as usize);
+    let d = from_le(le_d, d_byte_size as usize);
+    if n.bits() == 0 || d.bits() == 0 {
+        return SgxStatus::Unexpected;
+    }
+    let e = if le_e.is_null() || e_byte_size <= 0 {
+        None
+    } else {
+        Some(from_le(le_e, e_byte_size as usize))
+    };
+
+    *new_pri_key1 = into_handle(RsaPrivate::Type1 { n, d, e });
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_create_rsa_pub1_key(
+    mod_size: i32,
+    exp_size: i32,
+    le_n: *const u8,
+    le_e: *const u8,
+    new_pub_key1: *mut *mut c_void,
+) -> SgxStatus {
+    if new_pub_key1.is_null() || mod_size <= 0 || exp_size <= 0 || le_n.is_null() || le_e.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let n = from_le(le_n, mod_size as usize);
+    let e = from_le(le_e, exp_size as usize);
+    if n.bits() == 0 || e.bits() == 0 {
+        return SgxStatus::Unexpected;
+    }
+
+    *new_pub_key1 = into_handle(RsaPublic { n, e });
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_free_rsa_key(
+    p_rsa_key: *const c_void,
+    key_type: RsaKeyType,
+    _mod_size: i32,
+    _exp_size: i32,
+) -> SgxStatus {
+    if !p_rsa_key.is_null() {
+        match key_type {
+            RsaKeyType::PrivateKey => free_handle::<RsaPrivate>(p_rsa_key as *mut c_void),
+            RsaKeyType::PublicKey => free_handle::<RsaPublic>(p_rsa_key as *mut c_void),
+        }
+    }
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_rsa_pub_encrypt_sha256(
+    rsa_key: *const c_void,
+    pout_data: *mut u8,
+    pout_len: *mut usize,
+    pin_data: *const u8,
+    pin_len: usize,
+) -> SgxStatus {
+    if rsa_key.is_null()
+        || pout_len.is_null()
+        || pin_data.is_null()
+        || pin_len < 1
+        || pin_len >= i32::MAX as usize
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let public = public_key(rsa_key);
+    let mod_len = public.n.bits() / 8;
+    if pout_data.is_null() {
+        *pout_len = mod_len;
+        return SgxStatus::Success;
+    } else if *pout_len < mod_len {
+        return SgxStatus::InvalidParameter;
+    }
+
+    match oaep_encrypt(public, RsaHash::Sha256, &[], as_slice(pin_data, pin_len)) {
+        Ok(cipher) => {
+            copy_out(pout_data, &cipher);
+            *pout_len = mod_len;
+            SgxStatus::Success
+        }
+        Err(_) => SgxStatus::Unexpected,
+    }
+}
+
+pub unsafe fn sgx_rsa_priv_decrypt_sha256(
+    rsa_key: *const c_void,
+    pout_data: *mut u8,
+    pout_len: *mut usize,
+    pin_data: *const u8,
+    _pin_len: usize,
+) -> SgxStatus {
+    if rsa_key.is_null() || pout_len.is_null() || pin_data.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let private = private_key(rsa_key);
+    let data_len = match private {
+        RsaPrivate::Type1 { n, .. } => n.bits() / 8,
+        RsaPrivate::Type2 { p, .. } => p.bits() / 8 * 2,
+    };
+    if pout_data.is_null() {
+        *pout_len = data_len;
+        return SgxStatus::Success;
+    } else if *pout_len < data_len {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let cipher = as_slice(pin_data, private.block_size());
+    match oaep_decrypt(private, RsaHash::Sha256, &[], cipher) {
+        Ok(plain) => {
+            copy_out(pout_data, &plain);
+            *pout_len = plain.len();
+            SgxStatus::Success
+        }
+        Err(_) => SgxStatus::Unexpected,
+    }
+}
+
+pub unsafe fn sgx_rsa_sign_pkcs1_v15(
+    rsa_key: *const c_void,
+    rsa_pub_key: *const c_void,
+    hash: RsaHash,
+    p_data: *const u8,
+    data_size: usize,
+    p_signature: *mut u8,
+    signature_size: usize,
+) -> SgxStatus {
+    if rsa_key.is_null()
+        || p_data.is_null()
+        || data_size < 1
+        || data_size >= i32::MAX as usize
+        || p_signature.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let private = private_key(rsa_key);
+    if signature_size < private.size() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let em = try_status!(pkcs1_v15_encode(
+        hash,
+        as_slice(p_data, data_size),
+        private.block_size()
+    ));
+    match private.sign(&em, optional_public_key(rsa_pub_key)) {
+        Ok(signature) => {
+            copy_out(p_signature, &signature);
+            SgxStatus::Success
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn sgx_rsa_verify_pkcs1_v15(
+    rsa_pub_key: *const c_void,
+    hash: RsaHash,
+    p_data: *const u8,
+    data_size: usize,
+    p_signature: *const u8,
+    signature_size: usize,
+    p_result: *mut RsaResult,
+) -> SgxStatus {
+    if rsa_pub_key.is_null()
+        || p_data.is_null()
+        || data_size < 1
+        || data_size >= i32::MAX as usize
+        || p_signature.is_null()
+        || p_result.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    *p_result = RsaResult::InvalidSignature;
+    let public = public_key(rsa_pub_key);
+    if signature_size != public.size() {
+        return SgxStatus::Success;
+    }
+
+    let valid = try_status!(pkcs1_v15_verify(
+        public,
+        hash,
+        as_slice(p_data, data_size),
+        as_slice(p_signature, signature_size),
+    ));
+    if valid {
+        *p_result = RsaResult::Valid;
+    }
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_rsa_sign_pss(
+    rsa_key: *const c_void,
+    rsa_pub_key: *const c_void,
+    hash: RsaHash,
+    salt_size: usize,
+    p_data: *const u8,
+    data_size: usize,
+    p_signature: *mut u8,
+    signature_size: usize,
+) -> SgxStatus {
+    if rsa_key.is_null()
+        || p_data.is_null()
+        || data_size < 1
+        || data_size >= i32::MAX as usize
+        || p_signature.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let private = private_key(rsa_key);
+    let mod_size = private.size();
+    let h_len = hash_size(hash);
+    if signature_size < mod_size || mod_size < h_len + 2 || salt_size > mod_size - h_len - 2 {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let mut salt = Zeroizing::new(vec![0_u8; salt_size]);
+    try_status!(read_rand(&mut salt));
+    let em = try_status!(pss_encode(
+        hash,
+        as_slice(p_data, data_size),
+        &salt,
+        private.modulus().bits()
+    ));
+    match private.sign(&em, optional_public_key(rsa_pub_key)) {
+        Ok(signature) => {
+            copy_out(p_signature, &signature);
+            SgxStatus::Success
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn sgx_rsa_verify_pss(
+    rsa_pub_key: *const c_void,
+    hash: RsaHash,
+    p_data: *const u8,
+    data_size: usize,
+    p_signature: *const u8,
+    signature_size: usize,
+    p_result: *mut RsaResult,
+) -> SgxStatus {
+    if rsa_pub_key.is_null()
+        || p_data.is_null()
+        || data_size < 1
+        || data_size >= i32::MAX as usize
+        || p_signature.is_null()
+        || p_result.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    *p_result = RsaResult::InvalidSignature;
+    let public = public_key(rsa_pub_key);
+    if signature_size != public.size() {
+        return SgxStatus::Success;
+    }
+
+    let valid = try_status!(pss_verify(
+        public,
+        hash,
+        as_slice(p_data, data_size),
+        as_slice(p_signature, signature_size),
+    ));
+    if valid {
+        *p_result = RsaResult::Valid;
+    }
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_rsa_pub_encrypt_oaep(
+    rsa_key: *const c_void,
+    hash: RsaHash,
+    p_label: *const u8,
+    label_len: usize,
+    pout_data: *mut u8,
+    pout_len: *mut usize,
+    pin_data: *const u8,
+    pin_len: usize,
+) -> SgxStatus {
+    if rsa_key.is_null()
+        || pout_len.is_null()
+        || pin_data.is_null()
+        || pin_len < 1
+        || pin_len >= i32::MAX as usize
+        || label_len >= i32::MAX as usize
+        || (p_label.is_null() && label_len != 0)
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let public = public_key(rsa_key);
+    let mod_size = public.size();
+    if pout_data.is_null() {
+        *pout_len = mod_size;
+        return SgxStatus::Success;
+    }
+
+    let h_len = hash_size(hash);
+    if *pout_len < mod_size || mod_size < 2 * h_len + 2 || pin_len > mod_size - 2 * h_len - 2 {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let cipher = try_status!(oaep_encrypt(
+        public,
+        hash,
+        as_slice(p_label, label_len),
+        as_slice(pin_data, pin_len)
+    ));
+    copy_out(pout_data, &cipher);
+    *pout_len = mod_size;
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_rsa_priv_decrypt_oaep(
+    rsa_key: *const c_void,
+    hash: RsaHash,
+    p_label: *const u8,
+    label_len: usize,
+    pout_data: *mut u8,
+    pout_len: *mut usize,
+    pin_data: *const u8,
+    pin_len: usize,
+) -> SgxStatus {
+    if rsa_key.is_null()
+        || pout_len.is_null()
+        || pin_data.is_null()
+        || label_len >= i32::MAX as usize
+        || (p_label.is_null() && label_len != 0)
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let private = private_key(rsa_key);
+    let mod_size = private.size();
+    if pout_data.is_null() {
+        *pout_len = mod_size;
+        return SgxStatus::Success;
+    }
+
+    let h_len = hash_size(hash);
+    if pin_len != mod_size || mod_size < 2 * h_len + 2 || *pout_len < mod_size - 2 * h_len - 2 {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let plain = try_status!(oaep_decrypt(
+        private,
+        hash,
+        as_slice(p_label, label_len),
+        as_slice(pin_data, pin_len)
+    ));
+    if plain.len() > *pout_len {
+        return SgxStatus::InvalidParameter;
+    }
+    copy_out(pout_data, &plain);
+    *pout_len = plain.len();
+    SgxStatus::Success
+}
@@ -0,0 +1,137 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::sm3::Sm3;
+use super::{as_slice, free_handle, handle_mut, into_handle};
+use sgx_types::error::SgxStatus;
+use sgx_types::types::*;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512, Sha512_256};
+
+macro_rules! impl_hash {
+    ($hasher:ty, $hash:ty, $handle:ty,
+     $msg:ident, $init:ident, $update:ident, $get_hash:ident, $close:ident) => {
+        pub unsafe fn $msg(p_src: *const u8, src_len: u32, p_hash: *mut $hash) -> SgxStatus {
+            if p_src.is_null() || p_hash.is_null() || src_len > i32::MAX as u32 {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let digest = <$hasher>::digest(as_slice(p_src, src_len as usize));
+            (*p_hash).hash.copy_from_slice(&digest);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $init(p_handle: *mut $handle) -> SgxStatus {
+            if p_handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            *p_handle = into_handle(<$hasher>::new());
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $update(p_src: *const u8, src_len: u32, handle: $handle) -> SgxStatus {
+            if p_src.is_null() || handle.is_null() || src_len > i32::MAX as u32 {
+                return SgxStatus::InvalidParameter;
+            }
+
+            handle_mut::<$hasher>(handle).update(as_slice(p_src, src_len as usize));
+            SgxStatus::Success
+        }
+
+        // The running state is left intact, like IPP's GetTag.
+        pub unsafe fn $get_hash(handle: $handle, p_hash: *mut $hash) -> SgxStatus {
+            if handle.is_null() || p_hash.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            let digest = handle_mut::<$hasher>(handle).clone().finalize();
+            (*p_hash).hash.copy_from_slice(&digest);
+            SgxStatus::Success
+        }
+
+        pub unsafe fn $close(handle: $handle) -> SgxStatus {
+            if handle.is_null() {
+                return SgxStatus::InvalidParameter;
+            }
+
+            free_handle::<$hasher>(handle);
+            SgxStatus::Success
+        }
+    };
+}
+
+impl_hash!(
+    Sha1,
+    Sha1Hash,
+    ShaHandle,
+    sgx_sha1_msg,
+    sgx_sha1_init,
+    sgx_sha1_update,
+    sgx_sha1_get_hash,
+    sgx_sha1_close
+);
+impl_hash!(
+    Sha256,
+    Sha256Hash,
+    ShaHandle,
+    sgx_sha256_msg,
+    sgx_sha256_init,
+    sgx_sha256_update,
+    sgx_sha256_get_hash,
+    sgx_sha256_close
+);
+impl_hash!(
+    Sha384,
+    Sha384Hash,
+    ShaHandle,
+    sgx_sha384_msg,
+    sgx_sha384_init,
+    sgx_sha384_update,
+    sgx_sha384_get_hash,
+    sgx_sha384_close
+);
+impl_hash!(
+    Sha512,
+    Sha512Hash,
+    ShaHandle,
+    sgx_sha512_msg,
+    sgx_sha512_init,
+    sgx_sha512_update,
+    sgx_sha512_get_hash,
+    sgx_sha512_close
+);
+impl_hash!(
+    Sha512_256,
+    Sha512_256Hash,
+    ShaHandle,
+    sgx_sha512_256_msg,
+    sgx_sha512_256_init,
+    sgx_sha512_256_update,
+    sgx_sha512_256_get_hash,
+    sgx_sha512_256_close
+);
+impl_hash!(
+    Sm3,
+    Sm3Hash,
+    Sm3Handle,
+    sgx_sm3_msg,
+    sgx_sm3_init,
+    sgx_sm3_update,
+    sgx_sm3_get_hash,
+    sgx_sm3_close
+);
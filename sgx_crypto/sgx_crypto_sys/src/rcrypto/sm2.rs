@@ -0,0 +1,579 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// SM2 over the curve of GB/T 32918.5-2017, with a = p - 3. Coordinates and
+// scalars cross the interface little-endian, hashes big-endian.
+
+use super::ecc::calculate_priv_key;
+use super::sm3::Sm3;
+use super::{as_slice, free_handle, into_handle, into_status, read_rand, zero};
+use crypto_bigint::modular::constant_mod::Residue;
+use crypto_bigint::subtle::{Choice, ConditionallySelectable};
+use crypto_bigint::{impl_modulus, Encoding, U256};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::*;
+use sha2::Digest;
+
+impl_modulus!(
+    FieldModulus,
+    U256,
+    "FFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF00000000FFFFFFFFFFFFFFFF"
+);
+impl_modulus!(
+    OrderModulus,
+    U256,
+    "FFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFF7203DF6B21C6052B53BBF40939D54123"
+);
+
+type Fe = Residue<FieldModulus, { U256::LIMBS }>;
+type Sc = Residue<OrderModulus, { U256::LIMBS }>;
+
+const P: U256 =
+    U256::from_be_hex("FFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF00000000FFFFFFFFFFFFFFFF");
+const N: U256 =
+    U256::from_be_hex("FFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFF7203DF6B21C6052B53BBF40939D54123");
+const A: U256 =
+    U256::from_be_hex("FFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF00000000FFFFFFFFFFFFFFFC");
+const B: U256 =
+    U256::from_be_hex("28E9FA9E9D9F5E344D5A9E4BCF6509A7F39789F515AB8F92DDBCBD414D940E93");
+const GX: U256 =
+    U256::from_be_hex("32C4AE2C1F1981195F9904466A39C9948FE30BBFF2660BE1715A4589334C74C7");
+const GY: U256 =
+    U256::from_be_hex("BC3736A2F4F6779C59BDCEE36B692153D0A9877CC62A474002DF32E52139F0A0");
+
+const USER_ID: [u8; 16] = [
+    0x00, 0x70, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+];
+
+struct EccContext;
+
+#[inline]
+fn fe_eq(a: &Fe, b: &Fe) -> bool {
+    a.as_montgomery() == b.as_montgomery()
+}
+
+#[inline]
+fn fe_is_zero(a: &Fe) -> bool {
+    fe_eq(a, &Fe::ZERO)
+}
+
+fn fe_select(a: &Fe, b: &Fe, choice: Choice) -> Fe {
+    Fe::from_montgomery(U256::conditional_select(
+        a.as_montgomery(),
+        b.as_montgomery(),
+        choice,
+    ))
+}
+
+// Jacobian coordinates; z == 0 is the point at infinity.
+#[derive(Clone, Copy)]
+struct Point {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+impl Point {
+    const INFINITY: Point = Point {
+        x: Fe::ONE,
+        y: Fe::ONE,
+        z: Fe::ZERO,
+    };
+
+    fn generator() -> Point {
+        Point::from_affine(&Fe::new(&GX), &Fe::new(&GY))
+    }
+
+    fn from_affine(x: &Fe, y: &Fe) -> Point {
+        Point {
+            x: *x,
+            y: *y,
+            z: Fe::ONE,
+        }
+    }
+
+    // Coordinates are taken as they are stored in an Ec256PublicKey.
+    fn decode(public: &Ec256PublicKey) -> Option<Point> {
+        let x = U256::from_le_slice(&public.gx);
+        let y = U256::from_le_slice(&public.gy);
+        if x >= P || y >= P {
+            return None;
+        }
+
+        let (x, y) = (Fe::new(&x), Fe::new(&y));
+        let rhs = x.square() * x + Fe::new(&A) * x + Fe::new(&B);
+        fe_eq(&y.square(), &rhs).then(|| Point::from_affine(&x, &y))
+    }
+
+    fn to_affine(self) -> Option<(U256, U256)> {
+        let (z_inv, is_some) = self.z.invert();
+        if !bool::from(is_some) {
+            return None;
+        }
+        let z_inv2 = z_inv.square();
+        let x = self.x * z_inv2;
+        let y = self.y * z_inv2 * z_inv;
+        Some((x.retrieve(), y.retrieve()))
+    }
+
+    fn encode(&self, public: &mut Ec256PublicKey) -> SgxResult {
+        let (x, y) = self.to_affine().ok_or(SgxStatus::InvalidParameter)?;
+        public.gx.copy_from_slice(&x.to_le_bytes());
+        public.gy.copy_from_slice(&y.to_le_bytes());
+        Ok(())
+    }
+
+    fn is_infinity(&self) -> bool {
+        fe_is_zero(&self.z)
+    }
+
+    fn double(&self) -> Point {
+        if self.is_infinity() || fe_is_zero(&self.y) {
+            return Point::INFINITY;
+        }
+
+        let delta = self.z.square();
+        let gamma = self.y.square();
+        let beta = self.x * gamma;
+        let three = Fe::new(&U256::from_u8(3));
+        let alpha = three * (self.x - delta) * (self.x + delta);
+        let beta4 = beta + beta + beta + beta;
+        let x3 = alpha.square() - beta4 - beta4;
+        let z3 = (self.y + self.z).square() - gamma - delta;
+        let gamma2 = gamma.square();
+        let gamma8 = gamma2 + gamma2 + gamma2 + gamma2 + gamma2 + gamma2 + gamma2 + gamma2;
+        let y3 = alpha * (beta4 - x3) - gamma8;
+        Point {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    fn add(&self, other: &Point) -> Point {
+        if self.is_infinity() {
+            return *other;
+        }
+        if other.is_infinity() {
+            return *self;
+        }
+
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+        let h = u2 - u1;
+        let r = s2 - s1;
+        if fe_is_zero(&h) {
+            return if fe_is_zero(&r) {
+                self.double()
+            } else {
+                Point::INFINITY
+            };
+        }
+
+        let hh = h.square();
+        let hhh = h * hh;
+        let v = u1 * hh;
+        let x3 = r.square() - hhh - v - v;
+        let y3 = r * (v - x3) - s1 * hhh;
+        let z3 = self.z * other.z * h;
+        Point {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    fn select(a: &Point, b: &Point, choice: Choice) -> Point {
+        Point {
+            x: fe_select(&a.x, &b.x, choice),
+            y: fe_select(&a.y, &b.y, choice),
+            z: fe_select(&a.z, &b.z, choice),
+        }
+    }
+
+    // Double-and-always-add over all 256 bits of k.
+    fn mul(&self, k: &U256) -> Point {
+        let mut acc = Point::INFINITY;
+        for i in (0..U256::BITS).rev() {
+            acc = acc.double();
+            let sum = acc.add(self);
+            acc = Point::select(&acc, &sum, Choice::from(k.bit_vartime(i) as u8));
+        }
+        acc
+    }
+}
+
+fn private_key(r: &[u8]) -> SgxResult<U256> {
+    let d = U256::from_le_slice(r);
+    ensure!(d != U256::ZERO && d < N, SgxStatus::Unexpected);
+    Ok(d)
+}
+
+fn random_scalar() -> SgxResult<U256> {
+    let mut le = [0_u8; ECP256_KEY_SIZE];
+    loop {
+        read_rand(&mut le)?;
+        let d = U256::from_le_slice(&le);
+        // d = n - 1 has no inverse of 1 + d, so it is never used as a key.
+        if d != U256::ZERO && d < N.wrapping_sub(&U256::ONE) {
+            return Ok(d);
+        }
+    }
+}
+
+fn public_key(d: &U256) -> SgxResult<Ec256PublicKey> {
+    let mut public = Ec256PublicKey::default();
+    Point::generator().mul(d).encode(&mut public)?;
+    Ok(public)
+}
+
+fn digest(data: &[u8], public: &Ec256PublicKey) -> [u8; SM3_HASH_SIZE] {
+    let mut be_x = public.gx;
+    let mut be_y = public.gy;
+    be_x.reverse();
+    be_y.reverse();
+
+    let z = Sm3::new()
+        .chain_update(USER_ID)
+        .chain_update(A.to_be_bytes())
+        .chain_update(B.to_be_bytes())
+        .chain_update(GX.to_be_bytes())
+        .chain_update(GY.to_be_bytes())
+        .chain_update(be_x)
+        .chain_update(be_y)
+        .finalize();
+    Sm3::new()
+        .chain_update(z)
+        .chain_update(data)
+        .finalize()
+        .into()
+}
+
+fn sign_digest(hash: &[u8], d: &U256, k: &U256, signature: &mut Ec256Signature) -> SgxResult {
+    ensure!(*k != U256::ZERO && *k < N, SgxStatus::Unexpected);
+
+    let e = Sc::new(&U256::from_be_slice(hash));
+    let (x1, _) = Point::generator()
+        .mul(k)
+        .to_affine()
+        .ok_or(SgxStatus::Unexpected)?;
+    let r = e + Sc::new(&x1);
+    let k = Sc::new(k);
+    let d = Sc::new(d);
+    ensure!(
+        r.retrieve() != U256::ZERO && (r + k).retrieve() != U256::ZERO,
+        SgxStatus::Unexpected
+    );
+
+    let (inv, is_some) = (d + Sc::ONE).invert();
+    ensure!(bool::from(is_some), SgxStatus::Unexpected);
+    let s = inv * (k - r * d);
+    ensure!(s.retrieve() != U256::ZERO, SgxStatus::Unexpected);
+
+    to_words(&r.retrieve(), &mut signature.x);
+    to_words(&s.retrieve(), &mut signature.y);
+    Ok(())
+}
+
+fn verify_digest(hash: &[u8], public: &Ec256PublicKey, signature: &Ec256Signature) -> bool {
+    let q = match Point::decode(public) {
+        Some(q) => q,
+        None => return false,
+    };
+    let r = from_words(&signature.x);
+    let s = from_words(&signature.y);
+    if r == U256::ZERO || r >= N || s == U256::ZERO || s >= N {
+        return false;
+    }
+
+    let t = Sc::new(&r) + Sc::new(&s);
+    if t.retrieve() == U256::ZERO {
+        return false;
+    }
+    let point = Point::generator().mul(&s).add(&q.mul(&t.retrieve()));
+    match point.to_affine() {
+        Some((x1, _)) => {
+            let e = Sc::new(&U256::from_be_slice(hash));
+            (e + Sc::new(&x1)).retrieve() == r
+        }
+        None => false,
+    }
+}
+
+fn to_words(value: &U256, words: &mut [u32]) {
+    words
+        .iter_mut()
+        .zip(value.to_le_bytes().chunks_exact(4))
+        .for_each(|(w, l)| *w = u32::from_le_bytes([l[0], l[1], l[2], l[3]]));
+}
+
+fn from_words(words: &[u32]) -> U256 {
+    let mut le = [0_u8; ECP256_KEY_SIZE];
+    le.chunks_exact_mut(4)
+        .zip(words)
+        .for_each(|(l, w)| l.copy_from_slice(&w.to_le_bytes()));
+    U256::from_le_slice(&le)
+}
+
+pub unsafe fn sgx_sm2_open_context(p_ecc_handle: *mut EccHandle) -> SgxStatus {
+    if p_ecc_handle.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    *p_ecc_handle = into_handle(EccContext);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_sm2_close_context(ecc_handle: EccHandle) -> SgxStatus {
+    if ecc_handle.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    free_handle::<EccContext>(ecc_handle);
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_sm2_create_key_pair(
+    p_private: *mut Ec256PrivateKey,
+    p_public: *mut Ec256PublicKey,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null() || p_private.is_null() || p_public.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let d = try_status!(random_scalar());
+    *p_public = try_status!(public_key(&d));
+    (*p_private).r.copy_from_slice(&d.to_le_bytes());
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_sm2_check_point(
+    p_point: *const Ec256PublicKey,
+    ecc_handle: EccHandle,
+    p_valid: *mut i32,
+) -> SgxStatus {
+    if ecc_handle.is_null() || p_point.is_null() || p_valid.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    *p_valid = Point::decode(&*p_point).is_some() as i32;
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_sm2_compute_shared_dhkey(
+    p_private_b: *const Ec256PrivateKey,
+    p_public_ga: *const Ec256PublicKey,
+    p_shared_key: *mut Ec256SharedKey,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null()
+        || p_private_b.is_null()
+        || p_public_ga.is_null()
+        || p_shared_key.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let point = match Point::decode(&*p_public_ga) {
+        Some(point) => point,
+        None => return SgxStatus::InvalidParameter,
+    };
+    let d = try_status!(private_key(&(*p_private_b).r));
+    let (x, _) = try_status!(point.mul(&d).to_affine().ok_or(SgxStatus::Unexpected));
+    (*p_shared_key).s.copy_from_slice(&x.to_le_bytes());
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_sm2_point_mul(
+    p_scalar: *const Ec256PrivateKey,
+    p_point: *const Ec256PublicKey,
+    p_result: *mut Ec256PublicKey,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null() || p_scalar.is_null() || p_point.is_null() || p_result.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let result = Point::decode(&*p_point)
+        .ok_or(SgxStatus::InvalidParameter)
+        .and_then(|point| {
+            let k = U256::from_le_slice(&(*p_scalar).r);
+            point.mul(&k).encode(&mut *p_result)
+        });
+    if result.is_err() {
+        *p_result = Ec256PublicKey::default();
+    }
+    into_status(result)
+}
+
+pub unsafe fn sgx_sm2_point_add(
+    p_point_p: *const Ec256PublicKey,
+    p_point_q: *const Ec256PublicKey,
+    p_result: *mut Ec256PublicKey,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null() || p_point_p.is_null() || p_point_q.is_null() || p_result.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let result = match (Point::decode(&*p_point_p), Point::decode(&*p_point_q)) {
+        (Some(p), Some(q)) => p.add(&q).encode(&mut *p_result),
+        _ => Err(SgxStatus::InvalidParameter),
+    };
+    if result.is_err() {
+        *p_result = Ec256PublicKey::default();
+    }
+    into_status(result)
+}
+
+pub unsafe fn sgx_sm2_sign(
+    p_data: *const u8,
+    data_size: u32,
+    p_private: *const Ec256PrivateKey,
+    p_signature: *mut Ec256Signature,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null()
+        || p_private.is_null()
+        || p_signature.is_null()
+        || p_data.is_null()
+        || data_size < 1
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let d = try_status!(private_key(&(*p_private).r));
+    let public = try_status!(public_key(&d));
+    let hash = digest(as_slice(p_data, data_size as usize), &public);
+    let k = try_status!(random_scalar());
+    into_status(sign_digest(&hash, &d, &k, &mut *p_signature))
+}
+
+pub unsafe fn sgx_sm2_sign_with_nonce(
+    p_hash: *const u8,
+    p_private: *const Ec256PrivateKey,
+    p_nonce: *const Ec256PrivateKey,
+    p_signature: *mut Ec256Signature,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null()
+        || p_private.is_null()
+        || p_nonce.is_null()
+        || p_signature.is_null()
+        || p_hash.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let d = try_status!(private_key(&(*p_private).r));
+    let k = U256::from_le_slice(&(*p_nonce).r);
+    into_status(sign_digest(
+        as_slice(p_hash, SM3_HASH_SIZE),
+        &d,
+        &k,
+        &mut *p_signature,
+    ))
+}
+
+pub unsafe fn sgx_sm2_verify(
+    p_data: *const u8,
+    data_size: u32,
+    p_public: *const Ec256PublicKey,
+    p_signature: *const Ec256Signature,
+    p_result: *mut u8,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null()
+        || p_public.is_null()
+        || p_signature.is_null()
+        || p_data.is_null()
+        || data_size < 1
+        || p_result.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    let hash = digest(as_slice(p_data, data_size as usize), &*p_public);
+    sgx_sm2_verify_hash(hash.as_ptr(), p_public, p_signature, p_result, ecc_handle)
+}
+
+pub unsafe fn sgx_sm2_verify_hash(
+    hash: *const u8,
+    p_public: *const Ec256PublicKey,
+    p_signature: *const Ec256Signature,
+    p_result: *mut u8,
+    ecc_handle: EccHandle,
+) -> SgxStatus {
+    if ecc_handle.is_null()
+        || p_public.is_null()
+        || p_signature.is_null()
+        || hash.is_null()
+        || p_result.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    *p_result = if verify_digest(as_slice(hash, SM3_HASH_SIZE), &*p_public, &*p_signature) {
+        EcResult::Valid as u8
+    } else {
+        EcResult::InvalidSignature as u8
+    };
+    SgxStatus::Success
+}
+
+pub unsafe fn sgx_calculate_sm2_priv_key(
+    hash_drg: *const u8,
+    hash_drg_len: i32,
+    sgx_sm2_order: *const u8,
+    sgx_sm2_order_len: i32,
+    out_key: *mut u8,
+    out_key_len: i32,
+) -> SgxStatus {
+    calculate_priv_key(
+        hash_drg,
+        hash_drg_len,
+        sgx_sm2_order,
+        sgx_sm2_order_len,
+        out_key,
+        out_key_len,
+    )
+}
+
+pub unsafe fn sgx_sm2_calculate_pub_from_priv(
+    p_att_priv_key: *const Ec256PrivateKey,
+    p_att_pub_key: *mut Ec256PublicKey,
+) -> SgxStatus {
+    if p_att_priv_key.is_null() || p_att_pub_key.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    match private_key(&(*p_att_priv_key).r).and_then(|d| public_key(&d)) {
+        Ok(public) => {
+            *p_att_pub_key = public;
+            SgxStatus::Success
+        }
+        Err(_) => {
+            zero(p_att_pub_key.cast(), core::mem::size_of::<Ec256PublicKey>());
+            SgxStatus::Unexpected
+        }
+    }
+}
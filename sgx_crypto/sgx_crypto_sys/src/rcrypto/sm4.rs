@@ -0,0 +1,351 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::modes::{self, Block, BlockCipher128, CCM_IV_SIZE};
+use sgx_types::error::SgxStatus;
+use sgx_types::types::*;
+use zeroize::Zeroize;
+
+const CBC_IV_SIZE: u32 = 16;
+
+// GB/T 32907-2016.
+const SBOX: [u8; 256] = [
+    0xd6, 0x90, 0xe9, 0xfe, 0xcc, 0xe1, 0x3d, 0xb7, 0x16, 0xb6, 0x14, 0xc2, 0x28, 0xfb, 0x2c, 0x05,
+    0x2b, 0x67, 0x9a, 0x76, 0x2a, 0xbe, 0x04, 0xc3, 0xaa, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
+    0x9c, 0x42, 0x50, 0xf4, 0x91, 0xef, 0x98, 0x7a, 0x33, 0x54, 0x0b, 0x43, 0xed, 0xcf, 0xac, 0x62,
+    0xe4, 0xb3, 0x1c, 0xa9, 0xc9, 0x08, 0xe8, 0x95, 0x80, 0xdf, 0x94, 0xfa, 0x75, 0x8f, 0x3f, 0xa6,
+    0x47, 0x07, 0xa7, 0xfc, 0xf3, 0x73, 0x17, 0xba, 0x83, 0x59, 0x3c, 0x19, 0xe6, 0x85, 0x4f, 0xa8,
+    0x68, 0x6b, 0x81, 0xb2, 0x71, 0x64, 0xda, 0x8b, 0xf8, 0xeb, 0x0f, 0x4b, 0x70, 0x56, 0x9d, 0x35,
+    0x1e, 0x24, 0x0e, 0x5e, 0x63, 0x58, 0xd1, 0xa2, 0x25, 0x22, 0x7c, 0x3b, 0x01, 0x21, 0x78, 0x87,
+    0xd4, 0x00, 0x46, 0x57, 0x9f, 0xd3, 0x27, 0x52, 0x4c, 0x36, 0x02, 0xe7, 0xa0, 0xc4, 0xc8, 0x9e,
+    0xea, 0xbf, 0x8a, 0xd2, 0x40, 0xc7, 0x38, 0xb5, 0xa3, 0xf7, 0xf2, 0xce, 0xf9, 0x61, 0x15, 0xa1,
+    0xe0, 0xae, 0x5d, 0xa4, 0x9b, 0x34, 0x1a, 0x55, 0xad, 0x93, 0x32, 0x30, 0xf5, 0x8c, 0xb1, 0xe3,
+    0x1d, 0xf6, 0xe2, 0x2e, 0x82, 0x66, 0xca, 0x60, 0xc0, 0x29, 0x23, 0xab, 0x0d, 0x53, 0x4e, 0x6f,
+    0xd5, 0xdb, 0x37, 0x45, 0xde, 0xfd, 0x8e, 0x2f, 0x03, 0xff, 0x6a, 0x72, 0x6d, 0x6c, 0x5b, 0x51,
+    0x8d, 0x1b, 0xaf, 0x92, 0xbb, 0xdd, 0xbc, 0x7f, 0x11, 0xd9, 0x5c, 0x41, 0x1f, 0x10, 0x5a, 0xd8,
+    0x0a, 0xc1, 0x31, 0x88, 0xa5, 0xcd, 0x7b, 0xbd, 0x2d, 0x74, 0xd0, 0x12, 0xb8, 0xe5, 0xb4, 0xb0,
+    0x89, 0x69, 0x97, 0x4a, 0x0c, 0x96, 0x77, 0x7e, 0x65, 0xb9, 0xf1, 0x09, 0xc5, 0x6e, 0xc6, 0x84,
+    0x18, 0xf0, 0x7d, 0xec, 0x3a, 0xdc, 0x4d, 0x20, 0x79, 0xee, 0x5f, 0x3e, 0xd7, 0xcb, 0x39, 0x48,
+];
+
+const FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
+
+const CK: [u32; 32] = {
+    let mut ck = [0_u32; 32];
+    let mut i = 0;
+    while i < 32 {
+        let mut j = 0;
+        while j < 4 {
+            ck[i] = (ck[i] << 8) | (((4 * i + j) * 7) % 256) as u32;
+            j += 1;
+        }
+        i += 1;
+    }
+    ck
+};
+
+#[inline]
+fn tau(x: u32) -> u32 {
+    u32::from_be_bytes(x.to_be_bytes().map(|b| SBOX[b as usize]))
+}
+
+#[inline]
+fn round_t(x: u32) -> u32 {
+    let b = tau(x);
+    b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
+}
+
+#[inline]
+fn key_t(x: u32) -> u32 {
+    let b = tau(x);
+    b ^ b.rotate_left(13) ^ b.rotate_left(23)
+}
+
+pub struct Sm4 {
+    rk: [u32; 32],
+}
+
+impl Sm4 {
+    pub fn new(key: &[u8; 16]) -> Sm4 {
+        let mut k = [0_u32; 4];
+        for (i, k) in k.iter_mut().enumerate() {
+            *k = u32::from_be_bytes([key[4 * i], key[4 * i + 1], key[4 * i + 2], key[4 * i + 3]])
+                ^ FK[i];
+        }
+
+        let mut rk = [0_u32; 32];
+        for i in 0..32 {
+            let next = k[0] ^ key_t(k[1] ^ k[2] ^ k[3] ^ CK[i]);
+            rk[i] = next;
+            k = [k[1], k[2], k[3], next];
+        }
+        k.zeroize();
+        Sm4 { rk }
+    }
+
+    fn crypt(&self, block: &mut Block, decrypt: bool) {
+        let mut x = [0_u32; 4];
+        for (i, x) in x.iter_mut().enumerate() {
+            *x = u32::from_be_bytes([
+                block[4 * i],
+                block[4 * i + 1],
+                block[4 * i + 2],
+                block[4 * i + 3],
+            ]);
+        }
+
+        for i in 0..32 {
+            let rk = if decrypt { self.rk[31 - i] } else { self.rk[i] };
+            let next = x[0] ^ round_t(x[1] ^ x[2] ^ x[3] ^ rk);
+            x = [x[1], x[2], x[3], next];
+        }
+
+        for (i, x) in x.iter().rev().enumerate() {
+            block[4 * i..4 * i + 4].copy_from_slice(&x.to_be_bytes());
+        }
+    }
+}
+
+impl BlockCipher128 for Sm4 {
+    fn encrypt_block(&self, block: &mut Block) {
+        self.crypt(block, false);
+    }
+
+    fn decrypt_block(&self, block: &mut Block) {
+        self.crypt(block, true);
+    }
+}
+
+impl Drop for Sm4 {
+    fn drop(&mut self) {
+        self.rk.zeroize();
+    }
+}
+
+unsafe fn sm4_cipher(p_key: *const u8) -> Sm4 {
+    Sm4::new(&*p_key.cast::<[u8; 16]>())
+}
+
+pub unsafe fn sgx_sm4_ccm128_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_out_mac: *mut Mac128bit,
+) -> SgxStatus {
+    if p_key.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+    try_status!(modes::check_aead(
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        CCM_IV_SIZE,
+        p_aad,
+        aad_len,
+        p_out_mac.cast(),
+    ));
+
+    modes::ccm_encrypt(
+        sm4_cipher(p_key.cast()),
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        p_aad,
+        aad_len,
+        p_out_mac.cast(),
+    )
+}
+
+pub unsafe fn sgx_sm4_ccm128_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    p_in_mac: *const Mac128bit,
+) -> SgxStatus {
+    if p_key.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+    try_status!(modes::check_aead(
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        iv_len,
+        CCM_IV_SIZE,
+        p_aad,
+        aad_len,
+        p_in_mac.cast(),
+    ));
+
+    modes::ccm_decrypt(
+        sm4_cipher(p_key.cast()),
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        p_aad,
+        aad_len,
+        p_in_mac.cast(),
+    )
+}
+
+pub unsafe fn sgx_sm4_ccm128_init(
+    p_key: *const u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    p_aad: *const u8,
+    aad_len: u32,
+    sm4_ccm_state: *mut Sm4Handle,
+) -> SgxStatus {
+    if aad_len >= i32::MAX as u32
+        || p_key.is_null()
+        || iv_len as usize != CCM_IV_SIZE
+        || (aad_len > 0 && p_aad.is_null())
+        || p_iv.is_null()
+        || sm4_ccm_state.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    modes::ccm_init(sm4_cipher(p_key), p_iv, p_aad, aad_len, sm4_ccm_state)
+}
+
+pub unsafe fn sgx_sm4_ccm128_enc_update(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    sm4_ccm_state: Sm4Handle,
+) -> SgxStatus {
+    modes::ccm_update::<Sm4>(p_src, src_len, p_dst, sm4_ccm_state, true)
+}
+
+pub unsafe fn sgx_sm4_ccm128_dec_update(
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    sm4_ccm_state: Sm4Handle,
+) -> SgxStatus {
+    modes::ccm_update::<Sm4>(p_src, src_len, p_dst, sm4_ccm_state, false)
+}
+
+pub unsafe fn sgx_sm4_ccm128_enc_get_mac(mac: *mut u8, sm4_ccm_state: Sm4Handle) -> SgxStatus {
+    modes::ccm_get_mac::<Sm4>(mac, sm4_ccm_state)
+}
+
+pub unsafe fn sgx_sm4_ccm128_dec_verify_mac(mac: *const u8, sm4_ccm_state: Sm4Handle) -> SgxStatus {
+    modes::ccm_verify_mac::<Sm4>(mac, sm4_ccm_state)
+}
+
+pub unsafe fn sgx_sm4_ccm_close(sm4_ccm_state: Sm4Handle) -> SgxStatus {
+    modes::ccm_close::<Sm4>(sm4_ccm_state)
+}
+
+pub unsafe fn sgx_sm4_cbc_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+) -> SgxStatus {
+    sm4_cbc(p_key, p_src, src_len, p_dst, p_iv, iv_len, true)
+}
+
+pub unsafe fn sgx_sm4_cbc_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+) -> SgxStatus {
+    sm4_cbc(p_key, p_src, src_len, p_dst, p_iv, iv_len, false)
+}
+
+unsafe fn sm4_cbc(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_dst: *mut u8,
+    p_iv: *const u8,
+    iv_len: u32,
+    encrypt: bool,
+) -> SgxStatus {
+    if p_key.is_null()
+        || p_src.is_null()
+        || p_iv.is_null()
+        || iv_len != CBC_IV_SIZE
+        || p_dst.is_null()
+    {
+        return SgxStatus::InvalidParameter;
+    }
+
+    modes::cbc_crypt(
+        &sm4_cipher(p_key.cast()),
+        p_src,
+        src_len,
+        p_dst,
+        p_iv,
+        encrypt,
+    )
+}
+
+pub unsafe fn sgx_sm4_ctr_encrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    if p_key.is_null() || p_src.is_null() || p_ctr.is_null() || p_dst.is_null() {
+        return SgxStatus::InvalidParameter;
+    }
+
+    modes::ctr_crypt(
+        &sm4_cipher(p_key.cast()),
+        p_src,
+        src_len,
+        p_ctr,
+        ctr_inc_bits,
+        p_dst,
+    )
+}
+
+pub unsafe fn sgx_sm4_ctr_decrypt(
+    p_key: *const Key128bit,
+    p_src: *const u8,
+    src_len: u32,
+    p_ctr: *mut u8,
+    ctr_inc_bits: u32,
+    p_dst: *mut u8,
+) -> SgxStatus {
+    sgx_sm4_ctr_encrypt(p_key, p_src, src_len, p_ctr, ctr_inc_bits, p_dst)
+}
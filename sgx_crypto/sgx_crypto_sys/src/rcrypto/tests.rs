@@ -0,0 +1,703 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! Cross-checks the pure-Rust backend against the IPP-backed library.
+//!
+//! Deterministic operations must agree byte for byte; randomized ones
+//! (key generation, PSS, OAEP) are produced by one backend and consumed by
+//! the other.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ptr;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::*;
+
+// Evaluates `$body` once against each backend, bound to `$b`, and asserts
+// that both produce the same value.
+macro_rules! cross {
+    (|$b:ident| $body:expr) => {{
+        let ipp = {
+            use crate::bindings as $b;
+            $body
+        };
+        let rs = {
+            use crate::rcrypto as $b;
+            $body
+        };
+        assert_eq!(ipp, rs);
+        rs
+    }};
+}
+
+fn bytes(len: usize, seed: u32) -> Vec<u8> {
+    let mut x = seed.wrapping_mul(0x9e37_79b9) | 1;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as u8
+        })
+        .collect()
+}
+
+const LENS: [usize; 7] = [0, 1, 15, 16, 17, 64, 1000];
+
+#[test]
+fn hash() {
+    for (i, len) in LENS.into_iter().enumerate() {
+        let src = bytes(len, i as u32);
+        unsafe {
+            cross!(|b| {
+                let mut h = Sha1Hash::default();
+                (b::sgx_sha1_msg(src.as_ptr(), len as u32, &mut h), h)
+            });
+            cross!(|b| {
+                let mut h = Sha256Hash::default();
+                (b::sgx_sha256_msg(src.as_ptr(), len as u32, &mut h), h)
+            });
+            cross!(|b| {
+                let mut h = Sha384Hash::default();
+                (b::sgx_sha384_msg(src.as_ptr(), len as u32, &mut h), h)
+            });
+            cross!(|b| {
+                let mut h = Sha512Hash::default();
+                (b::sgx_sha512_msg(src.as_ptr(), len as u32, &mut h), h)
+            });
+            cross!(|b| {
+                let mut h = Sha512_256Hash::default();
+                (b::sgx_sha512_256_msg(src.as_ptr(), len as u32, &mut h), h)
+            });
+            cross!(|b| {
+                let mut h = Sm3Hash::default();
+                (b::sgx_sm3_msg(src.as_ptr(), len as u32, &mut h), h)
+            });
+        }
+    }
+}
+
+#[test]
+fn hmac() {
+    for (i, key_len) in [1_usize, 32, 64, 65, 200].into_iter().enumerate() {
+        let key = bytes(key_len, i as u32);
+        let src = bytes(100, 7);
+        unsafe {
+            cross!(|b| {
+                let mut mac = [0_u8; 32];
+                let status = b::sgx_hmac_sha256_msg(
+                    src.as_ptr(),
+                    100,
+                    key.as_ptr(),
+                    key_len as i32,
+                    mac.as_mut_ptr(),
+                    32,
+                );
+                (status, mac)
+            });
+            cross!(|b| {
+                let mut mac = [0_u8; 48];
+                let status = b::sgx_hmac_sha384_msg(
+                    src.as_ptr(),
+                    100,
+                    key.as_ptr(),
+                    key_len as i32,
+                    mac.as_mut_ptr(),
+                    48,
+                );
+                (status, mac)
+            });
+            cross!(|b| {
+                let mut mac = [0_u8; 32];
+                let status = b::sgx_hmac_sm3_msg(
+                    src.as_ptr(),
+                    100,
+                    key.as_ptr(),
+                    key_len as i32,
+                    mac.as_mut_ptr(),
+                    32,
+                );
+                (status, mac)
+            });
+        }
+    }
+}
+
+#[test]
+fn aes() {
+    for key_len in [16_usize, 24, 32] {
+        let key = bytes(key_len, key_len as u32);
+        let iv = bytes(16, 1);
+        let aad = bytes(20, 2);
+        for (i, len) in LENS.into_iter().enumerate() {
+            let src = bytes(len, i as u32);
+            unsafe {
+                let (_, dst, mac) = cross!(|b| {
+                    let mut dst = vec![0_u8; len];
+                    let mut mac = Mac128bit::default();
+                    let status = b::sgx_aes_gcm_encrypt_ex(
+                        key.as_ptr(),
+                        key_len as u32,
+                        src.as_ptr(),
+                        len as u32,
+                        dst.as_mut_ptr(),
+                        iv.as_ptr(),
+                        12,
+                        aad.as_ptr(),
+                        20,
+                        &mut mac,
+                    );
+                    (status, dst, mac)
+                });
+                cross!(|b| {
+                    let mut out = vec![0_u8; len];
+                    let status = b::sgx_aes_gcm_decrypt_ex(
+                        key.as_ptr(),
+                        key_len as u32,
+                        dst.as_ptr(),
+                        len as u32,
+                        out.as_mut_ptr(),
+                        iv.as_ptr(),
+                        12,
+                        aad.as_ptr(),
+                        20,
+                        &mac,
+                    );
+                    (status, out)
+                });
+                cross!(|b| {
+                    let mut dst = vec![0_u8; len];
+                    let mut mac = Mac128bit::default();
+                    let status = b::sgx_aes_ccm_encrypt_ex(
+                        key.as_ptr(),
+                        key_len as u32,
+                        src.as_ptr(),
+                        len as u32,
+                        dst.as_mut_ptr(),
+                        iv.as_ptr(),
+                        12,
+                        aad.as_ptr(),
+                        20,
+                        &mut mac,
+                    );
+                    (status, dst, mac)
+                });
+                cross!(|b| {
+                    let mut ctr = iv.clone();
+                    let mut dst = vec![0_u8; len];
+                    let status = b::sgx_aes_ctr_encrypt_ex(
+                        key.as_ptr(),
+                        key_len as u32,
+                        src.as_ptr(),
+                        len as u32,
+                        ctr.as_mut_ptr(),
+                        32,
+                        dst.as_mut_ptr(),
+                    );
+                    (status, ctr, dst)
+                });
+                cross!(|b| {
+                    let mut dst = vec![0_u8; len];
+                    let status = b::sgx_aes_cbc_encrypt_ex(
+                        key.as_ptr(),
+                        key_len as u32,
+                        src.as_ptr(),
+                        len as u32,
+                        dst.as_mut_ptr(),
+                        iv.as_ptr(),
+                        16,
+                    );
+                    (status, dst)
+                });
+            }
+        }
+    }
+
+    let key: Key128bit = bytes(16, 3).try_into().unwrap();
+    for (i, len) in LENS.into_iter().enumerate() {
+        let src = bytes(len, i as u32);
+        unsafe {
+            cross!(|b| {
+                let mut mac = Mac128bit::default();
+                (
+                    b::sgx_rijndael128_cmac_msg(&key, src.as_ptr(), len as u32, &mut mac),
+                    mac,
+                )
+            });
+        }
+    }
+}
+
+#[test]
+fn sm4() {
+    let key: Key128bit = bytes(16, 4).try_into().unwrap();
+    let iv = bytes(16, 5);
+    let aad = bytes(20, 6);
+    for (i, len) in LENS.into_iter().enumerate() {
+        let src = bytes(len, i as u32);
+        unsafe {
+            cross!(|b| {
+                let mut ctr = iv.clone();
+                let mut dst = vec![0_u8; len];
+                let status = b::sgx_sm4_ctr_encrypt(
+                    &key,
+                    src.as_ptr(),
+                    len as u32,
+                    ctr.as_mut_ptr(),
+                    128,
+                    dst.as_mut_ptr(),
+                );
+                (status, ctr, dst)
+            });
+            cross!(|b| {
+                let mut dst = vec![0_u8; len];
+                let status = b::sgx_sm4_cbc_encrypt(
+                    &key,
+                    src.as_ptr(),
+                    len as u32,
+                    dst.as_mut_ptr(),
+                    iv.as_ptr(),
+                    16,
+                );
+                (status, dst)
+            });
+            cross!(|b| {
+                let mut dst = vec![0_u8; len];
+                let mut mac = Mac128bit::default();
+                let status = b::sgx_sm4_ccm128_encrypt(
+                    &key,
+                    src.as_ptr(),
+                    len as u32,
+                    dst.as_mut_ptr(),
+                    iv.as_ptr(),
+                    12,
+                    aad.as_ptr(),
+                    20,
+                    &mut mac,
+                );
+                (status, dst, mac)
+            });
+        }
+    }
+}
+
+// Scalars below the order of every curve used here.
+fn scalar(seed: u32) -> Ec256PrivateKey {
+    let mut r: [u8; 32] = bytes(32, seed).try_into().unwrap();
+    r[31] &= 0x7f;
+    Ec256PrivateKey { r }
+}
+
+fn scalar384(seed: u32) -> Ec384PrivateKey {
+    let mut r: [u8; 48] = bytes(48, seed).try_into().unwrap();
+    r[47] &= 0x7f;
+    Ec384PrivateKey { r }
+}
+
+#[test]
+fn ecc256() {
+    let priv_a = scalar(1);
+    let priv_b = scalar(2);
+    let nonce = scalar(3);
+    let hash = bytes(32, 4);
+    unsafe {
+        let pub_b = cross!(|b| {
+            let mut public = Ec256PublicKey::default();
+            (
+                b::sgx_ecc256_calculate_pub_from_priv(&priv_b, &mut public),
+                public,
+            )
+        })
+        .1;
+        cross!(|b| {
+            let mut handle: EccHandle = ptr::null_mut();
+            b::sgx_ecc256_open_context(&mut handle);
+            let mut shared = Ec256SharedKey::default();
+            let status = b::sgx_ecc256_compute_shared_dhkey(&priv_a, &pub_b, &mut shared, handle);
+            b::sgx_ecc256_close_context(handle);
+            (status, shared)
+        });
+        cross!(|b| {
+            let mut handle: EccHandle = ptr::null_mut();
+            b::sgx_ecc256_open_context(&mut handle);
+            let mut signature = Ec256Signature::default();
+            let status = b::sgx_ecdsa_sign_with_nonce(
+                hash.as_ptr(),
+                &priv_b,
+                &nonce,
+                &mut signature,
+                handle,
+            );
+            b::sgx_ecc256_close_context(handle);
+            (status, signature)
+        });
+    }
+}
+
+#[test]
+fn ecc384() {
+    let priv_a = scalar384(1);
+    let priv_b = scalar384(2);
+    let nonce = scalar384(3);
+    let hash = bytes(48, 4);
+    unsafe {
+        let pub_b = cross!(|b| {
+            let mut public = Ec384PublicKey::default();
+            (
+                b::sgx_ecc384_calculate_pub_from_priv(&priv_b, &mut public),
+                public,
+            )
+        })
+        .1;
+        cross!(|b| {
+            let mut handle: EccHandle = ptr::null_mut();
+            b::sgx_ecc384_open_context(&mut handle);
+            let mut shared = Ec384SharedKey::default();
+            let status = b::sgx_ecc384_compute_shared_dhkey(&priv_a, &pub_b, &mut shared, handle);
+            b::sgx_ecc384_close_context(handle);
+            (status, shared)
+        });
+        cross!(|b| {
+            let mut handle: EccHandle = ptr::null_mut();
+            b::sgx_ecc384_open_context(&mut handle);
+            let mut signature = Ec384Signature::default();
+            let status = b::sgx_ecdsa384_sign_with_nonce(
+                hash.as_ptr(),
+                &priv_b,
+                &nonce,
+                &mut signature,
+                handle,
+            );
+            b::sgx_ecc384_close_context(handle);
+            (status, signature)
+        });
+    }
+}
+
+#[test]
+fn sm2() {
+    let priv_a = scalar(5);
+    let priv_b = scalar(6);
+    let nonce = scalar(7);
+    let hash = bytes(32, 8);
+    unsafe {
+        let pub_b = cross!(|b| {
+            let mut public = Ec256PublicKey::default();
+            (
+                b::sgx_sm2_calculate_pub_from_priv(&priv_b, &mut public),
+                public,
+            )
+        })
+        .1;
+        cross!(|b| {
+            let mut handle: EccHandle = ptr::null_mut();
+            b::sgx_sm2_open_context(&mut handle);
+            let mut shared = Ec256SharedKey::default();
+            let status = b::sgx_sm2_compute_shared_dhkey(&priv_a, &pub_b, &mut shared, handle);
+            b::sgx_sm2_close_context(handle);
+            (status, shared)
+        });
+        cross!(|b| {
+            let mut handle: EccHandle = ptr::null_mut();
+            b::sgx_sm2_open_context(&mut handle);
+            let mut signature = Ec256Signature::default();
+            let status =
+                b::sgx_sm2_sign_with_nonce(hash.as_ptr(), &priv_b, &nonce, &mut signature, handle);
+            b::sgx_sm2_close_context(handle);
+            (status, signature)
+        });
+    }
+}
+
+const N_SIZE: usize = 256;
+const E_SIZE: usize = 4;
+
+struct RsaComponents {
+    n: Vec<u8>,
+    d: Vec<u8>,
+    e: Vec<u8>,
+    p: Vec<u8>,
+    q: Vec<u8>,
+    dmp1: Vec<u8>,
+    dmq1: Vec<u8>,
+    iqmp: Vec<u8>,
+}
+
+fn rsa_components() -> RsaComponents {
+    let half = N_SIZE / 2;
+    let mut c = RsaComponents {
+        n: vec![0; N_SIZE],
+        d: vec![0; N_SIZE],
+        e: vec![1, 0, 1, 0],
+        p: vec![0; half],
+        q: vec![0; half],
+        dmp1: vec![0; half],
+        dmq1: vec![0; half],
+        iqmp: vec![0; half],
+    };
+    let status = unsafe {
+        super::sgx_create_rsa_key_pair(
+            N_SIZE as i32,
+            E_SIZE as i32,
+            c.n.as_mut_ptr(),
+            c.d.as_mut_ptr(),
+            c.e.as_mut_ptr(),
+            c.p.as_mut_ptr(),
+            c.q.as_mut_ptr(),
+            c.dmp1.as_mut_ptr(),
+            c.dmq1.as_mut_ptr(),
+            c.iqmp.as_mut_ptr(),
+        )
+    };
+    assert_eq!(status, SgxStatus::Success);
+    c
+}
+
+// Builds the public and CRT private key handles of one backend.
+macro_rules! rsa_handles {
+    ($b:ident, $c:expr) => {{
+        let mut public: *mut c_void = ptr::null_mut();
+        let mut private: *mut c_void = ptr::null_mut();
+        assert_eq!(
+            $b::sgx_create_rsa_pub1_key(
+                N_SIZE as i32,
+                E_SIZE as i32,
+                $c.n.as_ptr(),
+                $c.e.as_ptr(),
+                &mut public,
+            ),
+            SgxStatus::Success
+        );
+        assert_eq!(
+            $b::sgx_create_rsa_priv2_key(
+                N_SIZE as i32,
+                E_SIZE as i32,
+                $c.e.as_ptr(),
+                $c.p.as_ptr(),
+                $c.q.as_ptr(),
+                $c.dmp1.as_ptr(),
+                $c.dmq1.as_ptr(),
+                $c.iqmp.as_ptr(),
+                &mut private,
+            ),
+            SgxStatus::Success
+        );
+        (public, private)
+    }};
+}
+
+macro_rules! rsa_free {
+    ($b:ident, $keys:expr) => {{
+        $b::sgx_free_rsa_key($keys.0, RsaKeyType::PublicKey, N_SIZE as i32, E_SIZE as i32);
+        $b::sgx_free_rsa_key(
+            $keys.1,
+            RsaKeyType::PrivateKey,
+            N_SIZE as i32,
+            E_SIZE as i32,
+        );
+    }};
+}
+
+#[test]
+fn rsa_pkcs1_v15() {
+    let c = rsa_components();
+    let data = bytes(100, 9);
+    for hash in [RsaHash::Sha256, RsaHash::Sha384, RsaHash::Sha512] {
+        unsafe {
+            cross!(|b| {
+                let keys = rsa_handles!(b, c);
+                let mut signature = vec![0_u8; N_SIZE];
+                let status = b::sgx_rsa_sign_pkcs1_v15(
+                    keys.1,
+                    keys.0,
+                    hash,
+                    data.as_ptr(),
+                    data.len(),
+                    signature.as_mut_ptr(),
+                    N_SIZE,
+                );
+                rsa_free!(b, keys);
+                (status, signature)
+            });
+        }
+    }
+
+    let mut key = Rsa2048Key::default();
+    key.modulus.copy_from_slice(&c.n);
+    key.d.copy_from_slice(&c.d);
+    key.e.copy_from_slice(&c.e);
+    let mut public = Rsa2048PubKey::default();
+    public.modulus.copy_from_slice(&c.n);
+    public.exponent.copy_from_slice(&c.e);
+    unsafe {
+        cross!(|b| {
+            let mut signature = Rsa2048Signature::default();
+            let status = b::sgx_rsa2048_sign_ex(
+                data.as_ptr(),
+                data.len() as u32,
+                &key,
+                &public,
+                &mut signature,
+            );
+            (status, signature)
+        });
+    }
+}
+
+#[test]
+fn rsa_pss() {
+    let c = rsa_components();
+    let data = bytes(100, 10);
+    unsafe {
+        let ipp = {
+            use crate::bindings as b;
+            rsa_handles!(b, c)
+        };
+        let rs = rsa_handles!(super, c);
+        for hash in [RsaHash::Sha256, RsaHash::Sha384, RsaHash::Sha512] {
+            // IPP signs, Rust verifies.
+            let mut signature = vec![0_u8; N_SIZE];
+            let status = crate::bindings::sgx_rsa_sign_pss(
+                ipp.1,
+                ipp.0,
+                hash,
+                32,
+                data.as_ptr(),
+                data.len(),
+                signature.as_mut_ptr(),
+                N_SIZE,
+            );
+            assert_eq!(status, SgxStatus::Success);
+            let mut result = RsaResult::InvalidSignature;
+            let status = super::sgx_rsa_verify_pss(
+                rs.0,
+                hash,
+                data.as_ptr(),
+                data.len(),
+                signature.as_ptr(),
+                N_SIZE,
+                &mut result,
+            );
+            assert_eq!((status, result), (SgxStatus::Success, RsaResult::Valid));
+
+            // Rust signs, IPP verifies.
+            let status = super::sgx_rsa_sign_pss(
+                rs.1,
+                rs.0,
+                hash,
+                32,
+                data.as_ptr(),
+                data.len(),
+                signature.as_mut_ptr(),
+                N_SIZE,
+            );
+            assert_eq!(status, SgxStatus::Success);
+            let mut result = RsaResult::InvalidSignature;
+            let status = crate::bindings::sgx_rsa_verify_pss(
+                ipp.0,
+                hash,
+                data.as_ptr(),
+                data.len(),
+                signature.as_ptr(),
+                N_SIZE,
+                &mut result,
+            );
+            assert_eq!((status, result), (SgxStatus::Success, RsaResult::Valid));
+        }
+        {
+            use crate::bindings as b;
+            rsa_free!(b, ipp);
+        }
+        rsa_free!(super, rs);
+    }
+}
+
+#[test]
+fn rsa_oaep() {
+    let c = rsa_components();
+    let label = bytes(10, 11);
+    let msg = bytes(60, 12);
+    unsafe {
+        let ipp = {
+            use crate::bindings as b;
+            rsa_handles!(b, c)
+        };
+        let rs = rsa_handles!(super, c);
+        for hash in [RsaHash::Sha256, RsaHash::Sha384, RsaHash::Sha512] {
+            // IPP encrypts, Rust decrypts.
+            let mut cipher = vec![0_u8; N_SIZE];
+            let mut cipher_len = N_SIZE;
+            let status = crate::bindings::sgx_rsa_pub_encrypt_oaep(
+                ipp.0,
+                hash,
+                label.as_ptr(),
+                label.len(),
+                cipher.as_mut_ptr(),
+                &mut cipher_len,
+                msg.as_ptr(),
+                msg.len(),
+            );
+            assert_eq!(status, SgxStatus::Success);
+            let mut plain = vec![0_u8; N_SIZE];
+            let mut plain_len = N_SIZE;
+            let status = super::sgx_rsa_priv_decrypt_oaep(
+                rs.1,
+                hash,
+                label.as_ptr(),
+                label.len(),
+                plain.as_mut_ptr(),
+                &mut plain_len,
+                cipher.as_ptr(),
+                cipher_len,
+            );
+            assert_eq!(status, SgxStatus::Success);
+            assert_eq!(&plain[..plain_len], &msg[..]);
+
+            // Rust encrypts, IPP decrypts.
+            let mut cipher_len = N_SIZE;
+            let status = super::sgx_rsa_pub_encrypt_oaep(
+                rs.0,
+                hash,
+                label.as_ptr(),
+                label.len(),
+                cipher.as_mut_ptr(),
+                &mut cipher_len,
+                msg.as_ptr(),
+                msg.len(),
+            );
+            assert_eq!(status, SgxStatus::Success);
+            let mut plain_len = N_SIZE;
+            let status = crate::bindings::sgx_rsa_priv_decrypt_oaep(
+                ipp.1,
+                hash,
+                label.as_ptr(),
+                label.len(),
+                plain.as_mut_ptr(),
+                &mut plain_len,
+                cipher.as_ptr(),
+                cipher_len,
+            );
+            assert_eq!(status, SgxStatus::Success);
+            assert_eq!(&plain[..plain_len], &msg[..]);
+        }
+        {
+            use crate::bindings as b;
+            rsa_free!(b, ipp);
+        }
+        rsa_free!(super, rs);
+    }
+}
@@ -79,6 +79,8 @@
 
 #define sgx_create_rsa_pub_key sgx_create_rsa_pub1_key
 
+#define SGX_AES_KEY_SIZE_VALID(len) ((len) == 16 || (len) == 24 || (len) == 32)
+
 IppStatus sgx_ipp_newBN(const Ipp32u *p_data, int size_in_bytes, IppsBigNumState **p_new_BN);
 void sgx_ipp_secure_free_BN(IppsBigNumState *pBN, int size_in_bytes);
 IppStatus IPP_STDCALL sgx_ipp_DRNGen(Ipp32u* pRandBNU, int nBits, void* pCtx);
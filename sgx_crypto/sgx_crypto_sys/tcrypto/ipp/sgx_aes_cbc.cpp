@@ -35,12 +35,13 @@
 #include "stdlib.h"
 #include "string.h"
 
-/* AES-CBC 128-bit
+/* AES-CBC with 128, 192 or 256-bit keys
  * Parameters:
  *   Return:
  *     sgx_status_t - SGX_SUCCESS or failure as defined in sgx_error.h
  *   Inputs:
- *     sgx_aes_128bit_key_t *p_key - Pointer to the key used in encryption/decryption operation
+ *     uint8_t *p_key - Pointer to the key used in encryption/decryption operation
+ *     uint32_t key_len - Length of the key, 16, 24 or 32 bytes
  *     uint8_t *p_src - Pointer to the input stream to be encrypted/decrypted
  *     uint32_t src_len - Length of the input stream to be encrypted/decrypted
  *     uint8_t *p_iv - Pointer to initialization vector to use
@@ -48,20 +49,20 @@
  *   Output:
  *     uint8_t *p_dst - Pointer to the cipher text. Size of buffer should be >= src_len.
  */
-sgx_status_t sgx_aes_cbc_encrypt(const sgx_aes_cbc_128bit_key_t *p_key, const uint8_t *p_src,
-                                const uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv,
-                                const uint32_t iv_len)
+sgx_status_t sgx_aes_cbc_encrypt_ex(const uint8_t *p_key, uint32_t key_len, const uint8_t *p_src,
+                                   const uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv,
+                                   const uint32_t iv_len)
 {
     IppStatus error_code = ippStsNoErr;
     IppsAESSpec* ptr_ctx = NULL;
     int ctx_size = 0;
 
-    if ((p_key == NULL) || (p_src == NULL) || (p_iv == NULL) || (iv_len != SGX_AESCBC_IV_SIZE) || (p_dst == NULL))
+    if ((p_key == NULL) || !SGX_AES_KEY_SIZE_VALID(key_len) || (p_src == NULL) || (p_iv == NULL) || (iv_len != SGX_AESCBC_IV_SIZE) || (p_dst == NULL))
     {
         return SGX_ERROR_INVALID_PARAMETER;
     }
 
-    // AES-CBC-128 encryption
+    // AES-CBC encryption
     error_code = ippsAESGetSize(&ctx_size);
     if (error_code != ippStsNoErr)
     {
@@ -74,7 +75,7 @@ sgx_status_t sgx_aes_cbc_encrypt(const sgx_aes_cbc_128bit_key_t *p_key, const ui
     }
 
     // Init
-    error_code = ippsAESInit((const Ipp8u*)p_key, SGX_AESCBC_KEY_SIZE, ptr_ctx, ctx_size);
+    error_code = ippsAESInit((const Ipp8u*)p_key, (int)key_len, ptr_ctx, ctx_size);
     if (error_code != ippStsNoErr)
     {
         // Clear temp State before free.
@@ -107,20 +108,20 @@ sgx_status_t sgx_aes_cbc_encrypt(const sgx_aes_cbc_128bit_key_t *p_key, const ui
     return SGX_SUCCESS;
 }
 
-sgx_status_t sgx_aes_cbc_decrypt(const sgx_aes_cbc_128bit_key_t *p_key, const uint8_t *p_src,
-                                const uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv,
-                                const uint32_t iv_len)
+sgx_status_t sgx_aes_cbc_decrypt_ex(const uint8_t *p_key, uint32_t key_len, const uint8_t *p_src,
+                                   const uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv,
+                                   const uint32_t iv_len)
 {
     IppStatus error_code = ippStsNoErr;
     IppsAESSpec* ptr_ctx = NULL;
     int ctx_size = 0;
 
-    if ((p_key == NULL) || (p_src == NULL) || (p_iv == NULL) || (iv_len != SGX_AESCBC_IV_SIZE) || (p_dst == NULL))
+    if ((p_key == NULL) || !SGX_AES_KEY_SIZE_VALID(key_len) || (p_src == NULL) || (p_iv == NULL) || (iv_len != SGX_AESCBC_IV_SIZE) || (p_dst == NULL))
     {
         return SGX_ERROR_INVALID_PARAMETER;
     }
 
-    // AES-CBC-128 decryption
+    // AES-CBC decryption
     error_code = ippsAESGetSize(&ctx_size);
     if (error_code != ippStsNoErr)
     {
@@ -133,7 +134,7 @@ sgx_status_t sgx_aes_cbc_decrypt(const sgx_aes_cbc_128bit_key_t *p_key, const ui
     }
 
     // Init
-    error_code = ippsAESInit((const Ipp8u*)p_key, SGX_AESCBC_KEY_SIZE, ptr_ctx, ctx_size);
+    error_code = ippsAESInit((const Ipp8u*)p_key, (int)key_len, ptr_ctx, ctx_size);
     if (error_code != ippStsNoErr)
     {
         // Clear temp State before free.
@@ -165,3 +166,17 @@ sgx_status_t sgx_aes_cbc_decrypt(const sgx_aes_cbc_128bit_key_t *p_key, const ui
     free(ptr_ctx);
     return SGX_SUCCESS;
 }
+
+sgx_status_t sgx_aes_cbc_encrypt(const sgx_aes_cbc_128bit_key_t *p_key, const uint8_t *p_src,
+                                const uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv,
+                                const uint32_t iv_len)
+{
+    return sgx_aes_cbc_encrypt_ex((const uint8_t*)p_key, SGX_AESCBC_KEY_SIZE, p_src, src_len, p_dst, p_iv, iv_len);
+}
+
+sgx_status_t sgx_aes_cbc_decrypt(const sgx_aes_cbc_128bit_key_t *p_key, const uint8_t *p_src,
+                                const uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv,
+                                const uint32_t iv_len)
+{
+    return sgx_aes_cbc_decrypt_ex((const uint8_t*)p_key, SGX_AESCBC_KEY_SIZE, p_src, src_len, p_dst, p_iv, iv_len);
+}
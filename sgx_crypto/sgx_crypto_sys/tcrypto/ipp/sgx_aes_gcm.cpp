@@ -36,10 +36,11 @@
 #include "string.h"
 #include <limits.h>
 
-/* Rijndael AES-GCM
+/* Rijndael AES-GCM with 128, 192 or 256-bit keys
 * Parameters:
 *   Return: sgx_status_t  - SGX_SUCCESS or failure as defined sgx_error.h
-*   Inputs: sgx_aes_gcm_128bit_key_t *p_key - Pointer to key used in encryption/decryption operation
+*   Inputs: uint8_t *p_key - Pointer to key used in encryption/decryption operation
+*           uint32_t key_len - Length of the key, 16, 24 or 32 bytes
 *           uint8_t *p_src - Pointer to input stream to be encrypted/decrypted
 *           uint32_t src_len - Length of input stream to be encrypted/decrypted
 *           uint8_t *p_iv - Pointer to initialization vector to use
@@ -50,15 +51,15 @@
 *   Output: uint8_t *p_dst - Pointer to cipher text. Size of buffer should be >= src_len.
 *           sgx_aes_gcm_128bit_tag_t *p_out_mac - Pointer to MAC generated from encryption process
 * NOTE: Wrapper is responsible for confirming decryption tag matches encryption tag */
-sgx_status_t sgx_rijndael128GCM_encrypt(const sgx_aes_gcm_128bit_key_t *p_key, const uint8_t *p_src, uint32_t src_len,
-                                        uint8_t *p_dst, const uint8_t *p_iv, uint32_t iv_len, const uint8_t *p_aad, uint32_t aad_len,
-                                        sgx_aes_gcm_128bit_tag_t *p_out_mac)
+sgx_status_t sgx_aes_gcm_encrypt_ex(const uint8_t *p_key, uint32_t key_len, const uint8_t *p_src, uint32_t src_len,
+                                    uint8_t *p_dst, const uint8_t *p_iv, uint32_t iv_len, const uint8_t *p_aad, uint32_t aad_len,
+                                    sgx_aes_gcm_128bit_tag_t *p_out_mac)
 {
     IppStatus error_code = ippStsNoErr;
     IppsAES_GCMState* pState = NULL;
     int ippStateSize = 0;
 
-    if ((p_key == NULL) || ((src_len > 0) && (p_dst == NULL)) || ((src_len > 0) && (p_src == NULL))
+    if ((p_key == NULL) || !SGX_AES_KEY_SIZE_VALID(key_len) || ((src_len > 0) && (p_dst == NULL)) || ((src_len > 0) && (p_src == NULL))
         || (p_out_mac == NULL) || (iv_len != SGX_AESGCM_IV_SIZE) || ((aad_len > 0) && (p_aad == NULL))
         || (p_iv == NULL) || ((p_src == NULL) && (p_aad == NULL)))
     {
@@ -74,7 +75,7 @@ sgx_status_t sgx_rijndael128GCM_encrypt(const sgx_aes_gcm_128bit_key_t *p_key, c
     {
         return SGX_ERROR_OUT_OF_MEMORY;
     }
-    error_code = ippsAES_GCMInit((const Ipp8u *)p_key, SGX_AESGCM_KEY_SIZE, pState, ippStateSize);
+    error_code = ippsAES_GCMInit((const Ipp8u *)p_key, (int)key_len, pState, ippStateSize);
     if (error_code != ippStsNoErr)
     {
         // Clear temp State before free.
@@ -135,16 +136,16 @@ sgx_status_t sgx_rijndael128GCM_encrypt(const sgx_aes_gcm_128bit_key_t *p_key, c
     return SGX_SUCCESS;
 }
 
-sgx_status_t sgx_rijndael128GCM_decrypt(const sgx_aes_gcm_128bit_key_t *p_key, const uint8_t *p_src,
-                                        uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv, uint32_t iv_len,
-                                        const uint8_t *p_aad, uint32_t aad_len, const sgx_aes_gcm_128bit_tag_t *p_in_mac)
+sgx_status_t sgx_aes_gcm_decrypt_ex(const uint8_t *p_key, uint32_t key_len, const uint8_t *p_src,
+                                    uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv, uint32_t iv_len,
+                                    const uint8_t *p_aad, uint32_t aad_len, const sgx_aes_gcm_128bit_tag_t *p_in_mac)
 {
     IppStatus error_code = ippStsNoErr;
     uint8_t l_tag[SGX_AESGCM_MAC_SIZE];
     IppsAES_GCMState* pState = NULL;
     int ippStateSize = 0;
 
-    if ((p_key == NULL) || ((src_len > 0) && (p_dst == NULL)) || ((src_len > 0) && (p_src == NULL))
+    if ((p_key == NULL) || !SGX_AES_KEY_SIZE_VALID(key_len) || ((src_len > 0) && (p_dst == NULL)) || ((src_len > 0) && (p_src == NULL))
         || (p_in_mac == NULL) || (iv_len != SGX_AESGCM_IV_SIZE) || ((aad_len > 0) && (p_aad == NULL))
         || (p_iv == NULL) || ((p_src == NULL) && (p_aad == NULL)))
     {
@@ -163,7 +164,7 @@ sgx_status_t sgx_rijndael128GCM_decrypt(const sgx_aes_gcm_128bit_key_t *p_key, c
     {
         return SGX_ERROR_OUT_OF_MEMORY;
     }
-    error_code = ippsAES_GCMInit((const Ipp8u *)p_key, SGX_AESGCM_KEY_SIZE, pState, ippStateSize);
+    error_code = ippsAES_GCMInit((const Ipp8u *)p_key, (int)key_len, pState, ippStateSize);
     if (error_code != ippStsNoErr)
     {
         // Clear temp State before free.
@@ -234,10 +235,10 @@ sgx_status_t sgx_rijndael128GCM_decrypt(const sgx_aes_gcm_128bit_key_t *p_key, c
     return SGX_SUCCESS;
 }
 
-sgx_status_t sgx_aes_gcm128_init(const uint8_t *key, const uint8_t *iv, uint32_t iv_len, const uint8_t *aad,
+sgx_status_t sgx_aes_gcm_init_ex(const uint8_t *key, uint32_t key_len, const uint8_t *iv, uint32_t iv_len, const uint8_t *aad,
     uint32_t aad_len, sgx_aes_state_handle_t* aes_gcm_state)
 {
-    if ((aad_len >= INT_MAX) || (key == NULL) || (iv_len != SGX_AESGCM_IV_SIZE) || ((aad_len > 0) && (aad == NULL))
+    if ((aad_len >= INT_MAX) || (key == NULL) || !SGX_AES_KEY_SIZE_VALID(key_len) || (iv_len != SGX_AESGCM_IV_SIZE) || ((aad_len > 0) && (aad == NULL))
         || (iv == NULL) || (aes_gcm_state == NULL))
     {
         return SGX_ERROR_INVALID_PARAMETER;
@@ -257,7 +258,7 @@ sgx_status_t sgx_aes_gcm128_init(const uint8_t *key, const uint8_t *iv, uint32_t
             break;
         }
 
-        status = ippsAES_GCMInit(key, 16, p_state, state_size);
+        status = ippsAES_GCMInit(key, (int)key_len, p_state, state_size);
         ERROR_BREAK(status);
 
         status = ippsAES_GCMStart(iv, iv_len, aad, aad_len, p_state);
@@ -275,6 +276,26 @@ sgx_status_t sgx_aes_gcm128_init(const uint8_t *key, const uint8_t *iv, uint32_t
     return ret;
 }
 
+sgx_status_t sgx_rijndael128GCM_encrypt(const sgx_aes_gcm_128bit_key_t *p_key, const uint8_t *p_src, uint32_t src_len,
+                                        uint8_t *p_dst, const uint8_t *p_iv, uint32_t iv_len, const uint8_t *p_aad, uint32_t aad_len,
+                                        sgx_aes_gcm_128bit_tag_t *p_out_mac)
+{
+    return sgx_aes_gcm_encrypt_ex((const uint8_t *)p_key, SGX_AESGCM_KEY_SIZE, p_src, src_len, p_dst, p_iv, iv_len, p_aad, aad_len, p_out_mac);
+}
+
+sgx_status_t sgx_rijndael128GCM_decrypt(const sgx_aes_gcm_128bit_key_t *p_key, const uint8_t *p_src,
+                                        uint32_t src_len, uint8_t *p_dst, const uint8_t *p_iv, uint32_t iv_len,
+                                        const uint8_t *p_aad, uint32_t aad_len, const sgx_aes_gcm_128bit_tag_t *p_in_mac)
+{
+    return sgx_aes_gcm_decrypt_ex((const uint8_t *)p_key, SGX_AESGCM_KEY_SIZE, p_src, src_len, p_dst, p_iv, iv_len, p_aad, aad_len, p_in_mac);
+}
+
+sgx_status_t sgx_aes_gcm128_init(const uint8_t *key, const uint8_t *iv, uint32_t iv_len, const uint8_t *aad,
+    uint32_t aad_len, sgx_aes_state_handle_t* aes_gcm_state)
+{
+    return sgx_aes_gcm_init_ex(key, SGX_AESGCM_KEY_SIZE, iv, iv_len, aad, aad_len, aes_gcm_state);
+}
+
 
 sgx_status_t sgx_aes_gcm128_enc_get_mac(uint8_t *mac, sgx_aes_state_handle_t aes_gcm_state)
 {
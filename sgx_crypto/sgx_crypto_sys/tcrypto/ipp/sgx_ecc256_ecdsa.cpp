@@ -171,6 +171,136 @@ sgx_status_t sgx_ecdsa_sign(const uint8_t *p_data,
     }
 }
 
+/* Computes signature for a hash with a caller-supplied per-signature nonce,
+* e.g. one derived as in RFC 6979. The nonce must be in [1, n-1] and must never
+* be reused with a different hash.
+* Parameters:
+*   Return: sgx_status_t - SGX_SUCCESS or failure as defined sgx_error.h
+*   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to ECC crypto system
+*           uint8_t *p_hash - Pointer to the SHA256 hash to be signed - BIG ENDIAN
+*           sgx_ec256_private_t *p_private - Pointer to the private key - LITTLE ENDIAN
+*           sgx_ec256_private_t *p_nonce - Pointer to the nonce - LITTLE ENDIAN
+*   Output: sgx_ec256_signature_t *p_signature - Pointer to the signature - LITTLE ENDIAN  */
+sgx_status_t sgx_ecdsa_sign_with_nonce(const uint8_t *p_hash,
+                                       const sgx_ec256_private_t *p_private,
+                                       const sgx_ec256_private_t *p_nonce,
+                                       sgx_ec256_signature_t *p_signature,
+                                       sgx_ecc_state_handle_t ecc_handle)
+{
+    if ((ecc_handle == NULL) || (p_private == NULL) || (p_nonce == NULL) || (p_signature == NULL) || (p_hash == NULL))
+    {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    IppStatus ipp_ret = ippStsNoErr;
+    IppsECCPState* p_ecc_state = (IppsECCPState*)ecc_handle;
+    IppsBigNumState* p_ecp_order = NULL;
+    IppsBigNumState* p_hash_bn = NULL;
+    IppsBigNumState* p_msg_bn = NULL;
+    IppsBigNumState* p_eph_priv_bn = NULL;
+    IppsECCPPointState* p_eph_pub = NULL;
+    IppsBigNumState* p_reg_priv_bn = NULL;
+    IppsBigNumState* p_signx_bn = NULL;
+    IppsBigNumState* p_signy_bn = NULL;
+    Ipp32u *p_sigx = NULL;
+    Ipp32u *p_sigy = NULL;
+    int ecp_size = 0;
+    const int order_size = sizeof(sgx_nistp256_r);
+
+    do
+    {
+        ipp_ret = sgx_ipp_newBN(sgx_nistp256_r, order_size, &p_ecp_order);
+        ERROR_BREAK(ipp_ret);
+
+        /* Byte swap in creation of Big Number from SHA256 hash output */
+        ipp_ret = sgx_ipp_newBN(NULL, SGX_SHA256_HASH_SIZE, &p_hash_bn);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsSetOctString_BN((Ipp8u*)p_hash, SGX_SHA256_HASH_SIZE, p_hash_bn);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = sgx_ipp_newBN(NULL, order_size, &p_msg_bn);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsMod_BN(p_hash_bn, p_ecp_order, p_msg_bn);
+        ERROR_BREAK(ipp_ret);
+
+        // Set the ephemeral key pair from the nonce.
+        ipp_ret = sgx_ipp_newBN((uint32_t *)p_nonce->r, sizeof(p_nonce->r),
+            &p_eph_priv_bn);
+        ERROR_BREAK(ipp_ret);
+        //init eccp point
+        ipp_ret = ippsECCPPointGetSize(256, &ecp_size);
+        ERROR_BREAK(ipp_ret);
+        p_eph_pub = (IppsECCPPointState*)(malloc(ecp_size));
+        if (!p_eph_pub)
+        {
+            ipp_ret = ippStsNoMemErr;
+            break;
+        }
+        ipp_ret = ippsECCPPointInit(256, p_eph_pub);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPPublicKey(p_eph_priv_bn, p_eph_pub, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPSetKeyPair(p_eph_priv_bn, p_eph_pub, ippFalse, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+
+        // Set the regular private key.
+        ipp_ret = sgx_ipp_newBN((uint32_t *)p_private->r, sizeof(p_private->r),
+            &p_reg_priv_bn);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = sgx_ipp_newBN(NULL, order_size, &p_signx_bn);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = sgx_ipp_newBN(NULL, order_size, &p_signy_bn);
+        ERROR_BREAK(ipp_ret);
+
+        // Sign the message.
+        ipp_ret = ippsECCPSignDSA(p_msg_bn, p_reg_priv_bn, p_signx_bn, p_signy_bn,
+            p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+
+        IppsBigNumSGN sign;
+        int length;
+        ipp_ret = ippsRef_BN(&sign, &length,(Ipp32u**) &p_sigx, p_signx_bn);
+        ERROR_BREAK(ipp_ret);
+        memset(p_signature->x, 0, sizeof(p_signature->x));
+        ipp_ret = check_copy_size(sizeof(p_signature->x), ROUND_TO(length, 8) / 8);
+        ERROR_BREAK(ipp_ret);
+        memcpy(p_signature->x, p_sigx, ROUND_TO(length, 8) / 8);
+        memset_s(p_sigx, sizeof(p_signature->x), 0, ROUND_TO(length, 8) / 8);
+        ipp_ret = ippsRef_BN(&sign, &length,(Ipp32u**) &p_sigy, p_signy_bn);
+        ERROR_BREAK(ipp_ret);
+        memset(p_signature->y, 0, sizeof(p_signature->y));
+        ipp_ret = check_copy_size(sizeof(p_signature->y), ROUND_TO(length, 8) / 8);
+        ERROR_BREAK(ipp_ret);
+        memcpy(p_signature->y, p_sigy, ROUND_TO(length, 8) / 8);
+        memset_s(p_sigy, sizeof(p_signature->y), 0, ROUND_TO(length, 8) / 8);
+    } while (0);
+
+    // Clear buffer before free.
+    if (p_eph_pub)
+        memset_s(p_eph_pub, ecp_size, 0, ecp_size);
+    SAFE_FREE(p_eph_pub);
+    sgx_ipp_secure_free_BN(p_ecp_order, order_size);
+    sgx_ipp_secure_free_BN(p_hash_bn, SGX_SHA256_HASH_SIZE);
+    sgx_ipp_secure_free_BN(p_msg_bn, order_size);
+    sgx_ipp_secure_free_BN(p_eph_priv_bn, sizeof(p_nonce->r));
+    sgx_ipp_secure_free_BN(p_reg_priv_bn, sizeof(p_private->r));
+    sgx_ipp_secure_free_BN(p_signx_bn, order_size);
+    sgx_ipp_secure_free_BN(p_signy_bn, order_size);
+
+    switch (ipp_ret)
+    {
+    case ippStsNoErr: return SGX_SUCCESS;
+    case ippStsNoMemErr:
+    case ippStsMemAllocErr: return SGX_ERROR_OUT_OF_MEMORY;
+    case ippStsNullPtrErr:
+    case ippStsLengthErr:
+    case ippStsOutOfRangeErr:
+    case ippStsSizeErr:
+    case ippStsBadArgErr: return SGX_ERROR_INVALID_PARAMETER;
+    default: return SGX_ERROR_UNEXPECTED;
+    }
+}
+
 sgx_status_t sgx_ecdsa_verify(const uint8_t *p_data,
     uint32_t data_size,
     const sgx_ec256_public_t *p_public,
@@ -0,0 +1,468 @@
+/*
+ * Copyright (C) 2011-2021 Intel Corporation. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions
+ * are met:
+ *
+ *   * Redistributions of source code must retain the above copyright
+ *     notice, this list of conditions and the following disclaimer.
+ *   * Redistributions in binary form must reproduce the above copyright
+ *     notice, this list of conditions and the following disclaimer in
+ *     the documentation and/or other materials provided with the
+ *     distribution.
+ *   * Neither the name of Intel Corporation nor the names of its
+ *     contributors may be used to endorse or promote products derived
+ *     from this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+ * "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+ * LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+ * A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT
+ * OWNER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+ * SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT
+ * LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE,
+ * DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY
+ * THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+ * (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ *
+
+/**
+* File:
+*     sgx_rsa_padding.cpp
+* Description:
+*     Wrapper for RSA PKCS#1 v1.5, PSS and OAEP operations with a selectable
+*     hash function, working on key handles created by sgx_create_rsa_*_key.
+*
+*/
+#include <string.h>
+#include <stdint.h>
+#include <limits.h>
+#include "sgx_error.h"
+#include "sgx_trts.h"
+#include "ipp_wrapper.h"
+
+#define RSA_MAX_SEED_SIZE SGX_SHA512_HASH_SIZE
+
+static const IppsHashMethod* rsa_hash_method(sgx_rsa_hash_t hash, int *p_hash_size)
+{
+    switch (hash)
+    {
+    case SGX_RSA_HASH_SHA256:
+        *p_hash_size = SGX_SHA256_HASH_SIZE;
+        return ippsHashMethod_SHA256_TT();
+    case SGX_RSA_HASH_SHA384:
+        *p_hash_size = SGX_SHA384_HASH_SIZE;
+        return ippsHashMethod_SHA384_TT();
+    case SGX_RSA_HASH_SHA512:
+        *p_hash_size = SGX_SHA512_HASH_SIZE;
+        return ippsHashMethod_SHA512();
+    default:
+        return NULL;
+    }
+}
+
+static sgx_status_t rsa_ipp_status(IppStatus ipp_ret)
+{
+    switch (ipp_ret)
+    {
+    case ippStsNoErr: return SGX_SUCCESS;
+    case ippStsNoMemErr:
+    case ippStsMemAllocErr: return SGX_ERROR_OUT_OF_MEMORY;
+    case ippStsNullPtrErr:
+    case ippStsLengthErr:
+    case ippStsOutOfRangeErr:
+    case ippStsSizeErr:
+    case ippStsBadArgErr: return SGX_ERROR_INVALID_PARAMETER;
+    default: return SGX_ERROR_UNEXPECTED;
+    }
+}
+
+// Returns the modulus size in bytes of a public key handle.
+static IppStatus rsa_pub_mod_size(const IppsRSAPublicKeyState *p_key, int *p_size)
+{
+    IppsBigNumState *p_bn = NULL;
+    int bits = 0;
+
+    IppStatus ipp_ret = sgx_ipp_newBN(NULL, MAX_IPP_BN_LENGTH, &p_bn);
+    if (ipp_ret == ippStsNoErr) {
+        ipp_ret = ippsRSA_GetPublicKey(p_bn, NULL, p_key);
+    }
+    if (ipp_ret == ippStsNoErr) {
+        ipp_ret = ippsExtGet_BN(0, &bits, 0, p_bn);
+    }
+    if (ipp_ret == ippStsNoErr) {
+        *p_size = (bits + 7) / 8;
+    }
+    sgx_ipp_secure_free_BN(p_bn, MAX_IPP_BN_LENGTH);
+    return ipp_ret;
+}
+
+// Returns the modulus size in bytes of a private key handle of type 1 or 2.
+static IppStatus rsa_priv_mod_size(const IppsRSAPrivateKeyState *p_key, int *p_size)
+{
+    IppsBigNumState *p_bn = NULL;
+    int bits = 0;
+    int factor = 1;
+
+    IppStatus ipp_ret = sgx_ipp_newBN(NULL, MAX_IPP_BN_LENGTH, &p_bn);
+    if (ipp_ret == ippStsNoErr) {
+        if (ippsRSA_GetPrivateKeyType1(p_bn, NULL, p_key) != ippStsNoErr) {
+            //type 2 key, the prime factor P is half of the modulus size
+            //
+            ipp_ret = ippsRSA_GetPrivateKeyType2(p_bn, NULL, NULL, NULL, NULL, p_key);
+            factor = 2;
+        }
+    }
+    if (ipp_ret == ippStsNoErr) {
+        ipp_ret = ippsExtGet_BN(0, &bits, 0, p_bn);
+    }
+    if (ipp_ret == ippStsNoErr) {
+        *p_size = (bits + 7) / 8 * factor;
+    }
+    sgx_ipp_secure_free_BN(p_bn, MAX_IPP_BN_LENGTH);
+    return ipp_ret;
+}
+
+// Allocates a scratch buffer large enough for the private key and, if given,
+// the public key used to verify the signature before it is released.
+static IppStatus rsa_sign_buffer(const IppsRSAPrivateKeyState *p_key, const IppsRSAPublicKeyState *p_pub_key,
+    Ipp8u **pp_buff, int *p_buff_size)
+{
+    int buff_size = 0;
+    int pub_buff_size = 0;
+
+    IppStatus ipp_ret = ippsRSA_GetBufferSizePrivateKey(&buff_size, p_key);
+    if (ipp_ret != ippStsNoErr) {
+        return ipp_ret;
+    }
+    if (p_pub_key != NULL) {
+        ipp_ret = ippsRSA_GetBufferSizePublicKey(&pub_buff_size, p_pub_key);
+        if (ipp_ret != ippStsNoErr) {
+            return ipp_ret;
+        }
+        if (pub_buff_size > buff_size) {
+            buff_size = pub_buff_size;
+        }
+    }
+
+    *pp_buff = (Ipp8u *)malloc(buff_size);
+    if (*pp_buff == NULL) {
+        return ippStsMemAllocErr;
+    }
+    *p_buff_size = buff_size;
+    return ippStsNoErr;
+}
+
+sgx_status_t sgx_rsa_sign_pkcs1_v15(const void *rsa_key, const void *rsa_pub_key, sgx_rsa_hash_t hash,
+    const uint8_t *p_data, size_t data_size, uint8_t *p_signature, size_t signature_size)
+{
+    int hash_size = 0;
+    const IppsHashMethod *p_method = rsa_hash_method(hash, &hash_size);
+    if (rsa_key == NULL || p_method == NULL || p_data == NULL || data_size < 1 || data_size >= INT_MAX ||
+        p_signature == NULL) {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    const IppsRSAPrivateKeyState *p_key = (const IppsRSAPrivateKeyState *)rsa_key;
+    const IppsRSAPublicKeyState *p_pub_key = (const IppsRSAPublicKeyState *)rsa_pub_key;
+    Ipp8u *temp_buff = NULL;
+    int temp_buff_size = 0;
+    int mod_size = 0;
+    IppStatus ipp_ret = ippStsNoErr;
+
+    do {
+        ipp_ret = rsa_priv_mod_size(p_key, &mod_size);
+        ERROR_BREAK(ipp_ret);
+        if (signature_size < (size_t)mod_size) {
+            ipp_ret = ippStsSizeErr;
+            break;
+        }
+
+        ipp_ret = rsa_sign_buffer(p_key, p_pub_key, &temp_buff, &temp_buff_size);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = ippsRSASign_PKCS1v15_rmf(p_data, (int)data_size, p_signature, p_key, p_pub_key, p_method, temp_buff);
+    } while (0);
+
+    CLEAR_FREE_MEM(temp_buff, temp_buff_size);
+    return rsa_ipp_status(ipp_ret);
+}
+
+sgx_status_t sgx_rsa_verify_pkcs1_v15(const void *rsa_pub_key, sgx_rsa_hash_t hash,
+    const uint8_t *p_data, size_t data_size, const uint8_t *p_signature, size_t signature_size,
+    sgx_rsa_result_t *p_result)
+{
+    int hash_size = 0;
+    const IppsHashMethod *p_method = rsa_hash_method(hash, &hash_size);
+    if (rsa_pub_key == NULL || p_method == NULL || p_data == NULL || data_size < 1 || data_size >= INT_MAX ||
+        p_signature == NULL || p_result == NULL) {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+    *p_result = SGX_RSA_INVALID_SIGNATURE;
+
+    const IppsRSAPublicKeyState *p_key = (const IppsRSAPublicKeyState *)rsa_pub_key;
+    Ipp8u *temp_buff = NULL;
+    int temp_buff_size = 0;
+    int mod_size = 0;
+    int result = 0;
+    IppStatus ipp_ret = ippStsNoErr;
+
+    do {
+        ipp_ret = rsa_pub_mod_size(p_key, &mod_size);
+        ERROR_BREAK(ipp_ret);
+        if (signature_size != (size_t)mod_size) {
+            // a signature of the wrong length can never be valid
+            //
+            break;
+        }
+
+        ipp_ret = ippsRSA_GetBufferSizePublicKey(&temp_buff_size, p_key);
+        ERROR_BREAK(ipp_ret);
+        temp_buff = (Ipp8u *)malloc(temp_buff_size);
+        if (!temp_buff) {
+            ipp_ret = ippStsMemAllocErr;
+            break;
+        }
+
+        ipp_ret = ippsRSAVerify_PKCS1v15_rmf(p_data, (int)data_size, p_signature, &result, p_key, p_method, temp_buff);
+    } while (0);
+
+    if ((result != 0) && (ipp_ret == ippStsNoErr)) {
+        *p_result = SGX_RSA_VALID;
+    }
+
+    SAFE_FREE(temp_buff);
+    return rsa_ipp_status(ipp_ret);
+}
+
+sgx_status_t sgx_rsa_sign_pss(const void *rsa_key, const void *rsa_pub_key, sgx_rsa_hash_t hash, size_t salt_size,
+    const uint8_t *p_data, size_t data_size, uint8_t *p_signature, size_t signature_size)
+{
+    int hash_size = 0;
+    const IppsHashMethod *p_method = rsa_hash_method(hash, &hash_size);
+    if (rsa_key == NULL || p_method == NULL || p_data == NULL || data_size < 1 || data_size >= INT_MAX ||
+        p_signature == NULL) {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    const IppsRSAPrivateKeyState *p_key = (const IppsRSAPrivateKeyState *)rsa_key;
+    const IppsRSAPublicKeyState *p_pub_key = (const IppsRSAPublicKeyState *)rsa_pub_key;
+    Ipp8u *temp_buff = NULL;
+    int temp_buff_size = 0;
+    Ipp8u *p_salt = NULL;
+    int mod_size = 0;
+    sgx_status_t ret_code = SGX_ERROR_UNEXPECTED;
+    IppStatus ipp_ret = ippStsNoErr;
+
+    do {
+        ipp_ret = rsa_priv_mod_size(p_key, &mod_size);
+        ERROR_BREAK(ipp_ret);
+        // EMSA-PSS requires emLen >= hLen + sLen + 2
+        //
+        if (signature_size < (size_t)mod_size || mod_size < hash_size + 2 ||
+            salt_size > (size_t)(mod_size - hash_size - 2)) {
+            ipp_ret = ippStsSizeErr;
+            break;
+        }
+
+        if (salt_size > 0) {
+            p_salt = (Ipp8u *)malloc(salt_size);
+            if (!p_salt) {
+                ipp_ret = ippStsMemAllocErr;
+                break;
+            }
+            ret_code = sgx_read_rand(p_salt, salt_size);
+            if (ret_code != SGX_SUCCESS) {
+                break;
+            }
+        }
+
+        ipp_ret = rsa_sign_buffer(p_key, p_pub_key, &temp_buff, &temp_buff_size);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = ippsRSASign_PSS_rmf(p_data, (int)data_size, p_salt, (int)salt_size, p_signature, p_key, p_pub_key,
+            p_method, temp_buff);
+        ret_code = rsa_ipp_status(ipp_ret);
+    } while (0);
+
+    CLEAR_FREE_MEM(p_salt, salt_size);
+    CLEAR_FREE_MEM(temp_buff, temp_buff_size);
+    if (ipp_ret != ippStsNoErr) {
+        ret_code = rsa_ipp_status(ipp_ret);
+    }
+    return ret_code;
+}
+
+sgx_status_t sgx_rsa_verify_pss(const void *rsa_pub_key, sgx_rsa_hash_t hash,
+    const uint8_t *p_data, size_t data_size, const uint8_t *p_signature, size_t signature_size,
+    sgx_rsa_result_t *p_result)
+{
+    int hash_size = 0;
+    const IppsHashMethod *p_method = rsa_hash_method(hash, &hash_size);
+    if (rsa_pub_key == NULL || p_method == NULL || p_data == NULL || data_size < 1 || data_size >= INT_MAX ||
+        p_signature == NULL || p_result == NULL) {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+    *p_result = SGX_RSA_INVALID_SIGNATURE;
+
+    const IppsRSAPublicKeyState *p_key = (const IppsRSAPublicKeyState *)rsa_pub_key;
+    Ipp8u *temp_buff = NULL;
+    int temp_buff_size = 0;
+    int mod_size = 0;
+    int result = 0;
+    IppStatus ipp_ret = ippStsNoErr;
+
+    do {
+        ipp_ret = rsa_pub_mod_size(p_key, &mod_size);
+        ERROR_BREAK(ipp_ret);
+        if (signature_size != (size_t)mod_size) {
+            break;
+        }
+
+        ipp_ret = ippsRSA_GetBufferSizePublicKey(&temp_buff_size, p_key);
+        ERROR_BREAK(ipp_ret);
+        temp_buff = (Ipp8u *)malloc(temp_buff_size);
+        if (!temp_buff) {
+            ipp_ret = ippStsMemAllocErr;
+            break;
+        }
+
+        // the salt length is recovered from the encoded message
+        //
+        ipp_ret = ippsRSAVerify_PSS_rmf(p_data, (int)data_size, p_signature, &result, p_key, p_method, temp_buff);
+    } while (0);
+
+    if ((result != 0) && (ipp_ret == ippStsNoErr)) {
+        *p_result = SGX_RSA_VALID;
+    }
+
+    SAFE_FREE(temp_buff);
+    return rsa_ipp_status(ipp_ret);
+}
+
+sgx_status_t sgx_rsa_pub_encrypt_oaep(const void *rsa_key, sgx_rsa_hash_t hash, const uint8_t *p_label, size_t label_len,
+    unsigned char *pout_data, size_t *pout_len, const unsigned char *pin_data, const size_t pin_len)
+{
+    int hash_size = 0;
+    const IppsHashMethod *p_method = rsa_hash_method(hash, &hash_size);
+    if (rsa_key == NULL || p_method == NULL || pout_len == NULL || pin_data == NULL || pin_len < 1 ||
+        pin_len >= INT_MAX || label_len >= INT_MAX || (p_label == NULL && label_len != 0)) {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    const IppsRSAPublicKeyState *p_key = (const IppsRSAPublicKeyState *)rsa_key;
+    uint8_t *p_scratch_buffer = NULL;
+    int scratch_buff_size = 0;
+    Ipp8u seeds[RSA_MAX_SEED_SIZE] = { 0 };
+    int mod_size = 0;
+    sgx_status_t ret_code = SGX_ERROR_UNEXPECTED;
+    IppStatus ipp_ret = ippStsNoErr;
+
+    do {
+        ipp_ret = rsa_pub_mod_size(p_key, &mod_size);
+        ERROR_BREAK(ipp_ret);
+        if (pout_data == NULL) {
+            // return required pout_data buffer size
+            //
+            *pout_len = mod_size;
+            ret_code = SGX_SUCCESS;
+            break;
+        }
+        // EME-OAEP accepts at most k - 2 * hLen - 2 octets
+        //
+        if (*pout_len < (size_t)mod_size || mod_size < 2 * hash_size + 2 ||
+            pin_len > (size_t)(mod_size - 2 * hash_size - 2)) {
+            ipp_ret = ippStsSizeErr;
+            break;
+        }
+
+        ipp_ret = ippsRSA_GetBufferSizePublicKey(&scratch_buff_size, p_key);
+        ERROR_BREAK(ipp_ret);
+        p_scratch_buffer = (uint8_t *)malloc(scratch_buff_size);
+        if (!p_scratch_buffer) {
+            ipp_ret = ippStsMemAllocErr;
+            break;
+        }
+        memset(p_scratch_buffer, 0, scratch_buff_size);
+
+        ret_code = sgx_read_rand(seeds, hash_size);
+        if (ret_code != SGX_SUCCESS) {
+            break;
+        }
+
+        ipp_ret = ippsRSAEncrypt_OAEP_rmf(pin_data, (int)pin_len, p_label, (int)label_len, seeds,
+            pout_data, p_key, p_method, p_scratch_buffer);
+        ERROR_BREAK(ipp_ret);
+        *pout_len = mod_size;
+
+        ret_code = SGX_SUCCESS;
+    } while (0);
+
+    memset_s(seeds, RSA_MAX_SEED_SIZE, 0, RSA_MAX_SEED_SIZE);
+    CLEAR_FREE_MEM(p_scratch_buffer, scratch_buff_size);
+    if (ipp_ret != ippStsNoErr) {
+        ret_code = rsa_ipp_status(ipp_ret);
+    }
+    return ret_code;
+}
+
+sgx_status_t sgx_rsa_priv_decrypt_oaep(const void *rsa_key, sgx_rsa_hash_t hash, const uint8_t *p_label, size_t label_len,
+    unsigned char *pout_data, size_t *pout_len, const unsigned char *pin_data, const size_t pin_len)
+{
+    int hash_size = 0;
+    const IppsHashMethod *p_method = rsa_hash_method(hash, &hash_size);
+    if (rsa_key == NULL || p_method == NULL || pout_len == NULL || pin_data == NULL ||
+        label_len >= INT_MAX || (p_label == NULL && label_len != 0)) {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    const IppsRSAPrivateKeyState *p_key = (const IppsRSAPrivateKeyState *)rsa_key;
+    uint8_t *p_scratch_buffer = NULL;
+    int scratch_buff_size = 0;
+    int mod_size = 0;
+    int out_len = 0;
+    sgx_status_t ret_code = SGX_ERROR_UNEXPECTED;
+    IppStatus ipp_ret = ippStsNoErr;
+
+    do {
+        ipp_ret = rsa_priv_mod_size(p_key, &mod_size);
+        ERROR_BREAK(ipp_ret);
+        if (pout_data == NULL) {
+            // return required pout_data buffer size
+            //
+            *pout_len = mod_size;
+            ret_code = SGX_SUCCESS;
+            break;
+        }
+        if (pin_len != (size_t)mod_size || mod_size < 2 * hash_size + 2 ||
+            *pout_len < (size_t)(mod_size - 2 * hash_size - 2)) {
+            ipp_ret = ippStsSizeErr;
+            break;
+        }
+
+        ipp_ret = ippsRSA_GetBufferSizePrivateKey(&scratch_buff_size, p_key);
+        ERROR_BREAK(ipp_ret);
+        p_scratch_buffer = (uint8_t *)malloc(scratch_buff_size);
+        if (!p_scratch_buffer) {
+            ipp_ret = ippStsMemAllocErr;
+            break;
+        }
+
+        // a wrong label or padding is reported by IPP as a generic error
+        //
+        ipp_ret = ippsRSADecrypt_OAEP_rmf(pin_data, p_label, (int)label_len, pout_data, &out_len, p_key,
+            p_method, p_scratch_buffer);
+        ERROR_BREAK(ipp_ret);
+        *pout_len = out_len;
+
+        ret_code = SGX_SUCCESS;
+    } while (0);
+
+    CLEAR_FREE_MEM(p_scratch_buffer, scratch_buff_size);
+    if (ipp_ret != ippStsNoErr) {
+        ret_code = rsa_ipp_status(ipp_ret);
+    }
+    return ret_code;
+}
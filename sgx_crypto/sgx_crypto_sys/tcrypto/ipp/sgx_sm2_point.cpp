@@ -0,0 +1,285 @@
+/*
+ * Copyright (C) 2011-2021 Intel Corporation. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions
+ * are met:
+ *
+ *   * Redistributions of source code must retain the above copyright
+ *     notice, this list of conditions and the following disclaimer.
+ *   * Redistributions in binary form must reproduce the above copyright
+ *     notice, this list of conditions and the following disclaimer in
+ *     the documentation and/or other materials provided with the
+ *     distribution.
+ *   * Neither the name of Intel Corporation nor the names of its
+ *     contributors may be used to endorse or promote products derived
+ *     from this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+ * "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+ * LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+ * A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT
+ * OWNER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+ * SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT
+ * LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE,
+ * DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY
+ * THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+ * (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ *
+ */
+
+
+#include "ipp_wrapper.h"
+
+#define ECC_FIELD_SIZE 256
+
+/* Loads a LITTLE ENDIAN point into p_ecc_point and checks that it is a valid point
+ * on the curve and not infinity. */
+static IppStatus sgx_sm2_set_point(const sgx_ec256_public_t *p_point,
+                                   IppsECCPPointState *p_ecc_point,
+                                   IppsECCPState *p_ecc_state,
+                                   IppECResult *p_result)
+{
+    IppStatus ipp_ret = ippStsNoErr;
+    IppsBigNumState* BN_gx = NULL;
+    IppsBigNumState* BN_gy = NULL;
+
+    *p_result = ippECPointIsNotValid;
+    do
+    {
+        ipp_ret = sgx_ipp_newBN((const Ipp32u *)p_point->gx, sizeof(p_point->gx), &BN_gx);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = sgx_ipp_newBN((const Ipp32u *)p_point->gy, sizeof(p_point->gy), &BN_gy);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPSetPoint(BN_gx, BN_gy, p_ecc_point, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPCheckPoint(p_ecc_point, p_result, p_ecc_state);
+    } while (0);
+
+    sgx_ipp_secure_free_BN(BN_gx, sizeof(p_point->gx));
+    sgx_ipp_secure_free_BN(BN_gy, sizeof(p_point->gy));
+    return ipp_ret;
+}
+
+/* Checks that p_ecc_point is not infinity and stores it as a LITTLE ENDIAN point. */
+static IppStatus sgx_sm2_get_point(const IppsECCPPointState *p_ecc_point,
+                                   sgx_ec256_public_t *p_point,
+                                   IppsECCPState *p_ecc_state,
+                                   IppECResult *p_result)
+{
+    IppStatus ipp_ret = ippStsNoErr;
+    IppsBigNumState* BN_gx = NULL;
+    IppsBigNumState* BN_gy = NULL;
+    IppsBigNumSGN sgn = IppsBigNumPOS;
+    int length = 0;
+    Ipp32u *pdata = NULL;
+
+    do
+    {
+        ipp_ret = ippsECCPCheckPoint(p_ecc_point, p_result, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+        if (*p_result != ippECValid)
+        {
+            break;
+        }
+
+        ipp_ret = sgx_ipp_newBN(NULL, sizeof(p_point->gx), &BN_gx);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = sgx_ipp_newBN(NULL, sizeof(p_point->gy), &BN_gy);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPGetPoint(BN_gx, BN_gy, p_ecc_point, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+
+        memset(p_point, 0, sizeof(sgx_ec256_public_t));
+        ipp_ret = ippsRef_BN(&sgn, &length, &pdata, BN_gx);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = check_copy_size(sizeof(p_point->gx), ROUND_TO(length, 8) / 8);
+        ERROR_BREAK(ipp_ret);
+        memcpy(p_point->gx, pdata, ROUND_TO(length, 8) / 8);
+
+        ipp_ret = ippsRef_BN(&sgn, &length, &pdata, BN_gy);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = check_copy_size(sizeof(p_point->gy), ROUND_TO(length, 8) / 8);
+        ERROR_BREAK(ipp_ret);
+        memcpy(p_point->gy, pdata, ROUND_TO(length, 8) / 8);
+    } while (0);
+
+    sgx_ipp_secure_free_BN(BN_gx, sizeof(p_point->gx));
+    sgx_ipp_secure_free_BN(BN_gy, sizeof(p_point->gy));
+    return ipp_ret;
+}
+
+/* Computes the point [k]P on the SM2 curve
+* Parameters:
+*   Return: sgx_status_t - SGX_SUCCESS or failure as defined sgx_error.h
+*   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to ECC crypto system
+*           sgx_ec256_private_t *p_scalar - Pointer to the scalar k - LITTLE ENDIAN
+*           sgx_ec256_public_t *p_point - Pointer to the point P - LITTLE ENDIAN
+*   Output: sgx_ec256_public_t *p_result - Pointer to the resulting point - LITTLE ENDIAN
+*/
+sgx_status_t sgx_sm2_point_mul(const sgx_ec256_private_t *p_scalar,
+                               const sgx_ec256_public_t *p_point,
+                               sgx_ec256_public_t *p_result,
+                               sgx_ecc_state_handle_t ecc_handle)
+{
+    if ((ecc_handle == NULL) || (p_scalar == NULL) || (p_point == NULL) || (p_result == NULL))
+    {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    IppsBigNumState*    BN_k = NULL;
+    IppsECCPPointState* point_p = NULL;
+    IppsECCPPointState* point_r = NULL;
+    IppStatus           ipp_ret = ippStsNoErr;
+    int                 ecPointSize = 0;
+    IppsECCPState* p_ecc_state = (IppsECCPState*)ecc_handle;
+    IppECResult ipp_result = ippECValid;
+
+    do
+    {
+        ipp_ret = ippsECCPPointGetSize(ECC_FIELD_SIZE, &ecPointSize);
+        ERROR_BREAK(ipp_ret);
+        point_p = (IppsECCPPointState*)(malloc(ecPointSize));
+        point_r = (IppsECCPPointState*)(malloc(ecPointSize));
+        if (!point_p || !point_r)
+        {
+            ipp_ret = ippStsNoMemErr;
+            break;
+        }
+        ipp_ret = ippsECCPPointInit(ECC_FIELD_SIZE, point_p);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPPointInit(ECC_FIELD_SIZE, point_r);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = sgx_sm2_set_point(p_point, point_p, p_ecc_state, &ipp_result);
+        ERROR_BREAK(ipp_ret);
+        if (ipp_result != ippECValid)
+        {
+            break;
+        }
+
+        ipp_ret = sgx_ipp_newBN((const Ipp32u *)p_scalar->r, sizeof(sgx_ec256_private_t), &BN_k);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPMulPointScalar(point_p, BN_k, point_r, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = sgx_sm2_get_point(point_r, p_result, p_ecc_state, &ipp_result);
+    } while (0);
+
+    // Clear temp buffer before free.
+    if (point_p) memset_s(point_p, ecPointSize, 0, ecPointSize);
+    if (point_r) memset_s(point_r, ecPointSize, 0, ecPointSize);
+    SAFE_FREE(point_p);
+    SAFE_FREE(point_r);
+    sgx_ipp_secure_free_BN(BN_k, sizeof(sgx_ec256_private_t));
+
+    if (ipp_ret == ippStsNoErr && ipp_result != ippECValid)
+    {
+        memset_s(p_result, sizeof(sgx_ec256_public_t), 0, sizeof(sgx_ec256_public_t));
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+    switch (ipp_ret)
+    {
+    case ippStsNoErr: return SGX_SUCCESS;
+    case ippStsNoMemErr:
+    case ippStsMemAllocErr: return SGX_ERROR_OUT_OF_MEMORY;
+    case ippStsNullPtrErr:
+    case ippStsLengthErr:
+    case ippStsOutOfRangeErr:
+    case ippStsSizeErr:
+    case ippStsBadArgErr: return SGX_ERROR_INVALID_PARAMETER;
+    default: return SGX_ERROR_UNEXPECTED;
+    }
+}
+
+/* Computes the point P + Q on the SM2 curve
+* Parameters:
+*   Return: sgx_status_t - SGX_SUCCESS or failure as defined sgx_error.h
+*   Inputs: sgx_ecc_state_handle_t ecc_handle - Handle to ECC crypto system
+*           sgx_ec256_public_t *p_point_p - Pointer to the point P - LITTLE ENDIAN
+*           sgx_ec256_public_t *p_point_q - Pointer to the point Q - LITTLE ENDIAN
+*   Output: sgx_ec256_public_t *p_result - Pointer to the resulting point - LITTLE ENDIAN
+*/
+sgx_status_t sgx_sm2_point_add(const sgx_ec256_public_t *p_point_p,
+                               const sgx_ec256_public_t *p_point_q,
+                               sgx_ec256_public_t *p_result,
+                               sgx_ecc_state_handle_t ecc_handle)
+{
+    if ((ecc_handle == NULL) || (p_point_p == NULL) || (p_point_q == NULL) || (p_result == NULL))
+    {
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+
+    IppsECCPPointState* point_p = NULL;
+    IppsECCPPointState* point_q = NULL;
+    IppsECCPPointState* point_r = NULL;
+    IppStatus           ipp_ret = ippStsNoErr;
+    int                 ecPointSize = 0;
+    IppsECCPState* p_ecc_state = (IppsECCPState*)ecc_handle;
+    IppECResult ipp_result = ippECValid;
+
+    do
+    {
+        ipp_ret = ippsECCPPointGetSize(ECC_FIELD_SIZE, &ecPointSize);
+        ERROR_BREAK(ipp_ret);
+        point_p = (IppsECCPPointState*)(malloc(ecPointSize));
+        point_q = (IppsECCPPointState*)(malloc(ecPointSize));
+        point_r = (IppsECCPPointState*)(malloc(ecPointSize));
+        if (!point_p || !point_q || !point_r)
+        {
+            ipp_ret = ippStsNoMemErr;
+            break;
+        }
+        ipp_ret = ippsECCPPointInit(ECC_FIELD_SIZE, point_p);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPPointInit(ECC_FIELD_SIZE, point_q);
+        ERROR_BREAK(ipp_ret);
+        ipp_ret = ippsECCPPointInit(ECC_FIELD_SIZE, point_r);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = sgx_sm2_set_point(p_point_p, point_p, p_ecc_state, &ipp_result);
+        ERROR_BREAK(ipp_ret);
+        if (ipp_result != ippECValid)
+        {
+            break;
+        }
+        ipp_ret = sgx_sm2_set_point(p_point_q, point_q, p_ecc_state, &ipp_result);
+        ERROR_BREAK(ipp_ret);
+        if (ipp_result != ippECValid)
+        {
+            break;
+        }
+
+        ipp_ret = ippsECCPAddPoint(point_p, point_q, point_r, p_ecc_state);
+        ERROR_BREAK(ipp_ret);
+
+        ipp_ret = sgx_sm2_get_point(point_r, p_result, p_ecc_state, &ipp_result);
+    } while (0);
+
+    // Clear temp buffer before free.
+    if (point_p) memset_s(point_p, ecPointSize, 0, ecPointSize);
+    if (point_q) memset_s(point_q, ecPointSize, 0, ecPointSize);
+    if (point_r) memset_s(point_r, ecPointSize, 0, ecPointSize);
+    SAFE_FREE(point_p);
+    SAFE_FREE(point_q);
+    SAFE_FREE(point_r);
+
+    if (ipp_ret == ippStsNoErr && ipp_result != ippECValid)
+    {
+        memset_s(p_result, sizeof(sgx_ec256_public_t), 0, sizeof(sgx_ec256_public_t));
+        return SGX_ERROR_INVALID_PARAMETER;
+    }
+    switch (ipp_ret)
+    {
+    case ippStsNoErr: return SGX_SUCCESS;
+    case ippStsNoMemErr:
+    case ippStsMemAllocErr: return SGX_ERROR_OUT_OF_MEMORY;
+    case ippStsNullPtrErr:
+    case ippStsLengthErr:
+    case ippStsOutOfRangeErr:
+    case ippStsSizeErr:
+    case ippStsBadArgErr: return SGX_ERROR_INVALID_PARAMETER;
+    default: return SGX_ERROR_UNEXPECTED;
+    }
+}
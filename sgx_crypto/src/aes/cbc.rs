@@ -15,25 +15,27 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use super::{AesKey, AlignKey};
+use crate::cipher::BlockCipherMode;
 use core::array::TryFromSliceError;
 use core::convert::{From, TryFrom, TryInto};
 use sgx_crypto_sys::*;
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
-use sgx_types::types::{AlignKey128bit, Key128bit, AESCBC_IV_SIZE};
+use sgx_types::types::{Key128bit, AESCBC_IV_SIZE};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct AesCbc {
-    key: AlignKey128bit,
+pub struct AesCbc<K: AesKey = Key128bit> {
+    key: AlignKey<K>,
     iv: Nonce,
 }
 
-impl AesCbc {
+impl<K: AesKey> AesCbc<K> {
     const MBS_RIJ128: usize = 16;
 
-    pub fn new(key: &Key128bit, iv: Nonce) -> AesCbc {
+    pub fn new(key: &K, iv: Nonce) -> AesCbc<K> {
         AesCbc {
-            key: AlignKey128bit::from(key),
+            key: AlignKey::from(key),
             iv,
         }
     }
@@ -50,8 +52,9 @@ impl AesCbc {
         ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
 
         let status = unsafe {
-            sgx_aes_cbc_encrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_cbc_encrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 src.as_ptr(),
                 src_len as u32,
                 dst.as_mut_ptr(),
@@ -83,8 +86,9 @@ impl AesCbc {
         ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
 
         let status = unsafe {
-            sgx_aes_cbc_decrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_cbc_decrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 src.as_ptr(),
                 src_len as u32,
                 dst.as_mut_ptr(),
@@ -105,13 +109,40 @@ impl AesCbc {
     }
 }
 
-impl Default for AesCbc {
-    fn default() -> AesCbc {
-        let mut key = AlignKey128bit::default();
-        super::rand(&mut key.key);
+impl<K: AesKey> BlockCipherMode for AesCbc<K> {
+    type Key = K;
+    type Iv = Nonce;
 
+    #[inline]
+    fn new(key: &K, iv: Nonce) -> AesCbc<K> {
+        AesCbc::new(key, iv)
+    }
+
+    #[inline]
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.encrypt(src, dst)
+    }
+
+    #[inline]
+    fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.encrypt_in_place(in_out)
+    }
+
+    #[inline]
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.decrypt(src, dst)
+    }
+
+    #[inline]
+    fn decrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.decrypt_in_place(in_out)
+    }
+}
+
+impl<K: AesKey> Default for AesCbc<K> {
+    fn default() -> AesCbc<K> {
         AesCbc {
-            key,
+            key: AlignKey::random(),
             iv: Nonce::new(),
         }
     }
@@ -123,7 +154,7 @@ pub struct Nonce([u8; AESCBC_IV_SIZE]);
 impl Nonce {
     pub fn new() -> Nonce {
         let mut nonce = [0_u8; AESCBC_IV_SIZE];
-        super::rand(&mut nonce);
+        crate::rand(&mut nonce);
         Nonce(nonce)
     }
 
@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use super::{AesKey, AlignKey};
+use crate::cipher::Aead;
 use core::array::TryFromSliceError;
 use core::convert::{From, TryFrom, TryInto};
 use core::ptr;
@@ -22,27 +24,25 @@ use sgx_crypto_sys::*;
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
 use sgx_types::memeq::ConstTimeEq;
-use sgx_types::types::{
-    AesHandle, AlignKey128bit, AlignMac128bit, Key128bit, Mac128bit, AESCCM_IV_SIZE,
-};
+use sgx_types::types::{AesHandle, AlignMac128bit, Key128bit, Mac128bit, AESCCM_IV_SIZE};
 
 #[derive(Debug)]
-pub struct AesCcm<A: AsRef<[u8]>> {
-    key: AlignKey128bit,
+pub struct AesCcm<A: AsRef<[u8]>, K: AesKey = Key128bit> {
+    key: AlignKey<K>,
     iv: Nonce,
     aad: Aad<A>,
     handle: AesHandle,
 }
 
-impl<A: AsRef<[u8]>> AesCcm<A> {
-    pub fn new(key: &Key128bit, iv: Nonce, aad: Aad<A>) -> SgxResult<AesCcm<A>> {
+impl<A: AsRef<[u8]>, K: AesKey> AesCcm<A, K> {
+    pub fn new(key: &K, iv: Nonce, aad: Aad<A>) -> SgxResult<AesCcm<A, K>> {
         ensure!(
             aad.as_ref().len() <= i32::MAX as usize,
             SgxStatus::InvalidParameter
         );
 
         Ok(AesCcm {
-            key: AlignKey128bit::from(key),
+            key: AlignKey::from(key),
             iv,
             aad,
             handle: ptr::null_mut(),
@@ -72,8 +72,9 @@ impl<A: AsRef<[u8]>> AesCcm<A> {
 
         let mut mac = AlignMac128bit::default();
         let status = unsafe {
-            sgx_aes_ccm128_encrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_ccm_encrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 p_src,
                 src_len as u32,
                 p_dst,
@@ -118,8 +119,9 @@ impl<A: AsRef<[u8]>> AesCcm<A> {
         };
 
         let status = unsafe {
-            sgx_aes_ccm128_decrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_ccm_decrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 p_src,
                 src_len as u32,
                 p_dst,
@@ -148,8 +150,9 @@ impl<A: AsRef<[u8]>> AesCcm<A> {
 
         let mut mac = AlignMac128bit::default();
         let status = unsafe {
-            sgx_aes_ccm128_encrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_ccm_encrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 ptr::null(),
                 0,
                 ptr::null_mut(),
@@ -170,8 +173,9 @@ impl<A: AsRef<[u8]>> AesCcm<A> {
         ensure!(!aad.is_empty(), SgxStatus::InvalidParameter);
 
         let status = unsafe {
-            sgx_aes_ccm128_decrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_ccm_decrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 ptr::null(),
                 0,
                 ptr::null_mut(),
@@ -272,8 +276,9 @@ impl<A: AsRef<[u8]>> AesCcm<A> {
         };
 
         let status = unsafe {
-            sgx_aes_ccm128_init(
-                &self.key.key as *const u8,
+            sgx_aes_ccm_init_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 self.iv.as_ref().as_ptr(),
                 self.iv.as_ref().len() as u32,
                 p_aad,
@@ -287,13 +292,46 @@ impl<A: AsRef<[u8]>> AesCcm<A> {
     }
 }
 
-impl Default for AesCcm<[u8; 0]> {
-    fn default() -> AesCcm<[u8; 0]> {
-        let mut key = AlignKey128bit::default();
-        super::rand(&mut key.key);
+impl<A: AsRef<[u8]>, K: AesKey> Aead for AesCcm<A, K> {
+    type Key = K;
+    type Nonce = Nonce;
 
+    const NONCE_SIZE: usize = AESCCM_IV_SIZE;
+    const ID: u16 = 0x0200 | K::SIZE as u16;
+
+    #[inline]
+    fn zeroed_nonce() -> Nonce {
+        Nonce::zeroed()
+    }
+
+    fn encrypt_detached(
+        key: &K,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        let mut cipher = AesCcm::new(key, nonce, Aad::from(aad))?;
+        cipher.encrypt(src, dst)
+    }
+
+    fn decrypt_detached(
+        key: &K,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        let mut cipher = AesCcm::new(key, nonce, Aad::from(aad))?;
+        cipher.decrypt(src, dst, mac)
+    }
+}
+
+impl<K: AesKey> Default for AesCcm<[u8; 0], K> {
+    fn default() -> AesCcm<[u8; 0], K> {
         AesCcm {
-            key,
+            key: AlignKey::random(),
             iv: Nonce::new(),
             aad: Aad::default(),
             handle: ptr::null_mut(),
@@ -301,7 +339,7 @@ impl Default for AesCcm<[u8; 0]> {
     }
 }
 
-impl<A: AsRef<[u8]>> Drop for AesCcm<A> {
+impl<A: AsRef<[u8]>, K: AesKey> Drop for AesCcm<A, K> {
     fn drop(&mut self) {
         if !self.handle.is_null() {
             unsafe { sgx_aes_ccm_close(self.handle) };
@@ -391,7 +429,7 @@ pub struct Nonce([u8; AESCCM_IV_SIZE]);
 impl Nonce {
     pub fn new() -> Nonce {
         let mut nonce = [0_u8; AESCCM_IV_SIZE];
-        super::rand(&mut nonce);
+        crate::rand(&mut nonce);
         Nonce(nonce)
     }
 
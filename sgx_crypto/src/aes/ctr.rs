@@ -15,25 +15,27 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use super::{AesKey, AlignKey};
+use crate::cipher::BlockCipherMode;
 use core::array::TryFromSliceError;
 use core::convert::{From, TryFrom, TryInto};
 use sgx_crypto_sys::*;
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
-use sgx_types::types::{AlignKey128bit, Key128bit, AESCTR_CTR_SIZE};
+use sgx_types::types::{Key128bit, AESCTR_CTR_SIZE};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct AesCtr {
-    key: AlignKey128bit,
+pub struct AesCtr<K: AesKey = Key128bit> {
+    key: AlignKey<K>,
     ctr: Counter,
 }
 
-impl AesCtr {
+impl<K: AesKey> AesCtr<K> {
     const CTR_INC_BITS: u32 = 128;
 
-    pub fn new(key: &Key128bit, ctr: Counter) -> AesCtr {
+    pub fn new(key: &K, ctr: Counter) -> AesCtr<K> {
         AesCtr {
-            key: AlignKey128bit::from(key),
+            key: AlignKey::from(key),
             ctr,
         }
     }
@@ -50,8 +52,9 @@ impl AesCtr {
 
         let mut ctr = self.ctr;
         let status = unsafe {
-            sgx_aes_ctr_encrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_ctr_encrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 src.as_ptr(),
                 src_len as u32,
                 ctr.as_mut() as *mut u8,
@@ -83,8 +86,9 @@ impl AesCtr {
 
         let mut ctr = self.ctr;
         let status = unsafe {
-            sgx_aes_ctr_decrypt(
-                &self.key.key as *const Key128bit,
+            sgx_aes_ctr_decrypt_ex(
+                self.key.as_ptr(),
+                K::SIZE as u32,
                 src.as_ptr(),
                 src_len as u32,
                 ctr.as_mut() as *mut u8,
@@ -105,13 +109,40 @@ impl AesCtr {
     }
 }
 
-impl Default for AesCtr {
-    fn default() -> AesCtr {
-        let mut key = AlignKey128bit::default();
-        super::rand(&mut key.key);
+impl<K: AesKey> BlockCipherMode for AesCtr<K> {
+    type Key = K;
+    type Iv = Counter;
 
+    #[inline]
+    fn new(key: &K, iv: Counter) -> AesCtr<K> {
+        AesCtr::new(key, iv)
+    }
+
+    #[inline]
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.encrypt(src, dst)
+    }
+
+    #[inline]
+    fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.encrypt_in_place(in_out)
+    }
+
+    #[inline]
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.decrypt(src, dst)
+    }
+
+    #[inline]
+    fn decrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.decrypt_in_place(in_out)
+    }
+}
+
+impl<K: AesKey> Default for AesCtr<K> {
+    fn default() -> AesCtr<K> {
         AesCtr {
-            key,
+            key: AlignKey::random(),
             ctr: Counter::nonce(),
         }
     }
@@ -123,7 +154,7 @@ pub struct Counter([u8; AESCTR_CTR_SIZE]);
 impl Counter {
     pub fn nonce() -> Counter {
         let mut nonce = [0_u8; AESCTR_CTR_SIZE];
-        super::rand(&mut nonce);
+        crate::rand(&mut nonce);
         Counter(nonce)
     }
 
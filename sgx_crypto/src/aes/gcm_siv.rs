@@ -0,0 +1,380 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::polyval::Polyval;
+use super::{AesKey, AlignKey};
+use crate::cipher::Aead;
+use core::array::TryFromSliceError;
+use core::convert::{From, TryFrom, TryInto};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{Key128bit, Mac128bit, KEY_192BIT_SIZE};
+
+pub const AESGCMSIV_NONCE_SIZE: usize = 12;
+
+const BLOCK_SIZE: usize = 16;
+
+// AES-GCM-SIV (RFC 8452). The tag is computed over the whole plaintext before
+// any output is produced, so there is no incremental update interface.
+// RFC 8452 defines AES-128 and AES-256 keys only.
+#[derive(Debug)]
+pub struct AesGcmSiv<A: AsRef<[u8]>, K: AesKey = Key128bit> {
+    key: AlignKey<K>,
+    nonce: Nonce,
+    aad: Aad<A>,
+}
+
+struct SubKeys<K: AesKey> {
+    auth_key: [u8; BLOCK_SIZE],
+    enc_key: K,
+}
+
+impl<A: AsRef<[u8]>, K: AesKey> AesGcmSiv<A, K> {
+    pub fn new(key: &K, nonce: Nonce, aad: Aad<A>) -> SgxResult<AesGcmSiv<A, K>> {
+        ensure!(
+            aad.as_ref().len() < i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+
+        Ok(AesGcmSiv {
+            key: AlignKey::from(key),
+            nonce,
+            aad,
+        })
+    }
+
+    pub fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult<Mac128bit> {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(src_len < i32::MAX as usize, SgxStatus::InvalidParameter);
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        let keys = self.derive_keys()?;
+        let mac = self.tag(&keys, src)?;
+        ctr_apply(&keys.enc_key, &mac, src, dst)?;
+        Ok(mac)
+    }
+
+    pub fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult<Mac128bit> {
+        let mut dst = vec![0_u8; in_out.len()];
+        let mac = self.encrypt(in_out, dst.as_mut_slice())?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(mac)
+    }
+
+    pub fn decrypt(&mut self, src: &[u8], dst: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(src_len < i32::MAX as usize, SgxStatus::InvalidParameter);
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        let keys = self.derive_keys()?;
+        ctr_apply(&keys.enc_key, mac, src, dst)?;
+
+        let expected = self.tag(&keys, dst)?;
+        if !expected.ct_eq(mac) {
+            dst.fill(0);
+            bail!(SgxStatus::MacMismatch);
+        }
+        Ok(())
+    }
+
+    pub fn decrypt_in_place(&mut self, in_out: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        let mut dst = vec![0_u8; in_out.len()];
+        self.decrypt(in_out, dst.as_mut_slice(), mac)?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(())
+    }
+
+    pub fn mac(&mut self) -> SgxResult<Mac128bit> {
+        ensure!(!self.aad.as_ref().is_empty(), SgxStatus::InvalidParameter);
+
+        let keys = self.derive_keys()?;
+        self.tag(&keys, &[])
+    }
+
+    pub fn verify_mac(&mut self, mac: &Mac128bit) -> SgxResult {
+        let expected = self.mac()?;
+        ensure!(expected.ct_eq(mac), SgxStatus::MacMismatch);
+        Ok(())
+    }
+
+    fn derive_keys(&self) -> SgxResult<SubKeys<K>> {
+        ensure!(K::SIZE != KEY_192BIT_SIZE, SgxStatus::InvalidParameter);
+
+        let mut keys = SubKeys {
+            auth_key: [0_u8; BLOCK_SIZE],
+            enc_key: K::default(),
+        };
+
+        // Two blocks for the authentication key, then one per 8 bytes of the
+        // encryption key; only the first half of each block is used.
+        let mut block = [0_u8; BLOCK_SIZE];
+        for i in 0..(2 + K::SIZE / 8) as u32 {
+            block[..4].copy_from_slice(&i.to_le_bytes());
+            block[4..].copy_from_slice(self.nonce.as_ref());
+            super::encrypt_block(&self.key.key, &mut block)?;
+
+            let offset = i as usize * 8;
+            if i < 2 {
+                keys.auth_key[offset..offset + 8].copy_from_slice(&block[..8]);
+            } else {
+                let offset = offset - BLOCK_SIZE;
+                keys.enc_key.as_mut()[offset..offset + 8].copy_from_slice(&block[..8]);
+            }
+        }
+        block.fill(0);
+        Ok(keys)
+    }
+
+    fn tag(&self, keys: &SubKeys<K>, plaintext: &[u8]) -> SgxResult<Mac128bit> {
+        let aad = self.aad.as_ref();
+
+        let mut polyval = Polyval::new(&keys.auth_key);
+        polyval.update_padded(aad);
+        polyval.update_padded(plaintext);
+
+        let mut lengths = [0_u8; BLOCK_SIZE];
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_le_bytes());
+        lengths[8..].copy_from_slice(&(plaintext.len() as u64 * 8).to_le_bytes());
+        polyval.block(&lengths);
+
+        let mut s = polyval.finalize();
+        for (s, n) in s.iter_mut().zip(self.nonce.as_ref().iter()) {
+            *s ^= *n;
+        }
+        s[15] &= 0x7f;
+
+        super::encrypt_block(&keys.enc_key, &mut s)?;
+        Ok(s)
+    }
+}
+
+// CTR mode keyed by the tag; the counter is a little-endian 32-bit word that wraps.
+fn ctr_apply<K: AesKey>(key: &K, tag: &Mac128bit, src: &[u8], dst: &mut [u8]) -> SgxResult {
+    let mut counter = *tag;
+    counter[15] |= 0x80;
+
+    let mut keystream = [0_u8; BLOCK_SIZE];
+    for (src, dst) in src.chunks(BLOCK_SIZE).zip(dst.chunks_mut(BLOCK_SIZE)) {
+        keystream = counter;
+        super::encrypt_block(key, &mut keystream)?;
+        for ((d, s), k) in dst.iter_mut().zip(src.iter()).zip(keystream.iter()) {
+            *d = *s ^ *k;
+        }
+
+        let ctr = u32::from_le_bytes(counter[..4].try_into().unwrap()).wrapping_add(1);
+        counter[..4].copy_from_slice(&ctr.to_le_bytes());
+    }
+    keystream.fill(0);
+    Ok(())
+}
+
+impl<K: AesKey> Drop for SubKeys<K> {
+    fn drop(&mut self) {
+        self.auth_key.fill(0);
+        self.enc_key.as_mut().fill(0);
+    }
+}
+
+impl<A: AsRef<[u8]>, K: AesKey> Aead for AesGcmSiv<A, K> {
+    type Key = K;
+    type Nonce = Nonce;
+
+    const NONCE_SIZE: usize = AESGCMSIV_NONCE_SIZE;
+    const ID: u16 = 0x0300 | K::SIZE as u16;
+
+    #[inline]
+    fn zeroed_nonce() -> Nonce {
+        Nonce::zeroed()
+    }
+
+    fn encrypt_detached(
+        key: &K,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        let mut cipher = AesGcmSiv::new(key, nonce, Aad::from(aad))?;
+        cipher.encrypt(src, dst)
+    }
+
+    fn decrypt_detached(
+        key: &K,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        let mut cipher = AesGcmSiv::new(key, nonce, Aad::from(aad))?;
+        cipher.decrypt(src, dst, mac)
+    }
+}
+
+impl<K: AesKey> Default for AesGcmSiv<[u8; 0], K> {
+    fn default() -> AesGcmSiv<[u8; 0], K> {
+        AesGcmSiv {
+            key: AlignKey::random(),
+            nonce: Nonce::new(),
+            aad: Aad::default(),
+        }
+    }
+}
+
+impl<A: AsRef<[u8]>, K: AesKey> Drop for AesGcmSiv<A, K> {
+    fn drop(&mut self) {
+        self.key.key.as_mut().fill(0);
+    }
+}
+
+pub struct Aad<A>(A);
+
+impl<A: AsRef<[u8]>> Aad<A> {
+    #[inline]
+    pub fn from(aad: A) -> Aad<A> {
+        Aad(aad)
+    }
+}
+
+impl<A> AsRef<[u8]> for Aad<A>
+where
+    A: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl Aad<[u8; 0]> {
+    pub fn empty() -> Aad<[u8; 0]> {
+        Self::from([])
+    }
+}
+
+impl Default for Aad<[u8; 0]> {
+    fn default() -> Aad<[u8; 0]> {
+        Aad::empty()
+    }
+}
+
+impl<A> Clone for Aad<A>
+where
+    A: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Aad<A> {
+        Self(self.0.clone())
+    }
+}
+
+impl<A> Copy for Aad<A> where A: Copy {}
+
+impl<A> core::fmt::Debug for Aad<A>
+where
+    A: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Aad").field(&self.0).finish()
+    }
+}
+
+impl<A> PartialEq for Aad<A>
+where
+    A: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+impl<A> Eq for Aad<A> where A: Eq {}
+
+impl<A> ConstTimeEq for Aad<A>
+where
+    A: ConstTimeEq + BytewiseEquality + Eq,
+{
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+unsafe impl<A: ContiguousMemory> ContiguousMemory for Aad<A> {}
+
+unsafe impl<A: BytewiseEquality> BytewiseEquality for Aad<A> {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Nonce([u8; AESGCMSIV_NONCE_SIZE]);
+
+impl Nonce {
+    pub fn new() -> Nonce {
+        let mut nonce = [0_u8; AESGCMSIV_NONCE_SIZE];
+        crate::rand(&mut nonce);
+        Nonce(nonce)
+    }
+
+    #[inline]
+    pub fn zeroed() -> Nonce {
+        Nonce([0_u8; AESGCMSIV_NONCE_SIZE])
+    }
+}
+
+impl Default for Nonce {
+    #[inline]
+    fn default() -> Nonce {
+        Nonce::new()
+    }
+}
+
+impl AsRef<[u8; AESGCMSIV_NONCE_SIZE]> for Nonce {
+    #[inline]
+    fn as_ref(&self) -> &[u8; AESGCMSIV_NONCE_SIZE] {
+        &self.0
+    }
+}
+
+impl From<[u8; AESGCMSIV_NONCE_SIZE]> for Nonce {
+    #[inline]
+    fn from(nonce: [u8; AESGCMSIV_NONCE_SIZE]) -> Nonce {
+        Nonce(nonce)
+    }
+}
+
+impl From<&[u8; AESGCMSIV_NONCE_SIZE]> for Nonce {
+    #[inline]
+    fn from(nonce: &[u8; AESGCMSIV_NONCE_SIZE]) -> Nonce {
+        Nonce(*nonce)
+    }
+}
+
+impl TryFrom<&[u8]> for Nonce {
+    type Error = TryFromSliceError;
+
+    fn try_from(nonce: &[u8]) -> Result<Nonce, TryFromSliceError> {
+        let nonce: &[u8; AESGCMSIV_NONCE_SIZE] = nonce.try_into()?;
+        Ok(Nonce(*nonce))
+    }
+}
+
+unsafe impl ContiguousMemory for Nonce {}
+
+unsafe impl BytewiseEquality for Nonce {}
@@ -0,0 +1,204 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{AesKey, AlignKey};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::Key128bit;
+
+pub const KEYWRAP_BLOCK_SIZE: usize = 8;
+
+// RFC 3394 default initial value and RFC 5649 alternative initial value prefix.
+const DEFAULT_IV: [u8; KEYWRAP_BLOCK_SIZE] = [0xa6; KEYWRAP_BLOCK_SIZE];
+const AIV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+// AES key wrap (RFC 3394, NIST SP 800-38F KW) and key wrap with padding
+// (RFC 5649, KWP). Unwrapping checks the integrity of the wrapped key and
+// fails with SgxStatus::MacMismatch if it has been modified.
+#[derive(Debug)]
+pub struct AesKeyWrap<K: AesKey = Key128bit> {
+    kek: AlignKey<K>,
+}
+
+impl<K: AesKey> AesKeyWrap<K> {
+    pub fn new(kek: &K) -> AesKeyWrap<K> {
+        AesKeyWrap {
+            kek: AlignKey::from(kek),
+        }
+    }
+
+    // KW: the key must be a multiple of 8 bytes and at least 16 bytes long.
+    pub fn wrap(&self, key: &[u8]) -> SgxResult<Vec<u8>> {
+        let len = key.len();
+        ensure!(
+            len >= 2 * KEYWRAP_BLOCK_SIZE && len % KEYWRAP_BLOCK_SIZE == 0,
+            SgxStatus::InvalidParameter
+        );
+        ensure!(len < i32::MAX as usize, SgxStatus::InvalidParameter);
+
+        self.wrap_blocks(&DEFAULT_IV, key)
+    }
+
+    pub fn unwrap(&self, wrapped: &[u8]) -> SgxResult<Vec<u8>> {
+        let len = wrapped.len();
+        ensure!(
+            len >= 3 * KEYWRAP_BLOCK_SIZE && len % KEYWRAP_BLOCK_SIZE == 0,
+            SgxStatus::InvalidParameter
+        );
+        ensure!(len < i32::MAX as usize, SgxStatus::InvalidParameter);
+
+        let (iv, mut key) = self.unwrap_blocks(wrapped)?;
+        if !iv.ct_eq(&DEFAULT_IV) {
+            key.fill(0);
+            bail!(SgxStatus::MacMismatch);
+        }
+        Ok(key)
+    }
+
+    // KWP: keys of any length from 1 byte up are accepted.
+    pub fn wrap_with_padding(&self, key: &[u8]) -> SgxResult<Vec<u8>> {
+        let len = key.len();
+        ensure!(
+            len > 0 && len < i32::MAX as usize - KEYWRAP_BLOCK_SIZE,
+            SgxStatus::InvalidParameter
+        );
+
+        let mut iv = [0_u8; KEYWRAP_BLOCK_SIZE];
+        iv[..4].copy_from_slice(&AIV_PREFIX);
+        iv[4..].copy_from_slice(&(len as u32).to_be_bytes());
+
+        let padded_len = (len + KEYWRAP_BLOCK_SIZE - 1) / KEYWRAP_BLOCK_SIZE * KEYWRAP_BLOCK_SIZE;
+        let mut padded = vec![0_u8; padded_len];
+        padded[..len].copy_from_slice(key);
+
+        // A single padded block is encrypted directly, together with the AIV.
+        let wrapped = if padded_len == KEYWRAP_BLOCK_SIZE {
+            let mut block = [0_u8; 16];
+            block[..8].copy_from_slice(&iv);
+            block[8..].copy_from_slice(&padded);
+            let result = super::encrypt_block(&self.kek.key, &mut block).map(|_| block.to_vec());
+            block.fill(0);
+            result
+        } else {
+            self.wrap_blocks(&iv, &padded)
+        };
+        padded.fill(0);
+        wrapped
+    }
+
+    pub fn unwrap_with_padding(&self, wrapped: &[u8]) -> SgxResult<Vec<u8>> {
+        let len = wrapped.len();
+        ensure!(
+            len >= 2 * KEYWRAP_BLOCK_SIZE && len % KEYWRAP_BLOCK_SIZE == 0,
+            SgxStatus::InvalidParameter
+        );
+        ensure!(len < i32::MAX as usize, SgxStatus::InvalidParameter);
+
+        let (iv, mut key) = if len == 2 * KEYWRAP_BLOCK_SIZE {
+            let mut block: [u8; 16] = wrapped.try_into().unwrap();
+            super::decrypt_block(&self.kek.key, &mut block)?;
+            let iv: [u8; KEYWRAP_BLOCK_SIZE] = block[..8].try_into().unwrap();
+            let key = block[8..].to_vec();
+            block.fill(0);
+            (iv, key)
+        } else {
+            self.unwrap_blocks(wrapped)?
+        };
+
+        // Check the AIV prefix, the message length indicator and the zero
+        // padding together, so a failure does not tell which one was wrong.
+        let padded_len = key.len();
+        let mli = u32::from_be_bytes(iv[4..].try_into().unwrap()) as usize;
+        let mut valid = iv[..4].ct_eq(&AIV_PREFIX);
+        valid &= mli + KEYWRAP_BLOCK_SIZE > padded_len && mli <= padded_len;
+        valid &= key[mli.min(padded_len)..].iter().fold(0, |acc, b| acc | b) == 0;
+
+        if !valid {
+            key.fill(0);
+            bail!(SgxStatus::MacMismatch);
+        }
+        key.truncate(mli);
+        Ok(key)
+    }
+
+    // W(S) of RFC 3394 section 2.2.1, with the index based description.
+    fn wrap_blocks(&self, iv: &[u8; KEYWRAP_BLOCK_SIZE], plain: &[u8]) -> SgxResult<Vec<u8>> {
+        let n = plain.len() / KEYWRAP_BLOCK_SIZE;
+
+        let mut out = vec![0_u8; plain.len() + KEYWRAP_BLOCK_SIZE];
+        out[..KEYWRAP_BLOCK_SIZE].copy_from_slice(iv);
+        out[KEYWRAP_BLOCK_SIZE..].copy_from_slice(plain);
+
+        let mut block = [0_u8; 16];
+        for j in 0..6 {
+            for i in 1..=n {
+                let r = i * KEYWRAP_BLOCK_SIZE;
+                block[..8].copy_from_slice(&out[..8]);
+                block[8..].copy_from_slice(&out[r..r + 8]);
+                super::encrypt_block(&self.kek.key, &mut block)?;
+
+                let t = (n * j + i) as u64;
+                for (a, t) in block[..8].iter_mut().zip(t.to_be_bytes().iter()) {
+                    *a ^= *t;
+                }
+                out[..8].copy_from_slice(&block[..8]);
+                out[r..r + 8].copy_from_slice(&block[8..]);
+            }
+        }
+        block.fill(0);
+        Ok(out)
+    }
+
+    // W^-1(C) of RFC 3394 section 2.2.2, returning the recovered initial value
+    // and the plaintext blocks.
+    fn unwrap_blocks(&self, wrapped: &[u8]) -> SgxResult<([u8; KEYWRAP_BLOCK_SIZE], Vec<u8>)> {
+        let n = wrapped.len() / KEYWRAP_BLOCK_SIZE - 1;
+
+        let mut iv: [u8; KEYWRAP_BLOCK_SIZE] = wrapped[..8].try_into().unwrap();
+        let mut key = wrapped[KEYWRAP_BLOCK_SIZE..].to_vec();
+
+        let mut block = [0_u8; 16];
+        for j in (0..6).rev() {
+            for i in (1..=n).rev() {
+                let r = (i - 1) * KEYWRAP_BLOCK_SIZE;
+                let t = (n * j + i) as u64;
+                for ((b, a), t) in block[..8]
+                    .iter_mut()
+                    .zip(iv.iter())
+                    .zip(t.to_be_bytes().iter())
+                {
+                    *b = *a ^ *t;
+                }
+                block[8..].copy_from_slice(&key[r..r + 8]);
+                super::decrypt_block(&self.kek.key, &mut block)?;
+
+                iv.copy_from_slice(&block[..8]);
+                key[r..r + 8].copy_from_slice(&block[8..]);
+            }
+        }
+        block.fill(0);
+        Ok((iv, key))
+    }
+}
+
+impl<K: AesKey> Drop for AesKeyWrap<K> {
+    fn drop(&mut self) {
+        self.kek.key.as_mut().fill(0);
+    }
+}
@@ -15,20 +15,128 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use core::fmt;
+use sgx_crypto_sys::{sgx_aes_cbc_decrypt_ex, sgx_aes_ctr_encrypt_ex};
+use sgx_types::error::SgxResult;
+use sgx_types::types::{Key128bit, Key192bit, Key256bit};
+
 pub mod cbc;
 pub mod ccm;
 pub mod ctr;
 pub mod gcm;
+pub mod gcm_siv;
+pub mod keywrap;
+
+pub(crate) mod polyval;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An AES-128, AES-192 or AES-256 key.
+///
+/// The AES modes are generic over the key type and default to `Key128bit`,
+/// e.g. `AesGcm<A>` is AES-128-GCM and `AesGcm<A, Key256bit>` is AES-256-GCM.
+pub trait AesKey:
+    Copy + Default + Eq + fmt::Debug + AsRef<[u8]> + AsMut<[u8]> + private::Sealed
+{
+    const SIZE: usize;
+}
+
+macro_rules! impl_aes_key {
+    ($($t:ty;)*) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl AesKey for $t {
+                const SIZE: usize = core::mem::size_of::<$t>();
+            }
+        )*
+    }
+}
+
+impl_aes_key! {
+    Key128bit;
+    Key192bit;
+    Key256bit;
+}
+
+// Keeps the key within a single cache line, as AlignKey128bit does.
+#[repr(C, align(64))]
+#[derive(Clone, Copy, Default, Eq, PartialEq)]
+pub(crate) struct AlignKey<K: AesKey> {
+    pub key: K,
+}
 
-#[inline]
-pub(crate) fn rand(nonce: &mut [u8]) {
-    cfg_if! {
-        if #[cfg(feature = "tcrypto")] {
-            use sgx_trts::rand::Rng;
-            Rng::new().fill_bytes(nonce);
-        } else {
-            use sgx_rand::{RdRand, Rng};
-            RdRand::new().unwrap().fill_bytes(nonce);
-        }
+impl<K: AesKey> AlignKey<K> {
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.key.as_ref().as_ptr()
     }
+
+    pub fn random() -> AlignKey<K> {
+        let mut key = AlignKey::<K>::default();
+        crate::rand(key.key.as_mut());
+        key
+    }
+}
+
+impl<K: AesKey> From<&K> for AlignKey<K> {
+    #[inline]
+    fn from(key: &K) -> AlignKey<K> {
+        AlignKey { key: *key }
+    }
+}
+
+impl<K: AesKey> fmt::Debug for AlignKey<K> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("AlignKey")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+// Encrypts a single block in place: CTR mode over a zero block yields E(K, block).
+pub(crate) fn encrypt_block<K: AesKey>(key: &K, block: &mut [u8; 16]) -> SgxResult {
+    let src = [0_u8; 16];
+    let mut ctr = *block;
+    let status = unsafe {
+        sgx_aes_ctr_encrypt_ex(
+            key.as_ref().as_ptr(),
+            K::SIZE as u32,
+            src.as_ptr(),
+            src.len() as u32,
+            ctr.as_mut_ptr(),
+            128,
+            block.as_mut_ptr(),
+        )
+    };
+    // the counter holds the input block, incremented
+    ctr.fill(0);
+
+    ensure!(status.is_success(), status);
+    Ok(())
+}
+
+// Decrypts a single block in place: CBC mode with a zero IV yields D(K, block).
+pub(crate) fn decrypt_block<K: AesKey>(key: &K, block: &mut [u8; 16]) -> SgxResult {
+    let src = *block;
+    let iv = [0_u8; 16];
+    let status = unsafe {
+        sgx_aes_cbc_decrypt_ex(
+            key.as_ref().as_ptr(),
+            K::SIZE as u32,
+            src.as_ptr(),
+            src.len() as u32,
+            block.as_mut_ptr(),
+            iv.as_ptr(),
+            iv.len() as u32,
+        )
+    };
+
+    ensure!(status.is_success(), status);
+    Ok(())
 }
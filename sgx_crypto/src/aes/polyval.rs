@@ -0,0 +1,188 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// POLYVAL universal hash as specified in RFC 8452, section 3. The field
+// multiplication uses integer multiplies with holes, so it runs in
+// constant time without relying on carry-less multiply instructions.
+
+use core::convert::TryInto;
+
+pub(crate) const POLYVAL_BLOCK_SIZE: usize = 16;
+
+#[derive(Clone)]
+pub(crate) struct Polyval {
+    h: (u64, u64),
+    s: (u64, u64),
+}
+
+impl Polyval {
+    pub fn new(key: &[u8; POLYVAL_BLOCK_SIZE]) -> Polyval {
+        Polyval {
+            h: load(key),
+            s: (0, 0),
+        }
+    }
+
+    // Absorbs the input, zero-padding the final partial block.
+    pub fn update_padded(&mut self, data: &[u8]) {
+        let mut chunks = data.chunks_exact(POLYVAL_BLOCK_SIZE);
+        for chunk in &mut chunks {
+            self.block(chunk.try_into().unwrap());
+        }
+
+        let rem = chunks.remainder();
+        if !rem.is_empty() {
+            let mut block = [0_u8; POLYVAL_BLOCK_SIZE];
+            block[..rem.len()].copy_from_slice(rem);
+            self.block(&block);
+        }
+    }
+
+    pub fn block(&mut self, block: &[u8; POLYVAL_BLOCK_SIZE]) {
+        let x = load(block);
+        self.s = mul((self.s.0 ^ x.0, self.s.1 ^ x.1), self.h);
+    }
+
+    pub fn finalize(self) -> [u8; POLYVAL_BLOCK_SIZE] {
+        let mut out = [0_u8; POLYVAL_BLOCK_SIZE];
+        out[..8].copy_from_slice(&self.s.0.to_le_bytes());
+        out[8..].copy_from_slice(&self.s.1.to_le_bytes());
+        out
+    }
+}
+
+impl Drop for Polyval {
+    fn drop(&mut self) {
+        self.h = (0, 0);
+        self.s = (0, 0);
+    }
+}
+
+// GHASH (NIST SP 800-38D) computed with POLYVAL, as described in RFC 8452
+// appendix A: the key is multiplied by x and every block is byte-reversed.
+#[derive(Clone)]
+pub(crate) struct Ghash(Polyval);
+
+impl Ghash {
+    pub fn new(key: &[u8; POLYVAL_BLOCK_SIZE]) -> Ghash {
+        let mut h = *key;
+        h.reverse();
+        let (lo, hi) = load(&h);
+        h.fill(0);
+
+        // mulX_POLYVAL, with the reduction applied through a mask.
+        let mask = 0_u64.wrapping_sub(hi >> 63);
+        let lo2 = (lo << 1) ^ (mask & 1);
+        let hi2 = ((hi << 1) | (lo >> 63)) ^ (mask & 0xc200_0000_0000_0000);
+
+        Ghash(Polyval {
+            h: (lo2, hi2),
+            s: (0, 0),
+        })
+    }
+
+    pub fn update_padded(&mut self, data: &[u8]) {
+        let mut chunks = data.chunks_exact(POLYVAL_BLOCK_SIZE);
+        for chunk in &mut chunks {
+            self.block(chunk.try_into().unwrap());
+        }
+
+        let rem = chunks.remainder();
+        if !rem.is_empty() {
+            let mut block = [0_u8; POLYVAL_BLOCK_SIZE];
+            block[..rem.len()].copy_from_slice(rem);
+            self.block(&block);
+        }
+    }
+
+    pub fn block(&mut self, block: &[u8; POLYVAL_BLOCK_SIZE]) {
+        let mut x = *block;
+        x.reverse();
+        self.0.block(&x);
+    }
+
+    pub fn finalize(self) -> [u8; POLYVAL_BLOCK_SIZE] {
+        let mut out = self.0.finalize();
+        out.reverse();
+        out
+    }
+}
+
+#[inline]
+fn load(block: &[u8; POLYVAL_BLOCK_SIZE]) -> (u64, u64) {
+    (
+        u64::from_le_bytes(block[..8].try_into().unwrap()),
+        u64::from_le_bytes(block[8..].try_into().unwrap()),
+    )
+}
+
+// Karatsuba multiplication followed by reduction modulo x^128 + x^127 + x^126 + x^121 + 1.
+fn mul(x: (u64, u64), h: (u64, u64)) -> (u64, u64) {
+    let (h0, h1) = h;
+    let h0r = h0.reverse_bits();
+    let h1r = h1.reverse_bits();
+    let h2 = h0 ^ h1;
+    let h2r = h0r ^ h1r;
+
+    let (y0, y1) = x;
+    let y0r = y0.reverse_bits();
+    let y1r = y1.reverse_bits();
+    let y2 = y0 ^ y1;
+    let y2r = y0r ^ y1r;
+
+    let z0 = bmul64(y0, h0);
+    let z1 = bmul64(y1, h1);
+    let mut z2 = bmul64(y2, h2);
+    let mut z0h = bmul64(y0r, h0r);
+    let mut z1h = bmul64(y1r, h1r);
+    let mut z2h = bmul64(y2r, h2r);
+    z2 ^= z0 ^ z1;
+    z2h ^= z0h ^ z1h;
+    z0h = z0h.reverse_bits() >> 1;
+    z1h = z1h.reverse_bits() >> 1;
+    z2h = z2h.reverse_bits() >> 1;
+
+    let v0 = z0;
+    let mut v1 = z0h ^ z2;
+    let mut v2 = z1 ^ z2h;
+    let mut v3 = z1h;
+
+    v2 ^= v0 ^ (v0 >> 1) ^ (v0 >> 2) ^ (v0 >> 7);
+    v1 ^= (v0 << 63) ^ (v0 << 62) ^ (v0 << 57);
+    v3 ^= v1 ^ (v1 >> 1) ^ (v1 >> 2) ^ (v1 >> 7);
+    v2 ^= (v1 << 63) ^ (v1 << 62) ^ (v1 << 57);
+
+    (v2, v3)
+}
+
+// Carry-less 64x64 multiplication, keeping the low 64 bits.
+fn bmul64(x: u64, y: u64) -> u64 {
+    const M0: u64 = 0x1111_1111_1111_1111;
+    const M1: u64 = 0x2222_2222_2222_2222;
+    const M2: u64 = 0x4444_4444_4444_4444;
+    const M3: u64 = 0x8888_8888_8888_8888;
+
+    let (x0, x1, x2, x3) = (x & M0, x & M1, x & M2, x & M3);
+    let (y0, y1, y2, y3) = (y & M0, y & M1, y & M2, y & M3);
+
+    let z0 = x0.wrapping_mul(y0) ^ x1.wrapping_mul(y3) ^ x2.wrapping_mul(y2) ^ x3.wrapping_mul(y1);
+    let z1 = x0.wrapping_mul(y1) ^ x1.wrapping_mul(y0) ^ x2.wrapping_mul(y3) ^ x3.wrapping_mul(y2);
+    let z2 = x0.wrapping_mul(y2) ^ x1.wrapping_mul(y1) ^ x2.wrapping_mul(y0) ^ x3.wrapping_mul(y3);
+    let z3 = x0.wrapping_mul(y3) ^ x1.wrapping_mul(y2) ^ x2.wrapping_mul(y1) ^ x3.wrapping_mul(y0);
+
+    (z0 & M0) | (z1 & M1) | (z2 & M2) | (z3 & M3)
+}
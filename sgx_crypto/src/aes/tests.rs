@@ -0,0 +1,188 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::gcm_siv::{Aad, AesGcmSiv, Nonce};
+use super::keywrap::AesKeyWrap;
+use super::AesKey;
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::{Key128bit, Key192bit, Key256bit};
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// RFC 8452, appendix C.1 (AES-128) and C.2 (AES-256): key 01 00.., nonce 03 00..
+// (plaintext, aad, ciphertext || tag)
+const GCM_SIV_128: [(&str, &str, &str); 3] = [
+    ("", "", "dc20e2d83f25705bb49e439eca56de25"),
+    (
+        "0100000000000000",
+        "",
+        "b5d839330ac7b786578782fff6013b815b287c22493a364c",
+    ),
+    (
+        "02000000000000000000000000000000",
+        "01",
+        "e2b0c5da79a901c1745f700525cb335b8f8936ec039e4e4bb97ebd8c4457441f",
+    ),
+];
+
+const GCM_SIV_256: [(&str, &str, &str); 3] = [
+    ("", "", "07f5f4169bbf55a8400cd47ea6fd400f"),
+    (
+        "0100000000000000",
+        "",
+        "c2ef328e5c71c83b843122130f7364b761e0b97427e3df28",
+    ),
+    (
+        "02000000000000000000000000000000",
+        "01",
+        "c91545823cc24f17dbb0e9e807d5ec17b292d28ff61189e8e49f3875ef91aff7",
+    ),
+];
+
+fn gcm_siv_vectors<K: AesKey>(vectors: &[(&str, &str, &str)]) {
+    let mut key = K::default();
+    key.as_mut()[0] = 1;
+    let mut nonce = [0_u8; 12];
+    nonce[0] = 3;
+
+    for (plaintext, aad, expected) in vectors {
+        let plaintext = decode(plaintext);
+        let aad = decode(aad);
+        let expected = decode(expected);
+        let (ciphertext, tag) = expected.split_at(plaintext.len());
+
+        let mut aead = AesGcmSiv::new(&key, Nonce::from(nonce), Aad::from(aad.as_slice())).unwrap();
+        let mut out = vec![0_u8; plaintext.len()];
+        let mac = aead.encrypt(&plaintext, &mut out).unwrap();
+        assert_eq!(out, ciphertext);
+        assert_eq!(mac.as_slice(), tag);
+
+        let mut mac = mac;
+        let mut aead = AesGcmSiv::new(&key, Nonce::from(nonce), Aad::from(aad.as_slice())).unwrap();
+        aead.decrypt(ciphertext, &mut out, &mac).unwrap();
+        assert_eq!(out, plaintext);
+
+        mac[15] ^= 1;
+        let mut aead = AesGcmSiv::new(&key, Nonce::from(nonce), Aad::from(aad.as_slice())).unwrap();
+        assert_eq!(
+            aead.decrypt(ciphertext, &mut out, &mac),
+            Err(SgxStatus::MacMismatch)
+        );
+    }
+}
+
+#[test_case]
+fn aes128_gcm_siv() {
+    gcm_siv_vectors::<Key128bit>(&GCM_SIV_128);
+}
+
+#[test_case]
+fn aes256_gcm_siv() {
+    gcm_siv_vectors::<Key256bit>(&GCM_SIV_256);
+}
+
+const KEK: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+const KEY_DATA: &str = "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f";
+
+// RFC 3394, sections 4.1 to 4.6: (KEK size, key data size, wrapped key)
+const KEYWRAP: [(usize, usize, &str); 6] = [
+    (16, 16, "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5"),
+    (24, 16, "96778b25ae6ca435f92b5b97c050aed2468ab8a17ad84e5d"),
+    (32, 16, "64e8c3f9ce0f5ba263e9777905818a2a93c8191e7d6e8ae7"),
+    (
+        24,
+        24,
+        "031d33264e15d33268f24ec260743edce1c6c7ddee725a936ba814915c6762d2",
+    ),
+    (
+        32,
+        24,
+        "a8f9bc1612c68b3ff6e6f4fbe30e71e4769c8b80a32cb8958cd5d17d6b254da1",
+    ),
+    (
+        32,
+        32,
+        "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21",
+    ),
+];
+
+fn keywrap_vector<K: AesKey>(kek: &[u8], key_data: &[u8], expected: &[u8]) {
+    let mut key = K::default();
+    key.as_mut().copy_from_slice(kek);
+    let kw = AesKeyWrap::new(&key);
+
+    let wrapped = kw.wrap(key_data).unwrap();
+    assert_eq!(wrapped, expected);
+    assert_eq!(kw.unwrap(&wrapped).unwrap(), key_data);
+
+    let mut wrapped = wrapped;
+    wrapped[0] ^= 1;
+    assert_eq!(kw.unwrap(&wrapped), Err(SgxStatus::MacMismatch));
+}
+
+#[test_case]
+fn keywrap_rfc3394() {
+    let kek = decode(KEK);
+    let key_data = decode(KEY_DATA);
+    for (kek_size, data_size, expected) in KEYWRAP {
+        let (kek, key_data, expected) =
+            (&kek[..kek_size], &key_data[..data_size], decode(expected));
+        match kek_size {
+            16 => keywrap_vector::<Key128bit>(kek, key_data, &expected),
+            24 => keywrap_vector::<Key192bit>(kek, key_data, &expected),
+            _ => keywrap_vector::<Key256bit>(kek, key_data, &expected),
+        }
+    }
+}
+
+// RFC 5649, section 6: (key, wrapped key)
+const KEYWRAP_PAD: [(&str, &str); 2] = [
+    (
+        "c37b7e6492584340bed12207808941155068f738",
+        "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a",
+    ),
+    ("466f7250617369", "afbeb0f07dfbf5419200f2ccb50bb24f"),
+];
+
+#[test_case]
+fn keywrap_with_padding_rfc5649() {
+    let mut kek = Key192bit::default();
+    kek.copy_from_slice(&decode("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8"));
+    let kw = AesKeyWrap::new(&kek);
+
+    for (key, expected) in KEYWRAP_PAD {
+        let (key, expected) = (decode(key), decode(expected));
+        let wrapped = kw.wrap_with_padding(&key).unwrap();
+        assert_eq!(wrapped, expected);
+        assert_eq!(kw.unwrap_with_padding(&wrapped).unwrap(), key);
+
+        let mut wrapped = wrapped;
+        let last = wrapped.len() - 1;
+        wrapped[last] ^= 1;
+        assert_eq!(
+            kw.unwrap_with_padding(&wrapped),
+            Err(SgxStatus::MacMismatch)
+        );
+    }
+}
@@ -0,0 +1,121 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::CHACHA20_NONCE_SIZE;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::Key256bit;
+
+pub(crate) const CHACHA20_BLOCK_SIZE: usize = 64;
+
+const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+// ChaCha20 stream cipher as specified in RFC 8439, section 2.4.
+#[derive(Clone)]
+pub(crate) struct ChaCha20 {
+    state: [u32; 16],
+    keystream: [u8; CHACHA20_BLOCK_SIZE],
+    offset: usize,
+    exhausted: bool,
+}
+
+impl ChaCha20 {
+    pub fn new(key: &Key256bit, nonce: &[u8; CHACHA20_NONCE_SIZE], counter: u32) -> ChaCha20 {
+        let mut state = [0_u32; 16];
+        state[..4].copy_from_slice(&SIGMA);
+        for (i, chunk) in key.chunks_exact(4).enumerate() {
+            state[4 + i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        state[12] = counter;
+        for (i, chunk) in nonce.chunks_exact(4).enumerate() {
+            state[13 + i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        ChaCha20 {
+            state,
+            keystream: [0_u8; CHACHA20_BLOCK_SIZE],
+            offset: CHACHA20_BLOCK_SIZE,
+            exhausted: false,
+        }
+    }
+
+    pub fn apply_keystream(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        ensure!(src.len() == dst.len(), SgxStatus::InvalidParameter);
+
+        for (s, d) in src.iter().zip(dst.iter_mut()) {
+            if self.offset == CHACHA20_BLOCK_SIZE {
+                self.refill()?;
+            }
+            *d = *s ^ self.keystream[self.offset];
+            self.offset += 1;
+        }
+        Ok(())
+    }
+
+    pub fn keystream_block(&mut self, block: &mut [u8; CHACHA20_BLOCK_SIZE]) -> SgxResult {
+        self.refill()?;
+        block.copy_from_slice(&self.keystream);
+        self.offset = CHACHA20_BLOCK_SIZE;
+        Ok(())
+    }
+
+    fn refill(&mut self) -> SgxResult {
+        // The 32-bit block counter must not wrap, otherwise the keystream repeats.
+        ensure!(!self.exhausted, SgxStatus::InvalidState);
+
+        let mut working = self.state;
+        for _ in 0..10 {
+            quarter_round(&mut working, 0, 4, 8, 12);
+            quarter_round(&mut working, 1, 5, 9, 13);
+            quarter_round(&mut working, 2, 6, 10, 14);
+            quarter_round(&mut working, 3, 7, 11, 15);
+            quarter_round(&mut working, 0, 5, 10, 15);
+            quarter_round(&mut working, 1, 6, 11, 12);
+            quarter_round(&mut working, 2, 7, 8, 13);
+            quarter_round(&mut working, 3, 4, 9, 14);
+        }
+        for (i, (w, s)) in working.iter().zip(self.state.iter()).enumerate() {
+            self.keystream[i * 4..i * 4 + 4].copy_from_slice(&w.wrapping_add(*s).to_le_bytes());
+        }
+        working.fill(0);
+
+        match self.state[12].checked_add(1) {
+            Some(counter) => self.state[12] = counter,
+            None => self.exhausted = true,
+        }
+        self.offset = 0;
+        Ok(())
+    }
+}
+
+impl Drop for ChaCha20 {
+    fn drop(&mut self) {
+        self.state.fill(0);
+        self.keystream.fill(0);
+    }
+}
+
+#[inline(always)]
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
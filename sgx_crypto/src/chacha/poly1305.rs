@@ -0,0 +1,603 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::chacha20::{ChaCha20, CHACHA20_BLOCK_SIZE};
+use super::CHACHA20_NONCE_SIZE;
+use crate::cipher::Aead;
+use core::array::TryFromSliceError;
+use core::convert::{From, TryFrom, TryInto};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{AlignKey256bit, AlignMac128bit, Key256bit, Mac128bit};
+
+#[derive(Debug)]
+pub struct ChaCha20Poly1305<A: AsRef<[u8]>> {
+    key: AlignKey256bit,
+    nonce: Nonce,
+    aad: Aad<A>,
+    stream: Option<Stream>,
+}
+
+struct Stream {
+    cipher: ChaCha20,
+    mac: Poly1305,
+    len: u64,
+}
+
+impl core::fmt::Debug for Stream {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Stream").field("len", &self.len).finish()
+    }
+}
+
+impl<A: AsRef<[u8]>> ChaCha20Poly1305<A> {
+    pub fn new(key: &Key256bit, nonce: Nonce, aad: Aad<A>) -> SgxResult<ChaCha20Poly1305<A>> {
+        ensure!(
+            aad.as_ref().len() < i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+
+        Ok(ChaCha20Poly1305 {
+            key: AlignKey256bit::from(key),
+            nonce,
+            aad,
+            stream: None,
+        })
+    }
+
+    pub fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult<Mac128bit> {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(src_len < i32::MAX as usize, SgxStatus::InvalidParameter);
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        let mut stream = self.start()?;
+        stream.cipher.apply_keystream(src, dst)?;
+        stream.mac.update(dst);
+        stream.len = src_len as u64;
+
+        let mut mac = AlignMac128bit::default();
+        stream.finalize(self.aad.as_ref().len() as u64, &mut mac.mac);
+        Ok(mac.mac)
+    }
+
+    pub fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult<Mac128bit> {
+        let mut dst = vec![0_u8; in_out.len()];
+        let mac = self.encrypt(in_out, dst.as_mut_slice())?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(mac)
+    }
+
+    pub fn decrypt(&mut self, src: &[u8], dst: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(src_len < i32::MAX as usize, SgxStatus::InvalidParameter);
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        let mut stream = self.start()?;
+        stream.mac.update(src);
+        stream.len = src_len as u64;
+
+        let mut expected = AlignMac128bit::default();
+        stream.finalize(self.aad.as_ref().len() as u64, &mut expected.mac);
+        ensure!(expected.mac.ct_eq(mac), SgxStatus::MacMismatch);
+
+        stream.cipher.apply_keystream(src, dst)
+    }
+
+    pub fn decrypt_in_place(&mut self, in_out: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        let mut dst = vec![0_u8; in_out.len()];
+        self.decrypt(in_out, dst.as_mut_slice(), mac)?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(())
+    }
+
+    pub fn mac(&mut self) -> SgxResult<Mac128bit> {
+        ensure!(!self.aad.as_ref().is_empty(), SgxStatus::InvalidParameter);
+
+        let stream = self.start()?;
+        let mut mac = AlignMac128bit::default();
+        stream.finalize(self.aad.as_ref().len() as u64, &mut mac.mac);
+        Ok(mac.mac)
+    }
+
+    pub fn verify_mac(&mut self, mac: &Mac128bit) -> SgxResult {
+        let expected = self.mac()?;
+        ensure!(expected.ct_eq(mac), SgxStatus::MacMismatch);
+        Ok(())
+    }
+
+    pub fn enc_update(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(
+            (1..i32::MAX as usize).contains(&src_len),
+            SgxStatus::InvalidParameter
+        );
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        if self.stream.is_none() {
+            self.stream = Some(self.start()?);
+        }
+
+        let stream = self.stream.as_mut().ok_or(SgxStatus::Unexpected)?;
+        stream.cipher.apply_keystream(src, dst)?;
+        stream.mac.update(dst);
+        stream.len += src_len as u64;
+        Ok(())
+    }
+
+    pub fn enc_update_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        let mut dst = vec![0_u8; in_out.len()];
+        self.enc_update(in_out, dst.as_mut_slice())?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(())
+    }
+
+    pub unsafe fn dec_update(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(
+            (1..i32::MAX as usize).contains(&src_len),
+            SgxStatus::InvalidParameter
+        );
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        if self.stream.is_none() {
+            self.stream = Some(self.start()?);
+        }
+
+        let stream = self.stream.as_mut().ok_or(SgxStatus::Unexpected)?;
+        stream.mac.update(src);
+        stream.cipher.apply_keystream(src, dst)?;
+        stream.len += src_len as u64;
+        Ok(())
+    }
+
+    pub unsafe fn dec_update_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        let mut dst = vec![0_u8; in_out.len()];
+        self.dec_update(in_out, dst.as_mut_slice())?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(())
+    }
+
+    pub fn enc_get_mac(&mut self) -> SgxResult<Mac128bit> {
+        let aad_len = self.aad.as_ref().len() as u64;
+        let stream = self.stream.as_ref().ok_or(SgxStatus::InvalidState)?;
+
+        let mut mac = AlignMac128bit::default();
+        stream.finalize(aad_len, &mut mac.mac);
+        Ok(mac.mac)
+    }
+
+    pub fn dec_verify_mac(&mut self, mac: &Mac128bit) -> SgxResult {
+        let expected = self.enc_get_mac()?;
+        ensure!(expected.ct_eq(mac), SgxStatus::MacMismatch);
+        Ok(())
+    }
+
+    fn start(&self) -> SgxResult<Stream> {
+        let mut cipher = ChaCha20::new(&self.key.key, self.nonce.as_ref(), 0);
+
+        let mut block = [0_u8; CHACHA20_BLOCK_SIZE];
+        cipher.keystream_block(&mut block)?;
+        let mut mac = Poly1305::new(block[..32].try_into().unwrap());
+        block.fill(0);
+
+        mac.update(self.aad.as_ref());
+        mac.pad();
+
+        Ok(Stream {
+            cipher,
+            mac,
+            len: 0,
+        })
+    }
+}
+
+impl Stream {
+    // Finalizes a copy of the authenticator, so the stream may be continued.
+    fn finalize(&self, aad_len: u64, mac: &mut Mac128bit) {
+        let mut poly = self.mac.clone();
+        poly.pad();
+        poly.update(&aad_len.to_le_bytes());
+        poly.update(&self.len.to_le_bytes());
+        poly.finalize_into(mac);
+    }
+}
+
+impl<A: AsRef<[u8]>> Aead for ChaCha20Poly1305<A> {
+    type Key = Key256bit;
+    type Nonce = Nonce;
+
+    const NONCE_SIZE: usize = CHACHA20_NONCE_SIZE;
+    const ID: u16 = 0x0420;
+
+    #[inline]
+    fn zeroed_nonce() -> Nonce {
+        Nonce::zeroed()
+    }
+
+    fn encrypt_detached(
+        key: &Key256bit,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        let mut cipher = ChaCha20Poly1305::new(key, nonce, Aad::from(aad))?;
+        cipher.encrypt(src, dst)
+    }
+
+    fn decrypt_detached(
+        key: &Key256bit,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        let mut cipher = ChaCha20Poly1305::new(key, nonce, Aad::from(aad))?;
+        cipher.decrypt(src, dst, mac)
+    }
+}
+
+impl Default for ChaCha20Poly1305<[u8; 0]> {
+    fn default() -> ChaCha20Poly1305<[u8; 0]> {
+        let mut key = AlignKey256bit::default();
+        crate::rand(&mut key.key);
+
+        ChaCha20Poly1305 {
+            key,
+            nonce: Nonce::new(),
+            aad: Aad::default(),
+            stream: None,
+        }
+    }
+}
+
+impl<A: AsRef<[u8]>> Drop for ChaCha20Poly1305<A> {
+    fn drop(&mut self) {
+        self.key.key.fill(0);
+    }
+}
+
+// Poly1305 one-time authenticator (RFC 8439, section 2.5), 26-bit limb arithmetic.
+#[derive(Clone)]
+struct Poly1305 {
+    r: [u32; 5],
+    h: [u32; 5],
+    pad: [u32; 4],
+    buffer: [u8; 16],
+    leftover: usize,
+}
+
+impl Poly1305 {
+    fn new(key: &[u8; 32]) -> Poly1305 {
+        let r = [
+            le32(&key[0..4]) & 0x03ff_ffff,
+            (le32(&key[3..7]) >> 2) & 0x03ff_ff03,
+            (le32(&key[6..10]) >> 4) & 0x03ff_c0ff,
+            (le32(&key[9..13]) >> 6) & 0x03f0_3fff,
+            (le32(&key[12..16]) >> 8) & 0x000f_ffff,
+        ];
+        let pad = [
+            le32(&key[16..20]),
+            le32(&key[20..24]),
+            le32(&key[24..28]),
+            le32(&key[28..32]),
+        ];
+
+        Poly1305 {
+            r,
+            h: [0; 5],
+            pad,
+            buffer: [0; 16],
+            leftover: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        if self.leftover > 0 {
+            let want = core::cmp::min(16 - self.leftover, data.len());
+            self.buffer[self.leftover..self.leftover + want].copy_from_slice(&data[..want]);
+            self.leftover += want;
+            data = &data[want..];
+            if self.leftover < 16 {
+                return;
+            }
+            let block = self.buffer;
+            self.block(&block, false);
+            self.leftover = 0;
+        }
+
+        let mut chunks = data.chunks_exact(16);
+        for chunk in &mut chunks {
+            self.block(chunk.try_into().unwrap(), false);
+        }
+
+        let rem = chunks.remainder();
+        self.buffer[..rem.len()].copy_from_slice(rem);
+        self.leftover = rem.len();
+    }
+
+    // Zero-pads the pending input up to the next 16-byte boundary.
+    fn pad(&mut self) {
+        if self.leftover > 0 {
+            self.buffer[self.leftover..].fill(0);
+            let block = self.buffer;
+            self.block(&block, false);
+            self.leftover = 0;
+        }
+    }
+
+    fn block(&mut self, m: &[u8; 16], last: bool) {
+        let hibit: u32 = if last { 0 } else { 1 << 24 };
+        let [r0, r1, r2, r3, r4] = self.r;
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+
+        h0 += le32(&m[0..4]) & 0x03ff_ffff;
+        h1 += (le32(&m[3..7]) >> 2) & 0x03ff_ffff;
+        h2 += (le32(&m[6..10]) >> 4) & 0x03ff_ffff;
+        h3 += (le32(&m[9..13]) >> 6) & 0x03ff_ffff;
+        h4 += (le32(&m[12..16]) >> 8) | hibit;
+
+        let mul = |a: u32, b: u32| a as u64 * b as u64;
+        let d0 = mul(h0, r0) + mul(h1, s4) + mul(h2, s3) + mul(h3, s2) + mul(h4, s1);
+        let mut d1 = mul(h0, r1) + mul(h1, r0) + mul(h2, s4) + mul(h3, s3) + mul(h4, s2);
+        let mut d2 = mul(h0, r2) + mul(h1, r1) + mul(h2, r0) + mul(h3, s4) + mul(h4, s3);
+        let mut d3 = mul(h0, r3) + mul(h1, r2) + mul(h2, r1) + mul(h3, r0) + mul(h4, s4);
+        let mut d4 = mul(h0, r4) + mul(h1, r3) + mul(h2, r2) + mul(h3, r1) + mul(h4, r0);
+
+        let mut c = (d0 >> 26) as u32;
+        h0 = d0 as u32 & 0x03ff_ffff;
+        d1 += c as u64;
+        c = (d1 >> 26) as u32;
+        h1 = d1 as u32 & 0x03ff_ffff;
+        d2 += c as u64;
+        c = (d2 >> 26) as u32;
+        h2 = d2 as u32 & 0x03ff_ffff;
+        d3 += c as u64;
+        c = (d3 >> 26) as u32;
+        h3 = d3 as u32 & 0x03ff_ffff;
+        d4 += c as u64;
+        c = (d4 >> 26) as u32;
+        h4 = d4 as u32 & 0x03ff_ffff;
+        h0 += c * 5;
+        c = h0 >> 26;
+        h0 &= 0x03ff_ffff;
+        h1 += c;
+
+        self.h = [h0, h1, h2, h3, h4];
+    }
+
+    fn finalize_into(mut self, mac: &mut Mac128bit) {
+        if self.leftover > 0 {
+            self.buffer[self.leftover] = 1;
+            self.buffer[self.leftover + 1..].fill(0);
+            let block = self.buffer;
+            self.block(&block, true);
+        }
+
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.h;
+
+        let mut c = h1 >> 26;
+        h1 &= 0x03ff_ffff;
+        h2 += c;
+        c = h2 >> 26;
+        h2 &= 0x03ff_ffff;
+        h3 += c;
+        c = h3 >> 26;
+        h3 &= 0x03ff_ffff;
+        h4 += c;
+        c = h4 >> 26;
+        h4 &= 0x03ff_ffff;
+        h0 += c * 5;
+        c = h0 >> 26;
+        h0 &= 0x03ff_ffff;
+        h1 += c;
+
+        // compute h + -p and select it in constant time if h >= p
+        let mut g0 = h0.wrapping_add(5);
+        c = g0 >> 26;
+        g0 &= 0x03ff_ffff;
+        let mut g1 = h1.wrapping_add(c);
+        c = g1 >> 26;
+        g1 &= 0x03ff_ffff;
+        let mut g2 = h2.wrapping_add(c);
+        c = g2 >> 26;
+        g2 &= 0x03ff_ffff;
+        let mut g3 = h3.wrapping_add(c);
+        c = g3 >> 26;
+        g3 &= 0x03ff_ffff;
+        let g4 = h4.wrapping_add(c).wrapping_sub(1 << 26);
+
+        let mask = (g4 >> 31).wrapping_sub(1);
+        h0 = (h0 & !mask) | (g0 & mask);
+        h1 = (h1 & !mask) | (g1 & mask);
+        h2 = (h2 & !mask) | (g2 & mask);
+        h3 = (h3 & !mask) | (g3 & mask);
+        h4 = (h4 & !mask) | (g4 & mask);
+
+        let h0 = h0 | (h1 << 26);
+        let h1 = (h1 >> 6) | (h2 << 20);
+        let h2 = (h2 >> 12) | (h3 << 14);
+        let h3 = (h3 >> 18) | (h4 << 8);
+
+        let mut f = h0 as u64 + self.pad[0] as u64;
+        mac[0..4].copy_from_slice(&(f as u32).to_le_bytes());
+        f = h1 as u64 + self.pad[1] as u64 + (f >> 32);
+        mac[4..8].copy_from_slice(&(f as u32).to_le_bytes());
+        f = h2 as u64 + self.pad[2] as u64 + (f >> 32);
+        mac[8..12].copy_from_slice(&(f as u32).to_le_bytes());
+        f = h3 as u64 + self.pad[3] as u64 + (f >> 32);
+        mac[12..16].copy_from_slice(&(f as u32).to_le_bytes());
+    }
+}
+
+impl Drop for Poly1305 {
+    fn drop(&mut self) {
+        self.r.fill(0);
+        self.h.fill(0);
+        self.pad.fill(0);
+        self.buffer.fill(0);
+    }
+}
+
+#[inline]
+fn le32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+pub struct Aad<A>(A);
+
+impl<A: AsRef<[u8]>> Aad<A> {
+    #[inline]
+    pub fn from(aad: A) -> Aad<A> {
+        Aad(aad)
+    }
+}
+
+impl<A> AsRef<[u8]> for Aad<A>
+where
+    A: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl Aad<[u8; 0]> {
+    pub fn empty() -> Aad<[u8; 0]> {
+        Self::from([])
+    }
+}
+
+impl Default for Aad<[u8; 0]> {
+    fn default() -> Aad<[u8; 0]> {
+        Aad::empty()
+    }
+}
+
+impl<A> Clone for Aad<A>
+where
+    A: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Aad<A> {
+        Self(self.0.clone())
+    }
+}
+
+impl<A> Copy for Aad<A> where A: Copy {}
+
+impl<A> core::fmt::Debug for Aad<A>
+where
+    A: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Aad").field(&self.0).finish()
+    }
+}
+
+impl<A> PartialEq for Aad<A>
+where
+    A: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+impl<A> Eq for Aad<A> where A: Eq {}
+
+impl<A> ConstTimeEq for Aad<A>
+where
+    A: ConstTimeEq + BytewiseEquality + Eq,
+{
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+unsafe impl<A: ContiguousMemory> ContiguousMemory for Aad<A> {}
+
+unsafe impl<A: BytewiseEquality> BytewiseEquality for Aad<A> {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Nonce([u8; CHACHA20_NONCE_SIZE]);
+
+impl Nonce {
+    pub fn new() -> Nonce {
+        let mut nonce = [0_u8; CHACHA20_NONCE_SIZE];
+        crate::rand(&mut nonce);
+        Nonce(nonce)
+    }
+
+    #[inline]
+    pub fn zeroed() -> Nonce {
+        Nonce([0_u8; CHACHA20_NONCE_SIZE])
+    }
+}
+
+impl Default for Nonce {
+    #[inline]
+    fn default() -> Nonce {
+        Nonce::new()
+    }
+}
+
+impl AsRef<[u8; CHACHA20_NONCE_SIZE]> for Nonce {
+    #[inline]
+    fn as_ref(&self) -> &[u8; CHACHA20_NONCE_SIZE] {
+        &self.0
+    }
+}
+
+impl From<[u8; CHACHA20_NONCE_SIZE]> for Nonce {
+    #[inline]
+    fn from(nonce: [u8; CHACHA20_NONCE_SIZE]) -> Nonce {
+        Nonce(nonce)
+    }
+}
+
+impl From<&[u8; CHACHA20_NONCE_SIZE]> for Nonce {
+    #[inline]
+    fn from(nonce: &[u8; CHACHA20_NONCE_SIZE]) -> Nonce {
+        Nonce(*nonce)
+    }
+}
+
+impl TryFrom<&[u8]> for Nonce {
+    type Error = TryFromSliceError;
+
+    fn try_from(nonce: &[u8]) -> Result<Nonce, TryFromSliceError> {
+        let nonce: &[u8; CHACHA20_NONCE_SIZE] = nonce.try_into()?;
+        Ok(Nonce(*nonce))
+    }
+}
+
+unsafe impl ContiguousMemory for Nonce {}
+
+unsafe impl BytewiseEquality for Nonce {}
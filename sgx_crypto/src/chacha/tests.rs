@@ -0,0 +1,82 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::poly1305::{Aad, ChaCha20Poly1305, Nonce};
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::Key256bit;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// RFC 8439, section 2.8.2.
+const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+const NONCE: &str = "070000004041424344454647";
+const AAD: &str = "50515253c0c1c2c3c4c5c6c7";
+const CIPHERTEXT: &str = "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+                          3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+                          92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+                          3ff4def08e4b7a9de576d26586cec64b6116";
+const TAG: &str = "1ae10b594f09e26a7e902ecbd0600691";
+
+fn key() -> Key256bit {
+    let mut key = Key256bit::default();
+    for (i, b) in key.iter_mut().enumerate() {
+        *b = 0x80 + i as u8;
+    }
+    key
+}
+
+fn nonce() -> Nonce {
+    Nonce::try_from(decode(NONCE).as_slice()).unwrap()
+}
+
+#[test_case]
+fn chacha20_poly1305_encrypt() {
+    let aad = decode(AAD);
+    let mut aead = ChaCha20Poly1305::new(&key(), nonce(), Aad::from(aad.as_slice())).unwrap();
+
+    let mut ciphertext = vec![0_u8; PLAINTEXT.len()];
+    let mac = aead.encrypt(PLAINTEXT, &mut ciphertext).unwrap();
+    assert_eq!(ciphertext, decode(CIPHERTEXT));
+    assert_eq!(mac.as_slice(), decode(TAG).as_slice());
+}
+
+#[test_case]
+fn chacha20_poly1305_decrypt() {
+    let aad = decode(AAD);
+    let ciphertext = decode(CIPHERTEXT);
+    let mut mac = [0_u8; 16];
+    mac.copy_from_slice(&decode(TAG));
+
+    let mut aead = ChaCha20Poly1305::new(&key(), nonce(), Aad::from(aad.as_slice())).unwrap();
+    let mut plaintext = vec![0_u8; ciphertext.len()];
+    aead.decrypt(&ciphertext, &mut plaintext, &mac).unwrap();
+    assert_eq!(plaintext, PLAINTEXT);
+
+    mac[0] ^= 1;
+    let mut aead = ChaCha20Poly1305::new(&key(), nonce(), Aad::from(aad.as_slice())).unwrap();
+    assert_eq!(
+        aead.decrypt(&ciphertext, &mut plaintext, &mac),
+        Err(SgxStatus::MacMismatch)
+    );
+}
@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::kdf::HkdfSha256;
+use core::convert::TryFrom;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{Key128bit, Key192bit, Key256bit, Mac128bit};
+
+/// Authenticated encryption with associated data and a detached 128-bit tag.
+///
+/// The operations are one-shot and take the key, nonce and aad on every call,
+/// so code which selects the cipher at runtime only needs to be generic over
+/// `C: Aead`. The type parameter of the implementing cipher (the aad type of
+/// its inherent API) is irrelevant here, e.g. `AesGcm<[u8; 0]>` and
+/// `AesGcm<&[u8]>` behave the same.
+pub trait Aead {
+    type Key;
+    type Nonce: Copy + for<'a> TryFrom<&'a [u8]>;
+
+    const NONCE_SIZE: usize;
+
+    /// Identifies the cipher and its key size in persisted formats, so that
+    /// data protected with one cipher is not opened with another. The high
+    /// byte is the algorithm and the low byte the key size in bytes.
+    const ID: u16;
+
+    fn zeroed_nonce() -> Self::Nonce;
+
+    fn encrypt_detached(
+        key: &Self::Key,
+        nonce: Self::Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit>;
+
+    fn decrypt_detached(
+        key: &Self::Key,
+        nonce: Self::Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult;
+
+    fn encrypt_in_place_detached(
+        key: &Self::Key,
+        nonce: Self::Nonce,
+        aad: &[u8],
+        in_out: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        let mut dst = vec![0_u8; in_out.len()];
+        let mac = Self::encrypt_detached(key, nonce, aad, in_out, dst.as_mut_slice())?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(mac)
+    }
+
+    fn decrypt_in_place_detached(
+        key: &Self::Key,
+        nonce: Self::Nonce,
+        aad: &[u8],
+        in_out: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        let mut dst = vec![0_u8; in_out.len()];
+        Self::decrypt_detached(key, nonce, aad, in_out, dst.as_mut_slice(), mac)?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(())
+    }
+
+    fn mac(key: &Self::Key, nonce: Self::Nonce, aad: &[u8]) -> SgxResult<Mac128bit> {
+        Self::encrypt_detached(key, nonce, aad, &[], &mut [])
+    }
+
+    fn verify_mac(key: &Self::Key, nonce: Self::Nonce, aad: &[u8], mac: &Mac128bit) -> SgxResult {
+        Self::decrypt_detached(key, nonce, aad, &[], &mut [], mac)
+    }
+
+    fn nonce_from_slice(nonce: &[u8]) -> SgxResult<Self::Nonce> {
+        Self::Nonce::try_from(nonce).map_err(|_| SgxStatus::InvalidParameter)
+    }
+}
+
+/// A key that an `Aead` can be keyed with from a 128-bit key.
+///
+/// SGX seal keys and protected file node keys are 128-bit, so sgx_tseal and
+/// sgx_protected_fs take any `C: Aead` whose key implements this. A 128-bit
+/// key is used as it is. A longer key is expanded from the 128-bit key with
+/// HKDF-SHA256, so it still carries no more than 128 bits of security.
+pub trait FromKey128bit: Default + AsMut<[u8]> {
+    fn from_key128bit(key: &Key128bit) -> SgxResult<Self> {
+        let mut okm = Self::default();
+        let len = okm.as_mut().len();
+        if len == key.len() {
+            okm.as_mut().copy_from_slice(key);
+        } else {
+            let info = [b"sgx_crypto aead key".as_slice(), &[len as u8]].concat();
+            HkdfSha256::derive(&[], key, &info, okm.as_mut())?;
+        }
+        Ok(okm)
+    }
+}
+
+impl FromKey128bit for Key128bit {}
+impl FromKey128bit for Key192bit {}
+impl FromKey128bit for Key256bit {}
+
+/// Unauthenticated block cipher modes (CTR, CBC).
+///
+/// They give no integrity, so sgx_tseal and sgx_protected_fs only take an
+/// `Aead`.
+pub trait BlockCipherMode: Sized {
+    type Key;
+    type Iv: Copy + for<'a> TryFrom<&'a [u8]>;
+
+    fn new(key: &Self::Key, iv: Self::Iv) -> Self;
+
+    fn new_from_slice(key: &Self::Key, iv: &[u8]) -> SgxResult<Self> {
+        let iv = Self::Iv::try_from(iv).map_err(|_| SgxStatus::InvalidParameter)?;
+        Ok(Self::new(key, iv))
+    }
+
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult;
+
+    fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult;
+
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult;
+
+    fn decrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult;
+}
@@ -0,0 +1,374 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::edwards::EdwardsPoint;
+use super::scalar;
+use crate::sha::Sha512;
+use core::convert::{From, TryFrom, TryInto};
+use core::fmt;
+use core::mem;
+use core::slice;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::memeq::ConstTimeEq;
+
+#[cfg(any(feature = "tserialize", feature = "userialize"))]
+use sgx_serialize::{Deserialize, Serialize};
+
+pub const ED25519_KEY_SIZE: usize = 32;
+pub const ED25519_SIGNATURE_SIZE: usize = 64;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Ed25519KeyPair {
+    private: Ed25519PrivateKey,
+    public: Ed25519PublicKey,
+}
+
+impl Ed25519KeyPair {
+    pub fn create() -> SgxResult<Ed25519KeyPair> {
+        let private = Ed25519PrivateKey::create();
+        let public = private.export_public_key()?;
+        Ok(Ed25519KeyPair { private, public })
+    }
+
+    // The public key is derived from the seed again: signing with a public
+    // key that does not belong to the seed leaks the secret scalar.
+    #[inline]
+    pub fn sign<T: ?Sized>(&self, data: &T) -> SgxResult<Ed25519Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private.sign(data)
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> Ed25519PrivateKey {
+        self.private
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        self.public
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.private.clear();
+        self.public.clear();
+    }
+}
+
+// The private key is the 32-byte seed of RFC 8032.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Ed25519PrivateKey([u8; ED25519_KEY_SIZE]);
+
+impl Ed25519PrivateKey {
+    pub fn create() -> Ed25519PrivateKey {
+        let mut key = Ed25519PrivateKey::default();
+        crate::rand(&mut key.0);
+        key
+    }
+
+    pub fn sign<T: ?Sized>(&self, data: &T) -> SgxResult<Ed25519Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let public = self.export_public_key()?;
+        self.sign_with_public_key(as_bytes(data)?, &public)
+    }
+
+    pub fn export_public_key(&self) -> SgxResult<Ed25519PublicKey> {
+        let (mut a, mut prefix) = self.expand()?;
+        let public = EdwardsPoint::mul_base(&a).compress();
+
+        a.fill(0);
+        prefix.fill(0);
+        Ok(Ed25519PublicKey(public))
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> [u8; ED25519_KEY_SIZE] {
+        self.0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.fill(0);
+    }
+
+    fn sign_with_public_key(
+        &self,
+        data: &[u8],
+        public: &Ed25519PublicKey,
+    ) -> SgxResult<Ed25519Signature> {
+        let (mut a, mut prefix) = self.expand()?;
+
+        let mut r = scalar::reduce(&sha512(&[&prefix, data])?);
+        let big_r = EdwardsPoint::mul_base(&r).compress();
+        let k = scalar::reduce(&sha512(&[&big_r, &public.0, data])?);
+        let s = scalar::mul_add(&k, &a, &r);
+
+        a.fill(0);
+        prefix.fill(0);
+        r.fill(0);
+
+        let mut signature = Ed25519Signature::default();
+        signature.0[..32].copy_from_slice(&big_r);
+        signature.0[32..].copy_from_slice(&s);
+        Ok(signature)
+    }
+
+    // Returns the clamped secret scalar and the nonce prefix.
+    fn expand(&self) -> SgxResult<([u8; 32], [u8; 32])> {
+        let mut h = Sha512::digest(&self.0)?;
+
+        let mut a: [u8; 32] = h.hash[..32].try_into().unwrap();
+        a[0] &= 248;
+        a[31] &= 127;
+        a[31] |= 64;
+        let prefix: [u8; 32] = h.hash[32..].try_into().unwrap();
+
+        h.hash.fill(0);
+        Ok((a, prefix))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Ed25519PublicKey([u8; ED25519_KEY_SIZE]);
+
+impl Ed25519PublicKey {
+    pub fn verify<T: ?Sized>(&self, data: &T, signature: &Ed25519Signature) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        let data = as_bytes(data)?;
+
+        let a = match EdwardsPoint::decompress(&self.0) {
+            Some(a) => a,
+            None => return Ok(false),
+        };
+        let big_r: [u8; 32] = signature.0[..32].try_into().unwrap();
+        let s: [u8; 32] = signature.0[32..].try_into().unwrap();
+        if !scalar::is_canonical(&s) {
+            return Ok(false);
+        }
+
+        let k = scalar::reduce(&sha512(&[&big_r, &self.0, data])?);
+        let check = EdwardsPoint::mul_base(&s).add(&a.mul(&k).neg());
+        Ok(check.compress().ct_eq(&big_r))
+    }
+
+    pub fn check_point(&self) -> SgxResult<bool> {
+        Ok(EdwardsPoint::decompress(&self.0).is_some())
+    }
+
+    #[inline]
+    pub fn from_private_key(key: &Ed25519PrivateKey) -> SgxResult<Ed25519PublicKey> {
+        key.export_public_key()
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> [u8; ED25519_KEY_SIZE] {
+        self.0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.fill(0);
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Ed25519Signature([u8; ED25519_SIGNATURE_SIZE]);
+
+impl Ed25519Signature {
+    #[inline]
+    pub fn signature(&self) -> [u8; ED25519_SIGNATURE_SIZE] {
+        self.0
+    }
+}
+
+impl Default for Ed25519Signature {
+    fn default() -> Ed25519Signature {
+        Ed25519Signature([0_u8; ED25519_SIGNATURE_SIZE])
+    }
+}
+
+impl fmt::Debug for Ed25519Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ed25519Signature")
+            .field(&&self.0[..])
+            .finish()
+    }
+}
+
+fn as_bytes<T: ?Sized + ContiguousMemory>(data: &T) -> SgxResult<&[u8]> {
+    let size = mem::size_of_val(data);
+    ensure!(size < i32::MAX as usize, SgxStatus::InvalidParameter);
+    Ok(unsafe { slice::from_raw_parts((data as *const T).cast::<u8>(), size) })
+}
+
+fn sha512(parts: &[&[u8]]) -> SgxResult<[u8; 64]> {
+    let mut sha = Sha512::new()?;
+    for part in parts.iter().filter(|part| !part.is_empty()) {
+        sha.update(*part)?;
+    }
+    Ok(sha.finalize()?.hash)
+}
+
+// A key pair is only built from a public key that belongs to the private key.
+impl TryFrom<(Ed25519PrivateKey, Ed25519PublicKey)> for Ed25519KeyPair {
+    type Error = SgxStatus;
+
+    fn try_from(key_pair: (Ed25519PrivateKey, Ed25519PublicKey)) -> SgxResult<Ed25519KeyPair> {
+        let (private, public) = key_pair;
+        ensure!(
+            private.export_public_key()?.ct_eq(&public),
+            SgxStatus::InvalidParameter
+        );
+        Ok(Ed25519KeyPair { private, public })
+    }
+}
+
+impl From<Ed25519KeyPair> for (Ed25519PrivateKey, Ed25519PublicKey) {
+    fn from(key_pair: Ed25519KeyPair) -> (Ed25519PrivateKey, Ed25519PublicKey) {
+        (key_pair.private, key_pair.public)
+    }
+}
+
+impl From<&Ed25519KeyPair> for (Ed25519PrivateKey, Ed25519PublicKey) {
+    fn from(key_pair: &Ed25519KeyPair) -> (Ed25519PrivateKey, Ed25519PublicKey) {
+        (key_pair.private, key_pair.public)
+    }
+}
+
+impl From<Ed25519KeyPair> for [u8; ED25519_KEY_SIZE * 2] {
+    #[inline]
+    fn from(key: Ed25519KeyPair) -> [u8; ED25519_KEY_SIZE * 2] {
+        From::<&Ed25519KeyPair>::from(&key)
+    }
+}
+
+impl From<&Ed25519KeyPair> for [u8; ED25519_KEY_SIZE * 2] {
+    #[inline]
+    fn from(key: &Ed25519KeyPair) -> [u8; ED25519_KEY_SIZE * 2] {
+        let mut array = [0_u8; ED25519_KEY_SIZE * 2];
+        array[..ED25519_KEY_SIZE].copy_from_slice(key.private.as_ref());
+        array[ED25519_KEY_SIZE..].copy_from_slice(key.public.as_ref());
+        array
+    }
+}
+
+impl TryFrom<[u8; ED25519_KEY_SIZE * 2]> for Ed25519KeyPair {
+    type Error = SgxStatus;
+
+    #[inline]
+    fn try_from(key: [u8; ED25519_KEY_SIZE * 2]) -> SgxResult<Ed25519KeyPair> {
+        TryFrom::<&[u8; ED25519_KEY_SIZE * 2]>::try_from(&key)
+    }
+}
+
+impl TryFrom<&[u8; ED25519_KEY_SIZE * 2]> for Ed25519KeyPair {
+    type Error = SgxStatus;
+
+    #[inline]
+    fn try_from(key: &[u8; ED25519_KEY_SIZE * 2]) -> SgxResult<Ed25519KeyPair> {
+        let array: &[u8; ED25519_KEY_SIZE] = key[..ED25519_KEY_SIZE].try_into().unwrap();
+        let private_key = Ed25519PrivateKey::from(array);
+
+        let array: &[u8; ED25519_KEY_SIZE] = key[ED25519_KEY_SIZE..].try_into().unwrap();
+        let public_key = Ed25519PublicKey::from(array);
+
+        Ed25519KeyPair::try_from((private_key, public_key))
+    }
+}
+
+macro_rules! impl_bytes_conversion {
+    ($($t:ident, $size:expr;)*) => {$(
+        impl AsRef<[u8; $size]> for $t {
+            #[inline]
+            fn as_ref(&self) -> &[u8; $size] {
+                &self.0
+            }
+        }
+
+        impl From<$t> for [u8; $size] {
+            #[inline]
+            fn from(key: $t) -> [u8; $size] {
+                key.0
+            }
+        }
+
+        impl From<&$t> for [u8; $size] {
+            #[inline]
+            fn from(key: &$t) -> [u8; $size] {
+                key.0
+            }
+        }
+
+        impl From<[u8; $size]> for $t {
+            #[inline]
+            fn from(key: [u8; $size]) -> $t {
+                $t(key)
+            }
+        }
+
+        impl From<&[u8; $size]> for $t {
+            #[inline]
+            fn from(key: &[u8; $size]) -> $t {
+                $t(*key)
+            }
+        }
+
+        impl ConstTimeEq<$t> for $t {
+            #[inline]
+            fn ct_eq(&self, other: &$t) -> bool {
+                self.0.ct_eq(&other.0)
+            }
+        }
+
+        unsafe impl ContiguousMemory for $t {}
+
+        unsafe impl BytewiseEquality for $t {}
+    )*}
+}
+
+impl_bytes_conversion! {
+    Ed25519PrivateKey, ED25519_KEY_SIZE;
+    Ed25519PublicKey, ED25519_KEY_SIZE;
+    Ed25519Signature, ED25519_SIGNATURE_SIZE;
+}
+
+unsafe impl ContiguousMemory for Ed25519KeyPair {}
+
+unsafe impl BytewiseEquality for Ed25519KeyPair {}
@@ -0,0 +1,213 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::field::FieldElement;
+
+// Points on the twisted Edwards curve -x^2 + y^2 = 1 + d x^2 y^2 in
+// extended coordinates (X : Y : Z : T), x = X/Z, y = Y/Z, x y = T/Z.
+
+const EDWARDS_D: FieldElement = FieldElement([
+    929955233495203,
+    466365720129213,
+    1662059464998953,
+    2033849074728123,
+    1442794654840575,
+]);
+
+const EDWARDS_D2: FieldElement = FieldElement([
+    1859910466990425,
+    932731440258426,
+    1072319116312658,
+    1815898335770999,
+    633789495995903,
+]);
+
+const SQRT_M1: FieldElement = FieldElement([
+    1718705420411056,
+    234908883556509,
+    2233514472574048,
+    2117202627021982,
+    765476049583133,
+]);
+
+const BASEPOINT: EdwardsPoint = EdwardsPoint {
+    x: FieldElement([
+        1738742601995546,
+        1146398526822698,
+        2070867633025821,
+        562264141797630,
+        587772402128613,
+    ]),
+    y: FieldElement([
+        1801439850948184,
+        1351079888211148,
+        450359962737049,
+        900719925474099,
+        1801439850948198,
+    ]),
+    z: FieldElement::ONE,
+    t: FieldElement([
+        1841354044333475,
+        16398895984059,
+        755974180946558,
+        900171276175154,
+        1821297809914039,
+    ]),
+};
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct EdwardsPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement,
+}
+
+impl EdwardsPoint {
+    pub const IDENTITY: EdwardsPoint = EdwardsPoint {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ONE,
+        t: FieldElement::ZERO,
+    };
+
+    // RFC 8032, section 5.1.3. Non-canonical encodings are rejected.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+        let sign = bytes[31] >> 7;
+        let y = FieldElement::from_bytes(bytes);
+
+        let mut canonical = *bytes;
+        canonical[31] &= 0x7f;
+        if y.to_bytes() != canonical {
+            return None;
+        }
+
+        let yy = y.square();
+        let u = yy.sub(&FieldElement::ONE);
+        let v = yy.mul(&EDWARDS_D).add(&FieldElement::ONE);
+
+        let v3 = v.square().mul(&v);
+        let v7 = v3.square().mul(&v);
+        let mut x = u.mul(&v3).mul(&u.mul(&v7).pow_p58());
+
+        let vxx = v.mul(&x.square());
+        if !vxx.ct_eq(&u) {
+            if vxx.ct_eq(&u.neg()) {
+                x = x.mul(&SQRT_M1);
+            } else {
+                return None;
+            }
+        }
+
+        if x.is_zero() && sign == 1 {
+            return None;
+        }
+        if x.is_negative() as u8 != sign {
+            x = x.neg();
+        }
+
+        Some(EdwardsPoint {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t: x.mul(&y),
+        })
+    }
+
+    pub fn compress(&self) -> [u8; 32] {
+        let zinv = self.z.invert();
+        let x = self.x.mul(&zinv);
+        let y = self.y.mul(&zinv);
+
+        let mut s = y.to_bytes();
+        s[31] ^= (x.is_negative() as u8) << 7;
+        s
+    }
+
+    pub fn add(&self, rhs: &EdwardsPoint) -> EdwardsPoint {
+        let a = self.y.sub(&self.x).mul(&rhs.y.sub(&rhs.x));
+        let b = self.y.add(&self.x).mul(&rhs.y.add(&rhs.x));
+        let c = self.t.mul(&EDWARDS_D2).mul(&rhs.t);
+        let d = self.z.add(&self.z).mul(&rhs.z);
+        let e = b.sub(&a);
+        let f = d.sub(&c);
+        let g = d.add(&c);
+        let h = b.add(&a);
+
+        EdwardsPoint {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+
+    pub fn double(&self) -> EdwardsPoint {
+        let a = self.x.square();
+        let b = self.y.square();
+        let zz = self.z.square();
+        let c = zz.add(&zz);
+        let h = a.add(&b);
+        let e = h.sub(&self.x.add(&self.y).square());
+        let g = a.sub(&b);
+        let f = c.add(&g);
+
+        EdwardsPoint {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+
+    #[inline]
+    pub fn neg(&self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: self.x.neg(),
+            y: self.y,
+            z: self.z,
+            t: self.t.neg(),
+        }
+    }
+
+    // Constant-time double-and-add over all 256 bits of a little-endian scalar.
+    pub fn mul(&self, scalar: &[u8; 32]) -> EdwardsPoint {
+        let mut q = EdwardsPoint::IDENTITY;
+        for i in (0..256).rev() {
+            q = q.double();
+            let r = q.add(self);
+            let bit = ((scalar[i / 8] >> (i % 8)) & 1) as u64;
+            q = EdwardsPoint::conditional_select(&q, &r, bit);
+        }
+        q
+    }
+
+    #[inline]
+    pub fn mul_base(scalar: &[u8; 32]) -> EdwardsPoint {
+        BASEPOINT.mul(scalar)
+    }
+
+    #[inline]
+    fn conditional_select(a: &EdwardsPoint, b: &EdwardsPoint, choice: u64) -> EdwardsPoint {
+        EdwardsPoint {
+            x: FieldElement::conditional_select(&a.x, &b.x, choice),
+            y: FieldElement::conditional_select(&a.y, &b.y, choice),
+            z: FieldElement::conditional_select(&a.z, &b.z, choice),
+            t: FieldElement::conditional_select(&a.t, &b.t, choice),
+        }
+    }
+}
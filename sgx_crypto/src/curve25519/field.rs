@@ -0,0 +1,248 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// Arithmetic in GF(2^255 - 19), five 51-bit limbs.
+
+const MASK51: u64 = (1 << 51) - 1;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FieldElement(pub(crate) [u64; 5]);
+
+impl FieldElement {
+    pub const ZERO: FieldElement = FieldElement([0, 0, 0, 0, 0]);
+    pub const ONE: FieldElement = FieldElement([1, 0, 0, 0, 0]);
+
+    // p - 2
+    const INVERT_EXP: [u8; 32] = [
+        0xeb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0x7f,
+    ];
+    // (p - 5) / 8
+    const SQRT_EXP: [u8; 32] = [
+        0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0x0f,
+    ];
+
+    #[inline]
+    pub fn from_u64(v: u64) -> FieldElement {
+        FieldElement::reduce([v, 0, 0, 0, 0])
+    }
+
+    // The top bit is ignored.
+    pub fn from_bytes(bytes: &[u8; 32]) -> FieldElement {
+        let load = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        FieldElement([
+            load(0) & MASK51,
+            (load(6) >> 3) & MASK51,
+            (load(12) >> 6) & MASK51,
+            (load(19) >> 1) & MASK51,
+            (load(24) >> 12) & MASK51,
+        ])
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        let mut l = FieldElement::reduce(self.0).0;
+
+        let mut q = (l[0] + 19) >> 51;
+        q = (l[1] + q) >> 51;
+        q = (l[2] + q) >> 51;
+        q = (l[3] + q) >> 51;
+        q = (l[4] + q) >> 51;
+
+        l[0] += 19 * q;
+        l[1] += l[0] >> 51;
+        l[0] &= MASK51;
+        l[2] += l[1] >> 51;
+        l[1] &= MASK51;
+        l[3] += l[2] >> 51;
+        l[2] &= MASK51;
+        l[4] += l[3] >> 51;
+        l[3] &= MASK51;
+        l[4] &= MASK51;
+
+        let mut s = [0_u8; 32];
+        let packed = [
+            l[0] | (l[1] << 51),
+            (l[1] >> 13) | (l[2] << 38),
+            (l[2] >> 26) | (l[3] << 25),
+            (l[3] >> 39) | (l[4] << 12),
+        ];
+        for (chunk, v) in s.chunks_exact_mut(8).zip(packed) {
+            chunk.copy_from_slice(&v.to_le_bytes());
+        }
+        s
+    }
+
+    #[inline]
+    fn reduce(mut l: [u64; 5]) -> FieldElement {
+        let c0 = l[0] >> 51;
+        let c1 = l[1] >> 51;
+        let c2 = l[2] >> 51;
+        let c3 = l[3] >> 51;
+        let c4 = l[4] >> 51;
+
+        l[0] &= MASK51;
+        l[1] &= MASK51;
+        l[2] &= MASK51;
+        l[3] &= MASK51;
+        l[4] &= MASK51;
+
+        l[0] += c4 * 19;
+        l[1] += c0;
+        l[2] += c1;
+        l[3] += c2;
+        l[4] += c3;
+        FieldElement(l)
+    }
+
+    #[inline]
+    pub fn add(&self, rhs: &FieldElement) -> FieldElement {
+        let (a, b) = (&self.0, &rhs.0);
+        FieldElement::reduce([
+            a[0] + b[0],
+            a[1] + b[1],
+            a[2] + b[2],
+            a[3] + b[3],
+            a[4] + b[4],
+        ])
+    }
+
+    #[inline]
+    pub fn sub(&self, rhs: &FieldElement) -> FieldElement {
+        // add 16 * p to avoid underflow
+        let (a, b) = (&self.0, &rhs.0);
+        FieldElement::reduce([
+            (a[0] + 36028797018963664) - b[0],
+            (a[1] + 36028797018963952) - b[1],
+            (a[2] + 36028797018963952) - b[2],
+            (a[3] + 36028797018963952) - b[3],
+            (a[4] + 36028797018963952) - b[4],
+        ])
+    }
+
+    #[inline]
+    pub fn neg(&self) -> FieldElement {
+        FieldElement::ZERO.sub(self)
+    }
+
+    pub fn mul(&self, rhs: &FieldElement) -> FieldElement {
+        #[inline(always)]
+        fn m(x: u64, y: u64) -> u128 {
+            (x as u128) * (y as u128)
+        }
+
+        let (a, b) = (&self.0, &rhs.0);
+        let b1_19 = b[1] * 19;
+        let b2_19 = b[2] * 19;
+        let b3_19 = b[3] * 19;
+        let b4_19 = b[4] * 19;
+
+        let c0 = m(a[0], b[0]) + m(a[4], b1_19) + m(a[3], b2_19) + m(a[2], b3_19) + m(a[1], b4_19);
+        let mut c1 =
+            m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2_19) + m(a[3], b3_19) + m(a[2], b4_19);
+        let mut c2 =
+            m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3_19) + m(a[3], b4_19);
+        let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4_19);
+        let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+
+        let mut l = [0_u64; 5];
+        c1 += c0 >> 51;
+        l[0] = (c0 as u64) & MASK51;
+        c2 += c1 >> 51;
+        l[1] = (c1 as u64) & MASK51;
+        c3 += c2 >> 51;
+        l[2] = (c2 as u64) & MASK51;
+        c4 += c3 >> 51;
+        l[3] = (c3 as u64) & MASK51;
+        let carry = (c4 >> 51) as u64;
+        l[4] = (c4 as u64) & MASK51;
+
+        l[0] += carry * 19;
+        l[1] += l[0] >> 51;
+        l[0] &= MASK51;
+        FieldElement(l)
+    }
+
+    #[inline]
+    pub fn square(&self) -> FieldElement {
+        self.mul(self)
+    }
+
+    // The exponent is public, so a plain square-and-multiply is fine here.
+    fn pow(&self, exp: &[u8; 32]) -> FieldElement {
+        let mut r = FieldElement::ONE;
+        for i in (0..256).rev() {
+            r = r.square();
+            if (exp[i / 8] >> (i % 8)) & 1 == 1 {
+                r = r.mul(self);
+            }
+        }
+        r
+    }
+
+    #[inline]
+    pub fn invert(&self) -> FieldElement {
+        self.pow(&FieldElement::INVERT_EXP)
+    }
+
+    #[inline]
+    pub fn pow_p58(&self) -> FieldElement {
+        self.pow(&FieldElement::SQRT_EXP)
+    }
+
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.ct_eq(&FieldElement::ZERO)
+    }
+
+    pub fn ct_eq(&self, other: &FieldElement) -> bool {
+        let a = self.to_bytes();
+        let b = other.to_bytes();
+        a.iter()
+            .zip(b.iter())
+            .fold(0_u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+    }
+
+    // `choice` must be 0 or 1.
+    #[inline]
+    pub fn conditional_swap(a: &mut FieldElement, b: &mut FieldElement, choice: u64) {
+        let mask = 0_u64.wrapping_sub(choice);
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+
+    #[inline]
+    pub fn conditional_select(a: &FieldElement, b: &FieldElement, choice: u64) -> FieldElement {
+        let mask = 0_u64.wrapping_sub(choice);
+        let mut r = *a;
+        for (x, y) in r.0.iter_mut().zip(b.0.iter()) {
+            *x ^= mask & (*x ^ *y);
+        }
+        r
+    }
+}
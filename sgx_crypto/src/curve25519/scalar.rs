@@ -0,0 +1,113 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+// Arithmetic modulo the group order
+// L = 2^252 + 27742317777372353535851937790883648493.
+
+const L: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0x0000000000000000,
+    0x1000000000000000,
+];
+
+#[inline]
+fn load(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+#[inline]
+fn to_bytes(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut s = [0_u8; 32];
+    for (chunk, v) in s.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&v.to_le_bytes());
+    }
+    s
+}
+
+// r - L, and whether it borrowed.
+#[inline]
+fn sub_l(r: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut t = [0_u64; 4];
+    let mut borrow = 0_u64;
+    for i in 0..4 {
+        let (d1, b1) = r[i].overflowing_sub(L[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow);
+        t[i] = d2;
+        borrow = (b1 | b2) as u64;
+    }
+    (t, borrow)
+}
+
+// Constant-time bitwise reduction of a 512-bit little-endian integer.
+fn reduce_wide(x: &[u64; 8]) -> [u8; 32] {
+    let mut r = [0_u64; 4];
+    for i in (0..512).rev() {
+        let bit = (x[i / 64] >> (i % 64)) & 1;
+        r[3] = (r[3] << 1) | (r[2] >> 63);
+        r[2] = (r[2] << 1) | (r[1] >> 63);
+        r[1] = (r[1] << 1) | (r[0] >> 63);
+        r[0] = (r[0] << 1) | bit;
+
+        let (t, borrow) = sub_l(&r);
+        let mask = borrow.wrapping_sub(1);
+        for j in 0..4 {
+            r[j] = (t[j] & mask) | (r[j] & !mask);
+        }
+    }
+    to_bytes(&r)
+}
+
+pub(crate) fn reduce(bytes: &[u8; 64]) -> [u8; 32] {
+    let mut x = [0_u64; 8];
+    for (limb, chunk) in x.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = load(chunk);
+    }
+    reduce_wide(&x)
+}
+
+// (a * b + c) mod L
+pub(crate) fn mul_add(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let a: [u64; 4] = core::array::from_fn(|i| load(&a[i * 8..i * 8 + 8]));
+    let b: [u64; 4] = core::array::from_fn(|i| load(&b[i * 8..i * 8 + 8]));
+    let c: [u64; 4] = core::array::from_fn(|i| load(&c[i * 8..i * 8 + 8]));
+
+    let mut x = [0_u64; 8];
+    for i in 0..4 {
+        let mut carry = 0_u128;
+        for j in 0..4 {
+            let t = (a[i] as u128) * (b[j] as u128) + (x[i + j] as u128) + carry;
+            x[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        x[i + 4] = carry as u64;
+    }
+
+    let mut carry = 0_u128;
+    for (i, limb) in x.iter_mut().enumerate() {
+        let t = (*limb as u128) + (c.get(i).copied().unwrap_or(0) as u128) + carry;
+        *limb = t as u64;
+        carry = t >> 64;
+    }
+    reduce_wide(&x)
+}
+
+pub(crate) fn is_canonical(bytes: &[u8; 32]) -> bool {
+    let r: [u64; 4] = core::array::from_fn(|i| load(&bytes[i * 8..i * 8 + 8]));
+    let (_, borrow) = sub_l(&r);
+    borrow == 1
+}
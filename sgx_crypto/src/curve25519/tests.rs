@@ -0,0 +1,160 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::ed25519::{Ed25519KeyPair, Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+use super::x25519::{X25519PrivateKey, X25519PublicKey};
+use alloc::vec::Vec;
+use core::convert::{TryFrom, TryInto};
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn decode32(s: &str) -> [u8; 32] {
+    decode(s).try_into().unwrap()
+}
+
+// RFC 8032, section 7.1, tests 1 to 3: (secret key, public key, message, signature)
+const ED25519: [(&str, &str, &str, &str); 3] = [
+    (
+        "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+        "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        "",
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+         5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+    ),
+    (
+        "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+        "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+        "72",
+        "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+         085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+    ),
+    (
+        "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+        "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+        "af82",
+        "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac\
+         18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+    ),
+];
+
+#[test_case]
+fn ed25519_rfc8032() {
+    for (secret, public, message, signature) in ED25519 {
+        let private = Ed25519PrivateKey::from(decode32(secret));
+        let public = Ed25519PublicKey::from(decode32(public));
+        let message = decode(message);
+
+        assert_eq!(private.export_public_key().unwrap(), public);
+
+        let key_pair = Ed25519KeyPair::try_from((private, public)).unwrap();
+        let sig = key_pair.sign(message.as_slice()).unwrap();
+        assert_eq!(sig.signature().to_vec(), decode(signature));
+        assert!(public.verify(message.as_slice(), &sig).unwrap());
+
+        let mut bad = sig.signature();
+        bad[0] ^= 1;
+        assert!(!public
+            .verify(message.as_slice(), &Ed25519Signature::from(bad))
+            .unwrap());
+    }
+}
+
+#[test_case]
+fn ed25519_key_pair_mismatch() {
+    let private = Ed25519PrivateKey::from(decode32(ED25519[0].0));
+    let other = Ed25519PublicKey::from(decode32(ED25519[1].1));
+    assert_eq!(
+        Ed25519KeyPair::try_from((private, other)),
+        Err(SgxStatus::InvalidParameter)
+    );
+
+    let mut bytes = [0_u8; 64];
+    bytes[..32].copy_from_slice(&decode(ED25519[0].0));
+    bytes[32..].copy_from_slice(&decode(ED25519[1].1));
+    assert_eq!(
+        Ed25519KeyPair::try_from(&bytes),
+        Err(SgxStatus::InvalidParameter)
+    );
+
+    bytes[32..].copy_from_slice(&decode(ED25519[0].1));
+    let key_pair = Ed25519KeyPair::try_from(bytes).unwrap();
+    assert_eq!(<[u8; 64]>::from(key_pair), bytes);
+}
+
+// RFC 7748, section 5.2: (scalar, u-coordinate, output)
+const X25519: [(&str, &str, &str); 2] = [
+    (
+        "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+        "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+        "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+    ),
+    (
+        "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+        "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+        "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+    ),
+];
+
+#[test_case]
+fn x25519_rfc7748() {
+    for (scalar, u, output) in X25519 {
+        let private = X25519PrivateKey::from(decode32(scalar));
+        let shared = private
+            .shared_key(&X25519PublicKey::from(decode32(u)))
+            .unwrap();
+        assert_eq!(shared.as_ref().to_vec(), decode(output));
+    }
+}
+
+// RFC 7748, section 6.1
+#[test_case]
+fn x25519_diffie_hellman() {
+    let alice = X25519PrivateKey::from(decode32(
+        "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+    ));
+    let bob = X25519PrivateKey::from(decode32(
+        "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+    ));
+
+    let alice_public = alice.export_public_key().unwrap();
+    let bob_public = bob.export_public_key().unwrap();
+    assert_eq!(
+        alice_public.public_key().to_vec(),
+        decode("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+    );
+    assert_eq!(
+        bob_public.public_key().to_vec(),
+        decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+    );
+
+    let shared = decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+    let alice_shared = alice.shared_key(&bob_public).unwrap();
+    let bob_shared = bob.shared_key(&alice_public).unwrap();
+    assert_eq!(alice_shared.as_ref().to_vec(), shared);
+    assert_eq!(bob_shared.as_ref().to_vec(), shared);
+
+    // a low-order point gives an all-zero secret
+    let zero = X25519PublicKey::from([0_u8; 32]);
+    assert!(alice.shared_key(&zero).is_err());
+}
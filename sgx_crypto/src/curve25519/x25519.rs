@@ -0,0 +1,296 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::field::FieldElement;
+use crate::ecc::EcShareKey;
+use core::convert::From;
+use core::convert::TryInto;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::memeq::ConstTimeEq;
+
+#[cfg(any(feature = "tserialize", feature = "userialize"))]
+use sgx_serialize::{Deserialize, Serialize};
+
+pub const X25519_KEY_SIZE: usize = 32;
+
+const BASEPOINT: [u8; X25519_KEY_SIZE] = [
+    9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct X25519KeyPair {
+    private: X25519PrivateKey,
+    public: X25519PublicKey,
+}
+
+impl X25519KeyPair {
+    pub fn create() -> SgxResult<X25519KeyPair> {
+        let private = X25519PrivateKey::create();
+        let public = private.export_public_key()?;
+        Ok(X25519KeyPair { private, public })
+    }
+
+    #[inline]
+    pub fn shared_key(&self, peer_public_key: &X25519PublicKey) -> SgxResult<EcShareKey> {
+        self.private.shared_key(peer_public_key)
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> X25519PrivateKey {
+        self.private
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> X25519PublicKey {
+        self.public
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.private.clear();
+        self.public.clear();
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct X25519PrivateKey([u8; X25519_KEY_SIZE]);
+
+impl X25519PrivateKey {
+    pub fn create() -> X25519PrivateKey {
+        let mut key = X25519PrivateKey::default();
+        crate::rand(&mut key.0);
+        key
+    }
+
+    // Fails on a low-order peer key, which gives an all-zero secret
+    // (RFC 7748, section 6.1).
+    pub fn shared_key(&self, peer_public_key: &X25519PublicKey) -> SgxResult<EcShareKey> {
+        let shared = x25519(&self.0, &peer_public_key.0);
+        ensure!(
+            !shared.ct_eq(&[0_u8; X25519_KEY_SIZE]),
+            SgxStatus::InvalidParameter
+        );
+        Ok(EcShareKey::from(shared))
+    }
+
+    pub fn export_public_key(&self) -> SgxResult<X25519PublicKey> {
+        Ok(X25519PublicKey(x25519(&self.0, &BASEPOINT)))
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> [u8; X25519_KEY_SIZE] {
+        self.0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.fill(0);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct X25519PublicKey([u8; X25519_KEY_SIZE]);
+
+impl X25519PublicKey {
+    #[inline]
+    pub fn from_private_key(key: &X25519PrivateKey) -> SgxResult<X25519PublicKey> {
+        key.export_public_key()
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> [u8; X25519_KEY_SIZE] {
+        self.0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.fill(0);
+    }
+}
+
+// RFC 7748, section 5.
+fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    let x1 = FieldElement::from_bytes(u);
+    let a24 = FieldElement::from_u64(121665);
+    let mut x2 = FieldElement::ONE;
+    let mut z2 = FieldElement::ZERO;
+    let mut x3 = x1;
+    let mut z3 = FieldElement::ONE;
+    let mut swap = 0_u64;
+
+    for t in (0..255).rev() {
+        let bit = ((k[t / 8] >> (t % 8)) & 1) as u64;
+        swap ^= bit;
+        FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+        FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = x2.add(&z2);
+        let aa = a.square();
+        let b = x2.sub(&z2);
+        let bb = b.square();
+        let e = aa.sub(&bb);
+        let c = x3.add(&z3);
+        let d = x3.sub(&z3);
+        let da = d.mul(&a);
+        let cb = c.mul(&b);
+        x3 = da.add(&cb).square();
+        z3 = x1.mul(&da.sub(&cb).square());
+        x2 = aa.mul(&bb);
+        z2 = e.mul(&aa.add(&a24.mul(&e)));
+    }
+    FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+    FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+
+    k.fill(0);
+    x2.mul(&z2.invert()).to_bytes()
+}
+
+impl From<(X25519PrivateKey, X25519PublicKey)> for X25519KeyPair {
+    fn from(key_pair: (X25519PrivateKey, X25519PublicKey)) -> X25519KeyPair {
+        X25519KeyPair {
+            private: key_pair.0,
+            public: key_pair.1,
+        }
+    }
+}
+
+impl From<X25519KeyPair> for (X25519PrivateKey, X25519PublicKey) {
+    fn from(key_pair: X25519KeyPair) -> (X25519PrivateKey, X25519PublicKey) {
+        (key_pair.private, key_pair.public)
+    }
+}
+
+impl From<&X25519KeyPair> for (X25519PrivateKey, X25519PublicKey) {
+    fn from(key_pair: &X25519KeyPair) -> (X25519PrivateKey, X25519PublicKey) {
+        (key_pair.private, key_pair.public)
+    }
+}
+
+impl From<X25519KeyPair> for [u8; X25519_KEY_SIZE * 2] {
+    #[inline]
+    fn from(key: X25519KeyPair) -> [u8; X25519_KEY_SIZE * 2] {
+        From::<&X25519KeyPair>::from(&key)
+    }
+}
+
+impl From<&X25519KeyPair> for [u8; X25519_KEY_SIZE * 2] {
+    #[inline]
+    fn from(key: &X25519KeyPair) -> [u8; X25519_KEY_SIZE * 2] {
+        let mut array = [0_u8; X25519_KEY_SIZE * 2];
+        array[..X25519_KEY_SIZE].copy_from_slice(key.private.as_ref());
+        array[X25519_KEY_SIZE..].copy_from_slice(key.public.as_ref());
+        array
+    }
+}
+
+impl From<[u8; X25519_KEY_SIZE * 2]> for X25519KeyPair {
+    #[inline]
+    fn from(key: [u8; X25519_KEY_SIZE * 2]) -> X25519KeyPair {
+        From::<&[u8; X25519_KEY_SIZE * 2]>::from(&key)
+    }
+}
+
+impl From<&[u8; X25519_KEY_SIZE * 2]> for X25519KeyPair {
+    #[inline]
+    fn from(key: &[u8; X25519_KEY_SIZE * 2]) -> X25519KeyPair {
+        let array: &[u8; X25519_KEY_SIZE] = key[..X25519_KEY_SIZE].try_into().unwrap();
+        let private_key = X25519PrivateKey::from(array);
+
+        let array: &[u8; X25519_KEY_SIZE] = key[X25519_KEY_SIZE..].try_into().unwrap();
+        let public_key = X25519PublicKey::from(array);
+
+        X25519KeyPair::from((private_key, public_key))
+    }
+}
+
+macro_rules! impl_bytes_conversion {
+    ($($t:ident;)*) => {$(
+        impl AsRef<[u8; X25519_KEY_SIZE]> for $t {
+            #[inline]
+            fn as_ref(&self) -> &[u8; X25519_KEY_SIZE] {
+                &self.0
+            }
+        }
+
+        impl From<$t> for [u8; X25519_KEY_SIZE] {
+            #[inline]
+            fn from(key: $t) -> [u8; X25519_KEY_SIZE] {
+                key.0
+            }
+        }
+
+        impl From<&$t> for [u8; X25519_KEY_SIZE] {
+            #[inline]
+            fn from(key: &$t) -> [u8; X25519_KEY_SIZE] {
+                key.0
+            }
+        }
+
+        impl From<[u8; X25519_KEY_SIZE]> for $t {
+            #[inline]
+            fn from(key: [u8; X25519_KEY_SIZE]) -> $t {
+                $t(key)
+            }
+        }
+
+        impl From<&[u8; X25519_KEY_SIZE]> for $t {
+            #[inline]
+            fn from(key: &[u8; X25519_KEY_SIZE]) -> $t {
+                $t(*key)
+            }
+        }
+
+        impl ConstTimeEq<$t> for $t {
+            #[inline]
+            fn ct_eq(&self, other: &$t) -> bool {
+                self.0.ct_eq(&other.0)
+            }
+        }
+
+        unsafe impl ContiguousMemory for $t {}
+
+        unsafe impl BytewiseEquality for $t {}
+    )*}
+}
+
+impl_bytes_conversion! {
+    X25519PrivateKey;
+    X25519PublicKey;
+}
+
+unsafe impl ContiguousMemory for X25519KeyPair {}
+
+unsafe impl BytewiseEquality for X25519KeyPair {}
@@ -0,0 +1,341 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+pub const TAG_BOOLEAN: u8 = 0x01;
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_UTF8_STRING: u8 = 0x0c;
+pub const TAG_PRINTABLE_STRING: u8 = 0x13;
+pub const TAG_IA5_STRING: u8 = 0x16;
+pub const TAG_UTC_TIME: u8 = 0x17;
+pub const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+
+// Context-specific tags, [n] EXPLICIT / constructed and [n] IMPLICIT primitive.
+#[inline]
+pub const fn tag_context(n: u8) -> u8 {
+    0xa0 | n
+}
+
+#[inline]
+pub const fn tag_context_primitive(n: u8) -> u8 {
+    0x80 | n
+}
+
+// Object identifiers, as the content octets of the DER encoding.
+pub const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+pub const OID_PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+pub const OID_SECP384R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+pub const OID_SM2P256V1: &[u8] = &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x82, 0x2d];
+pub const OID_RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+pub const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+pub const OID_X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+
+#[derive(Clone, Debug, Default)]
+pub struct DerWriter {
+    buf: Vec<u8>,
+}
+
+impl DerWriter {
+    pub fn new() -> DerWriter {
+        DerWriter { buf: Vec::new() }
+    }
+
+    pub fn write_tlv(&mut self, tag: u8, value: &[u8]) {
+        self.buf.push(tag);
+        self.write_length(value.len());
+        self.buf.extend_from_slice(value);
+    }
+
+    // Appends an already encoded element.
+    pub fn write_raw(&mut self, der: &[u8]) {
+        self.buf.extend_from_slice(der);
+    }
+
+    // Writes a non-negative INTEGER from its big-endian magnitude.
+    pub fn write_integer(&mut self, be: &[u8]) {
+        let start = be.iter().position(|&b| b != 0).unwrap_or(be.len());
+        let be = &be[start..];
+        if be.is_empty() {
+            self.write_tlv(TAG_INTEGER, &[0]);
+        } else if be[0] & 0x80 != 0 {
+            self.buf.push(TAG_INTEGER);
+            self.write_length(be.len() + 1);
+            self.buf.push(0);
+            self.buf.extend_from_slice(be);
+        } else {
+            self.write_tlv(TAG_INTEGER, be);
+        }
+    }
+
+    #[inline]
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_integer(&value.to_be_bytes());
+    }
+
+    #[inline]
+    pub fn write_boolean(&mut self, value: bool) {
+        self.write_tlv(TAG_BOOLEAN, &[if value { 0xff } else { 0 }]);
+    }
+
+    #[inline]
+    pub fn write_octet_string(&mut self, value: &[u8]) {
+        self.write_tlv(TAG_OCTET_STRING, value);
+    }
+
+    // Writes a BIT STRING without unused bits.
+    pub fn write_bit_string(&mut self, value: &[u8]) {
+        self.buf.push(TAG_BIT_STRING);
+        self.write_length(value.len() + 1);
+        self.buf.push(0);
+        self.buf.extend_from_slice(value);
+    }
+
+    #[inline]
+    pub fn write_null(&mut self) {
+        self.write_tlv(TAG_NULL, &[]);
+    }
+
+    #[inline]
+    pub fn write_oid(&mut self, oid: &[u8]) {
+        self.write_tlv(TAG_OID, oid);
+    }
+
+    pub fn write_constructed<F>(&mut self, tag: u8, f: F)
+    where
+        F: FnOnce(&mut DerWriter),
+    {
+        let mut inner = DerWriter::new();
+        f(&mut inner);
+        self.write_tlv(tag, &inner.buf);
+        inner.clear();
+    }
+
+    #[inline]
+    pub fn write_sequence<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut DerWriter),
+    {
+        self.write_constructed(TAG_SEQUENCE, f)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        self.buf.as_slice()
+    }
+
+    #[inline]
+    pub fn into_vec(self) -> Vec<u8> {
+        self.buf
+    }
+
+    // Zeroes the buffer, for writers which held private key material.
+    pub fn clear(&mut self) {
+        self.buf.fill(0);
+        self.buf.clear();
+    }
+
+    fn write_length(&mut self, len: usize) {
+        if len < 0x80 {
+            self.buf.push(len as u8);
+        } else {
+            let bytes = len.to_be_bytes();
+            let start = bytes
+                .iter()
+                .position(|&b| b != 0)
+                .unwrap_or(bytes.len() - 1);
+            self.buf.push(0x80 | (bytes.len() - start) as u8);
+            self.buf.extend_from_slice(&bytes[start..]);
+        }
+    }
+}
+
+// A strict DER reader: definite minimal lengths and low tag numbers only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DerReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+    #[inline]
+    pub fn new(data: &'a [u8]) -> DerReader<'a> {
+        DerReader { data }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    #[inline]
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    pub fn finish(&self) -> SgxResult {
+        ensure!(self.data.is_empty(), SgxStatus::InvalidParameter);
+        Ok(())
+    }
+
+    // Reads one element, returning its tag and content octets.
+    pub fn read_tlv(&mut self) -> SgxResult<(u8, &'a [u8])> {
+        let (tag, header, len) = self.parse_header()?;
+        let value = &self.data[header..header + len];
+        self.data = &self.data[header + len..];
+        Ok((tag, value))
+    }
+
+    // Reads one element and returns its whole encoding.
+    pub fn read_raw(&mut self) -> SgxResult<&'a [u8]> {
+        let (_, header, len) = self.parse_header()?;
+        let raw = &self.data[..header + len];
+        self.data = &self.data[header + len..];
+        Ok(raw)
+    }
+
+    pub fn read(&mut self, tag: u8) -> SgxResult<&'a [u8]> {
+        let (actual, value) = self.read_tlv()?;
+        ensure!(actual == tag, SgxStatus::InvalidParameter);
+        Ok(value)
+    }
+
+    pub fn read_optional(&mut self, tag: u8) -> SgxResult<Option<&'a [u8]>> {
+        if self.peek_tag() == Some(tag) {
+            self.read(tag).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Reads a non-negative INTEGER and returns its big-endian magnitude
+    // without the sign octet.
+    pub fn read_integer(&mut self) -> SgxResult<&'a [u8]> {
+        let value = self.read(TAG_INTEGER)?;
+        ensure!(!value.is_empty(), SgxStatus::InvalidParameter);
+        ensure!(value[0] & 0x80 == 0, SgxStatus::InvalidParameter);
+        if value.len() > 1 {
+            ensure!(
+                value[0] != 0 || value[1] & 0x80 != 0,
+                SgxStatus::InvalidParameter
+            );
+        }
+        if value[0] == 0 {
+            Ok(&value[1..])
+        } else {
+            Ok(value)
+        }
+    }
+
+    pub fn read_u64(&mut self) -> SgxResult<u64> {
+        let value = self.read_integer()?;
+        ensure!(value.len() <= 8, SgxStatus::InvalidParameter);
+        Ok(value.iter().fold(0, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    pub fn read_boolean(&mut self) -> SgxResult<bool> {
+        match self.read(TAG_BOOLEAN)? {
+            [0] => Ok(false),
+            [0xff] => Ok(true),
+            _ => bail!(SgxStatus::InvalidParameter),
+        }
+    }
+
+    #[inline]
+    pub fn read_octet_string(&mut self) -> SgxResult<&'a [u8]> {
+        self.read(TAG_OCTET_STRING)
+    }
+
+    // Reads a BIT STRING which has no unused bits.
+    pub fn read_bit_string(&mut self) -> SgxResult<&'a [u8]> {
+        let value = self.read(TAG_BIT_STRING)?;
+        ensure!(
+            !value.is_empty() && value[0] == 0,
+            SgxStatus::InvalidParameter
+        );
+        Ok(&value[1..])
+    }
+
+    pub fn read_null(&mut self) -> SgxResult {
+        let value = self.read(TAG_NULL)?;
+        ensure!(value.is_empty(), SgxStatus::InvalidParameter);
+        Ok(())
+    }
+
+    pub fn read_oid(&mut self) -> SgxResult<&'a [u8]> {
+        let value = self.read(TAG_OID)?;
+        ensure!(!value.is_empty(), SgxStatus::InvalidParameter);
+        Ok(value)
+    }
+
+    #[inline]
+    pub fn read_constructed(&mut self, tag: u8) -> SgxResult<DerReader<'a>> {
+        self.read(tag).map(DerReader::new)
+    }
+
+    #[inline]
+    pub fn read_sequence(&mut self) -> SgxResult<DerReader<'a>> {
+        self.read_constructed(TAG_SEQUENCE)
+    }
+
+    fn parse_header(&self) -> SgxResult<(u8, usize, usize)> {
+        let data = self.data;
+        ensure!(data.len() >= 2, SgxStatus::InvalidParameter);
+
+        let tag = data[0];
+        ensure!(tag & 0x1f != 0x1f, SgxStatus::InvalidParameter);
+
+        let (header, len) = match data[1] {
+            len @ 0..=0x7f => (2, len as usize),
+            0x81..=0x84 => {
+                let n = (data[1] & 0x7f) as usize;
+                ensure!(data.len() >= 2 + n, SgxStatus::InvalidParameter);
+                ensure!(data[2] != 0, SgxStatus::InvalidParameter);
+                let len = data[2..2 + n]
+                    .iter()
+                    .fold(0_usize, |acc, &b| (acc << 8) | b as usize);
+                ensure!(len >= 0x80, SgxStatus::InvalidParameter);
+                (2 + n, len)
+            }
+            _ => bail!(SgxStatus::InvalidParameter),
+        };
+
+        ensure!(data.len() - header >= len, SgxStatus::InvalidParameter);
+        Ok((tag, header, len))
+    }
+}
@@ -0,0 +1,117 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{
+    AlgorithmIdentifier, AlgorithmParameters, DecodePrivateKey, DecodePublicKey, DerReader,
+    DerWriter, EncodePrivateKey, EncodePublicKey, PrivateKeyInfo, SubjectPublicKeyInfo,
+};
+use super::{OID_ED25519, OID_X25519};
+use crate::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, ED25519_KEY_SIZE};
+use crate::x25519::{X25519PrivateKey, X25519PublicKey, X25519_KEY_SIZE};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+// RFC 8410: the algorithm identifiers carry no parameters, the public key is
+// the raw encoding and the private key is an OCTET STRING inside PKCS#8.
+#[inline]
+fn algorithm(oid: &[u8]) -> AlgorithmIdentifier<'_> {
+    AlgorithmIdentifier {
+        oid,
+        parameters: AlgorithmParameters::Absent,
+    }
+}
+
+fn decode_private_key(der: &[u8], oid: &[u8], private: &mut [u8]) -> SgxResult {
+    let info = PrivateKeyInfo::from_der(der)?;
+    ensure!(
+        info.algorithm == algorithm(oid),
+        SgxStatus::InvalidParameter
+    );
+
+    let mut reader = DerReader::new(info.private_key);
+    let key = reader.read_octet_string()?;
+    reader.finish()?;
+    ensure!(key.len() == private.len(), SgxStatus::InvalidParameter);
+
+    private.copy_from_slice(key);
+    Ok(())
+}
+
+fn decode_public_key<'a>(der: &'a [u8], oid: &[u8]) -> SgxResult<&'a [u8]> {
+    let spki = SubjectPublicKeyInfo::from_der(der)?;
+    ensure!(
+        spki.algorithm == algorithm(oid),
+        SgxStatus::InvalidParameter
+    );
+    Ok(spki.subject_public_key)
+}
+
+macro_rules! impl_curve25519_der {
+    ($($private:ident, $public:ident, $oid:expr, $size:expr;)*) => {$(
+        impl EncodePublicKey for $public {
+            fn to_public_key_der(&self) -> SgxResult<Vec<u8>> {
+                let key: &[u8; $size] = self.as_ref();
+                let spki = SubjectPublicKeyInfo {
+                    algorithm: algorithm($oid),
+                    subject_public_key: key,
+                };
+                Ok(spki.to_der())
+            }
+        }
+
+        impl DecodePublicKey for $public {
+            fn from_public_key_der(der: &[u8]) -> SgxResult<$public> {
+                let key: &[u8; $size] = decode_public_key(der, $oid)?
+                    .try_into()
+                    .map_err(|_| SgxStatus::InvalidParameter)?;
+                Ok($public::from(key))
+            }
+        }
+
+        impl EncodePrivateKey for $private {
+            fn to_pkcs8_der(&self) -> SgxResult<Vec<u8>> {
+                let key: &[u8; $size] = self.as_ref();
+                let mut writer = DerWriter::new();
+                writer.write_octet_string(key);
+
+                let info = PrivateKeyInfo {
+                    algorithm: algorithm($oid),
+                    private_key: writer.as_slice(),
+                };
+                let der = info.to_der();
+                writer.clear();
+                Ok(der)
+            }
+        }
+
+        impl DecodePrivateKey for $private {
+            fn from_pkcs8_der(der: &[u8]) -> SgxResult<$private> {
+                let mut key = [0_u8; $size];
+                decode_private_key(der, $oid, &mut key)?;
+                let private = $private::from(&key);
+                key.fill(0);
+                Ok(private)
+            }
+        }
+    )*}
+}
+
+impl_curve25519_der! {
+    Ed25519PrivateKey, Ed25519PublicKey, OID_ED25519, ED25519_KEY_SIZE;
+    X25519PrivateKey, X25519PublicKey, OID_X25519, X25519_KEY_SIZE;
+}
@@ -0,0 +1,211 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{be_to_le, le_to_be, tag_context};
+use super::{
+    AlgorithmIdentifier, AlgorithmParameters, DecodePrivateKey, DecodePublicKey, DerReader,
+    DerSignature, DerWriter, EncodePrivateKey, EncodePublicKey, PrivateKeyInfo,
+    SubjectPublicKeyInfo,
+};
+use super::{OID_EC_PUBLIC_KEY, OID_PRIME256V1, OID_SECP384R1, OID_SM2P256V1};
+use crate::ecc::{EcPrivateKey, EcPublicKey, EcSignature};
+use crate::ecc::{P384PrivateKey, P384PublicKey, P384Signature};
+use crate::sm2::{Sm2PrivateKey, Sm2PublicKey, Sm2Signature};
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{ECP256_KEY_SIZE, ECP384_KEY_SIZE};
+
+const EC_PRIVATE_KEY_VERSION: u64 = 1;
+const UNCOMPRESSED_POINT: u8 = 0x04;
+
+#[inline]
+fn algorithm(curve: &[u8]) -> AlgorithmIdentifier<'_> {
+    AlgorithmIdentifier {
+        oid: OID_EC_PUBLIC_KEY,
+        parameters: AlgorithmParameters::Oid(curve),
+    }
+}
+
+// SGX public keys are gx || gy, each little-endian. The SEC1 uncompressed
+// point is 0x04 || X || Y, each big-endian.
+fn encode_point(key: &[u8]) -> Vec<u8> {
+    let (x, y) = key.split_at(key.len() / 2);
+    let mut point = Vec::with_capacity(key.len() + 1);
+    point.push(UNCOMPRESSED_POINT);
+    point.extend(x.iter().rev());
+    point.extend(y.iter().rev());
+    point
+}
+
+fn decode_point(point: &[u8], key: &mut [u8]) -> SgxResult {
+    ensure!(
+        point.len() == key.len() + 1 && point[0] == UNCOMPRESSED_POINT,
+        SgxStatus::InvalidParameter
+    );
+    let size = key.len() / 2;
+    let (x, y) = point[1..].split_at(size);
+    let (gx, gy) = key.split_at_mut(size);
+    be_to_le(x, gx)?;
+    be_to_le(y, gy)
+}
+
+// RFC 5915 ECPrivateKey. The curve is given by the PKCS#8 algorithm, so the
+// parameters field is left out, as OpenSSL does.
+fn encode_private_key(private: &[u8], public: &[u8]) -> Vec<u8> {
+    let mut scalar = le_to_be(private);
+    let point = encode_point(public);
+
+    let mut writer = DerWriter::new();
+    writer.write_sequence(|w| {
+        w.write_u64(EC_PRIVATE_KEY_VERSION);
+        w.write_octet_string(&scalar);
+        w.write_constructed(tag_context(1), |w| w.write_bit_string(&point));
+    });
+
+    scalar.fill(0);
+    writer.into_vec()
+}
+
+fn decode_private_key(der: &[u8], curve: &[u8], private: &mut [u8]) -> SgxResult {
+    let mut reader = DerReader::new(der);
+    let mut seq = reader.read_sequence()?;
+    reader.finish()?;
+
+    ensure!(
+        seq.read_u64()? == EC_PRIVATE_KEY_VERSION,
+        SgxStatus::InvalidParameter
+    );
+    let scalar = seq.read_octet_string()?;
+    ensure!(scalar.len() == private.len(), SgxStatus::InvalidParameter);
+    if let Some(params) = seq.read_optional(tag_context(0))? {
+        let mut params = DerReader::new(params);
+        ensure!(params.read_oid()? == curve, SgxStatus::InvalidParameter);
+        params.finish()?;
+    }
+    let _ = seq.read_optional(tag_context(1))?;
+    seq.finish()?;
+
+    ensure!(scalar.iter().any(|&b| b != 0), SgxStatus::InvalidParameter);
+    be_to_le(scalar, private)
+}
+
+// X9.62 Ecdsa-Sig-Value ::= SEQUENCE { r INTEGER, s INTEGER }
+fn encode_signature(signature: &[u8]) -> Vec<u8> {
+    let (r, s) = signature.split_at(signature.len() / 2);
+    let mut writer = DerWriter::new();
+    writer.write_sequence(|w| {
+        w.write_integer(&le_to_be(r));
+        w.write_integer(&le_to_be(s));
+    });
+    writer.into_vec()
+}
+
+fn decode_signature(der: &[u8], signature: &mut [u8]) -> SgxResult {
+    let mut reader = DerReader::new(der);
+    let mut seq = reader.read_sequence()?;
+    reader.finish()?;
+
+    let r = seq.read_integer()?;
+    let s = seq.read_integer()?;
+    seq.finish()?;
+
+    let (sig_r, sig_s) = signature.split_at_mut(signature.len() / 2);
+    be_to_le(r, sig_r)?;
+    be_to_le(s, sig_s)
+}
+
+macro_rules! impl_ec_der {
+    ($($private:ident, $public:ident, $signature:ident, $curve:expr, $size:expr;)*) => {$(
+        impl EncodePublicKey for $public {
+            fn to_public_key_der(&self) -> SgxResult<Vec<u8>> {
+                let key: &[u8; $size * 2] = self.as_ref();
+                let point = encode_point(key);
+                let spki = SubjectPublicKeyInfo {
+                    algorithm: algorithm($curve),
+                    subject_public_key: &point,
+                };
+                Ok(spki.to_der())
+            }
+        }
+
+        impl DecodePublicKey for $public {
+            fn from_public_key_der(der: &[u8]) -> SgxResult<$public> {
+                let spki = SubjectPublicKeyInfo::from_der(der)?;
+                ensure!(
+                    spki.algorithm == algorithm($curve),
+                    SgxStatus::InvalidParameter
+                );
+
+                let mut key = [0_u8; $size * 2];
+                decode_point(spki.subject_public_key, &mut key)?;
+                Ok($public::from(key))
+            }
+        }
+
+        impl EncodePrivateKey for $private {
+            fn to_pkcs8_der(&self) -> SgxResult<Vec<u8>> {
+                let public = self.export_public_key()?;
+                let private: &[u8; $size] = self.as_ref();
+                let public: &[u8; $size * 2] = public.as_ref();
+
+                let mut ec_key = encode_private_key(private, public);
+                let info = PrivateKeyInfo {
+                    algorithm: algorithm($curve),
+                    private_key: &ec_key,
+                };
+                let der = info.to_der();
+                ec_key.fill(0);
+                Ok(der)
+            }
+        }
+
+        impl DecodePrivateKey for $private {
+            fn from_pkcs8_der(der: &[u8]) -> SgxResult<$private> {
+                let info = PrivateKeyInfo::from_der(der)?;
+                ensure!(
+                    info.algorithm == algorithm($curve),
+                    SgxStatus::InvalidParameter
+                );
+
+                let mut key = [0_u8; $size];
+                decode_private_key(info.private_key, $curve, &mut key)?;
+                let private = $private::from(&key);
+                key.fill(0);
+                Ok(private)
+            }
+        }
+
+        impl DerSignature for $signature {
+            fn to_der(&self) -> Vec<u8> {
+                let signature: &[u8; $size * 2] = self.as_ref();
+                encode_signature(signature)
+            }
+
+            fn from_der(der: &[u8]) -> SgxResult<$signature> {
+                let mut signature = [0_u8; $size * 2];
+                decode_signature(der, &mut signature)?;
+                Ok($signature::from(signature))
+            }
+        }
+    )*}
+}
+
+impl_ec_der! {
+    EcPrivateKey, EcPublicKey, EcSignature, OID_PRIME256V1, ECP256_KEY_SIZE;
+    P384PrivateKey, P384PublicKey, P384Signature, OID_SECP384R1, ECP384_KEY_SIZE;
+    Sm2PrivateKey, Sm2PublicKey, Sm2Signature, OID_SM2P256V1, ECP256_KEY_SIZE;
+}
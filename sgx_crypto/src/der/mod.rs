@@ -0,0 +1,225 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+mod asn1;
+mod curve25519;
+mod ec;
+pub mod pem;
+pub(crate) mod rsa;
+
+pub use asn1::*;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+// SubjectPublicKeyInfo (RFC 5280) encoding of public keys.
+pub trait EncodePublicKey {
+    fn to_public_key_der(&self) -> SgxResult<Vec<u8>>;
+
+    fn to_public_key_pem(&self) -> SgxResult<String> {
+        let der = self.to_public_key_der()?;
+        Ok(pem::encode(pem::PUBLIC_KEY_LABEL, &der))
+    }
+}
+
+pub trait DecodePublicKey: Sized {
+    fn from_public_key_der(der: &[u8]) -> SgxResult<Self>;
+
+    fn from_public_key_pem(pem: &str) -> SgxResult<Self> {
+        let der = pem::decode_label(pem, pem::PUBLIC_KEY_LABEL)?;
+        Self::from_public_key_der(&der)
+    }
+}
+
+// PKCS#8 (RFC 5208) encoding of private keys. The returned buffers hold
+// secret material and should be zeroed by the caller after use.
+pub trait EncodePrivateKey {
+    fn to_pkcs8_der(&self) -> SgxResult<Vec<u8>>;
+
+    fn to_pkcs8_pem(&self) -> SgxResult<String> {
+        let mut der = self.to_pkcs8_der()?;
+        let pem = pem::encode(pem::PRIVATE_KEY_LABEL, &der);
+        der.fill(0);
+        Ok(pem)
+    }
+}
+
+pub trait DecodePrivateKey: Sized {
+    fn from_pkcs8_der(der: &[u8]) -> SgxResult<Self>;
+
+    fn from_pkcs8_pem(pem: &str) -> SgxResult<Self> {
+        let mut der = pem::decode_label(pem, pem::PRIVATE_KEY_LABEL)?;
+        let key = Self::from_pkcs8_der(&der);
+        der.fill(0);
+        key
+    }
+}
+
+// ASN.1 DER encoding of signatures, e.g. the X9.62 `Ecdsa-Sig-Value`.
+pub trait DerSignature: Sized {
+    fn to_der(&self) -> Vec<u8>;
+
+    fn from_der(der: &[u8]) -> SgxResult<Self>;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AlgorithmParameters<'a> {
+    Absent,
+    Null,
+    Oid(&'a [u8]),
+    // Any other parameters, as their whole DER encoding.
+    Other(&'a [u8]),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AlgorithmIdentifier<'a> {
+    pub oid: &'a [u8],
+    pub parameters: AlgorithmParameters<'a>,
+}
+
+impl<'a> AlgorithmIdentifier<'a> {
+    pub fn encode(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|w| {
+            w.write_oid(self.oid);
+            match self.parameters {
+                AlgorithmParameters::Absent => {}
+                AlgorithmParameters::Null => w.write_null(),
+                AlgorithmParameters::Oid(oid) => w.write_oid(oid),
+                AlgorithmParameters::Other(der) => w.write_raw(der),
+            }
+        });
+    }
+
+    pub fn decode(reader: &mut DerReader<'a>) -> SgxResult<AlgorithmIdentifier<'a>> {
+        let mut seq = reader.read_sequence()?;
+        let oid = seq.read_oid()?;
+        let parameters = match seq.peek_tag() {
+            None => AlgorithmParameters::Absent,
+            Some(TAG_NULL) => {
+                seq.read_null()?;
+                AlgorithmParameters::Null
+            }
+            Some(TAG_OID) => AlgorithmParameters::Oid(seq.read_oid()?),
+            Some(_) => AlgorithmParameters::Other(seq.read_raw()?),
+        };
+        seq.finish()?;
+        Ok(AlgorithmIdentifier { oid, parameters })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SubjectPublicKeyInfo<'a> {
+    pub algorithm: AlgorithmIdentifier<'a>,
+    pub subject_public_key: &'a [u8],
+}
+
+impl<'a> SubjectPublicKeyInfo<'a> {
+    pub fn encode(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|w| {
+            self.algorithm.encode(w);
+            w.write_bit_string(self.subject_public_key);
+        });
+    }
+
+    pub fn decode(reader: &mut DerReader<'a>) -> SgxResult<SubjectPublicKeyInfo<'a>> {
+        let mut seq = reader.read_sequence()?;
+        let algorithm = AlgorithmIdentifier::decode(&mut seq)?;
+        let subject_public_key = seq.read_bit_string()?;
+        seq.finish()?;
+        Ok(SubjectPublicKeyInfo {
+            algorithm,
+            subject_public_key,
+        })
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut writer = DerWriter::new();
+        self.encode(&mut writer);
+        writer.into_vec()
+    }
+
+    pub fn from_der(der: &'a [u8]) -> SgxResult<SubjectPublicKeyInfo<'a>> {
+        let mut reader = DerReader::new(der);
+        let spki = SubjectPublicKeyInfo::decode(&mut reader)?;
+        reader.finish()?;
+        Ok(spki)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PrivateKeyInfo<'a> {
+    pub algorithm: AlgorithmIdentifier<'a>,
+    pub private_key: &'a [u8],
+}
+
+impl<'a> PrivateKeyInfo<'a> {
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| {
+            w.write_u64(0);
+            self.algorithm.encode(w);
+            w.write_octet_string(self.private_key);
+        });
+        writer.into_vec()
+    }
+
+    // Accepts version 1 (RFC 5208) and version 2 (RFC 5958) structures;
+    // attributes and the optional public key are skipped.
+    pub fn from_der(der: &'a [u8]) -> SgxResult<PrivateKeyInfo<'a>> {
+        let mut reader = DerReader::new(der);
+        let mut seq = reader.read_sequence()?;
+        reader.finish()?;
+
+        let version = seq.read_u64()?;
+        ensure!(version <= 1, SgxStatus::InvalidParameter);
+        let algorithm = AlgorithmIdentifier::decode(&mut seq)?;
+        let private_key = seq.read_octet_string()?;
+        let _ = seq.read_optional(tag_context(0))?;
+        if version == 1 {
+            let _ = seq.read_optional(tag_context_primitive(1))?;
+        }
+        seq.finish()?;
+
+        Ok(PrivateKeyInfo {
+            algorithm,
+            private_key,
+        })
+    }
+}
+
+// SGX keeps big numbers little-endian, ASN.1 big-endian.
+pub(crate) fn le_to_be(le: &[u8]) -> Vec<u8> {
+    le.iter().rev().copied().collect()
+}
+
+// Converts a big-endian magnitude into a fixed-size little-endian buffer,
+// failing if it does not fit.
+pub(crate) fn be_to_le(be: &[u8], le: &mut [u8]) -> SgxResult {
+    let start = be.iter().position(|&b| b != 0).unwrap_or(be.len());
+    let be = &be[start..];
+    ensure!(be.len() <= le.len(), SgxStatus::InvalidParameter);
+
+    le.fill(0);
+    for (dst, src) in le.iter_mut().zip(be.iter().rev()) {
+        *dst = *src;
+    }
+    Ok(())
+}
@@ -0,0 +1,165 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+pub const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+pub const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+pub const CERTIFICATE_LABEL: &str = "CERTIFICATE";
+
+const LINE_WIDTH: usize = 64;
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// RFC 7468 textual encoding, with 64 column lines and LF line endings.
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let mut body = base64_encode(der);
+    let mut pem =
+        String::with_capacity(body.len() + body.len() / LINE_WIDTH + 2 * label.len() + 40);
+
+    pem.push_str("-----BEGIN ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        // base64 output is always ASCII
+        pem.push_str(core::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+    pem.push_str("-----END ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+
+    clear_string(&mut body);
+    pem
+}
+
+// Decodes the first PEM block of `pem`, returning its label and contents.
+// Text around the block and any whitespace inside it are ignored.
+pub fn decode(pem: &str) -> SgxResult<(String, Vec<u8>)> {
+    let begin = pem.find("-----BEGIN ").ok_or(SgxStatus::InvalidParameter)?;
+    let rest = &pem[begin + 11..];
+    let label_end = rest.find("-----").ok_or(SgxStatus::InvalidParameter)?;
+    let label = &rest[..label_end];
+    ensure!(
+        label
+            .bytes()
+            .all(|b| (0x21..=0x7e).contains(&b) || b == b' '),
+        SgxStatus::InvalidParameter
+    );
+
+    let rest = &rest[label_end + 5..];
+    let end = rest.find("-----END ").ok_or(SgxStatus::InvalidParameter)?;
+    let body = &rest[..end];
+    let trailer = &rest[end + 9..];
+    ensure!(
+        trailer.starts_with(label) && trailer[label.len()..].starts_with("-----"),
+        SgxStatus::InvalidParameter
+    );
+
+    let der = base64_decode(body)?;
+    Ok((String::from(label), der))
+}
+
+// Decodes a PEM block and checks that it carries the expected label.
+pub fn decode_label(pem: &str, label: &str) -> SgxResult<Vec<u8>> {
+    let (actual, mut der) = decode(pem)?;
+    if actual != label {
+        der.fill(0);
+        bail!(SgxStatus::InvalidParameter);
+    }
+    Ok(der)
+}
+
+pub(crate) fn clear_string(s: &mut String) {
+    // zeroing keeps the string valid UTF-8
+    unsafe { s.as_bytes_mut().fill(0) };
+    s.clear();
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = Vec::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32;
+        out.push(BASE64_CHARS[(n >> 18) as usize & 0x3f]);
+        out.push(BASE64_CHARS[(n >> 12) as usize & 0x3f]);
+        out.push(if chunk.len() > 1 {
+            BASE64_CHARS[(n >> 6) as usize & 0x3f]
+        } else {
+            b'='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_CHARS[n as usize & 0x3f]
+        } else {
+            b'='
+        });
+    }
+    // only ASCII characters were pushed
+    unsafe { String::from_utf8_unchecked(out) }
+}
+
+fn base64_decode(text: &str) -> SgxResult<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let mut acc = 0_u32;
+    let mut bits = 0;
+    let mut padding = 0;
+
+    for c in text.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => {
+                padding += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' | b'\n' => continue,
+            _ => bail!(SgxStatus::InvalidParameter),
+        };
+        // no data may follow the padding
+        ensure!(padding == 0, SgxStatus::InvalidParameter);
+
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    // the input must be a whole number of padded quanta, with zero filler bits
+    let quanta = out.len() % 3;
+    let valid = match (quanta, padding) {
+        (0, 0) => bits == 0,
+        (1, 2) => bits == 4 && acc == 0,
+        (2, 1) => bits == 2 && acc == 0,
+        _ => false,
+    };
+    if !valid {
+        out.fill(0);
+        bail!(SgxStatus::InvalidParameter);
+    }
+    Ok(out)
+}
@@ -0,0 +1,126 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{
+    be_to_le, le_to_be, AlgorithmIdentifier, AlgorithmParameters, DerReader, DerWriter,
+    PrivateKeyInfo, SubjectPublicKeyInfo, OID_RSA_ENCRYPTION,
+};
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+const RSA_PRIVATE_KEY_VERSION: u64 = 0;
+
+const ALGORITHM: AlgorithmIdentifier<'static> = AlgorithmIdentifier {
+    oid: OID_RSA_ENCRYPTION,
+    parameters: AlgorithmParameters::Null,
+};
+
+// Little-endian components of a two-prime RSA private key, in the order of
+// the PKCS#1 RSAPrivateKey structure.
+pub(crate) struct RsaPrivateComponents<'a> {
+    pub n: &'a [u8],
+    pub e: &'a [u8],
+    pub d: &'a [u8],
+    pub p: &'a [u8],
+    pub q: &'a [u8],
+    pub dmp1: &'a [u8],
+    pub dmq1: &'a [u8],
+    pub iqmp: &'a [u8],
+}
+
+pub(crate) struct RsaPrivateComponentsMut<'a> {
+    pub n: &'a mut [u8],
+    pub e: &'a mut [u8],
+    pub d: &'a mut [u8],
+    pub p: &'a mut [u8],
+    pub q: &'a mut [u8],
+    pub dmp1: &'a mut [u8],
+    pub dmq1: &'a mut [u8],
+    pub iqmp: &'a mut [u8],
+}
+
+// SubjectPublicKeyInfo wrapping the PKCS#1 RSAPublicKey { n, e }.
+pub(crate) fn encode_public_key(n: &[u8], e: &[u8]) -> Vec<u8> {
+    let mut writer = DerWriter::new();
+    writer.write_sequence(|w| {
+        w.write_integer(&le_to_be(n));
+        w.write_integer(&le_to_be(e));
+    });
+
+    let spki = SubjectPublicKeyInfo {
+        algorithm: ALGORITHM,
+        subject_public_key: writer.as_slice(),
+    };
+    spki.to_der()
+}
+
+pub(crate) fn decode_public_key(der: &[u8], n: &mut [u8], e: &mut [u8]) -> SgxResult {
+    let spki = SubjectPublicKeyInfo::from_der(der)?;
+    ensure!(spki.algorithm == ALGORITHM, SgxStatus::InvalidParameter);
+
+    let mut reader = DerReader::new(spki.subject_public_key);
+    let mut seq = reader.read_sequence()?;
+    reader.finish()?;
+
+    be_to_le(seq.read_integer()?, n)?;
+    be_to_le(seq.read_integer()?, e)?;
+    seq.finish()
+}
+
+// PKCS#8 wrapping the PKCS#1 RSAPrivateKey.
+pub(crate) fn encode_private_key(key: &RsaPrivateComponents<'_>) -> Vec<u8> {
+    let mut writer = DerWriter::new();
+    writer.write_sequence(|w| {
+        w.write_u64(RSA_PRIVATE_KEY_VERSION);
+        for component in [
+            key.n, key.e, key.d, key.p, key.q, key.dmp1, key.dmq1, key.iqmp,
+        ] {
+            let mut be = le_to_be(component);
+            w.write_integer(&be);
+            be.fill(0);
+        }
+    });
+
+    let info = PrivateKeyInfo {
+        algorithm: ALGORITHM,
+        private_key: writer.as_slice(),
+    };
+    let der = info.to_der();
+    writer.clear();
+    der
+}
+
+// Only two-prime keys are accepted.
+pub(crate) fn decode_private_key(der: &[u8], key: RsaPrivateComponentsMut<'_>) -> SgxResult {
+    let info = PrivateKeyInfo::from_der(der)?;
+    ensure!(info.algorithm == ALGORITHM, SgxStatus::InvalidParameter);
+
+    let mut reader = DerReader::new(info.private_key);
+    let mut seq = reader.read_sequence()?;
+    reader.finish()?;
+
+    ensure!(
+        seq.read_u64()? == RSA_PRIVATE_KEY_VERSION,
+        SgxStatus::InvalidParameter
+    );
+    for component in [
+        key.n, key.e, key.d, key.p, key.q, key.dmp1, key.dmq1, key.iqmp,
+    ] {
+        be_to_le(seq.read_integer()?, component)?;
+    }
+    seq.finish()
+}
@@ -0,0 +1,125 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{EcPrivateKey, EcPublicKey, EcShareKey, EcSignature};
+use super::{P384PrivateKey, P384PublicKey, P384ShareKey, P384Signature};
+use sgx_types::error::SgxResult;
+use sgx_types::marker::ContiguousMemory;
+use sgx_types::types::{Sha256Hash, Sha384Hash, ECP256_KEY_SIZE, ECP384_KEY_SIZE};
+
+// Common interface of the NIST prime curves, so that callers verifying
+// certificate chains or running ECDH can be generic over the curve size.
+// The key types keep their inherent API; `C::sign(&key, data)` and
+// `key.sign(data)` are the same operation.
+pub trait EcCurve {
+    type PrivateKey: Copy + Default;
+    type PublicKey: Copy + Default;
+    type ShareKey: Copy + Default;
+    type Signature: Copy + Default;
+    type Hash: Copy + Default;
+
+    const KEY_SIZE: usize;
+
+    fn create_key_pair() -> SgxResult<(Self::PrivateKey, Self::PublicKey)>;
+
+    fn export_public_key(key: &Self::PrivateKey) -> SgxResult<Self::PublicKey>;
+
+    fn sign<T: ?Sized + ContiguousMemory>(
+        key: &Self::PrivateKey,
+        data: &T,
+    ) -> SgxResult<Self::Signature>;
+
+    fn verify<T: ?Sized + ContiguousMemory>(
+        key: &Self::PublicKey,
+        data: &T,
+        signature: &Self::Signature,
+    ) -> SgxResult<bool>;
+
+    fn verify_hash(
+        key: &Self::PublicKey,
+        hash: &Self::Hash,
+        signature: &Self::Signature,
+    ) -> SgxResult<bool>;
+
+    fn shared_key(key: &Self::PrivateKey, peer_key: &Self::PublicKey) -> SgxResult<Self::ShareKey>;
+
+    fn check_point(key: &Self::PublicKey) -> SgxResult<bool>;
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NistP256;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NistP384;
+
+macro_rules! impl_ec_curve {
+    ($($curve:ty, $key_pair:ident, $private:ty, $public:ty, $share:ty, $sig:ty, $hash:ty, $size:expr;)*) => {$(
+        impl EcCurve for $curve {
+            type PrivateKey = $private;
+            type PublicKey = $public;
+            type ShareKey = $share;
+            type Signature = $sig;
+            type Hash = $hash;
+
+            const KEY_SIZE: usize = $size;
+
+            #[inline]
+            fn create_key_pair() -> SgxResult<($private, $public)> {
+                super::$key_pair::create().map(From::from)
+            }
+
+            #[inline]
+            fn export_public_key(key: &$private) -> SgxResult<$public> {
+                key.export_public_key()
+            }
+
+            #[inline]
+            fn sign<T: ?Sized + ContiguousMemory>(key: &$private, data: &T) -> SgxResult<$sig> {
+                key.sign(data)
+            }
+
+            #[inline]
+            fn verify<T: ?Sized + ContiguousMemory>(
+                key: &$public,
+                data: &T,
+                signature: &$sig,
+            ) -> SgxResult<bool> {
+                key.verify(data, signature)
+            }
+
+            #[inline]
+            fn verify_hash(key: &$public, hash: &$hash, signature: &$sig) -> SgxResult<bool> {
+                key.verify_hash(hash, signature)
+            }
+
+            #[inline]
+            fn shared_key(key: &$private, peer_key: &$public) -> SgxResult<$share> {
+                key.shared_key(peer_key)
+            }
+
+            #[inline]
+            fn check_point(key: &$public) -> SgxResult<bool> {
+                key.check_point()
+            }
+        }
+    )*}
+}
+
+impl_ec_curve! {
+    NistP256, EcKeyPair, EcPrivateKey, EcPublicKey, EcShareKey, EcSignature, Sha256Hash, ECP256_KEY_SIZE;
+    NistP384, P384KeyPair, P384PrivateKey, P384PublicKey, P384ShareKey, P384Signature, Sha384Hash, ECP384_KEY_SIZE;
+}
@@ -16,6 +16,7 @@
 // under the License..
 
 use crate::mac::AesCMac;
+use crate::sha::Sha256;
 use core::convert::From;
 use core::convert::TryInto;
 use core::mem;
@@ -33,6 +34,18 @@ use sgx_types::types::{
 #[cfg(any(feature = "tserialize", feature = "userialize"))]
 use sgx_serialize::{Deserialize, Serialize};
 
+mod curve;
+mod p384;
+mod rfc6979;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub use curve::*;
+pub use p384::*;
+
+pub(crate) use rfc6979::generate_nonce;
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(
     any(feature = "tserialize", feature = "userialize"),
@@ -137,6 +150,55 @@ impl EcPrivateKey {
         Ok(signature.into())
     }
 
+    /// Signs with the nonce derived from the key and the message as in
+    /// RFC 6979, rather than one drawn from the RNG. The same key and data
+    /// always give the same signature.
+    pub fn sign_deterministic<T: ?Sized>(&self, data: &T) -> SgxResult<EcSignature>
+    where
+        T: ContiguousMemory,
+    {
+        let hash = Sha256::digest(data)?;
+        self.sign_with_nonce(&hash, false)
+    }
+
+    /// As `sign_deterministic`, with RNG output also mixed into the nonce
+    /// (RFC 6979, section 3.6). Signatures are randomized, and the nonce stays
+    /// secret even if the RNG is weak.
+    pub fn sign_hedged<T: ?Sized>(&self, data: &T) -> SgxResult<EcSignature>
+    where
+        T: ContiguousMemory,
+    {
+        let hash = Sha256::digest(data)?;
+        self.sign_with_nonce(&hash, true)
+    }
+
+    fn sign_with_nonce(&self, hash: &Sha256Hash, hedged: bool) -> SgxResult<EcSignature> {
+        let mut k = generate_nonce::<Sha256>(&self.0.key.r, &Self::NISTP256, &hash.hash, hedged)?;
+        let mut nonce = Ec256PrivateKey::default();
+        nonce.r.copy_from_slice(&k);
+        k.fill(0);
+
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc256_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut signature = Ec256Signature::default();
+        let status = unsafe {
+            sgx_ecdsa_sign_with_nonce(
+                hash.hash.as_ptr(),
+                &self.0.key as *const Ec256PrivateKey,
+                &nonce as *const Ec256PrivateKey,
+                &mut signature as *mut Ec256Signature,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc256_close_context(handle) };
+        nonce.r.fill(0);
+
+        ensure!(status.is_success(), status);
+        Ok(signature.into())
+    }
+
     pub fn create_with_seed<H: AsRef<[u8]>>(hash_drg: H) -> SgxResult<EcPrivateKey> {
         let hash_drg = hash_drg.as_ref();
         if (hash_drg.is_empty()) || (hash_drg.len() > i32::MAX as usize) {
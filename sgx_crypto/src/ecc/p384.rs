@@ -0,0 +1,793 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::generate_nonce;
+use crate::mac::AesCMac;
+use crate::sha::Sha384;
+use core::convert::From;
+use core::convert::TryInto;
+use core::mem;
+use core::ptr;
+use sgx_crypto_sys::*;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::ECP384_KEY_SIZE;
+use sgx_types::types::{
+    AlignEc384PrivateKey, AlignEc384SharedKey, AlignKey128bit, Ec384PrivateKey, Ec384PublicKey,
+    Ec384SharedKey, Ec384Signature, EcResult, EccHandle, Key128bit, Sha384Hash,
+};
+
+#[cfg(any(feature = "tserialize", feature = "userialize"))]
+use sgx_serialize::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct P384KeyPair {
+    private: P384PrivateKey,
+    public: P384PublicKey,
+}
+
+impl P384KeyPair {
+    pub fn create() -> SgxResult<P384KeyPair> {
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut private = P384PrivateKey::default();
+        let mut public = P384PublicKey::default();
+        let status = unsafe {
+            sgx_ecc384_create_key_pair(
+                &mut private.0.key as *mut Ec384PrivateKey,
+                &mut public.0 as *mut Ec384PublicKey,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+
+        ensure!(status.is_success(), status);
+        Ok(P384KeyPair { private, public })
+    }
+
+    #[inline]
+    pub fn shared_key(&self, peer_public_key: &P384PublicKey) -> SgxResult<P384ShareKey> {
+        self.private.shared_key(peer_public_key)
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> P384PrivateKey {
+        self.private
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> P384PublicKey {
+        self.public
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.private.clear();
+        self.public.clear();
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct P384PrivateKey(AlignEc384PrivateKey);
+
+impl P384PrivateKey {
+    const NISTP384: [u8; ECP384_KEY_SIZE] = [
+        0x73, 0x29, 0xC5, 0xCC, 0x6A, 0x19, 0xEC, 0xEC, 0x7A, 0xA7, 0xB0, 0x48, 0xB2, 0x0D, 0x1A,
+        0x58, 0xDF, 0x2D, 0x37, 0xF4, 0x81, 0x4D, 0x63, 0xC7, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF,
+    ];
+
+    pub fn sign<T: ?Sized>(&self, data: &T) -> SgxResult<P384Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let size = mem::size_of_val(data);
+        ensure!(
+            (size > 0 && size < i32::MAX as usize),
+            SgxStatus::InvalidParameter
+        );
+
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut signature = Ec384Signature::default();
+        let status = unsafe {
+            sgx_ecdsa384_sign(
+                (data as *const T).cast(),
+                size as u32,
+                &self.0.key as *const Ec384PrivateKey,
+                &mut signature as *mut Ec384Signature,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+
+        ensure!(status.is_success(), status);
+        Ok(signature.into())
+    }
+
+    /// Signs with the nonce derived from the key and the message as in
+    /// RFC 6979, rather than one drawn from the RNG. The same key and data
+    /// always give the same signature.
+    pub fn sign_deterministic<T: ?Sized>(&self, data: &T) -> SgxResult<P384Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let hash = Sha384::digest(data)?;
+        self.sign_with_nonce(&hash, false)
+    }
+
+    /// As `sign_deterministic`, with RNG output also mixed into the nonce
+    /// (RFC 6979, section 3.6). Signatures are randomized, and the nonce stays
+    /// secret even if the RNG is weak.
+    pub fn sign_hedged<T: ?Sized>(&self, data: &T) -> SgxResult<P384Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let hash = Sha384::digest(data)?;
+        self.sign_with_nonce(&hash, true)
+    }
+
+    fn sign_with_nonce(&self, hash: &Sha384Hash, hedged: bool) -> SgxResult<P384Signature> {
+        let mut k = generate_nonce::<Sha384>(&self.0.key.r, &Self::NISTP384, &hash.hash, hedged)?;
+        let mut nonce = Ec384PrivateKey::default();
+        nonce.r.copy_from_slice(&k);
+        k.fill(0);
+
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut signature = Ec384Signature::default();
+        let status = unsafe {
+            sgx_ecdsa384_sign_with_nonce(
+                hash.hash.as_ptr(),
+                &self.0.key as *const Ec384PrivateKey,
+                &nonce as *const Ec384PrivateKey,
+                &mut signature as *mut Ec384Signature,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+        nonce.r.fill(0);
+
+        ensure!(status.is_success(), status);
+        Ok(signature.into())
+    }
+
+    pub fn shared_key(&self, peer_public_key: &P384PublicKey) -> SgxResult<P384ShareKey> {
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut shared_key = AlignEc384SharedKey::default();
+        let status = unsafe {
+            sgx_ecc384_compute_shared_dhkey(
+                &self.0.key as *const Ec384PrivateKey,
+                &peer_public_key.0 as *const Ec384PublicKey,
+                &mut shared_key.key as *mut Ec384SharedKey,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+
+        ensure!(status.is_success(), status);
+        Ok(From::from(shared_key))
+    }
+
+    pub fn export_public_key(&self) -> SgxResult<P384PublicKey> {
+        let mut public = P384PublicKey::default();
+        let status = unsafe {
+            sgx_ecc384_calculate_pub_from_priv(
+                &self.0.key as *const Ec384PrivateKey,
+                &mut public.0 as *mut Ec384PublicKey,
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(public)
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> Ec384PrivateKey {
+        self.0.key
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.as_mut().fill(0);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct P384PublicKey(Ec384PublicKey);
+
+impl P384PublicKey {
+    pub fn verify<T: ?Sized>(&self, data: &T, signature: &P384Signature) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        let size = mem::size_of_val(data);
+        ensure!(
+            (size > 0 && size < i32::MAX as usize),
+            SgxStatus::InvalidParameter
+        );
+
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut ec_result = EcResult::InvalidSignature;
+        let status = unsafe {
+            sgx_ecdsa384_verify(
+                (data as *const T).cast(),
+                size as u32,
+                &self.0 as *const Ec384PublicKey,
+                &signature.0 as *const Ec384Signature,
+                &mut ec_result as *mut EcResult as *mut u8,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+
+        ensure!(status.is_success(), status);
+        match ec_result {
+            EcResult::Valid => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
+    pub fn verify_hash(&self, hash: &Sha384Hash, signature: &P384Signature) -> SgxResult<bool> {
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut ec_result = EcResult::InvalidSignature;
+        let status = unsafe {
+            sgx_ecdsa384_verify_hash(
+                hash.as_ptr(),
+                &self.0 as *const Ec384PublicKey,
+                &signature.0 as *const Ec384Signature,
+                &mut ec_result as *mut EcResult as *mut u8,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+
+        ensure!(status.is_success(), status);
+        match ec_result {
+            EcResult::Valid => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
+    pub fn check_point(&self) -> SgxResult<bool> {
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_ecc384_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut valid: i32 = 0;
+        let status = unsafe {
+            sgx_ecc384_check_point(
+                &self.0 as *const Ec384PublicKey,
+                handle,
+                &mut valid as *mut i32,
+            )
+        };
+        let _ = unsafe { sgx_ecc384_close_context(handle) };
+
+        ensure!(status.is_success(), status);
+        if valid > 0 {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    #[inline]
+    pub fn from_private_key(key: &P384PrivateKey) -> SgxResult<P384PublicKey> {
+        key.export_public_key()
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> Ec384PublicKey {
+        self.0
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.as_mut().fill(0);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct P384ShareKey(AlignEc384SharedKey);
+
+impl P384ShareKey {
+    pub fn derive_key(&self, label: &[u8]) -> SgxResult<AlignKey128bit> {
+        ensure!(!label.is_empty(), SgxStatus::InvalidParameter);
+
+        let key = Key128bit::default();
+        let mut derive_key = AesCMac::cmac(&key, &self.0.key)?;
+
+        let derivation_len = label
+            .len()
+            .checked_add(4)
+            .ok_or(SgxStatus::InvalidParameter)?;
+        let mut derivation = vec![0_u8; label.len() + 4];
+        derivation[0] = 0x01;
+        derivation[1..derivation_len - 3].copy_from_slice(label);
+        derivation[derivation_len - 3..].copy_from_slice(&[0x00, 0x80, 0x00]);
+
+        let key = AesCMac::cmac_align(&derive_key, derivation.as_slice())
+            .map(|cmac| AlignKey128bit::from(cmac.mac))?;
+
+        derive_key.as_mut().fill(0);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn shared_key(&self) -> Ec384SharedKey {
+        self.0.key
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.as_mut().fill(0);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct P384Signature(Ec384Signature);
+
+impl P384Signature {
+    #[inline]
+    pub fn signature(&self) -> Ec384Signature {
+        self.0
+    }
+}
+
+impl From<(P384PrivateKey, P384PublicKey)> for P384KeyPair {
+    fn from(key_pair: (P384PrivateKey, P384PublicKey)) -> P384KeyPair {
+        P384KeyPair {
+            private: key_pair.0,
+            public: key_pair.1,
+        }
+    }
+}
+
+impl From<P384KeyPair> for (P384PrivateKey, P384PublicKey) {
+    fn from(key_pair: P384KeyPair) -> (P384PrivateKey, P384PublicKey) {
+        (key_pair.private, key_pair.public)
+    }
+}
+
+impl From<&P384KeyPair> for (P384PrivateKey, P384PublicKey) {
+    fn from(key_pair: &P384KeyPair) -> (P384PrivateKey, P384PublicKey) {
+        (key_pair.private, key_pair.public)
+    }
+}
+
+impl From<P384KeyPair> for [u8; ECP384_KEY_SIZE * 3] {
+    #[inline]
+    fn from(key: P384KeyPair) -> [u8; ECP384_KEY_SIZE * 3] {
+        From::<&P384KeyPair>::from(&key)
+    }
+}
+
+impl From<&P384KeyPair> for [u8; ECP384_KEY_SIZE * 3] {
+    #[inline]
+    fn from(key: &P384KeyPair) -> [u8; ECP384_KEY_SIZE * 3] {
+        let mut array = [0_u8; ECP384_KEY_SIZE * 3];
+        array[..ECP384_KEY_SIZE].copy_from_slice(&key.private.as_ref()[..]);
+        array[ECP384_KEY_SIZE..].copy_from_slice(&key.public.as_ref()[..]);
+        array
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE * 3]> for P384KeyPair {
+    #[inline]
+    fn from(key: [u8; ECP384_KEY_SIZE * 3]) -> P384KeyPair {
+        From::<&[u8; ECP384_KEY_SIZE * 3]>::from(&key)
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE * 3]> for P384KeyPair {
+    #[inline]
+    fn from(key: &[u8; ECP384_KEY_SIZE * 3]) -> P384KeyPair {
+        let array: &[u8; ECP384_KEY_SIZE] = key[..ECP384_KEY_SIZE].try_into().unwrap();
+        let private_key = P384PrivateKey::from(array);
+
+        let array: &[u8; ECP384_KEY_SIZE * 2] = key[ECP384_KEY_SIZE..].try_into().unwrap();
+        let public_key = P384PublicKey::from(array);
+
+        P384KeyPair::from((private_key, public_key))
+    }
+}
+
+impl From<AlignEc384PrivateKey> for P384PrivateKey {
+    #[inline]
+    fn from(key: AlignEc384PrivateKey) -> P384PrivateKey {
+        P384PrivateKey(key)
+    }
+}
+
+impl From<&AlignEc384PrivateKey> for P384PrivateKey {
+    #[inline]
+    fn from(key: &AlignEc384PrivateKey) -> P384PrivateKey {
+        P384PrivateKey(*key)
+    }
+}
+
+impl From<Ec384PrivateKey> for P384PrivateKey {
+    #[inline]
+    fn from(key: Ec384PrivateKey) -> P384PrivateKey {
+        P384PrivateKey(From::from(key))
+    }
+}
+
+impl From<&Ec384PrivateKey> for P384PrivateKey {
+    #[inline]
+    fn from(key: &Ec384PrivateKey) -> P384PrivateKey {
+        P384PrivateKey(From::from(key))
+    }
+}
+
+impl AsRef<[u8; ECP384_KEY_SIZE]> for P384PrivateKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8; ECP384_KEY_SIZE] {
+        self.0.as_ref()
+    }
+}
+
+impl From<P384PrivateKey> for [u8; ECP384_KEY_SIZE] {
+    #[inline]
+    fn from(key: P384PrivateKey) -> [u8; ECP384_KEY_SIZE] {
+        *key.as_ref()
+    }
+}
+
+impl From<&P384PrivateKey> for [u8; ECP384_KEY_SIZE] {
+    #[inline]
+    fn from(key: &P384PrivateKey) -> [u8; ECP384_KEY_SIZE] {
+        *key.as_ref()
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE]> for P384PrivateKey {
+    #[inline]
+    fn from(key: [u8; ECP384_KEY_SIZE]) -> P384PrivateKey {
+        P384PrivateKey(AlignEc384PrivateKey::from(key))
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE]> for P384PrivateKey {
+    #[inline]
+    fn from(key: &[u8; ECP384_KEY_SIZE]) -> P384PrivateKey {
+        P384PrivateKey(AlignEc384PrivateKey::from(key))
+    }
+}
+
+impl From<P384PrivateKey> for Ec384PrivateKey {
+    #[inline]
+    fn from(key: P384PrivateKey) -> Ec384PrivateKey {
+        key.0.key
+    }
+}
+
+impl From<&P384PrivateKey> for Ec384PrivateKey {
+    #[inline]
+    fn from(key: &P384PrivateKey) -> Ec384PrivateKey {
+        key.0.key
+    }
+}
+
+impl From<P384PrivateKey> for AlignEc384PrivateKey {
+    #[inline]
+    fn from(key: P384PrivateKey) -> AlignEc384PrivateKey {
+        key.0
+    }
+}
+
+impl From<&P384PrivateKey> for AlignEc384PrivateKey {
+    #[inline]
+    fn from(key: &P384PrivateKey) -> AlignEc384PrivateKey {
+        key.0
+    }
+}
+
+impl From<Ec384PublicKey> for P384PublicKey {
+    #[inline]
+    fn from(key: Ec384PublicKey) -> P384PublicKey {
+        P384PublicKey(key)
+    }
+}
+
+impl From<&Ec384PublicKey> for P384PublicKey {
+    #[inline]
+    fn from(key: &Ec384PublicKey) -> P384PublicKey {
+        P384PublicKey(*key)
+    }
+}
+
+impl AsRef<[u8; ECP384_KEY_SIZE * 2]> for P384PublicKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8; ECP384_KEY_SIZE * 2] {
+        self.0.as_ref()
+    }
+}
+
+impl From<P384PublicKey> for [u8; ECP384_KEY_SIZE * 2] {
+    #[inline]
+    fn from(key: P384PublicKey) -> [u8; ECP384_KEY_SIZE * 2] {
+        *key.as_ref()
+    }
+}
+
+impl From<&P384PublicKey> for [u8; ECP384_KEY_SIZE * 2] {
+    #[inline]
+    fn from(key: &P384PublicKey) -> [u8; ECP384_KEY_SIZE * 2] {
+        *key.as_ref()
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE * 2]> for P384PublicKey {
+    #[inline]
+    fn from(key: [u8; ECP384_KEY_SIZE * 2]) -> P384PublicKey {
+        P384PublicKey(Ec384PublicKey::from(key))
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE * 2]> for P384PublicKey {
+    #[inline]
+    fn from(key: &[u8; ECP384_KEY_SIZE * 2]) -> P384PublicKey {
+        P384PublicKey(Ec384PublicKey::from(key))
+    }
+}
+
+impl From<P384PublicKey> for Ec384PublicKey {
+    #[inline]
+    fn from(key: P384PublicKey) -> Ec384PublicKey {
+        key.0
+    }
+}
+
+impl From<&P384PublicKey> for Ec384PublicKey {
+    #[inline]
+    fn from(key: &P384PublicKey) -> Ec384PublicKey {
+        key.0
+    }
+}
+
+impl From<AlignEc384SharedKey> for P384ShareKey {
+    #[inline]
+    fn from(key: AlignEc384SharedKey) -> P384ShareKey {
+        P384ShareKey(key)
+    }
+}
+
+impl From<&AlignEc384SharedKey> for P384ShareKey {
+    #[inline]
+    fn from(key: &AlignEc384SharedKey) -> P384ShareKey {
+        P384ShareKey(*key)
+    }
+}
+
+impl From<Ec384SharedKey> for P384ShareKey {
+    #[inline]
+    fn from(key: Ec384SharedKey) -> P384ShareKey {
+        P384ShareKey(From::from(key))
+    }
+}
+
+impl From<&Ec384SharedKey> for P384ShareKey {
+    #[inline]
+    fn from(key: &Ec384SharedKey) -> P384ShareKey {
+        P384ShareKey(From::from(key))
+    }
+}
+
+impl AsRef<[u8; ECP384_KEY_SIZE]> for P384ShareKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8; ECP384_KEY_SIZE] {
+        self.0.as_ref()
+    }
+}
+
+impl From<P384ShareKey> for [u8; ECP384_KEY_SIZE] {
+    #[inline]
+    fn from(key: P384ShareKey) -> [u8; ECP384_KEY_SIZE] {
+        *key.as_ref()
+    }
+}
+
+impl From<&P384ShareKey> for [u8; ECP384_KEY_SIZE] {
+    #[inline]
+    fn from(key: &P384ShareKey) -> [u8; ECP384_KEY_SIZE] {
+        *key.as_ref()
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE]> for P384ShareKey {
+    #[inline]
+    fn from(key: [u8; ECP384_KEY_SIZE]) -> P384ShareKey {
+        P384ShareKey(AlignEc384SharedKey::from(key))
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE]> for P384ShareKey {
+    #[inline]
+    fn from(key: &[u8; ECP384_KEY_SIZE]) -> P384ShareKey {
+        P384ShareKey(AlignEc384SharedKey::from(key))
+    }
+}
+
+impl From<P384ShareKey> for Ec384SharedKey {
+    #[inline]
+    fn from(key: P384ShareKey) -> Ec384SharedKey {
+        key.0.key
+    }
+}
+
+impl From<&P384ShareKey> for Ec384SharedKey {
+    #[inline]
+    fn from(key: &P384ShareKey) -> Ec384SharedKey {
+        key.0.key
+    }
+}
+
+impl From<P384ShareKey> for AlignEc384SharedKey {
+    #[inline]
+    fn from(key: P384ShareKey) -> AlignEc384SharedKey {
+        key.0
+    }
+}
+
+impl From<Ec384Signature> for P384Signature {
+    #[inline]
+    fn from(signature: Ec384Signature) -> P384Signature {
+        P384Signature(signature)
+    }
+}
+
+impl From<&Ec384Signature> for P384Signature {
+    #[inline]
+    fn from(signature: &Ec384Signature) -> P384Signature {
+        P384Signature(*signature)
+    }
+}
+
+impl AsRef<[u8; ECP384_KEY_SIZE * 2]> for P384Signature {
+    #[inline]
+    fn as_ref(&self) -> &[u8; ECP384_KEY_SIZE * 2] {
+        self.0.as_ref()
+    }
+}
+
+impl From<P384Signature> for [u8; ECP384_KEY_SIZE * 2] {
+    #[inline]
+    fn from(signature: P384Signature) -> [u8; ECP384_KEY_SIZE * 2] {
+        *signature.as_ref()
+    }
+}
+
+impl From<&P384Signature> for [u8; ECP384_KEY_SIZE * 2] {
+    #[inline]
+    fn from(signature: &P384Signature) -> [u8; ECP384_KEY_SIZE * 2] {
+        *signature.as_ref()
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE * 2]> for P384Signature {
+    #[inline]
+    fn from(signature: [u8; ECP384_KEY_SIZE * 2]) -> P384Signature {
+        P384Signature(Ec384Signature::from(signature))
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE * 2]> for P384Signature {
+    #[inline]
+    fn from(signature: &[u8; ECP384_KEY_SIZE * 2]) -> P384Signature {
+        P384Signature(Ec384Signature::from(signature))
+    }
+}
+
+impl From<P384Signature> for Ec384Signature {
+    #[inline]
+    fn from(signature: P384Signature) -> Ec384Signature {
+        signature.0
+    }
+}
+
+impl From<&P384Signature> for Ec384Signature {
+    #[inline]
+    fn from(signature: &P384Signature) -> Ec384Signature {
+        signature.0
+    }
+}
+
+impl From<&P384ShareKey> for AlignEc384SharedKey {
+    #[inline]
+    fn from(key: &P384ShareKey) -> AlignEc384SharedKey {
+        key.0
+    }
+}
+
+impl ConstTimeEq<P384PrivateKey> for P384PrivateKey {
+    #[inline]
+    fn ct_eq(&self, other: &P384PrivateKey) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConstTimeEq<P384PublicKey> for P384PublicKey {
+    #[inline]
+    fn ct_eq(&self, other: &P384PublicKey) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConstTimeEq<P384ShareKey> for P384ShareKey {
+    #[inline]
+    fn ct_eq(&self, other: &P384ShareKey) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConstTimeEq<P384Signature> for P384Signature {
+    #[inline]
+    fn ct_eq(&self, other: &P384Signature) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+unsafe impl ContiguousMemory for P384KeyPair {}
+unsafe impl ContiguousMemory for P384PrivateKey {}
+unsafe impl ContiguousMemory for P384PublicKey {}
+unsafe impl ContiguousMemory for P384ShareKey {}
+unsafe impl ContiguousMemory for P384Signature {}
+
+unsafe impl BytewiseEquality for P384KeyPair {}
+unsafe impl BytewiseEquality for P384PrivateKey {}
+unsafe impl BytewiseEquality for P384PublicKey {}
+unsafe impl BytewiseEquality for P384ShareKey {}
+unsafe impl BytewiseEquality for P384Signature {}
@@ -0,0 +1,116 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::mac::HMacDigest;
+use crate::sha::Digest;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+// Per-signature nonce of RFC 6979, section 3.2, for curves whose order is as
+// long as the hash (P-256/SHA-256, P-384/SHA-384 and SM2/SM3), so that
+// bits2int is plain big-endian decoding. The key, order and returned nonce
+// are little-endian like the SGX key types; the hash is big-endian.
+//
+// A hedged nonce (section 3.6) also mixes fresh RNG output into the HMAC_DRBG
+// seed, so the nonce stays secret if either the RNG or the derivation holds.
+pub(crate) fn generate_nonce<D: Digest>(
+    private_key: &[u8],
+    order: &[u8],
+    hash: &[u8],
+    hedged: bool,
+) -> SgxResult<Vec<u8>> {
+    let size = order.len();
+    ensure!(
+        private_key.len() == size && hash.len() == size && D::OUTPUT_SIZE == size,
+        SgxStatus::InvalidParameter
+    );
+
+    let order: Vec<u8> = order.iter().rev().copied().collect();
+    let mut x: Vec<u8> = private_key.iter().rev().copied().collect();
+    // bits2octets(h1) = h1 mod q, and h1 < 2q.
+    let mut h1 = hash.to_vec();
+    if compare_be(&h1, &order) != Ordering::Less {
+        sub_be(&mut h1, &order);
+    }
+    let mut extra = Vec::new();
+    if hedged {
+        extra.resize(size, 0);
+        crate::rand(&mut extra);
+    }
+
+    let mut k = vec![0_u8; size];
+    let mut v = vec![0x01_u8; size];
+    for tag in [0x00_u8, 0x01] {
+        let t = hmac::<D>(&k, &[&v, &[tag], &x, &h1, &extra])?;
+        k.copy_from_slice(&t);
+        let t = hmac::<D>(&k, &[&v])?;
+        v.copy_from_slice(&t);
+    }
+    x.fill(0);
+
+    loop {
+        let t = hmac::<D>(&k, &[&v])?;
+        v.copy_from_slice(&t);
+        if v.iter().any(|&b| b != 0) && compare_be(&v, &order) == Ordering::Less {
+            k.fill(0);
+            v.reverse();
+            return Ok(v);
+        }
+
+        let t = hmac::<D>(&k, &[&v, &[0x00]])?;
+        k.copy_from_slice(&t);
+        let t = hmac::<D>(&k, &[&v])?;
+        v.copy_from_slice(&t);
+    }
+}
+
+fn hmac<D: Digest>(key: &[u8], data: &[&[u8]]) -> SgxResult<D::Output> {
+    let mut hmac = HMacDigest::<D>::new(key)?;
+    for part in data.iter().filter(|part| !part.is_empty()) {
+        hmac.update(*part)?;
+    }
+    hmac.finalize()
+}
+
+// Equal-length big-endian integers. The loops do not branch on the data,
+// since the candidate nonce is secret.
+fn compare_be(a: &[u8], b: &[u8]) -> Ordering {
+    let mut borrow = 0_i16;
+    let mut zero = 0_u8;
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        let diff = *x as i16 - *y as i16 - borrow;
+        borrow = (diff >> 8) & 1;
+        zero |= diff as u8;
+    }
+    if borrow != 0 {
+        Ordering::Less
+    } else if zero == 0 {
+        Ordering::Equal
+    } else {
+        Ordering::Greater
+    }
+}
+
+fn sub_be(a: &mut [u8], b: &[u8]) {
+    let mut borrow = 0_i16;
+    for (x, y) in a.iter_mut().rev().zip(b.iter().rev()) {
+        let diff = *x as i16 - *y as i16 - borrow;
+        borrow = (diff >> 8) & 1;
+        *x = diff as u8;
+    }
+}
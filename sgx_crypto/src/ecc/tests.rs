@@ -0,0 +1,234 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::*;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use sgx_test_utils::test_case;
+use sgx_types::types::{Sha384Hash, ECP384_KEY_SIZE};
+
+// RFC 6979, appendix A.2.5 (P-256, SHA-256).
+const P256_KEY: &str = "C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721";
+const P256_VECTORS: [(&str, &str, &str); 2] = [
+    (
+        "sample",
+        "EFD48B2AACB6A8FD1140DD9CD45E81D69D2C877B56AAF991C34D0EA84EAF3716",
+        "F7CB1C942D657C41D436C7A1B6E29F65F3E900DBB9AFF4064DC4AB2F843ACDA8",
+    ),
+    (
+        "test",
+        "F1ABB023518351CD71D881567B1EA663ED3EFCF6C5132B354F28D3B0B7D38367",
+        "019F4113742A2B14BD25926B49C649155F267E60D3814B4C0CC84250E46F0083",
+    ),
+];
+
+// RFC 6979, appendix A.2.6 (P-384, SHA-384).
+const P384_KEY: &str = "6B9D3DAD2E1B8C1C05B19875B6659F4DE23C3B667BF297BA9AA47740787137D896D5724E4C70A825F872C9EA60D2EDF5";
+const P384_VECTORS: [(&str, &str, &str); 2] = [
+    (
+        "sample",
+        "94EDBB92A5ECB8AAD4736E56C691916B3F88140666CE9FA73D64C4EA95AD133C81A648152E44ACF96E36DD1E80FABE46",
+        "99EF4AEB15F178CEA1FE40DB2603138F130E740A19624526203B6351D0A3A94FA329C145786E679E7B82C71A38628AC8",
+    ),
+    (
+        "test",
+        "8203B63D3C853E8D77227FB377BCF7B7B772E97892A80F36AB775D509D7A5FEB0542A7F0812998DA8F1DD3CA3CF023DB",
+        "DDD0760448D42D8A43AF45AF836FCE4DE8BE06B485E9B61B827C2F13173923E06A739F040649A667BF3B828246BAA5A5",
+    ),
+];
+
+// FIPS 186-4 CAVP SigGen.txt, [P-384,SHA-384]: d, Qx, Qy, SHA-384(Msg), R, S.
+const P384_SIGGEN: [[&str; 6]; 2] = [
+    [
+        "201b432d8df14324182d6261db3e4b3f46a8284482d52e370da41e6cbdf45ec2952f5db7ccbce3bc29449f4fb080ac97",
+        "c2b47944fb5de342d03285880177ca5f7d0f2fcad7678cce4229d6e1932fcac11bfc3c3e97d942a3c56bf34123013dbf",
+        "37257906a8223866eda0743c519616a76a758ae58aee81c5fd35fbf3a855b7754a36d4a0672df95d6c44a81cf7620c2d",
+        "31a452d6164d904bb5724c878280231eae705c29ce9d4bc7d58e020e1085f17eebcc1a38f0ed0bf2b344d81fbd896825",
+        "50835a9251bad008106177ef004b091a1e4235cd0da84fff54542b0ed755c1d6f251609d14ecf18f9e1ddfe69b946e32",
+        "0475f3d30c6463b646e8d3bf2455830314611cbde404be518b14464fdb195fdcc92eb222e61f426a4a592c00a6a89721",
+    ],
+    [
+        "23d9f4ea6d87b7d6163d64256e3449255db14786401a51daa7847161bf56d494325ad2ac8ba928394e01061d882c3528",
+        "5d42d6301c54a438f65970bae2a098cbc567e98840006e356221966c86d82e8eca515bca850eaa3cd41f175f03a0cbfd",
+        "4aef5a0ceece95d382bd70ab5ce1cb77408bae42b51a08816d5e5e1d3da8c18fcc95564a752730b0aabea983ccea4e2e",
+        "a92784916a40feaebfeab16ea28c0c65e45c5e81eb634052944865708072e20110bd669a9838d7e722e94ac75245cdd3",
+        "fb318f4cb1276282bb43f733a7fb7c567ce94f4d02924fc758635ab2d1107108bf159b85db080cdc3b30fbb5400016f3",
+        "588e3d7af5da03eae255ecb1813100d95edc243476b724b22db8e85377660d7645ddc1c2c2ee4eaea8b683dbe22f86ca",
+    ],
+];
+
+// CAVP KAS ECC CDH primitive, [P-384] COUNT = 0: QCAVSx, QCAVSy, dIUT, ZIUT.
+const P384_CDH: [&str; 4] = [
+    "a7c76b970c3b5fe8b05d2838ae04ab47697b9eaf52e764592efda27fe7513272734466b400091adbf2d68c58e0c50066",
+    "ac68f19f2e1cb879aed43a9969b91a0839c4c38a49749b661efedf243451915ed0905a32b060992b468c64766fc8437a",
+    "3cc3122a68f0d95027ad38c067916ba0eb8c38894d22e1b15618b6818a661774ad463b205da88cf699ab4d43c9cf98a1",
+    "5f9d29dc5e31a163060356213669c8ce132e22f57c9a04f40ba7fcead493b457e5621e766c40a2e3d4d6a04b25e533f1",
+];
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// Big-endian hex to the little-endian byte order of the SGX key types.
+fn decode_le(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .rev()
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// Two big-endian coordinates or scalars to the SGX layout.
+fn decode_pair(x: &str, y: &str) -> [u8; ECP384_KEY_SIZE * 2] {
+    let mut bytes = decode_le(x);
+    bytes.extend_from_slice(&decode_le(y));
+    bytes.try_into().unwrap()
+}
+
+#[test_case]
+fn p256_deterministic_signature() {
+    let mut key = [0_u8; ECP256_KEY_SIZE];
+    key.copy_from_slice(&decode_le(P256_KEY));
+    let private_key = EcPrivateKey::from(key);
+    let public_key = private_key.export_public_key().unwrap();
+
+    for (message, r, s) in P256_VECTORS {
+        let signature = private_key.sign_deterministic(message.as_bytes()).unwrap();
+        let bytes: &[u8; ECP256_KEY_SIZE * 2] = signature.as_ref();
+        assert_eq!(&bytes[..ECP256_KEY_SIZE], decode_le(r).as_slice());
+        assert_eq!(&bytes[ECP256_KEY_SIZE..], decode_le(s).as_slice());
+        assert!(public_key.verify(message.as_bytes(), &signature).unwrap());
+    }
+}
+
+#[test_case]
+fn p384_deterministic_signature() {
+    let mut key = [0_u8; ECP384_KEY_SIZE];
+    key.copy_from_slice(&decode_le(P384_KEY));
+    let private_key = P384PrivateKey::from(key);
+    let public_key = private_key.export_public_key().unwrap();
+
+    for (message, r, s) in P384_VECTORS {
+        let signature = private_key.sign_deterministic(message.as_bytes()).unwrap();
+        let bytes: &[u8; ECP384_KEY_SIZE * 2] = signature.as_ref();
+        assert_eq!(&bytes[..ECP384_KEY_SIZE], decode_le(r).as_slice());
+        assert_eq!(&bytes[ECP384_KEY_SIZE..], decode_le(s).as_slice());
+        assert!(public_key.verify(message.as_bytes(), &signature).unwrap());
+    }
+}
+
+#[test_case]
+fn hedged_signature() {
+    let key_pair = EcKeyPair::create().unwrap();
+    let private_key = key_pair.private_key();
+    let public_key = key_pair.public_key();
+
+    let sig1 = private_key.sign_hedged(b"sample").unwrap();
+    let sig2 = private_key.sign_hedged(b"sample").unwrap();
+    assert_ne!(sig1, sig2);
+    assert!(public_key.verify(b"sample", &sig1).unwrap());
+    assert!(public_key.verify(b"sample", &sig2).unwrap());
+
+    let key_pair = P384KeyPair::create().unwrap();
+    let signature = key_pair.private_key().sign_hedged(b"sample").unwrap();
+    assert!(key_pair.public_key().verify(b"sample", &signature).unwrap());
+}
+
+#[test_case]
+fn p384_cavp_signature() {
+    for [d, qx, qy, hash, r, s] in P384_SIGGEN {
+        let key: [u8; ECP384_KEY_SIZE] = decode_le(d).try_into().unwrap();
+        let private_key = P384PrivateKey::from(key);
+        let public_key = private_key.export_public_key().unwrap();
+        assert_eq!(public_key, P384PublicKey::from(decode_pair(qx, qy)));
+
+        let mut hash = Sha384Hash {
+            hash: decode(hash).try_into().unwrap(),
+        };
+        let signature = P384Signature::from(decode_pair(r, s));
+        assert!(public_key.verify_hash(&hash, &signature).unwrap());
+
+        // a signature with a random nonce
+        let message = b"sample";
+        let fresh = private_key.sign(message).unwrap();
+        assert!(public_key.verify(message, &fresh).unwrap());
+        assert!(!public_key.verify(b"samplf", &fresh).unwrap());
+
+        let mut bytes: [u8; ECP384_KEY_SIZE * 2] = signature.into();
+        bytes[ECP384_KEY_SIZE] ^= 1;
+        assert!(!public_key
+            .verify_hash(&hash, &P384Signature::from(bytes))
+            .unwrap());
+        hash.hash[0] ^= 1;
+        assert!(!public_key.verify_hash(&hash, &signature).unwrap());
+    }
+}
+
+#[test_case]
+fn p384_cavp_shared_key() {
+    let [qx, qy, d, z] = P384_CDH;
+    let key: [u8; ECP384_KEY_SIZE] = decode_le(d).try_into().unwrap();
+    let private_key = P384PrivateKey::from(key);
+    let peer_key = P384PublicKey::from(decode_pair(qx, qy));
+
+    let shared_key = private_key.shared_key(&peer_key).unwrap();
+    let bytes: &[u8; ECP384_KEY_SIZE] = shared_key.as_ref();
+    assert_eq!(bytes, decode_le(z).as_slice());
+
+    let alice = P384KeyPair::create().unwrap();
+    let bob = P384KeyPair::create().unwrap();
+    assert_eq!(
+        alice.shared_key(&bob.public_key()).unwrap(),
+        bob.shared_key(&alice.public_key()).unwrap()
+    );
+    assert_ne!(
+        alice.shared_key(&bob.public_key()).unwrap(),
+        alice.shared_key(&peer_key).unwrap()
+    );
+}
+
+#[test_case]
+fn p384_check_point() {
+    let [qx, qy, ..] = P384_CDH;
+    let point = decode_pair(qx, qy);
+    assert!(P384PublicKey::from(point).check_point().unwrap());
+    assert!(P384KeyPair::create()
+        .unwrap()
+        .public_key()
+        .check_point()
+        .unwrap());
+
+    // y + 1 is off the curve, as are the origin and x = p
+    let mut off_curve = point;
+    off_curve[ECP384_KEY_SIZE] ^= 1;
+    let mut x_is_p = [0_u8; ECP384_KEY_SIZE * 2];
+    x_is_p[..ECP384_KEY_SIZE].copy_from_slice(&decode_le(
+        "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+    ));
+    x_is_p[ECP384_KEY_SIZE..].copy_from_slice(&point[ECP384_KEY_SIZE..]);
+
+    let key: [u8; ECP384_KEY_SIZE] = decode_le(P384_CDH[2]).try_into().unwrap();
+    let private_key = P384PrivateKey::from(key);
+    for invalid in [off_curve, [0; ECP384_KEY_SIZE * 2], x_is_p] {
+        let public_key = P384PublicKey::from(invalid);
+        assert!(!public_key.check_point().unwrap());
+        assert!(private_key.shared_key(&public_key).is_err());
+    }
+}
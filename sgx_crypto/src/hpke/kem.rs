@@ -0,0 +1,308 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{labeled_expand, labeled_extract, Kem};
+use crate::ecc::{EcKeyPair, EcPrivateKey, EcPublicKey};
+use crate::kdf::HkdfSha256;
+use crate::x25519::{X25519KeyPair, X25519PrivateKey, X25519PublicKey, X25519_KEY_SIZE};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::ECP256_KEY_SIZE;
+
+// Order of the P-256 base point, big-endian.
+const P256_ORDER: [u8; ECP256_KEY_SIZE] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xBC, 0xE6, 0xFA, 0xAD, 0xA7, 0x17, 0x9E, 0x84, 0xF3, 0xB9, 0xCA, 0xC2, 0xFC, 0x63, 0x25, 0x51,
+];
+
+/// DHKEM(P-256, HKDF-SHA256).
+pub struct DhKemP256HkdfSha256;
+
+/// DHKEM(X25519, HKDF-SHA256).
+pub struct DhKemX25519HkdfSha256;
+
+// The Diffie-Hellman group under a DHKEM (RFC 9180, section 4.1).
+trait DhGroup: Kem {
+    fn dh(private_key: &Self::PrivateKey, public_key: &Self::PublicKey) -> SgxResult<Vec<u8>>;
+
+    fn public_key(private_key: &Self::PrivateKey) -> SgxResult<Self::PublicKey>;
+
+    fn suite_id() -> [u8; 5] {
+        let mut suite_id = [0_u8; 5];
+        suite_id[..3].copy_from_slice(b"KEM");
+        suite_id[3..].copy_from_slice(&Self::KEM_ID.to_be_bytes());
+        suite_id
+    }
+
+    fn extract_and_expand(dh: &[u8], kem_context: &[u8]) -> SgxResult<Vec<u8>> {
+        let suite_id = Self::suite_id();
+        let mut eae_prk = labeled_extract::<HkdfSha256>(&suite_id, &[], b"eae_prk", dh)?;
+        let shared_secret = labeled_expand::<HkdfSha256>(
+            &suite_id,
+            &eae_prk,
+            b"shared_secret",
+            kem_context,
+            Self::SECRET_SIZE,
+        );
+        eae_prk.fill(0);
+        shared_secret
+    }
+
+    fn dkp_prk(ikm: &[u8]) -> SgxResult<Vec<u8>> {
+        labeled_extract::<HkdfSha256>(&Self::suite_id(), &[], b"dkp_prk", ikm)
+    }
+}
+
+fn dhkem_encap<D: DhGroup>(
+    public_key: &D::PublicKey,
+    sender_key: Option<&D::PrivateKey>,
+    ephemeral: (D::PrivateKey, D::PublicKey),
+) -> SgxResult<(Vec<u8>, Vec<u8>)> {
+    let mut dh = D::dh(&ephemeral.0, public_key)?;
+    let enc = D::serialize_public_key(&ephemeral.1);
+    let mut kem_context = [enc.clone(), D::serialize_public_key(public_key)].concat();
+    if let Some(sender_key) = sender_key {
+        dh.extend(D::dh(sender_key, public_key)?);
+        kem_context.extend(D::serialize_public_key(&D::public_key(sender_key)?));
+    }
+
+    let shared_secret = D::extract_and_expand(&dh, &kem_context);
+    dh.fill(0);
+    Ok((shared_secret?, enc))
+}
+
+fn dhkem_decap<D: DhGroup>(
+    enc: &[u8],
+    private_key: &D::PrivateKey,
+    sender_key: Option<&D::PublicKey>,
+) -> SgxResult<Vec<u8>> {
+    let ephemeral = D::deserialize_public_key(enc)?;
+    let mut dh = D::dh(private_key, &ephemeral)?;
+    let public_key = D::public_key(private_key)?;
+    let mut kem_context = [enc, D::serialize_public_key(&public_key).as_slice()].concat();
+    if let Some(sender_key) = sender_key {
+        dh.extend(D::dh(private_key, sender_key)?);
+        kem_context.extend(D::serialize_public_key(sender_key));
+    }
+
+    let shared_secret = D::extract_and_expand(&dh, &kem_context);
+    dh.fill(0);
+    shared_secret
+}
+
+impl Kem for DhKemP256HkdfSha256 {
+    type PrivateKey = EcPrivateKey;
+    type PublicKey = EcPublicKey;
+
+    const KEM_ID: u16 = 0x0010;
+    const SECRET_SIZE: usize = 32;
+    const ENC_SIZE: usize = 65;
+    const PUBLIC_KEY_SIZE: usize = 65;
+    const PRIVATE_KEY_SIZE: usize = ECP256_KEY_SIZE;
+
+    fn generate_key_pair() -> SgxResult<(EcPrivateKey, EcPublicKey)> {
+        EcKeyPair::create().map(From::from)
+    }
+
+    // Rejection sampling of RFC 9180, section 7.1.3.
+    fn derive_key_pair(ikm: &[u8]) -> SgxResult<(EcPrivateKey, EcPublicKey)> {
+        let suite_id = Self::suite_id();
+        let mut dkp_prk = Self::dkp_prk(ikm)?;
+
+        for counter in 0..=u8::MAX {
+            let mut candidate = labeled_expand::<HkdfSha256>(
+                &suite_id,
+                &dkp_prk,
+                b"candidate",
+                &[counter],
+                Self::PRIVATE_KEY_SIZE,
+            )?;
+            let private_key = Self::deserialize_private_key(&candidate);
+            candidate.fill(0);
+            if let Ok(private_key) = private_key {
+                dkp_prk.fill(0);
+                let public_key = private_key.export_public_key()?;
+                return Ok((private_key, public_key));
+            }
+        }
+
+        dkp_prk.fill(0);
+        bail!(SgxStatus::Unexpected)
+    }
+
+    // The uncompressed point 0x04 || X || Y, each big-endian.
+    fn serialize_public_key(key: &EcPublicKey) -> Vec<u8> {
+        let key = key.public_key();
+        let mut point = Vec::with_capacity(Self::PUBLIC_KEY_SIZE);
+        point.push(0x04);
+        point.extend(key.gx.iter().rev());
+        point.extend(key.gy.iter().rev());
+        point
+    }
+
+    fn deserialize_public_key(key: &[u8]) -> SgxResult<EcPublicKey> {
+        ensure!(
+            key.len() == Self::PUBLIC_KEY_SIZE && key[0] == 0x04,
+            SgxStatus::InvalidParameter
+        );
+
+        let mut point = [0_u8; ECP256_KEY_SIZE * 2];
+        let (x, y) = key[1..].split_at(ECP256_KEY_SIZE);
+        let (gx, gy) = point.split_at_mut(ECP256_KEY_SIZE);
+        gx.iter_mut().zip(x.iter().rev()).for_each(|(d, s)| *d = *s);
+        gy.iter_mut().zip(y.iter().rev()).for_each(|(d, s)| *d = *s);
+
+        let public_key = EcPublicKey::from(point);
+        ensure!(public_key.check_point()?, SgxStatus::InvalidParameter);
+        Ok(public_key)
+    }
+
+    fn serialize_private_key(key: &EcPrivateKey) -> Vec<u8> {
+        key.as_ref().iter().rev().copied().collect()
+    }
+
+    fn deserialize_private_key(key: &[u8]) -> SgxResult<EcPrivateKey> {
+        ensure!(
+            key.len() == Self::PRIVATE_KEY_SIZE
+                && key.iter().any(|&b| b != 0)
+                && key < P256_ORDER.as_slice(),
+            SgxStatus::InvalidParameter
+        );
+
+        let mut scalar = [0_u8; ECP256_KEY_SIZE];
+        scalar
+            .iter_mut()
+            .zip(key.iter().rev())
+            .for_each(|(d, s)| *d = *s);
+        let private_key = EcPrivateKey::from(scalar);
+        scalar.fill(0);
+        Ok(private_key)
+    }
+
+    fn encap_with(
+        public_key: &EcPublicKey,
+        sender_key: Option<&EcPrivateKey>,
+        ephemeral: (EcPrivateKey, EcPublicKey),
+    ) -> SgxResult<(Vec<u8>, Vec<u8>)> {
+        dhkem_encap::<Self>(public_key, sender_key, ephemeral)
+    }
+
+    fn decap_with(
+        enc: &[u8],
+        private_key: &EcPrivateKey,
+        sender_key: Option<&EcPublicKey>,
+    ) -> SgxResult<Vec<u8>> {
+        dhkem_decap::<Self>(enc, private_key, sender_key)
+    }
+}
+
+impl DhGroup for DhKemP256HkdfSha256 {
+    // The x-coordinate of the shared point, big-endian.
+    fn dh(private_key: &EcPrivateKey, public_key: &EcPublicKey) -> SgxResult<Vec<u8>> {
+        let mut shared_key = private_key.shared_key(public_key)?;
+        let dh = shared_key.as_ref().iter().rev().copied().collect();
+        shared_key.clear();
+        Ok(dh)
+    }
+
+    #[inline]
+    fn public_key(private_key: &EcPrivateKey) -> SgxResult<EcPublicKey> {
+        private_key.export_public_key()
+    }
+}
+
+impl Kem for DhKemX25519HkdfSha256 {
+    type PrivateKey = X25519PrivateKey;
+    type PublicKey = X25519PublicKey;
+
+    const KEM_ID: u16 = 0x0020;
+    const SECRET_SIZE: usize = 32;
+    const ENC_SIZE: usize = X25519_KEY_SIZE;
+    const PUBLIC_KEY_SIZE: usize = X25519_KEY_SIZE;
+    const PRIVATE_KEY_SIZE: usize = X25519_KEY_SIZE;
+
+    fn generate_key_pair() -> SgxResult<(X25519PrivateKey, X25519PublicKey)> {
+        X25519KeyPair::create().map(From::from)
+    }
+
+    fn derive_key_pair(ikm: &[u8]) -> SgxResult<(X25519PrivateKey, X25519PublicKey)> {
+        let mut dkp_prk = Self::dkp_prk(ikm)?;
+        let sk = labeled_expand::<HkdfSha256>(
+            &Self::suite_id(),
+            &dkp_prk,
+            b"sk",
+            &[],
+            Self::PRIVATE_KEY_SIZE,
+        );
+        dkp_prk.fill(0);
+
+        let mut sk = sk?;
+        let private_key = Self::deserialize_private_key(&sk);
+        sk.fill(0);
+        let private_key = private_key?;
+        Ok((private_key, private_key.export_public_key()?))
+    }
+
+    fn serialize_public_key(key: &X25519PublicKey) -> Vec<u8> {
+        key.as_ref().to_vec()
+    }
+
+    fn deserialize_public_key(key: &[u8]) -> SgxResult<X25519PublicKey> {
+        let key: [u8; X25519_KEY_SIZE] = key.try_into().map_err(|_| SgxStatus::InvalidParameter)?;
+        Ok(X25519PublicKey::from(key))
+    }
+
+    fn serialize_private_key(key: &X25519PrivateKey) -> Vec<u8> {
+        key.as_ref().to_vec()
+    }
+
+    fn deserialize_private_key(key: &[u8]) -> SgxResult<X25519PrivateKey> {
+        let key: [u8; X25519_KEY_SIZE] = key.try_into().map_err(|_| SgxStatus::InvalidParameter)?;
+        Ok(X25519PrivateKey::from(key))
+    }
+
+    fn encap_with(
+        public_key: &X25519PublicKey,
+        sender_key: Option<&X25519PrivateKey>,
+        ephemeral: (X25519PrivateKey, X25519PublicKey),
+    ) -> SgxResult<(Vec<u8>, Vec<u8>)> {
+        dhkem_encap::<Self>(public_key, sender_key, ephemeral)
+    }
+
+    fn decap_with(
+        enc: &[u8],
+        private_key: &X25519PrivateKey,
+        sender_key: Option<&X25519PublicKey>,
+    ) -> SgxResult<Vec<u8>> {
+        dhkem_decap::<Self>(enc, private_key, sender_key)
+    }
+}
+
+impl DhGroup for DhKemX25519HkdfSha256 {
+    fn dh(private_key: &X25519PrivateKey, public_key: &X25519PublicKey) -> SgxResult<Vec<u8>> {
+        let mut shared_key = private_key.shared_key(public_key)?;
+        let dh = shared_key.as_ref().to_vec();
+        shared_key.clear();
+        Ok(dh)
+    }
+
+    #[inline]
+    fn public_key(private_key: &X25519PrivateKey) -> SgxResult<X25519PublicKey> {
+        private_key.export_public_key()
+    }
+}
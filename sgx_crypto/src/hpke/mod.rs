@@ -0,0 +1,455 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! Hybrid Public Key Encryption (RFC 9180), in the Base and Auth modes.
+//!
+//! A suite is chosen by its type parameters, e.g.
+//! `Hpke<DhKemP256HkdfSha256, HkdfSha256, Aes128Gcm>`. The sender encrypts to
+//! the receiver's public key without any interaction: it sends the
+//! encapsulated key `enc` along with the ciphertexts.
+
+use crate::aes::gcm::AesGcm;
+use crate::chacha::poly1305::ChaCha20Poly1305 as ChaCha20Poly1305Cipher;
+use crate::cipher::Aead;
+use crate::kdf::{HkdfSha256, HkdfSha384};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::marker::PhantomData;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{Key128bit, Key256bit, Mac128bit, MAC_128BIT_SIZE};
+
+mod kem;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub use kem::*;
+
+const HPKE_V1: &[u8] = b"HPKE-v1";
+
+pub const MODE_BASE: u8 = 0x00;
+pub const MODE_AUTH: u8 = 0x02;
+
+/// Key encapsulation mechanism, identified by its HPKE KEM id.
+pub trait Kem {
+    type PrivateKey: Copy;
+    type PublicKey: Copy;
+
+    const KEM_ID: u16;
+    /// Nsecret, Nenc, Npk and Nsk of RFC 9180, section 7.1.
+    const SECRET_SIZE: usize;
+    const ENC_SIZE: usize;
+    const PUBLIC_KEY_SIZE: usize;
+    const PRIVATE_KEY_SIZE: usize;
+
+    fn generate_key_pair() -> SgxResult<(Self::PrivateKey, Self::PublicKey)>;
+
+    fn derive_key_pair(ikm: &[u8]) -> SgxResult<(Self::PrivateKey, Self::PublicKey)>;
+
+    fn serialize_public_key(key: &Self::PublicKey) -> Vec<u8>;
+
+    fn deserialize_public_key(key: &[u8]) -> SgxResult<Self::PublicKey>;
+
+    fn serialize_private_key(key: &Self::PrivateKey) -> Vec<u8>;
+
+    fn deserialize_private_key(key: &[u8]) -> SgxResult<Self::PrivateKey>;
+
+    /// Returns the shared secret and the encapsulated key.
+    fn encap(public_key: &Self::PublicKey) -> SgxResult<(Vec<u8>, Vec<u8>)> {
+        let ephemeral = Self::generate_key_pair()?;
+        Self::encap_with(public_key, None, ephemeral)
+    }
+
+    fn decap(enc: &[u8], private_key: &Self::PrivateKey) -> SgxResult<Vec<u8>> {
+        Self::decap_with(enc, private_key, None)
+    }
+
+    fn auth_encap(
+        public_key: &Self::PublicKey,
+        sender_key: &Self::PrivateKey,
+    ) -> SgxResult<(Vec<u8>, Vec<u8>)> {
+        let ephemeral = Self::generate_key_pair()?;
+        Self::encap_with(public_key, Some(sender_key), ephemeral)
+    }
+
+    fn auth_decap(
+        enc: &[u8],
+        private_key: &Self::PrivateKey,
+        sender_key: &Self::PublicKey,
+    ) -> SgxResult<Vec<u8>> {
+        Self::decap_with(enc, private_key, Some(sender_key))
+    }
+
+    /// Encap and AuthEncap with a given ephemeral key pair.
+    fn encap_with(
+        public_key: &Self::PublicKey,
+        sender_key: Option<&Self::PrivateKey>,
+        ephemeral: (Self::PrivateKey, Self::PublicKey),
+    ) -> SgxResult<(Vec<u8>, Vec<u8>)>;
+
+    fn decap_with(
+        enc: &[u8],
+        private_key: &Self::PrivateKey,
+        sender_key: Option<&Self::PublicKey>,
+    ) -> SgxResult<Vec<u8>>;
+}
+
+/// Key derivation function, identified by its HPKE KDF id.
+pub trait Kdf {
+    const KDF_ID: u16;
+    /// Nh of RFC 9180, section 7.2.
+    const HASH_SIZE: usize;
+
+    fn extract(salt: &[u8], ikm: &[u8]) -> SgxResult<Vec<u8>>;
+
+    fn expand(prk: &[u8], info: &[u8], len: usize) -> SgxResult<Vec<u8>>;
+}
+
+/// AEAD, identified by its HPKE AEAD id.
+pub trait HpkeAead: Aead {
+    const AEAD_ID: u16;
+    /// Nk of RFC 9180, section 7.3.
+    const KEY_SIZE: usize;
+
+    fn key_from_slice(key: &[u8]) -> SgxResult<Self::Key>;
+}
+
+pub type Aes128Gcm = AesGcm<[u8; 0], Key128bit>;
+pub type Aes256Gcm = AesGcm<[u8; 0], Key256bit>;
+pub type ChaCha20Poly1305 = ChaCha20Poly1305Cipher<[u8; 0]>;
+
+macro_rules! impl_hpke_kdf {
+    ($($name:ty, $id:expr;)*) => {$(
+        impl Kdf for $name {
+            const KDF_ID: u16 = $id;
+            const HASH_SIZE: usize = <$name>::HASH_SIZE;
+
+            fn extract(salt: &[u8], ikm: &[u8]) -> SgxResult<Vec<u8>> {
+                <$name>::extract(salt, ikm).map(|prk| prk.to_vec())
+            }
+
+            fn expand(prk: &[u8], info: &[u8], len: usize) -> SgxResult<Vec<u8>> {
+                <$name>::from_prk(prk)?.expand_to_vec(info, len)
+            }
+        }
+    )*}
+}
+
+impl_hpke_kdf! {
+    HkdfSha256, 0x0001;
+    HkdfSha384, 0x0002;
+}
+
+macro_rules! impl_hpke_aead {
+    ($($name:ty, $id:expr, $key:ty;)*) => {$(
+        impl HpkeAead for $name {
+            const AEAD_ID: u16 = $id;
+            const KEY_SIZE: usize = core::mem::size_of::<$key>();
+
+            fn key_from_slice(key: &[u8]) -> SgxResult<$key> {
+                key.try_into().map_err(|_| SgxStatus::InvalidParameter)
+            }
+        }
+    )*}
+}
+
+impl_hpke_aead! {
+    Aes128Gcm, 0x0001, Key128bit;
+    Aes256Gcm, 0x0002, Key256bit;
+    ChaCha20Poly1305, 0x0003, Key256bit;
+}
+
+// LabeledExtract and LabeledExpand of RFC 9180, section 4.
+pub(crate) fn labeled_extract<K: Kdf>(
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+) -> SgxResult<Vec<u8>> {
+    let mut labeled_ikm = [HPKE_V1, suite_id, label, ikm].concat();
+    let prk = K::extract(salt, &labeled_ikm);
+    labeled_ikm.fill(0);
+    prk
+}
+
+pub(crate) fn labeled_expand<K: Kdf>(
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    len: usize,
+) -> SgxResult<Vec<u8>> {
+    ensure!(len <= u16::MAX as usize, SgxStatus::InvalidParameter);
+
+    let labeled_info = [&(len as u16).to_be_bytes(), HPKE_V1, suite_id, label, info].concat();
+    K::expand(prk, &labeled_info, len)
+}
+
+/// An HPKE cipher suite.
+pub struct Hpke<M: Kem, K: Kdf, A: HpkeAead> {
+    marker: PhantomData<(M, K, A)>,
+}
+
+impl<M: Kem, K: Kdf, A: HpkeAead> Hpke<M, K, A> {
+    pub fn suite_id() -> [u8; 10] {
+        let mut suite_id = [0_u8; 10];
+        suite_id[..4].copy_from_slice(b"HPKE");
+        suite_id[4..6].copy_from_slice(&M::KEM_ID.to_be_bytes());
+        suite_id[6..8].copy_from_slice(&K::KDF_ID.to_be_bytes());
+        suite_id[8..].copy_from_slice(&A::AEAD_ID.to_be_bytes());
+        suite_id
+    }
+
+    /// Returns the encapsulated key to send, and the context to seal with.
+    #[inline]
+    pub fn setup_base_sender(
+        public_key: &M::PublicKey,
+        info: &[u8],
+    ) -> SgxResult<(Vec<u8>, SenderContext<K, A>)> {
+        Self::setup_sender(MODE_BASE, public_key, info, None, M::generate_key_pair()?)
+    }
+
+    #[inline]
+    pub fn setup_base_receiver(
+        enc: &[u8],
+        private_key: &M::PrivateKey,
+        info: &[u8],
+    ) -> SgxResult<ReceiverContext<K, A>> {
+        Self::setup_receiver(MODE_BASE, enc, private_key, info, None)
+    }
+
+    /// As setup_base_sender, also authenticating the sender's key pair.
+    #[inline]
+    pub fn setup_auth_sender(
+        public_key: &M::PublicKey,
+        info: &[u8],
+        sender_key: &M::PrivateKey,
+    ) -> SgxResult<(Vec<u8>, SenderContext<K, A>)> {
+        Self::setup_sender(
+            MODE_AUTH,
+            public_key,
+            info,
+            Some(sender_key),
+            M::generate_key_pair()?,
+        )
+    }
+
+    #[inline]
+    pub fn setup_auth_receiver(
+        enc: &[u8],
+        private_key: &M::PrivateKey,
+        info: &[u8],
+        sender_key: &M::PublicKey,
+    ) -> SgxResult<ReceiverContext<K, A>> {
+        Self::setup_receiver(MODE_AUTH, enc, private_key, info, Some(sender_key))
+    }
+
+    /// Single-shot encryption; returns the encapsulated key and the ciphertext.
+    pub fn seal_base(
+        public_key: &M::PublicKey,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> SgxResult<(Vec<u8>, Vec<u8>)> {
+        let (enc, mut context) = Self::setup_base_sender(public_key, info)?;
+        let ciphertext = context.seal(aad, plaintext)?;
+        Ok((enc, ciphertext))
+    }
+
+    pub fn open_base(
+        enc: &[u8],
+        private_key: &M::PrivateKey,
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        let mut context = Self::setup_base_receiver(enc, private_key, info)?;
+        context.open(aad, ciphertext)
+    }
+
+    pub(crate) fn setup_sender(
+        mode: u8,
+        public_key: &M::PublicKey,
+        info: &[u8],
+        sender_key: Option<&M::PrivateKey>,
+        ephemeral: (M::PrivateKey, M::PublicKey),
+    ) -> SgxResult<(Vec<u8>, SenderContext<K, A>)> {
+        let (mut shared_secret, enc) = M::encap_with(public_key, sender_key, ephemeral)?;
+        let context = Self::key_schedule(mode, &shared_secret, info);
+        shared_secret.fill(0);
+        Ok((enc, SenderContext(context?)))
+    }
+
+    pub(crate) fn setup_receiver(
+        mode: u8,
+        enc: &[u8],
+        private_key: &M::PrivateKey,
+        info: &[u8],
+        sender_key: Option<&M::PublicKey>,
+    ) -> SgxResult<ReceiverContext<K, A>> {
+        let mut shared_secret = M::decap_with(enc, private_key, sender_key)?;
+        let context = Self::key_schedule(mode, &shared_secret, info);
+        shared_secret.fill(0);
+        Ok(ReceiverContext(context?))
+    }
+
+    // KeySchedule of RFC 9180, section 5.1, without a PSK.
+    fn key_schedule(mode: u8, shared_secret: &[u8], info: &[u8]) -> SgxResult<Context<K, A>> {
+        let suite_id = Self::suite_id();
+
+        let psk_id_hash = labeled_extract::<K>(&suite_id, &[], b"psk_id_hash", &[])?;
+        let info_hash = labeled_extract::<K>(&suite_id, &[], b"info_hash", info)?;
+        let key_schedule_context = [&[mode], psk_id_hash.as_slice(), info_hash.as_slice()].concat();
+
+        let mut secret = labeled_extract::<K>(&suite_id, shared_secret, b"secret", &[])?;
+        let context = Context::derive(&suite_id, &secret, &key_schedule_context);
+        secret.fill(0);
+        context
+    }
+}
+
+pub(crate) struct Context<K: Kdf, A: HpkeAead> {
+    key: Vec<u8>,
+    base_nonce: Vec<u8>,
+    exporter_secret: Vec<u8>,
+    seq: u64,
+    suite_id: [u8; 10],
+    marker: PhantomData<(K, A)>,
+}
+
+impl<K: Kdf, A: HpkeAead> Context<K, A> {
+    fn derive(suite_id: &[u8; 10], secret: &[u8], ks_context: &[u8]) -> SgxResult<Context<K, A>> {
+        Ok(Context {
+            key: labeled_expand::<K>(suite_id, secret, b"key", ks_context, A::KEY_SIZE)?,
+            base_nonce: labeled_expand::<K>(
+                suite_id,
+                secret,
+                b"base_nonce",
+                ks_context,
+                A::NONCE_SIZE,
+            )?,
+            exporter_secret: labeled_expand::<K>(
+                suite_id,
+                secret,
+                b"exp",
+                ks_context,
+                K::HASH_SIZE,
+            )?,
+            seq: 0,
+            suite_id: *suite_id,
+            marker: PhantomData,
+        })
+    }
+
+    // base_nonce xor I2OSP(seq, Nn); the sequence number must not wrap.
+    fn next_nonce(&mut self) -> SgxResult<A::Nonce> {
+        ensure!(self.seq < u64::MAX, SgxStatus::InvalidState);
+
+        let mut nonce = self.base_nonce.clone();
+        for (n, s) in nonce
+            .iter_mut()
+            .rev()
+            .zip(self.seq.to_be_bytes().iter().rev())
+        {
+            *n ^= *s;
+        }
+        self.seq += 1;
+        A::nonce_from_slice(&nonce)
+    }
+
+    fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> SgxResult<Vec<u8>> {
+        let key = A::key_from_slice(&self.key)?;
+        let nonce = self.next_nonce()?;
+
+        let len = plaintext.len();
+        let mut ciphertext = vec![0_u8; len + MAC_128BIT_SIZE];
+        let mac = A::encrypt_detached(&key, nonce, aad, plaintext, &mut ciphertext[..len])?;
+        ciphertext[len..].copy_from_slice(&mac);
+        Ok(ciphertext)
+    }
+
+    fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> SgxResult<Vec<u8>> {
+        ensure!(
+            ciphertext.len() >= MAC_128BIT_SIZE,
+            SgxStatus::InvalidParameter
+        );
+
+        let key = A::key_from_slice(&self.key)?;
+        let len = ciphertext.len() - MAC_128BIT_SIZE;
+        let mac: Mac128bit = ciphertext[len..].try_into().unwrap();
+
+        // The sequence number only advances on success, as in RFC 9180.
+        let seq = self.seq;
+        let nonce = self.next_nonce()?;
+        let mut plaintext = vec![0_u8; len];
+        if let Err(e) =
+            A::decrypt_detached(&key, nonce, aad, &ciphertext[..len], &mut plaintext, &mac)
+        {
+            self.seq = seq;
+            bail!(e);
+        }
+        Ok(plaintext)
+    }
+
+    fn export(&self, exporter_context: &[u8], len: usize) -> SgxResult<Vec<u8>> {
+        labeled_expand::<K>(
+            &self.suite_id,
+            &self.exporter_secret,
+            b"sec",
+            exporter_context,
+            len,
+        )
+    }
+}
+
+impl<K: Kdf, A: HpkeAead> Drop for Context<K, A> {
+    fn drop(&mut self) {
+        self.key.fill(0);
+        self.base_nonce.fill(0);
+        self.exporter_secret.fill(0);
+    }
+}
+
+pub struct SenderContext<K: Kdf, A: HpkeAead>(Context<K, A>);
+
+impl<K: Kdf, A: HpkeAead> SenderContext<K, A> {
+    /// Encrypts the next message; the tag is appended to the ciphertext.
+    #[inline]
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.0.seal(aad, plaintext)
+    }
+
+    #[inline]
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> SgxResult<Vec<u8>> {
+        self.0.export(exporter_context, len)
+    }
+}
+
+pub struct ReceiverContext<K: Kdf, A: HpkeAead>(Context<K, A>);
+
+impl<K: Kdf, A: HpkeAead> ReceiverContext<K, A> {
+    /// Decrypts the next message; messages must be opened in the order they were sealed.
+    #[inline]
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.0.open(aad, ciphertext)
+    }
+
+    #[inline]
+    pub fn export(&self, exporter_context: &[u8], len: usize) -> SgxResult<Vec<u8>> {
+        self.0.export(exporter_context, len)
+    }
+}
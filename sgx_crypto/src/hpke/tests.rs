@@ -0,0 +1,299 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::*;
+use crate::kdf::HkdfSha256;
+use alloc::string::String;
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+
+// RFC 9180, appendix A.
+const INFO: &str = "4f6465206f6e2061204772656369616e2055726e";
+const PLAINTEXT: &str = "4265617574792069732074727574682c20747275746820626561757479";
+
+struct TestVector {
+    mode: u8,
+    ikm_e: &'static str,
+    ikm_r: &'static str,
+    ikm_s: Option<&'static str>,
+    pk_em: &'static str,
+    sk_rm: &'static str,
+    shared_secret: &'static str,
+    // (sequence number, aad, ciphertext)
+    encryptions: &'static [(u64, &'static str, &'static str)],
+    // (exporter context, exported value), each of 32 bytes
+    exports: &'static [(&'static str, &'static str)],
+}
+
+// A.1.1, DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Base.
+const X25519_BASE: TestVector = TestVector {
+    mode: MODE_BASE,
+    ikm_e: "7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234",
+    ikm_r: "6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037",
+    ikm_s: None,
+    pk_em: "37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431",
+    sk_rm: "4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8",
+    shared_secret: "fe0e18c9f024ce43799ae393c7e8fe8fce9d218875e8227b0187c04e7d2ea1fc",
+    encryptions: &[
+        (
+            0,
+            "436f756e742d30",
+            "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a",
+        ),
+        (
+            1,
+            "436f756e742d31",
+            "af2d7e9ac9ae7e270f46ba1f975be53c09f8d875bdc8535458c2494e8a6eab251c03d0c22a56b8ca42c2063b84",
+        ),
+    ],
+    exports: &[
+        (
+            "",
+            "3853fe2b4035195a573ffc53856e77058e15d9ea064de3e59f4961d0095250ee",
+        ),
+        (
+            "00",
+            "2e8f0b54673c7029649d4eb9d5e33bf1872cf76d623ff164ac185da9e88c21a5",
+        ),
+        (
+            "54657374436f6e74657874",
+            "e9e43065102c3836401bed8c3c3c75ae46be1639869391d62c61f1ec7af54931",
+        ),
+    ],
+};
+
+// A.1.3, DHKEM(X25519, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Auth.
+const X25519_AUTH: TestVector = TestVector {
+    mode: MODE_AUTH,
+    ikm_e: "6e6d8f200ea2fb20c30b003a8b4f433d2f4ed4c2658d5bc8ce2fef718059c9f7",
+    ikm_r: "f1d4a30a4cef8d6d4e3b016e6fd3799ea057db4f345472ed302a67ce1c20cdec",
+    ikm_s: Some("94b020ce91d73fca4649006c7e7329a67b40c55e9e93cc907d282bbbff386f58"),
+    pk_em: "23fb952571a14a25e3d678140cd0e5eb47a0961bb18afcf85896e5453c312e76",
+    sk_rm: "fdea67cf831f1ca98d8e27b1f6abeb5b7745e9d35348b80fa407ff6958f9137e",
+    shared_secret: "2d6db4cf719dc7293fcbf3fa64690708e44e2bebc81f84608677958c0d4448a7",
+    encryptions: &[
+        (
+            0,
+            "436f756e742d30",
+            "5fd92cc9d46dbf8943e72a07e42f363ed5f721212cd90bcfd072bfd9f44e06b80fd17824947496e21b680c141b",
+        ),
+        (
+            1,
+            "436f756e742d31",
+            "d3736bb256c19bfa93d79e8f80b7971262cb7c887e35c26370cfed62254369a1b52e3d505b79dd699f002bc8ed",
+        ),
+    ],
+    exports: &[
+        (
+            "",
+            "28c70088017d70c896a8420f04702c5a321d9cbf0279fba899b59e51bac72c85",
+        ),
+        (
+            "00",
+            "25dfc004b0892be1888c3914977aa9c9bbaf2c7471708a49e1195af48a6f29ce",
+        ),
+        (
+            "54657374436f6e74657874",
+            "5a0131813abc9a522cad678eb6bafaabc43389934adb8097d23c5ff68059eb64",
+        ),
+    ],
+};
+
+// A.3.1, DHKEM(P-256, HKDF-SHA256), HKDF-SHA256, AES-128-GCM, Base.
+const P256_BASE: TestVector = TestVector {
+    mode: MODE_BASE,
+    ikm_e: "4270e54ffd08d79d5928020af4686d8f6b7d35dbe470265f1f5aa22816ce860e",
+    ikm_r: "668b37171f1072f3cf12ea8a236a45df23fc13b82af3609ad1e354f6ef817550",
+    ikm_s: None,
+    pk_em: "04a92719c6195d5085104f469a8b9814d5838ff72b60501e2c4466e5e67b325ac98536d7b61a1af4b78e5b7f951c0900be863c403ce65c9bfcb9382657222d18c4",
+    sk_rm: "f3ce7fdae57e1a310d87f1ebbde6f328be0a99cdbcadf4d6589cf29de4b8ffd2",
+    shared_secret: "c0d26aeab536609a572b07695d933b589dcf363ff9d93c93adea537aeabb8cb8",
+    encryptions: &[
+        (
+            0,
+            "436f756e742d30",
+            "5ad590bb8baa577f8619db35a36311226a896e7342a6d836d8b7bcd2f20b6c7f9076ac232e3ab2523f39513434",
+        ),
+        (
+            1,
+            "436f756e742d31",
+            "fa6f037b47fc21826b610172ca9637e82d6e5801eb31cbd3748271affd4ecb06646e0329cbdf3c3cd655b28e82",
+        ),
+    ],
+    exports: &[
+        (
+            "",
+            "5e9bc3d236e1911d95e65b576a8a86d478fb827e8bdfe77b741b289890490d4d",
+        ),
+        (
+            "00",
+            "6cff87658931bda83dc857e6353efe4987a201b849658d9b047aab4cf216e796",
+        ),
+        (
+            "54657374436f6e74657874",
+            "d8f1ea7942adbba7412c6d431c62d01371ea476b823eb697e1f6e6cae1dab85a",
+        ),
+    ],
+};
+
+fn decode_hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn check_vector<M: Kem, A: HpkeAead>(tv: &TestVector) {
+    let info = decode_hex(INFO);
+    let plaintext = decode_hex(PLAINTEXT);
+
+    let ephemeral = M::derive_key_pair(&decode_hex(tv.ikm_e)).unwrap();
+    assert_eq!(encode_hex(&M::serialize_public_key(&ephemeral.1)), tv.pk_em);
+    let (sk_r, pk_r) = M::derive_key_pair(&decode_hex(tv.ikm_r)).unwrap();
+    assert_eq!(encode_hex(&M::serialize_private_key(&sk_r)), tv.sk_rm);
+    let sender = tv
+        .ikm_s
+        .map(|ikm| M::derive_key_pair(&decode_hex(ikm)).unwrap());
+
+    let (shared_secret, enc) =
+        M::encap_with(&pk_r, sender.as_ref().map(|s| &s.0), ephemeral).unwrap();
+    assert_eq!(encode_hex(&shared_secret), tv.shared_secret);
+    assert_eq!(encode_hex(&enc), tv.pk_em);
+
+    let (enc, mut sender_ctx) = Hpke::<M, HkdfSha256, A>::setup_sender(
+        tv.mode,
+        &pk_r,
+        &info,
+        sender.as_ref().map(|s| &s.0),
+        ephemeral,
+    )
+    .unwrap();
+    let mut receiver_ctx = Hpke::<M, HkdfSha256, A>::setup_receiver(
+        tv.mode,
+        &enc,
+        &sk_r,
+        &info,
+        sender.as_ref().map(|s| &s.1),
+    )
+    .unwrap();
+
+    for (seq, aad, ciphertext) in tv.encryptions {
+        assert_eq!(sender_ctx.0.seq, *seq);
+        let aad = decode_hex(aad);
+        let sealed = sender_ctx.seal(&aad, &plaintext).unwrap();
+        assert_eq!(encode_hex(&sealed), *ciphertext);
+        assert_eq!(receiver_ctx.open(&aad, &sealed).unwrap(), plaintext);
+    }
+
+    for (context, value) in tv.exports {
+        let context = decode_hex(context);
+        assert_eq!(
+            encode_hex(&sender_ctx.export(&context, 32).unwrap()),
+            *value
+        );
+        assert_eq!(
+            encode_hex(&receiver_ctx.export(&context, 32).unwrap()),
+            *value
+        );
+    }
+}
+
+#[test_case]
+fn x25519_base_vector() {
+    check_vector::<DhKemX25519HkdfSha256, Aes128Gcm>(&X25519_BASE);
+}
+
+#[test_case]
+fn x25519_auth_vector() {
+    check_vector::<DhKemX25519HkdfSha256, Aes128Gcm>(&X25519_AUTH);
+}
+
+#[test_case]
+fn p256_base_vector() {
+    check_vector::<DhKemP256HkdfSha256, Aes128Gcm>(&P256_BASE);
+}
+
+fn roundtrip<M: Kem, A: HpkeAead>() {
+    let (sk_r, pk_r) = M::generate_key_pair().unwrap();
+    let (sk_s, pk_s) = M::generate_key_pair().unwrap();
+
+    let (enc, mut sender) =
+        Hpke::<M, HkdfSha256, A>::setup_auth_sender(&pk_r, b"info", &sk_s).unwrap();
+    let mut receiver =
+        Hpke::<M, HkdfSha256, A>::setup_auth_receiver(&enc, &sk_r, b"info", &pk_s).unwrap();
+    for msg in [&b"first"[..], b"", b"third"] {
+        let ciphertext = sender.seal(b"aad", msg).unwrap();
+        assert_eq!(ciphertext.len(), msg.len() + MAC_128BIT_SIZE);
+        assert_eq!(receiver.open(b"aad", &ciphertext).unwrap(), msg);
+    }
+    assert_eq!(
+        sender.export(b"ctx", 64).unwrap(),
+        receiver.export(b"ctx", 64).unwrap()
+    );
+
+    let (enc, ciphertext) =
+        Hpke::<M, HkdfSha256, A>::seal_base(&pk_r, b"info", b"aad", b"message").unwrap();
+    let plaintext =
+        Hpke::<M, HkdfSha256, A>::open_base(&enc, &sk_r, b"info", b"aad", &ciphertext).unwrap();
+    assert_eq!(plaintext, b"message");
+}
+
+#[test_case]
+fn aead_roundtrip() {
+    roundtrip::<DhKemX25519HkdfSha256, Aes256Gcm>();
+    roundtrip::<DhKemX25519HkdfSha256, ChaCha20Poly1305>();
+    roundtrip::<DhKemP256HkdfSha256, ChaCha20Poly1305>();
+}
+
+#[test_case]
+fn open_failure() {
+    let (sk_r, pk_r) = DhKemX25519HkdfSha256::generate_key_pair().unwrap();
+    let (enc, mut sender) =
+        Hpke::<DhKemX25519HkdfSha256, HkdfSha256, Aes128Gcm>::setup_base_sender(&pk_r, b"")
+            .unwrap();
+    let mut receiver =
+        Hpke::<DhKemX25519HkdfSha256, HkdfSha256, Aes128Gcm>::setup_base_receiver(&enc, &sk_r, b"")
+            .unwrap();
+
+    let mut ciphertext = sender.seal(b"", b"message").unwrap();
+    ciphertext[0] ^= 1;
+    assert_eq!(
+        receiver.open(b"", &ciphertext).unwrap_err(),
+        SgxStatus::MacMismatch
+    );
+    // A failed open leaves the sequence number where it was.
+    ciphertext[0] ^= 1;
+    assert_eq!(receiver.open(b"", &ciphertext).unwrap(), b"message");
+    assert_eq!(
+        receiver.open(b"", &[0_u8; 15]).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
+
+#[test_case]
+fn p256_invalid_keys() {
+    assert!(DhKemP256HkdfSha256::deserialize_private_key(&[0_u8; 32]).is_err());
+    assert!(DhKemP256HkdfSha256::deserialize_private_key(&[0xFF_u8; 32]).is_err());
+    let mut point = [0_u8; 65];
+    point[0] = 0x04;
+    point[1] = 1;
+    assert!(DhKemP256HkdfSha256::deserialize_public_key(&point).is_err());
+}
@@ -0,0 +1,104 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::mac::{HMac, HashType};
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{Mac256bit, Mac384bit, MAC_256BIT_SIZE, MAC_384BIT_SIZE};
+
+// HKDF (RFC 5869). An empty salt is the same as a salt of HashLen zeros.
+macro_rules! impl_hkdf {
+    ($name:ident, $prk:ty, $size:expr, $hash_type:expr) => {
+        pub struct $name {
+            prk: Vec<u8>,
+        }
+
+        impl $name {
+            pub const HASH_SIZE: usize = $size;
+            pub const MAX_OKM_SIZE: usize = 255 * $size;
+
+            pub fn new(salt: &[u8], ikm: &[u8]) -> SgxResult<$name> {
+                let prk = Self::extract(salt, ikm)?;
+                Ok($name { prk: prk.to_vec() })
+            }
+
+            pub fn from_prk(prk: &[u8]) -> SgxResult<$name> {
+                ensure!(
+                    (Self::HASH_SIZE..i32::MAX as usize).contains(&prk.len()),
+                    SgxStatus::InvalidParameter
+                );
+                Ok($name { prk: prk.to_vec() })
+            }
+
+            pub fn extract(salt: &[u8], ikm: &[u8]) -> SgxResult<$prk> {
+                let zeros = [0_u8; $size];
+                let salt = if salt.is_empty() { &zeros[..] } else { salt };
+
+                let mut hmac = HMac::new(salt, $hash_type)?;
+                if !ikm.is_empty() {
+                    hmac.update(ikm)?;
+                }
+                let mut prk = [0_u8; $size];
+                hmac.finalize_into(&mut prk)?;
+                Ok(prk)
+            }
+
+            pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> SgxResult {
+                ensure!(
+                    (1..=Self::MAX_OKM_SIZE).contains(&okm.len()),
+                    SgxStatus::InvalidParameter
+                );
+
+                let mut hmac = HMac::new(self.prk.as_slice(), $hash_type)?;
+                let mut block = [0_u8; $size];
+                for (i, chunk) in okm.chunks_mut($size).enumerate() {
+                    if i > 0 {
+                        hmac.update(&block)?;
+                    }
+                    if !info.is_empty() {
+                        hmac.update(info)?;
+                    }
+                    hmac.update(&[i as u8 + 1])?;
+                    hmac.finalize_into_reset(&mut block)?;
+                    chunk.copy_from_slice(&block[..chunk.len()]);
+                }
+                block.fill(0);
+                Ok(())
+            }
+
+            pub fn expand_to_vec(&self, info: &[u8], len: usize) -> SgxResult<Vec<u8>> {
+                let mut okm = vec![0_u8; len];
+                self.expand(info, okm.as_mut_slice())?;
+                Ok(okm)
+            }
+
+            pub fn derive(salt: &[u8], ikm: &[u8], info: &[u8], okm: &mut [u8]) -> SgxResult {
+                let hkdf = Self::new(salt, ikm)?;
+                hkdf.expand(info, okm)
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.prk.fill(0);
+            }
+        }
+    };
+}
+
+impl_hkdf!(HkdfSha256, Mac256bit, MAC_256BIT_SIZE, HashType::Sha256);
+impl_hkdf!(HkdfSha384, Mac384bit, MAC_384BIT_SIZE, HashType::Sha384);
@@ -0,0 +1,98 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{HkdfSha256, HkdfSha384};
+use crate::mac::{HMac, HashType};
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::MAC_384BIT_SIZE;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+const IKM: [u8; 22] = [0x0b; 22];
+const SALT: &str = "000102030405060708090a0b0c";
+const INFO: &str = "f0f1f2f3f4f5f6f7f8f9";
+
+// RFC 5869, appendix A.1
+#[test_case]
+fn hkdf_sha256_rfc5869() {
+    let prk = HkdfSha256::extract(&decode(SALT), &IKM).unwrap();
+    assert_eq!(
+        prk.to_vec(),
+        decode("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+    );
+
+    let mut okm = [0_u8; 42];
+    HkdfSha256::derive(&decode(SALT), &IKM, &decode(INFO), &mut okm).unwrap();
+    assert_eq!(
+        okm.to_vec(),
+        decode(
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+             34007208d5b887185865"
+        )
+    );
+}
+
+#[test_case]
+fn hkdf_sha384() {
+    let mut okm = [0_u8; 42];
+    HkdfSha384::derive(&decode(SALT), &IKM, &decode(INFO), &mut okm).unwrap();
+    assert_eq!(
+        okm.to_vec(),
+        decode(
+            "9b5097a86038b805309076a44b3a9f38063e25b516dcbf369f394cfab43685f7\
+             48b6457763e4f0204fc5"
+        )
+    );
+
+    let hkdf = HkdfSha384::new(&[], &IKM).unwrap();
+    let mut okm = vec![0_u8; HkdfSha384::MAX_OKM_SIZE + 1];
+    assert_eq!(hkdf.expand(&[], &mut okm), Err(SgxStatus::InvalidParameter));
+}
+
+// RFC 4231, test case 2
+#[test_case]
+fn hmac_sha384_rfc4231() {
+    let data = b"what do ya want for nothing?";
+    let expected = decode(
+        "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e\
+         8e2240ca5e69e2c78b3239ecfab21649",
+    );
+
+    let mut mac = [0_u8; MAC_384BIT_SIZE];
+    HMac::hmac_into(b"Jefe", HashType::Sha384, data, &mut mac).unwrap();
+    assert_eq!(mac.to_vec(), expected);
+
+    let mut hmac = HMac::new(b"Jefe", HashType::Sha384).unwrap();
+    hmac.update(&data[..4]).unwrap();
+    hmac.update(&data[4..]).unwrap();
+    let mut mac = [0_u8; MAC_384BIT_SIZE];
+    hmac.finalize_into_reset(&mut mac).unwrap();
+    assert_eq!(mac.to_vec(), expected);
+
+    hmac.update(data).unwrap();
+    hmac.verify(&expected).unwrap();
+
+    let hmac = HMac::new(b"Jefe", HashType::Sha384).unwrap();
+    assert_eq!(hmac.finalize().err(), Some(SgxStatus::InvalidParameter));
+}
@@ -49,9 +49,33 @@ extern crate sgx_tserialize as sgx_serialize;
 extern crate sgx_userialize as sgx_serialize;
 
 pub mod aes;
+pub mod chacha;
+pub mod cipher;
+mod curve25519;
+pub mod der;
 pub mod ecc;
+pub mod hpke;
+pub mod kdf;
 pub mod mac;
 pub mod rsa;
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
 pub mod sha;
+pub mod shamir;
 mod sm;
+pub mod x509;
+pub use curve25519::*;
 pub use sm::*;
+
+#[inline]
+pub(crate) fn rand(bytes: &mut [u8]) {
+    cfg_if! {
+        if #[cfg(feature = "tcrypto")] {
+            use sgx_trts::rand::Rng;
+            Rng::new().fill_bytes(bytes);
+        } else {
+            use sgx_rand::{RdRand, Rng};
+            RdRand::new().unwrap().fill_bytes(bytes);
+        }
+    }
+}
@@ -23,14 +23,28 @@ use sgx_crypto_sys::*;
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::marker::ContiguousMemory;
 use sgx_types::memeq::ConstTimeEq;
-use sgx_types::types::{AlignMac256bit, HMacHandle, Mac256bit, MAC_256BIT_SIZE};
+use sgx_types::types::{AlignMac256bit, HMacHandle, Mac256bit, MAC_256BIT_SIZE, MAC_384BIT_SIZE};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum HashType {
     Sha256,
     Sm3,
+    Sha384,
 }
 
+impl HashType {
+    #[inline]
+    pub const fn mac_size(&self) -> usize {
+        match self {
+            HashType::Sha256 | HashType::Sm3 => MAC_256BIT_SIZE,
+            HashType::Sha384 => MAC_384BIT_SIZE,
+        }
+    }
+}
+
+// The methods returning a Mac256bit fail with InvalidParameter for
+// HashType::Sha384; its tag is taken through the slice methods, e.g.
+// `finalize_into`, with a buffer of `HashType::mac_size` bytes.
 pub struct HMac {
     hash_type: HashType,
     handle: HMacHandle,
@@ -44,26 +58,8 @@ impl HMac {
             SgxStatus::InvalidParameter
         );
 
-        let mut handle: HMacHandle = ptr::null_mut();
         let key = key.to_owned();
-        let status = match hash_type {
-            HashType::Sha256 => unsafe {
-                sgx_hmac_sha256_init(
-                    key.as_ptr(),
-                    key.len() as i32,
-                    &mut handle as *mut HMacHandle,
-                )
-            },
-            HashType::Sm3 => unsafe {
-                sgx_hmac_sm3_init(
-                    key.as_ptr(),
-                    key.len() as i32,
-                    &mut handle as *mut HMacHandle,
-                )
-            },
-        };
-
-        ensure!(status.is_success(), status);
+        let handle = Self::init(hash_type, &key)?;
         Ok(HMac {
             hash_type,
             handle,
@@ -71,6 +67,11 @@ impl HMac {
         })
     }
 
+    #[inline]
+    pub fn hash_type(&self) -> HashType {
+        self.hash_type
+    }
+
     pub fn update<T: ?Sized>(&mut self, data: &T) -> SgxResult
     where
         T: ContiguousMemory,
@@ -81,13 +82,11 @@ impl HMac {
             SgxStatus::InvalidParameter
         );
 
+        let data = (data as *const T).cast();
         let status = match self.hash_type {
-            HashType::Sha256 => unsafe {
-                sgx_hmac_sha256_update((data as *const T).cast(), size as i32, self.handle)
-            },
-            HashType::Sm3 => unsafe {
-                sgx_hmac_sm3_update((data as *const T).cast(), size as i32, self.handle)
-            },
+            HashType::Sha256 => unsafe { sgx_hmac_sha256_update(data, size as i32, self.handle) },
+            HashType::Sm3 => unsafe { sgx_hmac_sm3_update(data, size as i32, self.handle) },
+            HashType::Sha384 => unsafe { sgx_hmac_sha384_update(data, size as i32, self.handle) },
         };
         ensure!(status.is_success(), status);
         Ok(())
@@ -105,25 +104,8 @@ impl HMac {
         Ok(mac)
     }
 
-    pub fn finalize_into(self, mac: &mut Mac256bit) -> SgxResult {
-        let status = match self.hash_type {
-            HashType::Sha256 => unsafe {
-                sgx_hmac_sha256_final(
-                    (mac as *mut Mac256bit).cast(),
-                    MAC_256BIT_SIZE as i32,
-                    self.handle,
-                )
-            },
-            HashType::Sm3 => unsafe {
-                sgx_hmac_sm3_final(
-                    (mac as *mut Mac256bit).cast(),
-                    MAC_256BIT_SIZE as i32,
-                    self.handle,
-                )
-            },
-        };
-        ensure!(status.is_success(), status);
-        Ok(())
+    pub fn finalize_into(self, mac: &mut [u8]) -> SgxResult {
+        self.final_raw(mac)
     }
 
     pub fn finalize_reset(&mut self) -> SgxResult<Mac256bit> {
@@ -138,62 +120,22 @@ impl HMac {
         Ok(mac)
     }
 
-    pub fn finalize_into_reset(&mut self, mac: &mut Mac256bit) -> SgxResult {
-        match self.hash_type {
-            HashType::Sha256 => {
-                let status = unsafe {
-                    sgx_hmac_sha256_final(
-                        (mac as *mut Mac256bit).cast(),
-                        MAC_256BIT_SIZE as i32,
-                        self.handle,
-                    )
-                };
-                ensure!(status.is_success(), status);
-
-                let status = unsafe { sgx_hmac_sha256_close(self.handle) };
-                debug_assert!(status.is_success());
-
-                let status = unsafe {
-                    sgx_hmac_sha256_init(
-                        self.key.as_ptr(),
-                        self.key.len() as i32,
-                        &mut self.handle as *mut HMacHandle,
-                    )
-                };
-                ensure!(status.is_success(), status);
+    pub fn finalize_into_reset(&mut self, mac: &mut [u8]) -> SgxResult {
+        self.final_raw(mac)?;
 
-                Ok(())
-            }
-            HashType::Sm3 => {
-                let status = unsafe {
-                    sgx_hmac_sm3_final(
-                        (mac as *mut Mac256bit).cast(),
-                        MAC_256BIT_SIZE as i32,
-                        self.handle,
-                    )
-                };
-                ensure!(status.is_success(), status);
-
-                let status = unsafe { sgx_hmac_sm3_close(self.handle) };
-                debug_assert!(status.is_success());
-
-                let status = unsafe {
-                    sgx_hmac_sm3_init(
-                        self.key.as_ptr(),
-                        self.key.len() as i32,
-                        &mut self.handle as *mut HMacHandle,
-                    )
-                };
-                ensure!(status.is_success(), status);
-
-                Ok(())
-            }
-        }
+        Self::close(self.hash_type, self.handle);
+        self.handle = ptr::null_mut();
+        self.handle = Self::init(self.hash_type, &self.key)?;
+        Ok(())
     }
 
-    pub fn verify(self, mac: &Mac256bit) -> SgxResult {
-        let mac_result = self.finalize_align()?;
-        ensure!(&mac_result.mac.ct_eq(mac), SgxStatus::MacMismatch);
+    pub fn verify(self, mac: &[u8]) -> SgxResult {
+        let mut mac_result = [0_u8; MAC_384BIT_SIZE];
+        let mac_result = &mut mac_result[..self.hash_type.mac_size()];
+        ensure!(mac.len() == mac_result.len(), SgxStatus::MacMismatch);
+
+        self.final_raw(mac_result)?;
+        ensure!(&mac_result.ct_eq(mac), SgxStatus::MacMismatch);
         Ok(())
     }
 
@@ -223,7 +165,7 @@ impl HMac {
         key: &[u8],
         hash_type: HashType,
         data: &T,
-        mac: &mut Mac256bit,
+        mac: &mut [u8],
     ) -> SgxResult
     where
         T: ContiguousMemory,
@@ -237,40 +179,80 @@ impl HMac {
             (!key.is_empty() && key.len() <= i32::MAX as usize),
             SgxStatus::InvalidParameter
         );
+        ensure!(
+            mac.len() == hash_type.mac_size(),
+            SgxStatus::InvalidParameter
+        );
 
+        let data = (data as *const T).cast();
+        let (key_ptr, key_len) = (key.as_ptr(), key.len() as i32);
+        let (mac_ptr, mac_len) = (mac.as_mut_ptr(), mac.len() as i32);
         let status = match hash_type {
             HashType::Sha256 => unsafe {
-                sgx_hmac_sha256_msg(
-                    (data as *const T).cast(),
-                    size as i32,
-                    key.as_ptr(),
-                    key.len() as i32,
-                    (mac as *mut Mac256bit).cast(),
-                    MAC_256BIT_SIZE as i32,
-                )
+                sgx_hmac_sha256_msg(data, size as i32, key_ptr, key_len, mac_ptr, mac_len)
             },
             HashType::Sm3 => unsafe {
-                sgx_hmac_sm3_msg(
-                    (data as *const T).cast(),
-                    size as i32,
-                    key.as_ptr(),
-                    key.len() as i32,
-                    (mac as *mut Mac256bit).cast(),
-                    MAC_256BIT_SIZE as i32,
-                )
+                sgx_hmac_sm3_msg(data, size as i32, key_ptr, key_len, mac_ptr, mac_len)
+            },
+            HashType::Sha384 => unsafe {
+                sgx_hmac_sha384_msg(data, size as i32, key_ptr, key_len, mac_ptr, mac_len)
             },
         };
         ensure!(status.is_success(), status);
 
         Ok(())
     }
+
+    fn init(hash_type: HashType, key: &[u8]) -> SgxResult<HMacHandle> {
+        let mut handle: HMacHandle = ptr::null_mut();
+        let (key_ptr, key_len) = (key.as_ptr(), key.len() as i32);
+        let status = match hash_type {
+            HashType::Sha256 => unsafe {
+                sgx_hmac_sha256_init(key_ptr, key_len, &mut handle as *mut HMacHandle)
+            },
+            HashType::Sm3 => unsafe {
+                sgx_hmac_sm3_init(key_ptr, key_len, &mut handle as *mut HMacHandle)
+            },
+            HashType::Sha384 => unsafe {
+                sgx_hmac_sha384_init(key_ptr, key_len, &mut handle as *mut HMacHandle)
+            },
+        };
+        ensure!(status.is_success(), status);
+        Ok(handle)
+    }
+
+    fn final_raw(&self, mac: &mut [u8]) -> SgxResult {
+        ensure!(
+            mac.len() == self.hash_type.mac_size(),
+            SgxStatus::InvalidParameter
+        );
+
+        let (mac_ptr, mac_len) = (mac.as_mut_ptr(), mac.len() as i32);
+        let status = match self.hash_type {
+            HashType::Sha256 => unsafe { sgx_hmac_sha256_final(mac_ptr, mac_len, self.handle) },
+            HashType::Sm3 => unsafe { sgx_hmac_sm3_final(mac_ptr, mac_len, self.handle) },
+            HashType::Sha384 => unsafe { sgx_hmac_sha384_final(mac_ptr, mac_len, self.handle) },
+        };
+        ensure!(status.is_success(), status);
+        Ok(())
+    }
+
+    fn close(hash_type: HashType, handle: HMacHandle) {
+        if handle.is_null() {
+            return;
+        }
+        let status = match hash_type {
+            HashType::Sha256 => unsafe { sgx_hmac_sha256_close(handle) },
+            HashType::Sm3 => unsafe { sgx_hmac_sm3_close(handle) },
+            HashType::Sha384 => unsafe { sgx_hmac_sha384_close(handle) },
+        };
+        debug_assert!(status.is_success());
+    }
 }
 
 impl Drop for HMac {
     fn drop(&mut self) {
-        match self.hash_type {
-            HashType::Sha256 => unsafe { sgx_hmac_sha256_close(self.handle) },
-            HashType::Sm3 => unsafe { sgx_hmac_sm3_close(self.handle) },
-        };
+        Self::close(self.hash_type, self.handle);
+        self.key.fill(0);
     }
 }
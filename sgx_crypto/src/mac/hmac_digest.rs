@@ -0,0 +1,116 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::sha::{Digest, Sha3_256, Sha3_512, Sha512, Sha512_256};
+use alloc::vec::Vec;
+use core::mem;
+use core::slice;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::ContiguousMemory;
+use sgx_types::memeq::ConstTimeEq;
+
+pub type HMacSha512 = HMacDigest<Sha512>;
+pub type HMacSha512_256 = HMacDigest<Sha512_256>;
+pub type HMacSha3_256 = HMacDigest<Sha3_256>;
+pub type HMacSha3_512 = HMacDigest<Sha3_512>;
+
+// HMAC (RFC 2104) over any `Digest`, for the hash functions that have no
+// HMAC in the IPP-backed `HMac`.
+pub struct HMacDigest<D: Digest> {
+    inner: D,
+    ipad: Vec<u8>,
+    opad: Vec<u8>,
+}
+
+impl<D: Digest> HMacDigest<D> {
+    pub fn new(key: &[u8]) -> SgxResult<HMacDigest<D>> {
+        ensure!(
+            (!key.is_empty() && key.len() <= i32::MAX as usize),
+            SgxStatus::InvalidParameter
+        );
+
+        let mut block = vec![0_u8; D::BLOCK_SIZE];
+        if key.len() > D::BLOCK_SIZE {
+            let mut hash = D::new()?;
+            hash.update(key)?;
+            block[..D::OUTPUT_SIZE].copy_from_slice(&hash.finalize()?);
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let ipad: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+        let opad: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+        block.fill(0);
+
+        let mut inner = D::new()?;
+        inner.update(&ipad)?;
+        Ok(HMacDigest { inner, ipad, opad })
+    }
+
+    pub fn update<T: ?Sized>(&mut self, data: &T) -> SgxResult
+    where
+        T: ContiguousMemory,
+    {
+        let size = mem::size_of_val(data);
+        ensure!(
+            (size > 0 && size < i32::MAX as usize),
+            SgxStatus::InvalidParameter
+        );
+
+        let data = unsafe { slice::from_raw_parts((data as *const T).cast::<u8>(), size) };
+        self.inner.update(data)
+    }
+
+    pub fn finalize(mut self) -> SgxResult<D::Output> {
+        self.finalize_reset()
+    }
+
+    pub fn finalize_reset(&mut self) -> SgxResult<D::Output> {
+        let inner_hash = self.inner.finalize_reset()?;
+        self.inner.update(&self.ipad)?;
+
+        let mut outer = D::new()?;
+        outer.update(&self.opad)?;
+        outer.update(&inner_hash)?;
+        outer.finalize()
+    }
+
+    pub fn verify(self, mac: &[u8]) -> SgxResult {
+        let mac_result = self.finalize()?;
+        ensure!(
+            mac.len() == D::OUTPUT_SIZE && mac_result.ct_eq(mac),
+            SgxStatus::MacMismatch
+        );
+        Ok(())
+    }
+
+    pub fn hmac<T: ?Sized>(key: &[u8], data: &T) -> SgxResult<D::Output>
+    where
+        T: ContiguousMemory,
+    {
+        let mut hmac = Self::new(key)?;
+        hmac.update(data)?;
+        hmac.finalize()
+    }
+}
+
+impl<D: Digest> Drop for HMacDigest<D> {
+    fn drop(&mut self) {
+        self.ipad.fill(0);
+        self.opad.fill(0);
+    }
+}
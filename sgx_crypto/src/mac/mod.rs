@@ -17,6 +17,11 @@
 
 mod cmac;
 mod hmac;
+mod hmac_digest;
 
 pub use cmac::*;
 pub use hmac::*;
+pub use hmac_digest::*;
+
+#[cfg(feature = "unit_test")]
+mod tests;
@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::HMacDigest;
+use crate::sha::{Digest, Sha3_256, Sha3_512, Sha512, Sha512_256};
+use crate::sm3::Sm3;
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// The RFC 4231 test cases 1, 2, 6 and 7: short keys, and keys and data
+// longer than a block.
+fn cases() -> [(Vec<u8>, &'static [u8]); 4] {
+    [
+        (vec![0x0b; 20], b"Hi There"),
+        (b"Jefe".to_vec(), b"what do ya want for nothing?"),
+        (
+            vec![0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        ),
+        (
+            vec![0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger \
+              than block-size data. The key needs to be hashed before being \
+              used by the HMAC algorithm.",
+        ),
+    ]
+}
+
+fn check<D: Digest>(expected: [&str; 4]) {
+    for ((key, data), expected) in cases().iter().zip(expected) {
+        let expected = decode(expected);
+        assert_eq!(
+            HMacDigest::<D>::hmac(key, *data).unwrap().to_vec(),
+            expected
+        );
+
+        let mut hmac = HMacDigest::<D>::new(key).unwrap();
+        for chunk in data.chunks(5) {
+            hmac.update(chunk).unwrap();
+        }
+        assert_eq!(hmac.finalize_reset().unwrap().to_vec(), expected);
+        // the MAC starts over after a reset
+        hmac.update(*data).unwrap();
+        hmac.verify(&expected).unwrap();
+
+        let mut tampered = expected.clone();
+        tampered[0] ^= 1;
+        let mut hmac = HMacDigest::<D>::new(key).unwrap();
+        hmac.update(*data).unwrap();
+        assert_eq!(hmac.verify(&tampered), Err(SgxStatus::MacMismatch));
+
+        let mut hmac = HMacDigest::<D>::new(key).unwrap();
+        hmac.update(*data).unwrap();
+        assert_eq!(
+            hmac.verify(&expected[..expected.len() - 1]),
+            Err(SgxStatus::MacMismatch)
+        );
+    }
+}
+
+#[test_case]
+fn hmac_sha512_rfc4231() {
+    check::<Sha512>([
+        "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+         daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+        "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+         9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+        "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+         6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+        "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944\
+         b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+    ]);
+}
+
+// The RFC 4231 inputs, with the MACs computed by Python's hmac module.
+#[test_case]
+fn hmac_sha512_256() {
+    check::<Sha512_256>([
+        "9f9126c3d9c3c330d760425ca8a217e31feae31bfe70196ff81642b868402eab",
+        "6df7b24630d5ccb2ee335407081a87188c221489768fa2020513b2d593359456",
+        "87123c45f7c537a404f8f47cdbedda1fc9bec60eeb971982ce7ef10e774e6539",
+        "6ea83f8e7315072c0bdaa33b93a26fc1659974637a9db8a887d06c05a7f35a66",
+    ]);
+}
+
+#[test_case]
+fn hmac_sha3_256() {
+    check::<Sha3_256>([
+        "ba85192310dffa96e2a3a40e69774351140bb7185e1202cdcc917589f95e16bb",
+        "c7d4072e788877ae3596bbb0da73b887c9171f93095b294ae857fbe2645e1ba5",
+        "ed73a374b96c005235f948032f09674a58c0ce555cfc1f223b02356560312c3b",
+        "65c5b06d4c3de32a7aef8763261e49adb6e2293ec8e7c61e8de61701fc63e123",
+    ]);
+}
+
+#[test_case]
+fn hmac_sha3_512() {
+    check::<Sha3_512>([
+        "eb3fbd4b2eaab8f5c504bd3a41465aacec15770a7cabac531e482f860b5ec7ba\
+         47ccb2c6f2afce8f88d22b6dc61380f23a668fd3888bb80537c0a0b86407689e",
+        "5a4bfeab6166427c7a3647b747292b8384537cdb89afb3bf5665e4c5e709350b\
+         287baec921fd7ca0ee7a0c31d022a95e1fc92ba9d77df883960275beb4e62024",
+        "00f751a9e50695b090ed6911a4b65524951cdc15a73a5d58bb55215ea2cd839a\
+         c79d2b44a39bafab27e83fde9e11f6340b11d991b1b91bf2eee7fc872426c3a4",
+        "38a456a004bd10d32c9ab8336684112862c3db61adcca31829355eaf46fd5c73\
+         d06a1f0d13fec9a652fb3811b577b1b1d1b9789f97ae5b83c6f44dfcf1d67eba",
+    ]);
+}
+
+#[test_case]
+fn hmac_sm3() {
+    check::<Sm3>([
+        "51b00d1fb49832bfb01c3ce27848e59f871d9ba938dc563b338ca964755cce70",
+        "2e87f1d16862e6d964b50a5200bf2b10b764faa9680a296a2405f24bec39f882",
+        "b4fd844e13342002f0b2e0690ea7741f1497d993a70494cea601e657bedf67a0",
+        "5acbdeb0c8c1ef3a99088fe51c0a1d5f4e1c175935f016aee74eb8056db18acb",
+    ]);
+}
+
+#[test_case]
+fn hmac_empty_key() {
+    assert_eq!(
+        HMacDigest::<Sha512>::new(&[]).err(),
+        Some(SgxStatus::InvalidParameter)
+    );
+}
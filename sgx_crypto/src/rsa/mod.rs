@@ -15,13 +15,29 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use alloc::vec::Vec;
+use core::cmp;
+use core::mem;
+use core::ptr;
+use sgx_crypto_sys::*;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::ContiguousMemory;
+use sgx_types::types::{RsaKey, RsaResult};
+
+#[cfg(any(feature = "tserialize", feature = "userialize"))]
+use sgx_serialize::{Deserialize, Serialize};
+
+pub use sgx_types::types::RsaHash;
+
 mod rsa2048;
 mod rsa3072;
+mod rsa4096;
 pub use rsa2048::*;
 pub use rsa3072::*;
+pub use rsa4096::*;
 
-#[cfg(any(feature = "tserialize", feature = "userialize"))]
-use sgx_serialize::{Deserialize, Serialize};
+#[cfg(feature = "unit_test")]
+mod tests;
 
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -33,3 +49,204 @@ enum RsaPrivateType {
     Type1 = 1,
     Type2 = 2,
 }
+
+// Padding operations shared by all key sizes. The handles come from the
+// create() methods of the key types and are released by the caller.
+
+fn data_size<T: ?Sized + ContiguousMemory>(data: &T) -> SgxResult<usize> {
+    let size = mem::size_of_val(data);
+    ensure!(
+        (size > 0 && size < i32::MAX as usize),
+        SgxStatus::InvalidParameter
+    );
+    Ok(size)
+}
+
+fn verify_result(status: SgxStatus, rsa_result: RsaResult) -> SgxResult<bool> {
+    ensure!(status.is_success(), status);
+    match rsa_result {
+        RsaResult::Valid => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+fn sign_pkcs1v15<T: ?Sized + ContiguousMemory>(
+    privkey: RsaKey,
+    pubkey: Option<RsaKey>,
+    hash: RsaHash,
+    data: &T,
+    signature: &mut [u8],
+) -> SgxResult {
+    let size = data_size(data)?;
+    let status = unsafe {
+        sgx_rsa_sign_pkcs1_v15(
+            privkey,
+            pubkey.unwrap_or(ptr::null_mut()),
+            hash,
+            (data as *const T).cast(),
+            size,
+            signature.as_mut_ptr(),
+            signature.len(),
+        )
+    };
+
+    ensure!(status.is_success(), status);
+    Ok(())
+}
+
+fn verify_pkcs1v15<T: ?Sized + ContiguousMemory>(
+    pubkey: RsaKey,
+    hash: RsaHash,
+    data: &T,
+    signature: &[u8],
+) -> SgxResult<bool> {
+    let size = data_size(data)?;
+    let mut rsa_result = RsaResult::InvalidSignature;
+    let status = unsafe {
+        sgx_rsa_verify_pkcs1_v15(
+            pubkey,
+            hash,
+            (data as *const T).cast(),
+            size,
+            signature.as_ptr(),
+            signature.len(),
+            &mut rsa_result as *mut RsaResult,
+        )
+    };
+    verify_result(status, rsa_result)
+}
+
+// RFC 7518 PS256/PS384/PS512 use a salt as long as the digest.
+fn sign_pss<T: ?Sized + ContiguousMemory>(
+    privkey: RsaKey,
+    pubkey: Option<RsaKey>,
+    hash: RsaHash,
+    salt_len: usize,
+    data: &T,
+    signature: &mut [u8],
+) -> SgxResult {
+    let size = data_size(data)?;
+    let status = unsafe {
+        sgx_rsa_sign_pss(
+            privkey,
+            pubkey.unwrap_or(ptr::null_mut()),
+            hash,
+            salt_len,
+            (data as *const T).cast(),
+            size,
+            signature.as_mut_ptr(),
+            signature.len(),
+        )
+    };
+
+    ensure!(status.is_success(), status);
+    Ok(())
+}
+
+// The salt length is recovered from the signature, so any is accepted.
+fn verify_pss<T: ?Sized + ContiguousMemory>(
+    pubkey: RsaKey,
+    hash: RsaHash,
+    data: &T,
+    signature: &[u8],
+) -> SgxResult<bool> {
+    let size = data_size(data)?;
+    let mut rsa_result = RsaResult::InvalidSignature;
+    let status = unsafe {
+        sgx_rsa_verify_pss(
+            pubkey,
+            hash,
+            (data as *const T).cast(),
+            size,
+            signature.as_ptr(),
+            signature.len(),
+            &mut rsa_result as *mut RsaResult,
+        )
+    };
+    verify_result(status, rsa_result)
+}
+
+// Splits the plaintext into blocks of at most k - 2 * hLen - 2 bytes, each
+// encrypted to one k-byte block, as the SHA-256 encrypt() methods do.
+fn encrypt_oaep(
+    pubkey: RsaKey,
+    key_size: usize,
+    hash: RsaHash,
+    label: &[u8],
+    plaintext: &[u8],
+) -> SgxResult<Vec<u8>> {
+    ensure!(!plaintext.is_empty(), SgxStatus::InvalidParameter);
+
+    let bs = key_size;
+    let bs_plain = bs - 2 * hash.hash_size() - 2;
+    let count = (plaintext.len() + bs_plain - 1) / bs_plain;
+    let mut ciphertext = vec![0_u8; bs * count];
+
+    for i in 0..count {
+        let cipher_slice = &mut ciphertext[i * bs..i * bs + bs];
+        let mut cipher_len = bs;
+        let plain_slice =
+            &plaintext[i * bs_plain..cmp::min(i * bs_plain + bs_plain, plaintext.len())];
+
+        let status = unsafe {
+            sgx_rsa_pub_encrypt_oaep(
+                pubkey,
+                hash,
+                label.as_ptr(),
+                label.len(),
+                cipher_slice.as_mut_ptr(),
+                &mut cipher_len as *mut usize,
+                plain_slice.as_ptr(),
+                plain_slice.len(),
+            )
+        };
+        ensure!(status.is_success(), status);
+    }
+
+    Ok(ciphertext)
+}
+
+fn decrypt_oaep(
+    privkey: RsaKey,
+    key_size: usize,
+    hash: RsaHash,
+    label: &[u8],
+    ciphertext: &[u8],
+) -> SgxResult<Vec<u8>> {
+    let bs = key_size;
+    ensure!(
+        !ciphertext.is_empty() && ciphertext.len() % bs == 0,
+        SgxStatus::InvalidParameter
+    );
+
+    let mut plaintext: Vec<u8> = Vec::new();
+    let mut plain = vec![0_u8; bs];
+    let plain_slice = plain.as_mut_slice();
+
+    for cipher_slice in ciphertext.chunks(bs) {
+        let mut plain_len = bs;
+        plain_slice.fill(0);
+
+        let status = unsafe {
+            sgx_rsa_priv_decrypt_oaep(
+                privkey,
+                hash,
+                label.as_ptr(),
+                label.len(),
+                plain_slice.as_mut_ptr(),
+                &mut plain_len as *mut usize,
+                cipher_slice.as_ptr(),
+                cipher_slice.len(),
+            )
+        };
+        if !status.is_success() {
+            plain_slice.fill(0);
+            plaintext.fill(0);
+            bail!(status);
+        }
+        plaintext.extend_from_slice(&plain_slice[..plain_len]);
+    }
+    plain_slice.fill(0);
+
+    Ok(plaintext)
+}
@@ -15,7 +15,12 @@
 // specific language governing permissions and limitations
 // under the License..
 
-use crate::rsa::RsaPrivateType;
+use crate::der::rsa::{
+    decode_private_key, decode_public_key, encode_private_key, encode_public_key,
+    RsaPrivateComponents, RsaPrivateComponentsMut,
+};
+use crate::der::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use crate::rsa::{RsaHash, RsaPrivateType};
 use alloc::vec::Vec;
 use core::cmp;
 use core::convert::{From, TryInto};
@@ -154,6 +159,73 @@ impl Rsa3072KeyPair {
         self.public_key().verify(data, signature)
     }
 
+    #[inline]
+    pub fn encrypt_oaep(
+        &self,
+        plaintext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        self.public_key().encrypt_oaep(plaintext, hash, label)
+    }
+
+    #[inline]
+    pub fn decrypt_oaep(
+        &self,
+        ciphertext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        self.private_key().decrypt_oaep(ciphertext, hash, label)
+    }
+
+    #[inline]
+    pub fn sign_pkcs1v15<T: ?Sized>(&self, data: &T, hash: RsaHash) -> SgxResult<Rsa3072Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private_key().sign_pkcs1v15(data, hash)
+    }
+
+    #[inline]
+    pub fn verify_pkcs1v15<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa3072Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        self.public_key().verify_pkcs1v15(data, signature, hash)
+    }
+
+    #[inline]
+    pub fn sign_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        hash: RsaHash,
+        salt_len: usize,
+    ) -> SgxResult<Rsa3072Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private_key().sign_pss(data, hash, salt_len)
+    }
+
+    #[inline]
+    pub fn verify_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa3072Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        self.public_key().verify_pss(data, signature, hash)
+    }
+
     #[inline]
     pub fn clear(&mut self) {
         *self = Default::default();
@@ -415,6 +487,52 @@ impl Rsa3072PrivateKey {
         Ok(signature)
     }
 
+    pub fn decrypt_oaep(
+        &self,
+        ciphertext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        let privkey = self.create()?;
+        let result = super::decrypt_oaep(privkey, RSA3072_KEY_SIZE, hash, label, ciphertext);
+        let _ = Self::free(privkey);
+        result
+    }
+
+    pub fn sign_pkcs1v15<T: ?Sized>(&self, data: &T, hash: RsaHash) -> SgxResult<Rsa3072Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let privkey = self.create()?;
+        let mut signature = Rsa3072Signature::default();
+        let result = super::sign_pkcs1v15(privkey, None, hash, data, &mut signature.signature);
+        let _ = Self::free(privkey);
+        result.map(|_| signature)
+    }
+
+    pub fn sign_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        hash: RsaHash,
+        salt_len: usize,
+    ) -> SgxResult<Rsa3072Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let privkey = self.create()?;
+        let mut signature = Rsa3072Signature::default();
+        let result = super::sign_pss(
+            privkey,
+            None,
+            hash,
+            salt_len,
+            data,
+            &mut signature.signature,
+        );
+        let _ = Self::free(privkey);
+        result.map(|_| signature)
+    }
+
     #[inline]
     pub fn export_public_key(&self) -> Rsa3072PublicKey {
         Rsa3072PublicKey {
@@ -645,6 +763,48 @@ impl Rsa3072PublicKey {
         }
     }
 
+    pub fn encrypt_oaep(
+        &self,
+        plaintext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        let pubkey = self.create()?;
+        let result = super::encrypt_oaep(pubkey, RSA3072_KEY_SIZE, hash, label, plaintext);
+        let _ = Self::free(pubkey);
+        result
+    }
+
+    pub fn verify_pkcs1v15<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa3072Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        let pubkey = self.create()?;
+        let result = super::verify_pkcs1v15(pubkey, hash, data, &signature.signature);
+        let _ = Self::free(pubkey);
+        result
+    }
+
+    pub fn verify_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa3072Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        let pubkey = self.create()?;
+        let result = super::verify_pss(pubkey, hash, data, &signature.signature);
+        let _ = Self::free(pubkey);
+        result
+    }
+
     #[inline]
     pub fn from_private_key(key: &Rsa3072PrivateKey) -> Rsa3072PublicKey {
         key.export_public_key()
@@ -745,3 +905,66 @@ impl_from_array! { Rsa3072PublicKey; }
 unsafe impl ContiguousMemory for Rsa3072PublicKey {}
 
 unsafe impl BytewiseEquality for Rsa3072PublicKey {}
+
+impl EncodePublicKey for Rsa3072PublicKey {
+    fn to_public_key_der(&self) -> SgxResult<Vec<u8>> {
+        Ok(encode_public_key(&self.n, &self.e))
+    }
+}
+
+impl DecodePublicKey for Rsa3072PublicKey {
+    fn from_public_key_der(der: &[u8]) -> SgxResult<Rsa3072PublicKey> {
+        let mut key = Rsa3072PublicKey::default();
+        decode_public_key(der, &mut key.n, &mut key.e)?;
+        ensure!(
+            key.n[RSA3072_KEY_SIZE - 1] != 0,
+            SgxStatus::InvalidParameter
+        );
+        Ok(key)
+    }
+}
+
+impl EncodePrivateKey for Rsa3072PrivateKey {
+    fn to_pkcs8_der(&self) -> SgxResult<Vec<u8>> {
+        // Type1 keys lack the CRT components required by PKCS#1.
+        ensure!(
+            self.privtype == RsaPrivateType::Type2,
+            SgxStatus::InvalidParameter
+        );
+
+        Ok(encode_private_key(&RsaPrivateComponents {
+            n: &self.n,
+            e: &self.e,
+            d: &self.d,
+            p: &self.p,
+            q: &self.q,
+            dmp1: &self.dmp1,
+            dmq1: &self.dmq1,
+            iqmp: &self.iqmp,
+        }))
+    }
+}
+
+impl DecodePrivateKey for Rsa3072PrivateKey {
+    fn from_pkcs8_der(der: &[u8]) -> SgxResult<Rsa3072PrivateKey> {
+        let mut key = Rsa3072PrivateKey::default();
+        let result = decode_private_key(
+            der,
+            RsaPrivateComponentsMut {
+                n: &mut key.n,
+                e: &mut key.e,
+                d: &mut key.d,
+                p: &mut key.p,
+                q: &mut key.q,
+                dmp1: &mut key.dmp1,
+                dmq1: &mut key.dmq1,
+                iqmp: &mut key.iqmp,
+            },
+        );
+        if result.is_err() || key.n[RSA3072_KEY_SIZE - 1] == 0 {
+            key.clear();
+            bail!(SgxStatus::InvalidParameter);
+        }
+        Ok(key)
+    }
+}
@@ -0,0 +1,872 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::der::rsa::{
+    decode_private_key, decode_public_key, encode_private_key, encode_public_key,
+    RsaPrivateComponents, RsaPrivateComponentsMut,
+};
+use crate::der::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use crate::rsa::{RsaHash, RsaPrivateType};
+use alloc::vec::Vec;
+use core::convert::{From, TryInto};
+use core::ptr;
+use sgx_crypto_sys::*;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::types::{
+    Rsa4096Key, Rsa4096Param, Rsa4096PubKey, Rsa4096Signature, RsaKey, RsaKeyType,
+};
+use sgx_types::types::{RSA4096_KEY_SIZE, RSA4096_PRI_EXP_SIZE, RSA4096_PUB_EXP_SIZE};
+
+#[cfg(any(feature = "tserialize", feature = "userialize"))]
+use sgx_serialize::{Deserialize, Serialize};
+
+const RSA4096_DEFAULT_E: [u8; RSA4096_PUB_EXP_SIZE] = [0x01, 0x00, 0x00, 0x01]; // 16777217
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Rsa4096KeyPair {
+    n: [u8; RSA4096_KEY_SIZE],
+    d: [u8; RSA4096_PRI_EXP_SIZE],
+    e: [u8; RSA4096_PUB_EXP_SIZE],
+    p: [u8; RSA4096_KEY_SIZE / 2],
+    q: [u8; RSA4096_KEY_SIZE / 2],
+    dmp1: [u8; RSA4096_KEY_SIZE / 2],
+    dmq1: [u8; RSA4096_KEY_SIZE / 2],
+    iqmp: [u8; RSA4096_KEY_SIZE / 2],
+    privtype: RsaPrivateType,
+}
+
+impl Rsa4096KeyPair {
+    pub fn create() -> SgxResult<Rsa4096KeyPair> {
+        let mut key = Rsa4096KeyPair::default();
+        let status = unsafe {
+            sgx_create_rsa_key_pair(
+                RSA4096_KEY_SIZE as i32,
+                RSA4096_PUB_EXP_SIZE as i32,
+                key.n.as_mut_ptr(),
+                key.d.as_mut_ptr(),
+                key.e.as_mut_ptr(),
+                key.p.as_mut_ptr(),
+                key.q.as_mut_ptr(),
+                key.dmp1.as_mut_ptr(),
+                key.dmq1.as_mut_ptr(),
+                key.iqmp.as_mut_ptr(),
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(key)
+    }
+
+    pub fn create_with_e(e: u32) -> SgxResult<Rsa4096KeyPair> {
+        let mut key = Rsa4096KeyPair {
+            e: e.to_le_bytes(),
+            ..Default::default()
+        };
+        let status = unsafe {
+            sgx_create_rsa_key_pair(
+                RSA4096_KEY_SIZE as i32,
+                RSA4096_PUB_EXP_SIZE as i32,
+                key.n.as_mut_ptr(),
+                key.d.as_mut_ptr(),
+                key.e.as_mut_ptr(),
+                key.p.as_mut_ptr(),
+                key.q.as_mut_ptr(),
+                key.dmp1.as_mut_ptr(),
+                key.dmq1.as_mut_ptr(),
+                key.iqmp.as_mut_ptr(),
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(key)
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> Rsa4096PublicKey {
+        Rsa4096PublicKey {
+            n: self.n,
+            e: self.e,
+        }
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> Rsa4096PrivateKey {
+        Rsa4096PrivateKey {
+            n: self.n,
+            d: self.d,
+            e: self.e,
+            p: self.p,
+            q: self.q,
+            dmp1: self.dmp1,
+            dmq1: self.dmq1,
+            iqmp: self.iqmp,
+            privtype: self.privtype,
+        }
+    }
+
+    #[inline]
+    pub fn encrypt(&self, plaintext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.public_key().encrypt(plaintext)
+    }
+
+    #[inline]
+    pub fn decrypt(&self, ciphertext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.private_key().decrypt(ciphertext)
+    }
+
+    #[inline]
+    pub fn sign<T: ?Sized>(&self, data: &T) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private_key().sign(data)
+    }
+
+    #[inline]
+    pub fn sign_and_verify<T: ?Sized>(&self, data: &T) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private_key().sign_and_verify(&self.public_key(), data)
+    }
+
+    #[inline]
+    pub fn verify<T: ?Sized>(&self, data: &T, signature: &Rsa4096Signature) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        self.public_key().verify(data, signature)
+    }
+
+    #[inline]
+    pub fn encrypt_oaep(
+        &self,
+        plaintext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        self.public_key().encrypt_oaep(plaintext, hash, label)
+    }
+
+    #[inline]
+    pub fn decrypt_oaep(
+        &self,
+        ciphertext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        self.private_key().decrypt_oaep(ciphertext, hash, label)
+    }
+
+    #[inline]
+    pub fn sign_pkcs1v15<T: ?Sized>(&self, data: &T, hash: RsaHash) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private_key().sign_pkcs1v15(data, hash)
+    }
+
+    #[inline]
+    pub fn verify_pkcs1v15<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa4096Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        self.public_key().verify_pkcs1v15(data, signature, hash)
+    }
+
+    #[inline]
+    pub fn sign_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        hash: RsaHash,
+        salt_len: usize,
+    ) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.private_key().sign_pss(data, hash, salt_len)
+    }
+
+    #[inline]
+    pub fn verify_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa4096Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        self.public_key().verify_pss(data, signature, hash)
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+}
+
+impl Default for Rsa4096KeyPair {
+    fn default() -> Self {
+        Rsa4096KeyPair {
+            n: [0; RSA4096_KEY_SIZE],
+            d: [0; RSA4096_PRI_EXP_SIZE],
+            e: RSA4096_DEFAULT_E,
+            p: [0; RSA4096_KEY_SIZE / 2],
+            q: [0; RSA4096_KEY_SIZE / 2],
+            dmp1: [0; RSA4096_KEY_SIZE / 2],
+            dmq1: [0; RSA4096_KEY_SIZE / 2],
+            iqmp: [0; RSA4096_KEY_SIZE / 2],
+            privtype: RsaPrivateType::Type2,
+        }
+    }
+}
+
+impl From<Rsa4096Param> for Rsa4096KeyPair {
+    fn from(param: Rsa4096Param) -> Rsa4096KeyPair {
+        Rsa4096KeyPair {
+            n: param.n,
+            d: param.d,
+            e: param.e,
+            p: param.p,
+            q: param.q,
+            dmp1: param.dmp1,
+            dmq1: param.dmq1,
+            iqmp: param.iqmp,
+            privtype: RsaPrivateType::Type2,
+        }
+    }
+}
+
+impl From<&Rsa4096Param> for Rsa4096KeyPair {
+    fn from(param: &Rsa4096Param) -> Rsa4096KeyPair {
+        Rsa4096KeyPair {
+            n: param.n,
+            d: param.d,
+            e: param.e,
+            p: param.p,
+            q: param.q,
+            dmp1: param.dmp1,
+            dmq1: param.dmq1,
+            iqmp: param.iqmp,
+            privtype: RsaPrivateType::Type2,
+        }
+    }
+}
+
+impl From<Rsa4096Key> for Rsa4096KeyPair {
+    fn from(key_pair: Rsa4096Key) -> Rsa4096KeyPair {
+        Rsa4096KeyPair {
+            n: key_pair.modulus,
+            d: key_pair.d,
+            e: key_pair.e,
+            p: [0; RSA4096_KEY_SIZE / 2],
+            q: [0; RSA4096_KEY_SIZE / 2],
+            dmp1: [0; RSA4096_KEY_SIZE / 2],
+            dmq1: [0; RSA4096_KEY_SIZE / 2],
+            iqmp: [0; RSA4096_KEY_SIZE / 2],
+            privtype: RsaPrivateType::Type1,
+        }
+    }
+}
+
+impl From<&Rsa4096Key> for Rsa4096KeyPair {
+    fn from(key_pair: &Rsa4096Key) -> Rsa4096KeyPair {
+        Rsa4096KeyPair {
+            n: key_pair.modulus,
+            d: key_pair.d,
+            e: key_pair.e,
+            p: [0; RSA4096_KEY_SIZE / 2],
+            q: [0; RSA4096_KEY_SIZE / 2],
+            dmp1: [0; RSA4096_KEY_SIZE / 2],
+            dmq1: [0; RSA4096_KEY_SIZE / 2],
+            iqmp: [0; RSA4096_KEY_SIZE / 2],
+            privtype: RsaPrivateType::Type1,
+        }
+    }
+}
+
+impl From<(Rsa4096PrivateKey, Rsa4096PublicKey)> for Rsa4096KeyPair {
+    fn from(key_pair: (Rsa4096PrivateKey, Rsa4096PublicKey)) -> Rsa4096KeyPair {
+        Rsa4096KeyPair {
+            n: key_pair.0.n,
+            d: key_pair.0.d,
+            e: key_pair.0.e,
+            p: key_pair.0.p,
+            q: key_pair.0.q,
+            dmp1: key_pair.0.dmp1,
+            dmq1: key_pair.0.dmq1,
+            iqmp: key_pair.0.iqmp,
+            privtype: key_pair.0.privtype,
+        }
+    }
+}
+
+impl From<Rsa4096KeyPair> for (Rsa4096PrivateKey, Rsa4096PublicKey) {
+    fn from(key_pair: Rsa4096KeyPair) -> (Rsa4096PrivateKey, Rsa4096PublicKey) {
+        (
+            Rsa4096PrivateKey {
+                n: key_pair.n,
+                d: key_pair.d,
+                e: key_pair.e,
+                p: key_pair.p,
+                q: key_pair.q,
+                dmp1: key_pair.dmp1,
+                dmq1: key_pair.dmq1,
+                iqmp: key_pair.iqmp,
+                privtype: key_pair.privtype,
+            },
+            Rsa4096PublicKey {
+                n: key_pair.n,
+                e: key_pair.e,
+            },
+        )
+    }
+}
+
+impl From<&Rsa4096KeyPair> for (Rsa4096PrivateKey, Rsa4096PublicKey) {
+    fn from(key_pair: &Rsa4096KeyPair) -> (Rsa4096PrivateKey, Rsa4096PublicKey) {
+        (
+            Rsa4096PrivateKey {
+                n: key_pair.n,
+                d: key_pair.d,
+                e: key_pair.e,
+                p: key_pair.p,
+                q: key_pair.q,
+                dmp1: key_pair.dmp1,
+                dmq1: key_pair.dmq1,
+                iqmp: key_pair.iqmp,
+                privtype: key_pair.privtype,
+            },
+            Rsa4096PublicKey {
+                n: key_pair.n,
+                e: key_pair.e,
+            },
+        )
+    }
+}
+
+impl_asref_array! { Rsa4096KeyPair; }
+impl_from_array! { Rsa4096KeyPair; }
+
+unsafe impl ContiguousMemory for Rsa4096KeyPair {}
+
+unsafe impl BytewiseEquality for Rsa4096KeyPair {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Rsa4096PrivateKey {
+    n: [u8; RSA4096_KEY_SIZE],
+    d: [u8; RSA4096_PRI_EXP_SIZE],
+    e: [u8; RSA4096_PUB_EXP_SIZE],
+    p: [u8; RSA4096_KEY_SIZE / 2],
+    q: [u8; RSA4096_KEY_SIZE / 2],
+    dmp1: [u8; RSA4096_KEY_SIZE / 2],
+    dmq1: [u8; RSA4096_KEY_SIZE / 2],
+    iqmp: [u8; RSA4096_KEY_SIZE / 2],
+    privtype: RsaPrivateType,
+}
+
+impl Rsa4096PrivateKey {
+    #[inline]
+    pub fn decrypt(&self, ciphertext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.decrypt_oaep(ciphertext, RsaHash::Sha256, &[])
+    }
+
+    #[inline]
+    pub fn sign<T: ?Sized>(&self, data: &T) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        self.sign_pkcs1v15(data, RsaHash::Sha256)
+    }
+
+    pub fn sign_and_verify<T: ?Sized>(
+        &self,
+        public_key: &Rsa4096PublicKey,
+        data: &T,
+    ) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let privkey = self.create()?;
+        let pubkey = match public_key.create() {
+            Ok(pubkey) => pubkey,
+            Err(e) => {
+                let _ = Self::free(privkey);
+                bail!(e);
+            }
+        };
+
+        let mut signature = Rsa4096Signature::default();
+        let result = super::sign_pkcs1v15(
+            privkey,
+            Some(pubkey),
+            RsaHash::Sha256,
+            data,
+            &mut signature.signature,
+        );
+        let _ = Self::free(privkey);
+        let _ = Rsa4096PublicKey::free(pubkey);
+        result.map(|_| signature)
+    }
+
+    pub fn decrypt_oaep(
+        &self,
+        ciphertext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        let privkey = self.create()?;
+        let result = super::decrypt_oaep(privkey, RSA4096_KEY_SIZE, hash, label, ciphertext);
+        let _ = Self::free(privkey);
+        result
+    }
+
+    pub fn sign_pkcs1v15<T: ?Sized>(&self, data: &T, hash: RsaHash) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let privkey = self.create()?;
+        let mut signature = Rsa4096Signature::default();
+        let result = super::sign_pkcs1v15(privkey, None, hash, data, &mut signature.signature);
+        let _ = Self::free(privkey);
+        result.map(|_| signature)
+    }
+
+    pub fn sign_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        hash: RsaHash,
+        salt_len: usize,
+    ) -> SgxResult<Rsa4096Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let privkey = self.create()?;
+        let mut signature = Rsa4096Signature::default();
+        let result = super::sign_pss(
+            privkey,
+            None,
+            hash,
+            salt_len,
+            data,
+            &mut signature.signature,
+        );
+        let _ = Self::free(privkey);
+        result.map(|_| signature)
+    }
+
+    #[inline]
+    pub fn export_public_key(&self) -> Rsa4096PublicKey {
+        Rsa4096PublicKey {
+            n: self.n,
+            e: self.e,
+        }
+    }
+
+    #[inline]
+    pub fn private_key(&self) -> Rsa4096Key {
+        Rsa4096Key {
+            modulus: self.n,
+            d: self.d,
+            e: self.e,
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+
+    fn create(&self) -> SgxResult<RsaKey> {
+        let mut key: RsaKey = ptr::null_mut();
+        let status = unsafe {
+            match self.privtype {
+                RsaPrivateType::Type1 => sgx_create_rsa_priv1_key(
+                    RSA4096_KEY_SIZE as i32,
+                    RSA4096_PUB_EXP_SIZE as i32,
+                    RSA4096_PRI_EXP_SIZE as i32,
+                    self.n.as_ptr(),
+                    self.e.as_ptr(),
+                    self.d.as_ptr(),
+                    &mut key as *mut RsaKey,
+                ),
+                RsaPrivateType::Type2 => {
+                    let mut status = sgx_create_rsa_priv2_key(
+                        RSA4096_KEY_SIZE as i32,
+                        RSA4096_PRI_EXP_SIZE as i32,
+                        self.e.as_ptr(),
+                        self.p.as_ptr(),
+                        self.q.as_ptr(),
+                        self.dmp1.as_ptr(),
+                        self.dmq1.as_ptr(),
+                        self.iqmp.as_ptr(),
+                        &mut key as *mut RsaKey,
+                    );
+                    if !status.is_success() {
+                        status = sgx_create_rsa_priv1_key(
+                            RSA4096_KEY_SIZE as i32,
+                            RSA4096_PUB_EXP_SIZE as i32,
+                            RSA4096_PRI_EXP_SIZE as i32,
+                            self.n.as_ptr(),
+                            self.e.as_ptr(),
+                            self.d.as_ptr(),
+                            &mut key as *mut RsaKey,
+                        );
+                    }
+                    status
+                }
+            }
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(key)
+    }
+
+    fn free(key: RsaKey) -> SgxResult {
+        let status = unsafe {
+            sgx_free_rsa_key(
+                key,
+                RsaKeyType::PrivateKey,
+                RSA4096_KEY_SIZE as i32,
+                RSA4096_PRI_EXP_SIZE as i32,
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(())
+    }
+}
+
+impl From<Rsa4096Key> for Rsa4096PrivateKey {
+    fn from(key: Rsa4096Key) -> Rsa4096PrivateKey {
+        Rsa4096PrivateKey {
+            n: key.modulus,
+            d: key.d,
+            e: key.e,
+            p: [0; RSA4096_KEY_SIZE / 2],
+            q: [0; RSA4096_KEY_SIZE / 2],
+            dmp1: [0; RSA4096_KEY_SIZE / 2],
+            dmq1: [0; RSA4096_KEY_SIZE / 2],
+            iqmp: [0; RSA4096_KEY_SIZE / 2],
+            privtype: RsaPrivateType::Type1,
+        }
+    }
+}
+
+impl From<&Rsa4096Key> for Rsa4096PrivateKey {
+    fn from(key: &Rsa4096Key) -> Rsa4096PrivateKey {
+        Rsa4096PrivateKey {
+            n: key.modulus,
+            d: key.d,
+            e: key.e,
+            p: [0; RSA4096_KEY_SIZE / 2],
+            q: [0; RSA4096_KEY_SIZE / 2],
+            dmp1: [0; RSA4096_KEY_SIZE / 2],
+            dmq1: [0; RSA4096_KEY_SIZE / 2],
+            iqmp: [0; RSA4096_KEY_SIZE / 2],
+            privtype: RsaPrivateType::Type1,
+        }
+    }
+}
+
+impl From<Rsa4096PrivateKey> for Rsa4096Key {
+    fn from(key: Rsa4096PrivateKey) -> Rsa4096Key {
+        Rsa4096Key {
+            modulus: key.n,
+            d: key.d,
+            e: key.e,
+        }
+    }
+}
+
+impl From<&Rsa4096PrivateKey> for Rsa4096Key {
+    fn from(key: &Rsa4096PrivateKey) -> Rsa4096Key {
+        Rsa4096Key {
+            modulus: key.n,
+            d: key.d,
+            e: key.e,
+        }
+    }
+}
+
+impl Default for Rsa4096PrivateKey {
+    fn default() -> Self {
+        Rsa4096PrivateKey {
+            n: [0; RSA4096_KEY_SIZE],
+            d: [0; RSA4096_PRI_EXP_SIZE],
+            e: RSA4096_DEFAULT_E,
+            p: [0; RSA4096_KEY_SIZE / 2],
+            q: [0; RSA4096_KEY_SIZE / 2],
+            dmp1: [0; RSA4096_KEY_SIZE / 2],
+            dmq1: [0; RSA4096_KEY_SIZE / 2],
+            iqmp: [0; RSA4096_KEY_SIZE / 2],
+            privtype: RsaPrivateType::Type2,
+        }
+    }
+}
+
+impl_asref_array! { Rsa4096PrivateKey; }
+impl_from_array! { Rsa4096PrivateKey; }
+
+unsafe impl ContiguousMemory for Rsa4096PrivateKey {}
+
+unsafe impl BytewiseEquality for Rsa4096PrivateKey {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    any(feature = "tserialize", feature = "userialize"),
+    derive(Deserialize, Serialize)
+)]
+pub struct Rsa4096PublicKey {
+    n: [u8; RSA4096_KEY_SIZE],
+    e: [u8; RSA4096_PUB_EXP_SIZE],
+}
+
+impl Rsa4096PublicKey {
+    #[inline]
+    pub fn encrypt(&self, plaintext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.encrypt_oaep(plaintext, RsaHash::Sha256, &[])
+    }
+
+    #[inline]
+    pub fn verify<T: ?Sized>(&self, data: &T, signature: &Rsa4096Signature) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        self.verify_pkcs1v15(data, signature, RsaHash::Sha256)
+    }
+
+    pub fn encrypt_oaep(
+        &self,
+        plaintext: &[u8],
+        hash: RsaHash,
+        label: &[u8],
+    ) -> SgxResult<Vec<u8>> {
+        let pubkey = self.create()?;
+        let result = super::encrypt_oaep(pubkey, RSA4096_KEY_SIZE, hash, label, plaintext);
+        let _ = Self::free(pubkey);
+        result
+    }
+
+    pub fn verify_pkcs1v15<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa4096Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        let pubkey = self.create()?;
+        let result = super::verify_pkcs1v15(pubkey, hash, data, &signature.signature);
+        let _ = Self::free(pubkey);
+        result
+    }
+
+    pub fn verify_pss<T: ?Sized>(
+        &self,
+        data: &T,
+        signature: &Rsa4096Signature,
+        hash: RsaHash,
+    ) -> SgxResult<bool>
+    where
+        T: ContiguousMemory,
+    {
+        let pubkey = self.create()?;
+        let result = super::verify_pss(pubkey, hash, data, &signature.signature);
+        let _ = Self::free(pubkey);
+        result
+    }
+
+    #[inline]
+    pub fn from_private_key(key: &Rsa4096PrivateKey) -> Rsa4096PublicKey {
+        key.export_public_key()
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> Rsa4096PubKey {
+        Rsa4096PubKey {
+            modulus: self.n,
+            exponent: self.e,
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+
+    fn create(&self) -> SgxResult<RsaKey> {
+        let mut key: RsaKey = ptr::null_mut();
+        let status = unsafe {
+            sgx_create_rsa_pub1_key(
+                RSA4096_KEY_SIZE as i32,
+                RSA4096_PUB_EXP_SIZE as i32,
+                self.n.as_ptr(),
+                self.e.as_ptr(),
+                &mut key as *mut RsaKey,
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(key)
+    }
+
+    fn free(key: RsaKey) -> SgxResult {
+        let status = unsafe {
+            sgx_free_rsa_key(
+                key,
+                RsaKeyType::PublicKey,
+                RSA4096_KEY_SIZE as i32,
+                RSA4096_PUB_EXP_SIZE as i32,
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(())
+    }
+}
+
+impl From<Rsa4096PubKey> for Rsa4096PublicKey {
+    fn from(key: Rsa4096PubKey) -> Rsa4096PublicKey {
+        Rsa4096PublicKey {
+            n: key.modulus,
+            e: key.exponent,
+        }
+    }
+}
+
+impl From<&Rsa4096PubKey> for Rsa4096PublicKey {
+    fn from(key: &Rsa4096PubKey) -> Rsa4096PublicKey {
+        Rsa4096PublicKey {
+            n: key.modulus,
+            e: key.exponent,
+        }
+    }
+}
+
+impl From<Rsa4096PublicKey> for Rsa4096PubKey {
+    fn from(key: Rsa4096PublicKey) -> Rsa4096PubKey {
+        Rsa4096PubKey {
+            modulus: key.n,
+            exponent: key.e,
+        }
+    }
+}
+
+impl From<&Rsa4096PublicKey> for Rsa4096PubKey {
+    fn from(key: &Rsa4096PublicKey) -> Rsa4096PubKey {
+        Rsa4096PubKey {
+            modulus: key.n,
+            exponent: key.e,
+        }
+    }
+}
+
+impl Default for Rsa4096PublicKey {
+    fn default() -> Self {
+        Rsa4096PublicKey {
+            n: [0; RSA4096_KEY_SIZE],
+            e: RSA4096_DEFAULT_E,
+        }
+    }
+}
+
+impl_asref_array! { Rsa4096PublicKey; }
+impl_from_array! { Rsa4096PublicKey; }
+
+unsafe impl ContiguousMemory for Rsa4096PublicKey {}
+
+unsafe impl BytewiseEquality for Rsa4096PublicKey {}
+
+impl EncodePublicKey for Rsa4096PublicKey {
+    fn to_public_key_der(&self) -> SgxResult<Vec<u8>> {
+        Ok(encode_public_key(&self.n, &self.e))
+    }
+}
+
+impl DecodePublicKey for Rsa4096PublicKey {
+    fn from_public_key_der(der: &[u8]) -> SgxResult<Rsa4096PublicKey> {
+        let mut key = Rsa4096PublicKey::default();
+        decode_public_key(der, &mut key.n, &mut key.e)?;
+        ensure!(
+            key.n[RSA4096_KEY_SIZE - 1] != 0,
+            SgxStatus::InvalidParameter
+        );
+        Ok(key)
+    }
+}
+
+impl EncodePrivateKey for Rsa4096PrivateKey {
+    fn to_pkcs8_der(&self) -> SgxResult<Vec<u8>> {
+        // Type1 keys lack the CRT components required by PKCS#1.
+        ensure!(
+            self.privtype == RsaPrivateType::Type2,
+            SgxStatus::InvalidParameter
+        );
+
+        Ok(encode_private_key(&RsaPrivateComponents {
+            n: &self.n,
+            e: &self.e,
+            d: &self.d,
+            p: &self.p,
+            q: &self.q,
+            dmp1: &self.dmp1,
+            dmq1: &self.dmq1,
+            iqmp: &self.iqmp,
+        }))
+    }
+}
+
+impl DecodePrivateKey for Rsa4096PrivateKey {
+    fn from_pkcs8_der(der: &[u8]) -> SgxResult<Rsa4096PrivateKey> {
+        let mut key = Rsa4096PrivateKey::default();
+        let result = decode_private_key(
+            der,
+            RsaPrivateComponentsMut {
+                n: &mut key.n,
+                e: &mut key.e,
+                d: &mut key.d,
+                p: &mut key.p,
+                q: &mut key.q,
+                dmp1: &mut key.dmp1,
+                dmq1: &mut key.dmq1,
+                iqmp: &mut key.iqmp,
+            },
+        );
+        if result.is_err() || key.n[RSA4096_KEY_SIZE - 1] == 0 {
+            key.clear();
+            bail!(SgxStatus::InvalidParameter);
+        }
+        Ok(key)
+    }
+}
@@ -0,0 +1,211 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! RustCrypto trait implementations for the sgx_crypto types.
+//!
+//! With the `rustcrypto` feature, the hash functions implement
+//! `digest::Digest`, AES-GCM implements `aead::Aead` and `aead::AeadInPlace`,
+//! and the ECDSA, SM2 and Ed25519 keys implement
+//! `signature::Signer` and `signature::Verifier`. In an enclave,
+//! `sgx_trts::rand::Rng` implements `rand_core::CryptoRng`.
+//!
+//! The trait crates are re-exported, so the versions always match. Where a
+//! type has an inherent method of the same name, e.g. `new`, call the trait
+//! method by its path: `<Aes256Gcm as KeyInit>::new(&key)`.
+
+use crate::aes::gcm::{Aad, AesGcm, Nonce};
+use crate::ecc::{EcPrivateKey, EcPublicKey, EcSignature};
+use crate::ecc::{P384PrivateKey, P384PublicKey, P384Signature};
+use crate::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+use crate::sha::{Sha1, Sha256, Sha384, Sha3_256, Sha3_512, Sha512, Sha512_256};
+use crate::sm2::{Sm2PrivateKey, Sm2PublicKey, Sm2Signature};
+use crate::sm3::Sm3;
+use aead::consts::{U0, U12, U16, U24, U32};
+use aead::{AeadCore, AeadInPlace, Key, KeyInit, KeySizeUser, Tag};
+use digest::consts::{U128, U136, U20, U48, U64, U72};
+use digest::core_api::BlockSizeUser;
+use digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+use sgx_types::types::{Key128bit, Key192bit, Key256bit};
+use signature::{Signer, Verifier};
+
+pub use aead;
+pub use digest;
+pub use rand_core;
+pub use signature;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub type Aes128Gcm = AesGcm<[u8; 0], Key128bit>;
+pub type Aes192Gcm = AesGcm<[u8; 0], Key192bit>;
+pub type Aes256Gcm = AesGcm<[u8; 0], Key256bit>;
+
+// The traits are infallible, while the hash functions only fail when the
+// backend does, so a failure there is treated as fatal.
+macro_rules! impl_rustcrypto_digest {
+    ($($t:ty, $output:ty, $block:ty;)*) => {$(
+        impl Default for $t {
+            #[inline]
+            fn default() -> $t {
+                <$t>::new().expect("failed to create hash state")
+            }
+        }
+
+        impl HashMarker for $t {}
+
+        impl OutputSizeUser for $t {
+            type OutputSize = $output;
+        }
+
+        impl BlockSizeUser for $t {
+            type BlockSize = $block;
+        }
+
+        impl Update for $t {
+            fn update(&mut self, data: &[u8]) {
+                // The backend rejects empty input, which is a no-op here.
+                if !data.is_empty() {
+                    <$t>::update(self, data).expect("failed to update hash state");
+                }
+            }
+        }
+
+        impl FixedOutput for $t {
+            fn finalize_into(self, out: &mut Output<Self>) {
+                let hash = <$t>::finalize(self).expect("failed to finalize hash");
+                out.copy_from_slice(&hash);
+            }
+        }
+
+        impl FixedOutputReset for $t {
+            fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                let hash = <$t>::finalize_reset(self).expect("failed to finalize hash");
+                out.copy_from_slice(&hash);
+            }
+        }
+
+        impl Reset for $t {
+            #[inline]
+            fn reset(&mut self) {
+                *self = <$t>::default();
+            }
+        }
+    )*}
+}
+
+impl_rustcrypto_digest! {
+    Sha1, U20, U64;
+    Sha256, U32, U64;
+    Sha384, U48, U128;
+    Sha512, U64, U128;
+    Sha512_256, U32, U128;
+    Sha3_256, U32, U136;
+    Sha3_512, U64, U72;
+    Sm3, U32, U64;
+}
+
+// Only the key of the cipher is used; the nonce and associated data come with
+// each call, as the trait expects.
+macro_rules! impl_rustcrypto_aead {
+    ($($key:ty, $size:ty;)*) => {$(
+        impl KeySizeUser for AesGcm<[u8; 0], $key> {
+            type KeySize = $size;
+        }
+
+        impl KeyInit for AesGcm<[u8; 0], $key> {
+            fn new(key: &Key<Self>) -> Self {
+                let mut k = <$key>::default();
+                k.copy_from_slice(key);
+                let cipher = AesGcm::new(&k, Nonce::zeroed(), Aad::empty());
+                k.fill(0);
+                cipher.expect("failed to create cipher")
+            }
+        }
+
+        impl AeadCore for AesGcm<[u8; 0], $key> {
+            type NonceSize = U12;
+            type TagSize = U16;
+            type CiphertextOverhead = U0;
+        }
+
+        impl AeadInPlace for AesGcm<[u8; 0], $key> {
+            fn encrypt_in_place_detached(
+                &self,
+                nonce: &aead::Nonce<Self>,
+                associated_data: &[u8],
+                buffer: &mut [u8],
+            ) -> aead::Result<Tag<Self>> {
+                let nonce = Nonce::try_from(nonce.as_slice()).map_err(|_| aead::Error)?;
+                let mut cipher = AesGcm::new(self.key(), nonce, Aad::from(associated_data))
+                    .map_err(|_| aead::Error)?;
+                let mac = cipher.encrypt_in_place(buffer).map_err(|_| aead::Error)?;
+                Ok(Tag::<Self>::from(mac))
+            }
+
+            fn decrypt_in_place_detached(
+                &self,
+                nonce: &aead::Nonce<Self>,
+                associated_data: &[u8],
+                buffer: &mut [u8],
+                tag: &Tag<Self>,
+            ) -> aead::Result<()> {
+                let nonce = Nonce::try_from(nonce.as_slice()).map_err(|_| aead::Error)?;
+                let mut mac = [0_u8; 16];
+                mac.copy_from_slice(tag);
+                let mut cipher = AesGcm::new(self.key(), nonce, Aad::from(associated_data))
+                    .map_err(|_| aead::Error)?;
+                cipher
+                    .decrypt_in_place(buffer, &mac)
+                    .map_err(|_| aead::Error)
+            }
+        }
+    )*}
+}
+
+impl_rustcrypto_aead! {
+    Key128bit, U16;
+    Key192bit, U24;
+    Key256bit, U32;
+}
+
+// A signature that does not verify and a verification that fails are
+// reported alike; signature::Error is opaque on purpose.
+macro_rules! impl_rustcrypto_signature {
+    ($($private:ty, $public:ty, $signature:ty;)*) => {$(
+        impl Signer<$signature> for $private {
+            fn try_sign(&self, msg: &[u8]) -> Result<$signature, signature::Error> {
+                self.sign(msg).map_err(|_| signature::Error::new())
+            }
+        }
+
+        impl Verifier<$signature> for $public {
+            fn verify(&self, msg: &[u8], signature: &$signature) -> Result<(), signature::Error> {
+                match <$public>::verify(self, msg, signature) {
+                    Ok(true) => Ok(()),
+                    _ => Err(signature::Error::new()),
+                }
+            }
+        }
+    )*}
+}
+
+impl_rustcrypto_signature! {
+    EcPrivateKey, EcPublicKey, EcSignature;
+    P384PrivateKey, P384PublicKey, P384Signature;
+    Sm2PrivateKey, Sm2PublicKey, Sm2Signature;
+    Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature;
+}
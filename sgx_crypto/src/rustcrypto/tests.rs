@@ -0,0 +1,156 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::*;
+use crate::cipher::Aead as _;
+use crate::ecc::{EcKeyPair, P384KeyPair};
+use crate::ed25519::Ed25519KeyPair;
+use crate::sm2::Sm2KeyPair;
+use aead::{Aead, Payload};
+use alloc::vec::Vec;
+use digest::Digest;
+use sgx_test_utils::test_case;
+
+const MESSAGE: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+macro_rules! check_digest {
+    ($($t:ty),*) => {$(
+        let expected = <$t>::digest(MESSAGE).unwrap();
+        assert_eq!(<$t as Digest>::digest(MESSAGE).as_slice(), &expected[..]);
+
+        let mut hasher = <$t as Digest>::new();
+        Digest::update(&mut hasher, &MESSAGE[..10]);
+        Digest::update(&mut hasher, []);
+        Digest::update(&mut hasher, &MESSAGE[10..]);
+        assert_eq!(Digest::finalize_reset(&mut hasher).as_slice(), &expected[..]);
+
+        Digest::update(&mut hasher, b"discarded");
+        Digest::reset(&mut hasher);
+        Digest::update(&mut hasher, MESSAGE);
+        assert_eq!(Digest::finalize(hasher).as_slice(), &expected[..]);
+    )*}
+}
+
+#[test_case]
+fn digest() {
+    check_digest!(Sha1, Sha256, Sha384, Sha512, Sha512_256, Sha3_256, Sha3_512, Sm3);
+
+    let empty = Digest::finalize(<Sha256 as Digest>::new());
+    assert_eq!(
+        empty.as_slice(),
+        &[
+            0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f,
+            0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b,
+            0x78, 0x52, 0xb8, 0x55
+        ]
+    );
+}
+
+#[test_case]
+fn aead() {
+    let key = [0x42_u8; 32];
+    let nonce = [0x24_u8; 12];
+    let aad = b"additional data";
+
+    let cipher = <Aes256Gcm as KeyInit>::new(&key.into());
+    let ciphertext = cipher
+        .encrypt(&nonce.into(), Payload { msg: MESSAGE, aad })
+        .unwrap();
+    assert_eq!(ciphertext.len(), MESSAGE.len() + 16);
+
+    // The same as the detached output of the native interface.
+    let mut dst = vec![0_u8; MESSAGE.len()];
+    let mac =
+        Aes256Gcm::encrypt_detached(&key, Nonce::from(nonce), aad, MESSAGE, &mut dst).unwrap();
+    let mut expected: Vec<u8> = dst;
+    expected.extend_from_slice(&mac);
+    assert_eq!(ciphertext, expected);
+
+    let plaintext = cipher
+        .decrypt(
+            &nonce.into(),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .unwrap();
+    assert_eq!(plaintext, MESSAGE);
+
+    let mut tampered = ciphertext.clone();
+    tampered[0] ^= 1;
+    assert!(cipher
+        .decrypt(
+            &nonce.into(),
+            Payload {
+                msg: &tampered,
+                aad,
+            }
+        )
+        .is_err());
+    assert!(cipher
+        .decrypt(
+            &nonce.into(),
+            Payload {
+                msg: &ciphertext,
+                aad: b"other data",
+            }
+        )
+        .is_err());
+
+    // An empty message only authenticates the associated data.
+    let cipher = <Aes128Gcm as KeyInit>::new(&[0x42_u8; 16].into());
+    let ciphertext = cipher
+        .encrypt(&nonce.into(), Payload { msg: &[], aad })
+        .unwrap();
+    assert_eq!(ciphertext.len(), 16);
+    assert!(cipher
+        .decrypt(
+            &nonce.into(),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            }
+        )
+        .unwrap()
+        .is_empty());
+}
+
+fn check_signature<S, P, V>(signer: &S, verifier: &V)
+where
+    S: Signer<P>,
+    V: Verifier<P>,
+{
+    let signature = signer.try_sign(MESSAGE).unwrap();
+    verifier.verify(MESSAGE, &signature).unwrap();
+    assert!(verifier.verify(b"another message", &signature).is_err());
+}
+
+#[test_case]
+fn signature() {
+    let key_pair = EcKeyPair::create().unwrap();
+    check_signature(&key_pair.private_key(), &key_pair.public_key());
+
+    let key_pair = P384KeyPair::create().unwrap();
+    check_signature(&key_pair.private_key(), &key_pair.public_key());
+
+    let key_pair = Sm2KeyPair::create().unwrap();
+    check_signature(&key_pair.private_key(), &key_pair.public_key());
+
+    let key_pair = Ed25519KeyPair::create().unwrap();
+    check_signature(&key_pair.private_key(), &key_pair.public_key());
+}
@@ -0,0 +1,85 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::sha::{Sha1, Sha256, Sha384, Sha3_256, Sha3_512, Sha512, Sha512_256};
+use crate::sm3::Sm3;
+use core::mem;
+use core::ops::Deref;
+use sgx_types::error::SgxResult;
+use sgx_types::types::{
+    Sha1Hash, Sha256Hash, Sha384Hash, Sha3_256Hash, Sha3_512Hash, Sha512Hash, Sha512_256Hash,
+    Sm3Hash,
+};
+
+// Common interface of the fixed-size hash functions, used to build HMAC
+// over any of them.
+pub trait Digest: Sized {
+    type Output: Copy + Default + Deref<Target = [u8]>;
+
+    const BLOCK_SIZE: usize;
+    const OUTPUT_SIZE: usize;
+
+    fn new() -> SgxResult<Self>;
+
+    fn update(&mut self, data: &[u8]) -> SgxResult;
+
+    fn finalize(self) -> SgxResult<Self::Output>;
+
+    fn finalize_reset(&mut self) -> SgxResult<Self::Output>;
+}
+
+macro_rules! impl_digest {
+    ($($t:ty, $hash:ty, $block:expr;)*) => {$(
+        impl Digest for $t {
+            type Output = $hash;
+
+            const BLOCK_SIZE: usize = $block;
+            const OUTPUT_SIZE: usize = mem::size_of::<$hash>();
+
+            #[inline]
+            fn new() -> SgxResult<Self> {
+                <$t>::new()
+            }
+
+            #[inline]
+            fn update(&mut self, data: &[u8]) -> SgxResult {
+                <$t>::update(self, data)
+            }
+
+            #[inline]
+            fn finalize(self) -> SgxResult<$hash> {
+                <$t>::finalize(self)
+            }
+
+            #[inline]
+            fn finalize_reset(&mut self) -> SgxResult<$hash> {
+                <$t>::finalize_reset(self)
+            }
+        }
+    )*}
+}
+
+impl_digest! {
+    Sha1, Sha1Hash, 64;
+    Sha256, Sha256Hash, 64;
+    Sha384, Sha384Hash, 128;
+    Sha512, Sha512Hash, 128;
+    Sha512_256, Sha512_256Hash, 128;
+    Sha3_256, Sha3_256Hash, 136;
+    Sha3_512, Sha3_512Hash, 72;
+    Sm3, Sm3Hash, 64;
+}
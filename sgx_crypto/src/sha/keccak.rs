@@ -0,0 +1,172 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI_LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+pub(crate) const SHA3_PAD: u8 = 0x06;
+pub(crate) const SHAKE_PAD: u8 = 0x1f;
+
+// Keccak sponge with a byte rate `rate` and domain separation byte `pad`.
+#[derive(Clone)]
+pub(crate) struct Keccak {
+    state: [u64; 25],
+    buffer: [u8; 200],
+    pos: usize,
+    rate: usize,
+    pad: u8,
+}
+
+impl Keccak {
+    pub fn new(rate: usize, pad: u8) -> Keccak {
+        debug_assert!(rate > 0 && rate < 200 && rate % 8 == 0);
+        Keccak {
+            state: [0; 25],
+            buffer: [0; 200],
+            pos: 0,
+            rate,
+            pad,
+        }
+    }
+
+    pub fn absorb(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = (self.rate - self.pos).min(data.len());
+            self.buffer[self.pos..self.pos + len].copy_from_slice(&data[..len]);
+            self.pos += len;
+            data = &data[len..];
+
+            if self.pos == self.rate {
+                self.absorb_block();
+                self.pos = 0;
+            }
+        }
+    }
+
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        self.buffer[self.pos..self.rate].fill(0);
+        self.buffer[self.pos] ^= self.pad;
+        self.buffer[self.rate - 1] ^= 0x80;
+        self.absorb_block();
+
+        let mut chunks = out.chunks_mut(self.rate).peekable();
+        while let Some(chunk) = chunks.next() {
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (self.state[i / 8] >> (8 * (i % 8))) as u8;
+            }
+            if chunks.peek().is_some() {
+                keccak_f(&mut self.state);
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = [0; 25];
+        self.buffer = [0; 200];
+        self.pos = 0;
+    }
+
+    fn absorb_block(&mut self) {
+        for (lane, bytes) in self
+            .state
+            .iter_mut()
+            .zip(self.buffer[..self.rate].chunks_exact(8))
+        {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        keccak_f(&mut self.state);
+    }
+}
+
+impl Drop for Keccak {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let mut c = [0_u64; 5];
+        for (x, c) in c.iter_mut().enumerate() {
+            *c = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut last = a[1];
+        for (&lane, &rotation) in PI_LANES.iter().zip(ROTATIONS.iter()) {
+            let tmp = a[lane];
+            a[lane] = last.rotate_left(rotation);
+            last = tmp;
+        }
+
+        // chi
+        for y in 0..5 {
+            let row = [
+                a[5 * y],
+                a[5 * y + 1],
+                a[5 * y + 2],
+                a[5 * y + 3],
+                a[5 * y + 4],
+            ];
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // iota
+        a[0] ^= rc;
+    }
+}
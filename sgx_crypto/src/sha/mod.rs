@@ -15,10 +15,22 @@
 // specific language governing permissions and limitations
 // under the License..
 
+mod digest;
+mod keccak;
 mod sha1;
 mod sha256;
+mod sha3;
 mod sha384;
+mod sha512;
+mod sha512_256;
 
+pub use self::digest::*;
 pub use sha1::*;
 pub use sha256::*;
+pub use sha3::*;
 pub use sha384::*;
+pub use sha512::*;
+pub use sha512_256::*;
+
+#[cfg(feature = "unit_test")]
+mod tests;
@@ -0,0 +1,142 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::sha::keccak::{Keccak, SHA3_PAD, SHAKE_PAD};
+use core::mem;
+use core::slice;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::ContiguousMemory;
+use sgx_types::types::{Sha3_256Hash, Sha3_512Hash};
+
+macro_rules! impl_sha3 {
+    ($name:ident, $hash:ty, $rate:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            keccak: Keccak,
+        }
+
+        impl $name {
+            pub fn new() -> SgxResult<$name> {
+                Ok($name {
+                    keccak: Keccak::new($rate, SHA3_PAD),
+                })
+            }
+
+            pub fn update<T: ?Sized>(&mut self, data: &T) -> SgxResult
+            where
+                T: ContiguousMemory,
+            {
+                self.keccak.absorb(as_bytes(data)?);
+                Ok(())
+            }
+
+            pub fn finalize(self) -> SgxResult<$hash> {
+                let mut hash = <$hash>::default();
+                self.finalize_into(&mut hash)?;
+                Ok(hash)
+            }
+
+            pub fn finalize_into(mut self, hash: &mut $hash) -> SgxResult {
+                self.keccak.squeeze(&mut hash.hash);
+                Ok(())
+            }
+
+            pub fn finalize_reset(&mut self) -> SgxResult<$hash> {
+                let mut hash = <$hash>::default();
+                self.finalize_into_reset(&mut hash)?;
+                Ok(hash)
+            }
+
+            pub fn finalize_into_reset(&mut self, hash: &mut $hash) -> SgxResult {
+                self.keccak.squeeze(&mut hash.hash);
+                self.keccak.reset();
+                Ok(())
+            }
+
+            pub fn digest<T: ?Sized>(data: &T) -> SgxResult<$hash>
+            where
+                T: ContiguousMemory,
+            {
+                let mut sha3 = Self::new()?;
+                sha3.update(data)?;
+                sha3.finalize()
+            }
+        }
+    };
+}
+
+macro_rules! impl_shake {
+    ($name:ident, $rate:expr) => {
+        #[derive(Clone)]
+        pub struct $name {
+            keccak: Keccak,
+        }
+
+        impl $name {
+            pub fn new() -> SgxResult<$name> {
+                Ok($name {
+                    keccak: Keccak::new($rate, SHAKE_PAD),
+                })
+            }
+
+            pub fn update<T: ?Sized>(&mut self, data: &T) -> SgxResult
+            where
+                T: ContiguousMemory,
+            {
+                self.keccak.absorb(as_bytes(data)?);
+                Ok(())
+            }
+
+            pub fn finalize_into(mut self, out: &mut [u8]) -> SgxResult {
+                ensure!(!out.is_empty(), SgxStatus::InvalidParameter);
+                self.keccak.squeeze(out);
+                Ok(())
+            }
+
+            pub fn finalize_into_reset(&mut self, out: &mut [u8]) -> SgxResult {
+                ensure!(!out.is_empty(), SgxStatus::InvalidParameter);
+                self.keccak.squeeze(out);
+                self.keccak.reset();
+                Ok(())
+            }
+
+            pub fn digest<T: ?Sized>(data: &T, out: &mut [u8]) -> SgxResult
+            where
+                T: ContiguousMemory,
+            {
+                let mut shake = Self::new()?;
+                shake.update(data)?;
+                shake.finalize_into(out)
+            }
+        }
+    };
+}
+
+impl_sha3!(Sha3_256, Sha3_256Hash, 136);
+impl_sha3!(Sha3_512, Sha3_512Hash, 72);
+impl_shake!(Shake128, 168);
+impl_shake!(Shake256, 136);
+
+#[inline]
+fn as_bytes<T: ?Sized + ContiguousMemory>(data: &T) -> SgxResult<&[u8]> {
+    let size = mem::size_of_val(data);
+    ensure!(
+        (size > 0 && size < i32::MAX as usize),
+        SgxStatus::InvalidParameter
+    );
+    Ok(unsafe { slice::from_raw_parts((data as *const T).cast::<u8>(), size) })
+}
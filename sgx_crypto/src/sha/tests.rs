@@ -0,0 +1,271 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{Sha3_256, Sha3_512, Sha512, Sha512_256, Shake128, Shake256};
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// FIPS 180-4 examples: one block, and two blocks for SHA-512.
+const ABC: &[u8] = b"abc";
+const TWO_BLOCKS: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                            hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+// FIPS 202 examples: 1600 bits of 0xa3, more than one block at every rate.
+const A3: [u8; 200] = [0xa3; 200];
+
+// Chunk sizes around the SHA3-256 and SHAKE256 rate of 136 bytes.
+const CHUNKS: [usize; 5] = [1, 7, 135, 136, 137];
+
+#[test_case]
+fn sha512_fips180() {
+    assert_eq!(
+        Sha512::digest(ABC).unwrap().to_vec(),
+        decode(
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        )
+    );
+    let expected = decode(
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+         501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+    );
+    assert_eq!(Sha512::digest(TWO_BLOCKS).unwrap().to_vec(), expected);
+
+    for chunk in CHUNKS {
+        let mut sha = Sha512::new().unwrap();
+        for data in TWO_BLOCKS.chunks(chunk) {
+            sha.update(data).unwrap();
+        }
+        assert_eq!(
+            sha.finalize_reset().unwrap().to_vec(),
+            expected,
+            "{}",
+            chunk
+        );
+        // the hash starts over after a reset
+        sha.update(ABC).unwrap();
+        assert_eq!(sha.finalize().unwrap(), Sha512::digest(ABC).unwrap());
+    }
+
+    assert_eq!(
+        Sha512::new().unwrap().finalize().unwrap().to_vec(),
+        decode(
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        )
+    );
+    assert_eq!(
+        Sha512::digest(&[0_u8; 0]).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
+
+#[test_case]
+fn sha512_256_fips180() {
+    assert_eq!(
+        Sha512_256::digest(ABC).unwrap().to_vec(),
+        decode("53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23")
+    );
+    let expected = decode("3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a");
+    assert_eq!(Sha512_256::digest(TWO_BLOCKS).unwrap().to_vec(), expected);
+
+    for chunk in CHUNKS {
+        let mut sha = Sha512_256::new().unwrap();
+        for data in TWO_BLOCKS.chunks(chunk) {
+            sha.update(data).unwrap();
+        }
+        assert_eq!(
+            sha.finalize_reset().unwrap().to_vec(),
+            expected,
+            "{}",
+            chunk
+        );
+        sha.update(ABC).unwrap();
+        assert_eq!(sha.finalize().unwrap(), Sha512_256::digest(ABC).unwrap());
+    }
+
+    assert_eq!(
+        Sha512_256::new().unwrap().finalize().unwrap().to_vec(),
+        decode("c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a")
+    );
+}
+
+#[test_case]
+fn sha3_256_fips202() {
+    assert_eq!(
+        Sha3_256::digest(ABC).unwrap().to_vec(),
+        decode("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+    );
+    let expected = decode("79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787");
+    assert_eq!(Sha3_256::digest(&A3).unwrap().to_vec(), expected);
+
+    for chunk in CHUNKS {
+        let mut sha3 = Sha3_256::new().unwrap();
+        for data in A3.chunks(chunk) {
+            sha3.update(data).unwrap();
+        }
+        assert_eq!(
+            sha3.finalize_reset().unwrap().to_vec(),
+            expected,
+            "{}",
+            chunk
+        );
+        sha3.update(ABC).unwrap();
+        assert_eq!(sha3.finalize().unwrap(), Sha3_256::digest(ABC).unwrap());
+    }
+
+    assert_eq!(
+        Sha3_256::new().unwrap().finalize().unwrap().to_vec(),
+        decode("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+    );
+    assert_eq!(
+        Sha3_256::digest(&[0_u8; 0]).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
+
+#[test_case]
+fn sha3_512_fips202() {
+    assert_eq!(
+        Sha3_512::digest(ABC).unwrap().to_vec(),
+        decode(
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+             10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+        )
+    );
+    let expected = decode(
+        "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8\
+         1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00",
+    );
+    assert_eq!(Sha3_512::digest(&A3).unwrap().to_vec(), expected);
+
+    for chunk in [1, 71, 72, 73] {
+        let mut sha3 = Sha3_512::new().unwrap();
+        for data in A3.chunks(chunk) {
+            sha3.update(data).unwrap();
+        }
+        assert_eq!(
+            sha3.finalize_reset().unwrap().to_vec(),
+            expected,
+            "{}",
+            chunk
+        );
+        sha3.update(ABC).unwrap();
+        assert_eq!(sha3.finalize().unwrap(), Sha3_512::digest(ABC).unwrap());
+    }
+
+    assert_eq!(
+        Sha3_512::new().unwrap().finalize().unwrap().to_vec(),
+        decode(
+            "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+             15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"
+        )
+    );
+}
+
+#[test_case]
+fn shake128_fips202() {
+    let mut out = [0_u8; 32];
+    Shake128::new().unwrap().finalize_into(&mut out).unwrap();
+    assert_eq!(
+        out.to_vec(),
+        decode("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")
+    );
+
+    Shake128::digest(ABC, &mut out).unwrap();
+    assert_eq!(
+        out.to_vec(),
+        decode("5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8")
+    );
+
+    // 4096 bits of output, squeezed over several blocks
+    let mut out = [0_u8; 512];
+    let mut shake = Shake128::new().unwrap();
+    for data in A3.chunks(167) {
+        shake.update(data).unwrap();
+    }
+    shake.finalize_into_reset(&mut out).unwrap();
+    assert_eq!(
+        out[..32].to_vec(),
+        decode("131ab8d2b594946b9c81333f9bb6e0ce75c3b93104fa3469d3917457385da037")
+    );
+    assert_eq!(
+        out[480..].to_vec(),
+        decode("44c9fb359fd56ac0a9a75a743cff6862f17d7259ab075216c0699511643b6439")
+    );
+
+    let mut short = [0_u8; 32];
+    shake.update(&A3).unwrap();
+    shake.finalize_into(&mut short).unwrap();
+    assert_eq!(short, out[..32]);
+
+    assert_eq!(
+        Shake128::digest(ABC, &mut []).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
+
+#[test_case]
+fn shake256_fips202() {
+    let mut out = [0_u8; 64];
+    Shake256::new().unwrap().finalize_into(&mut out).unwrap();
+    assert_eq!(
+        out.to_vec(),
+        decode(
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f\
+             d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"
+        )
+    );
+
+    Shake256::digest(ABC, &mut out).unwrap();
+    assert_eq!(
+        out.to_vec(),
+        decode(
+            "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+             d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+        )
+    );
+
+    for chunk in CHUNKS {
+        let mut out = [0_u8; 512];
+        let mut shake = Shake256::new().unwrap();
+        for data in A3.chunks(chunk) {
+            shake.update(data).unwrap();
+        }
+        shake.finalize_into(&mut out).unwrap();
+        assert_eq!(
+            out[..32].to_vec(),
+            decode("cd8a920ed141aa0407a22d59288652e9d9f1a7ee0c1e7c1ca699424da84a904d"),
+            "{}",
+            chunk
+        );
+        assert_eq!(
+            out[480..].to_vec(),
+            decode("6a1a9d7846436e4dca5728b6f760eef0ca92bf0be5615e96959d767197a0beeb"),
+            "{}",
+            chunk
+        );
+    }
+}
@@ -0,0 +1,279 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::mac::{HMac, HashType};
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{Mac256bit, MAC_256BIT_SIZE};
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub const SHARE_ID_SIZE: usize = 16;
+pub const SHARE_TAG_SIZE: usize = MAC_256BIT_SIZE;
+
+// id || threshold || index
+const SHARE_HEADER_SIZE: usize = SHARE_ID_SIZE + 2;
+
+/// One share of a secret split with [`Shamir`].
+///
+/// Shares of the same split carry the same random id, so shares from
+/// different splits are never combined. The tag authenticates the whole share
+/// under the key the `Shamir` instance was created with.
+#[derive(Clone)]
+pub struct Share {
+    id: [u8; SHARE_ID_SIZE],
+    threshold: u8,
+    index: u8,
+    value: Vec<u8>,
+    tag: Mac256bit,
+}
+
+impl Share {
+    #[inline]
+    pub fn id(&self) -> &[u8; SHARE_ID_SIZE] {
+        &self.id
+    }
+
+    #[inline]
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// The x coordinate of the share, from 1 to the number of shares.
+    #[inline]
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    #[inline]
+    pub fn tag(&self) -> &Mac256bit {
+        &self.tag
+    }
+
+    /// Encodes the share as id || threshold || index || value || tag.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SHARE_HEADER_SIZE + self.value.len() + SHARE_TAG_SIZE);
+        bytes.extend_from_slice(&self.id);
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.extend_from_slice(&self.value);
+        bytes.extend_from_slice(&self.tag);
+        bytes
+    }
+
+    /// Decodes a share produced by `to_bytes`. The tag is not checked here;
+    /// see [`Shamir::verify`].
+    pub fn from_slice(bytes: &[u8]) -> SgxResult<Share> {
+        ensure!(
+            bytes.len() > SHARE_HEADER_SIZE + SHARE_TAG_SIZE,
+            SgxStatus::InvalidParameter
+        );
+
+        let (header, rest) = bytes.split_at(SHARE_HEADER_SIZE);
+        let (value, tag) = rest.split_at(rest.len() - SHARE_TAG_SIZE);
+
+        let threshold = header[SHARE_ID_SIZE];
+        let index = header[SHARE_ID_SIZE + 1];
+        ensure!(threshold != 0 && index != 0, SgxStatus::InvalidParameter);
+
+        let mut share = Share {
+            id: [0_u8; SHARE_ID_SIZE],
+            threshold,
+            index,
+            value: value.to_vec(),
+            tag: [0_u8; SHARE_TAG_SIZE],
+        };
+        share.id.copy_from_slice(&header[..SHARE_ID_SIZE]);
+        share.tag.copy_from_slice(tag);
+        Ok(share)
+    }
+}
+
+impl Drop for Share {
+    fn drop(&mut self) {
+        self.value.fill(0);
+    }
+}
+
+/// Shamir secret sharing over GF(2^8).
+///
+/// Each byte of the secret is shared with its own random polynomial of degree
+/// `threshold - 1`, so any `threshold` shares recover the secret and fewer
+/// reveal nothing about it. Field arithmetic is branch-free and table-free,
+/// and every share is authenticated with HMAC-SHA256 under `auth_key` before
+/// it is used; a modified share fails with SgxStatus::MacMismatch.
+pub struct Shamir {
+    auth_key: Vec<u8>,
+}
+
+impl Shamir {
+    pub fn new(auth_key: &[u8]) -> SgxResult<Shamir> {
+        ensure!(
+            !auth_key.is_empty() && auth_key.len() <= i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+        Ok(Shamir {
+            auth_key: auth_key.to_vec(),
+        })
+    }
+
+    /// Splits `secret` into `count` shares, any `threshold` of which recover it.
+    pub fn split(&self, secret: &[u8], threshold: u8, count: u8) -> SgxResult<Vec<Share>> {
+        ensure!(!secret.is_empty(), SgxStatus::InvalidParameter);
+        ensure!(
+            secret.len() < i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            threshold != 0 && threshold <= count,
+            SgxStatus::InvalidParameter
+        );
+
+        let mut id = [0_u8; SHARE_ID_SIZE];
+        crate::rand(&mut id);
+
+        // coeffs[j * len + i] is the coefficient of x^(j + 1) for byte i; the
+        // constant term is the secret itself.
+        let len = secret.len();
+        let mut coeffs = vec![0_u8; (threshold as usize - 1) * len];
+        crate::rand(&mut coeffs);
+
+        let mut shares = Vec::with_capacity(count as usize);
+        for index in 1..=count {
+            let mut value = vec![0_u8; len];
+            for (i, y) in value.iter_mut().enumerate() {
+                let mut acc = 0_u8;
+                for j in (0..threshold as usize - 1).rev() {
+                    acc = gf_mul(acc, index) ^ coeffs[j * len + i];
+                }
+                *y = gf_mul(acc, index) ^ secret[i];
+            }
+
+            let mut share = Share {
+                id,
+                threshold,
+                index,
+                value,
+                tag: [0_u8; SHARE_TAG_SIZE],
+            };
+            share.tag = self.compute_tag(&share)?;
+            shares.push(share);
+        }
+
+        coeffs.fill(0);
+        Ok(shares)
+    }
+
+    pub fn verify(&self, share: &Share) -> SgxResult {
+        let mut hmac = HMac::new(&self.auth_key, HashType::Sha256)?;
+        self.update_tag(&mut hmac, share)?;
+        hmac.verify(&share.tag)
+    }
+
+    /// Recovers the secret from at least `threshold` shares of one split.
+    /// Every share is verified, and the first `threshold` of them are used.
+    pub fn combine(&self, shares: &[Share]) -> SgxResult<Vec<u8>> {
+        ensure!(!shares.is_empty(), SgxStatus::InvalidParameter);
+
+        let first = &shares[0];
+        let threshold = first.threshold as usize;
+        ensure!(shares.len() >= threshold, SgxStatus::InvalidParameter);
+        for share in shares {
+            ensure!(
+                share.id == first.id
+                    && share.threshold == first.threshold
+                    && share.value.len() == first.value.len(),
+                SgxStatus::InvalidParameter
+            );
+            self.verify(share)?;
+        }
+
+        let shares = &shares[..threshold];
+        for (i, share) in shares.iter().enumerate() {
+            ensure!(
+                shares[..i].iter().all(|s| s.index != share.index),
+                SgxStatus::InvalidParameter
+            );
+        }
+
+        // Lagrange interpolation at x = 0. The indices are public, so only the
+        // share values need constant-time handling.
+        let mut secret = vec![0_u8; first.value.len()];
+        for (i, share) in shares.iter().enumerate() {
+            let mut num = 1_u8;
+            let mut den = 1_u8;
+            for (j, other) in shares.iter().enumerate() {
+                if i != j {
+                    num = gf_mul(num, other.index);
+                    den = gf_mul(den, other.index ^ share.index);
+                }
+            }
+            let basis = gf_mul(num, gf_inv(den));
+            for (s, y) in secret.iter_mut().zip(share.value.iter()) {
+                *s ^= gf_mul(basis, *y);
+            }
+        }
+        Ok(secret)
+    }
+
+    fn compute_tag(&self, share: &Share) -> SgxResult<Mac256bit> {
+        let mut hmac = HMac::new(&self.auth_key, HashType::Sha256)?;
+        self.update_tag(&mut hmac, share)?;
+        hmac.finalize()
+    }
+
+    fn update_tag(&self, hmac: &mut HMac, share: &Share) -> SgxResult {
+        hmac.update(&share.id)?;
+        hmac.update(&[share.threshold, share.index])?;
+        hmac.update(share.value.as_slice())
+    }
+}
+
+impl Drop for Shamir {
+    fn drop(&mut self) {
+        self.auth_key.fill(0);
+    }
+}
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, without
+// secret-dependent branches or table lookups.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut r = 0_u8;
+    for _ in 0..8 {
+        r ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    r
+}
+
+// a^254 = a^-1 for a != 0.
+fn gf_inv(a: u8) -> u8 {
+    let a2 = gf_mul(a, a);
+    let a3 = gf_mul(a2, a);
+    let a6 = gf_mul(a3, a3);
+    let a12 = gf_mul(a6, a6);
+    let a15 = gf_mul(a12, a3);
+    let a30 = gf_mul(a15, a15);
+    let a60 = gf_mul(a30, a30);
+    let a63 = gf_mul(a60, a3);
+    let a126 = gf_mul(a63, a63);
+    let a127 = gf_mul(a126, a);
+    gf_mul(a127, a127)
+}
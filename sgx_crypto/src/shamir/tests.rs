@@ -0,0 +1,133 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::*;
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+
+const AUTH_KEY: &[u8] = b"shamir test authentication key";
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+#[test_case]
+fn gf_arithmetic() {
+    // FIPS 197, section 4.2.
+    assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+    assert_eq!(gf_mul(0x57, 0x13), 0xfe);
+    for a in 1..=255_u8 {
+        assert_eq!(gf_mul(a, gf_inv(a)), 1);
+    }
+}
+
+#[test_case]
+fn split_combine() {
+    let shamir = Shamir::new(AUTH_KEY).unwrap();
+    let shares = shamir.split(SECRET, 3, 5).unwrap();
+    assert_eq!(shares.len(), 5);
+    assert!(shares.iter().all(|s| s.id() == shares[0].id()));
+
+    // Every 3-of-5 subset recovers the secret.
+    for a in 0..5 {
+        for b in a + 1..5 {
+            for c in b + 1..5 {
+                let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                assert_eq!(shamir.combine(&subset).unwrap(), SECRET);
+            }
+        }
+    }
+    assert_eq!(shamir.combine(&shares).unwrap(), SECRET);
+
+    assert_eq!(
+        shamir.combine(&shares[..2]).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
+
+#[test_case]
+fn threshold_one() {
+    let shamir = Shamir::new(AUTH_KEY).unwrap();
+    let shares = shamir.split(SECRET, 1, 3).unwrap();
+    for share in shares.iter() {
+        assert_eq!(share.value, SECRET);
+        assert_eq!(shamir.combine(&[share.clone()]).unwrap(), SECRET);
+    }
+}
+
+#[test_case]
+fn invalid_split() {
+    let shamir = Shamir::new(AUTH_KEY).unwrap();
+    assert!(shamir.split(&[], 2, 3).is_err());
+    assert!(shamir.split(SECRET, 0, 3).is_err());
+    assert!(shamir.split(SECRET, 4, 3).is_err());
+    assert!(Shamir::new(&[]).is_err());
+}
+
+#[test_case]
+fn authentication() {
+    let shamir = Shamir::new(AUTH_KEY).unwrap();
+    let shares = shamir.split(SECRET, 2, 3).unwrap();
+
+    let mut tampered = shares[0].clone();
+    tampered.value[0] ^= 1;
+    assert_eq!(
+        shamir.combine(&[tampered, shares[1].clone()]).unwrap_err(),
+        SgxStatus::MacMismatch
+    );
+
+    let mut moved = shares[0].clone();
+    moved.index = 4;
+    assert_eq!(shamir.verify(&moved).unwrap_err(), SgxStatus::MacMismatch);
+
+    let other = Shamir::new(b"another key").unwrap();
+    assert_eq!(
+        other.combine(&shares[..2]).unwrap_err(),
+        SgxStatus::MacMismatch
+    );
+
+    // Shares of different splits are never mixed.
+    let second = shamir.split(SECRET, 2, 3).unwrap();
+    assert_eq!(
+        shamir
+            .combine(&[shares[0].clone(), second[1].clone()])
+            .unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+
+    assert_eq!(
+        shamir
+            .combine(&[shares[0].clone(), shares[0].clone()])
+            .unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
+
+#[test_case]
+fn encoding() {
+    let shamir = Shamir::new(AUTH_KEY).unwrap();
+    let shares = shamir.split(SECRET, 2, 2).unwrap();
+    let decoded: Vec<Share> = shares
+        .iter()
+        .map(|s| Share::from_slice(&s.to_bytes()).unwrap())
+        .collect();
+    for (a, b) in shares.iter().zip(decoded.iter()) {
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        shamir.verify(b).unwrap();
+    }
+    assert_eq!(shamir.combine(&decoded).unwrap(), SECRET);
+
+    let bytes = shares[0].to_bytes();
+    assert!(Share::from_slice(&bytes[..SHARE_HEADER_SIZE + SHARE_TAG_SIZE]).is_err());
+}
@@ -0,0 +1,136 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{kdf, point_from_be, point_to_be, Sm2Context};
+use super::{Sm2KeyPair, Sm2PrivateKey, Sm2PublicKey};
+use crate::sm3::Sm3;
+use alloc::vec::Vec;
+use core::mem;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{Sm3Hash, ECP256_KEY_SIZE};
+
+const UNCOMPRESSED_POINT: u8 = 0x04;
+const C1_SIZE: usize = 1 + ECP256_KEY_SIZE * 2;
+const C3_SIZE: usize = mem::size_of::<Sm3Hash>();
+
+// SM2 public key encryption (GB/T 32918.4). The ciphertext is C1 || C3 || C2,
+// with C1 an uncompressed point, C3 the SM3 check value and C2 the same
+// length as the plaintext.
+pub const SM2_CIPHERTEXT_OVERHEAD: usize = C1_SIZE + C3_SIZE;
+
+impl Sm2PublicKey {
+    pub fn encrypt(&self, plaintext: &[u8]) -> SgxResult<Vec<u8>> {
+        let len = plaintext.len();
+        ensure!(
+            len > 0 && len < i32::MAX as usize - SM2_CIPHERTEXT_OVERHEAD,
+            SgxStatus::InvalidParameter
+        );
+
+        let ctx = Sm2Context::new()?;
+        let mut ciphertext = vec![0_u8; len + SM2_CIPHERTEXT_OVERHEAD];
+        loop {
+            let mut ephemeral = Sm2KeyPair::create()?;
+            let point = ctx.point_mul(&ephemeral.private.0.key, &self.0);
+            ephemeral.private.clear();
+            let mut shared = point_to_be(&point?);
+
+            let c2 = &mut ciphertext[SM2_CIPHERTEXT_OVERHEAD..];
+            kdf(&[&shared], c2)?;
+            // t must not be all zero, otherwise pick another k.
+            if c2.iter().fold(0, |acc, b| acc | b) == 0 {
+                shared.fill(0);
+                continue;
+            }
+            for (c, m) in c2.iter_mut().zip(plaintext.iter()) {
+                *c ^= *m;
+            }
+
+            let c3 = check_value(&shared, plaintext);
+            shared.fill(0);
+
+            ciphertext[0] = UNCOMPRESSED_POINT;
+            ciphertext[1..C1_SIZE].copy_from_slice(&point_to_be(&ephemeral.public.0));
+            ciphertext[C1_SIZE..SM2_CIPHERTEXT_OVERHEAD].copy_from_slice(c3?.as_ref());
+            break;
+        }
+        Ok(ciphertext)
+    }
+}
+
+impl Sm2PrivateKey {
+    pub fn decrypt(&self, ciphertext: &[u8]) -> SgxResult<Vec<u8>> {
+        let len = ciphertext.len();
+        ensure!(
+            len > SM2_CIPHERTEXT_OVERHEAD && len < i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            ciphertext[0] == UNCOMPRESSED_POINT,
+            SgxStatus::InvalidParameter
+        );
+
+        // The point multiplication rejects a C1 which is not on the curve.
+        let c1 = point_from_be(&ciphertext[1..C1_SIZE])?;
+        let ctx = Sm2Context::new()?;
+        let mut shared = point_to_be(&ctx.point_mul(&self.0.key, &c1)?);
+
+        let mut plaintext = vec![0_u8; len - SM2_CIPHERTEXT_OVERHEAD];
+        let result = kdf(&[&shared], &mut plaintext).and_then(|_| {
+            ensure!(
+                plaintext.iter().fold(0, |acc, b| acc | b) != 0,
+                SgxStatus::InvalidParameter
+            );
+            for (m, c) in plaintext
+                .iter_mut()
+                .zip(ciphertext[SM2_CIPHERTEXT_OVERHEAD..].iter())
+            {
+                *m ^= *c;
+            }
+
+            let c3 = check_value(&shared, &plaintext)?;
+            ensure!(
+                c3.hash[..].ct_eq(&ciphertext[C1_SIZE..SM2_CIPHERTEXT_OVERHEAD]),
+                SgxStatus::MacMismatch
+            );
+            Ok(())
+        });
+        shared.fill(0);
+
+        if let Err(e) = result {
+            plaintext.fill(0);
+            bail!(e);
+        }
+        Ok(plaintext)
+    }
+}
+
+impl Sm2KeyPair {
+    #[inline]
+    pub fn decrypt(&self, ciphertext: &[u8]) -> SgxResult<Vec<u8>> {
+        self.private.decrypt(ciphertext)
+    }
+}
+
+// C3 = SM3(x2 || M || y2)
+fn check_value(shared: &[u8; ECP256_KEY_SIZE * 2], plaintext: &[u8]) -> SgxResult<Sm3Hash> {
+    let mut sm3 = Sm3::new()?;
+    sm3.update(&shared[..ECP256_KEY_SIZE])?;
+    sm3.update(plaintext)?;
+    sm3.update(&shared[ECP256_KEY_SIZE..])?;
+    sm3.finalize()
+}
@@ -0,0 +1,240 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{kdf, point_to_be, Sm2Context, Sm2KeyPair, Sm2PublicKey};
+use crate::sm3::Sm3;
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{Ec256PrivateKey, Ec256PublicKey, Sm3Hash, ECP256_KEY_SIZE};
+
+// The default user identity of GM/T 0009.
+pub const SM2_DEFAULT_ID: &[u8] = b"1234567812345678";
+
+// Curve parameters a, b, xG and yG, big-endian, as hashed into Z.
+const SM2_CURVE_PARAMS: [u8; ECP256_KEY_SIZE * 4] = [
+    0xFF, 0xFF, 0xFF, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFC,
+    0x28, 0xE9, 0xFA, 0x9E, 0x9D, 0x9F, 0x5E, 0x34, 0x4D, 0x5A, 0x9E, 0x4B, 0xCF, 0x65, 0x09, 0xA7,
+    0xF3, 0x97, 0x89, 0xF5, 0x15, 0xAB, 0x8F, 0x92, 0xDD, 0xBC, 0xBD, 0x41, 0x4D, 0x94, 0x0E, 0x93,
+    0x32, 0xC4, 0xAE, 0x2C, 0x1F, 0x19, 0x81, 0x19, 0x5F, 0x99, 0x04, 0x46, 0x6A, 0x39, 0xC9, 0x94,
+    0x8F, 0xE3, 0x0B, 0xBF, 0xF2, 0x66, 0x0B, 0xE1, 0x71, 0x5A, 0x45, 0x89, 0x33, 0x4C, 0x74, 0xC7,
+    0xBC, 0x37, 0x36, 0xA2, 0xF4, 0xF6, 0x77, 0x9C, 0x59, 0xBD, 0xCE, 0xE3, 0x6B, 0x69, 0x21, 0x53,
+    0xD0, 0xA9, 0x87, 0x7C, 0xC6, 0x2A, 0x47, 0x40, 0x02, 0xDF, 0x32, 0xE5, 0x21, 0x39, 0xF0, 0xA0,
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sm2ExchangeRole {
+    Initiator,
+    Responder,
+}
+
+// One party of the SM2 key exchange protocol (GB/T 32918.3). Both parties
+// send their ephemeral public key and compute the shared key; the optional
+// confirmation hashes are then exchanged and checked.
+#[derive(Debug)]
+pub struct Sm2KeyExchange {
+    role: Sm2ExchangeRole,
+    key_pair: Sm2KeyPair,
+    z: Sm3Hash,
+    ephemeral: Sm2KeyPair,
+}
+
+impl Sm2KeyExchange {
+    pub fn new(
+        role: Sm2ExchangeRole,
+        key_pair: &Sm2KeyPair,
+        id: &[u8],
+    ) -> SgxResult<Sm2KeyExchange> {
+        Self::with_ephemeral(role, key_pair, Sm2KeyPair::create()?, id)
+    }
+
+    pub(super) fn with_ephemeral(
+        role: Sm2ExchangeRole,
+        key_pair: &Sm2KeyPair,
+        ephemeral: Sm2KeyPair,
+        id: &[u8],
+    ) -> SgxResult<Sm2KeyExchange> {
+        let z = digest_z(id, &key_pair.public)?;
+
+        Ok(Sm2KeyExchange {
+            role,
+            key_pair: *key_pair,
+            z,
+            ephemeral,
+        })
+    }
+
+    #[inline]
+    pub fn role(&self) -> Sm2ExchangeRole {
+        self.role
+    }
+
+    #[inline]
+    pub fn ephemeral_public_key(&self) -> Sm2PublicKey {
+        self.ephemeral.public
+    }
+
+    pub fn compute_key(
+        &self,
+        peer_public_key: &Sm2PublicKey,
+        peer_id: &[u8],
+        peer_ephemeral_key: &Sm2PublicKey,
+        key_len: usize,
+    ) -> SgxResult<Sm2ExchangeKey> {
+        ensure!(
+            key_len > 0 && key_len <= i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+
+        let peer_z = digest_z(peer_id, peer_public_key)?;
+
+        // U = [t](P + [x2']R) with t = (d + x1' * r) mod n, computed as
+        // [d]Q + [x1']([r]Q) so that t never leaves the curve arithmetic.
+        let ctx = Sm2Context::new()?;
+        let peer_x = reduced_x(&peer_ephemeral_key.0);
+        let q = ctx.point_mul(&peer_x, &peer_ephemeral_key.0)?;
+        let q = ctx.point_add(&peer_public_key.0, &q)?;
+
+        let mut dq = ctx.point_mul(&self.key_pair.private.0.key, &q)?;
+        let mut rq = ctx.point_mul(&self.ephemeral.private.0.key, &q)?;
+        let point = ctx
+            .point_mul(&reduced_x(&self.ephemeral.public.0), &rq)
+            .and_then(|xrq| ctx.point_add(&dq, &xrq));
+        dq.as_mut().fill(0);
+        rq.as_mut().fill(0);
+        let mut u = point_to_be(&point?);
+
+        let local_r = point_to_be(&self.ephemeral.public.0);
+        let peer_r = point_to_be(&peer_ephemeral_key.0);
+        let (za, zb, ra, rb) = match self.role {
+            Sm2ExchangeRole::Initiator => (&self.z, &peer_z, &local_r, &peer_r),
+            Sm2ExchangeRole::Responder => (&peer_z, &self.z, &peer_r, &local_r),
+        };
+
+        let (xu, yu) = u.split_at(ECP256_KEY_SIZE);
+        let mut key = vec![0_u8; key_len];
+        let result = kdf(&[&u, &za.hash, &zb.hash], &mut key).and_then(|_| {
+            let mut sm3 = Sm3::new()?;
+            let parts: [&[u8]; 5] = [xu, &za.hash, &zb.hash, ra, rb];
+            for data in parts {
+                sm3.update(data)?;
+            }
+            let inner = sm3.finalize()?;
+
+            let s2 = confirmation(0x02, yu, &inner)?;
+            let s3 = confirmation(0x03, yu, &inner)?;
+            Ok(match self.role {
+                Sm2ExchangeRole::Initiator => (s3, s2),
+                Sm2ExchangeRole::Responder => (s2, s3),
+            })
+        });
+        u.fill(0);
+
+        match result {
+            Ok((confirmation, peer_confirmation)) => Ok(Sm2ExchangeKey {
+                key,
+                confirmation,
+                peer_confirmation,
+            }),
+            Err(e) => {
+                key.fill(0);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Drop for Sm2KeyExchange {
+    fn drop(&mut self) {
+        self.key_pair.clear();
+        self.ephemeral.clear();
+    }
+}
+
+// The agreed key, together with the confirmation hash to send to the peer
+// (S_A for the initiator, S_B for the responder) and the one expected back.
+pub struct Sm2ExchangeKey {
+    key: Vec<u8>,
+    confirmation: Sm3Hash,
+    peer_confirmation: Sm3Hash,
+}
+
+impl Sm2ExchangeKey {
+    #[inline]
+    pub fn key(&self) -> &[u8] {
+        self.key.as_slice()
+    }
+
+    #[inline]
+    pub fn confirmation(&self) -> Sm3Hash {
+        self.confirmation
+    }
+
+    pub fn verify_confirmation(&self, confirmation: &Sm3Hash) -> SgxResult {
+        ensure!(
+            self.peer_confirmation.hash.ct_eq(&confirmation.hash),
+            SgxStatus::MacMismatch
+        );
+        Ok(())
+    }
+}
+
+impl Drop for Sm2ExchangeKey {
+    fn drop(&mut self) {
+        self.key.fill(0);
+    }
+}
+
+impl core::fmt::Debug for Sm2ExchangeKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Sm2ExchangeKey")
+            .field("confirmation", &self.confirmation)
+            .finish_non_exhaustive()
+    }
+}
+
+// Z = SM3(ENTL || ID || a || b || xG || yG || xA || yA)
+pub fn digest_z(id: &[u8], public_key: &Sm2PublicKey) -> SgxResult<Sm3Hash> {
+    ensure!(
+        !id.is_empty() && id.len() <= u16::MAX as usize / 8,
+        SgxStatus::InvalidParameter
+    );
+
+    let mut sm3 = Sm3::new()?;
+    sm3.update(&((id.len() * 8) as u16).to_be_bytes())?;
+    sm3.update(id)?;
+    sm3.update(&SM2_CURVE_PARAMS)?;
+    sm3.update(&point_to_be(&public_key.0))?;
+    sm3.finalize()
+}
+
+// x' = 2^w + (x & (2^w - 1)) with w = 127, as a little-endian scalar.
+fn reduced_x(point: &Ec256PublicKey) -> Ec256PrivateKey {
+    let mut x = Ec256PrivateKey::default();
+    x.r[..16].copy_from_slice(&point.gx[..16]);
+    x.r[15] |= 0x80;
+    x
+}
+
+fn confirmation(prefix: u8, yu: &[u8], inner: &Sm3Hash) -> SgxResult<Sm3Hash> {
+    let mut sm3 = Sm3::new()?;
+    sm3.update(&[prefix])?;
+    sm3.update(yu)?;
+    sm3.update(&inner.hash)?;
+    sm3.finalize()
+}
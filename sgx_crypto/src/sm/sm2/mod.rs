@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::ecc::generate_nonce;
+use crate::sm3::Sm3;
 use crate::sm4::ccm::{Aad, Nonce, Sm4Ccm};
 use core::convert::From;
 use core::convert::TryInto;
@@ -27,12 +29,25 @@ use sgx_types::memeq::ConstTimeEq;
 use sgx_types::types::ECP256_KEY_SIZE;
 use sgx_types::types::{
     AlignEc256PrivateKey, AlignEc256SharedKey, AlignKey128bit, Ec256PrivateKey, Ec256PublicKey,
-    Ec256SharedKey, Ec256Signature, EcResult, EccHandle, Key128bit, Sha256Hash,
+    Ec256SharedKey, Ec256Signature, EcResult, EccHandle, Key128bit, Sha256Hash, Sm3Hash,
 };
 
 #[cfg(any(feature = "tserialize", feature = "userialize"))]
 use sgx_serialize::{Deserialize, Serialize};
 
+mod encryption;
+pub mod exchange;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub use encryption::SM2_CIPHERTEXT_OVERHEAD;
+
+// The user id of the Z digest in sgx_sm2_sign and sgx_sm2_verify.
+const SGX_SM2_USER_ID: [u8; 14] = [
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+];
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(
     any(feature = "tserialize", feature = "userialize"),
@@ -137,6 +152,73 @@ impl Sm2PrivateKey {
         Ok(signature.into())
     }
 
+    /// Signs with the nonce derived from the key and the message as in
+    /// RFC 6979, rather than one drawn from the RNG. The same key and data
+    /// always give the same signature.
+    pub fn sign_deterministic<T: ?Sized>(&self, data: &T) -> SgxResult<Sm2Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let hash = self.digest(data)?;
+        self.sign_with_nonce(&hash, false)
+    }
+
+    /// As `sign_deterministic`, with RNG output also mixed into the nonce
+    /// (RFC 6979, section 3.6). Signatures are randomized, and the nonce stays
+    /// secret even if the RNG is weak.
+    pub fn sign_hedged<T: ?Sized>(&self, data: &T) -> SgxResult<Sm2Signature>
+    where
+        T: ContiguousMemory,
+    {
+        let hash = self.digest(data)?;
+        self.sign_with_nonce(&hash, true)
+    }
+
+    fn sign_with_nonce(&self, hash: &Sm3Hash, hedged: bool) -> SgxResult<Sm2Signature> {
+        let mut k = generate_nonce::<Sm3>(&self.0.key.r, &Self::SM2_ORDER, &hash.hash, hedged)?;
+        let mut nonce = Ec256PrivateKey::default();
+        nonce.r.copy_from_slice(&k);
+        k.fill(0);
+
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_sm2_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+
+        let mut signature = Ec256Signature::default();
+        let status = unsafe {
+            sgx_sm2_sign_with_nonce(
+                hash.hash.as_ptr(),
+                &self.0.key as *const Ec256PrivateKey,
+                &nonce as *const Ec256PrivateKey,
+                &mut signature as *mut Ec256Signature,
+                handle,
+            )
+        };
+        let _ = unsafe { sgx_sm2_close_context(handle) };
+        nonce.r.fill(0);
+
+        ensure!(status.is_success(), status);
+        Ok(signature.into())
+    }
+
+    // e = SM3(Z || M), with Z over the user id that sgx_sm2_sign hashes.
+    fn digest<T: ?Sized>(&self, data: &T) -> SgxResult<Sm3Hash>
+    where
+        T: ContiguousMemory,
+    {
+        let size = mem::size_of_val(data);
+        ensure!(
+            (size > 0 && size < i32::MAX as usize),
+            SgxStatus::InvalidParameter
+        );
+
+        let z = exchange::digest_z(&SGX_SM2_USER_ID, &self.export_public_key()?)?;
+        let mut sm3 = Sm3::new()?;
+        sm3.update(&z.hash)?;
+        sm3.update(data)?;
+        sm3.finalize()
+    }
+
     pub fn create_with_seed<H: AsRef<[u8]>>(hash_drg: H) -> SgxResult<Sm2PrivateKey> {
         let hash_drg = hash_drg.as_ref();
         if (hash_drg.is_empty()) || (hash_drg.len() > i32::MAX as usize) {
@@ -368,6 +450,105 @@ impl Sm2Signature {
     }
 }
 
+// An SM2 curve context for the point operations used by encryption and key
+// exchange. Points are little-endian, as everywhere else in this module.
+struct Sm2Context(EccHandle);
+
+impl Sm2Context {
+    fn new() -> SgxResult<Sm2Context> {
+        let mut handle: EccHandle = ptr::null_mut();
+        let status = unsafe { sgx_sm2_open_context(&mut handle as *mut EccHandle) };
+        ensure!(status.is_success(), status);
+        Ok(Sm2Context(handle))
+    }
+
+    // Fails with InvalidParameter if the point is not on the curve or the
+    // result is the point at infinity.
+    fn point_mul(
+        &self,
+        scalar: &Ec256PrivateKey,
+        point: &Ec256PublicKey,
+    ) -> SgxResult<Ec256PublicKey> {
+        let mut result = Ec256PublicKey::default();
+        let status = unsafe {
+            sgx_sm2_point_mul(
+                scalar as *const Ec256PrivateKey,
+                point as *const Ec256PublicKey,
+                &mut result as *mut Ec256PublicKey,
+                self.0,
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(result)
+    }
+
+    fn point_add(&self, p: &Ec256PublicKey, q: &Ec256PublicKey) -> SgxResult<Ec256PublicKey> {
+        let mut result = Ec256PublicKey::default();
+        let status = unsafe {
+            sgx_sm2_point_add(
+                p as *const Ec256PublicKey,
+                q as *const Ec256PublicKey,
+                &mut result as *mut Ec256PublicKey,
+                self.0,
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(result)
+    }
+}
+
+impl Drop for Sm2Context {
+    fn drop(&mut self) {
+        let _ = unsafe { sgx_sm2_close_context(self.0) };
+    }
+}
+
+// The big-endian x || y encoding of a point, as hashed by the SM2 schemes.
+fn point_to_be(point: &Ec256PublicKey) -> [u8; ECP256_KEY_SIZE * 2] {
+    let mut be = [0_u8; ECP256_KEY_SIZE * 2];
+    for (b, x) in be[..ECP256_KEY_SIZE].iter_mut().zip(point.gx.iter().rev()) {
+        *b = *x;
+    }
+    for (b, y) in be[ECP256_KEY_SIZE..].iter_mut().zip(point.gy.iter().rev()) {
+        *b = *y;
+    }
+    be
+}
+
+fn point_from_be(be: &[u8]) -> SgxResult<Ec256PublicKey> {
+    ensure!(be.len() == ECP256_KEY_SIZE * 2, SgxStatus::InvalidParameter);
+
+    let mut point = Ec256PublicKey::default();
+    for (x, b) in point.gx.iter_mut().zip(be[..ECP256_KEY_SIZE].iter().rev()) {
+        *x = *b;
+    }
+    for (y, b) in point.gy.iter_mut().zip(be[ECP256_KEY_SIZE..].iter().rev()) {
+        *y = *b;
+    }
+    Ok(point)
+}
+
+// The key derivation function of GB/T 32918.4 section 5.4.3: SM3 over the
+// shared data followed by a 32-bit big-endian counter starting at 1.
+fn kdf(z: &[&[u8]], key: &mut [u8]) -> SgxResult {
+    ensure!(key.len() <= i32::MAX as usize, SgxStatus::InvalidParameter);
+
+    for (ct, block) in key.chunks_mut(mem::size_of::<Sm3Hash>()).enumerate() {
+        let mut sm3 = Sm3::new()?;
+        for z in z.iter().filter(|z| !z.is_empty()) {
+            sm3.update(*z)?;
+        }
+        sm3.update(&(ct as u32 + 1).to_be_bytes())?;
+
+        let mut hash = sm3.finalize()?;
+        block.copy_from_slice(&hash.hash[..block.len()]);
+        hash.hash.fill(0);
+    }
+    Ok(())
+}
+
 impl From<(Sm2PrivateKey, Sm2PublicKey)> for Sm2KeyPair {
     fn from(key_pair: (Sm2PrivateKey, Sm2PublicKey)) -> Sm2KeyPair {
         Sm2KeyPair {
@@ -0,0 +1,231 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::exchange::{digest_z, Sm2ExchangeRole, Sm2KeyExchange, SM2_DEFAULT_ID};
+use super::*;
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// A key pair from a little-endian private key.
+fn key_pair(private: &str) -> Sm2KeyPair {
+    let private = Sm2PrivateKey::from(<[u8; ECP256_KEY_SIZE]>::try_from(decode(private)).unwrap());
+    let public = private.export_public_key().unwrap();
+    Sm2KeyPair::from((private, public))
+}
+
+#[test_case]
+fn deterministic_signature() {
+    let key_pair = Sm2KeyPair::create().unwrap();
+    let private_key = key_pair.private_key();
+    let public_key = key_pair.public_key();
+
+    let sig1 = private_key.sign_deterministic(b"sample").unwrap();
+    let sig2 = private_key.sign_deterministic(b"sample").unwrap();
+    assert_eq!(sig1, sig2);
+    assert!(public_key.verify(b"sample", &sig1).unwrap());
+
+    let sig3 = private_key.sign_deterministic(b"test").unwrap();
+    assert_ne!(sig1, sig3);
+    assert!(public_key.verify(b"test", &sig3).unwrap());
+    assert!(!public_key.verify(b"sample", &sig3).unwrap());
+}
+
+#[test_case]
+fn hedged_signature() {
+    let key_pair = Sm2KeyPair::create().unwrap();
+    let private_key = key_pair.private_key();
+    let public_key = key_pair.public_key();
+
+    let sig1 = private_key.sign_hedged(b"sample").unwrap();
+    let sig2 = private_key.sign_hedged(b"sample").unwrap();
+    assert_ne!(sig1, sig2);
+    assert!(public_key.verify(b"sample", &sig1).unwrap());
+    assert!(public_key.verify(b"sample", &sig2).unwrap());
+}
+
+#[test_case]
+fn encryption_round_trip() {
+    let key_pair = Sm2KeyPair::create().unwrap();
+    let public_key = key_pair.public_key();
+    let message = b"encryption standard";
+
+    let ciphertext = public_key.encrypt(message).unwrap();
+    assert_eq!(ciphertext.len(), message.len() + SM2_CIPHERTEXT_OVERHEAD);
+    assert_ne!(public_key.encrypt(message).unwrap(), ciphertext);
+    assert_eq!(key_pair.decrypt(&ciphertext).unwrap(), message);
+
+    // C3 and C2
+    for i in [SM2_CIPHERTEXT_OVERHEAD - 1, ciphertext.len() - 1] {
+        let mut tampered = ciphertext.clone();
+        tampered[i] ^= 1;
+        assert_eq!(key_pair.decrypt(&tampered), Err(SgxStatus::MacMismatch));
+    }
+    let mut tampered = ciphertext;
+    tampered[0] = 0x02;
+    assert_eq!(
+        key_pair.decrypt(&tampered),
+        Err(SgxStatus::InvalidParameter)
+    );
+    assert_eq!(public_key.encrypt(b""), Err(SgxStatus::InvalidParameter));
+}
+
+// GB/T 32918.4 ciphertext C1 || C3 || C2 under the key of GB/T 32918.5,
+// also decrypted by OpenSSL.
+#[test_case]
+fn decryption_vector() {
+    let key_pair = key_pair("b8c5f74def81fb421ab5602869939388959fd3c68ae3363fb144217b8f204539");
+    assert_eq!(
+        point_to_be(&key_pair.public_key().0).to_vec(),
+        decode(
+            "09f9df311e5421a150dd7d161e4bc5c672179fad1833fc076bb08ff356f35020\
+             ccea490ce26775a52dc6ea718cc1aa600aed05fbf35e084a6632f6072da9ad13"
+        )
+    );
+
+    let ciphertext = decode(
+        "0404ebfc718e8d1798620432268e77feb6415e2ede0e073c0f4f640ecd2e149a\
+         73e858f9d81e5430a57b36daab8f950a3c64e6ee6a63094d99283aff767e124d\
+         f059983c18f809e262923c53aec295d30383b54e39d609d160afcb1908d0bd87\
+         6621886ca989ca9c7d58087307ca93092d651efa",
+    );
+    assert_eq!(
+        key_pair.decrypt(&ciphertext).unwrap(),
+        b"encryption standard"
+    );
+}
+
+#[test_case]
+fn key_exchange_round_trip() {
+    let alice = Sm2KeyPair::create().unwrap();
+    let bob = Sm2KeyPair::create().unwrap();
+
+    let initiator = Sm2KeyExchange::new(Sm2ExchangeRole::Initiator, &alice, b"alice").unwrap();
+    let responder = Sm2KeyExchange::new(Sm2ExchangeRole::Responder, &bob, b"bob").unwrap();
+
+    let alice_key = initiator
+        .compute_key(
+            &bob.public_key(),
+            b"bob",
+            &responder.ephemeral_public_key(),
+            48,
+        )
+        .unwrap();
+    let bob_key = responder
+        .compute_key(
+            &alice.public_key(),
+            b"alice",
+            &initiator.ephemeral_public_key(),
+            48,
+        )
+        .unwrap();
+    assert_eq!(alice_key.key().len(), 48);
+    assert_eq!(alice_key.key(), bob_key.key());
+    alice_key
+        .verify_confirmation(&bob_key.confirmation())
+        .unwrap();
+    bob_key
+        .verify_confirmation(&alice_key.confirmation())
+        .unwrap();
+
+    // the responder assumes another id for the initiator
+    let bob_key = responder
+        .compute_key(
+            &alice.public_key(),
+            b"mallory",
+            &initiator.ephemeral_public_key(),
+            48,
+        )
+        .unwrap();
+    assert_ne!(alice_key.key(), bob_key.key());
+    assert_eq!(
+        alice_key.verify_confirmation(&bob_key.confirmation()),
+        Err(SgxStatus::MacMismatch)
+    );
+}
+
+// Static keys d_A, d_B and ephemeral keys r_A, r_B, little-endian, with the
+// default user id; the expected values are from an independent
+// implementation of GB/T 32918.3.
+#[test_case]
+fn key_exchange_vector() {
+    let alice = key_pair("29002348be188467e14a6c3dd62cae725269905f4916f16df15abb41e926eb81");
+    let bob = key_pair("b529a2ef0de14af1889019eb6cdaadea3823b85693a53754eaa9457d91295178");
+    let alice_ephemeral =
+        key_pair("b30ba62edb123c15877e0c393e0f990024015e300d441c49064db74d4715ded4");
+    let bob_ephemeral =
+        key_pair("d64362599d1f5c3388bdc558340fbf4e16131110edea25914809b3144812077e");
+
+    assert_eq!(
+        digest_z(SM2_DEFAULT_ID, &alice.public_key())
+            .unwrap()
+            .hash
+            .to_vec(),
+        decode("3b85a57179e11e7e513aa622991f2ca74d1807a0bd4d4b38f90987a17ac245b1")
+    );
+
+    let initiator = Sm2KeyExchange::with_ephemeral(
+        Sm2ExchangeRole::Initiator,
+        &alice,
+        alice_ephemeral,
+        SM2_DEFAULT_ID,
+    )
+    .unwrap();
+    let responder = Sm2KeyExchange::with_ephemeral(
+        Sm2ExchangeRole::Responder,
+        &bob,
+        bob_ephemeral,
+        SM2_DEFAULT_ID,
+    )
+    .unwrap();
+
+    let alice_key = initiator
+        .compute_key(
+            &bob.public_key(),
+            SM2_DEFAULT_ID,
+            &bob_ephemeral.public_key(),
+            16,
+        )
+        .unwrap();
+    let bob_key = responder
+        .compute_key(
+            &alice.public_key(),
+            SM2_DEFAULT_ID,
+            &alice_ephemeral.public_key(),
+            16,
+        )
+        .unwrap();
+
+    let key = decode("6c89347354de2484c60b4ab1fde4c6e5");
+    assert_eq!(alice_key.key(), key.as_slice());
+    assert_eq!(bob_key.key(), key.as_slice());
+    // S_A and S_B
+    assert_eq!(
+        alice_key.confirmation().hash.to_vec(),
+        decode("18c7894b3816df16cf07b05c5ec0bef5d655d58f779cc1b400a4f3884644db88")
+    );
+    assert_eq!(
+        bob_key.confirmation().hash.to_vec(),
+        decode("d3a0fe15dee185ceae907a6b595cc32a266ed7b3367e9983a896dc32fa20f8eb")
+    );
+}
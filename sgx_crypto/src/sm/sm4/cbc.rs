@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::cipher::BlockCipherMode;
 use core::array::TryFromSliceError;
 use core::convert::{From, TryFrom, TryInto};
 use sgx_crypto_sys::*;
@@ -105,10 +106,40 @@ impl Sm4Cbc {
     }
 }
 
+impl BlockCipherMode for Sm4Cbc {
+    type Key = Key128bit;
+    type Iv = Nonce;
+
+    #[inline]
+    fn new(key: &Key128bit, iv: Nonce) -> Sm4Cbc {
+        Sm4Cbc::new(key, iv)
+    }
+
+    #[inline]
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.encrypt(src, dst)
+    }
+
+    #[inline]
+    fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.encrypt_in_place(in_out)
+    }
+
+    #[inline]
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.decrypt(src, dst)
+    }
+
+    #[inline]
+    fn decrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.decrypt_in_place(in_out)
+    }
+}
+
 impl Default for Sm4Cbc {
     fn default() -> Sm4Cbc {
         let mut key = AlignKey128bit::default();
-        super::rand(&mut key.key);
+        crate::rand(&mut key.key);
 
         Sm4Cbc {
             key,
@@ -123,7 +154,7 @@ pub struct Nonce([u8; SM4CBC_IV_SIZE]);
 impl Nonce {
     pub fn new() -> Nonce {
         let mut nonce = [0_u8; SM4CBC_IV_SIZE];
-        super::rand(&mut nonce);
+        crate::rand(&mut nonce);
         Nonce(nonce)
     }
 
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::cipher::Aead;
 use core::array::TryFromSliceError;
 use core::convert::{From, TryFrom, TryInto};
 use core::ptr;
@@ -287,10 +288,46 @@ impl<A: AsRef<[u8]>> Sm4Ccm<A> {
     }
 }
 
+impl<A: AsRef<[u8]>> Aead for Sm4Ccm<A> {
+    type Key = Key128bit;
+    type Nonce = Nonce;
+
+    const NONCE_SIZE: usize = SM4CCM_IV_SIZE;
+    const ID: u16 = 0x0610;
+
+    #[inline]
+    fn zeroed_nonce() -> Nonce {
+        Nonce::zeroed()
+    }
+
+    fn encrypt_detached(
+        key: &Key128bit,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        let mut cipher = Sm4Ccm::new(key, nonce, Aad::from(aad))?;
+        cipher.encrypt(src, dst)
+    }
+
+    fn decrypt_detached(
+        key: &Key128bit,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        let mut cipher = Sm4Ccm::new(key, nonce, Aad::from(aad))?;
+        cipher.decrypt(src, dst, mac)
+    }
+}
+
 impl Default for Sm4Ccm<[u8; 0]> {
     fn default() -> Sm4Ccm<[u8; 0]> {
         let mut key = AlignKey128bit::default();
-        super::rand(&mut key.key);
+        crate::rand(&mut key.key);
 
         Sm4Ccm {
             key,
@@ -391,7 +428,7 @@ pub struct Nonce([u8; SM4CCM_IV_SIZE]);
 impl Nonce {
     pub fn new() -> Nonce {
         let mut nonce = [0_u8; SM4CCM_IV_SIZE];
-        super::rand(&mut nonce);
+        crate::rand(&mut nonce);
         Nonce(nonce)
     }
 
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::cipher::BlockCipherMode;
 use core::array::TryFromSliceError;
 use core::convert::{From, TryFrom, TryInto};
 use sgx_crypto_sys::*;
@@ -105,10 +106,40 @@ impl Sm4Ctr {
     }
 }
 
+impl BlockCipherMode for Sm4Ctr {
+    type Key = Key128bit;
+    type Iv = Counter;
+
+    #[inline]
+    fn new(key: &Key128bit, iv: Counter) -> Sm4Ctr {
+        Sm4Ctr::new(key, iv)
+    }
+
+    #[inline]
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.encrypt(src, dst)
+    }
+
+    #[inline]
+    fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.encrypt_in_place(in_out)
+    }
+
+    #[inline]
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        self.decrypt(src, dst)
+    }
+
+    #[inline]
+    fn decrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult {
+        self.decrypt_in_place(in_out)
+    }
+}
+
 impl Default for Sm4Ctr {
     fn default() -> Sm4Ctr {
         let mut key = AlignKey128bit::default();
-        super::rand(&mut key.key);
+        crate::rand(&mut key.key);
 
         Sm4Ctr {
             key,
@@ -123,7 +154,7 @@ pub struct Counter([u8; SM4CTR_CTR_SIZE]);
 impl Counter {
     pub fn nonce() -> Counter {
         let mut nonce = [0_u8; SM4CTR_CTR_SIZE];
-        super::rand(&mut nonce);
+        crate::rand(&mut nonce);
         Counter(nonce)
     }
 
@@ -0,0 +1,350 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::aes::polyval::Ghash;
+use crate::cipher::Aead;
+use core::array::TryFromSliceError;
+use core::convert::{From, TryFrom, TryInto};
+use sgx_crypto_sys::*;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::{BytewiseEquality, ContiguousMemory};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{AlignKey128bit, Key128bit, Mac128bit, SM4GCM_IV_SIZE};
+
+const BLOCK_SIZE: usize = 16;
+
+// SM4-GCM (GB/T 36624, RFC 8998) with 96-bit nonces and 128-bit tags.
+#[derive(Debug)]
+pub struct Sm4Gcm<A: AsRef<[u8]>> {
+    key: AlignKey128bit,
+    iv: Nonce,
+    aad: Aad<A>,
+}
+
+impl<A: AsRef<[u8]>> Sm4Gcm<A> {
+    // inc32 of the counter block, as specified for GCM.
+    const CTR_INC_BITS: u32 = 32;
+
+    pub fn new(key: &Key128bit, iv: Nonce, aad: Aad<A>) -> SgxResult<Sm4Gcm<A>> {
+        ensure!(
+            aad.as_ref().len() < i32::MAX as usize,
+            SgxStatus::InvalidParameter
+        );
+
+        Ok(Sm4Gcm {
+            key: AlignKey128bit::from(key),
+            iv,
+            aad,
+        })
+    }
+
+    pub fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult<Mac128bit> {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(src_len < i32::MAX as usize, SgxStatus::InvalidParameter);
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        self.ctr_apply(src, dst)?;
+        self.tag(dst)
+    }
+
+    pub fn encrypt_in_place(&mut self, in_out: &mut [u8]) -> SgxResult<Mac128bit> {
+        let mut dst = vec![0_u8; in_out.len()];
+        let mac = self.encrypt(in_out, dst.as_mut_slice())?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(mac)
+    }
+
+    pub fn decrypt(&mut self, src: &[u8], dst: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        let src_len = src.len();
+        let dst_len = dst.len();
+
+        ensure!(src_len < i32::MAX as usize, SgxStatus::InvalidParameter);
+        ensure!(src_len == dst_len, SgxStatus::InvalidParameter);
+
+        let expected = self.tag(src)?;
+        ensure!(expected.ct_eq(mac), SgxStatus::MacMismatch);
+
+        self.ctr_apply(src, dst)
+    }
+
+    pub fn decrypt_in_place(&mut self, in_out: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        let mut dst = vec![0_u8; in_out.len()];
+        self.decrypt(in_out, dst.as_mut_slice(), mac)?;
+        in_out.clone_from_slice(dst.as_slice());
+        Ok(())
+    }
+
+    pub fn mac(&mut self) -> SgxResult<Mac128bit> {
+        ensure!(!self.aad.as_ref().is_empty(), SgxStatus::InvalidParameter);
+        self.tag(&[])
+    }
+
+    pub fn verify_mac(&mut self, mac: &Mac128bit) -> SgxResult {
+        let expected = self.mac()?;
+        ensure!(expected.ct_eq(mac), SgxStatus::MacMismatch);
+        Ok(())
+    }
+
+    // J0 = IV || 0^31 || 1
+    fn pre_counter(&self) -> [u8; BLOCK_SIZE] {
+        let mut j0 = [0_u8; BLOCK_SIZE];
+        j0[..SM4GCM_IV_SIZE].copy_from_slice(self.iv.as_ref());
+        j0[BLOCK_SIZE - 1] = 1;
+        j0
+    }
+
+    fn ctr(&self, counter: &mut [u8; BLOCK_SIZE], src: &[u8], dst: &mut [u8]) -> SgxResult {
+        let status = unsafe {
+            sgx_sm4_ctr_encrypt(
+                &self.key.key as *const Key128bit,
+                src.as_ptr(),
+                src.len() as u32,
+                counter.as_mut_ptr(),
+                Self::CTR_INC_BITS,
+                dst.as_mut_ptr(),
+            )
+        };
+
+        ensure!(status.is_success(), status);
+        Ok(())
+    }
+
+    // GCTR starting from inc32(J0).
+    fn ctr_apply(&self, src: &[u8], dst: &mut [u8]) -> SgxResult {
+        if src.is_empty() {
+            return Ok(());
+        }
+
+        let mut counter = self.pre_counter();
+        let ctr = u32::from_be_bytes(counter[12..].try_into().unwrap()).wrapping_add(1);
+        counter[12..].copy_from_slice(&ctr.to_be_bytes());
+        self.ctr(&mut counter, src, dst)
+    }
+
+    fn tag(&self, ciphertext: &[u8]) -> SgxResult<Mac128bit> {
+        let aad = self.aad.as_ref();
+
+        // H = E(K, 0^128)
+        let mut h = [0_u8; BLOCK_SIZE];
+        self.ctr(&mut [0_u8; BLOCK_SIZE], &[0_u8; BLOCK_SIZE], &mut h)?;
+
+        let mut ghash = Ghash::new(&h);
+        h.fill(0);
+        ghash.update_padded(aad);
+        ghash.update_padded(ciphertext);
+
+        let mut lengths = [0_u8; BLOCK_SIZE];
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+        lengths[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+        ghash.block(&lengths);
+        let s = ghash.finalize();
+
+        let mut mac = Mac128bit::default();
+        self.ctr(&mut self.pre_counter(), &s, &mut mac)?;
+        Ok(mac)
+    }
+}
+
+impl<A: AsRef<[u8]>> Aead for Sm4Gcm<A> {
+    type Key = Key128bit;
+    type Nonce = Nonce;
+
+    const NONCE_SIZE: usize = SM4GCM_IV_SIZE;
+    const ID: u16 = 0x0510;
+
+    #[inline]
+    fn zeroed_nonce() -> Nonce {
+        Nonce::zeroed()
+    }
+
+    fn encrypt_detached(
+        key: &Key128bit,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        let mut cipher = Sm4Gcm::new(key, nonce, Aad::from(aad))?;
+        cipher.encrypt(src, dst)
+    }
+
+    fn decrypt_detached(
+        key: &Key128bit,
+        nonce: Nonce,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        let mut cipher = Sm4Gcm::new(key, nonce, Aad::from(aad))?;
+        cipher.decrypt(src, dst, mac)
+    }
+}
+
+impl Default for Sm4Gcm<[u8; 0]> {
+    fn default() -> Sm4Gcm<[u8; 0]> {
+        let mut key = AlignKey128bit::default();
+        crate::rand(&mut key.key);
+
+        Sm4Gcm {
+            key,
+            iv: Nonce::new(),
+            aad: Aad::default(),
+        }
+    }
+}
+
+impl<A: AsRef<[u8]>> Drop for Sm4Gcm<A> {
+    fn drop(&mut self) {
+        self.key.key.fill(0);
+    }
+}
+
+pub struct Aad<A>(A);
+
+impl<A: AsRef<[u8]>> Aad<A> {
+    #[inline]
+    pub fn from(aad: A) -> Aad<A> {
+        Aad(aad)
+    }
+}
+
+impl<A> AsRef<[u8]> for Aad<A>
+where
+    A: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl Aad<[u8; 0]> {
+    pub fn empty() -> Aad<[u8; 0]> {
+        Self::from([])
+    }
+}
+
+impl Default for Aad<[u8; 0]> {
+    fn default() -> Aad<[u8; 0]> {
+        Aad::empty()
+    }
+}
+
+impl<A> Clone for Aad<A>
+where
+    A: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Aad<A> {
+        Self(self.0.clone())
+    }
+}
+
+impl<A> Copy for Aad<A> where A: Copy {}
+
+impl<A> core::fmt::Debug for Aad<A>
+where
+    A: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Aad").field(&self.0).finish()
+    }
+}
+
+impl<A> PartialEq for Aad<A>
+where
+    A: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+impl<A> Eq for Aad<A> where A: Eq {}
+
+impl<A> ConstTimeEq for Aad<A>
+where
+    A: ConstTimeEq + BytewiseEquality + Eq,
+{
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+unsafe impl<A: ContiguousMemory> ContiguousMemory for Aad<A> {}
+
+unsafe impl<A: BytewiseEquality> BytewiseEquality for Aad<A> {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Nonce([u8; SM4GCM_IV_SIZE]);
+
+impl Nonce {
+    pub fn new() -> Nonce {
+        let mut nonce = [0_u8; SM4GCM_IV_SIZE];
+        crate::rand(&mut nonce);
+        Nonce(nonce)
+    }
+
+    #[inline]
+    pub fn zeroed() -> Nonce {
+        Nonce([0_u8; SM4GCM_IV_SIZE])
+    }
+}
+
+impl Default for Nonce {
+    #[inline]
+    fn default() -> Nonce {
+        Nonce::new()
+    }
+}
+
+impl AsRef<[u8; SM4GCM_IV_SIZE]> for Nonce {
+    #[inline]
+    fn as_ref(&self) -> &[u8; SM4GCM_IV_SIZE] {
+        &self.0
+    }
+}
+
+impl From<[u8; SM4GCM_IV_SIZE]> for Nonce {
+    #[inline]
+    fn from(nonce: [u8; SM4GCM_IV_SIZE]) -> Nonce {
+        Nonce(nonce)
+    }
+}
+
+impl From<&[u8; SM4GCM_IV_SIZE]> for Nonce {
+    #[inline]
+    fn from(nonce: &[u8; SM4GCM_IV_SIZE]) -> Nonce {
+        Nonce(*nonce)
+    }
+}
+
+impl TryFrom<&[u8]> for Nonce {
+    type Error = TryFromSliceError;
+
+    fn try_from(nonce: &[u8]) -> Result<Nonce, TryFromSliceError> {
+        let nonce: &[u8; SM4GCM_IV_SIZE] = nonce.try_into()?;
+        Ok(Nonce(*nonce))
+    }
+}
+
+unsafe impl ContiguousMemory for Nonce {}
+
+unsafe impl BytewiseEquality for Nonce {}
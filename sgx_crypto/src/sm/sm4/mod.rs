@@ -18,16 +18,7 @@
 pub mod cbc;
 pub mod ccm;
 pub mod ctr;
+pub mod gcm;
 
-#[inline]
-pub(crate) fn rand(nonce: &mut [u8]) {
-    cfg_if! {
-        if #[cfg(feature = "tcrypto")] {
-            use sgx_trts::rand::Rng;
-            Rng::new().fill_bytes(nonce);
-        } else {
-            use sgx_rand::{RdRand, Rng};
-            RdRand::new().unwrap().fill_bytes(nonce);
-        }
-    }
-}
+#[cfg(feature = "unit_test")]
+mod tests;
@@ -0,0 +1,87 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::gcm::{Aad, Nonce, Sm4Gcm};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::Key128bit;
+
+fn decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// RFC 8998, appendix A.1
+#[test_case]
+fn sm4_gcm_rfc8998() {
+    let key: Key128bit = decode("0123456789abcdeffedcba9876543210")
+        .try_into()
+        .unwrap();
+    let nonce: [u8; 12] = decode("00001234567800000000abcd").try_into().unwrap();
+    let aad = decode("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+    let plaintext = decode(
+        "aaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbccccccccccccccccdddddddddddddddd\
+         eeeeeeeeeeeeeeeeffffffffffffffffeeeeeeeeeeeeeeeeaaaaaaaaaaaaaaaa",
+    );
+    let ciphertext = decode(
+        "17f399f08c67d5ee19d0dc9969c4bb7d5fd46fd3756489069157b282bb200735\
+         d82710ca5c22f0ccfa7cbf93d496ac15a56834cbcf98c397b4024a2691233b8d",
+    );
+    let tag = decode("83de3541e4c2b58177e065a9bf7b62ec");
+
+    let mut gcm = Sm4Gcm::new(&key, Nonce::from(nonce), Aad::from(aad.as_slice())).unwrap();
+    let mut out = vec![0_u8; plaintext.len()];
+    let mut mac = gcm.encrypt(&plaintext, &mut out).unwrap();
+    assert_eq!(out, ciphertext);
+    assert_eq!(mac.to_vec(), tag);
+
+    let mut gcm = Sm4Gcm::new(&key, Nonce::from(nonce), Aad::from(aad.as_slice())).unwrap();
+    gcm.decrypt(&ciphertext, &mut out, &mac).unwrap();
+    assert_eq!(out, plaintext);
+
+    mac[0] ^= 1;
+    let mut gcm = Sm4Gcm::new(&key, Nonce::from(nonce), Aad::from(aad.as_slice())).unwrap();
+    assert_eq!(
+        gcm.decrypt(&ciphertext, &mut out, &mac),
+        Err(SgxStatus::MacMismatch)
+    );
+}
+
+#[test_case]
+fn sm4_gcm_round_trip() {
+    let key = Key128bit::default();
+    for len in [0, 1, 16, 33] {
+        let plaintext = vec![0x5a_u8; len];
+        let mut buf = plaintext.clone();
+        let mut gcm = Sm4Gcm::new(&key, Nonce::zeroed(), Aad::from(b"aad")).unwrap();
+        let mac = gcm.encrypt_in_place(&mut buf).unwrap();
+
+        let mut gcm = Sm4Gcm::new(&key, Nonce::zeroed(), Aad::from(b"aad")).unwrap();
+        gcm.decrypt_in_place(&mut buf, &mac).unwrap();
+        assert_eq!(buf, plaintext);
+
+        let mut gcm = Sm4Gcm::new(&key, Nonce::zeroed(), Aad::from(b"aae")).unwrap();
+        assert_eq!(
+            gcm.decrypt_in_place(&mut buf, &mac),
+            Err(SgxStatus::MacMismatch)
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{BasicConstraints, Certificate, CertificateSigner, Clock, Extension, GeneralName};
+use super::{KeyUsage, Name, Validity};
+use crate::der::{tag_context, DerWriter, EncodePublicKey};
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+const SERIAL_NUMBER_SIZE: usize = 16;
+const MAX_SERIAL_NUMBER_SIZE: usize = 20;
+
+// Builds and signs X.509 v3 certificates.
+//
+// The issuer defaults to the subject, which gives a self-signed certificate
+// when signed with the subject's own key. A random 128-bit serial number is
+// used unless one is set.
+#[derive(Clone, Debug, Default)]
+pub struct CertificateBuilder {
+    serial_number: Option<Vec<u8>>,
+    issuer: Option<Name>,
+    subject: Name,
+    validity: Option<Validity>,
+    public_key: Option<Vec<u8>>,
+    extensions: Vec<Extension>,
+}
+
+impl CertificateBuilder {
+    #[inline]
+    pub fn new() -> CertificateBuilder {
+        CertificateBuilder::default()
+    }
+
+    // Big-endian and positive.
+    pub fn serial_number(&mut self, serial_number: &[u8]) -> &mut CertificateBuilder {
+        self.serial_number = Some(serial_number.to_vec());
+        self
+    }
+
+    pub fn issuer(&mut self, issuer: Name) -> &mut CertificateBuilder {
+        self.issuer = Some(issuer);
+        self
+    }
+
+    pub fn subject(&mut self, subject: Name) -> &mut CertificateBuilder {
+        self.subject = subject;
+        self
+    }
+
+    pub fn validity(&mut self, validity: Validity) -> &mut CertificateBuilder {
+        self.validity = Some(validity);
+        self
+    }
+
+    // Valid from the clock's current time for `duration` seconds.
+    pub fn valid_for<C: Clock + ?Sized>(
+        &mut self,
+        clock: &C,
+        duration: u64,
+    ) -> SgxResult<&mut CertificateBuilder> {
+        self.validity = Some(Validity::from_clock(clock, duration)?);
+        Ok(self)
+    }
+
+    pub fn public_key<K: EncodePublicKey>(
+        &mut self,
+        key: &K,
+    ) -> SgxResult<&mut CertificateBuilder> {
+        self.public_key = Some(key.to_public_key_der()?);
+        Ok(self)
+    }
+
+    pub fn subject_public_key_info(&mut self, spki: &[u8]) -> &mut CertificateBuilder {
+        self.public_key = Some(spki.to_vec());
+        self
+    }
+
+    pub fn basic_constraints(&mut self, constraints: BasicConstraints) -> &mut CertificateBuilder {
+        self.extension(Extension::basic_constraints(constraints))
+    }
+
+    pub fn key_usage(&mut self, usage: KeyUsage) -> &mut CertificateBuilder {
+        self.extension(Extension::key_usage(usage))
+    }
+
+    pub fn subject_alt_names(&mut self, names: &[GeneralName]) -> &mut CertificateBuilder {
+        self.extension(Extension::subject_alt_name(names))
+    }
+
+    pub fn extended_key_usage(&mut self, purposes: &[&[u8]]) -> &mut CertificateBuilder {
+        self.extension(Extension::extended_key_usage(purposes))
+    }
+
+    // Adds an extension, replacing any earlier one of the same type.
+    pub fn extension(&mut self, extension: Extension) -> &mut CertificateBuilder {
+        self.extensions.retain(|ext| ext.oid != extension.oid);
+        self.extensions.push(extension);
+        self
+    }
+
+    pub fn sign<S: CertificateSigner + ?Sized>(&self, signer: &S) -> SgxResult<Certificate> {
+        let public_key = self
+            .public_key
+            .as_ref()
+            .ok_or(SgxStatus::InvalidParameter)?;
+        let validity = self.validity.ok_or(SgxStatus::InvalidParameter)?;
+        let issuer = self.issuer.as_ref().unwrap_or(&self.subject);
+        ensure!(!issuer.is_empty(), SgxStatus::InvalidParameter);
+
+        let serial_number = match self.serial_number {
+            Some(ref serial_number) => {
+                let start = serial_number
+                    .iter()
+                    .position(|&b| b != 0)
+                    .ok_or(SgxStatus::InvalidParameter)?;
+                let serial_number = &serial_number[start..];
+                // At most 20 octets once encoded, sign octet included.
+                let len = serial_number.len() + usize::from(serial_number[0] & 0x80 != 0);
+                ensure!(len <= MAX_SERIAL_NUMBER_SIZE, SgxStatus::InvalidParameter);
+                serial_number.to_vec()
+            }
+            None => {
+                let mut serial_number = vec![0_u8; SERIAL_NUMBER_SIZE];
+                crate::rand(&mut serial_number);
+                serial_number
+            }
+        };
+        let algorithm = signer.signature_algorithm().algorithm_identifier();
+
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| {
+            w.write_constructed(tag_context(0), |w| w.write_u64(2));
+            w.write_integer(&serial_number);
+            algorithm.encode(w);
+            issuer.encode(w);
+            validity.encode(w);
+            self.subject.encode(w);
+            w.write_raw(public_key);
+            if !self.extensions.is_empty() {
+                w.write_constructed(tag_context(3), |w| {
+                    w.write_sequence(|w| {
+                        for ext in self.extensions.iter() {
+                            ext.encode(w);
+                        }
+                    });
+                });
+            }
+        });
+        let tbs = writer.into_vec();
+        let signature = signer.sign_certificate(&tbs)?;
+
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| {
+            w.write_raw(&tbs);
+            algorithm.encode(w);
+            w.write_bit_string(&signature);
+        });
+        Certificate::from_der(writer.as_slice())
+    }
+}
@@ -0,0 +1,386 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::extension::{decode_authority_key_identifier, decode_key_identifier, KNOWN_EXTENSIONS};
+use super::{BasicConstraints, Extension, GeneralName, KeyUsage, Name, Validity};
+use super::{CertificatePublicKey, Clock, SignatureAlgorithm};
+use super::{OID_AUTHORITY_KEY_IDENTIFIER, OID_BASIC_CONSTRAINTS, OID_KEY_USAGE};
+use super::{OID_SUBJECT_ALT_NAME, OID_SUBJECT_KEY_IDENTIFIER};
+use crate::der::pem;
+use crate::der::{tag_context, tag_context_primitive, AlgorithmIdentifier, DerReader, TAG_INTEGER};
+use alloc::string::String;
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+// Longest chain accepted by `verify_chain`, counting the leaf and the anchor.
+pub const MAX_CHAIN_LENGTH: usize = 8;
+
+const VERSION_V3: u64 = 2;
+
+// A parsed X.509 v1/v3 certificate (RFC 5280).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Certificate {
+    der: Vec<u8>,
+    tbs: Vec<u8>,
+    version: u64,
+    serial_number: Vec<u8>,
+    signature_algorithm: SignatureAlgorithm,
+    issuer: Name,
+    issuer_der: Vec<u8>,
+    validity: Validity,
+    subject: Name,
+    subject_der: Vec<u8>,
+    public_key: Vec<u8>,
+    extensions: Vec<Extension>,
+    signature: Vec<u8>,
+}
+
+impl Certificate {
+    pub fn from_der(der: &[u8]) -> SgxResult<Certificate> {
+        let mut reader = DerReader::new(der);
+        let mut cert = reader.read_sequence()?;
+        reader.finish()?;
+
+        let tbs = cert.read_raw()?;
+        let signature_algorithm = AlgorithmIdentifier::decode(&mut cert)?;
+        let signature = cert.read_bit_string()?;
+        cert.finish()?;
+
+        let mut reader = DerReader::new(tbs);
+        let mut seq = reader.read_sequence()?;
+        reader.finish()?;
+
+        let version = match seq.read_optional(tag_context(0))? {
+            Some(version) => {
+                let mut version = DerReader::new(version);
+                let v = version.read_u64()?;
+                version.finish()?;
+                ensure!(v <= VERSION_V3, SgxStatus::InvalidParameter);
+                v
+            }
+            None => 0,
+        };
+        // Serial numbers are kept as their raw two's complement encoding, as
+        // some issuers produce negative ones.
+        let serial_number = seq.read(TAG_INTEGER)?;
+        ensure!(
+            AlgorithmIdentifier::decode(&mut seq)? == signature_algorithm,
+            SgxStatus::InvalidParameter
+        );
+        let signature_algorithm =
+            SignatureAlgorithm::from_algorithm_identifier(&signature_algorithm)?;
+
+        let issuer_der = seq.read_raw()?;
+        let issuer = Name::from_der(issuer_der)?;
+        let validity = Validity::decode(&mut seq)?;
+        let subject_der = seq.read_raw()?;
+        let subject = Name::from_der(subject_der)?;
+        let public_key = seq.read_raw()?;
+
+        // issuerUniqueID and subjectUniqueID are not used.
+        let _ = seq.read_optional(tag_context_primitive(1))?;
+        let _ = seq.read_optional(tag_context_primitive(2))?;
+
+        let mut extensions: Vec<Extension> = Vec::new();
+        if let Some(exts) = seq.read_optional(tag_context(3))? {
+            ensure!(version == VERSION_V3, SgxStatus::InvalidParameter);
+            let mut reader = DerReader::new(exts);
+            let mut exts = reader.read_sequence()?;
+            reader.finish()?;
+            ensure!(!exts.is_empty(), SgxStatus::InvalidParameter);
+
+            while !exts.is_empty() {
+                let extension = Extension::decode(&mut exts)?;
+                ensure!(
+                    extensions.iter().all(|ext| ext.oid != extension.oid),
+                    SgxStatus::InvalidParameter
+                );
+                extensions.push(extension);
+            }
+        }
+        seq.finish()?;
+
+        Ok(Certificate {
+            der: der.to_vec(),
+            tbs: tbs.to_vec(),
+            version,
+            serial_number: serial_number.to_vec(),
+            signature_algorithm,
+            issuer,
+            issuer_der: issuer_der.to_vec(),
+            validity,
+            subject,
+            subject_der: subject_der.to_vec(),
+            public_key: public_key.to_vec(),
+            extensions,
+            signature: signature.to_vec(),
+        })
+    }
+
+    pub fn from_pem(pem: &str) -> SgxResult<Certificate> {
+        let der = pem::decode_label(pem, pem::CERTIFICATE_LABEL)?;
+        Certificate::from_der(&der)
+    }
+
+    // Parses a PEM bundle such as a PCK certificate chain, in order.
+    pub fn from_pem_chain(pem: &str) -> SgxResult<Vec<Certificate>> {
+        const END: &str = "-----END CERTIFICATE-----";
+
+        let mut certs = Vec::new();
+        let mut rest = pem;
+        while let Some(end) = rest.find(END) {
+            let (block, tail) = rest.split_at(end + END.len());
+            certs.push(Certificate::from_pem(block.trim_start())?);
+            rest = tail;
+        }
+        ensure!(
+            !certs.is_empty() && rest.trim().is_empty(),
+            SgxStatus::InvalidParameter
+        );
+        Ok(certs)
+    }
+
+    #[inline]
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    #[inline]
+    pub fn to_pem(&self) -> String {
+        pem::encode(pem::CERTIFICATE_LABEL, &self.der)
+    }
+
+    #[inline]
+    pub fn tbs_certificate(&self) -> &[u8] {
+        &self.tbs
+    }
+
+    // 0 for v1 up to 2 for v3.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    #[inline]
+    pub fn serial_number(&self) -> &[u8] {
+        &self.serial_number
+    }
+
+    #[inline]
+    pub fn signature_algorithm(&self) -> SignatureAlgorithm {
+        self.signature_algorithm
+    }
+
+    #[inline]
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    #[inline]
+    pub fn subject(&self) -> &Name {
+        &self.subject
+    }
+
+    #[inline]
+    pub fn validity(&self) -> Validity {
+        self.validity
+    }
+
+    // DER encoding of the SubjectPublicKeyInfo.
+    #[inline]
+    pub fn subject_public_key_info(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    #[inline]
+    pub fn public_key(&self) -> SgxResult<CertificatePublicKey> {
+        CertificatePublicKey::from_spki(&self.public_key)
+    }
+
+    #[inline]
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn extension(&self, oid: &[u8]) -> Option<&Extension> {
+        self.extensions.iter().find(|ext| ext.oid == oid)
+    }
+
+    pub fn basic_constraints(&self) -> SgxResult<Option<BasicConstraints>> {
+        self.extension(OID_BASIC_CONSTRAINTS)
+            .map(|ext| BasicConstraints::from_der(&ext.value))
+            .transpose()
+    }
+
+    pub fn key_usage(&self) -> SgxResult<Option<KeyUsage>> {
+        self.extension(OID_KEY_USAGE)
+            .map(|ext| KeyUsage::from_der(&ext.value))
+            .transpose()
+    }
+
+    pub fn subject_alt_names(&self) -> SgxResult<Vec<GeneralName>> {
+        self.extension(OID_SUBJECT_ALT_NAME)
+            .map(|ext| GeneralName::decode_list(&ext.value))
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+
+    pub fn subject_key_identifier(&self) -> SgxResult<Option<Vec<u8>>> {
+        self.extension(OID_SUBJECT_KEY_IDENTIFIER)
+            .map(|ext| decode_key_identifier(&ext.value))
+            .transpose()
+    }
+
+    pub fn authority_key_identifier(&self) -> SgxResult<Option<Vec<u8>>> {
+        match self.extension(OID_AUTHORITY_KEY_IDENTIFIER) {
+            Some(ext) => decode_authority_key_identifier(&ext.value),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    pub fn is_self_issued(&self) -> bool {
+        self.issuer_der == self.subject_der
+    }
+
+    #[inline]
+    pub fn is_valid_at(&self, time: u64) -> bool {
+        self.validity.contains(time)
+    }
+
+    // Checks the certificate signature against the issuer's public key.
+    pub fn verify_signature(&self, issuer_key: &CertificatePublicKey) -> SgxResult<bool> {
+        issuer_key.verify(self.signature_algorithm, &self.tbs, &self.signature)
+    }
+
+    // Validates the path from this certificate to one of `roots`, looking up
+    // issuers among `intermediates`. Every certificate on the path must be
+    // valid at `clock.now()`, be signed by the next one and, for issuers,
+    // satisfy the basic constraints and key usage of a CA. A certificate is
+    // used at most once on the path, and the path is at most
+    // MAX_CHAIN_LENGTH long, self-issued certificates included. Name
+    // constraints and policies are not processed.
+    pub fn verify_chain<C: Clock + ?Sized>(
+        &self,
+        intermediates: &[Certificate],
+        roots: &[Certificate],
+        clock: &C,
+    ) -> SgxResult {
+        let now = clock.now()?;
+        let mut current = self;
+        let mut path: Vec<&Certificate> = vec![self];
+        let mut path_len = 0_usize;
+
+        loop {
+            current.check_validity(now)?;
+            if roots.iter().any(|root| root.der == current.der) {
+                return Ok(());
+            }
+            ensure!(
+                path.len() + 1 < MAX_CHAIN_LENGTH,
+                SgxStatus::InvalidParameter
+            );
+
+            if let Some(root) = current.find_issuer(roots, &path)? {
+                root.check_validity(now)?;
+                // v1 trust anchors carry no constraints to check.
+                if root.version == VERSION_V3 {
+                    root.check_issuer(path_len)?;
+                }
+                return Ok(());
+            }
+
+            let issuer = current
+                .find_issuer(intermediates, &path)?
+                .ok_or(SgxStatus::InvalidSignature)?;
+            issuer.check_issuer(path_len)?;
+
+            // Self-issued intermediates do not count towards path length.
+            if !issuer.is_self_issued() {
+                path_len += 1;
+            }
+            path.push(issuer);
+            current = issuer;
+        }
+    }
+
+    // The first candidate that is not on `path` yet and whose key verifies
+    // the signature. Candidates that cannot be checked are skipped.
+    fn find_issuer<'a>(
+        &self,
+        candidates: &'a [Certificate],
+        path: &[&Certificate],
+    ) -> SgxResult<Option<&'a Certificate>> {
+        let key_id = self.authority_key_identifier()?;
+        for candidate in candidates.iter() {
+            if candidate.subject_der != self.issuer_der
+                || path.iter().any(|cert| cert.der == candidate.der)
+            {
+                continue;
+            }
+            if let (Some(key_id), Some(subject_key_id)) = (
+                key_id.as_ref(),
+                candidate.subject_key_identifier().ok().flatten(),
+            ) {
+                if *key_id != subject_key_id {
+                    continue;
+                }
+            }
+            let key = match candidate.public_key() {
+                Ok(key) => key,
+                Err(_) => continue,
+            };
+            if let Ok(true) = self.verify_signature(&key) {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
+    fn check_validity(&self, now: u64) -> SgxResult {
+        ensure!(self.is_valid_at(now), SgxStatus::InvalidParameter);
+        ensure!(
+            self.extensions
+                .iter()
+                .all(|ext| !ext.critical || KNOWN_EXTENSIONS.contains(&ext.oid.as_slice())),
+            SgxStatus::UnsupportedFeature
+        );
+        Ok(())
+    }
+
+    // `path_len` is the number of non-self-issued intermediates below.
+    fn check_issuer(&self, path_len: usize) -> SgxResult {
+        let constraints = self
+            .basic_constraints()?
+            .ok_or(SgxStatus::InvalidParameter)?;
+        ensure!(constraints.ca, SgxStatus::InvalidParameter);
+        if let Some(max) = constraints.path_len {
+            ensure!(path_len <= usize::from(max), SgxStatus::InvalidParameter);
+        }
+        if let Some(usage) = self.key_usage()? {
+            ensure!(
+                usage.contains(KeyUsage::KEY_CERT_SIGN),
+                SgxStatus::InvalidParameter
+            );
+        }
+        Ok(())
+    }
+}
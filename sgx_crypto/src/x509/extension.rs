@@ -0,0 +1,320 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::der::{
+    tag_context, tag_context_primitive, DerReader, DerWriter, SubjectPublicKeyInfo, TAG_BIT_STRING,
+    TAG_BOOLEAN,
+};
+use crate::sha::Sha1;
+use alloc::string::String;
+use alloc::vec::Vec;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+// Certificate extensions (RFC 5280, section 4.2.1).
+pub const OID_SUBJECT_KEY_IDENTIFIER: &[u8] = &[0x55, 0x1d, 0x0e];
+pub const OID_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+pub const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+pub const OID_BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+pub const OID_AUTHORITY_KEY_IDENTIFIER: &[u8] = &[0x55, 0x1d, 0x23];
+pub const OID_EXTENDED_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+
+// Extended key usage purposes.
+pub const OID_KP_SERVER_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01];
+pub const OID_KP_CLIENT_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x02];
+pub const OID_KP_CODE_SIGNING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x03];
+
+// Extensions understood by the chain validation. A certificate carrying any
+// other extension marked critical is rejected.
+pub(crate) const KNOWN_EXTENSIONS: &[&[u8]] = &[
+    OID_SUBJECT_KEY_IDENTIFIER,
+    OID_KEY_USAGE,
+    OID_SUBJECT_ALT_NAME,
+    OID_BASIC_CONSTRAINTS,
+    OID_AUTHORITY_KEY_IDENTIFIER,
+    OID_EXTENDED_KEY_USAGE,
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Extension {
+    pub oid: Vec<u8>,
+    pub critical: bool,
+    // DER encoding of the extension value, without the OCTET STRING wrapper.
+    pub value: Vec<u8>,
+}
+
+impl Extension {
+    #[inline]
+    pub fn new(oid: &[u8], critical: bool, value: &[u8]) -> Extension {
+        Extension {
+            oid: oid.to_vec(),
+            critical,
+            value: value.to_vec(),
+        }
+    }
+
+    pub fn basic_constraints(constraints: BasicConstraints) -> Extension {
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| {
+            if constraints.ca {
+                w.write_boolean(true);
+                if let Some(path_len) = constraints.path_len {
+                    w.write_u64(u64::from(path_len));
+                }
+            }
+        });
+        Extension::new(OID_BASIC_CONSTRAINTS, true, writer.as_slice())
+    }
+
+    pub fn key_usage(usage: KeyUsage) -> Extension {
+        // Named bit list: bit 0 is the most significant bit of the first
+        // octet and trailing zero bits are dropped.
+        let bits = usage.bits();
+        let mut content = Vec::with_capacity(3);
+        if bits == 0 {
+            content.push(0);
+        } else {
+            let highest = 15 - bits.leading_zeros() as usize;
+            let len = highest / 8 + 1;
+            content.push((7 - highest % 8) as u8);
+            for i in 0..len {
+                let octet = (0..8).fold(0_u8, |octet, j| {
+                    if bits & (1 << (i * 8 + j)) != 0 {
+                        octet | (0x80 >> j)
+                    } else {
+                        octet
+                    }
+                });
+                content.push(octet);
+            }
+        }
+
+        let mut writer = DerWriter::new();
+        writer.write_tlv(TAG_BIT_STRING, &content);
+        Extension::new(OID_KEY_USAGE, true, writer.as_slice())
+    }
+
+    pub fn subject_alt_name(names: &[GeneralName]) -> Extension {
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| {
+            for name in names.iter() {
+                name.encode(w);
+            }
+        });
+        Extension::new(OID_SUBJECT_ALT_NAME, false, writer.as_slice())
+    }
+
+    pub fn extended_key_usage(purposes: &[&[u8]]) -> Extension {
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| {
+            for purpose in purposes.iter() {
+                w.write_oid(purpose);
+            }
+        });
+        Extension::new(OID_EXTENDED_KEY_USAGE, false, writer.as_slice())
+    }
+
+    pub fn subject_key_identifier(key_id: &[u8]) -> Extension {
+        let mut writer = DerWriter::new();
+        writer.write_octet_string(key_id);
+        Extension::new(OID_SUBJECT_KEY_IDENTIFIER, false, writer.as_slice())
+    }
+
+    pub fn authority_key_identifier(key_id: &[u8]) -> Extension {
+        let mut writer = DerWriter::new();
+        writer.write_sequence(|w| w.write_tlv(tag_context_primitive(0), key_id));
+        Extension::new(OID_AUTHORITY_KEY_IDENTIFIER, false, writer.as_slice())
+    }
+
+    pub(crate) fn encode(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|w| {
+            w.write_oid(&self.oid);
+            if self.critical {
+                w.write_boolean(true);
+            }
+            w.write_octet_string(&self.value);
+        });
+    }
+
+    pub(crate) fn decode(reader: &mut DerReader<'_>) -> SgxResult<Extension> {
+        let mut seq = reader.read_sequence()?;
+        let oid = seq.read_oid()?;
+        let critical = if seq.peek_tag() == Some(TAG_BOOLEAN) {
+            seq.read_boolean()?
+        } else {
+            false
+        };
+        let value = seq.read_octet_string()?;
+        seq.finish()?;
+        Ok(Extension::new(oid, critical, value))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BasicConstraints {
+    pub ca: bool,
+    // Maximum number of intermediate CAs that may follow this one.
+    pub path_len: Option<u8>,
+}
+
+impl BasicConstraints {
+    pub fn from_der(der: &[u8]) -> SgxResult<BasicConstraints> {
+        let mut reader = DerReader::new(der);
+        let mut seq = reader.read_sequence()?;
+        reader.finish()?;
+
+        let ca = if seq.peek_tag() == Some(TAG_BOOLEAN) {
+            seq.read_boolean()?
+        } else {
+            false
+        };
+        let path_len = if seq.is_empty() {
+            None
+        } else {
+            let path_len = seq.read_u64()?;
+            ensure!(path_len <= u64::from(u8::MAX), SgxStatus::InvalidParameter);
+            Some(path_len as u8)
+        };
+        seq.finish()?;
+        Ok(BasicConstraints { ca, path_len })
+    }
+}
+
+impl_bitflags! {
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct KeyUsage: u16 {
+        const DIGITAL_SIGNATURE = 0x0001;
+        const NON_REPUDIATION   = 0x0002;
+        const KEY_ENCIPHERMENT  = 0x0004;
+        const DATA_ENCIPHERMENT = 0x0008;
+        const KEY_AGREEMENT     = 0x0010;
+        const KEY_CERT_SIGN     = 0x0020;
+        const CRL_SIGN          = 0x0040;
+        const ENCIPHER_ONLY     = 0x0080;
+        const DECIPHER_ONLY     = 0x0100;
+    }
+}
+
+impl KeyUsage {
+    pub fn from_der(der: &[u8]) -> SgxResult<KeyUsage> {
+        let mut reader = DerReader::new(der);
+        let content = reader.read(TAG_BIT_STRING)?;
+        reader.finish()?;
+
+        ensure!(
+            !content.is_empty() && content.len() <= 3 && content[0] < 8,
+            SgxStatus::InvalidParameter
+        );
+        let mut bits = 0_u16;
+        for (i, octet) in content[1..].iter().enumerate() {
+            for j in 0..8 {
+                if octet & (0x80 >> j) != 0 {
+                    bits |= 1 << (i * 8 + j);
+                }
+            }
+        }
+        Ok(KeyUsage::from_bits_truncate(bits))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GeneralName {
+    Email(String),
+    DnsName(String),
+    Uri(String),
+    // Four octets for IPv4, sixteen for IPv6.
+    IpAddress(Vec<u8>),
+    // Any other form, as its tag and content octets.
+    Other(u8, Vec<u8>),
+}
+
+impl GeneralName {
+    const TAG_EMAIL: u8 = tag_context_primitive(1);
+    const TAG_DNS_NAME: u8 = tag_context_primitive(2);
+    const TAG_URI: u8 = tag_context_primitive(6);
+    const TAG_IP_ADDRESS: u8 = tag_context_primitive(7);
+
+    fn encode(&self, writer: &mut DerWriter) {
+        match self {
+            GeneralName::Email(email) => writer.write_tlv(Self::TAG_EMAIL, email.as_bytes()),
+            GeneralName::DnsName(dns) => writer.write_tlv(Self::TAG_DNS_NAME, dns.as_bytes()),
+            GeneralName::Uri(uri) => writer.write_tlv(Self::TAG_URI, uri.as_bytes()),
+            GeneralName::IpAddress(ip) => writer.write_tlv(Self::TAG_IP_ADDRESS, ip),
+            GeneralName::Other(tag, value) => writer.write_tlv(*tag, value),
+        }
+    }
+
+    fn decode(reader: &mut DerReader<'_>) -> SgxResult<GeneralName> {
+        let (tag, value) = reader.read_tlv()?;
+        let ia5 = |value: &[u8]| -> SgxResult<String> {
+            ensure!(value.is_ascii(), SgxStatus::InvalidParameter);
+            Ok(String::from_utf8_lossy(value).into_owned())
+        };
+
+        let name = match tag {
+            Self::TAG_EMAIL => GeneralName::Email(ia5(value)?),
+            Self::TAG_DNS_NAME => GeneralName::DnsName(ia5(value)?),
+            Self::TAG_URI => GeneralName::Uri(ia5(value)?),
+            Self::TAG_IP_ADDRESS => {
+                ensure!(
+                    value.len() == 4 || value.len() == 16,
+                    SgxStatus::InvalidParameter
+                );
+                GeneralName::IpAddress(value.to_vec())
+            }
+            _ => GeneralName::Other(tag, value.to_vec()),
+        };
+        Ok(name)
+    }
+
+    pub(crate) fn decode_list(der: &[u8]) -> SgxResult<Vec<GeneralName>> {
+        let mut reader = DerReader::new(der);
+        let mut seq = reader.read_sequence()?;
+        reader.finish()?;
+
+        let mut names = Vec::new();
+        while !seq.is_empty() {
+            names.push(GeneralName::decode(&mut seq)?);
+        }
+        Ok(names)
+    }
+}
+
+// RFC 5280 method (1) key identifier: SHA-1 of the subjectPublicKey bits.
+pub fn key_identifier(spki: &[u8]) -> SgxResult<[u8; 20]> {
+    let spki = SubjectPublicKeyInfo::from_der(spki)?;
+    Ok(Sha1::digest(spki.subject_public_key)?.hash)
+}
+
+pub(crate) fn decode_authority_key_identifier(der: &[u8]) -> SgxResult<Option<Vec<u8>>> {
+    let mut reader = DerReader::new(der);
+    let mut seq = reader.read_sequence()?;
+    reader.finish()?;
+
+    let key_id = seq.read_optional(tag_context_primitive(0))?;
+    // authorityCertIssuer and authorityCertSerialNumber are not used.
+    let _ = seq.read_optional(tag_context(1))?;
+    let _ = seq.read_optional(tag_context_primitive(2))?;
+    seq.finish()?;
+    Ok(key_id.map(|key_id| key_id.to_vec()))
+}
+
+pub(crate) fn decode_key_identifier(der: &[u8]) -> SgxResult<Vec<u8>> {
+    let mut reader = DerReader::new(der);
+    let key_id = reader.read_octet_string()?;
+    reader.finish()?;
+    Ok(key_id.to_vec())
+}
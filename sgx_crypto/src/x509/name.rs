@@ -0,0 +1,167 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::der::{DerReader, DerWriter, TAG_PRINTABLE_STRING, TAG_SET, TAG_UTF8_STRING};
+use alloc::vec::Vec;
+use core::str;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+// X.520 attribute types.
+pub const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+pub const OID_COUNTRY_NAME: &[u8] = &[0x55, 0x04, 0x06];
+pub const OID_LOCALITY_NAME: &[u8] = &[0x55, 0x04, 0x07];
+pub const OID_STATE_OR_PROVINCE_NAME: &[u8] = &[0x55, 0x04, 0x08];
+pub const OID_ORGANIZATION_NAME: &[u8] = &[0x55, 0x04, 0x0a];
+pub const OID_ORGANIZATIONAL_UNIT_NAME: &[u8] = &[0x55, 0x04, 0x0b];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NameAttribute {
+    pub oid: Vec<u8>,
+    // Tag of the string type the value is encoded with.
+    pub tag: u8,
+    pub value: Vec<u8>,
+}
+
+impl NameAttribute {
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        str::from_utf8(&self.value).ok()
+    }
+}
+
+// An X.501 distinguished name. Each attribute is encoded as its own
+// relative distinguished name; multi-valued RDNs are flattened on parsing.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Name {
+    attributes: Vec<NameAttribute>,
+}
+
+impl Name {
+    #[inline]
+    pub fn new() -> Name {
+        Name::default()
+    }
+
+    pub fn add(&mut self, oid: &[u8], value: &str) -> &mut Name {
+        self.add_with_tag(oid, TAG_UTF8_STRING, value)
+    }
+
+    pub fn add_with_tag(&mut self, oid: &[u8], tag: u8, value: &str) -> &mut Name {
+        self.attributes.push(NameAttribute {
+            oid: oid.to_vec(),
+            tag,
+            value: value.as_bytes().to_vec(),
+        });
+        self
+    }
+
+    #[inline]
+    pub fn common_name(&mut self, value: &str) -> &mut Name {
+        self.add(OID_COMMON_NAME, value)
+    }
+
+    // Country names are two-letter codes and must be PrintableString.
+    #[inline]
+    pub fn country(&mut self, value: &str) -> &mut Name {
+        self.add_with_tag(OID_COUNTRY_NAME, TAG_PRINTABLE_STRING, value)
+    }
+
+    #[inline]
+    pub fn locality(&mut self, value: &str) -> &mut Name {
+        self.add(OID_LOCALITY_NAME, value)
+    }
+
+    #[inline]
+    pub fn state_or_province(&mut self, value: &str) -> &mut Name {
+        self.add(OID_STATE_OR_PROVINCE_NAME, value)
+    }
+
+    #[inline]
+    pub fn organization(&mut self, value: &str) -> &mut Name {
+        self.add(OID_ORGANIZATION_NAME, value)
+    }
+
+    #[inline]
+    pub fn organizational_unit(&mut self, value: &str) -> &mut Name {
+        self.add(OID_ORGANIZATIONAL_UNIT_NAME, value)
+    }
+
+    #[inline]
+    pub fn attributes(&self) -> &[NameAttribute] {
+        &self.attributes
+    }
+
+    // The first attribute of the given type, if it is a valid string.
+    pub fn find(&self, oid: &[u8]) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.oid == oid)
+            .and_then(|attr| attr.as_str())
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut writer = DerWriter::new();
+        self.encode(&mut writer);
+        writer.into_vec()
+    }
+
+    pub fn from_der(der: &[u8]) -> SgxResult<Name> {
+        let mut reader = DerReader::new(der);
+        let name = Name::decode(&mut reader)?;
+        reader.finish()?;
+        Ok(name)
+    }
+
+    pub(crate) fn encode(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|w| {
+            for attr in self.attributes.iter() {
+                w.write_constructed(TAG_SET, |w| {
+                    w.write_sequence(|w| {
+                        w.write_oid(&attr.oid);
+                        w.write_tlv(attr.tag, &attr.value);
+                    });
+                });
+            }
+        });
+    }
+
+    pub(crate) fn decode(reader: &mut DerReader<'_>) -> SgxResult<Name> {
+        let mut seq = reader.read_sequence()?;
+        let mut attributes = Vec::new();
+        while !seq.is_empty() {
+            let mut rdn = seq.read_constructed(TAG_SET)?;
+            ensure!(!rdn.is_empty(), SgxStatus::InvalidParameter);
+            while !rdn.is_empty() {
+                let mut atv = rdn.read_sequence()?;
+                let oid = atv.read_oid()?;
+                let (tag, value) = atv.read_tlv()?;
+                atv.finish()?;
+                attributes.push(NameAttribute {
+                    oid: oid.to_vec(),
+                    tag,
+                    value: value.to_vec(),
+                });
+            }
+        }
+        Ok(Name { attributes })
+    }
+}
@@ -0,0 +1,252 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::der::{
+    AlgorithmIdentifier, AlgorithmParameters, DecodePublicKey, DerSignature, SubjectPublicKeyInfo,
+};
+use crate::der::{
+    OID_EC_PUBLIC_KEY, OID_ED25519, OID_PRIME256V1, OID_RSA_ENCRYPTION, OID_SECP384R1,
+};
+use crate::ecc::{EcPrivateKey, EcPublicKey, EcSignature};
+use crate::ecc::{P384PrivateKey, P384PublicKey, P384Signature};
+use crate::ed25519::{
+    Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_SIGNATURE_SIZE,
+};
+use crate::rsa::{Rsa2048PrivateKey, Rsa2048PublicKey, Rsa3072PrivateKey, Rsa3072PublicKey};
+use crate::rsa::{Rsa4096PrivateKey, Rsa4096PublicKey};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{Rsa2048Signature, Rsa3072Signature, Rsa4096Signature};
+
+// Signature algorithms (RFC 5758, RFC 4055 and RFC 8410).
+pub const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+pub const OID_ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+pub const OID_SHA256_WITH_RSA_ENCRYPTION: &[u8] =
+    &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureAlgorithm {
+    EcdsaWithSha256,
+    EcdsaWithSha384,
+    Sha256WithRsaEncryption,
+    Ed25519,
+}
+
+impl SignatureAlgorithm {
+    pub fn oid(&self) -> &'static [u8] {
+        match self {
+            SignatureAlgorithm::EcdsaWithSha256 => OID_ECDSA_WITH_SHA256,
+            SignatureAlgorithm::EcdsaWithSha384 => OID_ECDSA_WITH_SHA384,
+            SignatureAlgorithm::Sha256WithRsaEncryption => OID_SHA256_WITH_RSA_ENCRYPTION,
+            SignatureAlgorithm::Ed25519 => OID_ED25519,
+        }
+    }
+
+    pub fn algorithm_identifier(&self) -> AlgorithmIdentifier<'static> {
+        let parameters = match self {
+            SignatureAlgorithm::Sha256WithRsaEncryption => AlgorithmParameters::Null,
+            _ => AlgorithmParameters::Absent,
+        };
+        AlgorithmIdentifier {
+            oid: self.oid(),
+            parameters,
+        }
+    }
+
+    // RSA parameters are NULL but some encoders leave them out; the ECDSA and
+    // EdDSA ones must be absent.
+    pub fn from_algorithm_identifier(
+        algorithm: &AlgorithmIdentifier<'_>,
+    ) -> SgxResult<SignatureAlgorithm> {
+        let signature_algorithm = match algorithm.oid {
+            OID_ECDSA_WITH_SHA256 => SignatureAlgorithm::EcdsaWithSha256,
+            OID_ECDSA_WITH_SHA384 => SignatureAlgorithm::EcdsaWithSha384,
+            OID_SHA256_WITH_RSA_ENCRYPTION => SignatureAlgorithm::Sha256WithRsaEncryption,
+            OID_ED25519 => SignatureAlgorithm::Ed25519,
+            _ => bail!(SgxStatus::UnsupportedFeature),
+        };
+        match (signature_algorithm, algorithm.parameters) {
+            (_, AlgorithmParameters::Absent)
+            | (SignatureAlgorithm::Sha256WithRsaEncryption, AlgorithmParameters::Null) => {
+                Ok(signature_algorithm)
+            }
+            _ => Err(SgxStatus::InvalidParameter),
+        }
+    }
+}
+
+// A private key that can sign certificates.
+pub trait CertificateSigner {
+    fn signature_algorithm(&self) -> SignatureAlgorithm;
+
+    // Signs the DER-encoded TBSCertificate and returns the signatureValue.
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>>;
+}
+
+impl CertificateSigner for EcPrivateKey {
+    #[inline]
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::EcdsaWithSha256
+    }
+
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>> {
+        Ok(self.sign(tbs)?.to_der())
+    }
+}
+
+impl CertificateSigner for P384PrivateKey {
+    #[inline]
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::EcdsaWithSha384
+    }
+
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>> {
+        Ok(self.sign(tbs)?.to_der())
+    }
+}
+
+impl CertificateSigner for Rsa2048PrivateKey {
+    #[inline]
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Sha256WithRsaEncryption
+    }
+
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>> {
+        Ok(self.sign(tbs)?.signature.to_vec())
+    }
+}
+
+impl CertificateSigner for Rsa3072PrivateKey {
+    #[inline]
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Sha256WithRsaEncryption
+    }
+
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>> {
+        Ok(self.sign(tbs)?.signature.to_vec())
+    }
+}
+
+impl CertificateSigner for Rsa4096PrivateKey {
+    #[inline]
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Sha256WithRsaEncryption
+    }
+
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>> {
+        Ok(self.sign(tbs)?.signature.to_vec())
+    }
+}
+
+impl CertificateSigner for Ed25519PrivateKey {
+    #[inline]
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        SignatureAlgorithm::Ed25519
+    }
+
+    fn sign_certificate(&self, tbs: &[u8]) -> SgxResult<Vec<u8>> {
+        Ok(self.sign(tbs)?.signature().to_vec())
+    }
+}
+
+// Subject public key of a certificate, for the key types sgx_crypto can
+// verify signatures with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CertificatePublicKey {
+    P256(EcPublicKey),
+    P384(P384PublicKey),
+    Rsa2048(Rsa2048PublicKey),
+    Rsa3072(Rsa3072PublicKey),
+    Rsa4096(Rsa4096PublicKey),
+    Ed25519(Ed25519PublicKey),
+}
+
+impl CertificatePublicKey {
+    pub fn from_spki(der: &[u8]) -> SgxResult<CertificatePublicKey> {
+        let spki = SubjectPublicKeyInfo::from_der(der)?;
+        let key = match (spki.algorithm.oid, spki.algorithm.parameters) {
+            (OID_EC_PUBLIC_KEY, AlgorithmParameters::Oid(OID_PRIME256V1)) => {
+                CertificatePublicKey::P256(EcPublicKey::from_public_key_der(der)?)
+            }
+            (OID_EC_PUBLIC_KEY, AlgorithmParameters::Oid(OID_SECP384R1)) => {
+                CertificatePublicKey::P384(P384PublicKey::from_public_key_der(der)?)
+            }
+            (OID_RSA_ENCRYPTION, _) => Rsa4096PublicKey::from_public_key_der(der)
+                .map(CertificatePublicKey::Rsa4096)
+                .or_else(|_| {
+                    Rsa3072PublicKey::from_public_key_der(der).map(CertificatePublicKey::Rsa3072)
+                })
+                .or_else(|_| {
+                    Rsa2048PublicKey::from_public_key_der(der).map(CertificatePublicKey::Rsa2048)
+                })?,
+            (OID_ED25519, _) => {
+                CertificatePublicKey::Ed25519(Ed25519PublicKey::from_public_key_der(der)?)
+            }
+            _ => bail!(SgxStatus::UnsupportedFeature),
+        };
+        Ok(key)
+    }
+
+    // Checks a signature over `data`. The algorithm has to match the key.
+    pub fn verify(
+        &self,
+        algorithm: SignatureAlgorithm,
+        data: &[u8],
+        signature: &[u8],
+    ) -> SgxResult<bool> {
+        match (self, algorithm) {
+            (CertificatePublicKey::P256(key), SignatureAlgorithm::EcdsaWithSha256) => {
+                match EcSignature::from_der(signature) {
+                    Ok(signature) => key.verify(data, &signature),
+                    Err(_) => Ok(false),
+                }
+            }
+            (CertificatePublicKey::P384(key), SignatureAlgorithm::EcdsaWithSha384) => {
+                match P384Signature::from_der(signature) {
+                    Ok(signature) => key.verify(data, &signature),
+                    Err(_) => Ok(false),
+                }
+            }
+            (CertificatePublicKey::Rsa2048(key), SignatureAlgorithm::Sha256WithRsaEncryption) => {
+                match signature.try_into() {
+                    Ok(signature) => key.verify(data, &Rsa2048Signature { signature }),
+                    Err(_) => Ok(false),
+                }
+            }
+            (CertificatePublicKey::Rsa3072(key), SignatureAlgorithm::Sha256WithRsaEncryption) => {
+                match signature.try_into() {
+                    Ok(signature) => key.verify(data, &Rsa3072Signature { signature }),
+                    Err(_) => Ok(false),
+                }
+            }
+            (CertificatePublicKey::Rsa4096(key), SignatureAlgorithm::Sha256WithRsaEncryption) => {
+                match signature.try_into() {
+                    Ok(signature) => key.verify(data, &Rsa4096Signature { signature }),
+                    Err(_) => Ok(false),
+                }
+            }
+            (CertificatePublicKey::Ed25519(key), SignatureAlgorithm::Ed25519) => {
+                match <[u8; ED25519_SIGNATURE_SIZE]>::try_from(signature) {
+                    Ok(signature) => key.verify(data, &Ed25519Signature::from(signature)),
+                    Err(_) => Ok(false),
+                }
+            }
+            _ => Ok(false),
+        }
+    }
+}
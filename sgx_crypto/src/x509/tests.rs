@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{BasicConstraints, Certificate, CertificateBuilder, Name, Validity};
+use crate::curve25519::ed25519::Ed25519PrivateKey;
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+const NOW: u64 = 1_700_000_000;
+
+fn clock() -> SgxResult<u64> {
+    Ok(NOW)
+}
+
+fn key(seed: u8) -> Ed25519PrivateKey {
+    Ed25519PrivateKey::from([seed; 32])
+}
+
+fn name(common_name: &str) -> Name {
+    let mut name = Name::new();
+    name.common_name(common_name);
+    name
+}
+
+// `ca` is None for an end-entity certificate, or the path length constraint.
+fn issue(
+    subject: &str,
+    subject_key: &Ed25519PrivateKey,
+    issuer: &str,
+    issuer_key: &Ed25519PrivateKey,
+    ca: Option<Option<u8>>,
+) -> Certificate {
+    let mut builder = CertificateBuilder::new();
+    builder
+        .subject(name(subject))
+        .issuer(name(issuer))
+        .validity(Validity::new(NOW - 3600, NOW + 3600).unwrap());
+    builder
+        .public_key(&subject_key.export_public_key().unwrap())
+        .unwrap();
+    if let Some(path_len) = ca {
+        builder.basic_constraints(BasicConstraints { ca: true, path_len });
+    }
+    builder.sign(issuer_key).unwrap()
+}
+
+#[test_case]
+fn chain_valid_path() {
+    let root = issue("root", &key(1), "root", &key(1), Some(None));
+    let inter = issue("inter", &key(2), "root", &key(1), Some(None));
+    // same subject as the intermediate, but another key
+    let decoy = issue("inter", &key(4), "root", &key(1), Some(None));
+    let leaf = issue("leaf", &key(3), "inter", &key(2), None);
+
+    let intermediates = [decoy, inter];
+    leaf.verify_chain(&intermediates, &[root.clone()], &clock)
+        .unwrap();
+    intermediates[1]
+        .verify_chain(&[], &[root.clone()], &clock)
+        .unwrap();
+    root.verify_chain(&[], &[root.clone()], &clock).unwrap();
+
+    assert_eq!(
+        leaf.verify_chain(&intermediates, &[], &clock),
+        Err(SgxStatus::InvalidSignature)
+    );
+    assert_eq!(
+        leaf.verify_chain(&intermediates, &[root], &|| -> SgxResult<u64> {
+            Ok(NOW + 7200)
+        }),
+        Err(SgxStatus::InvalidParameter)
+    );
+}
+
+#[test_case]
+fn chain_path_length_violation() {
+    let root = issue("root", &key(1), "root", &key(1), Some(Some(0)));
+    let inter = issue("inter", &key(2), "root", &key(1), Some(None));
+    let leaf = issue("leaf", &key(3), "inter", &key(2), None);
+    assert_eq!(
+        leaf.verify_chain(&[inter.clone()], &[root.clone()], &clock),
+        Err(SgxStatus::InvalidParameter)
+    );
+    // the intermediate alone is within the constraint
+    inter.verify_chain(&[], &[root.clone()], &clock).unwrap();
+
+    // an issuer that is not a CA
+    let not_ca = issue("inter", &key(2), "root", &key(1), None);
+    assert_eq!(
+        leaf.verify_chain(&[not_ca], &[root], &clock),
+        Err(SgxStatus::InvalidParameter)
+    );
+}
+
+#[test_case]
+fn chain_name_mismatch() {
+    let root = issue("root", &key(1), "root", &key(1), Some(None));
+    let inter = issue("inter", &key(2), "root", &key(1), Some(None));
+    // signed by the intermediate's key under another issuer name
+    let leaf = issue("leaf", &key(3), "other", &key(2), None);
+    assert_eq!(
+        leaf.verify_chain(&[inter], &[root], &clock),
+        Err(SgxStatus::InvalidSignature)
+    );
+}
+
+#[test_case]
+fn chain_cross_signed_loop() {
+    let root = issue("root", &key(1), "root", &key(1), Some(None));
+    // two self-issued CAs signing each other
+    let ca_a = issue("loop", &key(5), "loop", &key(6), Some(None));
+    let ca_b = issue("loop", &key(6), "loop", &key(5), Some(None));
+    let leaf = issue("leaf", &key(3), "loop", &key(5), None);
+
+    assert_eq!(
+        leaf.verify_chain(&[ca_a, ca_b], &[root], &clock),
+        Err(SgxStatus::InvalidSignature)
+    );
+
+    // a chain of self-issued CAs is still bounded by MAX_CHAIN_LENGTH
+    let keys: Vec<Ed25519PrivateKey> = (10..20).map(key).collect();
+    let chain: Vec<Certificate> = keys
+        .windows(2)
+        .map(|pair| issue("long", &pair[0], "long", &pair[1], Some(None)))
+        .collect();
+    let anchor = issue("long", &keys[9], "long", &keys[9], Some(None));
+    let leaf = issue("leaf", &key(3), "long", &keys[0], None);
+    assert_eq!(
+        leaf.verify_chain(&chain, &[anchor.clone()], &clock),
+        Err(SgxStatus::InvalidParameter)
+    );
+    chain[6].verify_chain(&chain, &[anchor], &clock).unwrap();
+}
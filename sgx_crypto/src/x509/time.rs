@@ -0,0 +1,202 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::der::{DerReader, DerWriter, TAG_GENERALIZED_TIME, TAG_UTC_TIME};
+use alloc::string::String;
+use sgx_types::error::{SgxResult, SgxStatus};
+
+// 9999-12-31T23:59:59Z, the largest time X.509 can express. RFC 5280 also
+// uses it for certificates that have no well-defined expiration date.
+pub const MAX_TIME: u64 = 253_402_300_799;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+// Source of the current time, in seconds since the Unix epoch (UTC).
+//
+// The enclave has no trusted time source of its own, so every operation that
+// depends on the current time takes a clock supplied by the caller.
+pub trait Clock {
+    fn now(&self) -> SgxResult<u64>;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SgxResult<u64>,
+{
+    #[inline]
+    fn now(&self) -> SgxResult<u64> {
+        self()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Validity {
+    pub not_before: u64,
+    pub not_after: u64,
+}
+
+impl Validity {
+    pub fn new(not_before: u64, not_after: u64) -> SgxResult<Validity> {
+        ensure!(
+            not_before <= not_after && not_after <= MAX_TIME,
+            SgxStatus::InvalidParameter
+        );
+        Ok(Validity {
+            not_before,
+            not_after,
+        })
+    }
+
+    // Validity starting now and lasting `duration` seconds.
+    pub fn from_clock<C: Clock + ?Sized>(clock: &C, duration: u64) -> SgxResult<Validity> {
+        let not_before = clock.now()?;
+        let not_after = not_before
+            .checked_add(duration)
+            .ok_or(SgxStatus::InvalidParameter)?;
+        Validity::new(not_before, not_after)
+    }
+
+    #[inline]
+    pub fn contains(&self, time: u64) -> bool {
+        self.not_before <= time && time <= self.not_after
+    }
+
+    pub(crate) fn encode(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|w| {
+            encode_time(w, self.not_before);
+            encode_time(w, self.not_after);
+        });
+    }
+
+    pub(crate) fn decode(reader: &mut DerReader<'_>) -> SgxResult<Validity> {
+        let mut seq = reader.read_sequence()?;
+        let not_before = decode_time(&mut seq)?;
+        let not_after = decode_time(&mut seq)?;
+        seq.finish()?;
+        Ok(Validity {
+            not_before,
+            not_after,
+        })
+    }
+}
+
+// RFC 5280: UTCTime through 2049, GeneralizedTime from 2050 on.
+fn encode_time(writer: &mut DerWriter, time: u64) {
+    let time = time.min(MAX_TIME);
+    let (year, month, day) = civil_from_days(time / SECS_PER_DAY);
+    let secs = time % SECS_PER_DAY;
+    let (hour, minute, second) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    let (tag, value): (u8, String) = if year < 2050 {
+        let value = format!(
+            "{:02}{:02}{:02}{:02}{:02}{:02}Z",
+            year % 100,
+            month,
+            day,
+            hour,
+            minute,
+            second
+        );
+        (TAG_UTC_TIME, value)
+    } else {
+        let value = format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}Z",
+            year, month, day, hour, minute, second
+        );
+        (TAG_GENERALIZED_TIME, value)
+    };
+    writer.write_tlv(tag, value.as_bytes());
+}
+
+fn decode_time(reader: &mut DerReader<'_>) -> SgxResult<u64> {
+    let (tag, value) = reader.read_tlv()?;
+    let (year, rest) = match (tag, value.len()) {
+        (TAG_UTC_TIME, 13) => {
+            let year = parse_digits(&value[..2])?;
+            (
+                if year >= 50 { 1900 + year } else { 2000 + year },
+                &value[2..],
+            )
+        }
+        (TAG_GENERALIZED_TIME, 15) => (parse_digits(&value[..4])?, &value[4..]),
+        _ => bail!(SgxStatus::InvalidParameter),
+    };
+    ensure!(rest[10] == b'Z', SgxStatus::InvalidParameter);
+
+    let month = parse_digits(&rest[0..2])?;
+    let day = parse_digits(&rest[2..4])?;
+    let hour = parse_digits(&rest[4..6])?;
+    let minute = parse_digits(&rest[6..8])?;
+    let second = parse_digits(&rest[8..10])?;
+    ensure!(
+        year >= 1970
+            && (1..=12).contains(&month)
+            && day >= 1
+            && day <= days_in_month(year, month)
+            && hour < 24
+            && minute < 60
+            && second < 60,
+        SgxStatus::InvalidParameter
+    );
+
+    Ok(days_from_civil(year, month, day) * SECS_PER_DAY + hour * 3600 + minute * 60 + second)
+}
+
+fn parse_digits(digits: &[u8]) -> SgxResult<u64> {
+    digits.iter().try_fold(0_u64, |acc, &d| {
+        ensure!(d.is_ascii_digit(), SgxStatus::InvalidParameter);
+        Ok(acc * 10 + u64::from(d - b'0'))
+    })
+}
+
+#[inline]
+fn is_leap_year(year: u64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date, for years >= 1970.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
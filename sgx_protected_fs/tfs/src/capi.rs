@@ -465,7 +465,7 @@ pub unsafe extern "C" fn sgx_remove(filename: *const c_char) -> i32 {
         }
     };
 
-    match fs_imp::remove(name) {
+    match fs_imp::remove(name, &OpenOptions::new()) {
         Ok(_) => 0,
         Err(_) => -1,
     }
@@ -491,7 +491,7 @@ pub unsafe extern "C" fn sgx_fexport_auto_key(
         }
     };
 
-    match fs_imp::export_key(name) {
+    match fs_imp::export_key(name, &OpenOptions::new()) {
         Ok(key) => {
             *export_key = key;
             0
@@ -533,7 +533,7 @@ pub unsafe extern "C" fn sgx_fimport_auto_key(
         KeyPolicy::MRSIGNER
     };
 
-    match fs_imp::import_key(name, *import_key, key_policy) {
+    match fs_imp::import_key(name, &OpenOptions::new(), *import_key, Some(key_policy)) {
         Ok(_) => 0,
         Err(_) => -1,
     }
@@ -0,0 +1,579 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::fs::{EncryptMode, OpenOptions, SgxFile};
+use sgx_rand::{RdRand, Rng};
+use sgx_types::error::errno::*;
+use std::borrow::ToOwned;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::sync::{Mutex, MutexGuard};
+use std::vec::Vec;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+const INDEX_NAME: &str = "index";
+const INDEX_MAGIC: [u8; 8] = *b"SGXFSDIR";
+const INDEX_VERSION: u32 = 1;
+const INDEX_HEADER_SIZE: usize = 16;
+const HOST_ID_SIZE: usize = 16;
+
+type HostId = [u8; HOST_ID_SIZE];
+
+/// A namespace of protected files kept in one host directory.
+///
+/// Files are named by logical paths such as `"db/wal/0001"`, which are kept
+/// in an index, itself a protected file. Each file is stored under a random
+/// host name, so the host only sees the index and a flat set of files of
+/// random names, and learns neither the file names nor how they are nested.
+/// Directories are implied by the paths of the files in them.
+///
+/// The index and the files are protected with the `EncryptMode` the
+/// namespace is opened with. With `EncryptMode::integrity_only` the index is
+/// not encrypted, so the file names are visible to the host.
+///
+/// The host directory must exist. The index stays open while the namespace
+/// is, so it can only be opened once at a time.
+pub struct ProtectedDir {
+    path: PathBuf,
+    encrypt_mode: EncryptMode,
+    opts: OpenOptions,
+    index: Mutex<DirIndex>,
+}
+
+struct DirIndex {
+    file: SgxFile,
+    len: usize,
+    entries: BTreeMap<String, HostId>,
+}
+
+/// An entry returned by `ProtectedDir::list`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DirEntry {
+    name: String,
+    is_dir: bool,
+}
+
+/// Metadata of a file or directory in a `ProtectedDir`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    is_dir: bool,
+    len: u64,
+}
+
+impl ProtectedDir {
+    /// Creates a namespace with an empty index in the host directory `path`.
+    pub fn create<P: AsRef<Path>>(path: P, encrypt_mode: EncryptMode) -> io::Result<ProtectedDir> {
+        Self::create_with(path, encrypt_mode, &OpenOptions::new())
+    }
+
+    /// Opens the namespace in the host directory `path`.
+    pub fn open<P: AsRef<Path>>(path: P, encrypt_mode: EncryptMode) -> io::Result<ProtectedDir> {
+        Self::open_with(path, encrypt_mode, &OpenOptions::new())
+    }
+
+    /// Like `create`, with the cipher, counter and storage of `opts` used for
+    /// the index and every file. The access mode of `opts` is ignored.
+    pub fn create_with<P: AsRef<Path>>(
+        path: P,
+        encrypt_mode: EncryptMode,
+        opts: &OpenOptions,
+    ) -> io::Result<ProtectedDir> {
+        let path = path.as_ref().to_path_buf();
+        let index_path = path.join(INDEX_NAME);
+        ensure!(
+            !opts.host_storage().try_exists(&index_path)?,
+            io::Error::from_raw_os_error(EEXIST)
+        );
+
+        let file =
+            with_access(opts, Access::Create).open_with(&index_path, encrypt_mode.clone(), None)?;
+        let mut index = DirIndex {
+            file,
+            len: 0,
+            entries: BTreeMap::new(),
+        };
+        index.save()?;
+
+        Ok(ProtectedDir {
+            path,
+            encrypt_mode,
+            opts: opts.clone(),
+            index: Mutex::new(index),
+        })
+    }
+
+    /// Like `open`, with the cipher, counter and storage of `opts` used for
+    /// the index and every file. The access mode of `opts` is ignored.
+    pub fn open_with<P: AsRef<Path>>(
+        path: P,
+        encrypt_mode: EncryptMode,
+        opts: &OpenOptions,
+    ) -> io::Result<ProtectedDir> {
+        let path = path.as_ref().to_path_buf();
+        let file = with_access(opts, Access::Update).open_with(
+            path.join(INDEX_NAME),
+            encrypt_mode.clone(),
+            None,
+        )?;
+        let index = DirIndex::load(file)?;
+
+        Ok(ProtectedDir {
+            path,
+            encrypt_mode,
+            opts: opts.clone(),
+            index: Mutex::new(index),
+        })
+    }
+
+    /// The host directory of the namespace.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Creates the file `name`, or truncates it if it exists, and opens it
+    /// for writing.
+    pub fn create_file<P: AsRef<str>>(&self, name: P) -> io::Result<SgxFile> {
+        self.open_file_with(name.as_ref(), Access::Create)
+    }
+
+    /// Opens the file `name` for reading.
+    pub fn open_file<P: AsRef<str>>(&self, name: P) -> io::Result<SgxFile> {
+        self.open_file_with(name.as_ref(), Access::Read)
+    }
+
+    /// Opens the file `name` for appending, creating it if needed.
+    pub fn append_file<P: AsRef<str>>(&self, name: P) -> io::Result<SgxFile> {
+        self.open_file_with(name.as_ref(), Access::Append)
+    }
+
+    /// Opens the file `name` for reading and writing.
+    pub fn update_file<P: AsRef<str>>(&self, name: P) -> io::Result<SgxFile> {
+        self.open_file_with(name.as_ref(), Access::Update)
+    }
+
+    pub fn exists<P: AsRef<str>>(&self, name: P) -> io::Result<bool> {
+        let name = file_name(name.as_ref())?;
+        let index = self.lock()?;
+        Ok(index.entries.contains_key(&name) || index.is_dir(&name))
+    }
+
+    /// Removes the file `name`.
+    pub fn remove_file<P: AsRef<str>>(&self, name: P) -> io::Result<()> {
+        let name = file_name(name.as_ref())?;
+        let mut index = self.lock()?;
+
+        let id = match index.entries.remove(&name) {
+            Some(id) => id,
+            None if index.is_dir(&name) => bail!(io::Error::from_raw_os_error(EISDIR)),
+            None => bail!(io::Error::from_raw_os_error(ENOENT)),
+        };
+        if let Err(e) = index.save() {
+            index.entries.insert(name, id);
+            bail!(e);
+        }
+        self.remove_host_file(&id)
+    }
+
+    /// Renames the file or directory `from` to `to`.
+    ///
+    /// A file replaces the file `to` if there is one. A directory is moved
+    /// with everything in it, and `to` must not exist. Only the index
+    /// changes; the files keep their host names.
+    pub fn rename<P: AsRef<str>, Q: AsRef<str>>(&self, from: P, to: Q) -> io::Result<()> {
+        let from = file_name(from.as_ref())?;
+        let to = file_name(to.as_ref())?;
+        let mut index = self.lock()?;
+        if from == to {
+            return Ok(());
+        }
+
+        let saved = index.entries.clone();
+        let mut replaced = None;
+        if let Some(id) = index.entries.get(&from).copied() {
+            ensure!(!index.is_dir(&to), io::Error::from_raw_os_error(EISDIR));
+            index.check_parents(&to)?;
+
+            index.entries.remove(&from);
+            replaced = index.entries.insert(to, id);
+        } else if index.is_dir(&from) {
+            ensure!(!is_inside(&to, &from), io::Error::from_raw_os_error(EINVAL));
+            ensure!(
+                !index.entries.contains_key(&to) && !index.is_dir(&to),
+                io::Error::from_raw_os_error(EEXIST)
+            );
+            index.check_parents(&to)?;
+
+            let moved: Vec<(String, HostId)> = index
+                .entries
+                .iter()
+                .filter(|(name, _)| is_inside(name, &from))
+                .map(|(name, id)| (name.clone(), *id))
+                .collect();
+            for (name, id) in moved {
+                index.entries.remove(&name);
+                index
+                    .entries
+                    .insert([to.as_str(), &name[from.len()..]].concat(), id);
+            }
+        } else {
+            bail!(io::Error::from_raw_os_error(ENOENT));
+        }
+
+        if let Err(e) = index.save() {
+            index.entries = saved;
+            bail!(e);
+        }
+        match replaced {
+            Some(id) => self.remove_host_file(&id),
+            None => Ok(()),
+        }
+    }
+
+    /// Lists the directory `name`, or the top of the namespace if `name` is
+    /// empty, in name order.
+    pub fn list<P: AsRef<str>>(&self, name: P) -> io::Result<Vec<DirEntry>> {
+        let name = dir_name(name.as_ref())?;
+        let index = self.lock()?;
+        if !name.is_empty() {
+            ensure!(
+                !index.entries.contains_key(&name),
+                io::Error::from_raw_os_error(ENOTDIR)
+            );
+            ensure!(index.is_dir(&name), io::Error::from_raw_os_error(ENOENT));
+        }
+
+        let prefix = if name.is_empty() { 0 } else { name.len() + 1 };
+        let mut list: Vec<DirEntry> = Vec::new();
+        for path in index.entries.keys().filter(|path| is_inside(path, &name)) {
+            let rest = &path[prefix..];
+            let entry = match rest.find('/') {
+                Some(pos) => DirEntry {
+                    name: rest[..pos].to_owned(),
+                    is_dir: true,
+                },
+                None => DirEntry {
+                    name: rest.to_owned(),
+                    is_dir: false,
+                },
+            };
+            // Paths are sorted, so the files of a directory follow each other.
+            if list.last() != Some(&entry) {
+                list.push(entry);
+            }
+        }
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(list)
+    }
+
+    /// The metadata of the file or directory `name`. The length of a file is
+    /// read from the file, which must not be open for writing.
+    pub fn metadata<P: AsRef<str>>(&self, name: P) -> io::Result<Metadata> {
+        let name = file_name(name.as_ref())?;
+        let id = {
+            let index = self.lock()?;
+            match index.entries.get(&name) {
+                Some(id) => *id,
+                None if index.is_dir(&name) => {
+                    return Ok(Metadata {
+                        is_dir: true,
+                        len: 0,
+                    })
+                }
+                None => bail!(io::Error::from_raw_os_error(ENOENT)),
+            }
+        };
+
+        let file = self.open_host_file(&id, Access::Read)?;
+        Ok(Metadata {
+            is_dir: false,
+            len: file.file_size()?,
+        })
+    }
+
+    fn open_file_with(&self, name: &str, access: Access) -> io::Result<SgxFile> {
+        let name = file_name(name)?;
+        let mut index = self.lock()?;
+
+        if let Some(id) = index.entries.get(&name).copied() {
+            return self.open_host_file(&id, access);
+        }
+        ensure!(!index.is_dir(&name), io::Error::from_raw_os_error(EISDIR));
+        ensure!(
+            access == Access::Create || access == Access::Append,
+            io::Error::from_raw_os_error(ENOENT)
+        );
+        index.check_parents(&name)?;
+
+        // The file is created before it is added to the index, so a failure
+        // can leave an unnamed file behind but never a name without a file.
+        let id = self.new_host_id(&index)?;
+        let file = self.open_host_file(&id, access)?;
+        index.entries.insert(name.clone(), id);
+        if let Err(e) = index.save() {
+            index.entries.remove(&name);
+            drop(file);
+            let _ = self.opts.host_storage().remove(&self.host_path(&id));
+            bail!(e);
+        }
+        Ok(file)
+    }
+
+    fn open_host_file(&self, id: &HostId, access: Access) -> io::Result<SgxFile> {
+        with_access(&self.opts, access).open_with(
+            self.host_path(id),
+            self.encrypt_mode.clone(),
+            None,
+        )
+    }
+
+    fn new_host_id(&self, index: &DirIndex) -> io::Result<HostId> {
+        let mut rng = RdRand::new().map_err(|_| io::Error::from_raw_os_error(EIO))?;
+        loop {
+            let mut id = [0_u8; HOST_ID_SIZE];
+            rng.fill_bytes(&mut id);
+            if !index.entries.values().any(|used| *used == id)
+                && !self.opts.host_storage().try_exists(&self.host_path(&id))?
+            {
+                return Ok(id);
+            }
+        }
+    }
+
+    // Also destroys the counter of the file, if the namespace has one.
+    #[inline]
+    fn remove_host_file(&self, id: &HostId) -> io::Result<()> {
+        self.opts
+            .remove_with(self.host_path(id), self.encrypt_mode.clone())
+    }
+
+    fn host_path(&self, id: &HostId) -> PathBuf {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let mut name = String::with_capacity(HOST_ID_SIZE * 2);
+        for b in id {
+            name.push(HEX[(b >> 4) as usize] as char);
+            name.push(HEX[(b & 0xf) as usize] as char);
+        }
+        self.path.join(name)
+    }
+
+    fn lock(&self) -> io::Result<MutexGuard<'_, DirIndex>> {
+        self.index
+            .lock()
+            .map_err(|_| io::Error::from_raw_os_error(EIO))
+    }
+}
+
+impl fmt::Debug for ProtectedDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProtectedDir")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DirIndex {
+    // Layout: magic | version u32 | count u32 | entries, where an entry is
+    // name_len u16 | name | host id. The index file never shrinks, so the
+    // bytes after the entries are zeroed and ignored.
+    fn load(mut file: SgxFile) -> io::Result<DirIndex> {
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+
+        let invalid = || io::Error::from_raw_os_error(EINVAL);
+        ensure!(
+            buf.len() >= INDEX_HEADER_SIZE && buf[..8] == INDEX_MAGIC,
+            invalid()
+        );
+        ensure!(
+            read_u32(&buf[8..]) == INDEX_VERSION,
+            io::Error::from_raw_os_error(ENOTSUP)
+        );
+        let count = read_u32(&buf[12..]);
+
+        let mut entries = BTreeMap::new();
+        let mut pos = INDEX_HEADER_SIZE;
+        for _ in 0..count {
+            let name_len = buf
+                .get(pos..pos + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+                .ok_or_else(invalid)?;
+            pos += 2;
+            let name = buf
+                .get(pos..pos + name_len)
+                .and_then(|b| std::str::from_utf8(b).ok())
+                .ok_or_else(invalid)?
+                .to_owned();
+            pos += name_len;
+            let mut id = [0_u8; HOST_ID_SIZE];
+            id.copy_from_slice(buf.get(pos..pos + HOST_ID_SIZE).ok_or_else(invalid)?);
+            pos += HOST_ID_SIZE;
+
+            ensure!(entries.insert(name, id).is_none(), invalid());
+        }
+
+        Ok(DirIndex {
+            file,
+            len: pos,
+            entries,
+        })
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(self.len);
+        buf.extend_from_slice(&INDEX_MAGIC);
+        buf.extend_from_slice(&INDEX_VERSION.to_le_bytes());
+        buf.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (name, id) in self.entries.iter() {
+            buf.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            buf.extend_from_slice(name.as_bytes());
+            buf.extend_from_slice(id);
+        }
+
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.write_all(&buf)?;
+        if buf.len() < self.len {
+            self.file.set_len(buf.len() as u64)?;
+        }
+        self.file.flush()?;
+        self.len = buf.len();
+        Ok(())
+    }
+
+    // Whether `name` is a directory, i.e. some file path starts with "name/".
+    fn is_dir(&self, name: &str) -> bool {
+        let prefix = [name, "/"].concat();
+        self.entries
+            .range::<str, _>((Bound::Included(prefix.as_str()), Bound::Unbounded))
+            .next()
+            .map_or(false, |(path, _)| path.starts_with(&prefix))
+    }
+
+    // No parent of `name` may be a file.
+    fn check_parents(&self, name: &str) -> io::Result<()> {
+        for (pos, _) in name.match_indices('/') {
+            ensure!(
+                !self.entries.contains_key(&name[..pos]),
+                io::Error::from_raw_os_error(ENOTDIR)
+            );
+        }
+        Ok(())
+    }
+}
+
+impl DirEntry {
+    /// The name of the entry within its directory.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    #[inline]
+    pub fn is_file(&self) -> bool {
+        !self.is_dir
+    }
+}
+
+impl Metadata {
+    #[inline]
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    #[inline]
+    pub fn is_file(&self) -> bool {
+        !self.is_dir
+    }
+
+    /// The length of a file in bytes, 0 for a directory.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Access {
+    Read,
+    Create,
+    Append,
+    Update,
+}
+
+fn with_access(opts: &OpenOptions, access: Access) -> OpenOptions {
+    let mut opts = opts.clone();
+    opts.read(access == Access::Read || access == Access::Update)
+        .write(access == Access::Create)
+        .append(access == Access::Append)
+        .update(access != Access::Read);
+    opts
+}
+
+// Normalizes a logical file path: components are separated by a single '/',
+// and may not be empty, "." or "..".
+fn file_name(name: &str) -> io::Result<String> {
+    let name = dir_name(name)?;
+    ensure!(!name.is_empty(), io::Error::from_raw_os_error(EINVAL));
+    Ok(name)
+}
+
+fn dir_name(name: &str) -> io::Result<String> {
+    let name = name.trim_matches('/');
+    ensure!(
+        name.len() <= u16::MAX as usize,
+        io::Error::from_raw_os_error(ENAMETOOLONG)
+    );
+    if !name.is_empty() {
+        ensure!(
+            !name.contains('\0')
+                && name
+                    .split('/')
+                    .all(|c| !c.is_empty() && c != "." && c != ".."),
+            io::Error::from_raw_os_error(EINVAL)
+        );
+    }
+    Ok(name.to_owned())
+}
+
+// Whether `path` is inside the directory `dir`; everything is inside "".
+#[inline]
+fn is_inside(path: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || (path.len() > dir.len() && path.starts_with(dir) && path.as_bytes()[dir.len()] == b'/')
+}
+
+#[inline]
+fn read_u32(buf: &[u8]) -> u32 {
+    u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
@@ -0,0 +1,193 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{DirEntry, ProtectedDir, INDEX_NAME};
+use crate::fs::{EncryptMode, MemoryStorage, OpenOptions};
+use sgx_test_utils::test_case;
+use sgx_types::error::errno::*;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::Key128bit;
+use std::borrow::ToOwned;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::vec::Vec;
+
+const DIR_KEY: Key128bit = [0x44; 16];
+
+#[cfg(feature = "tfs")]
+fn is_sgx_error(error: &io::Error, status: SgxStatus) -> bool {
+    error.raw_sgx_error() == Some(status)
+}
+
+#[cfg(feature = "ufs")]
+fn is_sgx_error(error: &io::Error, status: SgxStatus) -> bool {
+    error.to_string() == status.as_str()
+}
+
+fn options(storage: &MemoryStorage) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.storage(Arc::new(storage.clone()));
+    opts
+}
+
+fn create(storage: &MemoryStorage) -> ProtectedDir {
+    ProtectedDir::create_with("dir", EncryptMode::user_key(DIR_KEY), &options(storage)).unwrap()
+}
+
+fn reopen(storage: &MemoryStorage) -> io::Result<ProtectedDir> {
+    ProtectedDir::open_with("dir", EncryptMode::user_key(DIR_KEY), &options(storage))
+}
+
+fn write(dir: &ProtectedDir, name: &str, data: &[u8]) {
+    let mut file = dir.create_file(name).unwrap();
+    file.write_all(data).unwrap();
+}
+
+fn read(dir: &ProtectedDir, name: &str) -> io::Result<Vec<u8>> {
+    let mut file = dir.open_file(name)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn entry(name: &str, is_dir: bool) -> DirEntry {
+    DirEntry {
+        name: name.to_owned(),
+        is_dir,
+    }
+}
+
+fn os_error<T>(result: io::Result<T>) -> Option<i32> {
+    result.err().and_then(|error| error.raw_os_error())
+}
+
+// The host files of the namespace, without the index.
+fn host_files(storage: &MemoryStorage) -> Vec<PathBuf> {
+    let mut names = storage.names();
+    names.retain(|name| name != &Path::new("dir").join(INDEX_NAME));
+    names.sort();
+    names
+}
+
+#[test_case]
+fn dir_create_list_remove() {
+    let storage = MemoryStorage::new();
+    let dir = create(&storage);
+    write(&dir, "a", b"file a");
+    write(&dir, "db/wal/0001", b"wal 1");
+    write(&dir, "db/wal/0002", b"wal 2");
+    write(&dir, "db/x", b"");
+
+    assert_eq!(
+        dir.list("").unwrap(),
+        [entry("a", false), entry("db", true)]
+    );
+    assert_eq!(
+        dir.list("db").unwrap(),
+        [entry("wal", true), entry("x", false)]
+    );
+    assert_eq!(
+        dir.list("db/wal").unwrap(),
+        [entry("0001", false), entry("0002", false)]
+    );
+    assert_eq!(os_error(dir.list("a")), Some(ENOTDIR));
+    assert_eq!(os_error(dir.list("b")), Some(ENOENT));
+
+    assert!(dir.metadata("db/wal").unwrap().is_dir());
+    assert_eq!(dir.metadata("db/wal/0001").unwrap().len(), 5);
+    assert_eq!(os_error(dir.create_file("a/b")), Some(ENOTDIR));
+    assert_eq!(os_error(dir.open_file("db")), Some(EISDIR));
+
+    // The host only sees files of random names.
+    let files = host_files(&storage);
+    assert_eq!(files.len(), 4);
+    assert!(files
+        .iter()
+        .all(|name| name.file_name().unwrap().len() == 32));
+
+    // The index is kept across opens, and only opened once at a time.
+    assert!(reopen(&storage).is_err());
+    drop(dir);
+    let dir = reopen(&storage).unwrap();
+    assert_eq!(read(&dir, "db/wal/0002").unwrap(), b"wal 2");
+    assert!(dir.exists("db/x").unwrap());
+
+    dir.remove_file("db/x").unwrap();
+    assert!(!dir.exists("db/x").unwrap());
+    assert_eq!(host_files(&storage).len(), 3);
+    assert_eq!(os_error(dir.remove_file("db/x")), Some(ENOENT));
+    assert_eq!(os_error(dir.remove_file("db")), Some(EISDIR));
+
+    drop(dir);
+    let result =
+        ProtectedDir::create_with("dir", EncryptMode::user_key(DIR_KEY), &options(&storage));
+    assert_eq!(os_error(result), Some(EEXIST));
+}
+
+#[test_case]
+fn dir_rename() {
+    let storage = MemoryStorage::new();
+    let dir = create(&storage);
+    write(&dir, "a", b"file a");
+    write(&dir, "b", b"file b");
+    write(&dir, "db/wal/0001", b"wal 1");
+
+    // A file replaces the file it is renamed to, whose host file goes.
+    dir.rename("a", "b").unwrap();
+    assert!(!dir.exists("a").unwrap());
+    assert_eq!(read(&dir, "b").unwrap(), b"file a");
+    assert_eq!(host_files(&storage).len(), 2);
+
+    // A directory moves with everything in it.
+    dir.rename("db", "log/old").unwrap();
+    assert!(!dir.exists("db").unwrap());
+    assert_eq!(read(&dir, "log/old/wal/0001").unwrap(), b"wal 1");
+    assert_eq!(host_files(&storage).len(), 2);
+
+    assert_eq!(os_error(dir.rename("log", "log/old/new")), Some(EINVAL));
+    assert_eq!(os_error(dir.rename("log", "b")), Some(EEXIST));
+    assert_eq!(os_error(dir.rename("b", "log")), Some(EISDIR));
+    assert_eq!(os_error(dir.rename("c", "d")), Some(ENOENT));
+
+    drop(dir);
+    let dir = reopen(&storage).unwrap();
+    assert_eq!(
+        dir.list("").unwrap(),
+        [entry("b", false), entry("log", true)]
+    );
+}
+
+#[test_case]
+fn dir_swapped_host_files() {
+    let storage = MemoryStorage::new();
+    let dir = create(&storage);
+    write(&dir, "a", b"file a");
+    write(&dir, "b", b"file b");
+
+    // Each file is bound to its host name, so the host cannot swap them.
+    let files = host_files(&storage);
+    let first = storage.get(&files[0]).unwrap();
+    let second = storage.get(&files[1]).unwrap();
+    storage.set(&files[0], second).unwrap();
+    storage.set(&files[1], first).unwrap();
+
+    for name in ["a", "b"] {
+        let error = read(&dir, name).unwrap_err();
+        assert!(is_sgx_error(&error, SgxStatus::NameMismatch));
+    }
+}
@@ -16,13 +16,22 @@
 // under the License..
 
 use crate::sys as fs_imp;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
 use sgx_types::types::{Key128bit, Mac128bit};
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::os::unix::fs::FileExt;
 use std::path::Path;
 use std::string::String;
+use std::sync::Arc;
 use std::vec::Vec;
 
+pub use fs_imp::{CacheStats, DEFAULT_CACHE_SIZE, MIN_CACHE_SIZE};
+pub use fs_imp::{
+    CounterOp, CounterRequest, CounterResponse, CounterServer, CounterService, McUuid,
+    MemoryCounter, MonotonicCounter, ServiceCounter, MC_UUID_SIZE,
+};
+pub use fs_imp::{FileStorage, HostStorage, MemoryStorage, RecoveryFile, StorageFile};
+
 cfg_if! {
     if #[cfg(feature = "tfs")] {
         use sgx_rsrvmm::map::Map;
@@ -52,6 +61,13 @@ pub struct EncryptMode(fs_imp::EncryptMode);
 /// that the file contains internally.
 ///
 /// SgxFiles are automatically closed when they go out of scope.
+///
+/// A `SgxFile` is `Send` and `Sync`, so one open file can be shared between
+/// threads. The positional `read_at` and `write_at` neither use nor move the
+/// cursor, which has a lock of its own, so they never wait for the cursor or
+/// for each other's seeks. The node cache and the metadata are guarded by
+/// one lock, though, so the I/O itself is still done one call at a time. To
+/// read in parallel, open the file read-only once per thread.
 pub struct SgxFile {
     inner: fs_imp::SgxFile,
 }
@@ -213,6 +229,18 @@ impl SgxFile {
         self.inner.set_len(size)
     }
 
+    /// Reads from `offset` without moving the cursor.
+    pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.inner.read_at(buf, offset)
+    }
+
+    /// Writes at `offset` without moving the cursor. A file opened for
+    /// appending is written at its end, whatever the offset. Writing past
+    /// the end of the file fills the gap with zeros.
+    pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        self.inner.write_at(buf, offset)
+    }
+
     pub fn tell(&self) -> io::Result<u64> {
         self.inner.tell()
     }
@@ -233,6 +261,11 @@ impl SgxFile {
         self.inner.clear_cache()
     }
 
+    /// Returns the counters of the file's node cache since it was opened.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.inner.cache_stats()
+    }
+
     pub fn get_mac(&self) -> io::Result<Mac128bit> {
         self.inner.get_mac()
     }
@@ -295,11 +328,11 @@ impl Seek for &SgxFile {
 
 impl FileExt for SgxFile {
     fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
-        self.inner.read_at(buf, offset)
+        SgxFile::read_at(self, buf, offset)
     }
 
     fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
-        self.inner.write_at(buf, offset)
+        SgxFile::write_at(self, buf, offset)
     }
 }
 
@@ -324,12 +357,12 @@ impl Map for SgxFile {
 }
 
 pub fn remove<P: AsRef<Path>>(path: P) -> io::Result<()> {
-    fs_imp::remove(path.as_ref())
+    OpenOptions::new().remove(path)
 }
 
 #[cfg(feature = "tfs")]
 pub fn export_key<P: AsRef<Path>>(path: P) -> io::Result<Key128bit> {
-    fs_imp::export_key(path.as_ref())
+    OpenOptions::new().export_key(path)
 }
 
 #[cfg(feature = "tfs")]
@@ -338,7 +371,7 @@ pub fn import_key<P: AsRef<Path>>(
     key: Key128bit,
     key_policy: Option<KeyPolicy>,
 ) -> io::Result<()> {
-    fs_imp::import_key(path.as_ref(), key, key_policy)
+    OpenOptions::new().import_key(path, key, key_policy)
 }
 
 impl OpenOptions {
@@ -377,6 +410,68 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the AEAD used to protect the file nodes, AES-GCM by default.
+    ///
+    /// A file must be reopened with the same cipher it was created with. The
+    /// file records its cipher, and opening it with another one fails with
+    /// EINVAL. A cipher with a larger key, e.g. AES-256-GCM or
+    /// ChaCha20-Poly1305, is keyed by expanding the 128-bit node keys (see
+    /// `FromKey128bit`), so it does not add security over AES-128-GCM.
+    pub fn cipher<C: Aead>(&mut self) -> &mut OpenOptions
+    where
+        C::Key: FromKey128bit,
+    {
+        self.0.cipher::<C>();
+        self
+    }
+
+    /// Binds the file to a monotonic counter, which detects when an older
+    /// version of the file is put back in its place.
+    ///
+    /// A new file gets a counter of its own. An existing file must have been
+    /// created with a counter, and is rejected with SgxStatus::FileRollback if
+    /// it is older than the counter. A bound file opened without its counter
+    /// fails with EACCES, see `skip_rollback_check`.
+    pub fn counter(&mut self, counter: Arc<dyn MonotonicCounter>) -> &mut OpenOptions {
+        self.0.counter(counter);
+        self
+    }
+
+    /// Opens a file bound to a counter without the counter, read-only. The
+    /// file may then be older than the counter.
+    pub fn skip_rollback_check(&mut self, skip_rollback_check: bool) -> &mut OpenOptions {
+        self.0.skip_rollback_check(skip_rollback_check);
+        self
+    }
+
+    /// Keeps the file, and its recovery file, in `storage` instead of on the
+    /// host's file system.
+    pub fn storage(&mut self, storage: Arc<dyn HostStorage>) -> &mut OpenOptions {
+        self.0.storage(storage);
+        self
+    }
+
+    /// Sets the capacity of the node cache in bytes, DEFAULT_CACHE_SIZE by
+    /// default. It must be a multiple of the page size and at least
+    /// MIN_CACHE_SIZE. A `cache_size` passed to `open_with` takes precedence.
+    pub fn cache_size(&mut self, cache_size: usize) -> &mut OpenOptions {
+        self.0.cache_size(cache_size);
+        self
+    }
+
+    /// Reads up to `nodes` data nodes ahead of the cursor when the file is
+    /// read sequentially, limited to half of the node cache. Disabled (0) by
+    /// default.
+    pub fn read_ahead(&mut self, nodes: usize) -> &mut OpenOptions {
+        self.0.read_ahead(nodes);
+        self
+    }
+
+    #[inline]
+    pub(crate) fn host_storage(&self) -> &dyn HostStorage {
+        self.0.host_storage()
+    }
+
     /// Opens a file at `path` with the options specified by `self`.
     #[cfg(feature = "tfs")]
     pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<SgxFile> {
@@ -400,6 +495,46 @@ impl OpenOptions {
         let inner = fs_imp::SgxFile::open(path, &self.0, &encrypt_mode.0, cache_size)?;
         Ok(SgxFile { inner })
     }
+
+    /// Removes the file at `path` from the storage of `self`. Fails with
+    /// EINVAL if `self` has a counter, see `remove_with`.
+    pub fn remove<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs_imp::remove(path.as_ref(), &self.0)
+    }
+
+    /// Removes the file at `path` from the storage of `self`.
+    ///
+    /// With a counter, the file is opened first and the counter it is bound
+    /// to destroyed, so an older copy of the file put back in its place
+    /// cannot be opened with the counter either. The access mode of `self`
+    /// is ignored.
+    pub fn remove_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        encrypt_mode: EncryptMode,
+    ) -> io::Result<()> {
+        fs_imp::remove_with(path.as_ref(), &self.0, &encrypt_mode.0)
+    }
+
+    /// Exports the key of the auto-key file at `path`, with the cipher,
+    /// counter and storage of `self`. The access mode of `self` is ignored.
+    #[cfg(feature = "tfs")]
+    pub fn export_key<P: AsRef<Path>>(&self, path: P) -> io::Result<Key128bit> {
+        fs_imp::export_key(path.as_ref(), &self.0)
+    }
+
+    /// Imports `key` into the file at `path`, which then uses an auto key
+    /// under `key_policy`, MRSIGNER by default. Like `export_key`, it uses the
+    /// cipher, counter and storage of `self`.
+    #[cfg(feature = "tfs")]
+    pub fn import_key<P: AsRef<Path>>(
+        &self,
+        path: P,
+        key: Key128bit,
+        key_policy: Option<KeyPolicy>,
+    ) -> io::Result<()> {
+        fs_imp::import_key(path.as_ref(), &self.0, key, key_policy)
+    }
 }
 
 impl Default for OpenOptions {
@@ -48,10 +48,12 @@ extern crate sgx_urand as sgx_rand;
 #[cfg(feature = "ufs")]
 extern crate sgx_uprotected_fs;
 
+mod dir;
 mod fs;
 #[macro_use]
 mod sys;
 
+pub use dir::*;
 pub use fs::*;
 
 #[cfg(feature = "capi")]
@@ -0,0 +1,124 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use sgx_crypto::aes::gcm::AesGcm;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
+use sgx_types::error::SgxResult;
+use sgx_types::types::{Key128bit, Mac128bit};
+use std::fmt;
+
+type EncryptFn = fn(&Key128bit, &[u8], &[u8], &mut [u8]) -> SgxResult<Mac128bit>;
+type DecryptFn = fn(&Key128bit, &[u8], &[u8], &mut [u8], &Mac128bit) -> SgxResult;
+
+// The AEAD used for the metadata and the mht/data nodes. Every node has its
+// own key, so the nonce is always zero, the same as the SGX SDK layout.
+// Ciphers with larger keys are keyed by expanding the 128-bit node keys.
+#[derive(Clone, Copy)]
+pub struct NodeCipher {
+    id: u16,
+    encrypt: EncryptFn,
+    decrypt: DecryptFn,
+}
+
+impl NodeCipher {
+    pub fn new<C: Aead>() -> NodeCipher
+    where
+        C::Key: FromKey128bit,
+    {
+        NodeCipher {
+            id: if C::ID == <DefaultCipher as Aead>::ID {
+                0
+            } else {
+                C::ID
+            },
+            encrypt: encrypt::<C>,
+            decrypt: decrypt::<C>,
+        }
+    }
+
+    // The id recorded in the metadata node, zero for the default cipher so
+    // that its files keep the SGX SDK layout.
+    #[inline]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    #[inline]
+    pub fn encrypt(
+        &self,
+        key: &Key128bit,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+    ) -> SgxResult<Mac128bit> {
+        (self.encrypt)(key, aad, src, dst)
+    }
+
+    #[inline]
+    pub fn decrypt(
+        &self,
+        key: &Key128bit,
+        aad: &[u8],
+        src: &[u8],
+        dst: &mut [u8],
+        mac: &Mac128bit,
+    ) -> SgxResult {
+        (self.decrypt)(key, aad, src, dst, mac)
+    }
+}
+
+type DefaultCipher = AesGcm<[u8; 0]>;
+
+impl Default for NodeCipher {
+    fn default() -> NodeCipher {
+        NodeCipher::new::<DefaultCipher>()
+    }
+}
+
+impl fmt::Debug for NodeCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeCipher")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+fn encrypt<C: Aead>(key: &Key128bit, aad: &[u8], src: &[u8], dst: &mut [u8]) -> SgxResult<Mac128bit>
+where
+    C::Key: FromKey128bit,
+{
+    let mut key = C::Key::from_key128bit(key)?;
+    let result = C::encrypt_detached(&key, C::zeroed_nonce(), aad, src, dst);
+    key.as_mut().fill(0);
+    result
+}
+
+fn decrypt<C: Aead>(
+    key: &Key128bit,
+    aad: &[u8],
+    src: &[u8],
+    dst: &mut [u8],
+    mac: &Mac128bit,
+) -> SgxResult
+where
+    C::Key: FromKey128bit,
+{
+    let mut key = C::Key::from_key128bit(key)?;
+    let result = C::decrypt_detached(&key, C::zeroed_nonce(), aad, src, dst, mac);
+    key.as_mut().fill(0);
+    result
+}
@@ -0,0 +1,352 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::sys::metadata::{McUuid, MC_UUID_SIZE};
+use sgx_crypto::mac::AesCMac;
+use sgx_rand::{RdRand, Rng};
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{Key128bit, Mac128bit};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+/// A monotonic counter that protected files can be bound to.
+///
+/// A file bound to a counter stores the counter value in its encrypted
+/// metadata. Every flush writes the next value and then increments the
+/// counter, and open rejects a file whose value is older than the counter
+/// with SgxStatus::FileRollback. Removing the file, or opening it with
+/// write, destroys its counter.
+pub trait MonotonicCounter: Send + Sync {
+    /// Creates a counter, returning its id and initial value.
+    fn create(&self) -> SgxResult<(McUuid, u32)>;
+
+    fn read(&self, uuid: &McUuid) -> SgxResult<u32>;
+
+    /// Increments the counter from `value`, returning the new value. Fails
+    /// with SgxStatus::InvalidState if the counter does not hold `value`, so
+    /// an increment that is repeated or replayed has no effect.
+    fn increment(&self, uuid: &McUuid, value: u32) -> SgxResult<u32>;
+
+    fn destroy(&self, uuid: &McUuid) -> SgxResult;
+}
+
+/// Counters kept in enclave memory.
+///
+/// The counters are lost with the enclave, so this only protects files for
+/// the lifetime of one enclave instance. It is meant for tests.
+#[derive(Debug, Default)]
+pub struct MemoryCounter {
+    counters: Mutex<HashMap<McUuid, u32>>,
+}
+
+impl MemoryCounter {
+    pub fn new() -> MemoryCounter {
+        MemoryCounter::default()
+    }
+}
+
+impl MonotonicCounter for MemoryCounter {
+    fn create(&self) -> SgxResult<(McUuid, u32)> {
+        let mut counters = self.counters.lock().map_err(|_| SgxStatus::Unexpected)?;
+        let uuid = loop {
+            let uuid = new_uuid()?;
+            if !counters.contains_key(&uuid) {
+                break uuid;
+            }
+        };
+        counters.insert(uuid, 0);
+        Ok((uuid, 0))
+    }
+
+    fn read(&self, uuid: &McUuid) -> SgxResult<u32> {
+        let counters = self.counters.lock().map_err(|_| SgxStatus::Unexpected)?;
+        counters.get(uuid).copied().ok_or(SgxStatus::McNotFound)
+    }
+
+    fn increment(&self, uuid: &McUuid, value: u32) -> SgxResult<u32> {
+        let mut counters = self.counters.lock().map_err(|_| SgxStatus::Unexpected)?;
+        let counter = counters.get_mut(uuid).ok_or(SgxStatus::McNotFound)?;
+        ensure!(*counter == value, SgxStatus::InvalidState);
+        *counter = value.checked_add(1).ok_or(SgxStatus::McUsedUp)?;
+        Ok(*counter)
+    }
+
+    fn destroy(&self, uuid: &McUuid) -> SgxResult {
+        let mut counters = self.counters.lock().map_err(|_| SgxStatus::Unexpected)?;
+        counters
+            .remove(uuid)
+            .map(|_| ())
+            .ok_or(SgxStatus::McNotFound)
+    }
+}
+
+impl_enum! {
+    #[repr(u8)]
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum CounterOp {
+        Create = 1,
+        Read = 2,
+        Increment = 3,
+        Destroy = 4,
+    }
+}
+
+/// A request to a counter service. The MAC is made with the key shared by
+/// the enclave and the service.
+#[derive(Clone, Copy, Debug)]
+pub struct CounterRequest {
+    pub op: CounterOp,
+    pub uuid: McUuid,
+    /// The value the counter must hold for an increment, 0 otherwise.
+    pub value: u32,
+    pub nonce: [u8; 16],
+    pub mac: Mac128bit,
+}
+
+/// The answer of a counter service, bound to the request by its nonce.
+#[derive(Clone, Copy, Debug)]
+pub struct CounterResponse {
+    pub uuid: McUuid,
+    pub value: u32,
+    pub mac: Mac128bit,
+}
+
+/// The channel to a counter service outside the enclave, e.g. a counter
+/// enclave on the same platform reached through the untrusted runtime.
+///
+/// Requests and responses are authenticated end to end, so the channel
+/// itself does not have to be trusted.
+pub trait CounterService: Send + Sync {
+    fn call(&self, request: &CounterRequest) -> SgxResult<CounterResponse>;
+}
+
+/// A monotonic counter kept by a counter service.
+///
+/// Every request carries a fresh nonce, and a response is only accepted if
+/// its MAC covers that nonce, so an old response cannot be replayed to hide
+/// an increment. An increment names the value it starts from, so a replayed
+/// request cannot advance the counter past the file.
+pub struct ServiceCounter<S: CounterService> {
+    service: S,
+    key: Key128bit,
+}
+
+impl<S: CounterService> ServiceCounter<S> {
+    pub fn new(service: S, key: Key128bit) -> ServiceCounter<S> {
+        ServiceCounter { service, key }
+    }
+
+    fn call(&self, op: CounterOp, uuid: &McUuid, value: u32) -> SgxResult<(McUuid, u32)> {
+        let mut nonce = [0_u8; 16];
+        RdRand::new()
+            .map_err(|_| SgxStatus::Unexpected)?
+            .fill_bytes(&mut nonce);
+
+        let request = CounterRequest {
+            op,
+            uuid: *uuid,
+            value,
+            nonce,
+            mac: request_mac(&self.key, op, uuid, value, &nonce)?,
+        };
+        let response = self.service.call(&request)?;
+
+        let mac = response_mac(&self.key, &request, &response.uuid, response.value)?;
+        ensure!(mac.ct_eq(&response.mac), SgxStatus::MacMismatch);
+        ensure!(
+            op == CounterOp::Create || response.uuid == *uuid,
+            SgxStatus::MacMismatch
+        );
+        Ok((response.uuid, response.value))
+    }
+}
+
+impl<S: CounterService> MonotonicCounter for ServiceCounter<S> {
+    #[inline]
+    fn create(&self) -> SgxResult<(McUuid, u32)> {
+        self.call(CounterOp::Create, &McUuid::default(), 0)
+    }
+
+    #[inline]
+    fn read(&self, uuid: &McUuid) -> SgxResult<u32> {
+        self.call(CounterOp::Read, uuid, 0).map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn increment(&self, uuid: &McUuid, value: u32) -> SgxResult<u32> {
+        self.call(CounterOp::Increment, uuid, value)
+            .map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn destroy(&self, uuid: &McUuid) -> SgxResult {
+        self.call(CounterOp::Destroy, uuid, 0).map(|_| ())
+    }
+}
+
+impl<S: CounterService> fmt::Debug for ServiceCounter<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceCounter").finish_non_exhaustive()
+    }
+}
+
+impl<S: CounterService> Drop for ServiceCounter<S> {
+    fn drop(&mut self) {
+        self.key.fill(0);
+    }
+}
+
+/// The service side of `ServiceCounter`: checks each request and answers it
+/// from a set of counters.
+///
+/// It runs where the counters are kept, e.g. in a counter enclave that
+/// persists them with its own rollback protection.
+pub struct CounterServer<C: MonotonicCounter = MemoryCounter> {
+    counters: C,
+    key: Key128bit,
+}
+
+impl CounterServer {
+    pub fn new(key: Key128bit) -> CounterServer {
+        CounterServer::with_counters(MemoryCounter::new(), key)
+    }
+}
+
+impl<C: MonotonicCounter> CounterServer<C> {
+    pub fn with_counters(counters: C, key: Key128bit) -> CounterServer<C> {
+        CounterServer { counters, key }
+    }
+
+    pub fn handle(&self, request: &CounterRequest) -> SgxResult<CounterResponse> {
+        let mac = request_mac(
+            &self.key,
+            request.op,
+            &request.uuid,
+            request.value,
+            &request.nonce,
+        )?;
+        ensure!(mac.ct_eq(&request.mac), SgxStatus::MacMismatch);
+
+        let (uuid, value) = match request.op {
+            CounterOp::Create => self.counters.create()?,
+            CounterOp::Read => (request.uuid, self.counters.read(&request.uuid)?),
+            CounterOp::Increment => (
+                request.uuid,
+                self.counters.increment(&request.uuid, request.value)?,
+            ),
+            CounterOp::Destroy => {
+                self.counters.destroy(&request.uuid)?;
+                (request.uuid, 0)
+            }
+        };
+        Ok(CounterResponse {
+            uuid,
+            value,
+            mac: response_mac(&self.key, request, &uuid, value)?,
+        })
+    }
+}
+
+impl<C: MonotonicCounter> CounterService for CounterServer<C> {
+    #[inline]
+    fn call(&self, request: &CounterRequest) -> SgxResult<CounterResponse> {
+        self.handle(request)
+    }
+}
+
+impl<C: MonotonicCounter> fmt::Debug for CounterServer<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CounterServer").finish_non_exhaustive()
+    }
+}
+
+impl<C: MonotonicCounter> Drop for CounterServer<C> {
+    fn drop(&mut self) {
+        self.key.fill(0);
+    }
+}
+
+// The counter a file is opened with.
+#[derive(Clone)]
+pub struct FileCounter(Arc<dyn MonotonicCounter>);
+
+impl FileCounter {
+    #[inline]
+    pub fn new(counter: Arc<dyn MonotonicCounter>) -> FileCounter {
+        FileCounter(counter)
+    }
+}
+
+impl std::ops::Deref for FileCounter {
+    type Target = dyn MonotonicCounter;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl fmt::Debug for FileCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileCounter").finish_non_exhaustive()
+    }
+}
+
+fn new_uuid() -> SgxResult<McUuid> {
+    let mut uuid = [0_u8; MC_UUID_SIZE];
+    let mut rng = RdRand::new().map_err(|_| SgxStatus::Unexpected)?;
+    while uuid == [0_u8; MC_UUID_SIZE] {
+        rng.fill_bytes(&mut uuid);
+    }
+    Ok(McUuid::new(uuid))
+}
+
+fn request_mac(
+    key: &Key128bit,
+    op: CounterOp,
+    uuid: &McUuid,
+    value: u32,
+    nonce: &[u8; 16],
+) -> SgxResult<Mac128bit> {
+    let mut cmac = AesCMac::new(key)?;
+    cmac.update(b"request".as_slice())?;
+    cmac.update(&[op as u8])?;
+    cmac.update(uuid.as_ref())?;
+    cmac.update(&value.to_le_bytes())?;
+    cmac.update(nonce)?;
+    cmac.finalize()
+}
+
+fn response_mac(
+    key: &Key128bit,
+    request: &CounterRequest,
+    uuid: &McUuid,
+    value: u32,
+) -> SgxResult<Mac128bit> {
+    let mut cmac = AesCMac::new(key)?;
+    cmac.update(b"response".as_slice())?;
+    cmac.update(&[request.op as u8])?;
+    cmac.update(&request.nonce)?;
+    cmac.update(uuid.as_ref())?;
+    cmac.update(&value.to_le_bytes())?;
+    cmac.finalize()
+}
@@ -0,0 +1,224 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{
+    CounterOp, CounterRequest, CounterResponse, CounterServer, CounterService, MemoryCounter,
+    MonotonicCounter, ServiceCounter,
+};
+use crate::fs::{EncryptMode, MemoryStorage, OpenOptions};
+use sgx_test_utils::test_case;
+use sgx_types::error::errno::EACCES;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::Key128bit;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+const FILE_KEY: Key128bit = [0x11; 16];
+const SERVICE_KEY: Key128bit = [0x22; 16];
+
+// Keeps the requests it passes on, to replay them.
+struct Recorder {
+    server: CounterServer,
+    requests: Mutex<Vec<CounterRequest>>,
+}
+
+impl CounterService for Recorder {
+    fn call(&self, request: &CounterRequest) -> SgxResult<CounterResponse> {
+        self.requests.lock().unwrap().push(*request);
+        self.server.handle(request)
+    }
+}
+
+#[cfg(feature = "tfs")]
+fn is_sgx_error(error: &io::Error, status: SgxStatus) -> bool {
+    error.raw_sgx_error() == Some(status)
+}
+
+#[cfg(feature = "ufs")]
+fn is_sgx_error(error: &io::Error, status: SgxStatus) -> bool {
+    error.to_string() == status.as_str()
+}
+
+fn options(storage: &MemoryStorage, counter: Option<&Arc<dyn MonotonicCounter>>) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.storage(Arc::new(storage.clone()));
+    if let Some(counter) = counter {
+        opts.counter(counter.clone());
+    }
+    opts
+}
+
+fn read(opts: &OpenOptions, path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = opts.clone().read(true).open_with_key(path, FILE_KEY)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn append(opts: &OpenOptions, path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut file = opts.clone().append(true).open_with_key(path, FILE_KEY)?;
+    file.write_all(data)?;
+    file.flush()
+}
+
+#[test_case]
+fn counter_increment_from_value() {
+    let counter = MemoryCounter::new();
+    let (uuid, value) = counter.create().unwrap();
+
+    assert_eq!(counter.increment(&uuid, value), Ok(value + 1));
+    assert_eq!(
+        counter.increment(&uuid, value),
+        Err(SgxStatus::InvalidState)
+    );
+    assert_eq!(counter.read(&uuid), Ok(value + 1));
+
+    counter.destroy(&uuid).unwrap();
+    assert_eq!(counter.read(&uuid), Err(SgxStatus::McNotFound));
+    assert_eq!(
+        counter.increment(&uuid, value + 1),
+        Err(SgxStatus::McNotFound)
+    );
+}
+
+#[test_case]
+fn service_counter_replay() {
+    let counter = ServiceCounter::new(
+        Recorder {
+            server: CounterServer::new(SERVICE_KEY),
+            requests: Mutex::new(Vec::new()),
+        },
+        SERVICE_KEY,
+    );
+    let (uuid, value) = counter.create().unwrap();
+    assert_eq!(counter.increment(&uuid, value), Ok(value + 1));
+
+    // A replayed increment does not advance the counter again.
+    let request = *counter.service.requests.lock().unwrap().last().unwrap();
+    assert_eq!(request.op, CounterOp::Increment);
+    let server = &counter.service.server;
+    assert_eq!(
+        server.handle(&request).unwrap_err(),
+        SgxStatus::InvalidState
+    );
+    assert_eq!(counter.read(&uuid), Ok(value + 1));
+
+    // The expected value is covered by the MAC.
+    let mut request = request;
+    request.value += 1;
+    assert_eq!(server.handle(&request).unwrap_err(), SgxStatus::MacMismatch);
+    assert_eq!(counter.read(&uuid), Ok(value + 1));
+
+    // So is every request.
+    let counter = ServiceCounter::new(CounterServer::new(SERVICE_KEY), [0x33; 16]);
+    assert_eq!(counter.create().unwrap_err(), SgxStatus::MacMismatch);
+}
+
+#[test_case]
+fn counter_rollback() {
+    let storage = MemoryStorage::new();
+    let counter: Arc<dyn MonotonicCounter> = Arc::new(MemoryCounter::new());
+    let path = Path::new("file");
+
+    append(&options(&storage, Some(&counter)), path, b"old").unwrap();
+    let old = storage.get(path).unwrap();
+    append(&options(&storage, Some(&counter)), path, b" new").unwrap();
+    let new = storage.get(path).unwrap();
+
+    // The current file is accepted.
+    assert_eq!(
+        read(&options(&storage, Some(&counter)), path).unwrap(),
+        b"old new"
+    );
+
+    // The stale one is not.
+    storage.set(path, old).unwrap();
+    let error = read(&options(&storage, Some(&counter)), path).unwrap_err();
+    assert!(is_sgx_error(&error, SgxStatus::FileRollback), "{:?}", error);
+    let error = append(&options(&storage, Some(&counter)), path, b"!").unwrap_err();
+    assert!(is_sgx_error(&error, SgxStatus::FileRollback), "{:?}", error);
+
+    // Nor without the counter, unless the check is skipped, and then only
+    // for reading.
+    let error = read(&options(&storage, None), path).unwrap_err();
+    assert_eq!(error.raw_os_error(), Some(EACCES));
+    let mut opts = options(&storage, None);
+    opts.skip_rollback_check(true);
+    assert_eq!(read(&opts, path).unwrap(), b"old");
+    assert!(append(&opts, path, b"!").is_err());
+
+    storage.set(path, new).unwrap();
+    assert_eq!(
+        read(&options(&storage, Some(&counter)), path).unwrap(),
+        b"old new"
+    );
+}
+
+#[test_case]
+fn counter_destroyed_on_remove() {
+    let storage = MemoryStorage::new();
+    let counter: Arc<dyn MonotonicCounter> = Arc::new(MemoryCounter::new());
+    let path = Path::new("file");
+
+    append(&options(&storage, Some(&counter)), path, b"data").unwrap();
+    let data = storage.get(path).unwrap();
+
+    let opts = options(&storage, Some(&counter));
+    opts.remove_with(path, EncryptMode::user_key(FILE_KEY))
+        .unwrap();
+    assert!(storage.get(path).is_none());
+
+    // The file put back has no counter any more.
+    storage.set(path, data).unwrap();
+    let error = read(&options(&storage, Some(&counter)), path).unwrap_err();
+    assert!(is_sgx_error(&error, SgxStatus::McNotFound), "{:?}", error);
+}
+
+#[test_case]
+fn counter_destroyed_on_truncate() {
+    let storage = MemoryStorage::new();
+    let memory = Arc::new(MemoryCounter::new());
+    let counter: Arc<dyn MonotonicCounter> = memory.clone();
+    let path = Path::new("file");
+
+    append(&options(&storage, Some(&counter)), path, b"old").unwrap();
+    let data = storage.get(path).unwrap();
+    assert_eq!(memory.counters.lock().unwrap().len(), 1);
+
+    // Opening for write recreates the file with a new counter.
+    for _ in 0..3 {
+        let opts = options(&storage, Some(&counter));
+        let mut file = opts
+            .clone()
+            .write(true)
+            .open_with_key(path, FILE_KEY)
+            .unwrap();
+        file.write_all(b"new").unwrap();
+        file.flush().unwrap();
+    }
+    assert_eq!(memory.counters.lock().unwrap().len(), 1);
+    assert_eq!(
+        read(&options(&storage, Some(&counter)), path).unwrap(),
+        b"new"
+    );
+
+    storage.set(path, data).unwrap();
+    let error = read(&options(&storage, Some(&counter)), path).unwrap_err();
+    assert!(is_sgx_error(&error, SgxStatus::McNotFound), "{:?}", error);
+}
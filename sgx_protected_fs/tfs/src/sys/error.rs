@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use sgx_types::error::errno::EIO;
 use sgx_types::error::SgxStatus;
 use std::error::Error;
 use std::fmt;
@@ -76,6 +77,20 @@ impl FsError {
         }
     }
 
+    #[cfg(feature = "tfs")]
+    pub fn from_io_error(error: IoError) -> Self {
+        match (error.raw_sgx_error(), error.raw_os_error()) {
+            (Some(status), _) => Self::SgxError(status),
+            (None, Some(errno)) => Self::OsError(errno),
+            (None, None) => Self::OsError(EIO),
+        }
+    }
+
+    #[cfg(feature = "ufs")]
+    pub fn from_io_error(error: IoError) -> Self {
+        Self::OsError(error.raw_os_error().unwrap_or(EIO))
+    }
+
     pub fn set_errno(&self) {
         extern "C" {
             #[cfg_attr(target_os = "linux", link_name = "__errno_location")]
@@ -112,6 +127,13 @@ impl From<SgxStatus> for FsError {
     }
 }
 
+impl From<IoError> for FsError {
+    #[inline]
+    fn from(error: IoError) -> FsError {
+        FsError::from_io_error(error)
+    }
+}
+
 impl From<i32> for FsError {
     #[inline]
     fn from(code: i32) -> FsError {
@@ -17,7 +17,6 @@
 
 use crate::sys::error::FsResult;
 use crate::sys::file::{CloseMode, FileInner, FileStatus};
-use crate::sys::host;
 use sgx_types::error::SgxStatus;
 use sgx_types::types::Key128bit;
 
@@ -57,7 +56,7 @@ impl FileInner {
 
     fn remove_recovery_file(&mut self) {
         if !self.recovery_path.as_path().as_os_str().is_empty() {
-            let _ = host::remove(&self.recovery_path);
+            let _ = self.opts.host_storage().remove(&self.recovery_path);
         }
     }
 }
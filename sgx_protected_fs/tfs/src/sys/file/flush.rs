@@ -17,7 +17,7 @@
 
 use crate::sys::error::FsResult;
 use crate::sys::file::{FileInner, FileStatus};
-use crate::sys::host::{self, HostFs, RecoveryFile};
+use crate::sys::host::{HostFs, HostRecoveryFile};
 use crate::sys::metadata::MD_USER_DATA_SIZE;
 use crate::sys::node::FileNodeRef;
 use sgx_types::error::SgxStatus;
@@ -39,6 +39,11 @@ impl FileInner {
             return Ok(());
         }
 
+        let mc_value = self.next_counter_value().map_err(|error| {
+            self.set_file_status(FileStatus::FlushError);
+            error
+        })?;
+
         if self.is_need_write_node() {
             self.write_recovery_file().map_err(|error| {
                 self.set_file_status(FileStatus::FlushError);
@@ -68,6 +73,11 @@ impl FileInner {
             error
         })?;
 
+        self.increment_counter(mc_value).map_err(|error| {
+            self.set_file_status(FileStatus::FlushError);
+            error
+        })?;
+
         self.need_writing = false;
         Ok(())
     }
@@ -83,10 +93,13 @@ impl FileInner {
                 }
             }) {
                 node.write_to_disk(&mut self.host_file)?;
+                self.stats.dirty_flushes += 1;
+            }
+            let mut root_mht = self.root_mht.borrow_mut();
+            if root_mht.need_writing {
+                root_mht.write_to_disk(&mut self.host_file)?;
+                self.stats.dirty_flushes += 1;
             }
-            self.root_mht
-                .borrow_mut()
-                .write_to_disk(&mut self.host_file)?;
         }
 
         self.metadata.write_to_disk(&mut self.host_file)?;
@@ -101,6 +114,7 @@ impl FileInner {
         // 1. encrypt the changed data
         // 2. set the KEY+GMAC in the parent MHT
         // 3. set the need_writing flag for all the parents
+        let cipher = self.opts.cipher;
         for mut data_node in self.cache.iter().filter_map(|node| {
             let node = node.borrow_mut();
             if node.is_data() && node.need_writing {
@@ -110,7 +124,7 @@ impl FileInner {
             }
         }) {
             let key = data_node.derive_key(&mut self.key_gen)?;
-            data_node.encrypt(&key)?;
+            data_node.encrypt(&cipher, &key)?;
 
             let mut parent = data_node.parent.clone();
             while let Some(mht) = parent {
@@ -143,13 +157,13 @@ impl FileInner {
 
         for mut mht_node in mht_nodes.iter().map(|node| node.borrow_mut()) {
             let key = mht_node.derive_key(&mut self.key_gen)?;
-            mht_node.encrypt(&key)?;
+            mht_node.encrypt(&cipher, &key)?;
         }
 
         // update mht root gmac in the meta data node
         let mut root_mht = self.root_mht.borrow_mut();
         let key = root_mht.derive_key(&mut self.key_gen)?;
-        let mac = root_mht.encrypt(&key)?;
+        let mac = root_mht.encrypt(&cipher, &key)?;
 
         self.metadata.encrypted_plain.mht_key = key;
         self.metadata.encrypted_plain.mht_gmac = mac;
@@ -160,7 +174,37 @@ impl FileInner {
     #[inline]
     fn update_metadata(&mut self) -> FsResult {
         let key = self.metadata.derive_key(&mut self.key_gen)?;
-        self.metadata.encrypt(&key)
+        self.metadata.encrypt(&self.opts.cipher, &key)
+    }
+
+    // The metadata of a file bound to a counter is written with the next
+    // counter value, and the counter is only incremented once it is on disk.
+    fn next_counter_value(&mut self) -> FsResult<Option<u32>> {
+        let counter = match self.opts.counter {
+            Some(ref counter) => counter,
+            None => return Ok(None),
+        };
+
+        let mc_uuid = self.metadata.encrypted_plain.mc_uuid;
+        let value = counter
+            .read(&mc_uuid)?
+            .checked_add(1)
+            .ok_or(SgxStatus::McUsedUp)?;
+        self.metadata.encrypted_plain.mc_value = value;
+        Ok(Some(value))
+    }
+
+    fn increment_counter(&mut self, mc_value: Option<u32>) -> FsResult {
+        if let (Some(counter), Some(mc_value)) = (&self.opts.counter, mc_value) {
+            let mc_uuid = self.metadata.encrypted_plain.mc_uuid;
+            // Another writer got there first.
+            let value = match counter.increment(&mc_uuid, mc_value - 1) {
+                Err(SgxStatus::InvalidState) => bail!(esgx!(SgxStatus::FileRollback)),
+                result => result?,
+            };
+            ensure!(value == mc_value, esgx!(SgxStatus::FileRollback));
+        }
+        Ok(())
     }
 
     #[inline]
@@ -191,7 +235,7 @@ impl FileInner {
     }
 
     fn write_recovery_file_node(&mut self) -> FsResult {
-        let mut file = RecoveryFile::open(&self.recovery_path)?;
+        let mut file = HostRecoveryFile::open(self.opts.host_storage(), &self.recovery_path)?;
 
         for node in self.cache.iter().filter_map(|node| {
             let node = node.borrow();
@@ -215,7 +259,7 @@ impl FileInner {
     #[inline]
     fn write_recovery_file(&mut self) -> FsResult {
         self.write_recovery_file_node().map_err(|error| {
-            let _ = host::remove(&self.recovery_path);
+            let _ = self.opts.host_storage().remove(&self.recovery_path);
             error
         })
     }
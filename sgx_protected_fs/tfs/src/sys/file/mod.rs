@@ -16,21 +16,24 @@
 // under the License..
 
 use crate::sys::cache::LruCache;
+use crate::sys::cipher::NodeCipher;
+use crate::sys::counter::FileCounter;
 use crate::sys::error::{FsError, FsResult};
-use crate::sys::host::HostFile;
+use crate::sys::host::{FileStorage, HostFile, HostStorage, Storage};
 use crate::sys::keys::FsKeyGen;
 use crate::sys::metadata::MetadataInfo;
 use crate::sys::node::{FileNode, FileNodeRef};
 use crate::sys::EncryptMode;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
 use sgx_types::error::errno::*;
 use sgx_types::error::SgxStatus;
 use sgx_types::types::{Key128bit, KeyPolicy, Mac128bit};
 use std::io::SeekFrom;
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 
-pub use open::DEFAULT_CACHE_SIZE;
+pub use open::{DEFAULT_CACHE_SIZE, MIN_CACHE_SIZE};
 
 mod close;
 mod flush;
@@ -40,11 +43,25 @@ mod other;
 mod read;
 mod write;
 
+#[cfg(feature = "unit_test")]
+mod tests;
+
+// The cursor has a lock of its own, so positional I/O never waits for it.
+// The node cache and the metadata are guarded together by the lock of the
+// file: a cache miss may write dirty nodes back, which changes both. Locks
+// are taken cursor first.
 #[derive(Debug)]
 pub struct ProtectedFile {
+    cursor: Mutex<Cursor>,
     file: Mutex<FileInner>,
 }
 
+#[derive(Debug)]
+struct Cursor {
+    offset: usize,
+    end_of_file: bool,
+}
+
 #[derive(Debug)]
 struct FileInner {
     host_file: HostFile,
@@ -53,15 +70,22 @@ struct FileInner {
     key_gen: FsKeyGen,
     opts: OpenOptions,
     need_writing: bool,
-    end_of_file: bool,
     max_cache_page: usize,
+    // the position of the I/O in progress, the cursor is kept apart
     offset: usize,
+    read_end: usize,
     last_error: FsError,
     status: FileStatus,
     recovery_path: PathBuf,
     cache: LruCache<FileNode>,
+    stats: CacheStats,
 }
 
+// The nodes are shared through `Arc<RefCell<_>>`s, but every one of them is
+// owned by the file and only reached through `ProtectedFile::file`, so moving
+// the whole file to another thread is sound. This makes `ProtectedFile` Send and Sync.
+unsafe impl Send for FileInner {}
+
 impl ProtectedFile {
     pub fn open<P: AsRef<Path>>(
         path: P,
@@ -70,19 +94,29 @@ impl ProtectedFile {
         cache_size: Option<usize>,
     ) -> FsResult<Self> {
         let file = FileInner::open(path.as_ref(), opts, mode, cache_size)?;
+        let cursor = Cursor {
+            offset: if opts.append && !opts.update {
+                file.metadata.encrypted_plain.size
+            } else {
+                0
+            },
+            end_of_file: false,
+        };
         Ok(Self {
+            cursor: Mutex::new(cursor),
             file: Mutex::new(file),
         })
     }
 
     pub fn write(&self, buf: &[u8]) -> FsResult<usize> {
+        let mut cursor = self.lock_cursor()?;
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
             file.set_last_error(SgxStatus::Unexpected);
             file.set_file_status(FileStatus::MemoryCorrupted);
             SgxStatus::Unexpected
         })?;
-        file.write(buf).map_err(|error| {
+        file.write(buf, &mut cursor).map_err(|error| {
             file.set_last_error(error);
             error
         })
@@ -102,13 +136,14 @@ impl ProtectedFile {
     }
 
     pub fn read(&self, buf: &mut [u8]) -> FsResult<usize> {
+        let mut cursor = self.lock_cursor()?;
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
             file.set_last_error(SgxStatus::Unexpected);
             file.set_file_status(FileStatus::MemoryCorrupted);
             SgxStatus::Unexpected
         })?;
-        file.read(buf).map_err(|error| {
+        file.read(buf, &mut cursor).map_err(|error| {
             file.set_last_error(error);
             error
         })
@@ -128,39 +163,42 @@ impl ProtectedFile {
     }
 
     pub fn tell(&self) -> FsResult<u64> {
+        let cursor = self.lock_cursor()?;
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
             file.set_last_error(SgxStatus::Unexpected);
             file.set_file_status(FileStatus::MemoryCorrupted);
             SgxStatus::Unexpected
         })?;
-        file.tell().map_err(|error| {
+        file.tell(&cursor).map_err(|error| {
             file.set_last_error(error);
             error
         })
     }
 
     pub fn seek(&self, pos: SeekFrom) -> FsResult<u64> {
+        let mut cursor = self.lock_cursor()?;
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
             file.set_last_error(SgxStatus::Unexpected);
             file.set_file_status(FileStatus::MemoryCorrupted);
             SgxStatus::Unexpected
         })?;
-        file.seek(pos).map_err(|error| {
+        file.seek(pos, &mut cursor).map_err(|error| {
             file.set_last_error(error);
             error
         })
     }
 
     pub fn set_len(&self, size: u64) -> FsResult {
+        let mut cursor = self.lock_cursor()?;
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
             file.set_last_error(SgxStatus::Unexpected);
             file.set_file_status(FileStatus::MemoryCorrupted);
             SgxStatus::Unexpected
         })?;
-        file.set_len(size).map_err(|error| {
+        file.set_len(size, &mut cursor).map_err(|error| {
             file.set_last_error(error);
             error
         })
@@ -188,11 +226,11 @@ impl ProtectedFile {
     }
 
     pub fn get_eof(&self) -> bool {
-        let file = self
-            .file
+        let cursor = self
+            .cursor
             .lock()
             .unwrap_or_else(|posion_error| posion_error.into_inner());
-        file.get_eof()
+        cursor.end_of_file
     }
 
     pub fn get_error(&self) -> FsError {
@@ -203,6 +241,14 @@ impl ProtectedFile {
         file.get_last_error()
     }
 
+    pub fn cache_stats(&self) -> CacheStats {
+        let file = self
+            .file
+            .lock()
+            .unwrap_or_else(|posion_error| posion_error.into_inner());
+        file.stats
+    }
+
     pub fn clear_cache(&self) -> FsResult {
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
@@ -217,6 +263,7 @@ impl ProtectedFile {
     }
 
     pub fn clear_error(&self) -> FsResult {
+        let mut cursor = self.lock_cursor()?;
         let mut file = self.file.lock().map_err(|posion_error| {
             let mut file = posion_error.into_inner();
             file.set_last_error(SgxStatus::Unexpected);
@@ -226,7 +273,9 @@ impl ProtectedFile {
         file.clear_error().map_err(|error| {
             file.set_last_error(error);
             error
-        })
+        })?;
+        cursor.end_of_file = false;
+        Ok(())
     }
 
     pub fn get_metadata_mac(&self) -> FsResult<Mac128bit> {
@@ -266,15 +315,23 @@ impl ProtectedFile {
             })
     }
 
-    pub fn remove<P: AsRef<Path>>(path: P) -> FsResult {
-        FileInner::remove(path.as_ref())
+    fn lock_cursor(&self) -> FsResult<MutexGuard<'_, Cursor>> {
+        self.cursor.lock().map_err(|_| esgx!(SgxStatus::Unexpected))
+    }
+
+    pub fn remove<P: AsRef<Path>>(path: P, opts: &OpenOptions) -> FsResult {
+        FileInner::remove(path.as_ref(), opts)
+    }
+
+    pub fn remove_with<P: AsRef<Path>>(path: P, opts: &OpenOptions, mode: &OpenMode) -> FsResult {
+        FileInner::remove_with(path.as_ref(), opts, mode)
     }
 
     #[cfg(feature = "tfs")]
-    pub fn export_key<P: AsRef<Path>>(path: P) -> FsResult<Key128bit> {
+    pub fn export_key<P: AsRef<Path>>(path: P, opts: &OpenOptions) -> FsResult<Key128bit> {
         let mut file = FileInner::open(
             path.as_ref(),
-            &OpenOptions::new().read(true),
+            &opts.with_access(true, false),
             &OpenMode::ExportKey,
             None,
         )?;
@@ -284,12 +341,13 @@ impl ProtectedFile {
     #[cfg(feature = "tfs")]
     pub fn import_key<P: AsRef<Path>>(
         path: P,
+        opts: &OpenOptions,
         key: Key128bit,
         key_policy: Option<KeyPolicy>,
     ) -> FsResult {
         let mut file = FileInner::open(
             path.as_ref(),
-            &OpenOptions::new().read(true).update(true),
+            &opts.with_access(true, true),
             &OpenMode::ImportKey((key, key_policy.unwrap_or(KeyPolicy::MRSIGNER))),
             None,
         )?;
@@ -297,6 +355,21 @@ impl ProtectedFile {
     }
 }
 
+/// Counters of the node cache of one open file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    /// Node lookups served from the cache.
+    pub hits: u64,
+    /// Nodes read from the host and decrypted, including read-ahead.
+    pub misses: u64,
+    /// Clean nodes dropped to keep the cache within its capacity.
+    pub evictions: u64,
+    /// Dirty nodes written back to the host.
+    pub dirty_flushes: u64,
+    /// Plaintext bytes of the nodes decrypted.
+    pub bytes_decrypted: u64,
+}
+
 #[allow(dead_code)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum FileStatus {
@@ -324,13 +397,19 @@ impl Default for FileStatus {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct OpenOptions {
     pub read: bool,
     pub write: bool,
     pub append: bool,
     pub binary: bool,
     pub update: bool,
+    pub cipher: NodeCipher,
+    pub counter: Option<FileCounter>,
+    pub skip_rollback_check: bool,
+    pub storage: Option<Storage>,
+    pub cache_size: Option<usize>,
+    pub read_ahead: usize,
 }
 
 #[allow(dead_code)]
@@ -342,6 +421,12 @@ impl OpenOptions {
             append: false,
             binary: false,
             update: false,
+            cipher: NodeCipher::default(),
+            counter: None,
+            skip_rollback_check: false,
+            storage: None,
+            cache_size: None,
+            read_ahead: 0,
         }
     }
 
@@ -371,6 +456,57 @@ impl OpenOptions {
         self
     }
     #[inline]
+    pub fn cipher<C: Aead>(mut self) -> Self
+    where
+        C::Key: FromKey128bit,
+    {
+        self.cipher = NodeCipher::new::<C>();
+        self
+    }
+    #[inline]
+    pub fn counter(mut self, counter: Option<FileCounter>) -> Self {
+        self.counter = counter;
+        self
+    }
+    #[inline]
+    pub fn skip_rollback_check(mut self, skip_rollback_check: bool) -> Self {
+        self.skip_rollback_check = skip_rollback_check;
+        self
+    }
+    #[inline]
+    pub fn storage(mut self, storage: Option<Storage>) -> Self {
+        self.storage = storage;
+        self
+    }
+    #[inline]
+    pub fn cache_size(mut self, cache_size: Option<usize>) -> Self {
+        self.cache_size = cache_size;
+        self
+    }
+    #[inline]
+    pub fn read_ahead(mut self, read_ahead: usize) -> Self {
+        self.read_ahead = read_ahead;
+        self
+    }
+    #[inline]
+    pub fn host_storage(&self) -> &dyn HostStorage {
+        match self.storage {
+            Some(ref storage) => &**storage,
+            None => &FileStorage,
+        }
+    }
+    // The options with only read, and update, access.
+    #[inline]
+    pub fn with_access(&self, read: bool, update: bool) -> OpenOptions {
+        OpenOptions {
+            read,
+            write: false,
+            append: false,
+            update,
+            ..self.clone()
+        }
+    }
+    #[inline]
     pub fn readonly(&self) -> bool {
         self.read && !self.update
     }
@@ -99,6 +99,7 @@ impl FileInner {
         let physical_number = 1 + logic_number * (ATTACHED_DATA_NODES_COUNT + 1);
 
         if let Some(mht_node) = self.cache.find(physical_number) {
+            self.stats.hits += 1;
             return Ok(mht_node);
         }
 
@@ -114,7 +115,10 @@ impl FileInner {
         mht_node.read_from_disk(&mut self.host_file)?;
 
         let gcm_data = mht_node.get_gcm_data().ok_or(SgxStatus::Unexpected)?;
-        mht_node.decrypt(&gcm_data.key, &gcm_data.mac)?;
+        mht_node.decrypt(&self.opts.cipher, &gcm_data.key, &gcm_data.mac)?;
+        mht_node.new_node = false;
+        self.stats.misses += 1;
+        self.stats.bytes_decrypted += NODE_SIZE as u64;
 
         let mht_node = FileNode::build_ref(mht_node);
         ensure!(
@@ -148,6 +152,7 @@ impl FileInner {
         let (logic_number, physical_number) = self.get_data_node_numbers();
 
         if let Some(data_node) = self.cache.find(physical_number) {
+            self.stats.hits += 1;
             return Ok(data_node);
         }
 
@@ -163,7 +168,10 @@ impl FileInner {
         data_node.read_from_disk(&mut self.host_file)?;
 
         let gcm_data = data_node.get_gcm_data().ok_or(SgxStatus::Unexpected)?;
-        data_node.decrypt(&gcm_data.key, &gcm_data.mac)?;
+        data_node.decrypt(&self.opts.cipher, &gcm_data.key, &gcm_data.mac)?;
+        data_node.new_node = false;
+        self.stats.misses += 1;
+        self.stats.bytes_decrypted += NODE_SIZE as u64;
 
         let data_node = FileNode::build_ref(data_node);
         ensure!(
@@ -191,6 +199,7 @@ impl FileInner {
             let node = self.cache.back().ok_or(SgxStatus::Unexpected)?;
             if !node.borrow().need_writing {
                 let _node = self.cache.pop_back();
+                self.stats.evictions += 1;
             } else {
                 self.internal_flush(false).map_err(|error| {
                     if self.status.is_ok() {
@@ -203,6 +212,47 @@ impl FileInner {
         Ok(())
     }
 
+    // Loads the data nodes following the cursor that are not cached yet.
+    // Read-ahead takes at most half of the cache, so it does not evict the
+    // nodes it has just read.
+    pub fn read_ahead(&mut self) -> FsResult {
+        let count = self.opts.read_ahead.min(self.max_cache_page / 2);
+        let file_size = self.metadata.encrypted_plain.size;
+        let offset = self.offset;
+
+        // the node holding the cursor has just been read, unless the cursor is at its start
+        let mut node_offset = if offset > MD_USER_DATA_SIZE {
+            let offset_in_node = (offset - MD_USER_DATA_SIZE) % NODE_SIZE;
+            if offset_in_node != 0 {
+                offset + NODE_SIZE - offset_in_node
+            } else {
+                offset
+            }
+        } else {
+            MD_USER_DATA_SIZE
+        };
+
+        let mut result = Ok(());
+        for _ in 0..count {
+            if node_offset >= file_size {
+                break;
+            }
+
+            self.offset = node_offset;
+            let (_, physical_number) = self.get_data_node_numbers();
+            if self.cache.find(physical_number).is_none() {
+                if let Err(error) = self.get_data_node() {
+                    result = Err(error);
+                    break;
+                }
+            }
+            node_offset += NODE_SIZE;
+        }
+
+        self.offset = offset;
+        result
+    }
+
     fn get_node_numbers(&self) -> (u64, u64, u64, u64) {
         if self.offset < MD_USER_DATA_SIZE {
             return (0, 0, 0, 0);
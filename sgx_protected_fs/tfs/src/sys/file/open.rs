@@ -17,8 +17,8 @@
 
 use crate::sys::cache::LruCache;
 use crate::sys::error::FsResult;
-use crate::sys::file::{FileInner, FileStatus, OpenMode, OpenOptions};
-use crate::sys::host::{self, HostFile, HostFs};
+use crate::sys::file::{CacheStats, FileInner, FileStatus, OpenMode, OpenOptions};
+use crate::sys::host::{HostFile, HostFs};
 use crate::sys::keys::{FsKeyGen, RestoreKey};
 use crate::sys::metadata::MetadataInfo;
 use crate::sys::metadata::{
@@ -40,6 +40,7 @@ macro_rules! is_page_aligned {
 }
 
 pub const DEFAULT_CACHE_SIZE: usize = 48 * SE_PAGE_SIZE;
+pub const MIN_CACHE_SIZE: usize = 8 * SE_PAGE_SIZE;
 
 impl FileInner {
     pub fn open(
@@ -48,7 +49,7 @@ impl FileInner {
         mode: &OpenMode,
         cache_size: Option<usize>,
     ) -> FsResult<Self> {
-        let cache_size = Self::check_cache_size(cache_size)?;
+        let cache_size = Self::check_cache_size(cache_size.or(opts.cache_size))?;
         let file_name = path.file_name().ok_or(EINVAL)?.to_str().ok_or(EINVAL)?;
         Self::check_open_param(path, file_name, opts, mode)?;
 
@@ -56,7 +57,7 @@ impl FileInner {
 
         Self::check_file_exist(opts, mode, path)?;
 
-        let mut host_file = HostFile::open(path, opts.readonly())?;
+        let mut host_file = HostFile::open(opts.host_storage(), path, opts.readonly())?;
         let file_size = host_file.size();
 
         let mut recovery_file_name = file_name.to_owned();
@@ -64,31 +65,26 @@ impl FileInner {
         let recovery_path = path.with_file_name(recovery_file_name);
 
         let mut need_writing = false;
-        let mut offset = 0;
         let (host_file, metadata, root_mht) = if file_size > 0 {
             // existing file
             ensure!(!opts.write, eos!(EACCES));
 
             let (host_file, metadata, root_mht) =
-                match Self::open_file(&mut host_file, file_name, &key_gen, mode) {
+                match Self::open_file(&mut host_file, file_name, &key_gen, opts, mode) {
                     Ok((metadata, root_mht)) => (host_file, metadata, root_mht),
                     Err(e) if e.equal_to_sgx_error(SgxStatus::RecoveryNeeded) => {
                         let mut host_file =
                             Self::recover_and_reopen_file(host_file, path, &recovery_path, opts)?;
 
                         let (metadata, root_mht) =
-                            Self::open_file(&mut host_file, file_name, &key_gen, mode)?;
+                            Self::open_file(&mut host_file, file_name, &key_gen, opts, mode)?;
                         (host_file, metadata, root_mht)
                     }
                     Err(e) => bail!(e),
                 };
-
-            if opts.append && !opts.update {
-                offset = metadata.encrypted_plain.size;
-            }
             (host_file, metadata, root_mht)
         } else {
-            let metadata = Self::new_file(file_name, mode)?;
+            let metadata = Self::new_file(file_name, opts, mode)?;
             need_writing = true;
             (host_file, metadata, FileNode::new_root_ref(mode.into()))
         };
@@ -98,15 +94,16 @@ impl FileInner {
             metadata,
             root_mht,
             key_gen,
-            opts: *opts,
+            opts: opts.clone(),
             need_writing,
-            end_of_file: false,
             max_cache_page: cache_size,
-            offset,
+            offset: 0,
+            read_end: 0,
             last_error: esgx!(SgxStatus::Success),
             status: FileStatus::NotInitialized,
             recovery_path,
             cache: LruCache::new(cache_size),
+            stats: CacheStats::default(),
         };
 
         protected_file.status = FileStatus::Ok;
@@ -117,6 +114,7 @@ impl FileInner {
         host_file: &mut dyn HostFs,
         file_name: &str,
         key_gen: &dyn RestoreKey,
+        opts: &OpenOptions,
         mode: &OpenMode,
     ) -> FsResult<(MetadataInfo, FileNodeRef)> {
         let mut metadata = MetadataInfo::default();
@@ -134,6 +132,7 @@ impl FileInner {
 
         let encrypt_flags = mode.into();
         ensure!(encrypt_flags == metadata.encrypt_flags(), eos!(EINVAL));
+        ensure!(metadata.cipher() == opts.cipher.id(), eos!(EINVAL));
 
         let key_policy = mode.key_policy();
         if mode.is_auto_key() {
@@ -147,15 +146,18 @@ impl FileInner {
             }
             None => metadata.restore_key(key_gen)?,
         };
-        metadata.decrypt(&key)?;
+        metadata.decrypt(&opts.cipher, &key)?;
 
         let meta_file_name = metadata.file_name()?;
         ensure!(meta_file_name == file_name, esgx!(SgxStatus::NameMismatch));
 
+        Self::check_counter(&metadata, opts)?;
+
         let mut root_mht = FileNode::new_root(encrypt_flags);
         if metadata.encrypted_plain.size > MD_USER_DATA_SIZE {
             root_mht.read_from_disk(host_file)?;
             root_mht.decrypt(
+                &opts.cipher,
                 &metadata.encrypted_plain.mht_key,
                 &metadata.encrypted_plain.mht_gmac,
             )?;
@@ -164,11 +166,54 @@ impl FileInner {
         Ok((metadata, FileNode::build_ref(root_mht)))
     }
 
+    // A file bound to a counter must not be older than the counter. It may be
+    // one ahead, if the last flush wrote the file but did not get to increment
+    // the counter; the counter is brought up to date then.
+    fn check_counter(metadata: &MetadataInfo, opts: &OpenOptions) -> FsResult {
+        let mc_uuid = metadata.encrypted_plain.mc_uuid;
+        let mc_value = metadata.encrypted_plain.mc_value;
+
+        let counter = match opts.counter {
+            Some(ref counter) => counter,
+            None => {
+                // A bound file can only be opened without its counter if the
+                // caller asks to skip the check, and then only be read.
+                ensure!(
+                    mc_uuid.is_empty() || (opts.skip_rollback_check && opts.readonly()),
+                    eos!(EACCES)
+                );
+                return Ok(());
+            }
+        };
+        ensure!(!mc_uuid.is_empty(), eos!(EINVAL));
+
+        let value = counter.read(&mc_uuid)?;
+        if mc_value == value {
+            return Ok(());
+        }
+        ensure!(
+            value.checked_add(1) == Some(mc_value),
+            esgx!(SgxStatus::FileRollback)
+        );
+        ensure!(
+            counter.increment(&mc_uuid, value)? == mc_value,
+            esgx!(SgxStatus::FileRollback)
+        );
+        Ok(())
+    }
+
     #[inline]
-    fn new_file(file_name: &str, mode: &OpenMode) -> FsResult<MetadataInfo> {
+    fn new_file(file_name: &str, opts: &OpenOptions, mode: &OpenMode) -> FsResult<MetadataInfo> {
         let mut metadata = MetadataInfo::new();
 
+        if let Some(ref counter) = opts.counter {
+            let (mc_uuid, mc_value) = counter.create()?;
+            metadata.encrypted_plain.mc_uuid = mc_uuid;
+            metadata.encrypted_plain.mc_value = mc_value;
+        }
+
         metadata.set_encrypt_flags(mode.into());
+        metadata.set_cipher(opts.cipher.id());
         if let Some(key_policy) = mode.key_policy() {
             metadata.set_key_policy(key_policy);
         }
@@ -188,23 +233,29 @@ impl FileInner {
         let file_size = host_file.size();
         drop(host_file);
 
-        host::recovery(path, recovery_path)?;
-        let host_file = HostFile::open(path, opts.readonly())?;
+        let storage = opts.host_storage();
+        storage.recovery(path, recovery_path)?;
+        let host_file = HostFile::open(storage, path, opts.readonly())?;
         ensure!(host_file.size() == file_size, esgx!(SgxStatus::Unexpected));
         Ok(host_file)
     }
 
     fn check_file_exist(opts: &OpenOptions, mode: &OpenMode, path: &Path) -> FsResult {
-        let is_exist = host::try_exists(path)?;
+        let storage = opts.host_storage();
+        let is_exist = storage.try_exists(path)?;
 
         if opts.read || mode.import_key().is_some() {
             ensure!(is_exist, eos!(ENOENT));
         }
         if opts.write && is_exist {
-            // try to delete existing file
-            host::remove(path)?;
+            // try to delete existing file, and the counter it is bound to
+            if opts.counter.is_some() {
+                Self::remove_with(path, opts, mode)?;
+            } else {
+                storage.remove(path)?;
+            }
             // re-check
-            let is_exist = host::try_exists(path)?;
+            let is_exist = storage.try_exists(path)?;
             ensure!(!is_exist, eos!(EACCES));
         }
 
@@ -237,7 +288,7 @@ impl FileInner {
         cache_size
             .or(Some(DEFAULT_CACHE_SIZE))
             .and_then(|cache_size| {
-                if is_page_aligned!(cache_size) && cache_size >= DEFAULT_CACHE_SIZE {
+                if is_page_aligned!(cache_size) && cache_size >= MIN_CACHE_SIZE {
                     Some(cache_size / SE_PAGE_SIZE)
                 } else {
                     None
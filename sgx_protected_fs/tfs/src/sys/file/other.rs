@@ -16,8 +16,7 @@
 // under the License..
 
 use crate::sys::error::{FsError, FsResult};
-use crate::sys::file::{FileInner, FileStatus};
-use crate::sys::host;
+use crate::sys::file::{CloseMode, Cursor, FileInner, FileStatus, OpenMode, OpenOptions};
 use crate::sys::metadata::FILENAME_MAX_LEN;
 use sgx_types::error::errno::*;
 use sgx_types::error::SgxStatus;
@@ -26,20 +25,37 @@ use std::io::SeekFrom;
 use std::path::Path;
 
 impl FileInner {
+    // A file bound to a counter is removed with `remove_with`, which finds
+    // the counter to destroy it.
     #[inline]
-    pub fn remove(path: &Path) -> FsResult {
-        host::remove(path)
+    pub fn remove(path: &Path, opts: &OpenOptions) -> FsResult {
+        ensure!(opts.counter.is_none(), eos!(EINVAL));
+        opts.host_storage().remove(path).map_err(FsError::from)
     }
 
-    #[inline]
-    pub fn tell(&mut self) -> FsResult<u64> {
-        ensure!(self.status.is_ok(), esgx!(SgxStatus::BadStatus));
-        Ok(self.offset as u64)
+    // The counter of a bound file is destroyed before the file is removed, so
+    // a copy put back in its place can no longer be opened.
+    pub fn remove_with(path: &Path, opts: &OpenOptions, mode: &OpenMode) -> FsResult {
+        if let Some(ref counter) = opts.counter {
+            let read_opts = OpenOptions {
+                counter: None,
+                skip_rollback_check: true,
+                ..opts.with_access(true, false)
+            };
+            let mut file = Self::open(path, &read_opts, mode, None)?;
+            let mc_uuid = file.metadata.encrypted_plain.mc_uuid;
+            file.close(CloseMode::Normal)?;
+            if !mc_uuid.is_empty() {
+                counter.destroy(&mc_uuid)?;
+            }
+        }
+        opts.host_storage().remove(path).map_err(FsError::from)
     }
 
     #[inline]
-    pub fn get_eof(&self) -> bool {
-        self.end_of_file
+    pub fn tell(&self, cursor: &Cursor) -> FsResult<u64> {
+        ensure!(self.status.is_ok(), esgx!(SgxStatus::BadStatus));
+        Ok(cursor.offset as u64)
     }
 
     #[inline]
@@ -48,7 +64,7 @@ impl FileInner {
         Ok(self.metadata.encrypted_plain.size as u64)
     }
 
-    pub fn seek(&mut self, pos: SeekFrom) -> FsResult<u64> {
+    pub fn seek(&mut self, pos: SeekFrom, cursor: &mut Cursor) -> FsResult<u64> {
         ensure!(self.status.is_ok(), esgx!(SgxStatus::BadStatus));
 
         let file_size = self.metadata.encrypted_plain.size as u64;
@@ -68,7 +84,7 @@ impl FileInner {
                 }
             }
             SeekFrom::Current(off) => {
-                let cur_offset = self.offset as u64;
+                let cur_offset = cursor.offset as u64;
                 if off >= 0 {
                     match cur_offset.checked_add(off as u64) {
                         Some(new_offset) if new_offset <= file_size => Some(new_offset),
@@ -81,50 +97,34 @@ impl FileInner {
         }
         .ok_or(EINVAL)?;
 
-        self.offset = new_offset as usize;
-        self.end_of_file = false;
-        Ok(self.offset as u64)
+        cursor.offset = new_offset as usize;
+        cursor.end_of_file = false;
+        Ok(cursor.offset as u64)
     }
 
-    pub fn set_len(&mut self, size: u64) -> FsResult {
-        let new_size = size as usize;
-        let mut cur_offset = self.offset;
+    pub fn set_len(&mut self, size: u64, cursor: &mut Cursor) -> FsResult {
+        ensure!(self.status.is_ok(), esgx!(SgxStatus::BadStatus));
+        ensure!(
+            self.opts.write || self.opts.append || self.opts.update,
+            eos!(EACCES)
+        );
+
+        let new_size = usize::try_from(size).map_err(|_| eos!(EFBIG))?;
         let file_size = self.metadata.encrypted_plain.size;
 
-        let mut reset_len = if new_size > file_size {
+        if new_size > file_size {
             // expand the file by padding null bytes
-            self.seek(SeekFrom::End(0))?;
-            new_size - file_size
+            self.write_zeros(file_size, new_size - file_size)?;
         } else {
-            // shrink the file by setting null bytes between len and file_size
-            self.seek(SeekFrom::Start(size))?;
-            file_size - new_size
-        };
-
-        static ZEROS: [u8; 0x1000] = [0; 0x1000];
-        while reset_len > 0 {
-            let len = reset_len.min(0x1000);
-
-            let nwritten = match self.write(&ZEROS[..len]) {
-                Ok(n) => n,
-                Err(error) => {
-                    if new_size > file_size {
-                        self.offset = cur_offset;
-                        bail!(error);
-                    } else {
-                        // ignore errors in shrinking files
-                        break;
-                    }
-                }
-            };
-            reset_len -= nwritten;
+            // shrink the file by setting null bytes between len and file_size,
+            // ignoring errors
+            let _ = self.write_zeros(new_size, file_size - new_size);
         }
 
-        if cur_offset > new_size {
-            cur_offset = new_size;
+        if cursor.offset > new_size {
+            cursor.offset = new_size;
         }
-        self.offset = cur_offset;
-        self.end_of_file = false;
+        cursor.end_of_file = false;
         self.metadata.encrypted_plain.size = new_size;
         Ok(())
     }
@@ -151,10 +151,7 @@ impl FileInner {
 
     pub fn clear_error(&mut self) -> FsResult {
         match self.status {
-            FileStatus::Ok => {
-                self.set_last_error(SgxStatus::Success);
-                self.end_of_file = false;
-            }
+            FileStatus::Ok => self.set_last_error(SgxStatus::Success),
             FileStatus::WriteToDiskFailed => {
                 self.write_to_disk(true)?;
                 self.need_writing = false;
@@ -16,18 +16,41 @@
 // under the License..
 
 use crate::sys::error::FsResult;
-use crate::sys::file::FileInner;
+use crate::sys::file::{Cursor, FileInner};
 use crate::sys::metadata::MD_USER_DATA_SIZE;
 use crate::sys::node::NODE_SIZE;
 use sgx_types::error::errno::EACCES;
 use sgx_types::error::SgxStatus;
-use std::io::SeekFrom;
 
 #[cfg(feature = "tfs")]
 use sgx_trts::trts::EnclaveRange;
 
 impl FileInner {
-    pub fn read(&mut self, buf: &mut [u8]) -> FsResult<usize> {
+    // The cursor stays at the end of the file until it is moved, like a stdio
+    // stream, even if the file grows meanwhile.
+    pub fn read(&mut self, buf: &mut [u8], cursor: &mut Cursor) -> FsResult<usize> {
+        if cursor.end_of_file && !buf.is_empty() {
+            ensure!(self.status.is_ok(), esgx!(SgxStatus::BadStatus));
+            return Ok(0);
+        }
+
+        let len = self.read_data(buf, cursor.offset)?;
+        cursor.offset += len;
+        if len < buf.len() && cursor.offset == self.metadata.encrypted_plain.size {
+            cursor.end_of_file = true;
+        }
+        Ok(len)
+    }
+
+    pub fn read_at(&mut self, buf: &mut [u8], offset: u64) -> FsResult<usize> {
+        let file_size = self.metadata.encrypted_plain.size as u64;
+        if offset > file_size {
+            return Ok(0);
+        }
+        self.read_data(buf, offset as usize)
+    }
+
+    fn read_data(&mut self, buf: &mut [u8], start: usize) -> FsResult<usize> {
         if buf.is_empty() {
             return Ok(0);
         }
@@ -37,14 +60,11 @@ impl FileInner {
         ensure!(self.status.is_ok(), esgx!(SgxStatus::BadStatus));
         ensure!(self.opts.read || self.opts.update, eos!(EACCES));
 
-        if self.end_of_file {
-            return Ok(0);
-        }
         let file_size = self.metadata.encrypted_plain.size;
-        if self.offset == file_size {
-            self.end_of_file = true;
+        if start >= file_size {
             return Ok(0);
         }
+        self.offset = start;
 
         let mut left_to_read = buf.len();
         if left_to_read > file_size - self.offset {
@@ -52,6 +72,7 @@ impl FileInner {
         }
         let attempted_to_read = left_to_read;
         let mut offset = 0;
+        let sequential = self.offset == self.read_end;
 
         if self.offset < MD_USER_DATA_SIZE {
             let len = left_to_read.min(MD_USER_DATA_SIZE - self.offset);
@@ -82,27 +103,13 @@ impl FileInner {
             self.offset += len;
         }
 
-        // user wanted to read more and we had to shrink the request
-        if left_to_read == 0 && attempted_to_read != buf.len() {
-            assert!(self.offset == file_size);
-            self.end_of_file = true;
+        // a read that starts where the last one ended is part of a scan,
+        // a failed read-ahead is reported when the node is actually read
+        if sequential && left_to_read == 0 && self.opts.read_ahead > 0 {
+            let _ = self.read_ahead();
         }
+        self.read_end = self.offset;
 
         Ok(attempted_to_read - left_to_read)
     }
-
-    pub fn read_at(&mut self, buf: &mut [u8], offset: u64) -> FsResult<usize> {
-        let cur_offset = self.offset;
-        let file_size = self.metadata.encrypted_plain.size as u64;
-
-        if offset > file_size {
-            return Ok(0);
-        }
-
-        self.seek(SeekFrom::Start(offset))?;
-        let result = self.read(buf);
-        self.offset = cur_offset;
-
-        result
-    }
 }
@@ -0,0 +1,282 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::fs::{CacheStats, EncryptMode, MemoryStorage, OpenOptions, SgxFile};
+use crate::fs::{DEFAULT_CACHE_SIZE, MIN_CACHE_SIZE};
+use sgx_test_utils::test_case;
+use sgx_types::error::errno::EINVAL;
+use sgx_types::types::Key128bit;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::vec::Vec;
+
+const FILE_KEY: Key128bit = [0x55; 16];
+// The data nodes start after the user data kept in the metadata node.
+const MD_USER_DATA_SIZE: usize = 3072;
+const NODE_SIZE: usize = 4096;
+const DATA_NODES: u64 = 10;
+
+const THREADS: usize = 4;
+// Each thread has a region of a few nodes, unaligned to the nodes.
+const REGION: usize = 3 * NODE_SIZE + 100;
+const CHUNK: usize = 1000;
+
+fn options(storage: &MemoryStorage) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.storage(Arc::new(storage.clone()));
+    opts
+}
+
+fn create(storage: &MemoryStorage, path: &Path) -> SgxFile {
+    options(storage)
+        .write(true)
+        .update(true)
+        .open_with_key(path, FILE_KEY)
+        .unwrap()
+}
+
+// A file of DATA_NODES full data nodes.
+fn create_nodes(storage: &MemoryStorage, path: &Path) {
+    let mut file = create(storage, path);
+    let len = MD_USER_DATA_SIZE + DATA_NODES as usize * NODE_SIZE;
+    file.write_all(&vec![0x5a; len]).unwrap();
+}
+
+fn open(opts: &OpenOptions, path: &Path) -> SgxFile {
+    opts.clone()
+        .read(true)
+        .open_with_key(path, FILE_KEY)
+        .unwrap()
+}
+
+fn pattern(thread: usize, offset: usize) -> u8 {
+    (thread * 31 + offset % 251) as u8
+}
+
+#[test_case]
+fn concurrent_read_at_write_at() {
+    let storage = MemoryStorage::new();
+    let file = Arc::new(create(&storage, Path::new("concurrent")));
+    (&*file).write_all(&[0xff; 100]).unwrap();
+    (&*file).seek(SeekFrom::Start(10)).unwrap();
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|i| {
+            let file = file.clone();
+            thread::spawn(move || {
+                let start = i * REGION;
+                let data: Vec<u8> = (0..REGION).map(|j| pattern(i, j)).collect();
+                for (n, chunk) in data.chunks(CHUNK).enumerate() {
+                    let offset = start + n * CHUNK;
+                    assert_eq!(file.write_at(chunk, offset as u64).unwrap(), chunk.len());
+
+                    let mut buf = vec![0; chunk.len()];
+                    assert_eq!(file.read_at(&mut buf, offset as u64).unwrap(), chunk.len());
+                    assert_eq!(buf, chunk);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // The cursor stayed where it was.
+    assert_eq!(file.tell().unwrap(), 10);
+    assert_eq!(file.file_size().unwrap(), (THREADS * REGION) as u64);
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|i| {
+            let file = file.clone();
+            thread::spawn(move || {
+                // Each thread reads the region of the next one.
+                let region = (i + 1) % THREADS;
+                let mut buf = vec![0; REGION];
+                let offset = (region * REGION) as u64;
+                assert_eq!(file.read_at(&mut buf, offset).unwrap(), REGION);
+                assert!(buf
+                    .iter()
+                    .enumerate()
+                    .all(|(j, b)| *b == pattern(region, j)));
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test_case]
+fn positional_io_leaves_cursor() {
+    let storage = MemoryStorage::new();
+    let mut file = create(&storage, Path::new("cursor"));
+    file.write_all(b"0123456789").unwrap();
+
+    file.seek(SeekFrom::Start(4)).unwrap();
+    let mut buf = [0; 3];
+    assert_eq!(file.read_at(&mut buf, 1).unwrap(), 3);
+    assert_eq!(&buf, b"123");
+    assert_eq!(file.write_at(b"abc", 7).unwrap(), 3);
+    assert_eq!(file.tell().unwrap(), 4);
+
+    // Writing past the end fills the gap with zeros.
+    assert_eq!(file.write_at(b"z", 12).unwrap(), 1);
+    assert_eq!(file.file_size().unwrap(), 13);
+    assert_eq!(file.read_at(&mut buf, 10).unwrap(), 3);
+    assert_eq!(&buf, b"\0\0z");
+    assert_eq!(file.read_at(&mut buf, 13).unwrap(), 0);
+    assert_eq!(file.read_at(&mut buf, 20).unwrap(), 0);
+
+    let mut rest = Vec::new();
+    file.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"456abc\0\0z");
+    assert!(file.is_eof());
+
+    // A positional read does not clear the end of file.
+    assert_eq!(file.read_at(&mut buf, 0).unwrap(), 3);
+    assert!(file.is_eof());
+    assert_eq!(file.tell().unwrap(), 13);
+}
+
+#[test_case]
+fn cache_size_limits() {
+    let storage = MemoryStorage::new();
+    let path = Path::new("cache_size");
+    create_nodes(&storage, path);
+
+    let open_error = |opts: &OpenOptions, cache_size| {
+        opts.clone()
+            .read(true)
+            .open_with(path, EncryptMode::user_key(FILE_KEY), cache_size)
+            .err()
+            .and_then(|error| error.raw_os_error())
+    };
+    for cache_size in [MIN_CACHE_SIZE - 4096, MIN_CACHE_SIZE + 1, 0] {
+        let mut opts = options(&storage);
+        opts.cache_size(cache_size);
+        assert_eq!(open_error(&opts, None), Some(EINVAL));
+    }
+
+    // The size passed to open_with takes precedence.
+    let mut opts = options(&storage);
+    opts.cache_size(MIN_CACHE_SIZE);
+    assert_eq!(open_error(&opts, Some(MIN_CACHE_SIZE + 1)), Some(EINVAL));
+    assert_eq!(open_error(&options(&storage), Some(MIN_CACHE_SIZE)), None);
+
+    // A cache of 8 nodes holds 8 of the data nodes, the root MHT is kept
+    // apart, so reading the file evicts the other 2.
+    let file = open(&opts, path);
+    let mut buf = vec![0; MD_USER_DATA_SIZE + DATA_NODES as usize * NODE_SIZE];
+    assert_eq!(file.read_at(&mut buf, 0).unwrap(), buf.len());
+    let stats = file.cache_stats();
+    assert_eq!(stats.misses, DATA_NODES);
+    assert_eq!(stats.evictions, DATA_NODES - 8);
+
+    // The default cache keeps them all.
+    assert!(DEFAULT_CACHE_SIZE / NODE_SIZE > DATA_NODES as usize);
+    let file = open(&options(&storage), path);
+    assert_eq!(file.read_at(&mut buf, 0).unwrap(), buf.len());
+    assert_eq!(file.cache_stats().evictions, 0);
+}
+
+#[test_case]
+fn cache_stats_counters() {
+    let storage = MemoryStorage::new();
+    let path = Path::new("cache_stats");
+    let mut file = create(&storage, path);
+    assert_eq!(file.cache_stats(), CacheStats::default());
+
+    // Every data node is written, then the root MHT.
+    let len = MD_USER_DATA_SIZE + DATA_NODES as usize * NODE_SIZE;
+    file.write_all(&vec![0x5a; len]).unwrap();
+    file.flush().unwrap();
+    assert_eq!(file.cache_stats().dirty_flushes, DATA_NODES + 1);
+
+    // Nothing to write back, and the metadata node is not counted.
+    file.flush().unwrap();
+    file.write_at(b"meta", 0).unwrap();
+    file.flush().unwrap();
+    assert_eq!(file.cache_stats().dirty_flushes, DATA_NODES + 1);
+
+    file.write_at(b"node", (MD_USER_DATA_SIZE + NODE_SIZE) as u64)
+        .unwrap();
+    file.flush().unwrap();
+    assert_eq!(file.cache_stats().dirty_flushes, DATA_NODES + 3);
+    drop(file);
+
+    let file = open(&options(&storage), path);
+    let mut buf = vec![0; len];
+    assert_eq!(file.read_at(&mut buf, 0).unwrap(), len);
+    let stats = file.cache_stats();
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.misses, DATA_NODES);
+    assert_eq!(stats.bytes_decrypted, DATA_NODES * NODE_SIZE as u64);
+
+    assert_eq!(file.read_at(&mut buf, 0).unwrap(), len);
+    let stats = file.cache_stats();
+    assert_eq!(stats.hits, DATA_NODES);
+    assert_eq!(stats.misses, DATA_NODES);
+
+    // Clearing the cache keeps the counters.
+    file.clear_cache().unwrap();
+    assert_eq!(file.cache_stats(), stats);
+    assert_eq!(
+        file.read_at(&mut buf[..1], MD_USER_DATA_SIZE as u64)
+            .unwrap(),
+        1
+    );
+    assert_eq!(file.cache_stats().misses, DATA_NODES + 1);
+}
+
+#[test_case]
+fn read_ahead() {
+    let storage = MemoryStorage::new();
+    let path = Path::new("read_ahead");
+    create_nodes(&storage, path);
+    let mut opts = options(&storage);
+    opts.read_ahead(4);
+
+    // A scan loads the nodes ahead of the cursor.
+    let mut file = open(&opts, path);
+    let mut buf = vec![0; NODE_SIZE];
+    file.read_exact(&mut buf[..MD_USER_DATA_SIZE]).unwrap();
+    assert_eq!(file.cache_stats().misses, 4);
+    file.read_exact(&mut buf).unwrap();
+    let stats = file.cache_stats();
+    assert_eq!((stats.hits, stats.misses), (1, 5));
+
+    for _ in 1..DATA_NODES {
+        file.read_exact(&mut buf).unwrap();
+        assert!(buf.iter().all(|b| *b == 0x5a));
+    }
+    let stats = file.cache_stats();
+    assert_eq!((stats.hits, stats.misses), (DATA_NODES, DATA_NODES));
+
+    // A read elsewhere in the file does not.
+    let file = open(&opts, path);
+    let offset = MD_USER_DATA_SIZE + 5 * NODE_SIZE;
+    assert_eq!(file.read_at(&mut buf, offset as u64).unwrap(), NODE_SIZE);
+    assert_eq!(file.cache_stats().misses, 1);
+
+    // It takes at most half of the cache.
+    opts.read_ahead(100).cache_size(MIN_CACHE_SIZE);
+    let mut file = open(&opts, path);
+    file.read_exact(&mut buf[..MD_USER_DATA_SIZE]).unwrap();
+    assert_eq!(file.cache_stats().misses, 4);
+}
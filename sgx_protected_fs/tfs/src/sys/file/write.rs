@@ -16,18 +16,58 @@
 // under the License..
 
 use crate::sys::error::FsResult;
-use crate::sys::file::FileInner;
+use crate::sys::file::{Cursor, FileInner};
 use crate::sys::metadata::MD_USER_DATA_SIZE;
 use crate::sys::node::NODE_SIZE;
-use sgx_types::error::errno::EACCES;
+use sgx_types::error::errno::{EACCES, EFBIG};
 use sgx_types::error::SgxStatus;
-use std::io::SeekFrom;
 
 #[cfg(feature = "tfs")]
 use sgx_trts::trts::EnclaveRange;
 
 impl FileInner {
-    pub fn write(&mut self, buf: &[u8]) -> FsResult<usize> {
+    // A file opened for appending is written at its end, wherever the cursor
+    // is.
+    pub fn write(&mut self, buf: &[u8], cursor: &mut Cursor) -> FsResult<usize> {
+        let start = if self.opts.append {
+            self.metadata.encrypted_plain.size
+        } else {
+            cursor.offset
+        };
+        let len = self.write_data(buf, start)?;
+        cursor.offset = start + len;
+        Ok(len)
+    }
+
+    pub fn write_at(&mut self, buf: &[u8], offset: u64) -> FsResult<usize> {
+        let file_size = self.metadata.encrypted_plain.size;
+        if self.opts.append {
+            return self.write_data(buf, file_size);
+        }
+
+        if offset > file_size as u64 {
+            let len = usize::try_from(offset - file_size as u64).map_err(|_| eos!(EFBIG))?;
+            self.write_zeros(file_size, len)?;
+        }
+        self.write_data(buf, offset as usize)
+    }
+
+    // Writes `len` zeros from `start`, failing if a write makes no progress.
+    pub fn write_zeros(&mut self, start: usize, len: usize) -> FsResult {
+        static ZEROS: [u8; 0x1000] = [0; 0x1000];
+
+        let mut offset = start;
+        let end = start.checked_add(len).ok_or_else(|| eos!(EFBIG))?;
+        while offset < end {
+            let len = (end - offset).min(ZEROS.len());
+            let written_len = self.write_data(&ZEROS[..len], offset)?;
+            ensure!(written_len > 0, self.last_error);
+            offset += written_len;
+        }
+        Ok(())
+    }
+
+    fn write_data(&mut self, buf: &[u8], start: usize) -> FsResult<usize> {
         if buf.is_empty() {
             return Ok(0);
         }
@@ -40,10 +80,7 @@ impl FileInner {
             eos!(EACCES)
         );
 
-        if self.opts.append {
-            self.offset = self.metadata.encrypted_plain.size;
-        }
-
+        self.offset = start;
         let mut left_to_write = buf.len();
         let mut offset = 0;
 
@@ -105,33 +142,4 @@ impl FileInner {
         }
         Ok(offset)
     }
-
-    pub fn write_at(&mut self, buf: &[u8], offset: u64) -> FsResult<usize> {
-        let cur_offset = self.offset;
-        let file_size = self.metadata.encrypted_plain.size as u64;
-
-        if offset > file_size {
-            self.seek(SeekFrom::End(0))?;
-
-            static ZEROS: [u8; 0x1000] = [0; 0x1000];
-            let mut left_to_write = offset - file_size;
-            while left_to_write > 0 {
-                let len = left_to_write.min(0x1000) as usize;
-                let written_len = self.write(&ZEROS[..len]).map_err(|error| {
-                    self.offset = cur_offset;
-                    error
-                })?;
-                left_to_write -= written_len as u64;
-            }
-        }
-
-        self.seek(SeekFrom::Start(offset)).map_err(|error| {
-            self.offset = cur_offset;
-            error
-        })?;
-        let result = self.write(buf);
-        self.offset = cur_offset;
-
-        result
-    }
 }
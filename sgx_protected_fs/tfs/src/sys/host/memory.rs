@@ -0,0 +1,245 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{HostStorage, RecoveryFile, StorageFile};
+use crate::sys::node::NODE_SIZE;
+use sgx_types::error::errno::*;
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::io::{Error, Result};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::vec::Vec;
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+const RECOVERY_NODE_SIZE: usize = mem::size_of::<u64>() + NODE_SIZE;
+
+/// Protected files kept in memory, e.g. for tests.
+///
+/// The files are kept on the heap of the process they are created in, so in
+/// an enclave they take up enclave memory.
+///
+/// Clones share the same files. Open files are locked like on the host file
+/// system: a file opened for writing cannot be opened again until it is
+/// closed, and a file opened readonly can only be opened readonly.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    files: Arc<Mutex<HashMap<PathBuf, MemoryFileRef>>>,
+}
+
+type MemoryFileRef = Arc<Mutex<MemoryFile>>;
+
+#[derive(Debug, Default)]
+struct MemoryFile {
+    data: Vec<u8>,
+    readers: usize,
+    writer: bool,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+
+    /// The raw contents of the file `name`.
+    pub fn get(&self, name: &Path) -> Option<Vec<u8>> {
+        let files = self.files.lock().ok()?;
+        let file = files.get(name)?.lock().ok()?;
+        Some(file.data.clone())
+    }
+
+    /// Replaces the raw contents of the file `name`, creating it if needed.
+    pub fn set(&self, name: &Path, data: Vec<u8>) -> Result<()> {
+        let mut files = self.files()?;
+        let file = files.entry(name.to_path_buf()).or_default();
+        lock(file)?.data = data;
+        Ok(())
+    }
+
+    /// The names of all files, recovery files included.
+    pub fn names(&self) -> Vec<PathBuf> {
+        self.files
+            .lock()
+            .map(|files| files.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn files(&self) -> Result<MutexGuard<'_, HashMap<PathBuf, MemoryFileRef>>> {
+        self.files.lock().map_err(|_| Error::from_raw_os_error(EIO))
+    }
+}
+
+impl HostStorage for MemoryStorage {
+    fn open(&self, name: &Path, readonly: bool) -> Result<Box<dyn StorageFile>> {
+        let mut files = self.files()?;
+        let file = if readonly {
+            files
+                .get(name)
+                .cloned()
+                .ok_or_else(|| Error::from_raw_os_error(ENOENT))?
+        } else {
+            files.entry(name.to_path_buf()).or_default().clone()
+        };
+
+        {
+            let mut guard = lock(&file)?;
+            ensure!(
+                !guard.writer && (readonly || guard.readers == 0),
+                Error::from_raw_os_error(EWOULDBLOCK)
+            );
+            if readonly {
+                guard.readers += 1;
+            } else {
+                guard.writer = true;
+            }
+        }
+        Ok(Box::new(MemoryHandle { file, readonly }))
+    }
+
+    fn open_recovery(&self, name: &Path) -> Result<Box<dyn RecoveryFile>> {
+        let file = MemoryFileRef::default();
+        self.files()?.insert(name.to_path_buf(), file.clone());
+        Ok(Box::new(MemoryRecovery { file }))
+    }
+
+    fn recovery(&self, source: &Path, recovery: &Path) -> Result<()> {
+        let files = self.files()?;
+        let get = |name| {
+            files
+                .get(name)
+                .ok_or_else(|| Error::from_raw_os_error(ENOENT))
+        };
+        {
+            let recov = lock(get(recovery)?)?;
+            let mut src = lock(get(source)?)?;
+            ensure!(
+                recov.data.len() % RECOVERY_NODE_SIZE == 0,
+                Error::from_raw_os_error(ENOTSUP)
+            );
+
+            for node in recov.data.chunks_exact(RECOVERY_NODE_SIZE) {
+                let mut number = [0_u8; 8];
+                number.copy_from_slice(&node[0..8]);
+                let physical_node_number = u64::from_ne_bytes(number);
+                write_node(&mut src.data, physical_node_number, &node[8..])?;
+            }
+        }
+        drop(files);
+        self.remove(recovery)
+    }
+
+    fn try_exists(&self, name: &Path) -> Result<bool> {
+        Ok(self.files()?.contains_key(name))
+    }
+
+    fn remove(&self, name: &Path) -> Result<()> {
+        self.files()?
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| Error::from_raw_os_error(ENOENT))
+    }
+}
+
+// Like an unlinked file, an open file that is removed keeps its contents
+// until it is closed.
+struct MemoryHandle {
+    file: MemoryFileRef,
+    readonly: bool,
+}
+
+impl StorageFile for MemoryHandle {
+    fn size(&self) -> Result<usize> {
+        Ok(lock(&self.file)?.data.len())
+    }
+
+    fn read(&mut self, number: u64, node: &mut [u8]) -> Result<()> {
+        ensure!(node.len() == NODE_SIZE, Error::from_raw_os_error(EINVAL));
+
+        let file = lock(&self.file)?;
+        let offset = node_offset(number)?;
+        let data = file
+            .data
+            .get(offset..offset + NODE_SIZE)
+            .ok_or_else(|| Error::from_raw_os_error(EIO))?;
+        node.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn write(&mut self, number: u64, node: &[u8]) -> Result<()> {
+        ensure!(node.len() == NODE_SIZE, Error::from_raw_os_error(EINVAL));
+        ensure!(!self.readonly, Error::from_raw_os_error(EBADF));
+
+        write_node(&mut lock(&self.file)?.data, number, node)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for MemoryHandle {
+    fn drop(&mut self) {
+        if let Ok(mut file) = self.file.lock() {
+            if self.readonly {
+                file.readers -= 1;
+            } else {
+                file.writer = false;
+            }
+        }
+    }
+}
+
+struct MemoryRecovery {
+    file: MemoryFileRef,
+}
+
+impl RecoveryFile for MemoryRecovery {
+    fn write(&mut self, node: &[u8]) -> Result<()> {
+        ensure!(
+            node.len() == RECOVERY_NODE_SIZE,
+            Error::from_raw_os_error(EINVAL)
+        );
+
+        lock(&self.file)?.data.extend_from_slice(node);
+        Ok(())
+    }
+}
+
+fn lock(file: &MemoryFileRef) -> Result<MutexGuard<'_, MemoryFile>> {
+    file.lock().map_err(|_| Error::from_raw_os_error(EIO))
+}
+
+fn node_offset(number: u64) -> Result<usize> {
+    number
+        .checked_mul(NODE_SIZE as u64)
+        .and_then(|offset| usize::try_from(offset).ok())
+        .filter(|offset| *offset <= isize::MAX as usize - NODE_SIZE)
+        .ok_or_else(|| Error::from_raw_os_error(EFBIG))
+}
+
+fn write_node(data: &mut Vec<u8>, number: u64, node: &[u8]) -> Result<()> {
+    let offset = node_offset(number)?;
+    if data.len() < offset + NODE_SIZE {
+        data.resize(offset + NODE_SIZE, 0);
+    }
+    data[offset..offset + NODE_SIZE].copy_from_slice(node);
+    Ok(())
+}
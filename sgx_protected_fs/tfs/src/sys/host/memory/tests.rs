@@ -0,0 +1,196 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::MemoryStorage;
+use crate::fs::{MemoryCounter, OpenOptions};
+use crate::sys::host::{HostStorage, RecoveryFile, StorageFile};
+use sgx_test_utils::test_case;
+use sgx_types::error::errno::*;
+use sgx_types::types::Key128bit;
+use std::boxed::Box;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::vec::Vec;
+
+const FILE_KEY: Key128bit = [0x33; 16];
+
+// Fails every write to a file once `writes` is used up, like a crash in
+// the middle of a flush.
+struct FaultyStorage {
+    inner: MemoryStorage,
+    writes: Arc<AtomicUsize>,
+}
+
+struct FaultyFile {
+    file: Box<dyn StorageFile>,
+    writes: Arc<AtomicUsize>,
+}
+
+impl HostStorage for FaultyStorage {
+    fn open(&self, name: &Path, readonly: bool) -> io::Result<Box<dyn StorageFile>> {
+        let file = self.inner.open(name, readonly)?;
+        Ok(Box::new(FaultyFile {
+            file,
+            writes: self.writes.clone(),
+        }))
+    }
+
+    fn open_recovery(&self, name: &Path) -> io::Result<Box<dyn RecoveryFile>> {
+        self.inner.open_recovery(name)
+    }
+
+    fn recovery(&self, source: &Path, recovery: &Path) -> io::Result<()> {
+        self.inner.recovery(source, recovery)
+    }
+
+    fn try_exists(&self, name: &Path) -> io::Result<bool> {
+        self.inner.try_exists(name)
+    }
+
+    fn remove(&self, name: &Path) -> io::Result<()> {
+        self.inner.remove(name)
+    }
+}
+
+impl StorageFile for FaultyFile {
+    fn size(&self) -> io::Result<usize> {
+        self.file.size()
+    }
+
+    fn read(&mut self, number: u64, node: &mut [u8]) -> io::Result<()> {
+        self.file.read(number, node)
+    }
+
+    fn write(&mut self, number: u64, node: &[u8]) -> io::Result<()> {
+        self.writes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .map_err(|_| io::Error::from_raw_os_error(EIO))?;
+        self.file.write(number, node)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn options(storage: Arc<dyn HostStorage>) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.storage(storage);
+    opts
+}
+
+fn read(opts: &OpenOptions, path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = opts.clone().read(true).open_with_key(path, FILE_KEY)?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+#[test_case]
+fn memory_storage_round_trip() {
+    let storage = MemoryStorage::new();
+    let opts = options(Arc::new(storage.clone()));
+    let path = Path::new("round_trip");
+    let data = (0..20000).map(|i| i as u8).collect::<Vec<u8>>();
+
+    {
+        let mut file = opts
+            .clone()
+            .write(true)
+            .open_with_key(path, FILE_KEY)
+            .unwrap();
+        file.write_all(&data).unwrap();
+    }
+    assert_eq!(storage.names(), [path]);
+    assert_eq!(read(&opts, path).unwrap(), data);
+
+    // Appending keeps the earlier data.
+    {
+        let mut file = opts
+            .clone()
+            .append(true)
+            .open_with_key(path, FILE_KEY)
+            .unwrap();
+        file.write_all(b"tail").unwrap();
+    }
+    let mut expected = data;
+    expected.extend_from_slice(b"tail");
+    assert_eq!(read(&opts, path).unwrap(), expected);
+
+    opts.remove(path).unwrap();
+    assert!(storage.get(path).is_none());
+    assert!(storage.names().is_empty());
+    let error = read(&opts, path).unwrap_err();
+    assert_eq!(error.raw_os_error(), Some(ENOENT));
+}
+
+#[test_case]
+fn memory_storage_recovery() {
+    let storage = MemoryStorage::new();
+    let opts = options(Arc::new(storage.clone()));
+    let path = Path::new("recovery");
+    let recovery_path = Path::new("recovery_recovery");
+    let old = [0x5a_u8; 20000];
+
+    {
+        let mut file = opts
+            .clone()
+            .write(true)
+            .open_with_key(path, FILE_KEY)
+            .unwrap();
+        file.write_all(&old).unwrap();
+    }
+    let host_file = storage.get(path).unwrap();
+
+    // Allow the metadata with the update flag and one node to be written,
+    // then fail the rest of the flush.
+    let faulty = Arc::new(FaultyStorage {
+        inner: storage.clone(),
+        writes: Arc::new(AtomicUsize::new(2)),
+    });
+    {
+        let mut file = options(faulty)
+            .read(true)
+            .update(true)
+            .open_with_key(path, FILE_KEY)
+            .unwrap();
+        file.write_at(&[0xa5; 20000], 0).unwrap();
+        assert!(file.flush().is_err());
+    }
+    assert_ne!(storage.get(path).unwrap(), host_file);
+    assert!(storage.get(recovery_path).is_some());
+
+    // Opening the file again writes the old nodes back.
+    assert_eq!(read(&opts, path).unwrap(), old);
+    assert_eq!(storage.get(path).unwrap(), host_file);
+    assert!(storage.get(recovery_path).is_none());
+}
+
+#[test_case]
+fn memory_storage_remove_with_counter() {
+    let storage = MemoryStorage::new();
+    let mut opts = options(Arc::new(storage.clone()));
+    let path = Path::new("counter");
+    storage.set(path, Vec::new()).unwrap();
+
+    opts.counter(Arc::new(MemoryCounter::new()));
+    let error = opts.remove(path).unwrap_err();
+    assert_eq!(error.raw_os_error(), Some(EINVAL));
+    assert!(storage.get(path).is_some());
+}
@@ -15,20 +15,179 @@
 // specific language governing permissions and limitations
 // under the License..
 
-use crate::sys::error::FsResult;
+use crate::sys::error::{FsError, FsResult};
+use crate::sys::node::NODE_SIZE;
+use sgx_types::error::errno::*;
+use sgx_types::error::SgxStatus;
+use std::boxed::Box;
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+pub use memory::MemoryStorage;
 
 cfg_if! {
     if #[cfg(feature = "tfs")] {
         mod ocall;
-        pub use ocall::*;
+        pub use ocall::FileStorage;
     } else {
         mod ufs;
-        pub use ufs::*;
+        pub use ufs::FileStorage;
     }
 }
 
+mod memory;
+
+/// Where the nodes of protected files are kept.
+///
+/// A protected file is a sequence of encrypted 4KB nodes, and a recovery
+/// file is a sequence of records that each hold a node number (u64, native
+/// byte order) followed by the node. The storage only ever sees ciphertext,
+/// so it does not have to be trusted. `FileStorage`, the host's file system,
+/// is used when no storage is given.
+pub trait HostStorage: Send + Sync {
+    /// Opens the file `name`, creating it unless `readonly`. The file should
+    /// be locked against other writers while it is open.
+    fn open(&self, name: &Path, readonly: bool) -> io::Result<Box<dyn StorageFile>>;
+
+    /// Creates the recovery file `name`, truncating it if it exists.
+    fn open_recovery(&self, name: &Path) -> io::Result<Box<dyn RecoveryFile>>;
+
+    /// Writes the nodes of the recovery file back to `source`, then removes
+    /// the recovery file.
+    fn recovery(&self, source: &Path, recovery: &Path) -> io::Result<()>;
+
+    fn try_exists(&self, name: &Path) -> io::Result<bool>;
+
+    fn remove(&self, name: &Path) -> io::Result<()>;
+}
+
+/// An open protected file. It is moved along with the `SgxFile` using it,
+/// so it must be Send.
+pub trait StorageFile: Send {
+    /// The size of the file in bytes.
+    fn size(&self) -> io::Result<usize>;
+
+    fn read(&mut self, number: u64, node: &mut [u8]) -> io::Result<()>;
+
+    fn write(&mut self, number: u64, node: &[u8]) -> io::Result<()>;
+
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// An open recovery file, which is only appended to.
+pub trait RecoveryFile {
+    fn write(&mut self, node: &[u8]) -> io::Result<()>;
+}
+
 pub trait HostFs {
     fn read(&mut self, number: u64, node: &mut dyn AsMut<[u8]>) -> FsResult;
     fn write(&mut self, number: u64, node: &dyn AsRef<[u8]>) -> FsResult;
     fn flush(&mut self) -> FsResult;
 }
+
+// The storage a file is opened with.
+#[derive(Clone)]
+pub struct Storage(Arc<dyn HostStorage>);
+
+impl Storage {
+    #[inline]
+    pub fn new(storage: Arc<dyn HostStorage>) -> Storage {
+        Storage(storage)
+    }
+}
+
+impl Deref for Storage {
+    type Target = dyn HostStorage;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl fmt::Debug for Storage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Storage").finish_non_exhaustive()
+    }
+}
+
+pub struct HostFile {
+    file: Box<dyn StorageFile>,
+    size: usize,
+}
+
+impl HostFile {
+    pub fn open(storage: &dyn HostStorage, name: &Path, readonly: bool) -> FsResult<HostFile> {
+        let file = storage.open(name, readonly)?;
+        let size = file.size()?;
+
+        ensure!(
+            size <= i64::MAX as usize && size % NODE_SIZE == 0,
+            esgx!(SgxStatus::NotSgxFile)
+        );
+        Ok(HostFile { file, size })
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl HostFs for HostFile {
+    fn read(&mut self, number: u64, node: &mut dyn AsMut<[u8]>) -> FsResult {
+        self.file.read(number, node.as_mut()).map_err(FsError::from)
+    }
+
+    fn write(&mut self, number: u64, node: &dyn AsRef<[u8]>) -> FsResult {
+        self.file
+            .write(number, node.as_ref())
+            .map_err(FsError::from)
+    }
+
+    fn flush(&mut self) -> FsResult {
+        self.file.flush().map_err(FsError::from)
+    }
+}
+
+impl fmt::Debug for HostFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostFile")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+pub struct HostRecoveryFile {
+    file: Box<dyn RecoveryFile>,
+}
+
+impl HostRecoveryFile {
+    pub fn open(storage: &dyn HostStorage, name: &Path) -> FsResult<HostRecoveryFile> {
+        let file = storage.open_recovery(name)?;
+        Ok(HostRecoveryFile { file })
+    }
+}
+
+impl HostFs for HostRecoveryFile {
+    fn read(&mut self, _number: u64, _node: &mut dyn AsMut<[u8]>) -> FsResult {
+        bail!(eos!(ENOTSUP))
+    }
+
+    fn write(&mut self, _number: u64, node: &dyn AsRef<[u8]>) -> FsResult {
+        self.file.write(node.as_ref()).map_err(FsError::from)
+    }
+
+    fn flush(&mut self) -> FsResult {
+        bail!(eos!(ENOTSUP))
+    }
+}
+
+impl fmt::Debug for HostRecoveryFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostRecoveryFile").finish_non_exhaustive()
+    }
+}
@@ -15,13 +15,13 @@
 // specific language governing permissions and limitations
 // under the License..
 
-use super::HostFs;
-use crate::sys::error::FsResult;
-use crate::sys::node::NODE_SIZE;
+use super::{HostStorage, RecoveryFile, StorageFile};
 use sgx_types::error::errno::*;
 use sgx_types::error::SgxStatus;
 use sgx_types::types::{c_char, c_int, c_void, size_t, uint64_t, uint8_t};
+use std::boxed::Box;
 use std::ffi::CString;
+use std::io::{Error, Result};
 use std::path::Path;
 use std::ptr;
 
@@ -99,18 +99,53 @@ extern "C" {
     ) -> SgxStatus;
 }
 
-fn cstr(name: &Path) -> FsResult<CString> {
-    CString::new(name.to_str().ok_or(EINVAL)?).map_err(|_| eos!(EINVAL))
+fn cstr(name: &Path) -> Result<CString> {
+    let name = name
+        .to_str()
+        .ok_or_else(|| Error::from_raw_os_error(EINVAL))?;
+    CString::new(name).map_err(|_| Error::from_raw_os_error(EINVAL))
+}
+
+/// Protected files on the host's file system, reached through the
+/// `u_sgxfs_*` ocalls.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileStorage;
+
+impl HostStorage for FileStorage {
+    fn open(&self, name: &Path, readonly: bool) -> Result<Box<dyn StorageFile>> {
+        let file = HostFile::open(name, readonly)?;
+        Ok(Box::new(file))
+    }
+
+    fn open_recovery(&self, name: &Path) -> Result<Box<dyn RecoveryFile>> {
+        let file = HostRecoveryFile::open(name)?;
+        Ok(Box::new(file))
+    }
+
+    #[inline]
+    fn recovery(&self, source: &Path, recovery: &Path) -> Result<()> {
+        self::recovery(source, recovery)
+    }
+
+    #[inline]
+    fn try_exists(&self, name: &Path) -> Result<bool> {
+        self::try_exists(name)
+    }
+
+    #[inline]
+    fn remove(&self, name: &Path) -> Result<()> {
+        self::remove(name)
+    }
 }
 
 #[derive(Debug)]
-pub struct HostFile {
+struct HostFile {
     file: RawFileStream,
     size: usize,
 }
 
 impl HostFile {
-    pub fn open(name: &Path, readonly: bool) -> FsResult<HostFile> {
+    fn open(name: &Path, readonly: bool) -> Result<HostFile> {
         let mut file: RawFileStream = ptr::null_mut();
         let mut size: size_t = 0;
         let mut error: c_int = 0;
@@ -126,24 +161,15 @@ impl HostFile {
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
         ensure!(
             !file.is_null(),
-            eos!(if error != 0 { error } else { EACCES })
-        );
-        ensure!(
-            size <= i64::MAX as usize && size % NODE_SIZE == 0,
-            esgx!(SgxStatus::NotSgxFile)
+            Error::from_raw_os_error(if error != 0 { error } else { EACCES })
         );
         Ok(HostFile { file, size })
     }
 
-    #[inline]
-    pub fn size(&self) -> usize {
-        self.size
-    }
-
-    fn close(&mut self) -> FsResult {
+    fn close(&mut self) -> Result<()> {
         let mut result: c_int = 0;
         let mut error: c_int = 0;
 
@@ -155,21 +181,29 @@ impl HostFile {
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
         ensure!(
             result == 0,
             if error != 0 {
-                eos!(error)
+                Error::from_raw_os_error(error)
             } else {
-                esgx!(SgxStatus::CloseFailed)
+                Error::from_sgx_error(SgxStatus::CloseFailed)
             }
         );
         Ok(())
     }
 }
 
-impl HostFs for HostFile {
-    fn read(&mut self, number: u64, node: &mut dyn AsMut<[u8]>) -> FsResult {
+// The stream is only used through this handle.
+unsafe impl Send for HostFile {}
+
+impl StorageFile for HostFile {
+    #[inline]
+    fn size(&self) -> Result<usize> {
+        Ok(self.size)
+    }
+
+    fn read(&mut self, number: u64, node: &mut [u8]) -> Result<()> {
         let mut result: c_int = 0;
         let mut error: c_int = 0;
 
@@ -179,17 +213,20 @@ impl HostFs for HostFile {
                 &mut error as *mut c_int,
                 self.file,
                 number,
-                node.as_mut().as_mut_ptr(),
-                node.as_mut().len(),
+                node.as_mut_ptr(),
+                node.len(),
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
-        ensure!(result == 0, eos!(if error != 0 { error } else { EIO }));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
+        ensure!(
+            result == 0,
+            Error::from_raw_os_error(if error != 0 { error } else { EIO })
+        );
         Ok(())
     }
 
-    fn write(&mut self, number: u64, node: &dyn AsRef<[u8]>) -> FsResult {
+    fn write(&mut self, number: u64, node: &[u8]) -> Result<()> {
         let mut result: c_int = 0;
         let mut error: c_int = 0;
 
@@ -199,17 +236,20 @@ impl HostFs for HostFile {
                 &mut error as *mut c_int,
                 self.file,
                 number,
-                node.as_ref().as_ptr(),
-                node.as_ref().len(),
+                node.as_ptr(),
+                node.len(),
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
-        ensure!(result == 0, eos!(if error != 0 { error } else { EIO }));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
+        ensure!(
+            result == 0,
+            Error::from_raw_os_error(if error != 0 { error } else { EIO })
+        );
         Ok(())
     }
 
-    fn flush(&mut self) -> FsResult {
+    fn flush(&mut self) -> Result<()> {
         let mut result: c_int = 0;
         let mut error: c_int = 0;
 
@@ -221,8 +261,8 @@ impl HostFs for HostFile {
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
-        ensure!(result == 0, esgx!(SgxStatus::FluchFailed));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
+        ensure!(result == 0, Error::from_sgx_error(SgxStatus::FluchFailed));
         Ok(())
     }
 }
@@ -234,12 +274,12 @@ impl Drop for HostFile {
 }
 
 #[derive(Debug)]
-pub struct RecoveryFile {
+struct HostRecoveryFile {
     file: RawFileStream,
 }
 
-impl RecoveryFile {
-    pub fn open(name: &Path) -> FsResult<RecoveryFile> {
+impl HostRecoveryFile {
+    fn open(name: &Path) -> Result<HostRecoveryFile> {
         let mut file: RawFileStream = ptr::null_mut();
         let mut error: c_int = 0;
 
@@ -252,12 +292,15 @@ impl RecoveryFile {
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
-        ensure!(!file.is_null(), esgx!(SgxStatus::CantOpenRecoveryFile));
-        Ok(RecoveryFile { file })
+        ensure!(status.is_success(), Error::from_sgx_error(status));
+        ensure!(
+            !file.is_null(),
+            Error::from_sgx_error(SgxStatus::CantOpenRecoveryFile)
+        );
+        Ok(HostRecoveryFile { file })
     }
 
-    fn close(&mut self) -> FsResult {
+    fn close(&mut self) -> Result<()> {
         let mut result: c_int = 0;
         let mut error: c_int = 0;
 
@@ -269,25 +312,21 @@ impl RecoveryFile {
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
         ensure!(
             result == 0,
             if error != 0 {
-                eos!(error)
+                Error::from_raw_os_error(error)
             } else {
-                esgx!(SgxStatus::CloseFailed)
+                Error::from_sgx_error(SgxStatus::CloseFailed)
             }
         );
         Ok(())
     }
 }
 
-impl HostFs for RecoveryFile {
-    fn read(&mut self, _number: u64, _node: &mut dyn AsMut<[u8]>) -> FsResult {
-        bail!(eos!(ENOTSUP))
-    }
-
-    fn write(&mut self, _number: u64, node: &dyn AsRef<[u8]>) -> FsResult {
+impl RecoveryFile for HostRecoveryFile {
+    fn write(&mut self, node: &[u8]) -> Result<()> {
         let mut result: c_int = 0;
         let mut error: c_int = 0;
 
@@ -296,28 +335,27 @@ impl HostFs for RecoveryFile {
                 &mut result as *mut c_int,
                 &mut error as *mut c_int,
                 self.file,
-                node.as_ref().as_ptr(),
-                node.as_ref().len(),
+                node.as_ptr(),
+                node.len(),
             )
         };
 
-        ensure!(status.is_success(), esgx!(status));
-        ensure!(result == 0, esgx!(SgxStatus::CantWriteRecoveryFile));
+        ensure!(status.is_success(), Error::from_sgx_error(status));
+        ensure!(
+            result == 0,
+            Error::from_sgx_error(SgxStatus::CantWriteRecoveryFile)
+        );
         Ok(())
     }
-
-    fn flush(&mut self) -> FsResult {
-        bail!(eos!(ENOTSUP))
-    }
 }
 
-impl Drop for RecoveryFile {
+impl Drop for HostRecoveryFile {
     fn drop(&mut self) {
         let _ = self.close();
     }
 }
 
-pub fn try_exists(name: &Path) -> FsResult<bool> {
+fn try_exists(name: &Path) -> Result<bool> {
     let mut result: c_int = 0;
     let mut error: c_int = 0;
 
@@ -332,13 +370,13 @@ pub fn try_exists(name: &Path) -> FsResult<bool> {
         )
     };
 
-    ensure!(status.is_success(), esgx!(status));
-    ensure!(result >= 0, eos!(error));
+    ensure!(status.is_success(), Error::from_sgx_error(status));
+    ensure!(result >= 0, Error::from_raw_os_error(error));
 
     Ok(is_exists != 0)
 }
 
-pub fn remove(name: &Path) -> FsResult {
+fn remove(name: &Path) -> Result<()> {
     let mut result: c_int = 0;
     let mut error: c_int = 0;
 
@@ -351,12 +389,15 @@ pub fn remove(name: &Path) -> FsResult {
         )
     };
 
-    ensure!(status.is_success(), esgx!(status));
-    ensure!(result == 0, eos!(if error != 0 { error } else { EPERM }));
+    ensure!(status.is_success(), Error::from_sgx_error(status));
+    ensure!(
+        result == 0,
+        Error::from_raw_os_error(if error != 0 { error } else { EPERM })
+    );
     Ok(())
 }
 
-pub fn recovery(source: &Path, recovery: &Path) -> FsResult {
+fn recovery(source: &Path, recovery: &Path) -> Result<()> {
     let mut result: c_int = 0;
     let mut error: c_int = 0;
 
@@ -372,7 +413,10 @@ pub fn recovery(source: &Path, recovery: &Path) -> FsResult {
         )
     };
 
-    ensure!(status.is_success(), esgx!(status));
-    ensure!(result == 0, eos!(if error != 0 { error } else { EINVAL }));
+    ensure!(status.is_success(), Error::from_sgx_error(status));
+    ensure!(
+        result == 0,
+        Error::from_raw_os_error(if error != 0 { error } else { EINVAL })
+    );
     Ok(())
 }
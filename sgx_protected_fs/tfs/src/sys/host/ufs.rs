@@ -15,86 +15,60 @@
 // specific language governing permissions and limitations
 // under the License..
 
-use super::HostFs;
-use crate::sys::error::FsResult;
-use crate::sys::node::NODE_SIZE;
-use sgx_types::error::errno::*;
-use sgx_types::error::SgxStatus;
+use super::{HostStorage, RecoveryFile, StorageFile};
 use sgx_uprotected_fs as ufs;
+use std::boxed::Box;
+use std::io::{Error, Result};
 use std::path::Path;
 
-#[derive(Debug)]
-pub struct HostFile {
-    file: ufs::HostFile,
-    size: usize,
-}
-
-impl HostFile {
-    pub fn open(name: &Path, readonly: bool) -> FsResult<HostFile> {
-        let file = ufs::HostFile::open(name, readonly)?;
-        let size = file.size()?;
+/// Protected files on the host's file system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileStorage;
 
-        ensure!(
-            size <= i64::MAX as usize && size % NODE_SIZE == 0,
-            esgx!(SgxStatus::NotSgxFile)
-        );
-        Ok(HostFile { file, size })
+impl HostStorage for FileStorage {
+    fn open(&self, name: &Path, readonly: bool) -> Result<Box<dyn StorageFile>> {
+        let file = ufs::HostFile::open(name, readonly).map_err(Error::from_raw_os_error)?;
+        Ok(Box::new(file))
     }
 
-    #[inline]
-    pub fn size(&self) -> usize {
-        self.size
+    fn open_recovery(&self, name: &Path) -> Result<Box<dyn RecoveryFile>> {
+        let file = ufs::RecoveryFile::open(name).map_err(Error::from_raw_os_error)?;
+        Ok(Box::new(file))
     }
-}
 
-impl HostFs for HostFile {
-    fn read(&mut self, number: u64, node: &mut dyn AsMut<[u8]>) -> FsResult {
-        self.file.read(number, node.as_mut()).map_err(|e| eos!(e))
+    fn recovery(&self, source: &Path, recovery: &Path) -> Result<()> {
+        ufs::recovery(source, recovery).map_err(Error::from_raw_os_error)
     }
 
-    fn write(&mut self, number: u64, node: &dyn AsRef<[u8]>) -> FsResult {
-        self.file.write(number, node.as_ref()).map_err(|e| eos!(e))
+    fn try_exists(&self, name: &Path) -> Result<bool> {
+        ufs::try_exists(name).map_err(Error::from_raw_os_error)
     }
 
-    fn flush(&mut self) -> FsResult {
-        self.file.flush().map_err(|_| esgx!(SgxStatus::FluchFailed))
+    fn remove(&self, name: &Path) -> Result<()> {
+        ufs::remove(name).map_err(Error::from_raw_os_error)
     }
 }
 
-#[derive(Debug)]
-pub struct RecoveryFile {
-    file: ufs::RecoveryFile,
-}
-
-impl RecoveryFile {
-    pub fn open(name: &Path) -> FsResult<RecoveryFile> {
-        let file = ufs::RecoveryFile::open(name)?;
-        Ok(RecoveryFile { file })
+impl StorageFile for ufs::HostFile {
+    fn size(&self) -> Result<usize> {
+        ufs::HostFile::size(self).map_err(Error::from_raw_os_error)
     }
-}
 
-impl HostFs for RecoveryFile {
-    fn read(&mut self, _number: u64, _node: &mut dyn AsMut<[u8]>) -> FsResult {
-        bail!(eos!(ENOTSUP))
+    fn read(&mut self, number: u64, node: &mut [u8]) -> Result<()> {
+        ufs::HostFile::read(self, number, node).map_err(Error::from_raw_os_error)
     }
 
-    fn write(&mut self, _number: u64, node: &dyn AsRef<[u8]>) -> FsResult {
-        self.file.write(node.as_ref()).map_err(|e| eos!(e))
+    fn write(&mut self, number: u64, node: &[u8]) -> Result<()> {
+        ufs::HostFile::write(self, number, node).map_err(Error::from_raw_os_error)
     }
 
-    fn flush(&mut self) -> FsResult {
-        bail!(eos!(ENOTSUP))
+    fn flush(&mut self) -> Result<()> {
+        ufs::HostFile::flush(self).map_err(Error::from_raw_os_error)
     }
 }
 
-pub fn try_exists(name: &Path) -> FsResult<bool> {
-    ufs::try_exists(name).map_err(|e| eos!(e))
-}
-
-pub fn remove(name: &Path) -> FsResult {
-    ufs::remove(name).map_err(|e| eos!(e))
-}
-
-pub fn recovery(source: &Path, recovery: &Path) -> FsResult {
-    ufs::recovery(source, recovery).map_err(|e| eos!(e))
+impl RecoveryFile for ufs::RecoveryFile {
+    fn write(&mut self, node: &[u8]) -> Result<()> {
+        ufs::RecoveryFile::write(self, node).map_err(Error::from_raw_os_error)
+    }
 }
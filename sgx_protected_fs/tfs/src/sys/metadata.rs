@@ -15,12 +15,12 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::sys::cipher::NodeCipher;
 use crate::sys::error::FsResult;
 use crate::sys::file::OpenMode;
 use crate::sys::host::HostFs;
 use crate::sys::keys::{DeriveKey, KeyType, RestoreKey};
 use crate::sys::node::{META_DATA_PHY_NUM, NODE_SIZE};
-use sgx_crypto::aes::gcm::{Aad, AesGcm, Nonce};
 use sgx_types::error::SgxStatus;
 use sgx_types::types::{Attributes, CpuSvn, Key128bit, KeyId, KeyPolicy, Mac128bit};
 use std::ffi::CStr;
@@ -30,10 +30,32 @@ pub const SGX_FILE_ID: u64 = 0x5347_585F_4649_4C45;
 pub const SGX_FILE_MAJOR_VERSION: u8 = 0x01;
 pub const SGX_FILE_MINOR_VERSION: u8 = 0x00;
 
-#[derive(Copy, Clone, Debug, Default)]
+pub const MC_UUID_SIZE: usize = 16;
+
+/// The id of the monotonic counter a file is bound to.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
 #[repr(C)]
 pub struct McUuid {
-    mc_uuid: [u8; 16],
+    mc_uuid: [u8; MC_UUID_SIZE],
+}
+
+impl McUuid {
+    #[inline]
+    pub fn new(mc_uuid: [u8; MC_UUID_SIZE]) -> McUuid {
+        McUuid { mc_uuid }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.mc_uuid == [0_u8; MC_UUID_SIZE]
+    }
+}
+
+impl AsRef<[u8; MC_UUID_SIZE]> for McUuid {
+    #[inline]
+    fn as_ref(&self) -> &[u8; MC_UUID_SIZE] {
+        &self.mc_uuid
+    }
 }
 
 impl_enum! {
@@ -117,8 +139,11 @@ pub struct MetadataEncrypted {
     pub data: [u8; MD_USER_DATA_SIZE],
 }
 
-const METADATA_PADDING: usize =
-    NODE_SIZE - mem::size_of::<MetadataPlain>() - mem::size_of::<MetadataEncrypted>();
+const METADATA_CIPHER: usize = 2;
+const METADATA_PADDING: usize = NODE_SIZE
+    - mem::size_of::<MetadataPlain>()
+    - mem::size_of::<MetadataEncrypted>()
+    - METADATA_CIPHER;
 const METADATA_ENCRYPTED: usize = mem::size_of::<MetadataEncrypted>();
 
 #[derive(Clone, Copy, Debug)]
@@ -126,6 +151,8 @@ const METADATA_ENCRYPTED: usize = mem::size_of::<MetadataEncrypted>();
 pub struct Metadata {
     pub plaintext: MetadataPlain,
     pub ciphertext: [u8; METADATA_ENCRYPTED],
+    // the `NodeCipher` id, kept in the padding of the SGX SDK layout
+    pub cipher: [u8; METADATA_CIPHER],
     pub padding: [u8; METADATA_PADDING],
 }
 
@@ -134,6 +161,7 @@ impl Metadata {
         Metadata {
             plaintext: MetadataPlain::new(),
             ciphertext: [0_u8; METADATA_ENCRYPTED],
+            cipher: [0_u8; METADATA_CIPHER],
             padding: [0_u8; METADATA_PADDING],
         }
     }
@@ -210,6 +238,16 @@ impl MetadataInfo {
         self.node.metadata.plaintext.encrypt_flags = encrypt_flags;
     }
 
+    #[inline]
+    pub fn cipher(&self) -> u16 {
+        u16::from_le_bytes(self.node.metadata.cipher)
+    }
+
+    #[inline]
+    pub fn set_cipher(&mut self, cipher: u16) {
+        self.node.metadata.cipher = cipher.to_le_bytes();
+    }
+
     #[inline]
     pub fn key_policy(&self) -> KeyPolicy {
         self.node.metadata.plaintext.key_policy
@@ -247,16 +285,16 @@ impl MetadataInfo {
         Ok(name)
     }
 
-    pub fn encrypt(&mut self, key: &Key128bit) -> FsResult {
+    pub fn encrypt(&mut self, cipher: &NodeCipher, key: &Key128bit) -> FsResult {
         let mac = if !self.integrity_only() {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::empty())?;
-            aes.encrypt(
+            cipher.encrypt(
+                key,
+                &[],
                 self.encrypted_plain.as_ref(),
                 self.node.metadata.ciphertext.as_mut(),
             )?
         } else {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::from(&self.encrypted_plain))?;
-            let mac = aes.encrypt(&[], &mut [])?;
+            let mac = cipher.encrypt(key, self.encrypted_plain.as_ref(), &[], &mut [])?;
             self.node
                 .metadata
                 .ciphertext
@@ -270,21 +308,23 @@ impl MetadataInfo {
         Ok(())
     }
 
-    pub fn decrypt(&mut self, key: &Key128bit) -> FsResult {
+    pub fn decrypt(&mut self, cipher: &NodeCipher, key: &Key128bit) -> FsResult {
         if !self.integrity_only() {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::empty())?;
-            aes.decrypt(
+            cipher.decrypt(
+                key,
+                &[],
                 self.node.metadata.ciphertext.as_ref(),
                 self.encrypted_plain.as_mut(),
                 &self.node.metadata.plaintext.gmac,
             )?
         } else {
-            let mut aes = AesGcm::new(
+            cipher.decrypt(
                 key,
-                Nonce::zeroed(),
-                Aad::from(&self.node.metadata.ciphertext),
+                self.node.metadata.ciphertext.as_ref(),
+                &[],
+                &mut [],
+                &self.node.metadata.plaintext.gmac,
             )?;
-            aes.decrypt(&[], &mut [], &self.node.metadata.plaintext.gmac)?;
             self.encrypted_plain
                 .as_mut()
                 .copy_from_slice(self.node.metadata.ciphertext.as_ref());
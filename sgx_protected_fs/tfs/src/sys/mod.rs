@@ -15,8 +15,11 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::sys::counter::FileCounter;
 use crate::sys::error::FsError;
 use crate::sys::file::{self as file_imp, ProtectedFile};
+use crate::sys::host::Storage;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
 #[cfg(feature = "tfs")]
 use sgx_types::types::KeyPolicy;
 use sgx_types::types::{Key128bit, Mac128bit};
@@ -24,8 +27,15 @@ use std::boxed::Box;
 use std::io::{Result, SeekFrom};
 use std::mem::ManuallyDrop;
 use std::path::Path;
+use std::sync::Arc;
 
-pub use file::DEFAULT_CACHE_SIZE;
+pub use counter::{
+    CounterOp, CounterRequest, CounterResponse, CounterServer, CounterService, MemoryCounter,
+    MonotonicCounter, ServiceCounter,
+};
+pub use file::{CacheStats, DEFAULT_CACHE_SIZE, MIN_CACHE_SIZE};
+pub use host::{FileStorage, HostStorage, MemoryStorage, RecoveryFile, StorageFile};
+pub use metadata::{McUuid, MC_UUID_SIZE};
 
 #[macro_use]
 pub(crate) mod error;
@@ -33,6 +43,8 @@ pub(crate) mod error;
 mod node;
 
 mod cache;
+mod cipher;
+mod counter;
 mod file;
 mod host;
 mod keys;
@@ -73,6 +85,40 @@ impl OpenOptions {
     pub fn binary(&mut self, binary: bool) {
         self.0.binary = binary;
     }
+    #[inline]
+    pub fn cipher<C: Aead>(&mut self)
+    where
+        C::Key: FromKey128bit,
+    {
+        let opts = self.0.clone();
+        self.0 = opts.cipher::<C>();
+    }
+    #[inline]
+    pub fn counter(&mut self, counter: Arc<dyn MonotonicCounter>) {
+        let opts = self.0.clone();
+        self.0 = opts.counter(Some(FileCounter::new(counter)));
+    }
+    #[inline]
+    pub fn skip_rollback_check(&mut self, skip_rollback_check: bool) {
+        self.0.skip_rollback_check = skip_rollback_check;
+    }
+    #[inline]
+    pub fn storage(&mut self, storage: Arc<dyn HostStorage>) {
+        let opts = self.0.clone();
+        self.0 = opts.storage(Some(Storage::new(storage)));
+    }
+    #[inline]
+    pub fn cache_size(&mut self, cache_size: usize) {
+        self.0.cache_size = Some(cache_size);
+    }
+    #[inline]
+    pub fn read_ahead(&mut self, read_ahead: usize) {
+        self.0.read_ahead = read_ahead;
+    }
+    #[inline]
+    pub fn host_storage(&self) -> &dyn HostStorage {
+        self.0.host_storage()
+    }
 
     #[allow(dead_code)]
     pub fn check(&self) -> Result<()> {
@@ -192,6 +238,11 @@ impl SgxFile {
         self.file.get_error()
     }
 
+    #[inline]
+    pub fn cache_stats(&self) -> CacheStats {
+        self.file.cache_stats()
+    }
+
     #[inline]
     pub fn clear_cache(&self) -> Result<()> {
         self.file.clear_cache().map_err(|e| {
@@ -249,8 +300,20 @@ impl Drop for SgxFile {
 }
 
 #[inline]
-pub fn remove<P: AsRef<Path>>(path: P) -> Result<()> {
-    ProtectedFile::remove(path).map_err(|e| {
+pub fn remove<P: AsRef<Path>>(path: P, opts: &OpenOptions) -> Result<()> {
+    ProtectedFile::remove(path, &opts.0).map_err(|e| {
+        e.set_errno();
+        e.to_io_error()
+    })
+}
+
+#[inline]
+pub fn remove_with<P: AsRef<Path>>(
+    path: P,
+    opts: &OpenOptions,
+    encrypt_mode: &EncryptMode,
+) -> Result<()> {
+    ProtectedFile::remove_with(path, &opts.0, &encrypt_mode.into()).map_err(|e| {
         e.set_errno();
         e.to_io_error()
     })
@@ -258,8 +321,8 @@ pub fn remove<P: AsRef<Path>>(path: P) -> Result<()> {
 
 #[cfg(feature = "tfs")]
 #[inline]
-pub fn export_key<P: AsRef<Path>>(path: P) -> Result<Key128bit> {
-    ProtectedFile::export_key(path).map_err(|e| {
+pub fn export_key<P: AsRef<Path>>(path: P, opts: &OpenOptions) -> Result<Key128bit> {
+    ProtectedFile::export_key(path, &opts.0).map_err(|e| {
         e.set_errno();
         e.to_io_error()
     })
@@ -269,10 +332,11 @@ pub fn export_key<P: AsRef<Path>>(path: P) -> Result<Key128bit> {
 #[inline]
 pub fn import_key<P: AsRef<Path>>(
     path: P,
+    opts: &OpenOptions,
     key: Key128bit,
     key_policy: Option<KeyPolicy>,
 ) -> Result<()> {
-    ProtectedFile::import_key(path, key, key_policy).map_err(|e| {
+    ProtectedFile::import_key(path, &opts.0, key, key_policy).map_err(|e| {
         e.set_errno();
         e.to_io_error()
     })
@@ -16,11 +16,11 @@
 // under the License..
 
 use crate::sys::cache::NodeRef;
+use crate::sys::cipher::NodeCipher;
 use crate::sys::error::FsResult;
 use crate::sys::host::HostFs;
 use crate::sys::keys::{DeriveKey, KeyType};
 use crate::sys::metadata::EncryptFlags;
-use sgx_crypto::aes::gcm::{Aad, AesGcm, Nonce};
 use sgx_types::error::SgxStatus;
 use sgx_types::types::{Key128bit, Mac128bit};
 use std::cell::RefCell;
@@ -262,7 +262,7 @@ impl FileNode {
         Self::new_ref(NodeType::Mht, 0, ROOT_MHT_PHY_NUM, encrypt_flags)
     }
 
-    pub fn encrypt(&mut self, key: &Key128bit) -> FsResult<Mac128bit> {
+    pub fn encrypt(&mut self, cipher: &NodeCipher, key: &Key128bit) -> FsResult<Mac128bit> {
         let parent = if !self.is_root_mht() {
             let parent = self.parent.as_ref().ok_or(SgxStatus::Unexpected)?;
             Some(parent)
@@ -271,11 +271,14 @@ impl FileNode {
         };
 
         let mac = if !self.encrypt_flags.is_integrity_only() {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::empty())?;
-            aes.encrypt(self.plaintext.as_ref(), self.ciphertext.node_data.as_mut())?
+            cipher.encrypt(
+                key,
+                &[],
+                self.plaintext.as_ref(),
+                self.ciphertext.node_data.as_mut(),
+            )?
         } else {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::from(&self.plaintext))?;
-            let mac = aes.encrypt(&[], &mut [])?;
+            let mac = cipher.encrypt(key, self.plaintext.as_ref(), &[], &mut [])?;
             self.ciphertext
                 .node_data
                 .as_mut()
@@ -298,17 +301,17 @@ impl FileNode {
         Ok(mac)
     }
 
-    pub fn decrypt(&mut self, key: &Key128bit, mac: &Mac128bit) -> FsResult {
+    pub fn decrypt(&mut self, cipher: &NodeCipher, key: &Key128bit, mac: &Mac128bit) -> FsResult {
         if !self.encrypt_flags.is_integrity_only() {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::empty())?;
-            aes.decrypt(
+            cipher.decrypt(
+                key,
+                &[],
                 self.ciphertext.node_data.as_ref(),
                 self.plaintext.as_mut(),
                 mac,
             )?
         } else {
-            let mut aes = AesGcm::new(key, Nonce::zeroed(), Aad::from(&self.ciphertext.node_data))?;
-            aes.decrypt(&[], &mut [], mac)?;
+            cipher.decrypt(key, self.ciphertext.node_data.as_ref(), &[], &mut [], mac)?;
             self.plaintext
                 .as_mut()
                 .copy_from_slice(self.ciphertext.node_data.as_ref());
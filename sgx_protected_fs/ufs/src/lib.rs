@@ -145,6 +145,9 @@ impl HostFile {
     }
 }
 
+// The stream and the descriptor are owned by the file and only used through it.
+unsafe impl Send for HostFile {}
+
 impl Drop for HostFile {
     fn drop(&mut self) {
         unsafe {
@@ -18,8 +18,9 @@
 use crate::{Decodable, Decoder, Encodable, Encoder};
 use sgx_types::types::EnclaveIdentity;
 use sgx_types::types::{
-    AlignEc256PrivateKey, AlignEc256SharedKey, AlignKey128bit, AlignKey256bit, AlignMac128bit,
-    AlignMac256bit, Key128bit, Key256bit, Mac128bit, Mac256bit,
+    AlignEc256PrivateKey, AlignEc256SharedKey, AlignEc384PrivateKey, AlignEc384SharedKey,
+    AlignKey128bit, AlignKey256bit, AlignMac128bit, AlignMac256bit, Key128bit, Key256bit,
+    Mac128bit, Mac256bit,
 };
 use sgx_types::types::{
     Attributes, AttributesFlags, ConfigId, CpuSvn, KeyId, KeyName, KeyPolicy, KeyRequest,
@@ -29,9 +30,11 @@ use sgx_types::types::{
 };
 use sgx_types::types::{BaseName, PsSecPropDesc, QuoteNonce, Spid};
 use sgx_types::types::{Ec256PrivateKey, Ec256PublicKey, Ec256SharedKey, Ec256Signature};
+use sgx_types::types::{Ec384PrivateKey, Ec384PublicKey, Ec384SharedKey, Ec384Signature};
 use sgx_types::types::{
     Rsa2048Key, Rsa2048Param, Rsa2048PrivKey, Rsa2048PubKey, Rsa2048Signature, Rsa3072Key,
-    Rsa3072Param, Rsa3072PrivKey, Rsa3072PubKey, Rsa3072Signature, RsaKeyType, RsaResult,
+    Rsa3072Param, Rsa3072PrivKey, Rsa3072PubKey, Rsa3072Signature, Rsa4096Key, Rsa4096Param,
+    Rsa4096PrivKey, Rsa4096PubKey, Rsa4096Signature, RsaHash, RsaKeyType, RsaResult,
 };
 use sgx_types::types::{Sha1Hash, Sha256Hash, Sha384Hash, Sm3Hash};
 
@@ -197,6 +200,92 @@ impl Decodable for Ec256Signature {
     }
 }
 
+impl Encodable for Ec384SharedKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Ec384SharedKey { s: ref _s } = *self;
+        e.emit_struct("Ec384SharedKey", 1usize, |e| -> _ {
+            e.emit_struct_field("s", 0usize, |e| -> _ { Encodable::encode(&*_s, e) })
+        })
+    }
+}
+
+impl Decodable for Ec384SharedKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Ec384SharedKey, D::Error> {
+        d.read_struct("Ec384SharedKey", 1usize, |d| -> _ {
+            Ok(Ec384SharedKey {
+                s: d.read_struct_field("s", 0usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Ec384PrivateKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Ec384PrivateKey { r: ref _r } = *self;
+        e.emit_struct("Ec384PrivateKey", 1usize, |e| -> _ {
+            e.emit_struct_field("r", 0usize, |e| -> _ { Encodable::encode(&*_r, e) })
+        })
+    }
+}
+
+impl Decodable for Ec384PrivateKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Ec384PrivateKey, D::Error> {
+        d.read_struct("Ec384PrivateKey", 1usize, |d| -> _ {
+            Ok(Ec384PrivateKey {
+                r: d.read_struct_field("r", 0usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Ec384PublicKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Ec384PublicKey {
+            gx: ref _gx,
+            gy: ref _gy,
+        } = *self;
+        e.emit_struct("Ec384PublicKey", 2usize, |e| -> _ {
+            e.emit_struct_field("gx", 0usize, |e| -> _ { Encodable::encode(&*_gx, e) })?;
+            e.emit_struct_field("gy", 1usize, |e| -> _ { Encodable::encode(&*_gy, e) })
+        })
+    }
+}
+
+impl Decodable for Ec384PublicKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Ec384PublicKey, D::Error> {
+        d.read_struct("Ec384PublicKey", 2usize, |d| -> _ {
+            Ok(Ec384PublicKey {
+                gx: d.read_struct_field("gx", 0usize, Decodable::decode)?,
+                gy: d.read_struct_field("gy", 1usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Ec384Signature {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Ec384Signature {
+            x: ref _x,
+            y: ref _y,
+        } = *self;
+        e.emit_struct("Ec384Signature", 2usize, |e| -> _ {
+            e.emit_struct_field("x", 0usize, |e| -> _ { Encodable::encode(&*_x, e) })?;
+            e.emit_struct_field("y", 1usize, |e| -> _ { Encodable::encode(&*_y, e) })
+        })
+    }
+}
+
+impl Decodable for Ec384Signature {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Ec384Signature, D::Error> {
+        d.read_struct("Ec384Signature", 2usize, |d| -> _ {
+            Ok(Ec384Signature {
+                x: d.read_struct_field("x", 0usize, Decodable::decode)?,
+                y: d.read_struct_field("y", 1usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
 impl Encodable for RsaKeyType {
     fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
         match *self {
@@ -249,6 +338,183 @@ impl Decodable for RsaResult {
     }
 }
 
+impl Encodable for RsaHash {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        match *self {
+            RsaHash::Sha256 => e.emit_enum("RsaHash", |e| -> _ {
+                e.emit_enum_variant("Sha256", 0usize, 0usize, |_e| -> _ { Ok(()) })
+            }),
+            RsaHash::Sha384 => e.emit_enum("RsaHash", |e| -> _ {
+                e.emit_enum_variant("Sha384", 1usize, 0usize, |_e| -> _ { Ok(()) })
+            }),
+            RsaHash::Sha512 => e.emit_enum("RsaHash", |e| -> _ {
+                e.emit_enum_variant("Sha512", 2usize, 0usize, |_e| -> _ { Ok(()) })
+            }),
+        }
+    }
+}
+impl Decodable for RsaHash {
+    fn decode<D: Decoder>(d: &mut D) -> Result<RsaHash, D::Error> {
+        d.read_enum("RsaHash", |d| -> _ {
+            d.read_enum_variant(&["Sha256", "Sha384", "Sha512"], |_d, i| -> _ {
+                Ok(match i {
+                    0usize => RsaHash::Sha256,
+                    1usize => RsaHash::Sha384,
+                    2usize => RsaHash::Sha512,
+                    _ => panic!("internal error: entered unreachable code"),
+                })
+            })
+        })
+    }
+}
+
+impl Encodable for Rsa4096Param {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Rsa4096Param {
+            n: ref _n,
+            d: ref _d,
+            e: ref _e,
+            p: ref _p,
+            q: ref _q,
+            dmp1: ref _dmp1,
+            dmq1: ref _dmq1,
+            iqmp: ref _iqmp,
+        } = *self;
+        e.emit_struct("Rsa4096Param", 8usize, |e| -> _ {
+            e.emit_struct_field("n", 0usize, |e| -> _ { Encodable::encode(&*_n, e) })?;
+            e.emit_struct_field("d", 1usize, |e| -> _ { Encodable::encode(&*_d, e) })?;
+            e.emit_struct_field("e", 2usize, |e| -> _ { Encodable::encode(&*_e, e) })?;
+            e.emit_struct_field("p", 3usize, |e| -> _ { Encodable::encode(&*_p, e) })?;
+            e.emit_struct_field("q", 4usize, |e| -> _ { Encodable::encode(&*_q, e) })?;
+            e.emit_struct_field("dmp1", 5usize, |e| -> _ { Encodable::encode(&*_dmp1, e) })?;
+            e.emit_struct_field("dmq1", 6usize, |e| -> _ { Encodable::encode(&*_dmq1, e) })?;
+            e.emit_struct_field("iqmp", 7usize, |e| -> _ { Encodable::encode(&*_iqmp, e) })
+        })
+    }
+}
+
+impl Decodable for Rsa4096Param {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Rsa4096Param, D::Error> {
+        d.read_struct("Rsa4096Param", 8usize, |d| -> _ {
+            Ok(Rsa4096Param {
+                n: d.read_struct_field("n", 0usize, Decodable::decode)?,
+                d: d.read_struct_field("d", 1usize, Decodable::decode)?,
+                e: d.read_struct_field("e", 2usize, Decodable::decode)?,
+                p: d.read_struct_field("p", 3usize, Decodable::decode)?,
+                q: d.read_struct_field("q", 4usize, Decodable::decode)?,
+                dmp1: d.read_struct_field("dmp1", 5usize, Decodable::decode)?,
+                dmq1: d.read_struct_field("dmq1", 6usize, Decodable::decode)?,
+                iqmp: d.read_struct_field("iqmp", 7usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Rsa4096PubKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Rsa4096PubKey {
+            modulus: ref _modulus,
+            exponent: ref _exponent,
+        } = *self;
+        e.emit_struct("Rsa4096PubKey", 2usize, |e| -> _ {
+            e.emit_struct_field("modulus", 0usize, |e| -> _ {
+                Encodable::encode(&*_modulus, e)
+            })?;
+            e.emit_struct_field("exponent", 1usize, |e| -> _ {
+                Encodable::encode(&*_exponent, e)
+            })
+        })
+    }
+}
+impl Decodable for Rsa4096PubKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Rsa4096PubKey, D::Error> {
+        d.read_struct("Rsa4096PubKey", 2usize, |d| -> _ {
+            Ok(Rsa4096PubKey {
+                modulus: d.read_struct_field("modulus", 0usize, Decodable::decode)?,
+                exponent: d.read_struct_field("exponent", 1usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Rsa4096PrivKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Rsa4096PrivKey {
+            modulus: ref _modulus,
+            exponent: ref _exponent,
+        } = *self;
+        e.emit_struct("Rsa4096PrivKey", 2usize, |e| -> _ {
+            e.emit_struct_field("modulus", 0usize, |e| -> _ {
+                Encodable::encode(&*_modulus, e)
+            })?;
+            e.emit_struct_field("exponent", 1usize, |e| -> _ {
+                Encodable::encode(&*_exponent, e)
+            })
+        })
+    }
+}
+impl Decodable for Rsa4096PrivKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Rsa4096PrivKey, D::Error> {
+        d.read_struct("Rsa4096PrivKey", 2usize, |d| -> _ {
+            Ok(Rsa4096PrivKey {
+                modulus: d.read_struct_field("modulus", 0usize, Decodable::decode)?,
+                exponent: d.read_struct_field("exponent", 1usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Rsa4096Key {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Rsa4096Key {
+            modulus: ref _modulus,
+            d: ref _d,
+            e: ref _e,
+        } = *self;
+        e.emit_struct("Rsa4096Key", 3usize, |e| -> _ {
+            e.emit_struct_field("modulus", 0usize, |e| -> _ {
+                Encodable::encode(&*_modulus, e)
+            })?;
+            e.emit_struct_field("d", 1usize, |e| -> _ { Encodable::encode(&*_d, e) })?;
+            e.emit_struct_field("e", 2usize, |e| -> _ { Encodable::encode(&*_e, e) })
+        })
+    }
+}
+impl Decodable for Rsa4096Key {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Rsa4096Key, D::Error> {
+        d.read_struct("Rsa4096Key", 3usize, |d| -> _ {
+            Ok(Rsa4096Key {
+                modulus: d.read_struct_field("modulus", 0usize, Decodable::decode)?,
+                d: d.read_struct_field("d", 1usize, Decodable::decode)?,
+                e: d.read_struct_field("e", 2usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
+impl Encodable for Rsa4096Signature {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let Rsa4096Signature {
+            signature: ref _signature,
+        } = *self;
+        e.emit_struct("Rsa4096Signature", 1usize, |e| -> _ {
+            e.emit_struct_field("signature", 0usize, |e| -> _ {
+                Encodable::encode(&*_signature, e)
+            })
+        })
+    }
+}
+
+impl Decodable for Rsa4096Signature {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Rsa4096Signature, D::Error> {
+        d.read_struct("Rsa4096Signature", 1usize, |d| -> _ {
+            Ok(Rsa4096Signature {
+                signature: d.read_struct_field("signature", 0usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
 impl Encodable for Rsa3072Param {
     fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
         let Rsa3072Param {
@@ -669,6 +935,48 @@ impl Decodable for AlignEc256PrivateKey {
     }
 }
 
+impl Encodable for AlignEc384SharedKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let key = &self.key;
+        e.emit_struct("AlignEc384SharedKey", 1usize, |e| -> _ {
+            e.emit_struct_field("key", 0usize, |e| -> _ { Encodable::encode(key, e) })
+        })
+    }
+}
+
+impl Decodable for AlignEc384SharedKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<AlignEc384SharedKey, D::Error> {
+        d.read_struct("AlignEc384SharedKey", 1usize, |d| -> _ {
+            Ok(From::<Ec384SharedKey>::from(d.read_struct_field(
+                "key",
+                0usize,
+                Decodable::decode,
+            )?))
+        })
+    }
+}
+
+impl Encodable for AlignEc384PrivateKey {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let key = &self.key;
+        e.emit_struct("AlignEc384PrivateKey", 1usize, |e| -> _ {
+            e.emit_struct_field("key", 0usize, |e| -> _ { Encodable::encode(key, e) })
+        })
+    }
+}
+
+impl Decodable for AlignEc384PrivateKey {
+    fn decode<D: Decoder>(d: &mut D) -> Result<AlignEc384PrivateKey, D::Error> {
+        d.read_struct("AlignEc384PrivateKey", 1usize, |d| -> _ {
+            Ok(From::<Ec384PrivateKey>::from(d.read_struct_field(
+                "key",
+                0usize,
+                Decodable::decode,
+            )?))
+        })
+    }
+}
+
 impl Encodable for AttributesFlags {
     fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
         let bits = self.bits();
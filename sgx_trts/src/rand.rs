@@ -144,6 +144,35 @@ impl Default for Rng {
     }
 }
 
+#[cfg(feature = "rustcrypto")]
+impl rand_core::RngCore for Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        Rng::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Rng::next_u64(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Rng::fill_bytes(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        // CUSTOM_START is non-zero, so the code is too.
+        rand(dest).map_err(|e| {
+            let code = rand_core::Error::CUSTOM_START | e as u32;
+            rand_core::Error::from(core::num::NonZeroU32::new(code).unwrap())
+        })
+    }
+}
+
+#[cfg(feature = "rustcrypto")]
+impl rand_core::CryptoRng for Rng {}
+
 fn next_u32(fill_buf: &mut dyn FnMut(&mut [u8])) -> u32 {
     let mut buf = [0_u8; 4];
     fill_buf(&mut buf);
@@ -0,0 +1,299 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::se::{EnclaveKey, EnclaveReport};
+use sgx_crypto::kdf::HkdfSha256;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{
+    AlignKey128bit, Attributes, AttributesFlags, CpuSvn, KeyId, KeyName, KeyPolicy, KeyRequest,
+    Report, TSEAL_DEFAULT_MISCMASK,
+};
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+const HIERARCHY_KEY_ID: &[u8] = b"sgx_tse key hierarchy";
+
+/// Builds a `KeyRequest` for a seal, report or provisioning key.
+///
+/// Fields that are not set default to the values of the enclave's own
+/// report. `build` checks the request against that report, so a request that
+/// EGETKEY would reject, or that silently names a key the enclave cannot
+/// get, fails early with SgxStatus::InvalidParameter or
+/// SgxStatus::InvalidIsvsvn.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyRequestBuilder {
+    key_name: KeyName,
+    key_policy: KeyPolicy,
+    isv_svn: Option<u16>,
+    cpu_svn: Option<CpuSvn>,
+    config_svn: Option<u16>,
+    attribute_mask: Attributes,
+    misc_mask: u32,
+    key_id: KeyId,
+}
+
+impl KeyRequestBuilder {
+    /// A seal key bound to MRSIGNER, and to the KSS identity when the enclave
+    /// has KSS enabled.
+    pub fn seal() -> KeyRequestBuilder {
+        let policy = if Self::kss_enabled(Report::get_self()) {
+            KeyPolicy::MRSIGNER | KeyPolicy::KSS
+        } else {
+            KeyPolicy::MRSIGNER
+        };
+        Self::new(KeyName::Seal, policy)
+    }
+
+    /// The key used to verify reports targeted at this enclave.
+    pub fn report() -> KeyRequestBuilder {
+        Self::new(KeyName::Report, KeyPolicy::empty())
+    }
+
+    /// Requires the PROVISIONKEY attribute.
+    pub fn provision() -> KeyRequestBuilder {
+        Self::new(KeyName::Provision, KeyPolicy::MRSIGNER)
+    }
+
+    /// Requires the PROVISIONKEY attribute.
+    pub fn provision_seal() -> KeyRequestBuilder {
+        Self::new(KeyName::ProvisionSeal, KeyPolicy::MRSIGNER)
+    }
+
+    fn new(key_name: KeyName, key_policy: KeyPolicy) -> KeyRequestBuilder {
+        KeyRequestBuilder {
+            key_name,
+            key_policy,
+            isv_svn: None,
+            cpu_svn: None,
+            config_svn: None,
+            attribute_mask: Attributes {
+                flags: AttributesFlags::DEFAULT_MASK,
+                xfrm: 0,
+            },
+            misc_mask: TSEAL_DEFAULT_MISCMASK,
+            key_id: KeyId::default(),
+        }
+    }
+
+    #[inline]
+    pub fn key_policy(mut self, key_policy: KeyPolicy) -> KeyRequestBuilder {
+        self.key_policy = key_policy;
+        self
+    }
+
+    /// Requests the key of an older ISVSVN.
+    #[inline]
+    pub fn isv_svn(mut self, isv_svn: u16) -> KeyRequestBuilder {
+        self.isv_svn = Some(isv_svn);
+        self
+    }
+
+    /// Requests the key of an older CPUSVN.
+    #[inline]
+    pub fn cpu_svn(mut self, cpu_svn: CpuSvn) -> KeyRequestBuilder {
+        self.cpu_svn = Some(cpu_svn);
+        self
+    }
+
+    /// Requests the key of an older CONFIGSVN. Requires KSS.
+    #[inline]
+    pub fn config_svn(mut self, config_svn: u16) -> KeyRequestBuilder {
+        self.config_svn = Some(config_svn);
+        self
+    }
+
+    #[inline]
+    pub fn attribute_mask(mut self, attribute_mask: Attributes) -> KeyRequestBuilder {
+        self.attribute_mask = attribute_mask;
+        self
+    }
+
+    #[inline]
+    pub fn misc_mask(mut self, misc_mask: u32) -> KeyRequestBuilder {
+        self.misc_mask = misc_mask;
+        self
+    }
+
+    #[inline]
+    pub fn key_id(mut self, key_id: KeyId) -> KeyRequestBuilder {
+        self.key_id = key_id;
+        self
+    }
+
+    #[inline]
+    pub fn key_name(&self) -> KeyName {
+        self.key_name
+    }
+
+    pub fn build(&self) -> SgxResult<KeyRequest> {
+        let report = Report::get_self();
+        let kss = Self::kss_enabled(report);
+
+        ensure!(self.key_policy.is_valid(), SgxStatus::InvalidParameter);
+        ensure!(
+            kss || !self
+                .key_policy
+                .intersects(KeyPolicy::KSS | KeyPolicy::NOISVPRODID),
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            kss || self.config_svn.is_none(),
+            SgxStatus::InvalidParameter
+        );
+
+        match self.key_name {
+            KeyName::Report => {
+                // The report key only depends on the key id.
+                ensure!(self.key_policy.is_empty(), SgxStatus::InvalidParameter);
+                return Ok(KeyRequest {
+                    key_name: KeyName::Report,
+                    key_id: self.key_id,
+                    ..Default::default()
+                });
+            }
+            KeyName::Seal => ensure!(
+                self.key_policy
+                    .intersects(KeyPolicy::MRENCLAVE | KeyPolicy::MRSIGNER),
+                SgxStatus::InvalidParameter
+            ),
+            KeyName::Provision | KeyName::ProvisionSeal => ensure!(
+                report
+                    .body
+                    .attributes
+                    .flags
+                    .contains(AttributesFlags::PROVISIONKEY),
+                SgxStatus::InvalidParameter
+            ),
+            KeyName::EInitToken => bail!(SgxStatus::InvalidParameter),
+        }
+
+        ensure!(
+            self.attribute_mask
+                .flags
+                .contains(AttributesFlags::INITTED | AttributesFlags::DEBUG),
+            SgxStatus::InvalidParameter
+        );
+
+        let isv_svn = self.isv_svn.unwrap_or(report.body.isv_svn);
+        let config_svn = self.config_svn.unwrap_or(report.body.config_svn);
+        ensure!(
+            isv_svn <= report.body.isv_svn && config_svn <= report.body.config_svn,
+            SgxStatus::InvalidIsvsvn
+        );
+
+        Ok(KeyRequest {
+            key_name: self.key_name,
+            key_policy: self.key_policy,
+            isv_svn,
+            cpu_svn: self.cpu_svn.unwrap_or(report.body.cpu_svn),
+            attribute_mask: self.attribute_mask,
+            key_id: self.key_id,
+            misc_mask: self.misc_mask,
+            config_svn,
+            ..Default::default()
+        })
+    }
+
+    #[inline]
+    pub fn get_align_key(&self) -> SgxResult<AlignKey128bit> {
+        self.build()?.get_align_key()
+    }
+
+    #[inline]
+    fn kss_enabled(report: &Report) -> bool {
+        report
+            .body
+            .attributes
+            .flags
+            .intersects(AttributesFlags::KSS)
+    }
+}
+
+/// Application keys derived from a seal key, so the raw EGETKEY output never
+/// leaves this type.
+///
+/// Every epoch has its own root key, named by a key id that holds the epoch.
+/// A subkey is derived from the root key of its epoch with HKDF-SHA256, with
+/// the subkey name as info. Moving to a new epoch rotates every subkey, while
+/// the keys of earlier epochs can still be derived to migrate data.
+///
+/// The seal key depends on the security versions it is requested for, so
+/// they are pinned: the subkeys stay the same after an ISVSVN, CONFIGSVN or
+/// CPUSVN upgrade. Store the versions the hierarchy is first used under, e.g.
+/// from `Report::get_self()`, and pass them on every later start.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyHierarchy {
+    builder: KeyRequestBuilder,
+}
+
+impl KeyHierarchy {
+    /// Uses the default seal key policy. `config_svn` is ignored unless the
+    /// enclave has KSS enabled.
+    pub fn new(isv_svn: u16, config_svn: u16, cpu_svn: CpuSvn) -> SgxResult<KeyHierarchy> {
+        let mut builder = KeyRequestBuilder::seal().isv_svn(isv_svn).cpu_svn(cpu_svn);
+        if KeyRequestBuilder::kss_enabled(Report::get_self()) {
+            builder = builder.config_svn(config_svn);
+        }
+        Self::with_builder(builder)
+    }
+
+    /// Uses the seal key policy, masks and security versions of `builder`,
+    /// which must pin ISVSVN and CPUSVN, and CONFIGSVN with KSS. Its key id
+    /// is replaced by the one of each epoch.
+    pub fn with_builder(builder: KeyRequestBuilder) -> SgxResult<KeyHierarchy> {
+        ensure!(
+            builder.key_name() == KeyName::Seal,
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            builder.isv_svn.is_some() && builder.cpu_svn.is_some(),
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            builder.config_svn.is_some() || !KeyRequestBuilder::kss_enabled(Report::get_self()),
+            SgxStatus::InvalidParameter
+        );
+        builder.build()?;
+        Ok(KeyHierarchy { builder })
+    }
+
+    /// Derives the 128-bit subkey `name` of the epoch.
+    pub fn derive_key(&self, name: &str, epoch: u32) -> SgxResult<AlignKey128bit> {
+        let mut key = AlignKey128bit::default();
+        self.derive(name, epoch, key.as_mut())?;
+        Ok(key)
+    }
+
+    /// Derives the subkey `name` of the epoch into `okm`, which may be of any
+    /// length HKDF-SHA256 supports.
+    pub fn derive(&self, name: &str, epoch: u32, okm: &mut [u8]) -> SgxResult {
+        ensure!(!name.is_empty(), SgxStatus::InvalidParameter);
+
+        let mut key_id = KeyId::default();
+        key_id.id[..HIERARCHY_KEY_ID.len()].copy_from_slice(HIERARCHY_KEY_ID);
+        key_id.id[28..].copy_from_slice(&epoch.to_le_bytes());
+
+        let mut root = self.builder.key_id(key_id).get_align_key()?;
+        let result = HkdfSha256::derive(&[], root.as_ref(), name.as_bytes(), okm);
+        root.as_mut().fill(0);
+        if result.is_err() {
+            okm.fill(0);
+        }
+        result
+    }
+}
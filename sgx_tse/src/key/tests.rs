@@ -0,0 +1,170 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{KeyHierarchy, KeyRequestBuilder};
+use crate::se::EnclaveReport;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::{Attributes, AttributesFlags, KeyName, KeyPolicy, Report};
+
+fn pinned() -> KeyHierarchy {
+    let body = &Report::get_self().body;
+    KeyHierarchy::new(body.isv_svn, body.config_svn, body.cpu_svn).unwrap()
+}
+
+#[test_case]
+fn key_request_builder_defaults() {
+    let body = &Report::get_self().body;
+    let key_request = KeyRequestBuilder::seal().build().unwrap();
+    assert_eq!(key_request.key_name, KeyName::Seal);
+    assert!(key_request.key_policy.contains(KeyPolicy::MRSIGNER));
+    assert_eq!(key_request.isv_svn, body.isv_svn);
+    assert_eq!(key_request.cpu_svn, body.cpu_svn);
+    assert_eq!(key_request.config_svn, body.config_svn);
+
+    let key_request = KeyRequestBuilder::report().build().unwrap();
+    assert_eq!(key_request.key_name, KeyName::Report);
+    assert!(key_request.key_policy.is_empty());
+}
+
+#[test_case]
+fn key_request_builder_rejects() {
+    let body = &Report::get_self().body;
+    let kss = body.attributes.flags.intersects(AttributesFlags::KSS);
+    let seal = KeyRequestBuilder::seal();
+
+    // neither MRENCLAVE nor MRSIGNER
+    let builder = seal.key_policy(KeyPolicy::empty());
+    assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+    // the report key has no policy
+    let builder = KeyRequestBuilder::report().key_policy(KeyPolicy::MRSIGNER);
+    assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+    // EINITTOKEN keys are for the launch enclave only
+    let builder = KeyRequestBuilder::new(KeyName::EInitToken, KeyPolicy::MRSIGNER);
+    assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+
+    // the mask must keep INITTED and DEBUG
+    let builder = seal.attribute_mask(Attributes {
+        flags: AttributesFlags::INITTED,
+        xfrm: 0,
+    });
+    assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+
+    // no key of a version newer than the enclave
+    if body.isv_svn < u16::MAX {
+        let builder = seal.isv_svn(body.isv_svn + 1);
+        assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidIsvsvn);
+    }
+    if kss && body.config_svn < u16::MAX {
+        let builder = seal.config_svn(body.config_svn + 1);
+        assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidIsvsvn);
+    }
+
+    if !kss {
+        let builder = seal.config_svn(body.config_svn);
+        assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+        let builder = seal.key_policy(KeyPolicy::MRSIGNER | KeyPolicy::KSS);
+        assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+    }
+    if !body
+        .attributes
+        .flags
+        .contains(AttributesFlags::PROVISIONKEY)
+    {
+        let builder = KeyRequestBuilder::provision();
+        assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+        let builder = KeyRequestBuilder::provision_seal();
+        assert_eq!(builder.build().unwrap_err(), SgxStatus::InvalidParameter);
+    }
+}
+
+#[test_case]
+fn key_hierarchy_requires_pinned_svns() {
+    let body = &Report::get_self().body;
+    let kss = body.attributes.flags.intersects(AttributesFlags::KSS);
+    let seal = KeyRequestBuilder::seal();
+
+    for builder in [
+        seal,
+        seal.isv_svn(body.isv_svn),
+        seal.cpu_svn(body.cpu_svn),
+        KeyRequestBuilder::report(),
+    ] {
+        assert_eq!(
+            KeyHierarchy::with_builder(builder).unwrap_err(),
+            SgxStatus::InvalidParameter
+        );
+    }
+
+    let builder = seal.isv_svn(body.isv_svn).cpu_svn(body.cpu_svn);
+    let builder = if kss {
+        assert!(KeyHierarchy::with_builder(builder).is_err());
+        builder.config_svn(body.config_svn)
+    } else {
+        builder
+    };
+    let hierarchy = KeyHierarchy::with_builder(builder).unwrap();
+    assert_eq!(
+        hierarchy.derive_key("key", 0).unwrap(),
+        pinned().derive_key("key", 0).unwrap()
+    );
+
+    if body.isv_svn < u16::MAX {
+        assert_eq!(
+            KeyHierarchy::new(body.isv_svn + 1, body.config_svn, body.cpu_svn).unwrap_err(),
+            SgxStatus::InvalidIsvsvn
+        );
+    }
+}
+
+#[test_case]
+fn key_hierarchy_subkeys() {
+    let hierarchy = pinned();
+
+    // stable
+    let key = hierarchy.derive_key("data", 0).unwrap();
+    assert_eq!(hierarchy.derive_key("data", 0).unwrap(), key);
+    assert_eq!(pinned().derive_key("data", 0).unwrap(), key);
+    let mut okm = [0_u8; 32];
+    hierarchy.derive("data", 0, &mut okm).unwrap();
+    assert_eq!(&okm[..16], key.as_ref());
+
+    // separated by name and epoch
+    assert_ne!(hierarchy.derive_key("data2", 0).unwrap(), key);
+    assert_ne!(hierarchy.derive_key("dat", 0).unwrap(), key);
+    assert_ne!(hierarchy.derive_key("data", 1).unwrap(), key);
+    assert_ne!(
+        hierarchy.derive_key("data", 1).unwrap(),
+        hierarchy.derive_key("data", 2).unwrap()
+    );
+
+    // and from the seal key of the same versions
+    let seal_key = KeyRequestBuilder::seal().get_align_key().unwrap();
+    assert_ne!(seal_key, key);
+
+    // an older ISVSVN has its own subkeys
+    let body = &Report::get_self().body;
+    if body.isv_svn > 0 {
+        let older = KeyHierarchy::new(body.isv_svn - 1, body.config_svn, body.cpu_svn).unwrap();
+        assert_ne!(older.derive_key("data", 0).unwrap(), key);
+    }
+
+    assert_eq!(
+        hierarchy.derive_key("", 0).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+}
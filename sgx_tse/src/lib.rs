@@ -28,7 +28,9 @@ extern crate sgx_trts;
 #[macro_use]
 extern crate sgx_types;
 
+mod key;
 mod se;
+pub use self::key::*;
 pub use self::se::*;
 
 #[cfg(feature = "capi")]
@@ -15,17 +15,20 @@
 // specific language governing permissions and limitations
 // under the License..
 
-use crate::internal::InnerSealedData;
+use crate::internal::{InnerSealedData, InnerUnsealedData};
+use crate::migrate::SvnPolicy;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::alloc::Allocator;
 use core::marker::PhantomData;
 use core::mem;
 use core::slice;
+use sgx_crypto::aes::gcm::AesGcm;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
 use sgx_trts::trts::EnclaveRange;
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::marker::ContiguousMemory;
-use sgx_types::types::{Attributes, KeyPolicy, Mac};
+use sgx_types::types::{Attributes, KeyPolicy, KeyRequest, Mac};
 
 #[cfg(feature = "serialize")]
 use sgx_serialize::{Deserialize, Serialize};
@@ -68,6 +71,48 @@ impl<T: ContiguousMemory + ?Sized> MacAad<T> {
         )
     }
 
+    /// Authenticates the data with the given cipher instead of AES-GCM. The
+    /// same cipher must be passed to `unmac_with_cipher`; the blob records the
+    /// cipher, and another one is rejected with InvalidParameter.
+    pub fn mac_with_cipher<C: Aead>(aad: &T) -> SgxResult<MacAad<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        let size = mem::size_of_val(aad);
+        ensure!(size != 0, SgxStatus::InvalidParameter);
+
+        let aad = unsafe { slice::from_raw_parts(aad as *const _ as *const u8, size) };
+        InnerSealedData::mac_with_cipher::<C>(aad).map(|inner| MacAad {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn mac_with_key_policy_and_cipher<C: Aead>(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        aad: &T,
+    ) -> SgxResult<MacAad<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        let size = mem::size_of_val(aad);
+        ensure!(size != 0, SgxStatus::InvalidParameter);
+
+        let aad = unsafe { slice::from_raw_parts(aad as *const _ as *const u8, size) };
+        InnerSealedData::mac_with_key_policy_and_cipher::<C>(
+            key_policy,
+            attribute_mask,
+            misc_mask,
+            aad,
+        )
+        .map(|inner| MacAad {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
     #[inline]
     pub fn into_bytes(self) -> SgxResult<Vec<u8>> {
         self.inner.into_bytes()
@@ -112,11 +157,63 @@ impl<T: ContiguousMemory + ?Sized> MacAad<T> {
     pub fn payload_size(&self) -> u32 {
         self.inner.payload.len
     }
+
+    #[inline]
+    pub fn key_request(&self) -> &KeyRequest {
+        &self.inner.key_request
+    }
+
+    /// Whether the data was authenticated under an older ISVSVN, CONFIGSVN or
+    /// CPUSVN than the enclave runs with.
+    #[inline]
+    pub fn needs_reseal(&self) -> bool {
+        self.inner.needs_reseal()
+    }
+
+    /// Verifies the data and authenticates it again under the current
+    /// security versions, if the policy accepts the versions it was
+    /// authenticated under.
+    #[inline]
+    pub fn reseal(self, policy: &SvnPolicy) -> SgxResult<MacAad<T>> {
+        self.reseal_with_cipher::<AesGcm<[u8; 0]>>(policy)
+    }
+
+    pub fn reseal_with_cipher<C: Aead>(self, policy: &SvnPolicy) -> SgxResult<MacAad<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        self.inner
+            .reseal_with_cipher::<C>(policy)
+            .map(|inner| MacAad {
+                inner,
+                marker: PhantomData,
+            })
+    }
 }
 
 impl<T: ContiguousMemory> MacAad<T> {
+    #[inline]
     pub fn unmac(self) -> SgxResult<Box<T>> {
-        self.inner.verify().map(|inner| {
+        Self::into_aad(self.inner.verify())
+    }
+
+    #[inline]
+    pub fn unmac_with_cipher<C: Aead>(self) -> SgxResult<Box<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        Self::into_aad(self.inner.verify_with_cipher::<C>())
+    }
+
+    /// Verifies the data only if the policy accepts the security versions it
+    /// was authenticated under.
+    pub fn verify_with_policy(self, policy: &SvnPolicy) -> SgxResult<Box<T>> {
+        policy.check(&self.inner.key_request)?;
+        self.unmac()
+    }
+
+    fn into_aad(result: SgxResult<InnerUnsealedData>) -> SgxResult<Box<T>> {
+        result.map(|inner| {
             let ptr = Box::into_raw(inner.aad);
             unsafe { Box::from_raw(ptr as *mut T) }
         })
@@ -124,10 +221,28 @@ impl<T: ContiguousMemory> MacAad<T> {
 }
 
 impl<T: ContiguousMemory> MacAad<[T]> {
+    #[inline]
     pub fn unmac(self) -> SgxResult<Box<[T]>> {
-        self.inner
-            .verify()
-            .map(|inner| unsafe { mem::transmute(inner.aad) })
+        Self::into_aad(self.inner.verify())
+    }
+
+    #[inline]
+    pub fn unmac_with_cipher<C: Aead>(self) -> SgxResult<Box<[T]>>
+    where
+        C::Key: FromKey128bit,
+    {
+        Self::into_aad(self.inner.verify_with_cipher::<C>())
+    }
+
+    /// Verifies the data only if the policy accepts the security versions it
+    /// was authenticated under.
+    pub fn verify_with_policy(self, policy: &SvnPolicy) -> SgxResult<Box<[T]>> {
+        policy.check(&self.inner.key_request)?;
+        self.unmac()
+    }
+
+    fn into_aad(result: SgxResult<InnerUnsealedData>) -> SgxResult<Box<[T]>> {
+        result.map(|inner| unsafe { mem::transmute(inner.aad) })
     }
 }
 
@@ -0,0 +1,458 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! A self-describing, versioned format for sealed data.
+//!
+//! Unlike `SealedData`, whose bytes mirror `sgx_sealed_data_t`, an envelope
+//! says what it is and how it was sealed:
+//!
+//! ```text
+//! magic (8) || version (2) || cipher suite (2) || key policy (2)
+//!     || label length (2) || aad length (4) || ciphertext length (4)
+//!     || key request (512) || label || aad || ciphertext || tag (16)
+//! ```
+//!
+//! Everything before the ciphertext is the associated data of the cipher, so
+//! the label, the caller's aad and the header are all authenticated. The label
+//! names the type or purpose of the sealed data; unsealing with another label
+//! fails, so a blob cannot be unsealed into the wrong type.
+//!
+//! The cipher key is derived from the seal key named by the key request and
+//! the cipher suite, so a new suite never shares a key with an old one. The
+//! seal key has 128 bits, and so has every suite's security level.
+
+use crate::internal::InnerSealedData;
+use crate::migrate::SvnPolicy;
+use crate::seal::UnsealedData;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+use core::slice;
+use sgx_crypto::aes::gcm::{Aad, AesGcm, Nonce};
+use sgx_crypto::aes::AesKey;
+use sgx_crypto::kdf::HkdfSha256;
+use sgx_trts::trts::{is_within_enclave, EnclaveRange};
+use sgx_tse::EnclaveKey;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::marker::ContiguousMemory;
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{
+    AlignKey128bit, Attributes, Key128bit, Key256bit, KeyName, KeyPolicy, KeyRequest, Mac128bit,
+    MAC_128BIT_SIZE, TSEAL_DEFAULT_MISCMASK,
+};
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub const ENVELOPE_VERSION: u16 = 1;
+
+const ENVELOPE_MAGIC: [u8; 8] = *b"TSEALENV";
+const KEY_REQUEST_OFFSET: usize = 24;
+const HEADER_SIZE: usize = KEY_REQUEST_OFFSET + mem::size_of::<KeyRequest>();
+const ENVELOPE_KEY_INFO: &[u8] = b"sgx_tseal envelope key";
+
+/// The cipher an envelope is sealed with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u16)]
+pub enum CipherSuite {
+    Aes128Gcm = 1,
+    /// AES-256, with the key expanded from the 128-bit seal key. The security
+    /// level stays at 128 bits.
+    Aes256Gcm = 2,
+}
+
+impl CipherSuite {
+    fn from_u16(suite: u16) -> SgxResult<CipherSuite> {
+        match suite {
+            1 => Ok(CipherSuite::Aes128Gcm),
+            2 => Ok(CipherSuite::Aes256Gcm),
+            _ => bail!(SgxStatus::InvalidParameter),
+        }
+    }
+}
+
+impl Default for CipherSuite {
+    #[inline]
+    fn default() -> CipherSuite {
+        CipherSuite::Aes256Gcm
+    }
+}
+
+/// Whether the bytes start like a sealed envelope rather than `SealedData`.
+#[inline]
+pub fn is_envelope(raw: &[u8]) -> bool {
+    raw.len() >= HEADER_SIZE && raw[..8] == ENVELOPE_MAGIC
+}
+
+/// Sealed data in the envelope format, bound to a caller-supplied label.
+pub struct SealedEnvelope<T: ?Sized> {
+    suite: CipherSuite,
+    key_request: KeyRequest,
+    label: Box<[u8]>,
+    aad: Box<[u8]>,
+    ciphertext: Box<[u8]>,
+    tag: Mac128bit,
+    marker: PhantomData<T>,
+}
+
+impl<T: ContiguousMemory + ?Sized> SealedEnvelope<T> {
+    /// Seals the data with the default cipher suite and key policy.
+    pub fn seal(label: &[u8], data: &T, aad: Option<&[u8]>) -> SgxResult<SealedEnvelope<T>> {
+        Self::seal_with_options(
+            CipherSuite::default(),
+            InnerSealedData::default_key_policy(),
+            InnerSealedData::default_attribute_mask(),
+            TSEAL_DEFAULT_MISCMASK,
+            label,
+            data,
+            aad,
+        )
+    }
+
+    pub fn seal_with_options(
+        suite: CipherSuite,
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        label: &[u8],
+        data: &T,
+        aad: Option<&[u8]>,
+    ) -> SgxResult<SealedEnvelope<T>> {
+        let size = mem::size_of_val(data);
+        ensure!(
+            (1..u32::MAX as usize).contains(&size),
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            (1..=u16::MAX as usize).contains(&label.len()),
+            SgxStatus::InvalidParameter
+        );
+        let aad = aad.unwrap_or_default();
+        ensure!(aad.len() < u32::MAX as usize, SgxStatus::InvalidParameter);
+
+        let plaintext = unsafe { slice::from_raw_parts(data as *const _ as *const u8, size) };
+        ensure!(plaintext.is_enclave_range(), SgxStatus::InvalidParameter);
+
+        let key_request = InnerSealedData::seal_key_request(key_policy, attribute_mask, misc_mask)?;
+        let seal_key = key_request.get_align_key()?;
+        let mut envelope = SealedEnvelope {
+            suite,
+            key_request,
+            label: Box::from(label),
+            aad: Box::from(aad),
+            ciphertext: vec![0_u8; size].into_boxed_slice(),
+            tag: Mac128bit::default(),
+            marker: PhantomData,
+        };
+        let header = envelope.header();
+        envelope.tag = crypt(suite, seal_key, &header, |cipher| {
+            cipher.encrypt(plaintext, &mut envelope.ciphertext)
+        })?;
+        Ok(envelope)
+    }
+
+    pub fn into_bytes(self) -> SgxResult<Vec<u8>> {
+        self.to_bytes()
+    }
+
+    pub fn to_bytes(&self) -> SgxResult<Vec<u8>> {
+        let mut raw = self.header();
+        raw.extend_from_slice(&self.ciphertext);
+        raw.extend_from_slice(&self.tag);
+        Ok(raw)
+    }
+
+    #[inline]
+    pub fn from_bytes<A: Allocator>(raw: Vec<u8, A>) -> SgxResult<SealedEnvelope<T>> {
+        ensure!(
+            is_within_enclave(raw.as_ptr(), raw.capacity()),
+            SgxStatus::InvalidParameter
+        );
+        Self::from_slice(&raw)
+    }
+
+    pub fn from_slice(raw: &[u8]) -> SgxResult<SealedEnvelope<T>> {
+        ensure!(raw.is_enclave_range(), SgxStatus::InvalidParameter);
+        ensure!(is_envelope(raw), SgxStatus::InvalidParameter);
+
+        let read_u16 = |offset: usize| u16::from_le_bytes([raw[offset], raw[offset + 1]]);
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(raw[offset..offset + 4].try_into().unwrap()) as usize
+        };
+
+        ensure!(read_u16(8) == ENVELOPE_VERSION, SgxStatus::InvalidParameter);
+        let suite = CipherSuite::from_u16(read_u16(10))?;
+        let key_policy = read_u16(12);
+        let label_len = read_u16(14) as usize;
+        let aad_len = read_u32(16);
+        let ciphertext_len = read_u32(20);
+        ensure!(
+            label_len != 0 && ciphertext_len != 0,
+            SgxStatus::InvalidParameter
+        );
+
+        let raw_len = HEADER_SIZE
+            .checked_add(label_len)
+            .and_then(|len| len.checked_add(aad_len))
+            .and_then(|len| len.checked_add(ciphertext_len))
+            .and_then(|len| len.checked_add(MAC_128BIT_SIZE))
+            .ok_or(SgxStatus::InvalidParameter)?;
+        ensure!(raw.len() == raw_len, SgxStatus::InvalidParameter);
+
+        // The key name is checked before the bytes are taken as a KeyRequest.
+        ensure!(
+            read_u16(KEY_REQUEST_OFFSET) == KeyName::Seal as u16,
+            SgxStatus::InvalidParameter
+        );
+        let key_request: KeyRequest =
+            unsafe { ptr::read_unaligned(raw[KEY_REQUEST_OFFSET..].as_ptr().cast()) };
+        ensure!(
+            key_request.key_policy.bits() == key_policy,
+            SgxStatus::InvalidParameter
+        );
+
+        let label_end = HEADER_SIZE + label_len;
+        let aad_end = label_end + aad_len;
+        let ciphertext_end = aad_end + ciphertext_len;
+        let mut tag = Mac128bit::default();
+        tag.copy_from_slice(&raw[ciphertext_end..]);
+
+        Ok(SealedEnvelope {
+            suite,
+            key_request,
+            label: Box::from(&raw[HEADER_SIZE..label_end]),
+            aad: Box::from(&raw[label_end..aad_end]),
+            ciphertext: Box::from(&raw[aad_end..ciphertext_end]),
+            tag,
+            marker: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn version(&self) -> u16 {
+        ENVELOPE_VERSION
+    }
+
+    #[inline]
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.suite
+    }
+
+    #[inline]
+    pub fn key_policy(&self) -> KeyPolicy {
+        self.key_request.key_policy
+    }
+
+    #[inline]
+    pub fn key_request(&self) -> &KeyRequest {
+        &self.key_request
+    }
+
+    #[inline]
+    pub fn label(&self) -> &[u8] {
+        &self.label
+    }
+
+    #[inline]
+    pub fn aad(&self) -> &[u8] {
+        &self.aad
+    }
+
+    #[inline]
+    pub fn tag(&self) -> Mac128bit {
+        self.tag
+    }
+
+    // Checks the label and decrypts the ciphertext into a new buffer.
+    fn open(&self, label: &[u8]) -> SgxResult<Box<[u8]>> {
+        ensure!(self.label.ct_eq(label), SgxStatus::InvalidParameter);
+
+        let seal_key = InnerSealedData::unseal_key(&self.key_request)?;
+        let mut plaintext = vec![0_u8; self.ciphertext.len()].into_boxed_slice();
+        let result = crypt(self.suite, seal_key, &self.header(), |cipher| {
+            cipher.decrypt(&self.ciphertext, &mut plaintext, &self.tag)
+        });
+        if let Err(e) = result {
+            plaintext.fill(0);
+            return Err(e);
+        }
+        Ok(plaintext)
+    }
+
+    // Everything before the ciphertext.
+    fn header(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(HEADER_SIZE + self.label.len() + self.aad.len());
+        raw.extend_from_slice(&ENVELOPE_MAGIC);
+        raw.extend_from_slice(&ENVELOPE_VERSION.to_le_bytes());
+        raw.extend_from_slice(&(self.suite as u16).to_le_bytes());
+        raw.extend_from_slice(&self.key_request.key_policy.bits().to_le_bytes());
+        raw.extend_from_slice(&(self.label.len() as u16).to_le_bytes());
+        raw.extend_from_slice(&(self.aad.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&(self.ciphertext.len() as u32).to_le_bytes());
+        raw.extend_from_slice(self.key_request.as_ref());
+        raw.extend_from_slice(&self.label);
+        raw.extend_from_slice(&self.aad);
+        raw
+    }
+}
+
+impl<T: ContiguousMemory> SealedEnvelope<T> {
+    /// Unseals the data, which fails unless `label` is the label it was
+    /// sealed with.
+    pub fn unseal(self, label: &[u8]) -> SgxResult<UnsealedData<T>> {
+        ensure!(
+            self.ciphertext.len() == mem::size_of::<T>(),
+            SgxStatus::InvalidParameter
+        );
+        let plaintext = self.open(label)?;
+        let ptr = Box::into_raw(plaintext);
+        Ok(UnsealedData::new(
+            unsafe { Box::from_raw(ptr as *mut T) },
+            self.aad,
+        ))
+    }
+
+    /// Unseals the data only if the policy accepts the security versions it
+    /// was sealed under.
+    pub fn unseal_with_policy(
+        self,
+        label: &[u8],
+        policy: &SvnPolicy,
+    ) -> SgxResult<UnsealedData<T>> {
+        policy.check(&self.key_request)?;
+        self.unseal(label)
+    }
+}
+
+impl<T: ContiguousMemory> SealedEnvelope<[T]> {
+    /// Unseals the data, which fails unless `label` is the label it was
+    /// sealed with.
+    pub fn unseal(self, label: &[u8]) -> SgxResult<UnsealedData<[T]>> {
+        let size = mem::size_of::<T>();
+        ensure!(
+            size != 0 && self.ciphertext.len() % size == 0,
+            SgxStatus::InvalidParameter
+        );
+        let len = self.ciphertext.len() / size;
+        let plaintext = self.open(label)?;
+        let ptr = Box::into_raw(plaintext);
+        Ok(UnsealedData::new(
+            unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(ptr as *mut T, len)) },
+            self.aad,
+        ))
+    }
+
+    /// Unseals the data only if the policy accepts the security versions it
+    /// was sealed under.
+    pub fn unseal_with_policy(
+        self,
+        label: &[u8],
+        policy: &SvnPolicy,
+    ) -> SgxResult<UnsealedData<[T]>> {
+        policy.check(&self.key_request)?;
+        self.unseal(label)
+    }
+}
+
+impl<T: ?Sized> Clone for SealedEnvelope<T> {
+    fn clone(&self) -> SealedEnvelope<T> {
+        SealedEnvelope {
+            suite: self.suite,
+            key_request: self.key_request,
+            label: self.label.clone(),
+            aad: self.aad.clone(),
+            ciphertext: self.ciphertext.clone(),
+            tag: self.tag,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for SealedEnvelope<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SealedEnvelope")
+            .field("suite", &self.suite)
+            .field("key_request", &self.key_request)
+            .field("label", &self.label)
+            .field("aad", &self.aad)
+            .field("ciphertext", &self.ciphertext)
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+// AES-GCM under either key size, behind one interface.
+trait EnvelopeCipher {
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult<Mac128bit>;
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8], mac: &Mac128bit) -> SgxResult;
+}
+
+impl<K: AesKey> EnvelopeCipher for AesGcm<&[u8], K> {
+    #[inline]
+    fn encrypt(&mut self, src: &[u8], dst: &mut [u8]) -> SgxResult<Mac128bit> {
+        AesGcm::encrypt(self, src, dst)
+    }
+
+    #[inline]
+    fn decrypt(&mut self, src: &[u8], dst: &mut [u8], mac: &Mac128bit) -> SgxResult {
+        AesGcm::decrypt(self, src, dst, mac)
+    }
+}
+
+// Runs `f` with the cipher for the suite, keyed from the seal key and with
+// the header as associated data. The key is unique to the envelope, as the
+// key id of the key request is random, so the nonce is fixed.
+fn crypt<R, F>(
+    suite: CipherSuite,
+    mut seal_key: AlignKey128bit,
+    header: &[u8],
+    f: F,
+) -> SgxResult<R>
+where
+    F: FnOnce(&mut dyn EnvelopeCipher) -> SgxResult<R>,
+{
+    let mut info = Vec::with_capacity(ENVELOPE_KEY_INFO.len() + 2);
+    info.extend_from_slice(ENVELOPE_KEY_INFO);
+    info.extend_from_slice(&(suite as u16).to_le_bytes());
+
+    let result = match suite {
+        CipherSuite::Aes128Gcm => {
+            with_cipher::<Key128bit, R, F>(seal_key.as_ref(), &info, header, f)
+        }
+        CipherSuite::Aes256Gcm => {
+            with_cipher::<Key256bit, R, F>(seal_key.as_ref(), &info, header, f)
+        }
+    };
+    seal_key.as_mut().fill(0);
+    result
+}
+
+fn with_cipher<K, R, F>(seal_key: &[u8], info: &[u8], header: &[u8], f: F) -> SgxResult<R>
+where
+    K: AesKey + Default + AsMut<[u8]>,
+    F: FnOnce(&mut dyn EnvelopeCipher) -> SgxResult<R>,
+{
+    let mut key = K::default();
+    let result = HkdfSha256::derive(&[], seal_key, info, key.as_mut())
+        .and_then(|_| AesGcm::new(&key, Nonce::zeroed(), Aad::from(header)));
+    key.as_mut().fill(0);
+    f(&mut result?)
+}
@@ -0,0 +1,106 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{is_envelope, CipherSuite, SealedEnvelope, HEADER_SIZE};
+use crate::internal::InnerSealedData;
+use sgx_test_utils::test_case;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::TSEAL_DEFAULT_MISCMASK;
+
+const LABEL: &[u8] = b"test label";
+const AAD: &[u8] = b"test aad";
+
+#[test_case]
+fn envelope_round_trip() {
+    for suite in [CipherSuite::Aes128Gcm, CipherSuite::Aes256Gcm] {
+        let data = [0x5a_u8; 100];
+        let envelope = SealedEnvelope::<[u8]>::seal_with_options(
+            suite,
+            InnerSealedData::default_key_policy(),
+            InnerSealedData::default_attribute_mask(),
+            TSEAL_DEFAULT_MISCMASK,
+            LABEL,
+            &data[..],
+            Some(AAD),
+        )
+        .unwrap();
+        let raw = envelope.into_bytes().unwrap();
+        assert!(is_envelope(&raw));
+        assert_eq!(raw.len(), HEADER_SIZE + LABEL.len() + AAD.len() + 100 + 16);
+
+        let envelope = SealedEnvelope::<[u8]>::from_slice(&raw).unwrap();
+        assert_eq!(envelope.cipher_suite(), suite);
+        assert_eq!(envelope.label(), LABEL);
+        assert_eq!(envelope.aad(), AAD);
+        let unsealed = envelope.unseal(LABEL).unwrap();
+        assert_eq!(unsealed.to_plaintext(), &data[..]);
+        assert_eq!(unsealed.to_aad(), AAD);
+    }
+
+    let envelope = SealedEnvelope::seal(LABEL, &0x1234_5678_u32, None).unwrap();
+    assert_eq!(envelope.cipher_suite(), CipherSuite::default());
+    let raw = envelope.into_bytes().unwrap();
+    let unsealed = SealedEnvelope::<u32>::from_slice(&raw)
+        .unwrap()
+        .unseal(LABEL)
+        .unwrap();
+    assert_eq!(*unsealed.to_plaintext(), 0x1234_5678);
+    assert!(unsealed.to_aad().is_empty());
+}
+
+#[test_case]
+fn envelope_wrong_label() {
+    let envelope = SealedEnvelope::seal(LABEL, &[1_u8, 2, 3][..], Some(AAD)).unwrap();
+    let raw = envelope.to_bytes().unwrap();
+
+    assert_eq!(
+        envelope.clone().unseal(b"other label").unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+    assert_eq!(
+        envelope.unseal(&LABEL[..LABEL.len() - 1]).unwrap_err(),
+        SgxStatus::InvalidParameter
+    );
+
+    // A label changed in the bytes no longer matches the tag.
+    let mut raw = raw;
+    raw[HEADER_SIZE] ^= 1;
+    let mut label = LABEL.to_vec();
+    label[0] ^= 1;
+    let envelope = SealedEnvelope::<[u8]>::from_slice(&raw).unwrap();
+    assert_eq!(envelope.unseal(&label).unwrap_err(), SgxStatus::MacMismatch);
+}
+
+#[test_case]
+fn envelope_tampered() {
+    let envelope = SealedEnvelope::seal(LABEL, &[1_u8, 2, 3][..], Some(AAD)).unwrap();
+    let raw = envelope.into_bytes().unwrap();
+
+    // aad, ciphertext and tag
+    for offset in [HEADER_SIZE + LABEL.len(), raw.len() - 17, raw.len() - 1] {
+        let mut raw = raw.clone();
+        raw[offset] ^= 1;
+        let envelope = SealedEnvelope::<[u8]>::from_slice(&raw).unwrap();
+        assert_eq!(envelope.unseal(LABEL).unwrap_err(), SgxStatus::MacMismatch);
+    }
+
+    // truncated, and an unknown version
+    assert!(SealedEnvelope::<[u8]>::from_slice(&raw[..raw.len() - 1]).is_err());
+    let mut raw = raw;
+    raw[8] = 2;
+    assert!(SealedEnvelope::<[u8]>::from_slice(&raw).is_err());
+}
@@ -15,25 +15,35 @@
 // specific language governing permissions and limitations
 // under the License..
 
+use crate::migrate::SvnPolicy;
 use alloc::alloc::Global;
 use alloc::boxed::Box;
 use alloc::vec::{self, Vec};
 use core::alloc::Allocator;
 use core::convert::From;
 use core::mem;
-use sgx_crypto::aes::gcm::{Aad, AesGcm, Nonce};
+use sgx_crypto::aes::gcm::AesGcm;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
 use sgx_trts::fence::lfence;
 use sgx_trts::rand::rand;
 use sgx_trts::trts::{is_within_enclave, is_within_host, EnclaveRange};
 use sgx_tse::{EnclaveKey, EnclaveReport};
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::types::{
-    Attributes, AttributesFlags, CSealedData, KeyId, KeyName, KeyPolicy, KeyRequest, Report,
+    AlignKey128bit, Attributes, AttributesFlags, CSealedData, KeyId, KeyName, KeyPolicy,
+    KeyRequest, Report,
 };
 use sgx_types::types::{SEAL_TAG_SIZE, TSEAL_DEFAULT_MISCMASK};
 
 #[cfg(feature = "serialize")]
-use sgx_serialize::{Deserialize, Serialize};
+use sgx_serialize::{Decodable, Decoder, Deserialize, Encodable, Encoder, Serialize};
+
+#[cfg(all(feature = "unit_test", feature = "serialize"))]
+mod tests;
+
+// The cipher used by the plain seal/unseal/mac/verify functions, this is
+// the AES-GCM layout expected by the SGX SDK.
+type DefaultCipher = AesGcm<[u8; 0]>;
 
 #[derive(Clone, Debug, Default)]
 pub struct InnerUnsealedData {
@@ -52,12 +62,48 @@ pub struct Payload {
 }
 
 #[derive(Clone, Debug, Default)]
-#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
 pub struct InnerSealedData {
     pub key_request: KeyRequest,
+    pub cipher: u16,
     pub payload: Payload,
 }
 
+// The serialized layout predates the cipher field, so the cipher is kept in
+// the reserved1 field of the key request, which must be zero to derive the
+// key. Blobs sealed with the default cipher keep their old encoding.
+#[cfg(feature = "serialize")]
+impl Encodable for InnerSealedData {
+    fn encode<S: Encoder>(&self, e: &mut S) -> Result<(), S::Error> {
+        let mut key_request = self.key_request;
+        key_request.reserved1 = self.cipher;
+        e.emit_struct("InnerSealedData", 2usize, |e| -> _ {
+            e.emit_struct_field("key_request", 0usize, |e| -> _ {
+                Encodable::encode(&key_request, e)
+            })?;
+            e.emit_struct_field("payload", 1usize, |e| -> _ {
+                Encodable::encode(&self.payload, e)
+            })
+        })
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl Decodable for InnerSealedData {
+    fn decode<D: Decoder>(d: &mut D) -> Result<InnerSealedData, D::Error> {
+        d.read_struct("InnerSealedData", 2usize, |d| -> _ {
+            let mut key_request: KeyRequest =
+                d.read_struct_field("key_request", 0usize, Decodable::decode)?;
+            let cipher = key_request.reserved1;
+            key_request.reserved1 = 0;
+            Ok(InnerSealedData {
+                key_request,
+                cipher,
+                payload: d.read_struct_field("payload", 1usize, Decodable::decode)?,
+            })
+        })
+    }
+}
+
 impl InnerSealedData {
     pub fn raw_sealed_data_size(aad_len: u32, plaintext_len: u32) -> Option<u32> {
         let max = u32::MAX;
@@ -104,6 +150,7 @@ impl InnerSealedData {
         let raw_data = unsafe { &mut *(raw.as_mut_ptr() as *mut CSealedData) };
         raw_data.key_request = self.key_request;
         raw_data.plaintext_offset = ciphertext_len as u32;
+        raw_data.reserved[..2].copy_from_slice(&self.cipher.to_le_bytes());
         raw_data.aes_data.payload_size = self.payload.len;
         raw_data.aes_data.payload_tag = self.payload.tag;
 
@@ -150,6 +197,7 @@ impl InnerSealedData {
 
         Ok(InnerSealedData {
             key_request: raw_data.key_request,
+            cipher: u16::from_le_bytes([raw_data.reserved[0], raw_data.reserved[1]]),
             payload: Payload {
                 len: raw_data.aes_data.payload_size,
                 tag: raw_data.aes_data.payload_tag,
@@ -177,13 +225,9 @@ impl InnerSealedData {
         }
     }
 
-    pub fn seal(plaintext: &[u8], aad: Option<&[u8]>) -> SgxResult<InnerSealedData> {
-        let attribute_mask = Attributes {
-            flags: AttributesFlags::DEFAULT_MASK,
-            xfrm: 0,
-        };
-
-        let mut key_policy = KeyPolicy::MRSIGNER;
+    /// The key policy used when none is given: MRSIGNER, plus KSS when the
+    /// enclave has KSS enabled.
+    pub fn default_key_policy() -> KeyPolicy {
         let report = Report::get_self();
         if report
             .body
@@ -191,18 +235,79 @@ impl InnerSealedData {
             .flags
             .intersects(AttributesFlags::KSS)
         {
-            key_policy |= KeyPolicy::KSS;
+            KeyPolicy::MRSIGNER | KeyPolicy::KSS
+        } else {
+            KeyPolicy::MRSIGNER
+        }
+    }
+
+    #[inline]
+    pub fn default_attribute_mask() -> Attributes {
+        Attributes {
+            flags: AttributesFlags::DEFAULT_MASK,
+            xfrm: 0,
         }
+    }
 
-        Self::seal_with_key_policy(
+    /// Builds the request for a new seal key under the current enclave
+    /// identity, with a random key id.
+    pub fn seal_key_request(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+    ) -> SgxResult<KeyRequest> {
+        ensure!(key_policy.is_valid(), SgxStatus::InvalidParameter);
+        ensure!(
+            key_policy.intersects(KeyPolicy::MRENCLAVE | KeyPolicy::MRSIGNER),
+            SgxStatus::InvalidParameter
+        );
+        ensure!(
+            attribute_mask
+                .flags
+                .contains(AttributesFlags::INITTED | AttributesFlags::DEBUG),
+            SgxStatus::InvalidParameter
+        );
+
+        let mut key_id = KeyId::default();
+        rand(key_id.as_mut())?;
+        let report = Report::get_self();
+        let key_request = KeyRequest {
+            key_name: KeyName::Seal,
             key_policy,
+            isv_svn: report.body.isv_svn,
+            cpu_svn: report.body.cpu_svn,
             attribute_mask,
+            key_id,
+            misc_mask,
+            config_svn: report.body.config_svn,
+            ..Default::default()
+        };
+        key_id.as_mut().fill(0);
+        Ok(key_request)
+    }
+
+    #[inline]
+    pub fn seal(plaintext: &[u8], aad: Option<&[u8]>) -> SgxResult<InnerSealedData> {
+        Self::seal_with_cipher::<DefaultCipher>(plaintext, aad)
+    }
+
+    pub fn seal_with_cipher<C: Aead>(
+        plaintext: &[u8],
+        aad: Option<&[u8]>,
+    ) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        Self::seal_with_key_policy_and_cipher::<C>(
+            Self::default_key_policy(),
+            Self::default_attribute_mask(),
             TSEAL_DEFAULT_MISCMASK,
             plaintext,
             aad,
         )
     }
 
+    #[inline]
     pub fn seal_with_key_policy(
         key_policy: KeyPolicy,
         attribute_mask: Attributes,
@@ -210,6 +315,25 @@ impl InnerSealedData {
         plaintext: &[u8],
         aad: Option<&[u8]>,
     ) -> SgxResult<InnerSealedData> {
+        Self::seal_with_key_policy_and_cipher::<DefaultCipher>(
+            key_policy,
+            attribute_mask,
+            misc_mask,
+            plaintext,
+            aad,
+        )
+    }
+
+    pub fn seal_with_key_policy_and_cipher<C: Aead>(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        plaintext: &[u8],
+        aad: Option<&[u8]>,
+    ) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
         let aad_len = aad.map(|aad| aad.len()).unwrap_or(0);
         let plaintext_len = plaintext.len();
 
@@ -226,17 +350,6 @@ impl InnerSealedData {
             SgxStatus::InvalidParameter
         );
 
-        ensure!(key_policy.is_valid(), SgxStatus::InvalidParameter);
-        ensure!(
-            key_policy.intersects(KeyPolicy::MRENCLAVE | KeyPolicy::MRSIGNER),
-            SgxStatus::InvalidParameter
-        );
-        ensure!(
-            attribute_mask
-                .flags
-                .contains(AttributesFlags::INITTED | AttributesFlags::DEBUG),
-            SgxStatus::InvalidParameter
-        );
         ensure!(plaintext.is_enclave_range(), SgxStatus::InvalidParameter);
 
         let zero = [0_u8; 0];
@@ -249,59 +362,68 @@ impl InnerSealedData {
             );
         }
 
-        let mut key_id = KeyId::default();
-        rand(key_id.as_mut())?;
-        let report = Report::get_self();
-        let key_request = KeyRequest {
-            key_name: KeyName::Seal,
-            key_policy,
-            isv_svn: report.body.isv_svn,
-            cpu_svn: report.body.cpu_svn,
-            attribute_mask,
-            key_id,
-            misc_mask,
-            config_svn: report.body.config_svn,
-            ..Default::default()
-        };
-
-        let result = Self::seal_data_helper(plaintext, aad, &key_request);
-        key_id.as_mut().fill(0);
+        let mut key_request = Self::seal_key_request(key_policy, attribute_mask, misc_mask)?;
+        let result = Self::seal_data_helper::<C>(plaintext, aad, &key_request);
+        key_request.key_id.as_mut().fill(0);
         result
     }
 
     #[inline]
     pub fn unseal(&self) -> SgxResult<InnerUnsealedData> {
+        self.unseal_with_cipher::<DefaultCipher>()
+    }
+
+    #[inline]
+    pub fn unseal_with_cipher<C: Aead>(&self) -> SgxResult<InnerUnsealedData>
+    where
+        C::Key: FromKey128bit,
+    {
         ensure!(self.is_enclave_range(), SgxStatus::InvalidParameter);
 
-        self.unseal_data_helper()
+        self.unseal_data_helper::<C>()
     }
 
+    #[inline]
     pub fn mac(aad: &[u8]) -> SgxResult<InnerSealedData> {
-        let attribute_mask = Attributes {
-            flags: AttributesFlags::DEFAULT_MASK,
-            xfrm: 0,
-        };
-
-        let mut key_policy = KeyPolicy::MRSIGNER;
-        let report = Report::get_self();
-        if report
-            .body
-            .attributes
-            .flags
-            .intersects(AttributesFlags::KSS)
-        {
-            key_policy = KeyPolicy::MRSIGNER | KeyPolicy::KSS;
-        }
+        Self::mac_with_cipher::<DefaultCipher>(aad)
+    }
 
-        Self::mac_with_key_policy(key_policy, attribute_mask, TSEAL_DEFAULT_MISCMASK, aad)
+    pub fn mac_with_cipher<C: Aead>(aad: &[u8]) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        Self::mac_with_key_policy_and_cipher::<C>(
+            Self::default_key_policy(),
+            Self::default_attribute_mask(),
+            TSEAL_DEFAULT_MISCMASK,
+            aad,
+        )
     }
 
+    #[inline]
     pub fn mac_with_key_policy(
         key_policy: KeyPolicy,
         attribute_mask: Attributes,
         misc_mask: u32,
         aad: &[u8],
     ) -> SgxResult<InnerSealedData> {
+        Self::mac_with_key_policy_and_cipher::<DefaultCipher>(
+            key_policy,
+            attribute_mask,
+            misc_mask,
+            aad,
+        )
+    }
+
+    pub fn mac_with_key_policy_and_cipher<C: Aead>(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        aad: &[u8],
+    ) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
         let aad_len = aad.len();
         ensure!(
             (1..u32::MAX as usize).contains(&aad_len),
@@ -312,64 +434,109 @@ impl InnerSealedData {
             SgxStatus::InvalidParameter
         );
 
-        ensure!(key_policy.is_valid(), SgxStatus::InvalidParameter);
-        ensure!(
-            key_policy.intersects(KeyPolicy::MRENCLAVE | KeyPolicy::MRSIGNER),
-            SgxStatus::InvalidParameter
-        );
-        ensure!(
-            attribute_mask
-                .flags
-                .contains(AttributesFlags::INITTED | AttributesFlags::DEBUG),
-            SgxStatus::InvalidParameter
-        );
         ensure!(
             aad.is_enclave_range() || aad.is_host_range(),
             SgxStatus::InvalidParameter
         );
 
-        let mut key_id = KeyId::default();
-        rand(key_id.as_mut())?;
-        let report = Report::get_self();
-        let key_request = KeyRequest {
-            key_name: KeyName::Seal,
-            key_policy,
-            isv_svn: report.body.isv_svn,
-            cpu_svn: report.body.cpu_svn,
-            attribute_mask,
-            key_id,
-            misc_mask,
-            config_svn: report.body.config_svn,
-            ..Default::default()
-        };
-
-        let result = Self::mac_data_helper(aad, &key_request);
-        key_id.as_mut().fill(0);
+        let mut key_request = Self::seal_key_request(key_policy, attribute_mask, misc_mask)?;
+        let result = Self::mac_data_helper::<C>(aad, &key_request);
+        key_request.key_id.as_mut().fill(0);
         result
     }
 
     #[inline]
     pub fn verify(&self) -> SgxResult<InnerUnsealedData> {
+        self.verify_with_cipher::<DefaultCipher>()
+    }
+
+    #[inline]
+    pub fn verify_with_cipher<C: Aead>(&self) -> SgxResult<InnerUnsealedData>
+    where
+        C::Key: FromKey128bit,
+    {
         ensure!(self.is_enclave_range(), SgxStatus::InvalidParameter);
 
-        self.verify_data_helper()
+        self.verify_data_helper::<C>()
+    }
+
+    /// Gets the key a sealed blob was sealed with. Apart from an SVN or
+    /// memory failure, every error is reported as a MAC mismatch.
+    pub fn unseal_key(key_request: &KeyRequest) -> SgxResult<AlignKey128bit> {
+        let key = key_request.get_align_key().map_err(|e| match e {
+            SgxStatus::InvalidCpusvn | SgxStatus::InvalidIsvsvn | SgxStatus::OutOfMemory => e,
+            _ => SgxStatus::MacMismatch,
+        })?;
+
+        lfence();
+        Ok(key)
     }
 
-    fn seal_data_helper(
+    /// Whether the blob was sealed under an older ISVSVN, CONFIGSVN or CPUSVN
+    /// than the enclave runs with.
+    pub fn needs_reseal(&self) -> bool {
+        let report = Report::get_self();
+        self.key_request.isv_svn < report.body.isv_svn
+            || self.key_request.config_svn < report.body.config_svn
+            || self.key_request.cpu_svn != report.body.cpu_svn
+    }
+
+    /// Seals the payload again under the current security versions, with the
+    /// same key policy, attribute mask and misc mask. A blob that is already
+    /// current is returned as it is.
+    pub fn reseal_with_cipher<C: Aead>(self, policy: &SvnPolicy) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        policy.check(&self.key_request)?;
+        if !self.needs_reseal() {
+            return Ok(self);
+        }
+
+        let key_policy = self.key_request.key_policy;
+        let attribute_mask = self.key_request.attribute_mask;
+        let misc_mask = self.key_request.misc_mask;
+
+        if self.payload.ciphertext.is_empty() {
+            let verified = self.verify_with_cipher::<C>()?;
+            Self::mac_with_key_policy_and_cipher::<C>(
+                key_policy,
+                attribute_mask,
+                misc_mask,
+                &verified.aad,
+            )
+        } else {
+            let mut unsealed = self.unseal_with_cipher::<C>()?;
+            let result = Self::seal_with_key_policy_and_cipher::<C>(
+                key_policy,
+                attribute_mask,
+                misc_mask,
+                &unsealed.plaintext,
+                Some(&unsealed.aad),
+            );
+            unsealed.plaintext.fill(0);
+            result
+        }
+    }
+
+    fn seal_data_helper<C: Aead>(
         plaintext: &[u8],
         aad: &[u8],
         key_request: &KeyRequest,
-    ) -> SgxResult<InnerSealedData> {
-        let mut key = key_request.get_align_key()?;
-        let mut aes = AesGcm::new(&key.key, Nonce::zeroed(), Aad::from(aad))?;
+    ) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        let key = key_request.get_align_key()?;
 
         let mut ciphertext = vec![0_u8; plaintext.len()].into_boxed_slice();
-        let result = aes.encrypt(plaintext, &mut ciphertext);
-        key.as_mut().fill(0);
-        let tag = result?;
+        let tag = with_cipher_key::<C, _, _>(key, |key| {
+            C::encrypt_detached(key, C::zeroed_nonce(), aad, plaintext, &mut ciphertext)
+        })?;
 
         Ok(InnerSealedData {
             key_request: *key_request,
+            cipher: cipher_id::<C>(),
             payload: Payload {
                 len: (plaintext.len() + aad.len()) as u32,
                 tag,
@@ -379,25 +546,25 @@ impl InnerSealedData {
         })
     }
 
-    fn unseal_data_helper(&self) -> SgxResult<InnerUnsealedData> {
-        let mut key = self.key_request.get_align_key().map_err(|e| {
-            if e == SgxStatus::InvalidCpusvn
-                || e == SgxStatus::InvalidIsvsvn
-                || e == SgxStatus::OutOfMemory
-            {
-                e
-            } else {
-                SgxStatus::MacMismatch
-            }
-        })?;
+    fn unseal_data_helper<C: Aead>(&self) -> SgxResult<InnerUnsealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        ensure!(self.cipher == cipher_id::<C>(), SgxStatus::InvalidParameter);
 
-        lfence();
+        let key = Self::unseal_key(&self.key_request)?;
 
         let mut plaintext = vec![0_u8; self.payload.ciphertext.len()].into_boxed_slice();
-        let mut aes = AesGcm::new(&key.key, Nonce::zeroed(), Aad::from(&self.payload.aad))?;
-        let result = aes.decrypt(&self.payload.ciphertext, &mut plaintext, &self.payload.tag);
-        key.as_mut().fill(0);
-        result?;
+        with_cipher_key::<C, _, _>(key, |key| {
+            C::decrypt_detached(
+                key,
+                C::zeroed_nonce(),
+                &self.payload.aad,
+                &self.payload.ciphertext,
+                &mut plaintext,
+                &self.payload.tag,
+            )
+        })?;
 
         Ok(InnerUnsealedData {
             payload_len: self.payload.len,
@@ -406,16 +573,17 @@ impl InnerSealedData {
         })
     }
 
-    fn mac_data_helper(aad: &[u8], key_request: &KeyRequest) -> SgxResult<InnerSealedData> {
-        let mut key = key_request.get_align_key()?;
-        let mut aes = AesGcm::new(&key.key, Nonce::zeroed(), Aad::from(aad))?;
+    fn mac_data_helper<C: Aead>(aad: &[u8], key_request: &KeyRequest) -> SgxResult<InnerSealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        let key = key_request.get_align_key()?;
 
-        let result = aes.mac();
-        key.as_mut().fill(0);
-        let tag = result?;
+        let tag = with_cipher_key::<C, _, _>(key, |key| C::mac(key, C::zeroed_nonce(), aad))?;
 
         Ok(InnerSealedData {
             key_request: *key_request,
+            cipher: cipher_id::<C>(),
             payload: Payload {
                 len: aad.len() as u32,
                 tag,
@@ -425,18 +593,18 @@ impl InnerSealedData {
         })
     }
 
-    fn verify_data_helper(&self) -> SgxResult<InnerUnsealedData> {
-        let mut key = self.key_request.get_align_key().map_err(|e| match e {
-            SgxStatus::InvalidCpusvn | SgxStatus::InvalidIsvsvn | SgxStatus::OutOfMemory => e,
-            _ => SgxStatus::MacMismatch,
-        })?;
+    fn verify_data_helper<C: Aead>(&self) -> SgxResult<InnerUnsealedData>
+    where
+        C::Key: FromKey128bit,
+    {
+        ensure!(!self.payload.aad.is_empty(), SgxStatus::InvalidParameter);
+        ensure!(self.cipher == cipher_id::<C>(), SgxStatus::InvalidParameter);
 
-        lfence();
+        let key = Self::unseal_key(&self.key_request)?;
 
-        let mut aes = AesGcm::new(&key.key, Nonce::zeroed(), Aad::from(&self.payload.aad))?;
-        let result = aes.verify_mac(&self.payload.tag);
-        key.as_mut().fill(0);
-        result?;
+        with_cipher_key::<C, _, _>(key, |key| {
+            C::verify_mac(key, C::zeroed_nonce(), &self.payload.aad, &self.payload.tag)
+        })?;
 
         Ok(InnerUnsealedData {
             payload_len: self.payload.len,
@@ -446,6 +614,31 @@ impl InnerSealedData {
     }
 }
 
+// The cipher recorded in a sealed blob. It is zero for the default cipher, so
+// that those blobs keep the SGX SDK layout, and a blob sealed with another
+// cipher is rejected with InvalidParameter before it is decrypted.
+fn cipher_id<C: Aead>() -> u16 {
+    if C::ID == DefaultCipher::ID {
+        0
+    } else {
+        C::ID
+    }
+}
+
+// Keys the cipher from the seal key, and clears both keys afterwards.
+fn with_cipher_key<C: Aead, R, F>(mut key: AlignKey128bit, f: F) -> SgxResult<R>
+where
+    C::Key: FromKey128bit,
+    F: FnOnce(&C::Key) -> SgxResult<R>,
+{
+    let cipher_key = C::Key::from_key128bit(&key.key);
+    key.as_mut().fill(0);
+    let mut cipher_key = cipher_key?;
+    let result = f(&cipher_key);
+    cipher_key.as_mut().fill(0);
+    result
+}
+
 impl EnclaveRange for Payload {
     fn is_enclave_range(&self) -> bool {
         if !is_within_enclave(self as *const _ as *const u8, mem::size_of_val(self)) {
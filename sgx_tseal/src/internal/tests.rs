@@ -0,0 +1,88 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{InnerSealedData, Payload};
+use alloc::boxed::Box;
+use sgx_serialize::opaque;
+use sgx_serialize::{Deserialize, Serialize};
+use sgx_test_utils::test_case;
+use sgx_types::types::{KeyName, KeyPolicy, KeyRequest};
+
+// The layout of InnerSealedData before it recorded the cipher.
+#[derive(Serialize, Deserialize)]
+struct OldSealedData {
+    key_request: KeyRequest,
+    payload: Payload,
+}
+
+fn key_request() -> KeyRequest {
+    let mut key_request = KeyRequest {
+        key_name: KeyName::Seal,
+        key_policy: KeyPolicy::MRSIGNER,
+        isv_svn: 3,
+        config_svn: 5,
+        ..Default::default()
+    };
+    key_request.key_id.id.fill(0x5a);
+    key_request
+}
+
+fn payload() -> Payload {
+    Payload {
+        len: 7,
+        tag: [0xa5; 16],
+        ciphertext: Box::from(&[1_u8, 2, 3, 4][..]),
+        aad: Box::from(&[5_u8, 6, 7][..]),
+    }
+}
+
+#[test_case]
+fn sealed_data_old_format() {
+    let old = OldSealedData {
+        key_request: key_request(),
+        payload: payload(),
+    };
+    let bytes = opaque::encode(&old).unwrap();
+
+    let data: InnerSealedData = opaque::decode(&bytes).unwrap();
+    assert_eq!(data.cipher, 0);
+    assert_eq!(data.key_request.as_ref(), key_request().as_ref());
+    assert_eq!(data.payload.len, 7);
+    assert_eq!(data.payload.tag, [0xa5; 16]);
+    assert_eq!(&*data.payload.ciphertext, &[1, 2, 3, 4]);
+    assert_eq!(&*data.payload.aad, &[5, 6, 7]);
+
+    // the default cipher keeps the old encoding
+    assert_eq!(opaque::encode(&data).unwrap(), bytes);
+}
+
+#[test_case]
+fn sealed_data_cipher() {
+    let data = InnerSealedData {
+        key_request: key_request(),
+        cipher: 3,
+        payload: payload(),
+    };
+    let bytes = opaque::encode(&data).unwrap();
+
+    let decoded: InnerSealedData = opaque::decode(&bytes).unwrap();
+    assert_eq!(decoded.cipher, 3);
+    assert_eq!(decoded.key_request.as_ref(), key_request().as_ref());
+
+    let old: OldSealedData = opaque::decode(&bytes).unwrap();
+    assert_eq!(old.key_request.reserved1, 3);
+}
@@ -19,6 +19,11 @@
 #![cfg_attr(target_vendor = "teaclave", feature(rustc_private))]
 #![feature(allocator_api)]
 
+#[cfg(all(not(target_vendor = "teaclave"), feature = "stream"))]
+extern crate sgx_tstd as std;
+#[cfg(all(target_vendor = "teaclave", feature = "stream"))]
+extern crate std;
+
 #[macro_use]
 extern crate alloc;
 
@@ -32,7 +37,12 @@ extern crate sgx_types;
 extern crate sgx_serialize;
 
 pub mod aad;
+pub mod envelope;
+pub mod migrate;
 pub mod seal;
+pub mod share;
+#[cfg(feature = "stream")]
+pub mod stream;
 
 mod internal;
 
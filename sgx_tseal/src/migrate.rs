@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! Migration of sealed data across ISVSVN, CONFIGSVN and CPUSVN upgrades.
+
+use crate::internal::InnerSealedData;
+use alloc::vec::Vec;
+use sgx_crypto::aes::gcm::AesGcm;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
+use sgx_tse::EnclaveReport;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{KeyRequest, Report};
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+/// The security versions a sealed blob may have been sealed under to still be
+/// unsealed or resealed.
+///
+/// A blob sealed by a newer enclave or on a newer platform is always refused,
+/// as EGETKEY would refuse it. There is no default: `SvnPolicy::new(0, 0)`
+/// accepts every older version, `SvnPolicy::current()` none.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SvnPolicy {
+    pub min_isv_svn: u16,
+    pub min_config_svn: u16,
+    /// Refuses blobs sealed under another CPUSVN, e.g. before a microcode
+    /// update. CPUSVNs cannot be ordered, so there is no minimum.
+    pub require_current_cpu_svn: bool,
+}
+
+impl SvnPolicy {
+    pub fn new(min_isv_svn: u16, min_config_svn: u16) -> SvnPolicy {
+        SvnPolicy {
+            min_isv_svn,
+            min_config_svn,
+            require_current_cpu_svn: false,
+        }
+    }
+
+    /// Accepts only blobs sealed under the current security versions.
+    pub fn current() -> SvnPolicy {
+        let report = Report::get_self();
+        SvnPolicy {
+            min_isv_svn: report.body.isv_svn,
+            min_config_svn: report.body.config_svn,
+            require_current_cpu_svn: true,
+        }
+    }
+
+    /// Checks the versions named by the key request of a sealed blob. There
+    /// is no status for CONFIGSVN, so it fails as SgxStatus::InvalidIsvsvn.
+    pub fn check(&self, key_request: &KeyRequest) -> SgxResult {
+        let report = Report::get_self();
+        ensure!(
+            (self.min_isv_svn..=report.body.isv_svn).contains(&key_request.isv_svn),
+            SgxStatus::InvalidIsvsvn
+        );
+        ensure!(
+            (self.min_config_svn..=report.body.config_svn).contains(&key_request.config_svn),
+            SgxStatus::InvalidIsvsvn
+        );
+        if self.require_current_cpu_svn {
+            ensure!(
+                key_request.cpu_svn == report.body.cpu_svn,
+                SgxStatus::InvalidCpusvn
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Reseals a batch of blobs from `SealedData::into_bytes` or
+/// `MacAad::into_bytes` under the current security versions.
+///
+/// Every blob is checked and resealed before anything is returned, so a
+/// failure leaves the stored blobs as they were. The result holds `None` for
+/// the blobs that are already current.
+#[inline]
+pub fn reseal_batch<B: AsRef<[u8]>>(
+    blobs: &[B],
+    policy: &SvnPolicy,
+) -> SgxResult<Vec<Option<Vec<u8>>>> {
+    reseal_batch_with_cipher::<AesGcm<[u8; 0]>, B>(blobs, policy)
+}
+
+pub fn reseal_batch_with_cipher<C: Aead, B: AsRef<[u8]>>(
+    blobs: &[B],
+    policy: &SvnPolicy,
+) -> SgxResult<Vec<Option<Vec<u8>>>>
+where
+    C::Key: FromKey128bit,
+{
+    let mut resealed = Vec::with_capacity(blobs.len());
+    for blob in blobs {
+        let inner = InnerSealedData::from_slice(blob.as_ref())?;
+        if inner.needs_reseal() {
+            let bytes = inner.reseal_with_cipher::<C>(policy)?.into_bytes()?;
+            resealed.push(Some(bytes));
+        } else {
+            resealed.push(None);
+        }
+    }
+    Ok(resealed)
+}
@@ -0,0 +1,100 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::SvnPolicy;
+use sgx_test_utils::test_case;
+use sgx_tse::EnclaveReport;
+use sgx_types::error::SgxStatus;
+use sgx_types::types::{KeyRequest, Report};
+
+fn key_request(isv_svn: u16, config_svn: u16) -> KeyRequest {
+    KeyRequest {
+        isv_svn,
+        config_svn,
+        cpu_svn: Report::get_self().body.cpu_svn,
+        ..Default::default()
+    }
+}
+
+#[test_case]
+fn svn_policy_bounds() {
+    let body = &Report::get_self().body;
+    let (isv_svn, config_svn) = (body.isv_svn, body.config_svn);
+    let any = SvnPolicy::new(0, 0);
+
+    assert!(any.check(&key_request(isv_svn, config_svn)).is_ok());
+    assert!(SvnPolicy::current()
+        .check(&key_request(isv_svn, config_svn))
+        .is_ok());
+
+    // sealed by a newer enclave, or below the minimum
+    if isv_svn < u16::MAX {
+        let newer = key_request(isv_svn + 1, config_svn);
+        assert_eq!(any.check(&newer), Err(SgxStatus::InvalidIsvsvn));
+        let policy = SvnPolicy::new(isv_svn + 1, 0);
+        let current = key_request(isv_svn, config_svn);
+        assert_eq!(policy.check(&current), Err(SgxStatus::InvalidIsvsvn));
+    }
+    if config_svn < u16::MAX {
+        let newer = key_request(isv_svn, config_svn + 1);
+        assert_eq!(any.check(&newer), Err(SgxStatus::InvalidIsvsvn));
+        let policy = SvnPolicy::new(0, config_svn + 1);
+        let current = key_request(isv_svn, config_svn);
+        assert_eq!(policy.check(&current), Err(SgxStatus::InvalidIsvsvn));
+    }
+
+    // sealed by an older enclave
+    if isv_svn > 0 {
+        let key_request = key_request(isv_svn - 1, config_svn);
+        assert!(any.check(&key_request).is_ok());
+        assert!(SvnPolicy::new(isv_svn - 1, 0).check(&key_request).is_ok());
+        assert_eq!(
+            SvnPolicy::current().check(&key_request),
+            Err(SgxStatus::InvalidIsvsvn)
+        );
+    }
+    if config_svn > 0 {
+        let key_request = key_request(isv_svn, config_svn - 1);
+        assert!(any.check(&key_request).is_ok());
+        assert!(SvnPolicy::new(0, config_svn - 1)
+            .check(&key_request)
+            .is_ok());
+        assert_eq!(
+            SvnPolicy::current().check(&key_request),
+            Err(SgxStatus::InvalidIsvsvn)
+        );
+    }
+}
+
+#[test_case]
+fn svn_policy_cpu_svn() {
+    let body = &Report::get_self().body;
+    let mut key_request = key_request(body.isv_svn, body.config_svn);
+    key_request.cpu_svn.svn[0] ^= 1;
+
+    let mut policy = SvnPolicy::new(0, 0);
+    assert!(policy.check(&key_request).is_ok());
+    policy.require_current_cpu_svn = true;
+    assert_eq!(policy.check(&key_request), Err(SgxStatus::InvalidCpusvn));
+    assert_eq!(
+        SvnPolicy::current().check(&key_request),
+        Err(SgxStatus::InvalidCpusvn)
+    );
+
+    key_request.cpu_svn = body.cpu_svn;
+    assert!(policy.check(&key_request).is_ok());
+}
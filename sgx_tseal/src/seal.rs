@@ -15,7 +15,8 @@
 // specific language governing permissions and limitations
 // under the License..
 
-use crate::internal::InnerSealedData;
+use crate::internal::{InnerSealedData, InnerUnsealedData};
+use crate::migrate::SvnPolicy;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::alloc::Allocator;
@@ -24,10 +25,12 @@ use core::fmt;
 use core::marker::PhantomData;
 use core::mem;
 use core::slice;
+use sgx_crypto::aes::gcm::AesGcm;
+use sgx_crypto::cipher::{Aead, FromKey128bit};
 use sgx_trts::trts::{is_within_enclave, is_within_host, EnclaveRange};
 use sgx_types::error::{SgxResult, SgxStatus};
 use sgx_types::marker::ContiguousMemory;
-use sgx_types::types::{Attributes, KeyPolicy, Mac};
+use sgx_types::types::{Attributes, KeyPolicy, KeyRequest, Mac};
 
 #[cfg(feature = "serialize")]
 use sgx_serialize::{Deserialize, Serialize};
@@ -70,6 +73,54 @@ impl<T: ContiguousMemory + ?Sized> SealedData<T> {
             })
     }
 
+    /// Seals the data with the given cipher instead of AES-GCM. The same cipher
+    /// must be passed to `unseal_with_cipher`; the blob records the cipher, and
+    /// another one is rejected with InvalidParameter.
+    ///
+    /// A cipher with a larger key, e.g. AES-256-GCM or ChaCha20-Poly1305, is
+    /// keyed by expanding the 128-bit seal key (see `FromKey128bit`), so it
+    /// does not add security over the 128-bit seal key.
+    pub fn seal_with_cipher<C: Aead>(data: &T, aad: Option<&[u8]>) -> SgxResult<SealedData<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        let size = mem::size_of_val(data);
+        ensure!(size != 0, SgxStatus::InvalidParameter);
+
+        let plaintext = unsafe { slice::from_raw_parts(data as *const _ as *const u8, size) };
+        InnerSealedData::seal_with_cipher::<C>(plaintext, aad).map(|inner| SealedData {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn seal_with_key_policy_and_cipher<C: Aead>(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        data: &T,
+        aad: Option<&[u8]>,
+    ) -> SgxResult<SealedData<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        let size = mem::size_of_val(data);
+        ensure!(size != 0, SgxStatus::InvalidParameter);
+
+        let plaintext = unsafe { slice::from_raw_parts(data as *const _ as *const u8, size) };
+        InnerSealedData::seal_with_key_policy_and_cipher::<C>(
+            key_policy,
+            attribute_mask,
+            misc_mask,
+            plaintext,
+            aad,
+        )
+        .map(|inner| SealedData {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
     #[inline]
     pub fn into_bytes(self) -> SgxResult<Vec<u8>> {
         self.inner.into_bytes()
@@ -114,11 +165,62 @@ impl<T: ContiguousMemory + ?Sized> SealedData<T> {
     pub fn payload_size(&self) -> u32 {
         self.inner.payload.len
     }
+
+    #[inline]
+    pub fn key_request(&self) -> &KeyRequest {
+        &self.inner.key_request
+    }
+
+    /// Whether the data was sealed under an older ISVSVN, CONFIGSVN or CPUSVN
+    /// than the enclave runs with, and should be resealed.
+    #[inline]
+    pub fn needs_reseal(&self) -> bool {
+        self.inner.needs_reseal()
+    }
+
+    /// Unseals the data and seals it again under the current security
+    /// versions, if the policy accepts the versions it was sealed under.
+    #[inline]
+    pub fn reseal(self, policy: &SvnPolicy) -> SgxResult<SealedData<T>> {
+        self.reseal_with_cipher::<AesGcm<[u8; 0]>>(policy)
+    }
+
+    pub fn reseal_with_cipher<C: Aead>(self, policy: &SvnPolicy) -> SgxResult<SealedData<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        self.inner
+            .reseal_with_cipher::<C>(policy)
+            .map(|inner| SealedData {
+                inner,
+                marker: PhantomData,
+            })
+    }
 }
 
 impl<T: ContiguousMemory> SealedData<T> {
+    #[inline]
     pub fn unseal(self) -> SgxResult<UnsealedData<T>> {
-        self.inner.unseal().map(|inner| {
+        Self::into_unsealed(self.inner.unseal())
+    }
+
+    #[inline]
+    pub fn unseal_with_cipher<C: Aead>(self) -> SgxResult<UnsealedData<T>>
+    where
+        C::Key: FromKey128bit,
+    {
+        Self::into_unsealed(self.inner.unseal_with_cipher::<C>())
+    }
+
+    /// Unseals the data only if the policy accepts the security versions it
+    /// was sealed under.
+    pub fn unseal_with_policy(self, policy: &SvnPolicy) -> SgxResult<UnsealedData<T>> {
+        policy.check(&self.inner.key_request)?;
+        self.unseal()
+    }
+
+    fn into_unsealed(result: SgxResult<InnerUnsealedData>) -> SgxResult<UnsealedData<T>> {
+        result.map(|inner| {
             let ptr = Box::into_raw(inner.plaintext);
             UnsealedData {
                 payload_size: inner.payload_len,
@@ -131,8 +233,28 @@ impl<T: ContiguousMemory> SealedData<T> {
 }
 
 impl<T: ContiguousMemory> SealedData<[T]> {
+    #[inline]
     pub fn unseal(self) -> SgxResult<UnsealedData<[T]>> {
-        self.inner.unseal().map(|inner| UnsealedData {
+        Self::into_unsealed(self.inner.unseal())
+    }
+
+    #[inline]
+    pub fn unseal_with_cipher<C: Aead>(self) -> SgxResult<UnsealedData<[T]>>
+    where
+        C::Key: FromKey128bit,
+    {
+        Self::into_unsealed(self.inner.unseal_with_cipher::<C>())
+    }
+
+    /// Unseals the data only if the policy accepts the security versions it
+    /// was sealed under.
+    pub fn unseal_with_policy(self, policy: &SvnPolicy) -> SgxResult<UnsealedData<[T]>> {
+        policy.check(&self.inner.key_request)?;
+        self.unseal()
+    }
+
+    fn into_unsealed(result: SgxResult<InnerUnsealedData>) -> SgxResult<UnsealedData<[T]>> {
+        result.map(|inner| UnsealedData {
             payload_size: inner.payload_len,
             plaintext: unsafe { mem::transmute(inner.plaintext) },
             aad: inner.aad,
@@ -149,6 +271,17 @@ pub struct UnsealedData<T: ?Sized> {
     marker: PhantomData<T>,
 }
 
+impl<T: ?Sized> UnsealedData<T> {
+    pub(crate) fn new(plaintext: Box<T>, aad: Box<[u8]>) -> UnsealedData<T> {
+        UnsealedData {
+            payload_size: (mem::size_of_val(&*plaintext) + aad.len()) as u32,
+            plaintext,
+            aad,
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<T: ContiguousMemory + ?Sized> UnsealedData<T> {
     #[inline]
     pub fn payload_size(&self) -> u32 {
@@ -0,0 +1,102 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use crate::seal::SealedData;
+use alloc::vec::Vec;
+use core::alloc::Allocator;
+use sgx_crypto::shamir::Share;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::memeq::ConstTimeEq;
+use sgx_types::types::{Attributes, KeyPolicy, Mac};
+
+// Binds the sealed blob to its purpose, so an ordinary sealed buffer is never
+// taken for a share.
+const SHARE_AAD: &[u8] = b"sgx_tseal shamir share";
+
+/// A secret share sealed to the enclave.
+///
+/// The share keeps its HMAC tag through sealing, so the recovered share is
+/// still checked by `Shamir::combine`.
+#[derive(Debug)]
+pub struct SealedShare {
+    inner: SealedData<[u8]>,
+}
+
+impl SealedShare {
+    pub fn seal(share: &Share) -> SgxResult<SealedShare> {
+        let mut plaintext = share.to_bytes();
+        let result = SealedData::<[u8]>::seal(plaintext.as_slice(), Some(SHARE_AAD));
+        plaintext.fill(0);
+        result.map(|inner| SealedShare { inner })
+    }
+
+    pub fn seal_with_key_policy(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        share: &Share,
+    ) -> SgxResult<SealedShare> {
+        let mut plaintext = share.to_bytes();
+        let result = SealedData::<[u8]>::seal_with_key_policy(
+            key_policy,
+            attribute_mask,
+            misc_mask,
+            plaintext.as_slice(),
+            Some(SHARE_AAD),
+        );
+        plaintext.fill(0);
+        result.map(|inner| SealedShare { inner })
+    }
+
+    pub fn unseal(self) -> SgxResult<Share> {
+        let unsealed = self.inner.unseal()?;
+        ensure!(
+            unsealed.to_aad().ct_eq(SHARE_AAD),
+            SgxStatus::InvalidParameter
+        );
+
+        let mut plaintext = unsealed.into_plaintext();
+        let share = Share::from_slice(&plaintext);
+        plaintext.fill(0);
+        share
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> SgxResult<Vec<u8>> {
+        self.inner.into_bytes()
+    }
+
+    #[inline]
+    pub fn to_bytes(&self) -> SgxResult<Vec<u8>> {
+        self.inner.to_bytes()
+    }
+
+    #[inline]
+    pub fn from_bytes<A: Allocator>(data: Vec<u8, A>) -> SgxResult<SealedShare> {
+        SealedData::<[u8]>::from_bytes(data).map(|inner| SealedShare { inner })
+    }
+
+    #[inline]
+    pub fn from_slice(data: &[u8]) -> SgxResult<SealedShare> {
+        SealedData::<[u8]>::from_slice(data).map(|inner| SealedShare { inner })
+    }
+
+    #[inline]
+    pub fn tag(&self) -> Mac {
+        self.inner.tag()
+    }
+}
@@ -0,0 +1,432 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+//! Streaming sealing of payloads too large to hold in enclave memory.
+//!
+//! A sealed stream is a header followed by segments:
+//!
+//! ```text
+//! header:  magic (8) || version (4) || segment size (4) || key request (512)
+//! segment: ciphertext (up to segment size) || tag (16)
+//! ```
+//!
+//! Every segment is AES-GCM sealed under one key derived from the seal key
+//! named by the key request, with the header as associated data. The nonce of
+//! segment `i` is `i` (little endian, 8 bytes) followed by a one byte final
+//! flag, so segments cannot be reordered, dropped or moved between streams.
+//! Only the last segment is shorter than the segment size, and only it carries
+//! the final flag, so a truncated stream is detected as well.
+
+use crate::internal::InnerSealedData;
+use alloc::vec::Vec;
+use core::cmp;
+use core::mem;
+use core::ptr;
+use sgx_crypto::aes::gcm::{Aad, AesGcm, Nonce};
+use sgx_crypto::kdf::HkdfSha256;
+use sgx_tse::EnclaveKey;
+use sgx_types::error::{SgxResult, SgxStatus};
+use sgx_types::types::{
+    AlignKey128bit, Attributes, Key128bit, KeyName, KeyPolicy, KeyRequest, Mac128bit,
+    AESGCM_IV_SIZE, KEY_128BIT_SIZE, MAC_128BIT_SIZE, TSEAL_DEFAULT_MISCMASK,
+};
+use std::io::{self, ErrorKind, Read, Write};
+
+#[cfg(feature = "unit_test")]
+mod tests;
+
+pub const STREAM_VERSION: u32 = 1;
+pub const DEFAULT_SEGMENT_SIZE: usize = 0x10000;
+pub const MAX_SEGMENT_SIZE: usize = 0x400000;
+
+const STREAM_MAGIC: [u8; 8] = *b"TSEALSTM";
+const KEY_REQUEST_OFFSET: usize = 16;
+const HEADER_SIZE: usize = KEY_REQUEST_OFFSET + mem::size_of::<KeyRequest>();
+const STREAM_KEY_INFO: &[u8] = b"sgx_tseal stream key";
+
+/// Seals everything written to it into a stream on `inner`.
+///
+/// Data is buffered and sealed one segment at a time, so enclave memory use
+/// is bounded by the segment size. `finish` must be called to seal the last
+/// segment; a stream that is dropped unfinished is rejected as truncated.
+///
+/// After an error of `inner` the stream is in an unknown state, so every
+/// later call fails with SgxStatus::InvalidState.
+pub struct SealWriter<W: Write> {
+    inner: W,
+    stream: Stream,
+    key_request: KeyRequest,
+    index: u64,
+    started: bool,
+    failed: bool,
+}
+
+impl<W: Write> SealWriter<W> {
+    pub fn new(inner: W) -> SgxResult<SealWriter<W>> {
+        Self::with_key_policy(
+            InnerSealedData::default_key_policy(),
+            InnerSealedData::default_attribute_mask(),
+            TSEAL_DEFAULT_MISCMASK,
+            inner,
+        )
+    }
+
+    pub fn with_key_policy(
+        key_policy: KeyPolicy,
+        attribute_mask: Attributes,
+        misc_mask: u32,
+        inner: W,
+    ) -> SgxResult<SealWriter<W>> {
+        let key_request = InnerSealedData::seal_key_request(key_policy, attribute_mask, misc_mask)?;
+        let seal_key = key_request.get_align_key()?;
+        let stream = Stream::new(&key_request, seal_key, DEFAULT_SEGMENT_SIZE)?;
+        Ok(SealWriter {
+            inner,
+            stream,
+            key_request,
+            index: 0,
+            started: false,
+            failed: false,
+        })
+    }
+
+    /// Sets the segment size, which must be done before anything is written.
+    pub fn set_segment_size(&mut self, segment_size: usize) -> SgxResult {
+        ensure!(
+            !self.started && self.stream.buf.is_empty(),
+            SgxStatus::InvalidState
+        );
+        ensure!(
+            (1..=MAX_SEGMENT_SIZE).contains(&segment_size),
+            SgxStatus::InvalidParameter
+        );
+        self.stream.set_segment_size(segment_size);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn segment_size(&self) -> usize {
+        self.stream.segment_size
+    }
+
+    #[inline]
+    pub fn key_request(&self) -> &KeyRequest {
+        &self.key_request
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Seals the last segment, flushes and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        // A full segment is never the last one: the final segment is always
+        // shorter, possibly empty.
+        if self.stream.buf.len() == self.stream.segment_size {
+            self.write_segment(false)?;
+        }
+        self.write_segment(true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    // The segment is sealed in place, so a segment that fails to be written
+    // must never be sealed again: the writer fails from then on.
+    fn write_segment(&mut self, last: bool) -> io::Result<()> {
+        ensure!(!self.failed, io::Error::from(SgxStatus::InvalidState));
+
+        let result = self.seal_and_write(last);
+        self.stream.buf.fill(0);
+        self.stream.buf.clear();
+        match result {
+            Ok(()) => {
+                self.index += 1;
+                Ok(())
+            }
+            Err(e) => {
+                self.failed = true;
+                Err(e)
+            }
+        }
+    }
+
+    fn seal_and_write(&mut self, last: bool) -> io::Result<()> {
+        if !self.started {
+            self.inner.write_all(&self.stream.header)?;
+            self.started = true;
+        }
+
+        let tag = self.stream.seal_segment(self.index, last)?;
+        self.inner.write_all(&self.stream.buf)?;
+        self.inner.write_all(&tag)
+    }
+}
+
+impl<W: Write> Write for SealWriter<W> {
+    // A full segment is only sealed once more data follows it, so an error
+    // is reported before any byte of `buf` is taken, or by the next call.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        ensure!(!self.failed, io::Error::from(SgxStatus::InvalidState));
+
+        let segment_size = self.stream.segment_size;
+        let mut written = 0;
+        while written < buf.len() {
+            if self.stream.buf.len() == segment_size {
+                match self.write_segment(false) {
+                    Ok(()) => {}
+                    Err(_) if written > 0 => break,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let len = cmp::min(buf.len() - written, segment_size - self.stream.buf.len());
+            self.stream
+                .buf
+                .extend_from_slice(&buf[written..written + len]);
+            written += len;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        ensure!(!self.failed, io::Error::from(SgxStatus::InvalidState));
+        self.inner.flush()
+    }
+}
+
+/// Unseals a stream written by [`SealWriter`] from `inner`.
+///
+/// Data is only returned once its segment has been authenticated. A
+/// modified, reordered or truncated stream, or one with data after the final
+/// segment, fails with an error of SgxStatus::MacMismatch. After any error,
+/// of `inner` too, every later read fails with SgxStatus::InvalidState.
+pub struct UnsealReader<R: Read> {
+    inner: R,
+    stream: Stream,
+    key_request: KeyRequest,
+    index: u64,
+    pos: usize,
+    finished: bool,
+    failed: bool,
+}
+
+impl<R: Read> UnsealReader<R> {
+    pub fn new(mut inner: R) -> io::Result<UnsealReader<R>> {
+        let mut header = [0_u8; HEADER_SIZE];
+        inner.read_exact(&mut header)?;
+
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        let segment_size = u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize;
+        ensure!(
+            header[..8] == STREAM_MAGIC && version == STREAM_VERSION,
+            io::Error::from(SgxStatus::InvalidParameter)
+        );
+        ensure!(
+            (1..=MAX_SEGMENT_SIZE).contains(&segment_size),
+            io::Error::from(SgxStatus::InvalidParameter)
+        );
+
+        // The key name is checked before the bytes are taken as a KeyRequest.
+        let key_name = u16::from_le_bytes(
+            header[KEY_REQUEST_OFFSET..KEY_REQUEST_OFFSET + 2]
+                .try_into()
+                .unwrap(),
+        );
+        ensure!(
+            key_name == KeyName::Seal as u16,
+            io::Error::from(SgxStatus::InvalidParameter)
+        );
+        let key_request: KeyRequest =
+            unsafe { ptr::read_unaligned(header[KEY_REQUEST_OFFSET..].as_ptr().cast()) };
+
+        let seal_key = InnerSealedData::unseal_key(&key_request)?;
+        let stream = Stream::new(&key_request, seal_key, segment_size)?;
+        Ok(UnsealReader {
+            inner,
+            stream,
+            key_request,
+            index: 0,
+            pos: 0,
+            finished: false,
+            failed: false,
+        })
+    }
+
+    #[inline]
+    pub fn segment_size(&self) -> usize {
+        self.stream.segment_size
+    }
+
+    #[inline]
+    pub fn key_request(&self) -> &KeyRequest {
+        &self.key_request
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    fn read_segment(&mut self) -> io::Result<()> {
+        let size = self.stream.segment_size + MAC_128BIT_SIZE;
+        self.stream.buf.resize(size, 0);
+
+        let mut len = 0;
+        while len < size {
+            match self.inner.read(&mut self.stream.buf[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        ensure!(
+            len >= MAC_128BIT_SIZE,
+            io::Error::from(SgxStatus::MacMismatch)
+        );
+
+        let last = len < size;
+        let mut tag = [0_u8; MAC_128BIT_SIZE];
+        tag.copy_from_slice(&self.stream.buf[len - MAC_128BIT_SIZE..len]);
+        self.stream.buf.truncate(len - MAC_128BIT_SIZE);
+
+        self.stream.open_segment(self.index, last, &tag)?;
+        if last {
+            self.check_end()?;
+        }
+        self.index += 1;
+        self.pos = 0;
+        self.finished = last;
+        Ok(())
+    }
+
+    // Nothing may follow the final segment, even after a reader has once
+    // reported the end of the stream.
+    fn check_end(&mut self) -> io::Result<()> {
+        let mut byte = [0_u8; 1];
+        loop {
+            match self.inner.read(&mut byte) {
+                Ok(0) => return Ok(()),
+                Ok(_) => bail!(io::Error::from(SgxStatus::MacMismatch)),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for UnsealReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        ensure!(!self.failed, io::Error::from(SgxStatus::InvalidState));
+
+        if self.pos == self.stream.buf.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            // The buffer may hold ciphertext, or plaintext that failed to
+            // authenticate, neither of which may be returned.
+            if let Err(e) = self.read_segment() {
+                self.stream.buf.fill(0);
+                self.stream.buf.clear();
+                self.pos = 0;
+                self.failed = true;
+                return Err(e);
+            }
+        }
+
+        let len = cmp::min(buf.len(), self.stream.buf.len() - self.pos);
+        buf[..len].copy_from_slice(&self.stream.buf[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+// The key, header and segment buffer shared by both directions. The buffer
+// holds plaintext, so it is cleared along with the key.
+struct Stream {
+    key: Key128bit,
+    header: [u8; HEADER_SIZE],
+    segment_size: usize,
+    buf: Vec<u8>,
+}
+
+impl Stream {
+    fn new(
+        key_request: &KeyRequest,
+        mut seal_key: AlignKey128bit,
+        segment_size: usize,
+    ) -> SgxResult<Stream> {
+        let mut key = [0_u8; KEY_128BIT_SIZE];
+        let result = HkdfSha256::derive(&[], seal_key.as_ref(), STREAM_KEY_INFO, &mut key);
+        seal_key.as_mut().fill(0);
+        result?;
+
+        let mut header = [0_u8; HEADER_SIZE];
+        header[..8].copy_from_slice(&STREAM_MAGIC);
+        header[8..12].copy_from_slice(&STREAM_VERSION.to_le_bytes());
+        header[KEY_REQUEST_OFFSET..].copy_from_slice(key_request.as_ref());
+
+        let mut stream = Stream {
+            key,
+            header,
+            segment_size: 0,
+            buf: Vec::new(),
+        };
+        stream.set_segment_size(segment_size);
+        Ok(stream)
+    }
+
+    fn set_segment_size(&mut self, segment_size: usize) {
+        self.header[12..16].copy_from_slice(&(segment_size as u32).to_le_bytes());
+        self.segment_size = segment_size;
+        self.buf = Vec::with_capacity(segment_size + MAC_128BIT_SIZE);
+    }
+
+    fn seal_segment(&mut self, index: u64, last: bool) -> SgxResult<Mac128bit> {
+        ensure!(index < u64::MAX, SgxStatus::InvalidState);
+        let mut cipher = AesGcm::new(
+            &self.key,
+            segment_nonce(index, last),
+            Aad::from(&self.header[..]),
+        )?;
+        cipher.encrypt_in_place(&mut self.buf)
+    }
+
+    fn open_segment(&mut self, index: u64, last: bool, tag: &Mac128bit) -> SgxResult {
+        let mut cipher = AesGcm::new(
+            &self.key,
+            segment_nonce(index, last),
+            Aad::from(&self.header[..]),
+        )?;
+        cipher.decrypt_in_place(&mut self.buf, tag)
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        self.key.fill(0);
+        self.buf.fill(0);
+    }
+}
+
+fn segment_nonce(index: u64, last: bool) -> Nonce {
+    let mut nonce = [0_u8; AESGCM_IV_SIZE];
+    nonce[..8].copy_from_slice(&index.to_le_bytes());
+    nonce[8] = last as u8;
+    Nonce::from(nonce)
+}
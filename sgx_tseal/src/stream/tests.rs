@@ -0,0 +1,266 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License..
+
+use super::{SealWriter, UnsealReader, HEADER_SIZE};
+use alloc::vec::Vec;
+use sgx_test_utils::test_case;
+use sgx_types::types::MAC_128BIT_SIZE;
+use std::io::{self, Cursor, Read, Write};
+
+const SEGMENT_SIZE: usize = 100;
+const SEALED_SEGMENT_SIZE: usize = SEGMENT_SIZE + MAC_128BIT_SIZE;
+
+fn seal(data: &[u8]) -> Vec<u8> {
+    let mut writer = SealWriter::new(Vec::new()).unwrap();
+    writer.set_segment_size(SEGMENT_SIZE).unwrap();
+    writer.write_all(data).unwrap();
+    writer.finish().unwrap()
+}
+
+fn unseal<R: Read>(reader: R) -> io::Result<Vec<u8>> {
+    let mut reader = UnsealReader::new(reader)?;
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+#[test_case]
+fn stream_round_trip() {
+    for len in [0, 1, SEGMENT_SIZE - 1, SEGMENT_SIZE, 3 * SEGMENT_SIZE + 1] {
+        let data = data(len);
+        let sealed = seal(&data);
+        assert_eq!(
+            sealed.len(),
+            HEADER_SIZE + len + (len / SEGMENT_SIZE + 1) * MAC_128BIT_SIZE
+        );
+        assert_eq!(unseal(Cursor::new(&sealed)).unwrap(), data);
+    }
+}
+
+#[test_case]
+fn stream_truncated() {
+    let sealed = seal(&data(3 * SEGMENT_SIZE + 1));
+    // every cut, including one at a segment boundary
+    for len in 0..sealed.len() {
+        assert!(unseal(Cursor::new(&sealed[..len])).is_err(), "{}", len);
+    }
+}
+
+#[test_case]
+fn stream_reordered() {
+    let sealed = seal(&data(3 * SEGMENT_SIZE + 1));
+    let first = HEADER_SIZE..HEADER_SIZE + SEALED_SEGMENT_SIZE;
+    let second = first.end..first.end + SEALED_SEGMENT_SIZE;
+
+    let mut swapped = sealed.clone();
+    swapped[first.clone()].copy_from_slice(&sealed[second.clone()]);
+    swapped[second].copy_from_slice(&sealed[first.clone()]);
+    assert!(unseal(Cursor::new(&swapped)).is_err());
+
+    // a segment taken from another stream under the same key
+    let other = seal(&data(3 * SEGMENT_SIZE + 1));
+    let mut spliced = sealed;
+    spliced[first.clone()].copy_from_slice(&other[first]);
+    assert!(unseal(Cursor::new(&spliced)).is_err());
+}
+
+#[test_case]
+fn stream_tampered_header() {
+    let sealed = seal(&data(SEGMENT_SIZE + 1));
+    for i in (0..HEADER_SIZE).step_by(7) {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 1;
+        assert!(unseal(Cursor::new(&tampered)).is_err(), "{}", i);
+    }
+}
+
+// Reports the end of the stream once after `eof_at` bytes, then carries on.
+struct PausingReader {
+    data: Vec<u8>,
+    pos: usize,
+    eof_at: Option<usize>,
+}
+
+impl Read for PausingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let end = match self.eof_at {
+            Some(eof_at) if self.pos == eof_at => {
+                self.eof_at = None;
+                return Ok(0);
+            }
+            Some(eof_at) => eof_at,
+            None => self.data.len(),
+        };
+        let len = buf.len().min(end - self.pos);
+        buf[..len].copy_from_slice(&self.data[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[test_case]
+fn stream_trailing_data() {
+    let data = data(SEGMENT_SIZE + 1);
+    let sealed = seal(&data);
+
+    let mut extended = sealed.clone();
+    extended.push(0);
+    assert!(unseal(Cursor::new(&extended)).is_err());
+
+    let reader = PausingReader {
+        data: extended,
+        pos: 0,
+        eof_at: Some(sealed.len()),
+    };
+    assert!(unseal(reader).is_err());
+
+    let reader = PausingReader {
+        data: sealed.clone(),
+        pos: 0,
+        eof_at: Some(sealed.len()),
+    };
+    assert_eq!(unseal(reader).unwrap(), data);
+}
+
+// Fails once when `fail_at` bytes have been written, then carries on.
+struct FailingWriter {
+    data: Vec<u8>,
+    fail_at: Option<usize>,
+}
+
+impl Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = match self.fail_at {
+            Some(fail_at) if self.data.len() == fail_at => {
+                self.fail_at = None;
+                return Err(io::Error::from(io::ErrorKind::Other));
+            }
+            Some(fail_at) => buf.len().min(fail_at - self.data.len()),
+            None => buf.len(),
+        };
+        self.data.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test_case]
+fn stream_write_error() {
+    let data = data(3 * SEGMENT_SIZE + 1);
+    // in the header, in the first segment and in the tag of the second
+    for fail_at in [
+        1,
+        HEADER_SIZE + 1,
+        HEADER_SIZE + 2 * SEALED_SEGMENT_SIZE - 1,
+    ] {
+        let inner = FailingWriter {
+            data: Vec::new(),
+            fail_at: Some(fail_at),
+        };
+        let mut writer = SealWriter::new(inner).unwrap();
+        writer.set_segment_size(SEGMENT_SIZE).unwrap();
+
+        let mut written = 0;
+        while written < data.len() {
+            match writer.write(&data[written..]) {
+                Ok(len) => written += len,
+                Err(_) => break,
+            }
+        }
+        // every retry fails instead of sealing the segment again
+        assert!(written < data.len(), "{}", fail_at);
+        assert!(writer.write(&data[written..]).is_err(), "{}", fail_at);
+        assert!(writer.flush().is_err(), "{}", fail_at);
+
+        let sealed = &writer.get_ref().data;
+        assert!(sealed.len() <= fail_at, "{}", fail_at);
+        for segment in data.chunks_exact(SEGMENT_SIZE) {
+            let part = &segment[..16];
+            assert!(!sealed.windows(16).any(|w| w == part), "{}", fail_at);
+        }
+        assert!(writer.finish().is_err(), "{}", fail_at);
+    }
+}
+
+// Fails once after `fail_at` bytes, then carries on.
+struct FailingReader {
+    data: Vec<u8>,
+    pos: usize,
+    fail_at: Option<usize>,
+}
+
+impl Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let end = match self.fail_at {
+            Some(fail_at) if self.pos == fail_at => {
+                self.fail_at = None;
+                return Err(io::Error::from(io::ErrorKind::Other));
+            }
+            Some(fail_at) => fail_at,
+            None => self.data.len(),
+        };
+        let len = buf.len().min(end - self.pos);
+        buf[..len].copy_from_slice(&self.data[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+#[test_case]
+fn stream_read_error() {
+    let data = data(SEGMENT_SIZE + 1);
+    let sealed = seal(&data);
+    let mut tampered = sealed.clone();
+    tampered[HEADER_SIZE] ^= 1;
+
+    // in the first segment, before the final one and at the end of the stream
+    for (sealed, fail_at) in [
+        (&sealed, HEADER_SIZE + 1),
+        (&sealed, HEADER_SIZE + SEALED_SEGMENT_SIZE),
+        (&sealed, sealed.len()),
+        (&tampered, tampered.len()),
+    ] {
+        let inner = FailingReader {
+            data: sealed.clone(),
+            pos: 0,
+            fail_at: Some(fail_at),
+        };
+        let mut reader = UnsealReader::new(inner).unwrap();
+
+        let mut read = Vec::new();
+        let mut buf = [0_u8; 7];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => panic!("{}", fail_at),
+                Ok(len) => read.extend_from_slice(&buf[..len]),
+                Err(_) => break,
+            }
+        }
+        // every retry fails instead of returning the buffered segment
+        for _ in 0..3 {
+            assert!(reader.read(&mut buf).is_err(), "{}", fail_at);
+        }
+        assert!(data.starts_with(&read), "{}", fail_at);
+    }
+}
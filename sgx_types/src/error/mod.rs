@@ -100,6 +100,7 @@ impl_enum! {
         RecoveryNeeded          = 0x0000_7007,	    /* When openeing the file, recovery is needed, but the recovery process failed. */
         FluchFailed             = 0x0000_7008,	    /* fflush operation (to disk) failed (only used when no EXXX is returned). */
         CloseFailed             = 0x0000_7009,	    /* fclose operation (to disk) failed (only used when no EXXX is returned). */
+        FileRollback            = 0x0000_700A,      /* The file is older than its monotonic counter (rollback attack). */
 
         UnsupportedAttKeyid     = 0x0000_8001,      /* platform quoting infrastructure does not support the key. */
         AttKeyCertFailed        = 0x0000_8002,      /* Failed to generate and certify the attestation key. */
@@ -193,6 +194,7 @@ impl SgxStatus {
             SgxStatus::RecoveryNeeded => "When openeing the file, recovery is needed, but the recovery process failed.",
             SgxStatus::FluchFailed => "fflush operation failed.",
             SgxStatus::CloseFailed => "fclose operation failed.",
+            SgxStatus::FileRollback => "The file is older than its monotonic counter.",
 
             SgxStatus::UnsupportedAttKeyid => "platform quoting infrastructure does not support the key.",
             SgxStatus::AttKeyCertFailed => "Failed to generate and certify the attestation key.",
@@ -278,6 +280,7 @@ impl SgxStatus {
             SgxStatus::RecoveryNeeded => "RecoveryNeeded",
             SgxStatus::FluchFailed => "FluchFailed",
             SgxStatus::CloseFailed => "CloseFailed",
+            SgxStatus::FileRollback => "FileRollback",
 
             SgxStatus::UnsupportedAttKeyid => "UnsupportedAttKeyid",
             SgxStatus::AttKeyCertFailed => "AttKeyCertFailed",
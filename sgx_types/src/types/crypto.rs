@@ -25,6 +25,10 @@ use core::ops::{Deref, DerefMut};
 pub const SHA1_HASH_SIZE: usize = 20;
 pub const SHA256_HASH_SIZE: usize = 32;
 pub const SHA384_HASH_SIZE: usize = 48;
+pub const SHA512_HASH_SIZE: usize = 64;
+pub const SHA512_256_HASH_SIZE: usize = 32;
+pub const SHA3_256_HASH_SIZE: usize = 32;
+pub const SHA3_512_HASH_SIZE: usize = 64;
 pub const SM3_HASH_SIZE: usize = 32;
 
 pub type ShaHandle = *mut c_void;
@@ -82,6 +86,18 @@ impl_struct! {
         pub hash: [u8; SHA256_HASH_SIZE],
     }
 
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Sha512_256Hash {
+        pub hash: [u8; SHA512_256_HASH_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Sha3_256Hash {
+        pub hash: [u8; SHA3_256_HASH_SIZE],
+    }
+
     #[repr(C)]
     #[derive(Debug, Eq, PartialEq)]
     pub struct Sm3Hash {
@@ -95,6 +111,18 @@ impl_copy_clone! {
     pub struct Sha384Hash {
         pub hash: [u8; SHA384_HASH_SIZE],
     }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Sha512Hash {
+        pub hash: [u8; SHA512_HASH_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Sha3_512Hash {
+        pub hash: [u8; SHA3_512_HASH_SIZE],
+    }
 }
 
 macro_rules! impl_hash_eq {
@@ -119,39 +147,59 @@ impl_hash_eq! {
     Sha1Hash;
     Sha256Hash;
     Sha384Hash;
+    Sha512Hash;
+    Sha512_256Hash;
+    Sha3_256Hash;
+    Sha3_512Hash;
     Sm3Hash;
 }
 
 impl_struct_default! {
     Sha384Hash; //48
+    Sha512Hash; //64
+    Sha3_512Hash; //64
 }
 
 impl_struct_ContiguousMemory! {
     Sha384Hash;
+    Sha512Hash;
+    Sha3_512Hash;
 }
 
 impl_asref_array! {
     Sha1Hash;
     Sha256Hash;
     Sha384Hash;
+    Sha512Hash;
+    Sha512_256Hash;
+    Sha3_256Hash;
+    Sha3_512Hash;
     Sm3Hash;
 }
 impl_asmut_array! {
     Sha1Hash;
     Sha256Hash;
     Sha384Hash;
+    Sha512Hash;
+    Sha512_256Hash;
+    Sha3_256Hash;
+    Sha3_512Hash;
     Sm3Hash;
 }
 impl_from_array! {
     Sha1Hash;
     Sha256Hash;
     Sha384Hash;
+    Sha512Hash;
+    Sha512_256Hash;
+    Sha3_256Hash;
+    Sha3_512Hash;
     Sm3Hash;
 }
 
 impl_unsafe_marker_for! {
     BytewiseEquality,
-    Sha1Hash Sha256Hash Sha384Hash Sm3Hash
+    Sha1Hash Sha256Hash Sha384Hash Sha512Hash Sha512_256Hash Sha3_256Hash Sha3_512Hash Sm3Hash
 }
 
 macro_rules! impl_hash_deref {
@@ -188,16 +236,28 @@ impl_hash_deref! {
     Sha1Hash;
     Sha256Hash;
     Sha384Hash;
+    Sha512Hash;
+    Sha512_256Hash;
+    Sha3_256Hash;
+    Sha3_512Hash;
     Sm3Hash;
 }
 
 pub const KEY_128BIT_SIZE: usize = 16;
+pub const KEY_192BIT_SIZE: usize = 24;
 pub const KEY_256BIT_SIZE: usize = 32;
 pub const MAC_128BIT_SIZE: usize = 16;
 pub const MAC_256BIT_SIZE: usize = 32;
+pub const MAC_384BIT_SIZE: usize = 48;
 
 pub const ECP256_KEY_SIZE: usize = 32;
 pub const NISTP_ECP256_KEY_SIZE: usize = ECP256_KEY_SIZE / 4;
+pub const ECP384_KEY_SIZE: usize = 48;
+pub const NISTP_ECP384_KEY_SIZE: usize = ECP384_KEY_SIZE / 4;
+
+pub const RSA4096_KEY_SIZE: usize = 512;
+pub const RSA4096_PRI_EXP_SIZE: usize = 512;
+pub const RSA4096_PUB_EXP_SIZE: usize = 4;
 
 pub const RSA3072_KEY_SIZE: usize = 384;
 pub const RSA3072_PRI_EXP_SIZE: usize = 384;
@@ -212,12 +272,15 @@ pub const AESCCM_IV_SIZE: usize = 12;
 pub const AESCBC_IV_SIZE: usize = 16;
 pub const AESCTR_CTR_SIZE: usize = 16;
 pub const SM4CCM_IV_SIZE: usize = 12;
+pub const SM4GCM_IV_SIZE: usize = 12;
 pub const SM4CBC_IV_SIZE: usize = 16;
 pub const SM4CTR_CTR_SIZE: usize = 16;
 
+pub type Key192bit = [u8; KEY_192BIT_SIZE];
 pub type Key256bit = [u8; KEY_256BIT_SIZE];
 pub type Mac128bit = [u8; MAC_128BIT_SIZE];
 pub type Mac256bit = [u8; MAC_256BIT_SIZE];
+pub type Mac384bit = [u8; MAC_384BIT_SIZE];
 
 impl_struct! {
     #[repr(C)]
@@ -277,6 +340,72 @@ impl_unsafe_marker_for! {
     Ec256PrivateKey Ec256SharedKey Ec256PublicKey Ec256Signature
 }
 
+impl_copy_clone! {
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Ec384SharedKey {
+        pub s: [u8; ECP384_KEY_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Ec384PrivateKey {
+        pub r: [u8; ECP384_KEY_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Ec384PublicKey {
+        pub gx: [u8; ECP384_KEY_SIZE],
+        pub gy: [u8; ECP384_KEY_SIZE],
+    }
+}
+
+impl_struct! {
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Ec384Signature {
+        pub x: [u32; NISTP_ECP384_KEY_SIZE],
+        pub y: [u32; NISTP_ECP384_KEY_SIZE],
+    }
+}
+
+impl_struct_default! {
+    Ec384SharedKey; //48
+    Ec384PrivateKey; //48
+    Ec384PublicKey; //96
+}
+
+impl_struct_ContiguousMemory! {
+    Ec384SharedKey;
+    Ec384PrivateKey;
+    Ec384PublicKey;
+}
+
+impl_asref_array! {
+    Ec384SharedKey;
+    Ec384PrivateKey;
+    Ec384PublicKey;
+    Ec384Signature;
+}
+impl_asmut_array! {
+    Ec384SharedKey;
+    Ec384PrivateKey;
+    Ec384PublicKey;
+    Ec384Signature;
+}
+impl_from_array! {
+    Ec384SharedKey;
+    Ec384PrivateKey;
+    Ec384PublicKey;
+    Ec384Signature;
+}
+
+impl_unsafe_marker_for! {
+    BytewiseEquality,
+    Ec384PrivateKey Ec384SharedKey Ec384PublicKey Ec384Signature
+}
+
 pub type RsaKey = *mut c_void;
 
 /* intel sgx sdk 2.1.3 */
@@ -305,6 +434,155 @@ impl RsaResult {
     }
 }
 
+impl_enum! {
+    #[repr(u32)]
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum RsaHash {
+        Sha256   = 0,   /* SHA-256 digest and MGF1 hash */
+        Sha384   = 1,   /* SHA-384 digest and MGF1 hash */
+        Sha512   = 2,   /* SHA-512 digest and MGF1 hash */
+    }
+}
+
+impl RsaHash {
+    #[inline]
+    pub fn hash_size(&self) -> usize {
+        match self {
+            RsaHash::Sha256 => SHA256_HASH_SIZE,
+            RsaHash::Sha384 => SHA384_HASH_SIZE,
+            RsaHash::Sha512 => SHA512_HASH_SIZE,
+        }
+    }
+}
+
+impl_copy_clone! {
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Rsa4096Param {
+        pub n: [u8; RSA4096_KEY_SIZE],
+        pub d: [u8; RSA4096_PRI_EXP_SIZE],
+        pub e: [u8; RSA4096_PUB_EXP_SIZE],
+        pub p: [u8; RSA4096_KEY_SIZE / 2],
+        pub q: [u8; RSA4096_KEY_SIZE / 2],
+        pub dmp1: [u8; RSA4096_KEY_SIZE / 2],
+        pub dmq1: [u8; RSA4096_KEY_SIZE / 2],
+        pub iqmp: [u8; RSA4096_KEY_SIZE / 2],
+    }
+}
+
+impl_struct_default! {
+    Rsa4096Param; //2308
+}
+
+impl_struct_ContiguousMemory! {
+    Rsa4096Param;
+}
+
+impl_unsafe_marker_for! {BytewiseEquality, Rsa4096Param}
+
+impl_asref_array! {
+    Rsa4096Param;
+}
+impl_asmut_array! {
+    Rsa4096Param;
+}
+impl_from_array! {
+    Rsa4096Param;
+}
+
+impl_copy_clone! {
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Rsa4096PubKey {
+        pub modulus: [u8; RSA4096_KEY_SIZE],
+        pub exponent: [u8; RSA4096_PUB_EXP_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Rsa4096PrivKey {
+        pub modulus: [u8; RSA4096_KEY_SIZE],
+        pub exponent: [u8; RSA4096_PRI_EXP_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Rsa4096Key {
+        pub modulus: [u8; RSA4096_KEY_SIZE],
+        pub d: [u8; RSA4096_PRI_EXP_SIZE],
+        pub e: [u8; RSA4096_PUB_EXP_SIZE],
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Eq, PartialEq)]
+    pub struct Rsa4096Signature {
+        pub signature: [u8; RSA4096_KEY_SIZE],
+    }
+}
+
+impl From<(Rsa4096PrivKey, Rsa4096PubKey)> for Rsa4096Key {
+    #[inline]
+    fn from(key_pair: (Rsa4096PrivKey, Rsa4096PubKey)) -> Rsa4096Key {
+        Rsa4096Key {
+            modulus: key_pair.0.modulus,
+            d: key_pair.0.exponent,
+            e: key_pair.1.exponent,
+        }
+    }
+}
+
+impl From<Rsa4096Key> for (Rsa4096PrivKey, Rsa4096PubKey) {
+    #[inline]
+    fn from(key_pair: Rsa4096Key) -> (Rsa4096PrivKey, Rsa4096PubKey) {
+        (
+            Rsa4096PrivKey {
+                modulus: key_pair.modulus,
+                exponent: key_pair.d,
+            },
+            Rsa4096PubKey {
+                modulus: key_pair.modulus,
+                exponent: key_pair.e,
+            },
+        )
+    }
+}
+
+impl_struct_default! {
+    Rsa4096PubKey; //516
+    Rsa4096PrivKey; //1024
+    Rsa4096Key; //1028
+    Rsa4096Signature; //512
+}
+
+impl_struct_ContiguousMemory! {
+    Rsa4096PubKey;
+    Rsa4096PrivKey;
+    Rsa4096Key;
+    Rsa4096Signature;
+}
+
+impl_unsafe_marker_for! {BytewiseEquality,
+Rsa4096PubKey Rsa4096PrivKey Rsa4096Key Rsa4096Signature}
+
+impl_asref_array! {
+    Rsa4096PubKey;
+    Rsa4096PrivKey;
+    Rsa4096Key;
+    Rsa4096Signature;
+}
+impl_asmut_array! {
+    Rsa4096PubKey;
+    Rsa4096PrivKey;
+    Rsa4096Key;
+    Rsa4096Signature;
+}
+impl_from_array! {
+    Rsa4096PubKey;
+    Rsa4096PrivKey;
+    Rsa4096Key;
+    Rsa4096Signature;
+}
+
 impl_copy_clone! {
     #[repr(C)]
     #[derive(Debug, Eq, PartialEq)]
@@ -941,6 +1219,156 @@ impl fmt::Debug for AlignEc256PrivateKey {
     }
 }
 
+#[repr(C, align(64))]
+#[derive(Copy, Clone, Default)]
+pub struct AlignEc384SharedKey {
+    _pad1: [u8; 8],
+    pub key: Ec384SharedKey,
+    _pad2: [u8; 8],
+}
+
+impl From<Ec384SharedKey> for AlignEc384SharedKey {
+    #[inline]
+    fn from(key: Ec384SharedKey) -> AlignEc384SharedKey {
+        AlignEc384SharedKey {
+            _pad1: [0; 8],
+            key,
+            _pad2: [0; 8],
+        }
+    }
+}
+
+impl From<&Ec384SharedKey> for AlignEc384SharedKey {
+    #[inline]
+    fn from(key: &Ec384SharedKey) -> AlignEc384SharedKey {
+        AlignEc384SharedKey {
+            _pad1: [0; 8],
+            key: *key,
+            _pad2: [0; 8],
+        }
+    }
+}
+
+impl AsRef<[u8; ECP384_KEY_SIZE]> for AlignEc384SharedKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8; ECP384_KEY_SIZE] {
+        self.key.as_ref()
+    }
+}
+
+impl AsMut<[u8; ECP384_KEY_SIZE]> for AlignEc384SharedKey {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8; ECP384_KEY_SIZE] {
+        self.key.as_mut()
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE]> for AlignEc384SharedKey {
+    #[inline]
+    fn from(key: [u8; ECP384_KEY_SIZE]) -> AlignEc384SharedKey {
+        AlignEc384SharedKey::from(Ec384SharedKey::from(key))
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE]> for AlignEc384SharedKey {
+    #[inline]
+    fn from(key: &[u8; ECP384_KEY_SIZE]) -> AlignEc384SharedKey {
+        AlignEc384SharedKey::from(Ec384SharedKey::from(key))
+    }
+}
+
+impl PartialEq for AlignEc384SharedKey {
+    #[inline]
+    fn eq(&self, other: &AlignEc384SharedKey) -> bool {
+        self.key.eq(&other.key)
+    }
+}
+
+impl Eq for AlignEc384SharedKey {}
+
+impl fmt::Debug for AlignEc384SharedKey {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("AlignEc384SharedKey")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+#[repr(C, align(64))]
+#[derive(Copy, Clone, Default)]
+pub struct AlignEc384PrivateKey {
+    _pad1: [u8; 8],
+    pub key: Ec384PrivateKey,
+    _pad2: [u8; 8],
+}
+
+impl From<Ec384PrivateKey> for AlignEc384PrivateKey {
+    #[inline]
+    fn from(key: Ec384PrivateKey) -> AlignEc384PrivateKey {
+        AlignEc384PrivateKey {
+            _pad1: [0; 8],
+            key,
+            _pad2: [0; 8],
+        }
+    }
+}
+
+impl From<&Ec384PrivateKey> for AlignEc384PrivateKey {
+    #[inline]
+    fn from(key: &Ec384PrivateKey) -> AlignEc384PrivateKey {
+        AlignEc384PrivateKey {
+            _pad1: [0; 8],
+            key: *key,
+            _pad2: [0; 8],
+        }
+    }
+}
+
+impl AsRef<[u8; ECP384_KEY_SIZE]> for AlignEc384PrivateKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8; ECP384_KEY_SIZE] {
+        self.key.as_ref()
+    }
+}
+
+impl AsMut<[u8; ECP384_KEY_SIZE]> for AlignEc384PrivateKey {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8; ECP384_KEY_SIZE] {
+        self.key.as_mut()
+    }
+}
+
+impl From<[u8; ECP384_KEY_SIZE]> for AlignEc384PrivateKey {
+    #[inline]
+    fn from(key: [u8; ECP384_KEY_SIZE]) -> AlignEc384PrivateKey {
+        AlignEc384PrivateKey::from(Ec384PrivateKey::from(key))
+    }
+}
+
+impl From<&[u8; ECP384_KEY_SIZE]> for AlignEc384PrivateKey {
+    #[inline]
+    fn from(key: &[u8; ECP384_KEY_SIZE]) -> AlignEc384PrivateKey {
+        AlignEc384PrivateKey::from(Ec384PrivateKey::from(key))
+    }
+}
+
+impl PartialEq for AlignEc384PrivateKey {
+    #[inline]
+    fn eq(&self, other: &AlignEc384PrivateKey) -> bool {
+        self.key.eq(&other.key)
+    }
+}
+
+impl Eq for AlignEc384PrivateKey {}
+
+impl fmt::Debug for AlignEc384PrivateKey {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("AlignEc384PrivateKey")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
 impl_struct_ContiguousMemory! {
     AlignKey128bit;
     AlignKey256bit;
@@ -948,9 +1376,12 @@ impl_struct_ContiguousMemory! {
     AlignMac256bit;
     AlignEc256SharedKey;
     AlignEc256PrivateKey;
+    AlignEc384SharedKey;
+    AlignEc384PrivateKey;
 }
 
 impl_unsafe_marker_for! {
     BytewiseEquality,
     AlignKey128bit AlignKey256bit AlignMac128bit AlignMac256bit AlignEc256SharedKey AlignEc256PrivateKey
+    AlignEc384SharedKey AlignEc384PrivateKey
 }
@@ -20,6 +20,10 @@
 
 #[macro_use]
 extern crate sgx_tstd as std;
+extern crate sgx_crypto;
+extern crate sgx_tprotected_fs;
+extern crate sgx_tse;
+extern crate sgx_tseal;
 extern crate sgx_types;
 
 use sgx_types::error::SgxStatus;